@@ -0,0 +1,79 @@
+//! Shared payroll analytics types and client for cross-contract calls.
+//!
+//! The payroll contract pushes one [`PayoutRecord`] per periodic payout to
+//! the `payroll_analytics` contract, which aggregates them into the metrics
+//! served to dashboards. Depend on this crate (rlib only) from either side;
+//! deploy `payroll_analytics` separately — do not link it as a cdylib
+//! dependency.
+
+#![no_std]
+
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+/// One payout as pushed by the payroll contract.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutRecord {
+    pub employee: Address,
+    pub employer: Address,
+    /// Amount paid, in the agreement's base token units.
+    pub amount: i128,
+    /// Paid periods that were on time.
+    pub on_time: u32,
+    /// Paid periods that were late.
+    pub late: u32,
+    /// Sum of the lateness of the late periods, in seconds.
+    pub seconds_late: u64,
+    /// Employer's reporting currency at the time of the payout.
+    pub reporting_currency: Option<Address>,
+    /// `amount` converted into `reporting_currency`; `None` when no usable
+    /// rate was stored.
+    pub reporting_amount: Option<i128>,
+}
+
+/// Lifetime payout metrics for one employee.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EmployeeMetrics {
+    /// Total paid, in each agreement's base token units (FX payouts are
+    /// counted at their base amount).
+    pub total_received: i128,
+    pub on_time_payouts: u32,
+    pub late_payouts: u32,
+    /// Sum of the lateness of all late payouts, in seconds.
+    pub total_seconds_late: u64,
+    /// `total_seconds_late / late_payouts`, in whole days.
+    pub average_days_late: u64,
+    /// On-time payouts since the last late one.
+    pub current_streak: u32,
+    /// Ledger timestamp of the most recent payout, 0 if none.
+    pub last_payout_at: u64,
+}
+
+/// Payout totals for one employer, in its reporting currency.
+///
+/// Totals restart whenever the reporting currency changes so they are never
+/// expressed in a mix of units.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EmployerMetrics {
+    pub reporting_currency: Option<Address>,
+    /// Sum of converted payouts since `since`.
+    pub total_paid_reporting: i128,
+    pub converted_payouts: u32,
+    /// Payouts left out of the total because no usable rate was stored.
+    pub unconverted_payouts: u32,
+    /// Ledger timestamp at which the current reporting currency was set.
+    pub since: u64,
+}
+
+/// Client for the deployed analytics contract, as called by the payroll
+/// contract.
+#[contractclient(name = "PayrollAnalyticsClient")]
+pub trait PayrollAnalyticsInterface {
+    /// Adds a payout to the employee's metrics and the employer's totals.
+    fn record_payout(env: Env, record: PayoutRecord);
+
+    /// Restarts the employer's totals in `currency`, or clears them.
+    fn set_reporting_currency(env: Env, employer: Address, currency: Option<Address>);
+}
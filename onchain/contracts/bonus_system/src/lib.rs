@@ -3,43 +3,62 @@
 #![allow(deprecated)] // Soroban SDK uses deprecated publish method
 #![allow(clippy::needless_borrows_for_generic_args)]
 
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, token, xdr::ToXdr, Address, Env, String, Vec,
+};
+use webhook_interface::{event_types, WebhookNotification, WebhookRegistryClient};
+
+pub use stellopay_interfaces::bonus::{
+    ApprovalStatus, ClawbackExecutedEvent, Incentive, IncentiveCancelledEvent,
+    IncentiveClaimedEvent, IncentiveCreatedEvent, IncentiveKind,
+};
 
 #[contract]
 pub struct BonusSystemContract;
 
+/// Running bonus totals for one employer, kept up to date on every state
+/// transition so outstanding liabilities can be reconciled without replaying
+/// events. Escrow still held is `total_escrowed - total_claimed -
+/// total_refunded`.
 #[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub enum IncentiveKind {
-    OneTime,
-    Recurring,
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EmployerBonusStats {
+    /// Amount escrowed at creation across all incentives.
+    pub total_escrowed: i128,
+    /// Amount paid out to employees.
+    pub total_claimed: i128,
+    /// Amount returned to the employer on cancellation.
+    pub total_refunded: i128,
+    /// Amount clawed back from employees to the employer.
+    pub total_clawed_back: i128,
+    /// Incentives neither completed nor cancelled.
+    pub open_incentives: u32,
 }
 
+/// Maximum number of incentives settled by one batch approve/reject call.
+pub const MAX_APPROVAL_BATCH: u32 = 50;
+
+/// Maximum number of scheduled claims one `execute_scheduled_claims` call runs.
+pub const MAX_SCHEDULED_CLAIM_BATCH: u32 = 50;
+
+/// Per-incentive result of a batch approve/reject.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub enum ApprovalStatus {
-    Pending,
-    Approved,
-    Rejected,
-    Cancelled,
-    Completed,
+pub enum ApprovalOutcome {
+    /// The incentive moved to the requested status.
+    Applied,
+    NotFound,
+    /// The caller is not the incentive's approver.
+    NotApprover,
+    /// The incentive is no longer pending.
+    NotPending,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Incentive {
-    pub id: u128,
-    pub employer: Address,
-    pub employee: Address,
-    pub approver: Address,
-    pub token: Address,
-    pub kind: IncentiveKind,
-    pub status: ApprovalStatus,
-    pub amount_per_payout: i128,
-    pub total_payouts: u32,
-    pub claimed_payouts: u32,
-    pub start_time: u64,
-    pub interval_seconds: u64,
+pub struct BatchApprovalResult {
+    pub incentive_id: u128,
+    pub outcome: ApprovalOutcome,
 }
 
 #[contracttype]
@@ -58,67 +77,95 @@ enum StorageKey {
     EmployeeTerminated(Address),
     // Clawback tracking
     ClawbackTotal(u128),
+    // Employer analytics
+    EmployerStats(Address),
+    // Alternate claim destination: employee -> payout address
+    PayoutAddress(Address),
+    // Incentives of an employee neither completed nor cancelled
+    EmployeeOpenIncentives(Address),
+    // Time a scheduled claim of an incentive becomes executable
+    ScheduledClaim(u128),
+    // Webhook registry notified of claims
+    WebhookRegistry,
+    // Incentive payouts claimed across all employers
+    ClaimedPayouts,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct IncentiveCreatedEvent {
+pub struct IncentiveApprovedEvent {
     pub incentive_id: u128,
-    pub employer: Address,
-    pub employee: Address,
     pub approver: Address,
-    pub token: Address,
-    pub kind: IncentiveKind,
-    pub escrowed_amount: i128,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct IncentiveApprovedEvent {
+pub struct IncentiveRejectedEvent {
     pub incentive_id: u128,
     pub approver: Address,
 }
 
+/// How the escrow of an approved recurring incentive was split when it was
+/// cancelled mid-schedule.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct IncentiveRejectedEvent {
+pub struct ApprovedCancellation {
+    /// Whole payouts vested but not yet claimed, paid to the employee.
+    pub vested_amount: i128,
+    /// Share of the in-progress interval, paid to the employee.
+    pub prorated_amount: i128,
+    /// Rest of the escrow, returned to the employer.
+    pub refunded_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovedIncentiveCancelledEvent {
     pub incentive_id: u128,
+    pub employer: Address,
     pub approver: Address,
+    pub vested_amount: i128,
+    pub prorated_amount: i128,
+    pub refunded_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimScheduledEvent {
+    pub incentive_id: u128,
+    pub employee: Address,
+    pub execute_at: u64,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct IncentiveClaimedEvent {
+pub struct ScheduledClaimExecutedEvent {
     pub incentive_id: u128,
     pub employee: Address,
-    pub payouts_claimed: u32,
+    /// Zero when nothing was claimable at execution.
     pub amount: i128,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct IncentiveCancelledEvent {
+pub struct ScheduledClaimCancelledEvent {
     pub incentive_id: u128,
-    pub employer: Address,
-    pub refunded_amount: i128,
+    pub employee: Address,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct BonusCapSetEvent {
-    pub admin: Address,
-    pub employee: Option<Address>,
-    pub cap_amount: i128,
+pub struct PayoutAddressSetEvent {
+    pub employee: Address,
+    pub payout: Address,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct ClawbackExecutedEvent {
+pub struct BonusCapSetEvent {
     pub admin: Address,
-    pub employee: Address,
-    pub incentive_id: u128,
-    pub clawback_amount: i128,
-    pub reason_hash: u128,
+    pub employee: Option<Address>,
+    pub cap_amount: i128,
 }
 
 #[contracttype]
@@ -173,12 +220,211 @@ fn next_incentive_id(env: &Env) -> u128 {
     next
 }
 
+/// Derives the ID of an incentive created by `employer` with `nonce`: the
+/// first 16 bytes (big-endian) of `sha256(xdr(employer) || nonce_be_bytes)`
+/// with the top bit set, so it can never collide with a counter ID.
+fn derive_incentive_id(env: &Env, employer: &Address, nonce: u64) -> u128 {
+    let mut preimage = employer.clone().to_xdr(env);
+    preimage.extend_from_array(&nonce.to_be_bytes());
+    let digest = env.crypto().sha256(&preimage).to_array();
+    let mut id_bytes = [0u8; 16];
+    id_bytes.copy_from_slice(&digest[..16]);
+    u128::from_be_bytes(id_bytes) | (1 << 127)
+}
+
+/// Assigns the ID for a new incentive: derived from `nonce` when given,
+/// otherwise the next counter value.
+fn new_incentive_id(env: &Env, employer: &Address, nonce: Option<u64>) -> u128 {
+    let Some(nonce) = nonce else {
+        return next_incentive_id(env);
+    };
+    let incentive_id = derive_incentive_id(env, employer, nonce);
+    assert!(
+        !env.storage()
+            .persistent()
+            .has(&StorageKey::Incentive(incentive_id)),
+        "Incentive nonce already used"
+    );
+    incentive_id
+}
+
+fn read_payout_address(env: &Env, employee: &Address) -> Address {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::PayoutAddress(employee.clone()))
+        .unwrap_or_else(|| employee.clone())
+}
+
+/// Notifies the linked webhook registry, if any, of `event_type` for the
+/// incentive's employer. A failing registry never blocks the caller.
+fn notify(env: &Env, event_type: &str, incentive: &Incentive, amount: i128) {
+    let Some(registry) = env
+        .storage()
+        .persistent()
+        .get::<_, Address>(&StorageKey::WebhookRegistry)
+    else {
+        return;
+    };
+    let notification = WebhookNotification {
+        owner: incentive.employer.clone(),
+        event_type: String::from_str(env, event_type),
+        subject: incentive.employee.clone(),
+        reference: incentive.id,
+        amount,
+    };
+    let _ = WebhookRegistryClient::new(env, &registry)
+        .try_notify(&env.current_contract_address(), &notification);
+}
+
+fn read_open_incentives(env: &Env, employee: &Address) -> Vec<u128> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::EmployeeOpenIncentives(employee.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+fn add_open_incentive(env: &Env, employee: &Address, incentive_id: u128) {
+    let mut ids = read_open_incentives(env, employee);
+    ids.push_back(incentive_id);
+    env.storage()
+        .persistent()
+        .set(&StorageKey::EmployeeOpenIncentives(employee.clone()), &ids);
+}
+
+fn remove_open_incentive(env: &Env, employee: &Address, incentive_id: u128) {
+    let mut ids = read_open_incentives(env, employee);
+    if let Some(pos) = ids.first_index_of(incentive_id) {
+        ids.remove(pos);
+        env.storage()
+            .persistent()
+            .set(&StorageKey::EmployeeOpenIncentives(employee.clone()), &ids);
+    }
+}
+
+fn read_employer_stats(env: &Env, employer: &Address) -> EmployerBonusStats {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::EmployerStats(employer.clone()))
+        .unwrap_or_default()
+}
+
+fn update_employer_stats(
+    env: &Env,
+    employer: &Address,
+    update: impl FnOnce(&mut EmployerBonusStats),
+) {
+    let mut stats = read_employer_stats(env, employer);
+    update(&mut stats);
+    env.storage()
+        .persistent()
+        .set(&StorageKey::EmployerStats(employer.clone()), &stats);
+}
+
+/// Moves a pending incentive to `decision` (Approved or Rejected) on behalf
+/// of `approver`, reporting why it could not instead of panicking.
+fn decide_incentive(
+    env: &Env,
+    approver: &Address,
+    incentive_id: u128,
+    decision: ApprovalStatus,
+) -> ApprovalOutcome {
+    let Some(mut incentive) = env
+        .storage()
+        .persistent()
+        .get::<_, Incentive>(&StorageKey::Incentive(incentive_id))
+    else {
+        return ApprovalOutcome::NotFound;
+    };
+    if incentive.approver != *approver {
+        return ApprovalOutcome::NotApprover;
+    }
+    if incentive.status != ApprovalStatus::Pending {
+        return ApprovalOutcome::NotPending;
+    }
+
+    incentive.status = decision.clone();
+    write_incentive(env, &incentive);
+
+    if decision == ApprovalStatus::Approved {
+        env.events().publish(
+            ("incentive_approved", incentive_id),
+            IncentiveApprovedEvent {
+                incentive_id,
+                approver: approver.clone(),
+            },
+        );
+        notify(
+            env,
+            event_types::INCENTIVE_APPROVED,
+            &incentive,
+            checked_mul_amount(incentive.amount_per_payout, incentive.total_payouts),
+        );
+    } else {
+        env.events().publish(
+            ("incentive_rejected", incentive_id),
+            IncentiveRejectedEvent {
+                incentive_id,
+                approver: approver.clone(),
+            },
+        );
+    }
+    ApprovalOutcome::Applied
+}
+
+fn decide_incentives(
+    env: &Env,
+    approver: &Address,
+    incentive_ids: Vec<u128>,
+    decision: ApprovalStatus,
+) -> Vec<BatchApprovalResult> {
+    require_initialized(env);
+    approver.require_auth();
+    assert!(
+        incentive_ids.len() <= MAX_APPROVAL_BATCH,
+        "Batch exceeds maximum size"
+    );
+
+    let mut results = Vec::new(env);
+    for incentive_id in incentive_ids.iter() {
+        results.push_back(BatchApprovalResult {
+            incentive_id,
+            outcome: decide_incentive(env, approver, incentive_id, decision.clone()),
+        });
+    }
+    results
+}
+
 fn checked_mul_amount(amount_per_payout: i128, payouts: u32) -> i128 {
     amount_per_payout
         .checked_mul(i128::from(payouts))
         .expect("Amount overflow")
 }
 
+fn claimable_payouts(now: u64, incentive: &Incentive) -> u32 {
+    if incentive.status != ApprovalStatus::Approved {
+        return 0;
+    }
+
+    match incentive.kind {
+        IncentiveKind::OneTime => {
+            if now >= incentive.start_time && incentive.claimed_payouts == 0 {
+                1
+            } else {
+                0
+            }
+        }
+        IncentiveKind::Recurring => {
+            let vested = vested_payouts(
+                now,
+                incentive.start_time,
+                incentive.interval_seconds,
+                incentive.total_payouts,
+            );
+            vested.saturating_sub(incentive.claimed_payouts)
+        }
+    }
+}
+
 fn vested_payouts(now: u64, start_time: u64, interval_seconds: u64, total_payouts: u32) -> u32 {
     if now < start_time {
         return 0;
@@ -197,6 +443,41 @@ fn vested_payouts(now: u64, start_time: u64, interval_seconds: u64, total_payout
     }
 }
 
+/// Share of `amount_per_payout` earned after `elapsed_seconds` of an
+/// `interval_seconds` interval, rounded down. Elapsed time beyond the
+/// interval counts as the whole interval; a zero-length interval earns
+/// nothing.
+pub fn prorated_payout(
+    amount_per_payout: i128,
+    elapsed_seconds: u64,
+    interval_seconds: u64,
+) -> i128 {
+    if interval_seconds == 0 || amount_per_payout <= 0 {
+        return 0;
+    }
+    let elapsed = elapsed_seconds.min(interval_seconds);
+    let interval = i128::from(interval_seconds);
+    // Split the product so it cannot overflow for any valid amount.
+    let whole = amount_per_payout / interval * i128::from(elapsed);
+    let rest =
+        (amount_per_payout % interval) as u128 * u128::from(elapsed) / u128::from(interval_seconds);
+    whole + rest as i128
+}
+
+/// Seconds the in-progress interval of a recurring incentive has run at
+/// `now`: since its latest vested payout, while payouts remain. Zero before
+/// the first payout and after the last.
+fn in_progress_elapsed(now: u64, incentive: &Incentive, vested: u32) -> u64 {
+    if vested == 0 || vested >= incentive.total_payouts {
+        return 0;
+    }
+    let last_vested_at = u64::from(vested - 1)
+        .checked_mul(incentive.interval_seconds)
+        .and_then(|offset| incentive.start_time.checked_add(offset))
+        .expect("Schedule overflow");
+    now.saturating_sub(last_vested_at)
+}
+
 // Helper: Calculate current period (30-day periods)
 fn get_current_period(env: &Env) -> u64 {
     const SECONDS_PER_PERIOD: u64 = 2_592_000; // 30 days
@@ -359,6 +640,55 @@ impl BonusSystemContract {
         token: Address,
         amount: i128,
         unlock_time: u64,
+    ) -> u128 {
+        Self::create_one_time(
+            env,
+            employer,
+            employee,
+            approver,
+            token,
+            amount,
+            unlock_time,
+            None,
+        )
+    }
+
+    /// @notice Same as `create_one_time_bonus`, but the incentive ID is derived from
+    /// `employer` and `nonce` (see `compute_incentive_id`) so it is known before submission.
+    /// @dev Panics if the employer already used `nonce`.
+    /// @param nonce Employer-chosen value, unique per employer.
+    /// @return u128
+    pub fn create_nonced_one_time_bonus(
+        env: Env,
+        employer: Address,
+        employee: Address,
+        approver: Address,
+        token: Address,
+        amount: i128,
+        unlock_time: u64,
+        nonce: u64,
+    ) -> u128 {
+        Self::create_one_time(
+            env,
+            employer,
+            employee,
+            approver,
+            token,
+            amount,
+            unlock_time,
+            Some(nonce),
+        )
+    }
+
+    fn create_one_time(
+        env: Env,
+        employer: Address,
+        employee: Address,
+        approver: Address,
+        token: Address,
+        amount: i128,
+        unlock_time: u64,
+        nonce: Option<u64>,
     ) -> u128 {
         require_initialized(&env);
         employer.require_auth();
@@ -373,7 +703,7 @@ impl BonusSystemContract {
         // Enforce bonus caps
         check_and_enforce_cap(&env, &employee, amount);
 
-        let incentive_id = next_incentive_id(&env);
+        let incentive_id = new_incentive_id(&env, &employer, nonce);
         let incentive = Incentive {
             id: incentive_id,
             employer: employer.clone(),
@@ -396,9 +726,18 @@ impl BonusSystemContract {
         );
 
         write_incentive(&env, &incentive);
+        add_open_incentive(&env, &employee, incentive_id);
+        notify(&env, event_types::INCENTIVE_CREATED, &incentive, amount);
 
         // Update bonus totals
         update_bonus_totals(&env, &employee, amount);
+        update_employer_stats(&env, &employer, |stats| {
+            stats.total_escrowed = stats
+                .total_escrowed
+                .checked_add(amount)
+                .expect("Employer escrow total overflow");
+            stats.open_incentives += 1;
+        });
 
         env.events().publish(
             ("incentive_created", incentive_id),
@@ -437,6 +776,71 @@ impl BonusSystemContract {
         total_payouts: u32,
         start_time: u64,
         interval_seconds: u64,
+    ) -> u128 {
+        Self::create_recurring(
+            env,
+            employer,
+            employee,
+            approver,
+            token,
+            amount_per_payout,
+            total_payouts,
+            start_time,
+            interval_seconds,
+            None,
+        )
+    }
+
+    /// @notice Same as `create_recurring_incentive`, but the incentive ID is derived from
+    /// `employer` and `nonce` (see `compute_incentive_id`) so it is known before submission.
+    /// @dev Panics if the employer already used `nonce`.
+    /// @param nonce Employer-chosen value, unique per employer.
+    /// @return u128
+    pub fn create_nonced_recurring_bonus(
+        env: Env,
+        employer: Address,
+        employee: Address,
+        approver: Address,
+        token: Address,
+        amount_per_payout: i128,
+        total_payouts: u32,
+        start_time: u64,
+        interval_seconds: u64,
+        nonce: u64,
+    ) -> u128 {
+        Self::create_recurring(
+            env,
+            employer,
+            employee,
+            approver,
+            token,
+            amount_per_payout,
+            total_payouts,
+            start_time,
+            interval_seconds,
+            Some(nonce),
+        )
+    }
+
+    /// @notice Returns the ID an incentive created by `employer` with `nonce` gets.
+    /// @dev First 16 bytes (big-endian) of `sha256(xdr(employer) || nonce_be_bytes)` with
+    /// the top bit set; counter IDs never reach that range.
+    /// @return u128
+    pub fn compute_incentive_id(env: Env, employer: Address, nonce: u64) -> u128 {
+        derive_incentive_id(&env, &employer, nonce)
+    }
+
+    fn create_recurring(
+        env: Env,
+        employer: Address,
+        employee: Address,
+        approver: Address,
+        token: Address,
+        amount_per_payout: i128,
+        total_payouts: u32,
+        start_time: u64,
+        interval_seconds: u64,
+        nonce: Option<u64>,
     ) -> u128 {
         require_initialized(&env);
         employer.require_auth();
@@ -455,7 +859,7 @@ impl BonusSystemContract {
         // Enforce bonus caps
         check_and_enforce_cap(&env, &employee, escrowed_amount);
 
-        let incentive_id = next_incentive_id(&env);
+        let incentive_id = new_incentive_id(&env, &employer, nonce);
         let incentive = Incentive {
             id: incentive_id,
             employer: employer.clone(),
@@ -478,9 +882,23 @@ impl BonusSystemContract {
         );
 
         write_incentive(&env, &incentive);
+        add_open_incentive(&env, &employee, incentive_id);
+        notify(
+            &env,
+            event_types::INCENTIVE_CREATED,
+            &incentive,
+            escrowed_amount,
+        );
 
         // Update bonus totals
         update_bonus_totals(&env, &employee, escrowed_amount);
+        update_employer_stats(&env, &employer, |stats| {
+            stats.total_escrowed = stats
+                .total_escrowed
+                .checked_add(escrowed_amount)
+                .expect("Employer escrow total overflow");
+            stats.open_incentives += 1;
+        });
 
         env.events().publish(
             ("incentive_created", incentive_id),
@@ -506,23 +924,12 @@ impl BonusSystemContract {
         require_initialized(&env);
         approver.require_auth();
 
-        let mut incentive = read_incentive(&env, incentive_id);
-        assert!(incentive.approver == approver, "Only approver can approve");
-        assert!(
-            incentive.status == ApprovalStatus::Pending,
-            "Incentive is not pending"
-        );
-
-        incentive.status = ApprovalStatus::Approved;
-        write_incentive(&env, &incentive);
-
-        env.events().publish(
-            ("incentive_approved", incentive_id),
-            IncentiveApprovedEvent {
-                incentive_id,
-                approver,
-            },
-        );
+        match decide_incentive(&env, &approver, incentive_id, ApprovalStatus::Approved) {
+            ApprovalOutcome::Applied => {}
+            ApprovalOutcome::NotFound => panic!("Incentive not found"),
+            ApprovalOutcome::NotApprover => panic!("Only approver can approve"),
+            ApprovalOutcome::NotPending => panic!("Incentive is not pending"),
+        }
     }
 
     /// @notice Rejects a pending incentive.
@@ -533,27 +940,43 @@ impl BonusSystemContract {
         require_initialized(&env);
         approver.require_auth();
 
-        let mut incentive = read_incentive(&env, incentive_id);
-        assert!(incentive.approver == approver, "Only approver can reject");
-        assert!(
-            incentive.status == ApprovalStatus::Pending,
-            "Incentive is not pending"
-        );
+        match decide_incentive(&env, &approver, incentive_id, ApprovalStatus::Rejected) {
+            ApprovalOutcome::Applied => {}
+            ApprovalOutcome::NotFound => panic!("Incentive not found"),
+            ApprovalOutcome::NotApprover => panic!("Only approver can reject"),
+            ApprovalOutcome::NotPending => panic!("Incentive is not pending"),
+        }
+    }
 
-        incentive.status = ApprovalStatus::Rejected;
-        write_incentive(&env, &incentive);
+    /// @notice Approves several pending incentives in one call.
+    /// @dev Ids that cannot be approved are reported in the results instead of aborting the batch.
+    /// @param approver Approver address.
+    /// @param incentive_ids At most `MAX_APPROVAL_BATCH` incentive identifiers.
+    /// @return Vec<BatchApprovalResult> One outcome per id, in input order.
+    pub fn batch_approve_incentives(
+        env: Env,
+        approver: Address,
+        incentive_ids: Vec<u128>,
+    ) -> Vec<BatchApprovalResult> {
+        decide_incentives(&env, &approver, incentive_ids, ApprovalStatus::Approved)
+    }
 
-        env.events().publish(
-            ("incentive_rejected", incentive_id),
-            IncentiveRejectedEvent {
-                incentive_id,
-                approver,
-            },
-        );
+    /// @notice Rejects several pending incentives in one call.
+    /// @dev Ids that cannot be rejected are reported in the results instead of aborting the batch.
+    /// @param approver Approver address.
+    /// @param incentive_ids At most `MAX_APPROVAL_BATCH` incentive identifiers.
+    /// @return Vec<BatchApprovalResult> One outcome per id, in input order.
+    pub fn batch_reject_incentives(
+        env: Env,
+        approver: Address,
+        incentive_ids: Vec<u128>,
+    ) -> Vec<BatchApprovalResult> {
+        decide_incentives(&env, &approver, incentive_ids, ApprovalStatus::Rejected)
     }
 
     /// @notice Claims currently available payouts for an approved incentive.
     /// @dev One-time bonus claims exactly one payout after unlock. Recurring claims all accrued payouts.
+    /// Funds go to the employee's payout address (see `set_payout_address`).
     /// @param employee Employee claiming funds.
     /// @param incentive_id Incentive identifier.
     /// @return amount Claimed token amount.
@@ -561,7 +984,7 @@ impl BonusSystemContract {
         require_initialized(&env);
         employee.require_auth();
 
-        let mut incentive = read_incentive(&env, incentive_id);
+        let incentive = read_incentive(&env, incentive_id);
         assert!(incentive.employee == employee, "Only employee can claim");
         assert!(
             incentive.status == ApprovalStatus::Approved,
@@ -588,6 +1011,39 @@ impl BonusSystemContract {
             }
         };
 
+        Self::pay_out(&env, incentive, payouts_to_claim)
+    }
+
+    /// @notice Claims every vested payout of the employee's approved incentives from one employer.
+    /// @dev Incentives with nothing claimable are skipped, so this never fails for lack of
+    /// vested payouts and returns zero instead. Lets a payroll contract pay salary and bonuses
+    /// in one run when the employee authorizes the nested call.
+    /// @param employee Employee claiming funds.
+    /// @param employer Employer whose incentives are claimed.
+    /// @return amount Total claimed token amount, summed across tokens.
+    pub fn claim_vested_incentives(env: Env, employee: Address, employer: Address) -> i128 {
+        require_initialized(&env);
+        employee.require_auth();
+
+        let now = env.ledger().timestamp();
+        let mut total: i128 = 0;
+        for incentive_id in read_open_incentives(&env, &employee).iter() {
+            let incentive = read_incentive(&env, incentive_id);
+            if incentive.employer != employer {
+                continue;
+            }
+            let payouts = claimable_payouts(now, &incentive);
+            if payouts > 0 {
+                let amount = Self::pay_out(&env, incentive, payouts);
+                total = total.checked_add(amount).expect("Claim total overflow");
+            }
+        }
+        total
+    }
+
+    fn pay_out(env: &Env, mut incentive: Incentive, payouts_to_claim: u32) -> i128 {
+        let incentive_id = incentive.id;
+        let employee = incentive.employee.clone();
         let amount = checked_mul_amount(incentive.amount_per_payout, payouts_to_claim);
         // Checks-effects-interactions:
         // update payout counters before transfer to block reentrant double-claim.
@@ -596,14 +1052,35 @@ impl BonusSystemContract {
             .checked_add(payouts_to_claim)
             .expect("Payout counter overflow");
 
-        if incentive.claimed_payouts == incentive.total_payouts {
+        let completed = incentive.claimed_payouts == incentive.total_payouts;
+        if completed {
             incentive.status = ApprovalStatus::Completed;
+            remove_open_incentive(env, &employee, incentive_id);
         }
 
-        write_incentive(&env, &incentive);
-        token::Client::new(&env, &incentive.token).transfer(
+        write_incentive(env, &incentive);
+        let claimed: u64 = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::ClaimedPayouts)
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &StorageKey::ClaimedPayouts,
+            &claimed.saturating_add(u64::from(payouts_to_claim)),
+        );
+        let payout = read_payout_address(env, &employee);
+        update_employer_stats(env, &incentive.employer, |stats| {
+            stats.total_claimed = stats
+                .total_claimed
+                .checked_add(amount)
+                .expect("Employer claim total overflow");
+            if completed {
+                stats.open_incentives -= 1;
+            }
+        });
+        token::Client::new(env, &incentive.token).transfer(
             &env.current_contract_address(),
-            &employee,
+            &payout,
             &amount,
         );
         env.events().publish(
@@ -615,12 +1092,146 @@ impl BonusSystemContract {
                 amount,
             },
         );
+        notify(env, event_types::INCENTIVE_CLAIMED, &incentive, amount);
 
         amount
     }
 
+    /// @notice Sets the address that receives the employee's incentive claims.
+    /// @dev Claims still require the employee's own authorization; only the destination changes.
+    /// Clawbacks keep pulling from the employee address. Passing the employee address itself
+    /// restores the default.
+    /// @param employee Employee identity address (must authenticate).
+    /// @param payout Address to receive claimed funds.
+    pub fn set_payout_address(env: Env, employee: Address, payout: Address) {
+        require_initialized(&env);
+        employee.require_auth();
+
+        let key = StorageKey::PayoutAddress(employee.clone());
+        if payout == employee {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &payout);
+        }
+
+        env.events().publish(
+            ("payout_address_set",),
+            PayoutAddressSetEvent { employee, payout },
+        );
+    }
+
+    /// @notice Returns where the employee's claims are paid.
+    /// @param employee Employee identity address.
+    /// @return Address The registered payout address, or the employee address if none.
+    pub fn get_payout_address(env: Env, employee: Address) -> Address {
+        read_payout_address(&env, &employee)
+    }
+
+    /// @notice Schedules a claim of the incentive for a future date, e.g. a tax-year boundary.
+    /// @dev At `execute_at` any keeper can run it with `execute_scheduled_claims`, claiming every
+    /// payout vested by then to the employee's payout address. Scheduling again replaces the
+    /// date. The employee can still claim directly in the meantime.
+    /// @param incentive_id Approved incentive of the calling employee.
+    /// @param execute_at Ledger timestamp, after the current one, the claim runs at.
+    pub fn schedule_claim(env: Env, incentive_id: u128, execute_at: u64) {
+        require_initialized(&env);
+        let incentive = read_incentive(&env, incentive_id);
+        incentive.employee.require_auth();
+        assert!(
+            incentive.status == ApprovalStatus::Approved,
+            "Incentive is not approved"
+        );
+        assert!(
+            execute_at > env.ledger().timestamp(),
+            "Execution time must be in the future"
+        );
+
+        env.storage()
+            .persistent()
+            .set(&StorageKey::ScheduledClaim(incentive_id), &execute_at);
+        env.events().publish(
+            ("claim_scheduled", incentive_id),
+            ClaimScheduledEvent {
+                incentive_id,
+                employee: incentive.employee,
+                execute_at,
+            },
+        );
+    }
+
+    /// @notice Cancels the incentive's scheduled claim before it runs.
+    /// @param incentive_id Incentive of the calling employee.
+    pub fn cancel_scheduled_claim(env: Env, incentive_id: u128) {
+        require_initialized(&env);
+        let incentive = read_incentive(&env, incentive_id);
+        incentive.employee.require_auth();
+        let key = StorageKey::ScheduledClaim(incentive_id);
+        assert!(env.storage().persistent().has(&key), "No scheduled claim");
+
+        env.storage().persistent().remove(&key);
+        env.events().publish(
+            ("scheduled_claim_cancelled", incentive_id),
+            ScheduledClaimCancelledEvent {
+                incentive_id,
+                employee: incentive.employee,
+            },
+        );
+    }
+
+    /// @notice Returns when the incentive's scheduled claim becomes executable, if one is set.
+    /// @param incentive_id Incentive identifier.
+    pub fn get_scheduled_claim(env: Env, incentive_id: u128) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::ScheduledClaim(incentive_id))
+    }
+
+    /// @notice Runs the due scheduled claims among `incentive_ids`. Callable by any keeper.
+    /// @dev Ids without a schedule, or not yet due, are skipped. A due schedule is consumed even
+    /// when nothing is claimable any more (e.g. the employee claimed directly), paying zero.
+    /// @param incentive_ids At most `MAX_SCHEDULED_CLAIM_BATCH` incentive identifiers.
+    /// @return amount Total claimed token amount, summed across tokens.
+    pub fn execute_scheduled_claims(env: Env, incentive_ids: Vec<u128>) -> i128 {
+        require_initialized(&env);
+        assert!(
+            incentive_ids.len() <= MAX_SCHEDULED_CLAIM_BATCH,
+            "Batch exceeds maximum size"
+        );
+
+        let now = env.ledger().timestamp();
+        let mut total: i128 = 0;
+        for incentive_id in incentive_ids.iter() {
+            let key = StorageKey::ScheduledClaim(incentive_id);
+            match env.storage().persistent().get::<_, u64>(&key) {
+                Some(execute_at) if execute_at <= now => {}
+                _ => continue,
+            }
+            env.storage().persistent().remove(&key);
+
+            let incentive = read_incentive(&env, incentive_id);
+            let employee = incentive.employee.clone();
+            let payouts = claimable_payouts(now, &incentive);
+            let amount = if payouts > 0 {
+                Self::pay_out(&env, incentive, payouts)
+            } else {
+                0
+            };
+            total = total.checked_add(amount).expect("Claim total overflow");
+            env.events().publish(
+                ("scheduled_claim_executed", incentive_id),
+                ScheduledClaimExecutedEvent {
+                    incentive_id,
+                    employee,
+                    amount,
+                },
+            );
+        }
+        total
+    }
+
     /// @notice Cancels a pending or rejected incentive and refunds remaining escrow.
-    /// @dev Approved incentives cannot be cancelled to preserve payout guarantees.
+    /// @dev Approved incentives cannot be cancelled here to preserve payout guarantees; see
+    /// `cancel_approved_incentive`.
     /// @param employer Employer requesting cancellation.
     /// @param incentive_id Incentive identifier.
     /// @return refunded_amount Refunded token amount.
@@ -644,6 +1255,14 @@ impl BonusSystemContract {
 
         incentive.status = ApprovalStatus::Cancelled;
         write_incentive(&env, &incentive);
+        remove_open_incentive(&env, &incentive.employee, incentive_id);
+        update_employer_stats(&env, &employer, |stats| {
+            stats.total_refunded = stats
+                .total_refunded
+                .checked_add(refunded_amount)
+                .expect("Employer refund total overflow");
+            stats.open_incentives -= 1;
+        });
 
         token::Client::new(&env, &incentive.token).transfer(
             &env.current_contract_address(),
@@ -663,6 +1282,103 @@ impl BonusSystemContract {
         refunded_amount
     }
 
+    /// @notice Cancels an approved recurring incentive mid-schedule, with the approver's consent.
+    /// @dev Vested but unclaimed payouts are paid in full, and the in-progress interval is paid
+    /// pro rata to the time elapsed since the latest vested payout (see `prorated_payout`).
+    /// Payments go to the employee's payout address; the rest of the escrow is refunded.
+    /// @param employer Employer requesting cancellation.
+    /// @param incentive_id Incentive identifier.
+    /// @return ApprovedCancellation Amounts paid to the employee and refunded to the employer.
+    pub fn cancel_approved_incentive(
+        env: Env,
+        employer: Address,
+        incentive_id: u128,
+    ) -> ApprovedCancellation {
+        require_initialized(&env);
+        employer.require_auth();
+
+        let mut incentive = read_incentive(&env, incentive_id);
+        assert!(incentive.employer == employer, "Only employer can cancel");
+        assert!(
+            incentive.status == ApprovalStatus::Approved,
+            "Incentive is not approved"
+        );
+        assert!(
+            incentive.kind == IncentiveKind::Recurring,
+            "Only recurring incentives can be prorated"
+        );
+        // Approval guaranteed the payouts, so withdrawing it needs the approver too.
+        incentive.approver.require_auth();
+
+        let now = env.ledger().timestamp();
+        let vested = vested_payouts(
+            now,
+            incentive.start_time,
+            incentive.interval_seconds,
+            incentive.total_payouts,
+        );
+        let vested_amount = checked_mul_amount(
+            incentive.amount_per_payout,
+            vested.saturating_sub(incentive.claimed_payouts),
+        );
+        let prorated_amount = prorated_payout(
+            incentive.amount_per_payout,
+            in_progress_elapsed(now, &incentive, vested),
+            incentive.interval_seconds,
+        );
+        let refunded_amount = checked_mul_amount(
+            incentive.amount_per_payout,
+            incentive.total_payouts - vested,
+        ) - prorated_amount;
+        let paid_amount = vested_amount + prorated_amount;
+
+        incentive.claimed_payouts = vested;
+        incentive.status = ApprovalStatus::Cancelled;
+        write_incentive(&env, &incentive);
+        remove_open_incentive(&env, &incentive.employee, incentive_id);
+        update_employer_stats(&env, &employer, |stats| {
+            stats.total_claimed = stats
+                .total_claimed
+                .checked_add(paid_amount)
+                .expect("Employer claim total overflow");
+            stats.total_refunded = stats
+                .total_refunded
+                .checked_add(refunded_amount)
+                .expect("Employer refund total overflow");
+            stats.open_incentives -= 1;
+        });
+
+        let token = token::Client::new(&env, &incentive.token);
+        if paid_amount > 0 {
+            token.transfer(
+                &env.current_contract_address(),
+                &read_payout_address(&env, &incentive.employee),
+                &paid_amount,
+            );
+        }
+        if refunded_amount > 0 {
+            token.transfer(&env.current_contract_address(), &employer, &refunded_amount);
+        }
+
+        env.events().publish(
+            ("approved_incentive_cancelled", incentive_id),
+            ApprovedIncentiveCancelledEvent {
+                incentive_id,
+                employer,
+                approver: incentive.approver,
+                vested_amount,
+                prorated_amount,
+                refunded_amount,
+            },
+        );
+
+        ApprovedCancellation {
+            vested_amount,
+            prorated_amount,
+            refunded_amount,
+        }
+    }
+
     /// @notice Reads a stored incentive by id.
     /// @param incentive_id Incentive identifier.
     /// @return incentive Optional incentive object.
@@ -673,6 +1389,22 @@ impl BonusSystemContract {
             .get(&StorageKey::Incentive(incentive_id))
     }
 
+    /// @notice Returns the running bonus totals for an employer.
+    /// @param employer Employer address.
+    /// @return EmployerBonusStats All-zero if the employer never created an incentive.
+    pub fn get_employer_bonus_stats(env: Env, employer: Address) -> EmployerBonusStats {
+        read_employer_stats(&env, &employer)
+    }
+
+    /// @notice Returns the number of incentive payouts claimed across all employers.
+    /// @return payouts Zero until the first claim.
+    pub fn get_total_claimed_payouts(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::ClaimedPayouts)
+            .unwrap_or(0)
+    }
+
     /// @notice Returns claimable payout count at the current ledger timestamp.
     /// @dev Returns zero unless incentive is approved.
     /// @param incentive_id Incentive identifier.
@@ -687,29 +1419,14 @@ impl BonusSystemContract {
             None => return 0,
         };
 
-        if incentive.status != ApprovalStatus::Approved {
-            return 0;
-        }
+        claimable_payouts(env.ledger().timestamp(), &incentive)
+    }
 
-        let now = env.ledger().timestamp();
-        match incentive.kind {
-            IncentiveKind::OneTime => {
-                if now >= incentive.start_time && incentive.claimed_payouts == 0 {
-                    1
-                } else {
-                    0
-                }
-            }
-            IncentiveKind::Recurring => {
-                let vested = vested_payouts(
-                    now,
-                    incentive.start_time,
-                    incentive.interval_seconds,
-                    incentive.total_payouts,
-                );
-                vested.saturating_sub(incentive.claimed_payouts)
-            }
-        }
+    /// @notice Returns the employee's incentives that are neither completed nor cancelled.
+    /// @param employee Employee address.
+    /// @return ids Incentive identifiers in creation order.
+    pub fn get_open_incentives(env: Env, employee: Address) -> Vec<u128> {
+        read_open_incentives(&env, &employee)
     }
 
     /// @notice Returns contract owner.
@@ -718,6 +1435,38 @@ impl BonusSystemContract {
         env.storage().persistent().get(&StorageKey::Owner)
     }
 
+    /// @notice Links the webhook registry notified of incentive creations, approvals and
+    /// claims, or unlinks it.
+    /// @dev Owner-only. The registry must also allow this contract as a notifier; it holds
+    /// all webhook state, so each notification is one storage-free call to it.
+    /// @param admin Admin address (must be owner).
+    /// @param registry Deployed `webhook_contract`, or None to stop notifying.
+    pub fn set_webhook_registry(env: Env, admin: Address, registry: Option<Address>) {
+        admin.require_auth();
+        let owner: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Owner)
+            .expect("Owner not set");
+        assert!(admin == owner, "Only owner can set webhook registry");
+
+        match registry {
+            Some(registry) => env
+                .storage()
+                .persistent()
+                .set(&StorageKey::WebhookRegistry, &registry),
+            None => env
+                .storage()
+                .persistent()
+                .remove(&StorageKey::WebhookRegistry),
+        }
+    }
+
+    /// @notice Returns the linked webhook registry, if any.
+    pub fn get_webhook_registry(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&StorageKey::WebhookRegistry)
+    }
+
     /// @notice Sets bonus cap for employee or period.
     /// @dev Admin-only function. If employee is None, sets period cap.
     /// @param admin Admin address (must be owner).
@@ -842,6 +1591,12 @@ impl BonusSystemContract {
             &StorageKey::ClawbackTotal(incentive_id),
             &new_clawback_total,
         );
+        update_employer_stats(&env, &incentive.employer, |stats| {
+            stats.total_clawed_back = stats
+                .total_clawed_back
+                .checked_add(clawback_amount)
+                .expect("Employer clawback total overflow");
+        });
 
         // Emit event
         env.events().publish(
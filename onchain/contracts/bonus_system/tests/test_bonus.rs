@@ -1,6 +1,13 @@
-use bonus_system::{ApprovalStatus, BonusSystemContract, BonusSystemContractClient, IncentiveKind};
-use soroban_sdk::testutils::{Address as _, Ledger};
-use soroban_sdk::{token, Address, Env};
+use bonus_system::{
+    prorated_payout, ApprovalOutcome, ApprovalStatus, ApprovedCancellation, BonusSystemContract,
+    BonusSystemContractClient, EmployerBonusStats, IncentiveKind, MAX_APPROVAL_BATCH,
+};
+use soroban_sdk::testutils::{Address as _, Events as _, Ledger};
+use soroban_sdk::{token, vec, Address, BytesN, Env, String, TryFromVal};
+use webhook_contract::{
+    NotificationEvent, RetryConfig, SecurityConfig, SignatureMethod, WebhookRegistration,
+    WebhookRegistry, WebhookRegistryClient,
+};
 
 fn create_token<'a>(env: &Env, admin: &Address) -> token::Client<'a> {
     let token_address = env.register_stellar_asset_contract(admin.clone());
@@ -1136,3 +1143,784 @@ fn test_concurrent_bonuses_respect_caps() {
     );
     assert!(result.is_err());
 }
+
+// ============================================
+// DETERMINISTIC INCENTIVE ID TESTS
+// ============================================
+
+#[test]
+fn test_nonced_incentive_ids_are_precomputable() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let other_employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token_client = create_token(&env, &token_admin);
+    let client = create_contract(&env);
+
+    token::StellarAssetClient::new(&env, &token_client.address).mint(&employer, &1_000);
+    client.initialize(&owner);
+
+    let expected = client.compute_incentive_id(&employer, &7);
+    assert_ne!(expected, client.compute_incentive_id(&employer, &8));
+    assert_ne!(expected, client.compute_incentive_id(&other_employer, &7));
+
+    let one_time = client.create_nonced_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &100,
+        &0,
+        &7,
+    );
+    assert_eq!(one_time, expected);
+    assert_eq!(client.get_incentive(&one_time).unwrap().id, expected);
+
+    let recurring = client.create_nonced_recurring_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &100,
+        &3,
+        &0,
+        &60,
+        &8,
+    );
+    assert_eq!(recurring, client.compute_incentive_id(&employer, &8));
+
+    // Counter IDs are unaffected
+    let counted = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &100,
+        &0,
+    );
+    assert_eq!(counted, 1);
+}
+
+#[test]
+#[should_panic(expected = "Incentive nonce already used")]
+fn test_nonced_incentive_rejects_reused_nonce() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token_client = create_token(&env, &token_admin);
+    let client = create_contract(&env);
+
+    token::StellarAssetClient::new(&env, &token_client.address).mint(&employer, &1_000);
+    client.initialize(&owner);
+
+    for _ in 0..2 {
+        client.create_nonced_one_time_bonus(
+            &employer,
+            &employee,
+            &approver,
+            &token_client.address,
+            &100,
+            &0,
+            &7,
+        );
+    }
+}
+
+// ============================================
+// BATCH APPROVAL TESTS
+// ============================================
+
+#[test]
+fn test_batch_approve_reports_per_id_outcomes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let other_approver = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token_client = create_token(&env, &token_admin);
+    let client = create_contract(&env);
+
+    token::StellarAssetClient::new(&env, &token_client.address).mint(&employer, &1_000);
+    client.initialize(&owner);
+
+    let create = |approver: &Address| {
+        client.create_one_time_bonus(
+            &employer,
+            &employee,
+            approver,
+            &token_client.address,
+            &100,
+            &0,
+        )
+    };
+    let first = create(&approver);
+    let second = create(&approver);
+    let foreign = create(&other_approver);
+    let already_rejected = create(&approver);
+    client.reject_incentive(&approver, &already_rejected);
+
+    let results = client.batch_approve_incentives(
+        &approver,
+        &vec![&env, first, foreign, 999, already_rejected, second],
+    );
+    let outcomes: std::vec::Vec<_> = results
+        .iter()
+        .map(|r| (r.incentive_id, r.outcome))
+        .collect();
+    assert_eq!(
+        outcomes,
+        [
+            (first, ApprovalOutcome::Applied),
+            (foreign, ApprovalOutcome::NotApprover),
+            (999, ApprovalOutcome::NotFound),
+            (already_rejected, ApprovalOutcome::NotPending),
+            (second, ApprovalOutcome::Applied),
+        ]
+    );
+    assert_eq!(
+        client.get_incentive(&first).unwrap().status,
+        ApprovalStatus::Approved
+    );
+    assert_eq!(
+        client.get_incentive(&foreign).unwrap().status,
+        ApprovalStatus::Pending
+    );
+
+    let results = client.batch_reject_incentives(&other_approver, &vec![&env, foreign, first]);
+    assert_eq!(results.get(0).unwrap().outcome, ApprovalOutcome::Applied);
+    assert_eq!(
+        results.get(1).unwrap().outcome,
+        ApprovalOutcome::NotApprover
+    );
+    assert_eq!(
+        client.get_incentive(&foreign).unwrap().status,
+        ApprovalStatus::Rejected
+    );
+}
+
+#[test]
+#[should_panic(expected = "Batch exceeds maximum size")]
+fn test_batch_approve_rejects_oversized_batch() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let client = create_contract(&env);
+    client.initialize(&owner);
+
+    let mut ids = soroban_sdk::Vec::new(&env);
+    for id in 0..=u128::from(MAX_APPROVAL_BATCH) {
+        ids.push_back(id);
+    }
+    client.batch_approve_incentives(&approver, &ids);
+}
+
+// ============================================
+// EMPLOYER ANALYTICS TESTS
+// ============================================
+
+#[test]
+fn test_employer_bonus_stats_track_each_transition() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token_client = create_token(&env, &token_admin);
+    let client = create_contract(&env);
+
+    token::StellarAssetClient::new(&env, &token_client.address).mint(&employer, &1_000);
+    client.initialize(&owner);
+    assert_eq!(
+        client.get_employer_bonus_stats(&employer),
+        EmployerBonusStats::default()
+    );
+    set_time(&env, 1_000);
+
+    let one_time = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &100,
+        &0,
+    );
+    let recurring = client.create_recurring_incentive(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &50,
+        &3,
+        &1_000,
+        &100,
+    );
+    let cancelled = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &200,
+        &0,
+    );
+
+    client.approve_incentive(&approver, &one_time);
+    client.approve_incentive(&approver, &recurring);
+    client.claim_incentive(&employee, &one_time);
+    client.claim_incentive(&employee, &recurring);
+    client.reject_incentive(&approver, &cancelled);
+    client.cancel_incentive(&employer, &cancelled);
+    client.execute_clawback(&owner, &employee, &one_time, &40, &0);
+
+    assert_eq!(
+        client.get_employer_bonus_stats(&employer),
+        EmployerBonusStats {
+            total_escrowed: 450,
+            total_claimed: 150,
+            total_refunded: 200,
+            total_clawed_back: 40,
+            open_incentives: 1,
+        }
+    );
+
+    set_time(&env, 1_200);
+    client.claim_incentive(&employee, &recurring);
+    let stats = client.get_employer_bonus_stats(&employer);
+    assert_eq!(stats.total_claimed, 250);
+    assert_eq!(stats.open_incentives, 0);
+}
+
+// ============================================
+// PAYOUT ADDRESS TESTS
+// ============================================
+
+#[test]
+fn test_claims_go_to_registered_payout_address() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let cold_wallet = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token_client = create_token(&env, &token_admin);
+    let client = create_contract(&env);
+
+    token::StellarAssetClient::new(&env, &token_client.address).mint(&employer, &1_000);
+    client.initialize(&owner);
+    assert_eq!(client.get_payout_address(&employee), employee);
+
+    client.set_payout_address(&employee, &cold_wallet);
+    assert_eq!(client.get_payout_address(&employee), cold_wallet);
+
+    let first = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &300,
+        &0,
+    );
+    client.approve_incentive(&approver, &first);
+    client.claim_incentive(&employee, &first);
+    assert_eq!(token_client.balance(&cold_wallet), 300);
+    assert_eq!(token_client.balance(&employee), 0);
+
+    // Registering the identity address restores the default
+    client.set_payout_address(&employee, &employee);
+    assert_eq!(client.get_payout_address(&employee), employee);
+    let second = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &200,
+        &0,
+    );
+    client.approve_incentive(&approver, &second);
+    client.claim_incentive(&employee, &second);
+    assert_eq!(token_client.balance(&employee), 200);
+}
+
+#[test]
+#[should_panic(expected = "Only employee can claim")]
+fn test_payout_address_cannot_claim_itself() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let cold_wallet = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token_client = create_token(&env, &token_admin);
+    let client = create_contract(&env);
+
+    token::StellarAssetClient::new(&env, &token_client.address).mint(&employer, &1_000);
+    client.initialize(&owner);
+    client.set_payout_address(&employee, &cold_wallet);
+
+    let incentive_id = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &300,
+        &0,
+    );
+    client.approve_incentive(&approver, &incentive_id);
+    client.claim_incentive(&cold_wallet, &incentive_id);
+}
+
+// ============================================
+// BULK CLAIM TESTS
+// ============================================
+
+#[test]
+fn test_claim_vested_incentives_claims_only_due_payouts_from_employer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let other_employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token_client = create_token(&env, &token_admin);
+    let client = create_contract(&env);
+
+    let minter = token::StellarAssetClient::new(&env, &token_client.address);
+    minter.mint(&employer, &10_000);
+    minter.mint(&other_employer, &10_000);
+    client.initialize(&owner);
+    set_time(&env, 1_000);
+
+    let bonus = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &500,
+        &1_000,
+    );
+    let recurring = client.create_recurring_incentive(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &100,
+        &3,
+        &1_000,
+        &60,
+    );
+    let locked = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &700,
+        &5_000,
+    );
+    let pending = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &900,
+        &1_000,
+    );
+    let foreign = client.create_one_time_bonus(
+        &other_employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &1_100,
+        &1_000,
+    );
+    for id in [bonus, recurring, locked, foreign] {
+        client.approve_incentive(&approver, &id);
+    }
+
+    set_time(&env, 1_060);
+    assert_eq!(client.claim_vested_incentives(&employee, &employer), 700);
+    assert_eq!(token_client.balance(&employee), 700);
+    assert_eq!(
+        client.get_open_incentives(&employee),
+        vec![&env, recurring, locked, pending, foreign]
+    );
+
+    // Nothing new has vested: returns zero rather than failing
+    assert_eq!(client.claim_vested_incentives(&employee, &employer), 0);
+}
+
+#[test]
+fn test_prorated_payout_edge_cases() {
+    assert_eq!(prorated_payout(100, 0, 10), 0);
+    assert_eq!(prorated_payout(100, 5, 10), 50);
+    assert_eq!(prorated_payout(100, 10, 10), 100);
+    // Time past the interval earns no more than the whole payout
+    assert_eq!(prorated_payout(100, 25, 10), 100);
+    // Rounds down
+    assert_eq!(prorated_payout(100, 3, 7), 42);
+    assert_eq!(prorated_payout(1, 9, 10), 0);
+    assert_eq!(prorated_payout(100, 5, 0), 0);
+    assert_eq!(prorated_payout(0, 5, 10), 0);
+    // No overflow at the extremes
+    assert_eq!(prorated_payout(i128::MAX, 1, 2), i128::MAX / 2);
+    assert_eq!(prorated_payout(i128::MAX, u64::MAX, u64::MAX), i128::MAX);
+    assert_eq!(
+        prorated_payout(i128::MAX, u64::MAX - 1, u64::MAX),
+        i128::MAX - i128::MAX / i128::from(u64::MAX) - 1
+    );
+}
+
+fn approved_recurring<'a>(
+    env: &Env,
+) -> (
+    BonusSystemContractClient<'a>,
+    token::Client<'a>,
+    Address,
+    Address,
+    Address,
+    u128,
+) {
+    let owner = Address::generate(env);
+    let employer = Address::generate(env);
+    let employee = Address::generate(env);
+    let approver = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let token_client = create_token(env, &token_admin);
+    let client = create_contract(env);
+
+    token::StellarAssetClient::new(env, &token_client.address).mint(&employer, &500);
+
+    client.initialize(&owner);
+    // 5 payouts of 100, vesting at 1_000, 1_010, ..., 1_040
+    let incentive_id = client.create_recurring_incentive(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &100,
+        &5,
+        &1_000,
+        &10,
+    );
+    client.approve_incentive(&approver, &incentive_id);
+    (
+        client,
+        token_client,
+        employer,
+        employee,
+        approver,
+        incentive_id,
+    )
+}
+
+#[test]
+fn test_cancel_approved_incentive_prorates_in_progress_interval() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_client, employer, employee, approver, incentive_id) =
+        approved_recurring(&env);
+
+    set_time(&env, 1_005);
+    assert_eq!(client.claim_incentive(&employee, &incentive_id), 100);
+
+    // Payouts 2 and 3 have vested; payout 4 is 4s into its 10s interval
+    set_time(&env, 1_024);
+    assert_eq!(
+        client.cancel_approved_incentive(&employer, &incentive_id),
+        ApprovedCancellation {
+            vested_amount: 200,
+            prorated_amount: 40,
+            refunded_amount: 160,
+        }
+    );
+    assert!(env.auths().iter().any(|(address, _)| *address == approver));
+
+    assert_eq!(token_client.balance(&employee), 340);
+    assert_eq!(token_client.balance(&employer), 160);
+    let stored = client.get_incentive(&incentive_id).unwrap();
+    assert_eq!(stored.status, ApprovalStatus::Cancelled);
+    assert_eq!(stored.claimed_payouts, 3);
+    let stats = client.get_employer_bonus_stats(&employer);
+    assert_eq!(
+        (
+            stats.total_claimed,
+            stats.total_refunded,
+            stats.open_incentives
+        ),
+        (340, 160, 0)
+    );
+    assert!(client.get_open_incentives(&employee).is_empty());
+}
+
+#[test]
+fn test_cancel_approved_incentive_outside_schedule_pays_whole_payouts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_client, employer, employee, _, incentive_id) = approved_recurring(&env);
+
+    // Before the first payout nothing is in progress
+    set_time(&env, 900);
+    assert_eq!(
+        client.cancel_approved_incentive(&employer, &incentive_id),
+        ApprovedCancellation {
+            vested_amount: 0,
+            prorated_amount: 0,
+            refunded_amount: 500,
+        }
+    );
+    assert_eq!(token_client.balance(&employee), 0);
+
+    // After the last payout vested there is nothing left to prorate
+    let (client, token_client, employer, employee, _, incentive_id) = approved_recurring(&env);
+    set_time(&env, 1_045);
+    assert_eq!(
+        client.cancel_approved_incentive(&employer, &incentive_id),
+        ApprovedCancellation {
+            vested_amount: 500,
+            prorated_amount: 0,
+            refunded_amount: 0,
+        }
+    );
+    assert_eq!(token_client.balance(&employee), 500);
+}
+
+#[test]
+#[should_panic(expected = "Only recurring incentives can be prorated")]
+fn test_cancel_approved_one_time_bonus_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let approver = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token_client = create_token(&env, &token_admin);
+    let client = create_contract(&env);
+
+    token::StellarAssetClient::new(&env, &token_client.address).mint(&employer, &500);
+
+    client.initialize(&owner);
+    let incentive_id = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &200,
+        &10,
+    );
+    client.approve_incentive(&approver, &incentive_id);
+    client.cancel_approved_incentive(&employer, &incentive_id);
+}
+
+#[test]
+fn test_keeper_executes_due_scheduled_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_client, _, employee, _, incentive_id) = approved_recurring(&env);
+
+    set_time(&env, 1_005);
+    client.schedule_claim(&incentive_id, &1_025);
+    assert_eq!(client.get_scheduled_claim(&incentive_id), Some(1_025));
+
+    // Not due yet: skipped and kept
+    set_time(&env, 1_020);
+    let ids = vec![&env, incentive_id, 999];
+    assert_eq!(client.execute_scheduled_claims(&ids), 0);
+    assert_eq!(client.get_scheduled_claim(&incentive_id), Some(1_025));
+
+    set_time(&env, 1_025);
+    assert_eq!(client.execute_scheduled_claims(&ids), 300);
+    assert_eq!(token_client.balance(&employee), 300);
+    assert_eq!(client.get_scheduled_claim(&incentive_id), None);
+    assert_eq!(client.execute_scheduled_claims(&ids), 0);
+}
+
+#[test]
+fn test_scheduled_claim_after_direct_claim_pays_nothing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_client, _, employee, _, incentive_id) = approved_recurring(&env);
+
+    client.schedule_claim(&incentive_id, &1_015);
+    set_time(&env, 1_015);
+    assert_eq!(client.claim_incentive(&employee, &incentive_id), 200);
+    assert_eq!(
+        client.execute_scheduled_claims(&vec![&env, incentive_id]),
+        0
+    );
+    assert_eq!(client.get_scheduled_claim(&incentive_id), None);
+    assert_eq!(token_client.balance(&employee), 200);
+}
+
+#[test]
+#[should_panic(expected = "No scheduled claim")]
+fn test_cancel_scheduled_claim() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, _, _, incentive_id) = approved_recurring(&env);
+
+    client.schedule_claim(&incentive_id, &2_000);
+    client.cancel_scheduled_claim(&incentive_id);
+    assert_eq!(client.get_scheduled_claim(&incentive_id), None);
+    client.cancel_scheduled_claim(&incentive_id);
+}
+
+#[test]
+#[should_panic(expected = "Execution time must be in the future")]
+fn test_schedule_claim_in_the_past_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, _, _, _, incentive_id) = approved_recurring(&env);
+
+    set_time(&env, 1_500);
+    client.schedule_claim(&incentive_id, &1_500);
+}
+
+/// Links a webhook registry to the bonus contract, with one webhook of
+/// `employer` subscribed to `event`.
+fn link_registry<'a>(
+    env: &Env,
+    client: &BonusSystemContractClient,
+    employer: &Address,
+    event: &str,
+) -> WebhookRegistryClient<'a> {
+    let registry = WebhookRegistryClient::new(env, &env.register(WebhookRegistry, ()));
+    let admin = Address::generate(env);
+    registry.initialize(&admin);
+    registry.set_notifier(&admin, &client.address, &true);
+    registry.register_webhook(
+        employer,
+        &WebhookRegistration {
+            name: String::from_str(env, "bonuses"),
+            description: String::from_str(env, ""),
+            url: String::from_str(env, "https://hooks.example.com"),
+            events: vec![env, String::from_str(env, event)],
+            secret_hash: BytesN::from_array(env, &[1; 32]),
+            retry_config: RetryConfig {
+                max_retries: 3,
+                retry_delay: 5,
+                exponential_backoff: true,
+                max_delay: 60,
+            },
+            security_config: SecurityConfig {
+                signature_method: SignatureMethod::HmacSha256,
+                rate_limit_per_minute: 60,
+                require_tls: true,
+            },
+        },
+    );
+    client.set_webhook_registry(
+        &client.get_owner().unwrap(),
+        &Some(registry.address.clone()),
+    );
+    registry
+}
+
+/// (event type, reference, amount) of each notification the registry
+/// emitted during the last invocation.
+fn notifications(env: &Env, registry: &Address) -> std::vec::Vec<(String, u128, i128)> {
+    env.events()
+        .all()
+        .iter()
+        .filter(|(contract, _, _)| contract == registry)
+        .map(|(_, topics, data)| {
+            let event = NotificationEvent::try_from_val(env, &data).unwrap();
+            (
+                String::try_from_val(env, &topics.get(0).unwrap()).unwrap(),
+                event.reference,
+                event.amount,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_claims_notify_the_webhook_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, employer, employee, _, incentive_id) = approved_recurring(&env);
+    let registry = link_registry(&env, &client, &employer, "incentive_claimed");
+
+    set_time(&env, 1_015);
+    assert_eq!(client.claim_incentive(&employee, &incentive_id), 200);
+    let notifications: std::vec::Vec<_> = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract, _, _)| *contract == registry.address)
+        .collect();
+    assert_eq!(notifications.len(), 1);
+    let (_, topics, data) = &notifications[0];
+    assert_eq!(
+        String::try_from_val(&env, &topics.get(0).unwrap()).unwrap(),
+        String::from_str(&env, "incentive_claimed")
+    );
+    let event = NotificationEvent::try_from_val(&env, data).unwrap();
+    assert_eq!(
+        (event.source, event.subject, event.reference, event.amount),
+        (client.address.clone(), employee, incentive_id, 200)
+    );
+}
+
+#[test]
+fn test_creation_and_approval_notify_the_webhook_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token_client, employer, employee, approver, _) = approved_recurring(&env);
+    token::StellarAssetClient::new(&env, &token_client.address).mint(&employer, &250);
+    let registry = link_registry(&env, &client, &employer, "*");
+
+    let incentive_id = client.create_one_time_bonus(
+        &employer,
+        &employee,
+        &approver,
+        &token_client.address,
+        &250,
+        &2_000,
+    );
+    assert_eq!(
+        notifications(&env, &registry.address),
+        [(
+            String::from_str(&env, "incentive_created"),
+            incentive_id,
+            250
+        )]
+    );
+
+    client.approve_incentive(&approver, &incentive_id);
+    assert_eq!(
+        notifications(&env, &registry.address),
+        [(
+            String::from_str(&env, "incentive_approved"),
+            incentive_id,
+            250
+        )]
+    );
+}
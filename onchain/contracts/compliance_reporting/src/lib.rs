@@ -26,10 +26,10 @@
 //! Off-chain indexers should consume events and snapshot data independently.
 
 use audit_logger::{AuditLogEntry, AuditLoggerContractClient};
-use payment_history::{PaymentHistoryContractClient, PaymentRecord};
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, symbol_short, Address, Bytes, Env, Vec,
 };
+use stellopay_interfaces::history::{PaymentHistoryClient, PaymentRecord};
 
 /// Maximum number of records that can be returned in a single `generate_report`
 /// call. Prevents instruction-limit overflows on Soroban.
@@ -729,7 +729,7 @@ impl ComplianceReportingContract {
 
         // 3. Fetch PaymentHistory records for the employee, failing closed with a
         //    typed DependencyUnavailable error if the dependency call fails.
-        let ph_client = PaymentHistoryContractClient::new(&env, &payment_history_addr);
+        let ph_client = PaymentHistoryClient::new(&env, &payment_history_addr);
         let payments = ph_client
             .try_get_payments_by_employee(&employee, &1, &MAX_QUERY_LIMIT)
             .map_err(|_| ComplianceError::DependencyUnavailable)?
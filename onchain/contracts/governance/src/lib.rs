@@ -9,6 +9,7 @@ use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, Address, BytesN, Env, IntoVal, Symbol,
     Val, Vec,
 };
+pub use stellopay_interfaces::treasury::ApprovedSpend;
 use withdrawal_timelock::{
     OperationKind as TimelockOperationKind, OperationStatus as TimelockOperationStatus,
     TimelockedOperation, WithdrawalTimelockClient,
@@ -85,6 +86,52 @@ pub enum ProposalKind {
     ///
     /// Layout: `(new_arbiter)`.
     ArbiterChange(Address),
+    /// Issue a soulbound credential to a vetted holder, replacing any
+    /// earlier credential for the same role.
+    ///
+    /// Layout: `(holder, role, expires_at)`; `None` never expires.
+    IssueCredential(Address, CredentialRole, Option<u64>),
+    /// Revoke a holder's credential.
+    ///
+    /// Layout: `(holder, role)`.
+    RevokeCredential(Address, CredentialRole),
+    /// Allow-list a yield strategy employers may route idle escrow to, or
+    /// remove it from the list.
+    ///
+    /// Layout: `(strategy, approved)`.
+    YieldStrategy(Address, bool),
+    /// Approve a transfer out of a contract's protocol treasury. The treasury
+    /// looks the spend up by proposal id and pays it once.
+    ///
+    /// Layout: `(spend)`.
+    TreasurySpend(ApprovedSpend),
+}
+
+/// Roles governance can vet holders for.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CredentialRole {
+    /// May be granted access to employers' audit trails.
+    Auditor,
+    /// May be assigned to resolve disputes.
+    Mediator,
+    /// May attest payroll exports imported from off-chain payroll systems.
+    PayrollAttestor,
+}
+
+/// Non-transferable credential issued by governance.
+///
+/// Credentials are stored against the holder and have no transfer path; a
+/// holder that changes address needs a new credential.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Credential {
+    pub holder: Address,
+    pub role: CredentialRole,
+    pub issued_at: u64,
+    /// Timestamp from which the credential is no longer valid, if any.
+    pub expires_at: Option<u64>,
+    pub revoked: bool,
 }
 
 /// Lifecycle status for a proposal.
@@ -167,6 +214,17 @@ pub enum StorageKey {
     Parameter(Symbol),
     Arbiter,
     ApprovedUpgrade(Address),
+    Credential(Address, CredentialRole),
+    ApprovedStrategy(Address),
+    ApprovedSpend(u128),
+}
+
+fn revoke_stored_credential(env: &Env, holder: &Address, role: CredentialRole) {
+    let key = StorageKey::Credential(holder.clone(), role);
+    if let Some(mut credential) = env.storage().persistent().get::<_, Credential>(&key) {
+        credential.revoked = true;
+        env.storage().persistent().set(&key, &credential);
+    }
 }
 
 fn require_initialized(env: &Env) -> Result<(), GovernanceError> {
@@ -629,6 +687,36 @@ impl GovernanceContract {
                     .persistent()
                     .set(&StorageKey::Arbiter, new_arbiter);
             }
+            ProposalKind::IssueCredential(holder, role, expires_at) => {
+                env.storage().persistent().set(
+                    &StorageKey::Credential(holder.clone(), *role),
+                    &Credential {
+                        holder: holder.clone(),
+                        role: *role,
+                        issued_at: env.ledger().timestamp(),
+                        expires_at: *expires_at,
+                        revoked: false,
+                    },
+                );
+            }
+            ProposalKind::RevokeCredential(holder, role) => {
+                revoke_stored_credential(&env, holder, *role);
+            }
+            ProposalKind::YieldStrategy(strategy, true) => {
+                env.storage()
+                    .persistent()
+                    .set(&StorageKey::ApprovedStrategy(strategy.clone()), &true);
+            }
+            ProposalKind::YieldStrategy(strategy, false) => {
+                env.storage()
+                    .persistent()
+                    .remove(&StorageKey::ApprovedStrategy(strategy.clone()));
+            }
+            ProposalKind::TreasurySpend(spend) => {
+                env.storage()
+                    .persistent()
+                    .set(&StorageKey::ApprovedSpend(proposal_id), spend);
+            }
         }
 
         proposal.status = ProposalStatus::Executed;
@@ -667,6 +755,24 @@ impl GovernanceContract {
         Ok(())
     }
 
+    /// @notice Revokes a credential immediately, without a proposal.
+    /// @dev Owner-only emergency path for compromised holders; issuing still requires a
+    ///      governance proposal. Revoking a missing credential is a no-op.
+    /// @param caller Owner address.
+    /// @param holder Credential holder.
+    /// @param role Role of the credential to revoke.
+    pub fn revoke_credential(
+        env: Env,
+        caller: Address,
+        holder: Address,
+        role: CredentialRole,
+    ) -> Result<(), GovernanceError> {
+        require_initialized(&env)?;
+        require_owner(&env, &caller)?;
+        revoke_stored_credential(&env, &holder, role);
+        Ok(())
+    }
+
     /// @notice Backward-compatible alias for `create_proposal`.
     pub fn propose(
         env: Env,
@@ -858,6 +964,42 @@ impl GovernanceContract {
         env.storage().persistent().get(&StorageKey::Arbiter)
     }
 
+    /// @notice Returns a holder's credential for a role, including revoked or expired ones.
+    pub fn get_credential(env: Env, holder: Address, role: CredentialRole) -> Option<Credential> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::Credential(holder, role))
+    }
+
+    /// @notice Returns whether a holder has an unrevoked, unexpired credential for a role.
+    /// @dev Consumers call this at the moment they rely on the credential, so revocation and
+    ///      expiry take effect without notifying them.
+    pub fn has_valid_credential(env: Env, holder: Address, role: CredentialRole) -> bool {
+        match Self::get_credential(env.clone(), holder, role) {
+            Some(credential) => {
+                !credential.revoked
+                    && credential
+                        .expires_at
+                        .map_or(true, |expires_at| env.ledger().timestamp() < expires_at)
+            }
+            None => false,
+        }
+    }
+
+    /// @notice Returns whether a yield strategy is on the allow-list.
+    pub fn is_strategy_approved(env: Env, strategy: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&StorageKey::ApprovedStrategy(strategy))
+    }
+
+    /// @notice Returns the treasury spend an executed proposal approved, if any.
+    pub fn get_approved_spend(env: Env, proposal_id: u128) -> Option<ApprovedSpend> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::ApprovedSpend(proposal_id))
+    }
+
     /// @notice Returns the last approved upgrade hash for a target contract.
     pub fn get_approved_upgrade(env: Env, target: Address) -> Option<BytesN<32>> {
         env.storage()
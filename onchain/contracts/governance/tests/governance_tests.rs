@@ -1,8 +1,8 @@
 #![cfg(test)]
 
 use governance::{
-    GovernanceContract, GovernanceContractClient, GovernanceError, ProposalKind, ProposalPage,
-    ProposalStatus, VoteChoice,
+    ApprovedSpend, CredentialRole, GovernanceContract, GovernanceContractClient, GovernanceError,
+    ProposalKind, ProposalPage, ProposalStatus, VoteChoice,
 };
 use multisig::{MultisigContract, MultisigContractClient};
 use rbac::{RbacContract, RbacContractClient, Role};
@@ -90,6 +90,24 @@ fn advance_time(env: &Env, seconds: u64) {
     });
 }
 
+/// Passes and executes a proposal with the votes of the owner and employer A.
+fn pass_proposal(env: &Env, setup: &TestContracts, kind: ProposalKind) -> u128 {
+    let proposal_id = setup.governance.create_proposal(&setup.owner, &kind);
+    setup
+        .governance
+        .cast_vote(&setup.owner, &proposal_id, &VoteChoice::For);
+    setup
+        .governance
+        .cast_vote(&setup.employer_a, &proposal_id, &VoteChoice::For);
+    advance_time(env, 3601);
+    setup.governance.finalize_proposal(&proposal_id);
+    advance_time(env, 60);
+    setup
+        .governance
+        .execute_proposal(&setup.signer_a, &proposal_id);
+    proposal_id
+}
+
 #[test]
 fn initialize_links_external_contracts() {
     let env = create_env();
@@ -724,3 +742,130 @@ fn list_proposals_status_filter_with_pagination() {
 
     assert_eq!(active_proposals.len(), 5);
 }
+
+#[test]
+fn credentials_are_issued_and_revoked_by_proposal() {
+    let env = create_env();
+    let setup = setup(&env);
+    let mediator = Address::generate(&env);
+
+    assert!(!setup
+        .governance
+        .has_valid_credential(&mediator, &CredentialRole::Mediator));
+    pass_proposal(
+        &env,
+        &setup,
+        ProposalKind::IssueCredential(mediator.clone(), CredentialRole::Mediator, None),
+    );
+    assert!(setup
+        .governance
+        .has_valid_credential(&mediator, &CredentialRole::Mediator));
+    // A credential covers only the role it was issued for
+    assert!(!setup
+        .governance
+        .has_valid_credential(&mediator, &CredentialRole::Auditor));
+
+    pass_proposal(
+        &env,
+        &setup,
+        ProposalKind::RevokeCredential(mediator.clone(), CredentialRole::Mediator),
+    );
+    assert!(!setup
+        .governance
+        .has_valid_credential(&mediator, &CredentialRole::Mediator));
+    assert!(
+        setup
+            .governance
+            .get_credential(&mediator, &CredentialRole::Mediator)
+            .unwrap()
+            .revoked
+    );
+}
+
+#[test]
+fn credential_expires_and_owner_can_revoke_immediately() {
+    let env = create_env();
+    let setup = setup(&env);
+    let auditor = Address::generate(&env);
+    let expires_at = env.ledger().timestamp() + 10_000;
+
+    pass_proposal(
+        &env,
+        &setup,
+        ProposalKind::IssueCredential(auditor.clone(), CredentialRole::Auditor, Some(expires_at)),
+    );
+    assert!(setup
+        .governance
+        .has_valid_credential(&auditor, &CredentialRole::Auditor));
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = expires_at);
+    assert!(!setup
+        .governance
+        .has_valid_credential(&auditor, &CredentialRole::Auditor));
+
+    let mediator = Address::generate(&env);
+    pass_proposal(
+        &env,
+        &setup,
+        ProposalKind::IssueCredential(mediator.clone(), CredentialRole::Mediator, None),
+    );
+    assert_eq!(
+        setup.governance.try_revoke_credential(
+            &setup.outsider,
+            &mediator,
+            &CredentialRole::Mediator
+        ),
+        Err(Ok(GovernanceError::NotOwner))
+    );
+    setup
+        .governance
+        .revoke_credential(&setup.owner, &mediator, &CredentialRole::Mediator);
+    assert!(!setup
+        .governance
+        .has_valid_credential(&mediator, &CredentialRole::Mediator));
+}
+
+#[test]
+fn yield_strategies_are_allow_listed_by_proposal() {
+    let env = create_env();
+    let setup = setup(&env);
+    let strategy = Address::generate(&env);
+
+    assert!(!setup.governance.is_strategy_approved(&strategy));
+    pass_proposal(
+        &env,
+        &setup,
+        ProposalKind::YieldStrategy(strategy.clone(), true),
+    );
+    assert!(setup.governance.is_strategy_approved(&strategy));
+
+    pass_proposal(
+        &env,
+        &setup,
+        ProposalKind::YieldStrategy(strategy.clone(), false),
+    );
+    assert!(!setup.governance.is_strategy_approved(&strategy));
+}
+
+#[test]
+fn treasury_spends_are_recorded_by_proposal() {
+    let env = create_env();
+    let setup = setup(&env);
+    let spend = ApprovedSpend {
+        treasury: Address::generate(&env),
+        token: Address::generate(&env),
+        to: Address::generate(&env),
+        amount: 500,
+    };
+
+    let pending = setup
+        .governance
+        .create_proposal(&setup.owner, &ProposalKind::TreasurySpend(spend.clone()));
+    assert_eq!(setup.governance.get_approved_spend(&pending), None);
+
+    let proposal_id = pass_proposal(&env, &setup, ProposalKind::TreasurySpend(spend.clone()));
+    assert_eq!(
+        setup.governance.get_approved_spend(&proposal_id),
+        Some(spend)
+    );
+}
@@ -39,8 +39,9 @@
 //!   `record_payment`. Any other caller receives an `Auth(InvalidAction)` error.
 //! * The contract may only be initialized **once**; subsequent calls panic with
 //!   "Already initialized".
-//! * Records are **immutable**: there is no update or delete path. Index
-//!   entries are written once and never modified, preventing history tampering.
+//! * Records are **immutable** apart from owner-initiated pseudonymization,
+//!   which only replaces the payee address. Index entries are written once and
+//!   never modified, preventing history tampering.
 //! * Index counts can only increase, ensuring no entry can be silently replaced
 //!   and no historical record can be pruned by an unauthorized party.
 //! * `limit` is hard-capped at [`MAX_PAGE_SIZE`] (100) to bound ledger reads
@@ -55,6 +56,26 @@
 //! `payment_hash` (transaction-level reference key). Because records are
 //! immutable, indexers never need to handle update or delete messages.
 //!
+//! ## Subscriptions
+//!
+//! A relayer can `subscribe` to the payments of one employer, employee, or
+//! agreement. Each recorded payment then also emits one `payment_matched`
+//! event per matching subscription, tagged with the subscription ID and the
+//! subscriber as topics, so the relayer can route payments without filtering
+//! the full event stream. At most [`MAX_SUBSCRIPTIONS_PER_FILTER`] active
+//! subscriptions can share a filter, bounding the work added to
+//! `record_payment`.
+//!
+//! ## Pseudonymization
+//!
+//! To honour a legal erasure request, the owner can `pseudonymize_employee`.
+//! The employee's index moves to an opaque replacement ID and the `to` field
+//! of each of their records is replaced by this contract's address, with the
+//! replacement ID in `to_pseudonym`. Amounts, tokens, timestamps and the
+//! agreement and employer indices are left untouched, so accounting totals
+//! still add up. Payments recorded for the employee afterwards are stored the
+//! same way.
+//!
 //! See [`docs/payment-history.md`](../../../../docs/payment-history.md) for the
 //! full integration guide, pagination examples, storage key reference, and
 //! canonical reconciliation patterns.
@@ -64,13 +85,16 @@
 mod events;
 mod storage;
 
-use events::PaymentRecorded;
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Vec};
+use events::{EmployeePseudonymized, PaymentMatched, PaymentRecorded};
+use soroban_sdk::{contract, contractimpl, vec, xdr::ToXdr, Address, BytesN, Env, Vec};
 use storage::StorageKey;
 
-/// Re-export `PaymentRecord` so consumers and tests can import it directly
-/// from the crate root.
-pub use storage::PaymentRecord;
+use storage::RankedPayment;
+/// Re-export the storage types so consumers and tests can import them
+/// directly from the crate root.
+pub use storage::{
+    EmployeeTotals, PaymentRecord, RecipientTotal, Subscription, SubscriptionFilter, TokenTotal,
+};
 
 /// Maximum number of records returned in a single paginated query.
 ///
@@ -80,6 +104,27 @@ pub use storage::PaymentRecord;
 /// silently; no error is raised.
 pub const MAX_PAGE_SIZE: u32 = 100;
 
+/// Maximum number of active subscriptions sharing one filter.
+///
+/// Every recorded payment emits one event per matching subscription across
+/// its three filters, so this bounds the events added to `record_payment`.
+pub const MAX_SUBSCRIPTIONS_PER_FILTER: u32 = 10;
+
+/// Decimal places that `get_employee_totals_normalized` rescales amounts to,
+/// matching Stellar classic assets. Also assumed for unregistered tokens.
+pub const NORMALIZED_DECIMALS: u32 = 7;
+
+/// Highest decimals value accepted by `set_token_decimals`.
+pub const MAX_TOKEN_DECIMALS: u32 = 18;
+
+/// Entries kept per employer leaderboard. Bounds the work each
+/// `record_payment` spends keeping the leaderboards sorted.
+pub const MAX_LEADERBOARD_SIZE: u32 = 10;
+
+/// Length of a top-recipients period. A payment with timestamp `t` counts
+/// towards period `t / LEADERBOARD_PERIOD_SECONDS`.
+pub const LEADERBOARD_PERIOD_SECONDS: u64 = 30 * 86_400;
+
 #[contract]
 pub struct PaymentHistoryContract;
 
@@ -178,6 +223,14 @@ impl PaymentHistoryContract {
 
         let id = global_count;
 
+        // A pseudonymized payee is recorded under its replacement ID only.
+        let to_pseudonym = Self::pseudonym_of(&env, &to);
+        let to = if to_pseudonym.is_some() {
+            env.current_contract_address()
+        } else {
+            to
+        };
+
         // Persist the canonical payment record keyed by its global ID.
         let record = PaymentRecord {
             id,
@@ -187,6 +240,7 @@ impl PaymentHistoryContract {
             amount,
             from: from.clone(),
             to: to.clone(),
+            to_pseudonym: to_pseudonym.clone(),
             timestamp,
         };
         env.storage()
@@ -242,21 +296,56 @@ impl PaymentHistoryContract {
             .set(&StorageKey::EmployerPayment(from.clone(), from_count), &id);
 
         // ── Append-only index: Employee (to) ─────────────────────────────────
-        // Mirrors the agreement index strategy, partitioned by employee address.
+        // Mirrors the agreement index strategy, partitioned by employee address,
+        // or by replacement ID for a pseudonymized employee.
         //
         // Pagination key: EmployeePayment(employee, position) → global_id
-        let mut to_count: u32 = env
-            .storage()
-            .persistent()
-            .get(&StorageKey::EmployeePaymentCount(to.clone()))
-            .unwrap_or(0);
-        to_count += 1;
-        env.storage()
-            .persistent()
-            .set(&StorageKey::EmployeePaymentCount(to.clone()), &to_count);
-        env.storage()
-            .persistent()
-            .set(&StorageKey::EmployeePayment(to.clone(), to_count), &id);
+        //             or: PseudonymPayment(replacement_id, position) → global_id
+        if let Some(replacement_id) = to_pseudonym {
+            let mut to_count: u32 = env
+                .storage()
+                .persistent()
+                .get(&StorageKey::PseudonymPaymentCount(replacement_id.clone()))
+                .unwrap_or(0);
+            to_count += 1;
+            env.storage().persistent().set(
+                &StorageKey::PseudonymPaymentCount(replacement_id.clone()),
+                &to_count,
+            );
+            env.storage()
+                .persistent()
+                .set(&StorageKey::PseudonymPayment(replacement_id, to_count), &id);
+        } else {
+            Self::add_employee_total(&env, &to, &token, amount);
+            Self::rank_recipient(
+                &env,
+                &from,
+                timestamp / LEADERBOARD_PERIOD_SECONDS,
+                &to,
+                Self::normalized_amount(&env, &token, amount),
+            );
+
+            let mut to_count: u32 = env
+                .storage()
+                .persistent()
+                .get(&StorageKey::EmployeePaymentCount(to.clone()))
+                .unwrap_or(0);
+            to_count += 1;
+            env.storage()
+                .persistent()
+                .set(&StorageKey::EmployeePaymentCount(to.clone()), &to_count);
+            env.storage()
+                .persistent()
+                .set(&StorageKey::EmployeePayment(to.clone(), to_count), &id);
+        }
+
+        // ── Bounded leaderboard: Employer's largest payments ─────────────────
+        Self::rank_payment(
+            &env,
+            &from,
+            id,
+            Self::normalized_amount(&env, &token, amount),
+        );
 
         // Emit event so indexers can build real-time payment feeds without
         // polling storage. Both payment_id and payment_hash are included so
@@ -269,12 +358,39 @@ impl PaymentHistoryContract {
                 agreement_id,
                 token,
                 amount,
-                from,
-                to,
+                from: from.clone(),
+                to: to.clone(),
                 timestamp,
             },
         );
 
+        // Targeted events for relayers subscribed to this payment's employer,
+        // employee, or agreement.
+        let filters = vec![
+            &env,
+            SubscriptionFilter::Employer(from),
+            SubscriptionFilter::Employee(to),
+            SubscriptionFilter::Agreement(agreement_id),
+        ];
+        for filter in filters.iter() {
+            for subscription_id in Self::filter_subscriptions(&env, &filter).iter() {
+                let subscription: Subscription = env
+                    .storage()
+                    .persistent()
+                    .get(&StorageKey::Subscription(subscription_id))
+                    .unwrap();
+                events::emit_payment_matched(
+                    &env,
+                    PaymentMatched {
+                        subscription_id,
+                        subscriber: subscription.subscriber,
+                        payment_id: id,
+                        agreement_id,
+                    },
+                );
+            }
+        }
+
         id
     }
 
@@ -497,4 +613,520 @@ impl PaymentHistoryContract {
         }
         result
     }
+
+    /// Register the number of decimals a token uses.
+    ///
+    /// @notice Read by `get_employee_totals_normalized`. Tokens without a
+    /// registration are treated as having [`NORMALIZED_DECIMALS`].
+    ///
+    /// @param token     Stellar asset contract address.
+    /// @param decimals  The token's decimal places, at most [`MAX_TOKEN_DECIMALS`].
+    ///
+    /// @panics "Decimals out of range" if `decimals` exceeds [`MAX_TOKEN_DECIMALS`].
+    pub fn set_token_decimals(env: Env, token: Address, decimals: u32) {
+        let owner: Address = env.storage().persistent().get(&StorageKey::Owner).unwrap();
+        owner.require_auth();
+
+        if decimals > MAX_TOKEN_DECIMALS {
+            panic!("Decimals out of range");
+        }
+        env.storage()
+            .persistent()
+            .set(&StorageKey::TokenDecimals(token), &decimals);
+    }
+
+    /// Return the decimals registered for a token, if any.
+    pub fn get_token_decimals(env: Env, token: Address) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::TokenDecimals(token))
+    }
+
+    /// Return everything an employee has received, per token and in total,
+    /// with amounts rescaled to [`NORMALIZED_DECIMALS`] decimals.
+    ///
+    /// @notice Raw amounts of tokens with different precision cannot be added
+    /// directly; `normalized_total` can. It is a unit count, not a valuation:
+    /// no exchange rates are applied.
+    ///
+    /// @param employee  The employee address to query.
+    /// @return          Per-token breakdown and normalized sum (empty if none).
+    ///
+    /// @panics "Normalized amount overflow" if rescaling overflows `i128`.
+    pub fn get_employee_totals_normalized(env: Env, employee: Address) -> EmployeeTotals {
+        let mut totals = EmployeeTotals {
+            normalized_total: 0,
+            breakdown: Vec::new(&env),
+        };
+        for token in Self::employee_tokens(&env, &employee).iter() {
+            let raw_amount: i128 = env
+                .storage()
+                .persistent()
+                .get(&StorageKey::EmployeeTokenTotal(
+                    employee.clone(),
+                    token.clone(),
+                ))
+                .unwrap_or(0);
+            let decimals =
+                Self::get_token_decimals(env.clone(), token.clone()).unwrap_or(NORMALIZED_DECIMALS);
+            let normalized_amount = Self::normalize(raw_amount, decimals);
+            totals.normalized_total = totals
+                .normalized_total
+                .checked_add(normalized_amount)
+                .expect("Normalized amount overflow");
+            totals.breakdown.push_back(TokenTotal {
+                token,
+                decimals,
+                raw_amount,
+                normalized_amount,
+            });
+        }
+        totals
+    }
+
+    /// Return an employer's largest payments, largest first.
+    ///
+    /// @notice Payments are ranked by amount normalized to
+    /// [`NORMALIZED_DECIMALS`], so tokens of different precision compare
+    /// fairly. Equal amounts keep the earlier payment first.
+    ///
+    /// @dev Read from a leaderboard maintained by `record_payment`; no
+    /// history scan is performed.
+    ///
+    /// @param employer  The employer address to query.
+    /// @param top_n     Entries to return; capped at [`MAX_LEADERBOARD_SIZE`].
+    /// @return          Up to `top_n` `PaymentRecord`s.
+    pub fn get_largest_payments(env: Env, employer: Address, top_n: u32) -> Vec<PaymentRecord> {
+        let board: Vec<RankedPayment> = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::LargestPayments(employer))
+            .unwrap_or(Vec::new(&env));
+        let mut result = Vec::new(&env);
+        for entry in board.iter().take(top_n as usize) {
+            let record: PaymentRecord = env
+                .storage()
+                .persistent()
+                .get(&StorageKey::Payment(entry.payment_id))
+                .unwrap();
+            result.push_back(record);
+        }
+        result
+    }
+
+    /// Return the recipients an employer paid the most in a period, largest
+    /// first.
+    ///
+    /// @notice Totals are normalized to [`NORMALIZED_DECIMALS`] and summed
+    /// across tokens. Payments to pseudonymized employees are not counted.
+    ///
+    /// @param employer  The employer address to query.
+    /// @param period    `timestamp / LEADERBOARD_PERIOD_SECONDS` of the period.
+    /// @param top_n     Entries to return; capped at [`MAX_LEADERBOARD_SIZE`].
+    /// @return          Up to `top_n` recipients with their period totals.
+    pub fn get_top_recipients(
+        env: Env,
+        employer: Address,
+        period: u64,
+        top_n: u32,
+    ) -> Vec<RecipientTotal> {
+        let board: Vec<RecipientTotal> = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::TopRecipients(employer, period))
+            .unwrap_or(Vec::new(&env));
+        if top_n >= board.len() {
+            board
+        } else {
+            board.slice(0..top_n)
+        }
+    }
+
+    /// Replace an employee's address in the payment history with an opaque
+    /// identifier.
+    ///
+    /// @notice Supports legal erasure requests without changing any amount,
+    /// token, timestamp or count. Afterwards the employee's payments are only
+    /// reachable through `get_payments_by_pseudonym(replacement_id, ..)`.
+    ///
+    /// @dev Every record paid to `employee` has `to` replaced by this
+    /// contract's address and `to_pseudonym` set to `replacement_id`. The
+    /// `EmployeePayment` index moves position-for-position to
+    /// `PseudonymPayment` and the old keys are removed, as are the employee's
+    /// per-token totals and subscriptions filtering on the employee. Only `sha256(xdr(employee))` is kept, so
+    /// later payments to the same address are pseudonymized on arrival.
+    /// Work grows with the employee's history; very large histories may
+    /// exceed a single invocation's resource budget.
+    ///
+    /// @param employee        The address to erase.
+    /// @param replacement_id  Opaque identifier chosen by the owner.
+    ///
+    /// @panics "Employee already pseudonymized" on a second call for the same address.
+    /// @panics "Replacement ID already in use" if the ID was assigned before.
+    pub fn pseudonymize_employee(env: Env, employee: Address, replacement_id: BytesN<32>) {
+        let owner: Address = env.storage().persistent().get(&StorageKey::Owner).unwrap();
+        owner.require_auth();
+
+        if Self::pseudonym_of(&env, &employee).is_some() {
+            panic!("Employee already pseudonymized");
+        }
+        if env
+            .storage()
+            .persistent()
+            .has(&StorageKey::PseudonymPaymentCount(replacement_id.clone()))
+        {
+            panic!("Replacement ID already in use");
+        }
+
+        let placeholder = env.current_contract_address();
+        let count = Self::get_employee_payment_count(env.clone(), employee.clone());
+        for i in 1..=count {
+            let global_id: u128 = env
+                .storage()
+                .persistent()
+                .get(&StorageKey::EmployeePayment(employee.clone(), i))
+                .unwrap();
+            let mut record: PaymentRecord = env
+                .storage()
+                .persistent()
+                .get(&StorageKey::Payment(global_id))
+                .unwrap();
+            record.to = placeholder.clone();
+            record.to_pseudonym = Some(replacement_id.clone());
+            env.storage()
+                .persistent()
+                .set(&StorageKey::Payment(global_id), &record);
+            env.storage()
+                .persistent()
+                .remove(&StorageKey::EmployeePayment(employee.clone(), i));
+            env.storage().persistent().set(
+                &StorageKey::PseudonymPayment(replacement_id.clone(), i),
+                &global_id,
+            );
+        }
+        env.storage()
+            .persistent()
+            .remove(&StorageKey::EmployeePaymentCount(employee.clone()));
+        env.storage().persistent().set(
+            &StorageKey::PseudonymPaymentCount(replacement_id.clone()),
+            &count,
+        );
+
+        for token in Self::employee_tokens(&env, &employee).iter() {
+            env.storage()
+                .persistent()
+                .remove(&StorageKey::EmployeeTokenTotal(employee.clone(), token));
+        }
+        env.storage()
+            .persistent()
+            .remove(&StorageKey::EmployeeTokens(employee.clone()));
+
+        let filter = SubscriptionFilter::Employee(employee.clone());
+        for subscription_id in Self::filter_subscriptions(&env, &filter).iter() {
+            env.storage()
+                .persistent()
+                .remove(&StorageKey::Subscription(subscription_id));
+        }
+        env.storage()
+            .persistent()
+            .remove(&StorageKey::FilterSubscriptions(filter));
+
+        env.storage().persistent().set(
+            &StorageKey::Pseudonym(Self::address_digest(&env, &employee)),
+            &replacement_id,
+        );
+
+        events::emit_employee_pseudonymized(
+            &env,
+            EmployeePseudonymized {
+                replacement_id,
+                payments_rewritten: count,
+            },
+        );
+    }
+
+    /// Return the number of payments recorded under a replacement ID.
+    ///
+    /// @param replacement_id  The ID passed to `pseudonymize_employee`.
+    /// @return                Total payment count (0 if unknown).
+    pub fn get_pseudonym_payment_count(env: Env, replacement_id: BytesN<32>) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::PseudonymPaymentCount(replacement_id))
+            .unwrap_or(0)
+    }
+
+    /// Return a paginated slice of payment records for a pseudonymized employee.
+    ///
+    /// @notice Same pagination rules as `get_payments_by_employee`.
+    ///
+    /// @param replacement_id  The ID passed to `pseudonymize_employee`.
+    /// @param start_index     1-based start position (inclusive).
+    /// @param limit           Maximum records to return; capped at 100.
+    /// @return                Ordered slice of `PaymentRecord`s, oldest-first.
+    pub fn get_payments_by_pseudonym(
+        env: Env,
+        replacement_id: BytesN<32>,
+        start_index: u32,
+        limit: u32,
+    ) -> Vec<PaymentRecord> {
+        let count = Self::get_pseudonym_payment_count(env.clone(), replacement_id.clone());
+        let mut result = Vec::new(&env);
+
+        if start_index == 0 || start_index > count {
+            return result;
+        }
+
+        let effective_limit = limit.min(MAX_PAGE_SIZE);
+        let end = start_index
+            .saturating_add(effective_limit)
+            .min(count.saturating_add(1));
+
+        for i in start_index..end {
+            let global_id: u128 = env
+                .storage()
+                .persistent()
+                .get(&StorageKey::PseudonymPayment(replacement_id.clone(), i))
+                .unwrap();
+            let record: PaymentRecord = env
+                .storage()
+                .persistent()
+                .get(&StorageKey::Payment(global_id))
+                .unwrap();
+            result.push_back(record);
+        }
+        result
+    }
+
+    /// Register for targeted `payment_matched` events on payments matching
+    /// `filter`.
+    ///
+    /// @notice Subscribing is open to any address; the subscriber must
+    /// authenticate so only it can later cancel the subscription.
+    ///
+    /// @param subscriber  Address registering the subscription.
+    /// @param filter      Employer, employee, or agreement to follow.
+    /// @return            The new subscription ID (starts at 1).
+    ///
+    /// @panics "Too many subscriptions for filter" if the filter already has
+    ///         [`MAX_SUBSCRIPTIONS_PER_FILTER`] active subscriptions.
+    pub fn subscribe(env: Env, subscriber: Address, filter: SubscriptionFilter) -> u64 {
+        subscriber.require_auth();
+
+        let mut ids = Self::filter_subscriptions(&env, &filter);
+        if ids.len() >= MAX_SUBSCRIPTIONS_PER_FILTER {
+            panic!("Too many subscriptions for filter");
+        }
+
+        let id: u64 = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::SubscriptionCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .persistent()
+            .set(&StorageKey::SubscriptionCount, &id);
+        env.storage().persistent().set(
+            &StorageKey::Subscription(id),
+            &Subscription {
+                id,
+                subscriber,
+                filter: filter.clone(),
+            },
+        );
+        ids.push_back(id);
+        env.storage()
+            .persistent()
+            .set(&StorageKey::FilterSubscriptions(filter), &ids);
+        id
+    }
+
+    /// Cancel a subscription. Payments recorded afterwards no longer emit
+    /// `payment_matched` events for it.
+    ///
+    /// @param subscriber       Address that registered the subscription.
+    /// @param subscription_id  The subscription to cancel.
+    ///
+    /// @panics "Subscription not found" for unknown or cancelled IDs.
+    /// @panics "Not subscription owner" if `subscriber` did not register it.
+    pub fn unsubscribe(env: Env, subscriber: Address, subscription_id: u64) {
+        subscriber.require_auth();
+
+        let subscription: Subscription = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Subscription(subscription_id))
+            .expect("Subscription not found");
+        if subscription.subscriber != subscriber {
+            panic!("Not subscription owner");
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&StorageKey::Subscription(subscription_id));
+        let mut ids = Self::filter_subscriptions(&env, &subscription.filter);
+        if let Some(pos) = ids.first_index_of(subscription_id) {
+            ids.remove(pos);
+        }
+        env.storage()
+            .persistent()
+            .set(&StorageKey::FilterSubscriptions(subscription.filter), &ids);
+    }
+
+    /// Fetch an active subscription by ID.
+    ///
+    /// @param subscription_id  The subscription to look up.
+    /// @return                 The subscription, or `None` if unknown or cancelled.
+    pub fn get_subscription(env: Env, subscription_id: u64) -> Option<Subscription> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::Subscription(subscription_id))
+    }
+
+    /// Return the IDs of the active subscriptions matching `filter`.
+    ///
+    /// @param filter  The employer, employee, or agreement filter.
+    /// @return        Subscription IDs in registration order.
+    pub fn get_filter_subscriptions(env: Env, filter: SubscriptionFilter) -> Vec<u64> {
+        Self::filter_subscriptions(&env, &filter)
+    }
+
+    fn employee_tokens(env: &Env, employee: &Address) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::EmployeeTokens(employee.clone()))
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn add_employee_total(env: &Env, employee: &Address, token: &Address, amount: i128) {
+        let key = StorageKey::EmployeeTokenTotal(employee.clone(), token.clone());
+        let total: Option<i128> = env.storage().persistent().get(&key);
+        if total.is_none() {
+            let mut tokens = Self::employee_tokens(env, employee);
+            tokens.push_back(token.clone());
+            env.storage()
+                .persistent()
+                .set(&StorageKey::EmployeeTokens(employee.clone()), &tokens);
+        }
+        env.storage()
+            .persistent()
+            .set(&key, &(total.unwrap_or(0) + amount));
+    }
+
+    fn normalized_amount(env: &Env, token: &Address, amount: i128) -> i128 {
+        let decimals =
+            Self::get_token_decimals(env.clone(), token.clone()).unwrap_or(NORMALIZED_DECIMALS);
+        Self::normalize(amount, decimals)
+    }
+
+    /// Position at which `amount` enters a board sorted largest first, after
+    /// any equal entries.
+    fn rank_position(amounts: impl Iterator<Item = i128>, amount: i128) -> u32 {
+        let mut position = 0;
+        for existing in amounts {
+            if existing < amount {
+                break;
+            }
+            position += 1;
+        }
+        position
+    }
+
+    fn rank_payment(env: &Env, employer: &Address, payment_id: u128, normalized_amount: i128) {
+        let key = StorageKey::LargestPayments(employer.clone());
+        let mut board: Vec<RankedPayment> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        let position = Self::rank_position(
+            board.iter().map(|entry| entry.normalized_amount),
+            normalized_amount,
+        );
+        if position >= MAX_LEADERBOARD_SIZE {
+            return;
+        }
+        board.insert(
+            position,
+            RankedPayment {
+                payment_id,
+                normalized_amount,
+            },
+        );
+        if board.len() > MAX_LEADERBOARD_SIZE {
+            board.pop_back();
+        }
+        env.storage().persistent().set(&key, &board);
+    }
+
+    /// Add to a recipient's period total and re-rank them. Totals only grow,
+    /// so a recipient dropped from a full board had a total no larger than
+    /// every remaining entry and re-enters once it overtakes the smallest.
+    fn rank_recipient(
+        env: &Env,
+        employer: &Address,
+        period: u64,
+        recipient: &Address,
+        normalized_amount: i128,
+    ) {
+        let total_key =
+            StorageKey::PeriodRecipientTotal(employer.clone(), period, recipient.clone());
+        let total: i128 =
+            env.storage().persistent().get(&total_key).unwrap_or(0i128) + normalized_amount;
+        env.storage().persistent().set(&total_key, &total);
+
+        let key = StorageKey::TopRecipients(employer.clone(), period);
+        let mut board: Vec<RecipientTotal> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if let Some(index) = board.iter().position(|entry| &entry.recipient == recipient) {
+            board.remove(index as u32);
+        }
+        let position =
+            Self::rank_position(board.iter().map(|entry| entry.normalized_amount), total);
+        if position >= MAX_LEADERBOARD_SIZE {
+            return;
+        }
+        board.insert(
+            position,
+            RecipientTotal {
+                recipient: recipient.clone(),
+                normalized_amount: total,
+            },
+        );
+        if board.len() > MAX_LEADERBOARD_SIZE {
+            board.pop_back();
+        }
+        env.storage().persistent().set(&key, &board);
+    }
+
+    fn normalize(amount: i128, decimals: u32) -> i128 {
+        if decimals >= NORMALIZED_DECIMALS {
+            amount / 10i128.pow(decimals - NORMALIZED_DECIMALS)
+        } else {
+            amount
+                .checked_mul(10i128.pow(NORMALIZED_DECIMALS - decimals))
+                .expect("Normalized amount overflow")
+        }
+    }
+
+    fn address_digest(env: &Env, address: &Address) -> BytesN<32> {
+        env.crypto().sha256(&address.clone().to_xdr(env)).to_bytes()
+    }
+
+    fn pseudonym_of(env: &Env, address: &Address) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::Pseudonym(Self::address_digest(env, address)))
+    }
+
+    fn filter_subscriptions(env: &Env, filter: &SubscriptionFilter) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::FilterSubscriptions(filter.clone()))
+            .unwrap_or(Vec::new(env))
+    }
 }
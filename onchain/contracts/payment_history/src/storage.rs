@@ -1,47 +1,59 @@
-use soroban_sdk::{contracttype, Address, BytesN};
+use soroban_sdk::{contracttype, Address, BytesN, Vec};
 
-/// Canonical record of a single completed payment.
-///
-/// Once written to storage under `StorageKey::Payment(id)`, this record is
-/// never modified. Immutability is enforced at the contract level: there is no
-/// update or delete code path. Any discrepancy between this record and an
-/// off-chain index is therefore always an off-chain artifact, never a
-/// contract-side rewrite.
+pub use stellopay_interfaces::history::{PaymentRecord, SubscriptionFilter};
+
+/// A relayer's registration for targeted payment events.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct PaymentRecord {
-    /// Globally unique payment identifier (1-based, monotonically increasing).
-    /// This is the primary key used by all three indices.
-    pub id: u128,
-
-    /// The employment agreement this payment belongs to.
-    pub agreement_id: u128,
+pub struct Subscription {
+    /// Subscription identifier (1-based, monotonically increasing).
+    pub id: u64,
+    /// Address that registered the subscription and may cancel it.
+    pub subscriber: Address,
+    pub filter: SubscriptionFilter,
+}
 
-    /// 32-byte reference hash supplied by the payroll contract at record time.
-    ///
-    /// @dev Typically the Stellar transaction hash of the token transfer, so
-    /// indexers and UI clients can deep-link directly to the on-chain transaction
-    /// without recomputing payroll math. The contract stores it verbatim and does
-    /// not verify its content; integrity depends on the trustworthy payroll caller.
-    /// A reverse-lookup index (`StorageKey::PaymentByHash`) enables O(1) queries
-    /// by hash in addition to queries by ID, agreement, employer, and employee.
-    pub payment_hash: BytesN<32>,
-
-    /// Stellar asset contract address of the token transferred.
+/// One token's share of an employee's received payments.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenTotal {
     pub token: Address,
+    /// Decimals registered for `token`, or [`crate::NORMALIZED_DECIMALS`]
+    /// if none were registered.
+    pub decimals: u32,
+    /// Sum of payment amounts in the token's own base units.
+    pub raw_amount: i128,
+    /// `raw_amount` rescaled to [`crate::NORMALIZED_DECIMALS`] decimals.
+    /// Rounded towards zero when the token has more decimals.
+    pub normalized_amount: i128,
+}
 
-    /// Transfer amount in the token's smallest base unit.
-    pub amount: i128,
-
-    /// Employer address that originated the payment.
-    pub from: Address,
+/// An employee's received payments, summed across tokens.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmployeeTotals {
+    /// Sum of every breakdown entry's `normalized_amount`.
+    pub normalized_total: i128,
+    /// One entry per token, in the order the tokens were first paid.
+    pub breakdown: Vec<TokenTotal>,
+}
 
-    /// Employee address that received the payment.
-    pub to: Address,
+/// A recipient's normalized total from one employer in one period.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecipientTotal {
+    pub recipient: Address,
+    /// Sum received, normalized to [`crate::NORMALIZED_DECIMALS`] decimals.
+    pub normalized_amount: i128,
+}
 
-    /// Unix timestamp (seconds) recorded by the payroll contract at the time
-    /// of the transfer.
-    pub timestamp: u64,
+/// Leaderboard entry for an employer's largest payments.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RankedPayment {
+    pub payment_id: u128,
+    /// Payment amount, normalized to [`crate::NORMALIZED_DECIMALS`] decimals.
+    pub normalized_amount: i128,
 }
 
 /// Enumeration of all persistent storage keys used by this contract.
@@ -63,6 +75,22 @@ pub struct PaymentRecord {
 ///
 /// EmployeePaymentCount(employee)       → u32    (# payments to employee)
 /// EmployeePayment(employee, pos)       → u128   (global_id at 1-based pos)
+///
+/// SubscriptionCount                    → u64    (highest assigned subscription ID)
+/// Subscription(id)                     → Subscription
+/// FilterSubscriptions(filter)          → Vec<u64> (active subscription IDs)
+///
+/// Pseudonym(sha256(xdr(employee)))     → BytesN<32> (replacement ID)
+/// PseudonymPaymentCount(replacement)   → u32    (# payments to pseudonym)
+/// PseudonymPayment(replacement, pos)   → u128   (global_id at 1-based pos)
+///
+/// TokenDecimals(token)                 → u32    (registered by the owner)
+/// EmployeeTokens(employee)             → Vec<Address> (tokens received)
+/// EmployeeTokenTotal(employee, token)  → i128   (raw sum received)
+///
+/// LargestPayments(employer)            → Vec<RankedPayment>  (largest first)
+/// PeriodRecipientTotal(employer, period, recipient) → i128 (normalized)
+/// TopRecipients(employer, period)      → Vec<RecipientTotal> (largest first)
 /// ```
 ///
 /// The three index families (Agreement, Employer, Employee) share the same
@@ -119,4 +147,51 @@ pub enum StorageKey {
     /// `EmployeePayment(employee, position)` maps each 1-based position to a
     /// global payment ID, partitioned by employee address.
     EmployeePayment(Address, u32), // key: (employee, 1-based position)
+
+    // ── Subscriptions ──────────────────────────────────────────────────────
+    /// Highest assigned subscription ID.
+    SubscriptionCount,
+
+    /// Active subscription by ID. Removed on unsubscribe.
+    Subscription(u64),
+
+    /// Active subscription IDs matching a filter, in registration order.
+    FilterSubscriptions(SubscriptionFilter),
+
+    // ── Pseudonymized employee index ───────────────────────────────────────
+    /// Replacement ID assigned to a pseudonymized employee.
+    ///
+    /// @dev Keyed by `sha256(xdr(employee))` so the raw address is never
+    /// stored again once the employee is pseudonymized.
+    Pseudonym(BytesN<32>),
+
+    /// Total number of payments received under this replacement ID. Written
+    /// at pseudonymization time, so its presence marks the ID as taken.
+    PseudonymPaymentCount(BytesN<32>), // key: replacement_id
+
+    /// Pagination pointer for the pseudonym index. Takes over the employee's
+    /// `EmployeePayment` positions unchanged.
+    PseudonymPayment(BytesN<32>, u32), // key: (replacement_id, 1-based position)
+
+    // ── Per-token employee totals ──────────────────────────────────────────
+    /// Decimal places of a token, registered by the owner.
+    TokenDecimals(Address),
+
+    /// Tokens an employee has received, in first-payment order.
+    EmployeeTokens(Address),
+
+    /// Running sum of an employee's payments in one token, in base units.
+    EmployeeTokenTotal(Address, Address), // key: (employee, token)
+
+    // ── Employer leaderboards ──────────────────────────────────────────────
+    /// The employer's largest payments by normalized amount, largest first.
+    /// Holds at most `MAX_LEADERBOARD_SIZE` entries.
+    LargestPayments(Address), // key: employer
+
+    /// Normalized sum paid by an employer to one recipient in one period.
+    PeriodRecipientTotal(Address, u64, Address), // key: (employer, period, recipient)
+
+    /// The employer's top recipients in a period, largest first.
+    /// Holds at most `MAX_LEADERBOARD_SIZE` entries.
+    TopRecipients(Address, u64), // key: (employer, period)
 }
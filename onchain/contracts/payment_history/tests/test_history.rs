@@ -22,6 +22,14 @@
 //! * Security — record immutability, index counts only increase (no pruning),
 //!   hash index written atomically with the primary record
 //! * Large history — 20 records, boundary reads at exact count edge
+//! * Subscriptions — sequential IDs, targeted events per matching filter,
+//!   unsubscribe, owner check, per-filter cap
+//! * Pseudonymization — index moved to replacement ID, payee address stripped
+//!   from old and new records, totals preserved, owner-only, no reuse
+//! * Normalized totals — per-token breakdown rescaled to 7 decimals, default
+//!   decimals for unregistered tokens, decimals range check
+//! * Leaderboards — largest payments and per-period top recipients ranked by
+//!   normalized amount, bounded size, eviction and re-entry
 //!
 //! ## Security notes
 //!
@@ -54,7 +62,11 @@
 
 #![cfg(test)]
 
-use payment_history::{PaymentHistoryContract, PaymentHistoryContractClient, MAX_PAGE_SIZE};
+use payment_history::{
+    PaymentHistoryContract, PaymentHistoryContractClient, RecipientTotal, Subscription,
+    SubscriptionFilter, TokenTotal, LEADERBOARD_PERIOD_SECONDS, MAX_LEADERBOARD_SIZE,
+    MAX_PAGE_SIZE, MAX_SUBSCRIPTIONS_PER_FILTER, MAX_TOKEN_DECIMALS,
+};
 use soroban_sdk::{
     testutils::{Address as _, Events},
     Address, BytesN, Env, IntoVal, Symbol,
@@ -1326,3 +1338,597 @@ fn test_reconciliation_out_of_order_events_are_stable() {
     assert_eq!(page.get(0).unwrap().id, id1);
     assert_eq!(page.get(1).unwrap().id, id2);
 }
+
+// ─── Subscriptions ───────────────────────────────────────────────────────────
+
+/// Collect `(subscription_id, payment_id)` for every `payment_matched` event
+/// addressed to `subscriber` in the current invocation's event log.
+fn matched_for(env: &Env, subscriber: &Address) -> soroban_sdk::Vec<(u64, u128)> {
+    let mut matched = soroban_sdk::Vec::new(env);
+    for (_, topics, data) in env.events().all().iter() {
+        let name: Symbol = topics.get(0).unwrap().into_val(env);
+        if name != Symbol::new(env, "payment_matched") {
+            continue;
+        }
+        let to: Address = topics.get(2).unwrap().into_val(env);
+        if &to != subscriber {
+            continue;
+        }
+        let subscription_id: u64 = topics.get(1).unwrap().into_val(env);
+        let fields: soroban_sdk::Map<Symbol, soroban_sdk::Val> = data.into_val(env);
+        let payment_id: u128 = fields
+            .get(Symbol::new(env, "payment_id"))
+            .unwrap()
+            .into_val(env);
+        matched.push_back((subscription_id, payment_id));
+    }
+    matched
+}
+
+#[test]
+fn test_subscribe_assigns_sequential_ids() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let relayer = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let first = client.subscribe(&relayer, &SubscriptionFilter::Employer(employer.clone()));
+    let second = client.subscribe(&relayer, &SubscriptionFilter::Agreement(4));
+
+    assert_eq!((first, second), (1, 2));
+    assert_eq!(
+        client.get_subscription(&first),
+        Some(Subscription {
+            id: first,
+            subscriber: relayer.clone(),
+            filter: SubscriptionFilter::Employer(employer.clone()),
+        })
+    );
+    assert_eq!(
+        client.get_filter_subscriptions(&SubscriptionFilter::Employer(employer)),
+        soroban_sdk::vec![&env, first]
+    );
+}
+
+#[test]
+fn test_record_payment_emits_targeted_events_per_matching_filter() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let token = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let other_employee = Address::generate(&env);
+    let employer_relayer = Address::generate(&env);
+    let employee_relayer = Address::generate(&env);
+
+    let by_employer = client.subscribe(
+        &employer_relayer,
+        &SubscriptionFilter::Employer(employer.clone()),
+    );
+    let by_agreement = client.subscribe(&employer_relayer, &SubscriptionFilter::Agreement(3));
+    let by_employee = client.subscribe(
+        &employee_relayer,
+        &SubscriptionFilter::Employee(employee.clone()),
+    );
+
+    let paid = record(
+        &client, &env, 3, 1, &token, 100, &employer, &employee, 1_000,
+    );
+    assert_eq!(
+        matched_for(&env, &employer_relayer),
+        soroban_sdk::vec![&env, (by_employer, paid), (by_agreement, paid)]
+    );
+    assert_eq!(
+        matched_for(&env, &employee_relayer),
+        soroban_sdk::vec![&env, (by_employee, paid)]
+    );
+
+    // A payment to someone else on another agreement only matches the employer
+    let other = record(
+        &client,
+        &env,
+        9,
+        2,
+        &token,
+        100,
+        &employer,
+        &other_employee,
+        2_000,
+    );
+    assert_eq!(
+        matched_for(&env, &employer_relayer),
+        soroban_sdk::vec![&env, (by_employer, other)]
+    );
+    assert_eq!(matched_for(&env, &employee_relayer).len(), 0);
+}
+
+#[test]
+fn test_unsubscribe_stops_targeted_events() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let token = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let relayer = Address::generate(&env);
+    let filter = SubscriptionFilter::Employee(employee.clone());
+    let first = client.subscribe(&relayer, &filter);
+    let second = client.subscribe(&relayer, &filter);
+
+    client.unsubscribe(&relayer, &first);
+    assert_eq!(client.get_subscription(&first), None);
+    assert_eq!(
+        client.get_filter_subscriptions(&filter),
+        soroban_sdk::vec![&env, second]
+    );
+
+    let paid = record(
+        &client, &env, 1, 1, &token, 100, &employer, &employee, 1_000,
+    );
+    assert_eq!(
+        matched_for(&env, &relayer),
+        soroban_sdk::vec![&env, (second, paid)]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Not subscription owner")]
+fn test_unsubscribe_rejects_other_address() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let relayer = Address::generate(&env);
+    let id = client.subscribe(&relayer, &SubscriptionFilter::Agreement(1));
+    client.unsubscribe(&Address::generate(&env), &id);
+}
+
+#[test]
+#[should_panic(expected = "Subscription not found")]
+fn test_unsubscribe_unknown_id_panics() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    client.unsubscribe(&Address::generate(&env), &7);
+}
+
+#[test]
+#[should_panic(expected = "Too many subscriptions for filter")]
+fn test_subscribe_caps_subscriptions_per_filter() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let filter = SubscriptionFilter::Agreement(1);
+    for _ in 0..MAX_SUBSCRIPTIONS_PER_FILTER {
+        client.subscribe(&Address::generate(&env), &filter);
+    }
+    client.subscribe(&Address::generate(&env), &filter);
+}
+
+// ─── Pseudonymization ────────────────────────────────────────────────────────
+
+#[test]
+fn test_pseudonymize_employee_moves_index_and_strips_address() {
+    let env = create_env();
+    let (contract_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let token = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let other = Address::generate(&env);
+    let replacement = make_hash(&env, 0xEE);
+
+    let first = record(
+        &client, &env, 1, 1, &token, 100, &employer, &employee, 1_000,
+    );
+    record(&client, &env, 1, 2, &token, 50, &employer, &other, 1_500);
+    let second = record(
+        &client, &env, 2, 3, &token, 200, &employer, &employee, 2_000,
+    );
+
+    client.pseudonymize_employee(&employee, &replacement);
+
+    assert_eq!(client.get_employee_payment_count(&employee), 0);
+    assert_eq!(client.get_payments_by_employee(&employee, &1, &10).len(), 0);
+    assert_eq!(client.get_pseudonym_payment_count(&replacement), 2);
+
+    let page = client.get_payments_by_pseudonym(&replacement, &1, &10);
+    assert_eq!(page.len(), 2);
+    for (record, (id, amount, timestamp)) in page
+        .iter()
+        .zip([(first, 100i128, 1_000u64), (second, 200, 2_000)])
+    {
+        assert_eq!(record.id, id);
+        assert_eq!(record.amount, amount);
+        assert_eq!(record.timestamp, timestamp);
+        assert_eq!(record.from, employer);
+        assert_eq!(record.to, contract_id);
+        assert_eq!(record.to_pseudonym, Some(replacement.clone()));
+    }
+
+    // Other payees, the employer index and the agreement totals are untouched
+    let untouched = client.get_payments_by_employee(&other, &1, &10);
+    assert_eq!(untouched.get(0).unwrap().to, other);
+    assert_eq!(untouched.get(0).unwrap().to_pseudonym, None);
+    assert_eq!(client.get_employer_payment_count(&employer), 3);
+    assert_eq!(client.get_agreement_payment_count(&1), 2);
+    assert_eq!(client.get_global_payment_count(), 3);
+}
+
+#[test]
+fn test_payments_after_pseudonymization_use_replacement_id() {
+    let env = create_env();
+    let (contract_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let token = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let replacement = make_hash(&env, 0xEE);
+
+    record(
+        &client, &env, 1, 1, &token, 100, &employer, &employee, 1_000,
+    );
+    client.pseudonymize_employee(&employee, &replacement);
+    let later = record(
+        &client, &env, 1, 2, &token, 100, &employer, &employee, 2_000,
+    );
+
+    let stored = client.get_payment_by_id(&later).unwrap();
+    assert_eq!(stored.to, contract_id);
+    assert_eq!(stored.to_pseudonym, Some(replacement.clone()));
+    assert_eq!(client.get_employee_payment_count(&employee), 0);
+    assert_eq!(client.get_pseudonym_payment_count(&replacement), 2);
+    assert_eq!(
+        client
+            .get_payments_by_pseudonym(&replacement, &2, &1)
+            .get(0)
+            .unwrap()
+            .id,
+        later
+    );
+}
+
+#[test]
+fn test_pseudonymize_employee_removes_employee_subscriptions() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let employee = Address::generate(&env);
+    let relayer = Address::generate(&env);
+    let filter = SubscriptionFilter::Employee(employee.clone());
+    let id = client.subscribe(&relayer, &filter);
+
+    client.pseudonymize_employee(&employee, &make_hash(&env, 0xEE));
+
+    assert_eq!(client.get_subscription(&id), None);
+    assert_eq!(client.get_filter_subscriptions(&filter).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Employee already pseudonymized")]
+fn test_pseudonymize_employee_twice_panics() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let employee = Address::generate(&env);
+    client.pseudonymize_employee(&employee, &make_hash(&env, 1));
+    client.pseudonymize_employee(&employee, &make_hash(&env, 2));
+}
+
+#[test]
+#[should_panic(expected = "Replacement ID already in use")]
+fn test_pseudonymize_employee_rejects_reused_replacement_id() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    client.pseudonymize_employee(&Address::generate(&env), &make_hash(&env, 1));
+    client.pseudonymize_employee(&Address::generate(&env), &make_hash(&env, 1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_pseudonymize_employee_requires_owner_auth() {
+    let env = Env::default();
+    let (_id, client) = register_contract(&env);
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &Address::generate(&env));
+
+    client.pseudonymize_employee(&Address::generate(&env), &make_hash(&env, 1));
+}
+
+// ─── Normalized totals ───────────────────────────────────────────────────────
+
+#[test]
+fn test_employee_totals_normalize_across_token_decimals() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let usdc = Address::generate(&env);
+    let wide = Address::generate(&env);
+    let unregistered = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    client.set_token_decimals(&usdc, &6);
+    client.set_token_decimals(&wide, &18);
+
+    // 1.5 USDC (6 decimals), twice
+    record(
+        &client, &env, 1, 1, &usdc, 1_500_000, &employer, &employee, 1_000,
+    );
+    record(
+        &client, &env, 1, 2, &usdc, 1_500_000, &employer, &employee, 2_000,
+    );
+    // 2 units of an 18-decimal token plus dust below 7 decimals
+    record(
+        &client,
+        &env,
+        1,
+        3,
+        &wide,
+        2_000_000_000_000_000_123,
+        &employer,
+        &employee,
+        3_000,
+    );
+    // 0.5 of a token assumed to use 7 decimals
+    record(
+        &client,
+        &env,
+        1,
+        4,
+        &unregistered,
+        5_000_000,
+        &employer,
+        &employee,
+        4_000,
+    );
+
+    let totals = client.get_employee_totals_normalized(&employee);
+    assert_eq!(
+        totals.breakdown,
+        soroban_sdk::vec![
+            &env,
+            TokenTotal {
+                token: usdc,
+                decimals: 6,
+                raw_amount: 3_000_000,
+                normalized_amount: 30_000_000,
+            },
+            TokenTotal {
+                token: wide,
+                decimals: 18,
+                raw_amount: 2_000_000_000_000_000_123,
+                normalized_amount: 20_000_000,
+            },
+            TokenTotal {
+                token: unregistered,
+                decimals: 7,
+                raw_amount: 5_000_000,
+                normalized_amount: 5_000_000,
+            },
+        ]
+    );
+    assert_eq!(totals.normalized_total, 55_000_000);
+}
+
+#[test]
+fn test_employee_totals_empty_for_unknown_employee() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let totals = client.get_employee_totals_normalized(&Address::generate(&env));
+    assert_eq!(totals.normalized_total, 0);
+    assert_eq!(totals.breakdown.len(), 0);
+}
+
+#[test]
+fn test_pseudonymize_employee_clears_employee_totals() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let token = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    record(
+        &client, &env, 1, 1, &token, 100, &employer, &employee, 1_000,
+    );
+
+    client.pseudonymize_employee(&employee, &make_hash(&env, 0xEE));
+    record(
+        &client, &env, 1, 2, &token, 100, &employer, &employee, 2_000,
+    );
+
+    assert_eq!(
+        client
+            .get_employee_totals_normalized(&employee)
+            .breakdown
+            .len(),
+        0
+    );
+}
+
+#[test]
+#[should_panic(expected = "Decimals out of range")]
+fn test_set_token_decimals_rejects_out_of_range() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    client.set_token_decimals(&Address::generate(&env), &(MAX_TOKEN_DECIMALS + 1));
+}
+
+// ─── Leaderboards ────────────────────────────────────────────────────────────
+
+#[test]
+fn test_largest_payments_ranked_by_normalized_amount() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let xlm = Address::generate(&env);
+    let usdc = Address::generate(&env);
+    client.set_token_decimals(&usdc, &6);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+
+    let small = record(
+        &client, &env, 1, 1, &xlm, 10_000_000, &employer, &employee, 1,
+    );
+    // 5 USDC outranks 3 XLM despite the smaller raw amount
+    let big = record(
+        &client, &env, 1, 2, &usdc, 5_000_000, &employer, &employee, 2,
+    );
+    let mid = record(
+        &client, &env, 1, 3, &xlm, 30_000_000, &employer, &employee, 3,
+    );
+    let tie = record(
+        &client, &env, 1, 4, &xlm, 10_000_000, &employer, &employee, 4,
+    );
+
+    let ids: std::vec::Vec<u128> = client
+        .get_largest_payments(&employer, &10)
+        .iter()
+        .map(|record| record.id)
+        .collect();
+    assert_eq!(ids, [big, mid, small, tie]);
+
+    let top_two = client.get_largest_payments(&employer, &2);
+    assert_eq!(top_two.len(), 2);
+    assert_eq!(top_two.get(1).unwrap().id, mid);
+}
+
+#[test]
+fn test_largest_payments_keeps_bounded_board() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let token = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let total = MAX_LEADERBOARD_SIZE as u8 + 5;
+    for seed in 1..=total {
+        record(
+            &client,
+            &env,
+            1,
+            seed,
+            &token,
+            seed as i128 * 100,
+            &employer,
+            &employee,
+            seed as u64,
+        );
+    }
+
+    let board = client.get_largest_payments(&employer, &u32::MAX);
+    assert_eq!(board.len(), MAX_LEADERBOARD_SIZE);
+    assert_eq!(board.get(0).unwrap().amount, total as i128 * 100);
+    assert_eq!(
+        board.get(MAX_LEADERBOARD_SIZE - 1).unwrap().amount,
+        (total as i128 - MAX_LEADERBOARD_SIZE as i128 + 1) * 100
+    );
+    assert_eq!(
+        client
+            .get_largest_payments(&Address::generate(&env), &5)
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_top_recipients_accumulate_per_period() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let token = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let next_period = LEADERBOARD_PERIOD_SECONDS;
+
+    record(&client, &env, 1, 1, &token, 300, &employer, &alice, 10);
+    record(&client, &env, 1, 2, &token, 200, &employer, &bob, 20);
+    // Bob overtakes Alice with a second payment in the same period
+    record(&client, &env, 1, 3, &token, 200, &employer, &bob, 30);
+    // Next period starts fresh
+    record(
+        &client,
+        &env,
+        1,
+        4,
+        &token,
+        50,
+        &employer,
+        &alice,
+        next_period,
+    );
+
+    assert_eq!(
+        client.get_top_recipients(&employer, &0, &10),
+        soroban_sdk::vec![
+            &env,
+            RecipientTotal {
+                recipient: bob.clone(),
+                normalized_amount: 400,
+            },
+            RecipientTotal {
+                recipient: alice.clone(),
+                normalized_amount: 300,
+            },
+        ]
+    );
+    assert_eq!(client.get_top_recipients(&employer, &0, &1).len(), 1);
+    assert_eq!(
+        client.get_top_recipients(&employer, &1, &10),
+        soroban_sdk::vec![
+            &env,
+            RecipientTotal {
+                recipient: alice,
+                normalized_amount: 50,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_top_recipients_readmits_recipient_after_eviction() {
+    let env = create_env();
+    let (_id, client) = register_contract(&env);
+    initialize_contract(&env, &client);
+
+    let token = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let late = Address::generate(&env);
+
+    record(&client, &env, 1, 1, &token, 1, &employer, &late, 1);
+    for seed in 2..=(MAX_LEADERBOARD_SIZE as u8 + 1) {
+        let recipient = Address::generate(&env);
+        record(
+            &client, &env, 1, seed, &token, 100, &employer, &recipient, 1,
+        );
+    }
+    let board = client.get_top_recipients(&employer, &0, &u32::MAX);
+    assert_eq!(board.len(), MAX_LEADERBOARD_SIZE);
+    assert!(board.iter().all(|entry| entry.recipient != late));
+
+    record(&client, &env, 1, 200, &token, 500, &employer, &late, 2);
+    let leader = client.get_top_recipients(&employer, &0, &1).get(0).unwrap();
+    assert_eq!(leader.recipient, late);
+    assert_eq!(leader.normalized_amount, 501);
+}
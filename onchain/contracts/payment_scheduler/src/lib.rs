@@ -52,14 +52,30 @@
 //! * `job_executed`  — payment transferred; contains `execution_index` and `amount`.
 //! * `job_failed`    — insufficient funds; contains `retry_count` / `max_retries`.
 //! * `job_cancelled` — schedule permanently removed by employer.
+//! * `payout_run`    — one per `process_due_payments` call that evaluated jobs;
+//!   contains the [`PayoutOrder`] used and how many jobs were paid.
+//!
+//! ## Payout Order
+//!
+//! When the escrow cannot cover every due job, the order in which due jobs are
+//! evaluated decides who gets paid. The owner picks that order with
+//! `set_payout_order`; it never depends on the caller's input. Ties are always
+//! broken by job id, so every keeper evaluates the same jobs in the same order.
+//! To keep each call bounded, orders other than `JobId` sort only the first
+//! `4 * max_jobs` due jobs by id.
 
 #![no_std]
 #![allow(deprecated)] // env.events().publish() — codebase-wide pattern
 
+extern crate alloc;
+
+use alloc::vec::Vec as StdVec;
+
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, token, xdr::ToXdr, Address, Bytes, BytesN,
-    Env, IntoVal, Symbol, Vec,
+    Env, IntoVal, String, Symbol, Vec,
 };
+use webhook_interface::{event_types, WebhookNotification, WebhookRegistryClient};
 // ─── Error Types ─────────────────────────────────────────────────────────────
 
 /// Errors returned by the payment scheduler contract.
@@ -92,6 +108,8 @@ pub enum SchedulerError {
     AlreadyCancelled = 10,
     /// The job is not in a cancellable state (must be `Active` or `Paused`).
     JobNotCancellable = 11,
+    /// The caller is not the contract owner.
+    NotOwner = 12,
 }
 
 // ─── Domain Types ─────────────────────────────────────────────────────────────
@@ -107,6 +125,22 @@ pub enum JobStatus {
     Cancelled,
 }
 
+/// Order in which `process_due_payments` evaluates due jobs, which decides
+/// who is paid first when the escrow cannot cover every job.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PayoutOrder {
+    /// Creation order (ascending job id). The default.
+    JobId,
+    /// Earliest `next_scheduled_time` first, so the longest-waiting payment
+    /// is paid first.
+    OldestDue,
+    /// Smallest `amount` first, so the escrow pays as many jobs as it can.
+    SmallestAmount,
+    /// Ascending recipient address.
+    Recipient,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum RetryState {
@@ -221,6 +255,10 @@ enum StorageKey {
     ScheduleId(BytesN<32>),
     /// Address of the payment retry contract.
     RetryContract,
+    /// Order due jobs are evaluated in (`PayoutOrder`).
+    PayoutOrder,
+    /// Linked webhook registry (`Address`).
+    WebhookRegistry,
 }
 
 // ─── Events ───────────────────────────────────────────────────────────────────
@@ -274,6 +312,25 @@ pub struct JobFundedEvent {
     pub amount: i128,
 }
 
+/// Emitted when the owner changes the payout order via `set_payout_order`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutOrderSetEvent {
+    pub order: PayoutOrder,
+}
+
+/// Emitted at the end of each `process_due_payments` call that evaluated at
+/// least one job.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutRunEvent {
+    pub order: PayoutOrder,
+    /// Due jobs evaluated, in `order`.
+    pub evaluated: u32,
+    /// Evaluated jobs that were paid; the rest went to the retry contract.
+    pub paid: u32,
+}
+
 // ─── Internal Helpers ─────────────────────────────────────────────────────────
 
 fn require_initialized(env: &Env) -> Result<(), SchedulerError> {
@@ -315,6 +372,100 @@ fn write_job(env: &Env, job: &PaymentJob) {
         .set(&StorageKey::Job(job.id), job);
 }
 
+/// Authenticates `owner` and checks it is the contract owner.
+fn require_owner(env: &Env, owner: &Address) -> Result<(), SchedulerError> {
+    require_initialized(env)?;
+    owner.require_auth();
+    let stored_owner: Address = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::Owner)
+        .ok_or(SchedulerError::NotInitialized)?;
+    if stored_owner != *owner {
+        return Err(SchedulerError::NotOwner);
+    }
+    Ok(())
+}
+
+/// Notifies the linked webhook registry, if any, that `job` was executed. A
+/// failing registry never blocks the payment.
+fn notify_executed(env: &Env, job: &PaymentJob) {
+    let Some(registry) = env
+        .storage()
+        .persistent()
+        .get::<_, Address>(&StorageKey::WebhookRegistry)
+    else {
+        return;
+    };
+    let notification = WebhookNotification {
+        owner: job.employer.clone(),
+        event_type: String::from_str(env, event_types::SCHEDULE_EXECUTED),
+        subject: job.recipient.clone(),
+        reference: job.id,
+        amount: job.amount,
+    };
+    let _ = WebhookRegistryClient::new(env, &registry)
+        .try_notify(&env.current_contract_address(), &notification);
+}
+
+fn payout_order(env: &Env) -> PayoutOrder {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::PayoutOrder)
+        .unwrap_or(PayoutOrder::JobId)
+}
+
+/// Due jobs read per evaluated job when `PayoutOrder` is not `JobId`.
+///
+/// Sorting needs more candidates than it evaluates, but reading every job
+/// would lift the ledger-limit bound `max_jobs` gives callers.
+const ORDER_WINDOW_FACTOR: u32 = 4;
+
+/// Collects up to `max_jobs` due `Active` jobs, sorted by `order` with ties
+/// broken by job id.
+///
+/// Jobs are read in id order until the window is full: `max_jobs` due jobs
+/// for `JobId`, `max_jobs * ORDER_WINDOW_FACTOR` for the other orders, which
+/// sort within that window. Evaluated jobs are no longer due, so the window
+/// moves on to later jobs on the next call.
+fn due_jobs(env: &Env, now: u64, max_jobs: u32, order: PayoutOrder) -> StdVec<PaymentJob> {
+    let highest_id = env
+        .storage()
+        .persistent()
+        .get::<_, u128>(&StorageKey::NextJobId)
+        .unwrap_or(0);
+    let window = match order {
+        PayoutOrder::JobId => max_jobs,
+        _ => max_jobs.saturating_mul(ORDER_WINDOW_FACTOR),
+    } as usize;
+
+    let mut jobs = StdVec::new();
+    let mut job_id: u128 = 1;
+    while job_id <= highest_id && jobs.len() < window {
+        if let Some(job) = env
+            .storage()
+            .persistent()
+            .get::<_, PaymentJob>(&StorageKey::Job(job_id))
+        {
+            if job.status == JobStatus::Active && now >= job.next_scheduled_time {
+                jobs.push(job);
+            }
+        }
+        job_id = job_id.saturating_add(1);
+    }
+
+    match order {
+        PayoutOrder::JobId => {}
+        PayoutOrder::OldestDue => jobs.sort_by_key(|job| (job.next_scheduled_time, job.id)),
+        PayoutOrder::SmallestAmount => jobs.sort_by_key(|job| (job.amount, job.id)),
+        PayoutOrder::Recipient => {
+            jobs.sort_by(|a, b| a.recipient.cmp(&b.recipient).then(a.id.cmp(&b.id)))
+        }
+    }
+    jobs.truncate(max_jobs as usize);
+    jobs
+}
+
 /// Derives the deterministic schedule fingerprint from the job's immutable parameters.
 ///
 /// The fingerprint is a SHA-256 hash over the concatenation of the canonical
@@ -613,6 +764,58 @@ impl PaymentSchedulerContract {
         Ok(())
     }
 
+    /// @notice Sets the order `process_due_payments` evaluates due jobs in.
+    /// @dev Takes effect from the next call. Emits `payout_order_set`.
+    /// @param owner Contract owner. Must authenticate.
+    /// @param order New payout order.
+    /// @return Ok(()) on success, `Err(NotOwner)` for any other caller.
+    pub fn set_payout_order(
+        env: Env,
+        owner: Address,
+        order: PayoutOrder,
+    ) -> Result<(), SchedulerError> {
+        require_owner(&env, &owner)?;
+
+        env.storage()
+            .persistent()
+            .set(&StorageKey::PayoutOrder, &order);
+        env.events()
+            .publish(("payout_order_set",), PayoutOrderSetEvent { order });
+
+        Ok(())
+    }
+
+    /// @notice Links the webhook registry notified of every executed job, or
+    ///         unlinks it with `None`.
+    /// @dev The registry holds all webhook state and must also allow this
+    ///      contract as a notifier.
+    /// @param owner Contract owner. Must authenticate.
+    /// @param registry Deployed `webhook_contract`, or `None`.
+    /// @return Ok(()) on success, `Err(NotOwner)` for any other caller.
+    pub fn set_webhook_registry(
+        env: Env,
+        owner: Address,
+        registry: Option<Address>,
+    ) -> Result<(), SchedulerError> {
+        require_owner(&env, &owner)?;
+        match registry {
+            Some(registry) => env
+                .storage()
+                .persistent()
+                .set(&StorageKey::WebhookRegistry, &registry),
+            None => env
+                .storage()
+                .persistent()
+                .remove(&StorageKey::WebhookRegistry),
+        }
+        Ok(())
+    }
+
+    /// @notice Returns the linked webhook registry, if any.
+    pub fn get_webhook_registry(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&StorageKey::WebhookRegistry)
+    }
+
     // ── Execution ─────────────────────────────────────────────────────────────
 
     /// @notice Processes due payments across all registered jobs.
@@ -620,6 +823,10 @@ impl PaymentSchedulerContract {
     ///      service, or any Stellar account). Processes at most `max_jobs` jobs
     ///      per call to bound ledger resource consumption.
     ///
+    ///      Due jobs are evaluated in the configured `PayoutOrder` (see
+    ///      `set_payout_order`), ties broken by job id, so the jobs paid from
+    ///      an escrow too small for all of them never depend on the caller.
+    ///
     ///      For each `Active` job whose `next_scheduled_time <= now`:
     ///      * If the scheduler's escrow balance covers `amount`:
     ///        - State is written before the transfer (state-before-interaction).
@@ -633,113 +840,109 @@ impl PaymentSchedulerContract {
     ///        - Otherwise `next_scheduled_time` is advanced and the job retries.
     ///        - Emits `job_failed`.
     ///
+    ///      Emits `payout_run` with the order used and the paid count.
+    ///
     /// @param max_jobs Maximum number of jobs to evaluate in this call.
     ///                 Pass a small value (e.g. 10–50) to stay within ledger limits.
     /// @return Number of jobs that were actually evaluated (not necessarily paid).
     pub fn process_due_payments(env: Env, max_jobs: u32) -> u32 {
-        if require_initialized(&env).is_err() {
+        if require_initialized(&env).is_err() || max_jobs == 0 {
             return 0;
         }
 
         let now = env.ledger().timestamp();
+        let order = payout_order(&env);
         let mut processed: u32 = 0;
-
-        let highest_id = env
-            .storage()
-            .persistent()
-            .get::<_, u128>(&StorageKey::NextJobId)
-            .unwrap_or(0);
-
-        if highest_id == 0 || max_jobs == 0 {
-            return 0;
-        }
-
-        let mut job_id: u128 = 1;
-        while job_id <= highest_id && processed < max_jobs {
-            if let Some(job) = env
-                .storage()
-                .persistent()
-                .get::<_, PaymentJob>(&StorageKey::Job(job_id))
-            {
-                if job.status == JobStatus::Active && now >= job.next_scheduled_time {
-                    let mut job_mut = job;
-                    let token_client = token::Client::new(&env, &job_mut.token);
-                    let balance = token_client.balance(&env.current_contract_address());
-
-                    if balance >= job_mut.amount {
-                        // Checks-effects-interactions:
-                        // commit job progress before transfer so reentrant
-                        // callbacks cannot re-execute the same due payment.
-                        job_mut.executions = job_mut.executions.saturating_add(1);
-                        job_mut.retry_count = 0;
-                        job_mut.next_scheduled_time = now.saturating_add(job_mut.interval_seconds);
-
-                        if let Some(max_exec) = job_mut.max_executions {
-                            if job_mut.executions >= max_exec {
-                                job_mut.status = JobStatus::Completed;
-                            }
-                        }
-
-                        // State-before-interaction: persist before token transfer.
-                        write_job(&env, &job_mut);
-
-                        token_client.transfer(
-                            &env.current_contract_address(),
-                            &job_mut.recipient,
-                            &job_mut.amount,
-                        );
-
-                        env.events().publish(
-                            ("job_executed", job_mut.id),
-                            JobExecutedEvent {
-                                job_id: job_mut.id,
-                                execution_index: job_mut.executions,
-                                amount: job_mut.amount,
-                            },
-                        );
-                    } else {
-                        // Insufficient funds: offload to payment_retry contract.
-                        let payment_id = compute_payment_id(
-                            &env,
-                            &job_mut.employer,
-                            &job_mut.recipient,
-                            job_mut.amount,
-                            job_mut.next_scheduled_time,
-                        );
-
-                        let retry_addr = env
-                            .storage()
-                            .persistent()
-                            .get::<_, Address>(&StorageKey::RetryContract)
-                            .unwrap();
-                        let retry_client = RetryContractClient::new(&env, &retry_addr);
-
-                        let retry_config = RetryConfig {
-                            max_retries: job_mut.max_retries,
-                            retry_intervals: soroban_sdk::vec![&env, 30u64, 60u64, 120u64], // Default backoff
-                        };
-
-                        retry_client.schedule_retry(
-                            &payment_id,
-                            &job_mut.employer,
-                            &job_mut.recipient,
-                            &job_mut.token,
-                            &job_mut.amount,
-                            &retry_config,
-                        );
-
-                        // Advance the job to the next period as the retry is now managed externally
-                        job_mut.next_scheduled_time = now.saturating_add(job_mut.interval_seconds);
-                        write_job(&env, &job_mut);
-
-                        env.events()
-                            .publish(("payment_failed", payment_id.clone()), payment_id);
+        let mut paid: u32 = 0;
+
+        for job in due_jobs(&env, now, max_jobs, order) {
+            let mut job_mut = job;
+            let token_client = token::Client::new(&env, &job_mut.token);
+            let balance = token_client.balance(&env.current_contract_address());
+
+            if balance >= job_mut.amount {
+                // Checks-effects-interactions:
+                // commit job progress before transfer so reentrant
+                // callbacks cannot re-execute the same due payment.
+                job_mut.executions = job_mut.executions.saturating_add(1);
+                job_mut.retry_count = 0;
+                job_mut.next_scheduled_time = now.saturating_add(job_mut.interval_seconds);
+
+                if let Some(max_exec) = job_mut.max_executions {
+                    if job_mut.executions >= max_exec {
+                        job_mut.status = JobStatus::Completed;
                     }
-                    processed = processed.saturating_add(1);
                 }
+
+                // State-before-interaction: persist before token transfer.
+                write_job(&env, &job_mut);
+
+                token_client.transfer(
+                    &env.current_contract_address(),
+                    &job_mut.recipient,
+                    &job_mut.amount,
+                );
+
+                env.events().publish(
+                    ("job_executed", job_mut.id),
+                    JobExecutedEvent {
+                        job_id: job_mut.id,
+                        execution_index: job_mut.executions,
+                        amount: job_mut.amount,
+                    },
+                );
+                notify_executed(&env, &job_mut);
+                paid = paid.saturating_add(1);
+            } else {
+                // Insufficient funds: offload to payment_retry contract.
+                let payment_id = compute_payment_id(
+                    &env,
+                    &job_mut.employer,
+                    &job_mut.recipient,
+                    job_mut.amount,
+                    job_mut.next_scheduled_time,
+                );
+
+                let retry_addr = env
+                    .storage()
+                    .persistent()
+                    .get::<_, Address>(&StorageKey::RetryContract)
+                    .unwrap();
+                let retry_client = RetryContractClient::new(&env, &retry_addr);
+
+                let retry_config = RetryConfig {
+                    max_retries: job_mut.max_retries,
+                    retry_intervals: soroban_sdk::vec![&env, 30u64, 60u64, 120u64], // Default backoff
+                };
+
+                retry_client.schedule_retry(
+                    &payment_id,
+                    &job_mut.employer,
+                    &job_mut.recipient,
+                    &job_mut.token,
+                    &job_mut.amount,
+                    &retry_config,
+                );
+
+                // Advance the job to the next period as the retry is now managed externally
+                job_mut.next_scheduled_time = now.saturating_add(job_mut.interval_seconds);
+                write_job(&env, &job_mut);
+
+                env.events()
+                    .publish(("payment_failed", payment_id.clone()), payment_id);
             }
+            processed = processed.saturating_add(1);
+        }
 
-            job_id = job_id.saturating_add(1);
+        if processed > 0 {
+            env.events().publish(
+                ("payout_run",),
+                PayoutRunEvent {
+                    order,
+                    evaluated: processed,
+                    paid,
+                },
+            );
         }
 
         processed
@@ -794,6 +997,12 @@ impl PaymentSchedulerContract {
         env.storage().persistent().get(&StorageKey::Job(job_id))
     }
 
+    /// @notice Returns the order `process_due_payments` evaluates due jobs in.
+    /// @return The configured `PayoutOrder`; `JobId` until one is set.
+    pub fn get_payout_order(env: Env) -> PayoutOrder {
+        payout_order(&env)
+    }
+
     /// @notice Returns the contract owner address.
     /// @return `Some(Address)` after initialization, `None` before.
     pub fn get_owner(env: Env) -> Option<Address> {
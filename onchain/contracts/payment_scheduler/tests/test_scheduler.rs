@@ -17,20 +17,28 @@
 //!   before transfer)
 //! * `get_job_id_by_schedule` — lookup by deterministic ID
 //! * `get_owner` / `get_job` view helpers
+//! * Payout order — each `PayoutOrder` picks the jobs paid from an underfunded
+//!   escrow; only the owner may change it
+//! * Webhooks — executed jobs notify the linked registry
 
 #![cfg(test)]
 
 use std::ops::Add;
 
 use soroban_sdk::{
+    contract, contractimpl,
     testutils::{Address as _, Events, Ledger},
     token::{Client as TokenClient, StellarAssetClient},
-    Address, Env, IntoVal, Val, Vec,
+    vec, Address, BytesN, Env, IntoVal, String, TryFromVal, Val, Vec,
+};
+use webhook_contract::{
+    NotificationEvent, RetryConfig as WebhookRetryConfig, SecurityConfig, SignatureMethod,
+    WebhookRegistration, WebhookRegistry, WebhookRegistryClient,
 };
 
 use payment_scheduler::{
     JobFundedEvent, JobStatus, PaymentJob, PaymentSchedulerContract,
-    PaymentSchedulerContractClient, SchedulerError,
+    PaymentSchedulerContractClient, PayoutOrder, RetryConfig, SchedulerError,
 };
 
 // ─── Fixtures ─────────────────────────────────────────────────────────────────
@@ -993,3 +1001,219 @@ fn test_conflict_detection_prevents_duplicates() {
         SchedulerError::DuplicateSchedule
     );
 }
+
+// ─── Payout order ─────────────────────────────────────────────────────────────
+
+/// Stands in for `payment_retry` so underfunded jobs can be processed.
+#[contract]
+struct RetrySink;
+
+#[contractimpl]
+impl RetrySink {
+    pub fn schedule_retry(
+        _env: Env,
+        _payment_id: BytesN<32>,
+        _payer: Address,
+        _recipient: Address,
+        _token: Address,
+        _amount: i128,
+        _config: RetryConfig,
+    ) {
+    }
+}
+
+/// Runs one `process_due_payments(max_jobs)` over three due jobs funded with
+/// 250 and returns the (recipient, paid) pair of each job, by job id.
+///
+/// | job | amount | start |
+/// |-----|--------|-------|
+/// | 1   | 150    | 50    |
+/// | 2   | 250    | 0     |
+/// | 3   | 100    | 20    |
+fn run_with_order(order: PayoutOrder, max_jobs: u32) -> std::vec::Vec<(Address, bool)> {
+    let env = create_env();
+    let (scheduler_id, client) = register_contract(&env);
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &env.register(RetrySink, ()));
+    client.set_payout_order(&owner, &order);
+
+    let employer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    StellarAssetClient::new(&env, &token.address).mint(&scheduler_id, &250i128);
+
+    let mut jobs = std::vec::Vec::new();
+    for (amount, start) in [(150i128, 50u64), (250, 0), (100, 20)] {
+        let recipient = Address::generate(&env);
+        let id = client.create_job(
+            &employer,
+            &recipient,
+            &token.address,
+            &amount,
+            &1_000u64,
+            &start,
+            &None,
+            &1u32,
+        );
+        jobs.push((id, recipient));
+    }
+
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    assert_eq!(client.process_due_payments(&max_jobs), max_jobs);
+    jobs.into_iter()
+        .map(|(id, recipient)| (recipient, client.get_job(&id).unwrap().executions == 1))
+        .collect()
+}
+
+fn paid(jobs: &[(Address, bool)]) -> std::vec::Vec<usize> {
+    (0..jobs.len())
+        .filter(|&i| jobs[i].1)
+        .map(|i| i + 1)
+        .collect()
+}
+
+#[test]
+fn test_payout_order_decides_who_is_paid_when_underfunded() {
+    // Job 1 leaves 100, which cannot cover job 2
+    assert_eq!(paid(&run_with_order(PayoutOrder::JobId, 2)), [1]);
+    // Job 2 waited longest and takes the whole escrow
+    assert_eq!(paid(&run_with_order(PayoutOrder::OldestDue, 2)), [2]);
+    // Jobs 3 and 1 together fit the escrow
+    assert_eq!(
+        paid(&run_with_order(PayoutOrder::SmallestAmount, 2)),
+        [1, 3]
+    );
+
+    // Only the lowest recipient address is evaluated
+    let jobs = run_with_order(PayoutOrder::Recipient, 1);
+    let lowest = (0..3).min_by(|&a, &b| jobs[a].0.cmp(&jobs[b].0)).unwrap();
+    assert_eq!(paid(&jobs), [lowest + 1]);
+}
+
+#[test]
+fn test_payout_order_sorts_within_bounded_window() {
+    let env = create_env();
+    let (scheduler_id, client) = register_contract(&env);
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &env.register(RetrySink, ()));
+    client.set_payout_order(&owner, &PayoutOrder::SmallestAmount);
+
+    let employer = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    StellarAssetClient::new(&env, &token.address).mint(&scheduler_id, &1_000i128);
+
+    // With max_jobs = 1 the window holds jobs 1-4; job 5 is the smallest but
+    // waits for the next call.
+    let mut ids = std::vec::Vec::new();
+    for amount in [50i128, 40, 30, 20, 10] {
+        ids.push(client.create_job(
+            &employer,
+            &Address::generate(&env),
+            &token.address,
+            &amount,
+            &1_000u64,
+            &0u64,
+            &None,
+            &1u32,
+        ));
+    }
+    env.ledger().with_mut(|li| li.timestamp = 100);
+
+    let executions = |client: &PaymentSchedulerContractClient| -> std::vec::Vec<u32> {
+        ids.iter()
+            .map(|id| client.get_job(id).unwrap().executions)
+            .collect()
+    };
+    assert_eq!(client.process_due_payments(&1), 1);
+    assert_eq!(executions(&client), [0, 0, 0, 1, 0]);
+    assert_eq!(client.process_due_payments(&1), 1);
+    assert_eq!(executions(&client), [0, 0, 0, 1, 1]);
+}
+
+#[test]
+fn test_only_owner_sets_payout_order() {
+    let env = create_env();
+    let (_, client) = register_contract(&env);
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &Address::generate(&env));
+    assert_eq!(client.get_payout_order(), PayoutOrder::JobId);
+
+    let result = client.try_set_payout_order(&Address::generate(&env), &PayoutOrder::OldestDue);
+    assert_eq!(result.unwrap_err().unwrap(), SchedulerError::NotOwner);
+
+    client.set_payout_order(&owner, &PayoutOrder::SmallestAmount);
+    assert_eq!(client.get_payout_order(), PayoutOrder::SmallestAmount);
+}
+
+// ─── Webhooks ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_executed_jobs_notify_the_webhook_registry() {
+    let env = create_env();
+    let (scheduler_id, client) = register_contract(&env);
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &Address::generate(&env));
+    let employer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let token = create_token_contract(&env, &Address::generate(&env));
+    StellarAssetClient::new(&env, &token.address).mint(&scheduler_id, &100i128);
+
+    let registry = WebhookRegistryClient::new(&env, &env.register(WebhookRegistry, ()));
+    registry.initialize(&owner);
+    registry.set_notifier(&owner, &scheduler_id, &true);
+    registry.register_webhook(
+        &employer,
+        &WebhookRegistration {
+            name: String::from_str(&env, "scheduler"),
+            description: String::from_str(&env, ""),
+            url: String::from_str(&env, "https://hooks.example.com"),
+            events: vec![&env, String::from_str(&env, "schedule_executed")],
+            secret_hash: BytesN::from_array(&env, &[1; 32]),
+            retry_config: WebhookRetryConfig {
+                max_retries: 3,
+                retry_delay: 5,
+                exponential_backoff: true,
+                max_delay: 60,
+            },
+            security_config: SecurityConfig {
+                signature_method: SignatureMethod::HmacSha256,
+                rate_limit_per_minute: 60,
+                require_tls: true,
+            },
+        },
+    );
+    let stranger = Address::generate(&env);
+    let result = client.try_set_webhook_registry(&stranger, &Some(registry.address.clone()));
+    assert_eq!(result.unwrap_err().unwrap(), SchedulerError::NotOwner);
+    client.set_webhook_registry(&owner, &Some(registry.address.clone()));
+    assert_eq!(
+        client.get_webhook_registry(),
+        Some(registry.address.clone())
+    );
+
+    let job_id = client.create_job(
+        &employer,
+        &recipient,
+        &token.address,
+        &100i128,
+        &0u64,
+        &0u64,
+        &Some(1u32),
+        &1u32,
+    );
+    assert_eq!(client.process_due_payments(&10u32), 1);
+
+    let sent: std::vec::Vec<NotificationEvent> = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract, _, _)| *contract == registry.address)
+        .map(|(_, _, data)| NotificationEvent::try_from_val(&env, &data).unwrap())
+        .collect();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(
+        (sent[0].subject.clone(), sent[0].reference, sent[0].amount),
+        (recipient, job_id, 100)
+    );
+}
@@ -0,0 +1,200 @@
+//! Payroll analytics companion contract.
+//!
+//! Holds the payout metrics that used to live in the payroll contract, so
+//! their storage grows here instead of there. The linked payroll contract
+//! pushes one compact [`PayoutRecord`] per periodic payout; this contract
+//! aggregates them into:
+//!
+//! - per-employee [`EmployeeMetrics`]: total received, on-time and late
+//!   payouts, lateness and the current on-time streak;
+//! - per-employer [`EmployerMetrics`]: payout totals in the employer's
+//!   reporting currency, converted by the payroll contract at its oracle
+//!   rates.
+//!
+//! Only the linked payroll contract may push records. Queries are open.
+
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+pub use analytics_interface::{EmployeeMetrics, EmployerMetrics, PayoutRecord};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// ---------------------------------------------------------------------------
+// Storage
+// ---------------------------------------------------------------------------
+
+/// Storage keys for the analytics contract.
+#[contracttype]
+#[derive(Clone)]
+enum StorageKey {
+    /// Admin allowed to relink the payroll contract.
+    Admin,
+    /// Payroll contract allowed to push records.
+    Payroll,
+    /// Payout metrics for an employee -> EmployeeMetrics
+    Employee(Address),
+    /// Reporting-currency totals for an employer -> EmployerMetrics
+    Employer(Address),
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+/// Reverts unless the call comes from the linked payroll contract.
+fn require_payroll(env: &Env) {
+    let payroll: Address = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::Payroll)
+        .expect("Contract not initialized");
+    payroll.require_auth();
+}
+
+fn new_employer_metrics(env: &Env, currency: Address) -> EmployerMetrics {
+    EmployerMetrics {
+        reporting_currency: Some(currency),
+        since: env.ledger().timestamp(),
+        ..EmployerMetrics::default()
+    }
+}
+
+fn record_employee(env: &Env, record: &PayoutRecord) {
+    let key = StorageKey::Employee(record.employee.clone());
+    let mut metrics: EmployeeMetrics = env.storage().persistent().get(&key).unwrap_or_default();
+    metrics.total_received = metrics.total_received.saturating_add(record.amount);
+    metrics.late_payouts = metrics.late_payouts.saturating_add(record.late);
+    metrics.on_time_payouts = metrics.on_time_payouts.saturating_add(record.on_time);
+    metrics.total_seconds_late = metrics
+        .total_seconds_late
+        .saturating_add(record.seconds_late);
+    if metrics.late_payouts > 0 {
+        metrics.average_days_late =
+            metrics.total_seconds_late / u64::from(metrics.late_payouts) / SECONDS_PER_DAY;
+    }
+    if record.late > 0 {
+        metrics.current_streak = 0;
+    }
+    metrics.current_streak = metrics.current_streak.saturating_add(record.on_time);
+    metrics.last_payout_at = env.ledger().timestamp();
+    env.storage().persistent().set(&key, &metrics);
+}
+
+fn record_employer(env: &Env, record: &PayoutRecord) {
+    let Some(currency) = record.reporting_currency.clone() else {
+        return;
+    };
+    let key = StorageKey::Employer(record.employer.clone());
+    let mut metrics: EmployerMetrics = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .filter(|stored: &EmployerMetrics| stored.reporting_currency == record.reporting_currency)
+        // Also covers a payroll contract linked after the currency was set.
+        .unwrap_or_else(|| new_employer_metrics(env, currency));
+    match record.reporting_amount {
+        Some(converted) => {
+            metrics.total_paid_reporting = metrics.total_paid_reporting.saturating_add(converted);
+            metrics.converted_payouts = metrics.converted_payouts.saturating_add(1);
+        }
+        None => {
+            metrics.unconverted_payouts = metrics.unconverted_payouts.saturating_add(1);
+        }
+    }
+    env.storage().persistent().set(&key, &metrics);
+}
+
+// ---------------------------------------------------------------------------
+// Contract
+// ---------------------------------------------------------------------------
+
+#[contract]
+pub struct PayrollAnalytics;
+
+#[contractimpl]
+impl PayrollAnalytics {
+    /// Sets the admin and links the payroll contract allowed to push records.
+    ///
+    /// # Access Control
+    /// Requires admin authentication. Only callable once.
+    pub fn initialize(env: Env, admin: Address, payroll: Address) {
+        admin.require_auth();
+        if env.storage().persistent().has(&StorageKey::Admin) {
+            panic!("Already initialized");
+        }
+        env.storage().persistent().set(&StorageKey::Admin, &admin);
+        env.storage()
+            .persistent()
+            .set(&StorageKey::Payroll, &payroll);
+    }
+
+    /// Links another payroll contract, e.g. after a redeployment. Metrics
+    /// recorded so far are kept.
+    ///
+    /// # Access Control
+    /// Requires admin authentication
+    pub fn set_payroll_contract(env: Env, admin: Address, payroll: Address) {
+        let stored: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Admin)
+            .expect("Contract not initialized");
+        admin.require_auth();
+        assert!(admin == stored, "Unauthorized");
+        env.storage()
+            .persistent()
+            .set(&StorageKey::Payroll, &payroll);
+    }
+
+    /// Returns the linked payroll contract, if initialized.
+    pub fn get_payroll_contract(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&StorageKey::Payroll)
+    }
+
+    /// Adds a payout to the employee's metrics and, when it carries a
+    /// reporting currency, to the employer's totals.
+    ///
+    /// # Access Control
+    /// Only the linked payroll contract
+    pub fn record_payout(env: Env, record: PayoutRecord) {
+        require_payroll(&env);
+        record_employee(&env, &record);
+        record_employer(&env, &record);
+    }
+
+    /// Restarts the employer's totals in `currency`, or clears them with
+    /// `None`.
+    ///
+    /// # Access Control
+    /// Only the linked payroll contract
+    pub fn set_reporting_currency(env: Env, employer: Address, currency: Option<Address>) {
+        require_payroll(&env);
+        let key = StorageKey::Employer(employer);
+        match currency {
+            Some(currency) => env
+                .storage()
+                .persistent()
+                .set(&key, &new_employer_metrics(&env, currency)),
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    /// Returns the metrics for `employee`, all zero if they were never paid.
+    pub fn get_employee_metrics(env: Env, employee: Address) -> EmployeeMetrics {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::Employee(employee))
+            .unwrap_or_default()
+    }
+
+    /// Returns the reporting-currency totals for `employer`, all zero if no
+    /// reporting currency is set.
+    pub fn get_employer_metrics(env: Env, employer: Address) -> EmployerMetrics {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::Employer(employer))
+            .unwrap_or_default()
+    }
+}
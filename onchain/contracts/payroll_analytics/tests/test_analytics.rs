@@ -0,0 +1,160 @@
+#![cfg(test)]
+
+use payroll_analytics::{
+    EmployeeMetrics, EmployerMetrics, PayoutRecord, PayrollAnalytics, PayrollAnalyticsClient,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+    Address, Env, IntoVal,
+};
+
+const DAY: u64 = 86_400;
+
+fn setup(env: &Env) -> (PayrollAnalyticsClient<'_>, Address, Address) {
+    env.ledger().with_mut(|l| l.timestamp = 1_000_000);
+    let client = PayrollAnalyticsClient::new(env, &env.register(PayrollAnalytics, ()));
+    let admin = Address::generate(env);
+    let payroll = Address::generate(env);
+    env.mock_all_auths();
+    client.initialize(&admin, &payroll);
+    (client, admin, payroll)
+}
+
+fn record(employee: &Address, employer: &Address) -> PayoutRecord {
+    PayoutRecord {
+        employee: employee.clone(),
+        employer: employer.clone(),
+        amount: 3_000,
+        on_time: 1,
+        late: 2,
+        seconds_late: 3 * DAY,
+        reporting_currency: None,
+        reporting_amount: None,
+    }
+}
+
+#[test]
+fn payouts_accumulate_per_employee() {
+    let env = Env::default();
+    let (client, _, _) = setup(&env);
+    let employee = Address::generate(&env);
+    let employer = Address::generate(&env);
+
+    client.record_payout(&record(&employee, &employer));
+    client.record_payout(&PayoutRecord {
+        on_time: 2,
+        late: 0,
+        seconds_late: 0,
+        ..record(&employee, &employer)
+    });
+
+    assert_eq!(
+        client.get_employee_metrics(&employee),
+        EmployeeMetrics {
+            total_received: 6_000,
+            on_time_payouts: 3,
+            late_payouts: 2,
+            total_seconds_late: 3 * DAY,
+            average_days_late: 1,
+            current_streak: 3,
+            last_payout_at: 1_000_000,
+        }
+    );
+    // No reporting currency: no employer totals
+    assert_eq!(
+        client.get_employer_metrics(&employer),
+        EmployerMetrics::default()
+    );
+}
+
+#[test]
+fn employer_totals_restart_with_the_reporting_currency() {
+    let env = Env::default();
+    let (client, _, _) = setup(&env);
+    let employee = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let usd = Address::generate(&env);
+    let eur = Address::generate(&env);
+
+    client.set_reporting_currency(&employer, &Some(usd.clone()));
+    for reporting_amount in [Some(7_500), None] {
+        client.record_payout(&PayoutRecord {
+            reporting_currency: Some(usd.clone()),
+            reporting_amount,
+            ..record(&employee, &employer)
+        });
+    }
+    let metrics = client.get_employer_metrics(&employer);
+    assert_eq!(
+        (
+            metrics.total_paid_reporting,
+            metrics.converted_payouts,
+            metrics.unconverted_payouts
+        ),
+        (7_500, 1, 1)
+    );
+
+    // A record in another currency than the stored totals starts over
+    env.ledger().with_mut(|l| l.timestamp += DAY);
+    client.record_payout(&PayoutRecord {
+        reporting_currency: Some(eur.clone()),
+        reporting_amount: Some(2_700),
+        ..record(&employee, &employer)
+    });
+    assert_eq!(
+        client.get_employer_metrics(&employer),
+        EmployerMetrics {
+            reporting_currency: Some(eur),
+            total_paid_reporting: 2_700,
+            converted_payouts: 1,
+            unconverted_payouts: 0,
+            since: 1_000_000 + DAY,
+        }
+    );
+
+    client.set_reporting_currency(&employer, &None);
+    assert_eq!(
+        client.get_employer_metrics(&employer),
+        EmployerMetrics::default()
+    );
+}
+
+#[test]
+fn only_the_payroll_contract_may_push() {
+    let env = Env::default();
+    let (client, admin, payroll) = setup(&env);
+    let employee = Address::generate(&env);
+    let payout = record(&employee, &Address::generate(&env));
+
+    let intruder = Address::generate(&env);
+    env.mock_auths(&[MockAuth {
+        address: &intruder,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "record_payout",
+            args: (payout.clone(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(client.try_record_payout(&payout).is_err());
+
+    env.mock_auths(&[MockAuth {
+        address: &payroll,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "record_payout",
+            args: (payout.clone(),).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.record_payout(&payout);
+    assert_eq!(client.get_employee_metrics(&employee).total_received, 3_000);
+
+    env.mock_all_auths();
+    assert!(client
+        .try_set_payroll_contract(&Address::generate(&env), &intruder)
+        .is_err());
+    client.set_payroll_contract(&admin, &intruder);
+    assert_eq!(client.get_payroll_contract(), Some(intruder));
+    assert!(client.try_initialize(&admin, &payroll).is_err());
+}
@@ -1,6 +1,9 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+use soroban_sdk::{contract, contractimpl, contracttype, vec, Address, Env, String, Symbol, Vec};
+use webhook_interface::{event_types, WebhookNotification, WebhookRegistryClient};
+
+pub use stellopay_interfaces::escrow::{FundedEvent, RefundedEvent, ReleasedEvent};
 
 /// PayrollEscrow Contract for managing fund deposits, releases, and refunds.
 ///
@@ -12,6 +15,8 @@ use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
 /// # Security Model
 ///
 /// - Only the manager contract can release or refund funds
+/// - If the manager stops acting on an agreement, its registered employee can
+///   claim a predefined fallback share of the balance
 /// - Only the admin can initialize, upgrade, or rotate the manager
 /// - Per-agreement balance tracking prevents cross-agreement fund mixing
 /// - All operations emit events for auditability
@@ -34,20 +39,174 @@ pub enum StorageKey {
     Admin,
     /// Initialization flag
     Initialized,
+    /// Seconds between a cancellation signal and the earliest refund -> u64
+    DisputeWindow,
+    /// Pending cancellation of an agreement: agreement_id -> Cancellation
+    Cancellation(u128),
+    /// Number of funding receipts: agreement_id -> u32
+    FundingCount(u128),
+    /// Funding receipt: (agreement_id, index) -> FundingReceipt
+    FundingReceipt(u128, u32),
+    /// Addresses that funded the current balance: agreement_id -> Vec<Address>
+    AgreementFunders(u128),
+    /// Amount contributed to the current balance: (agreement_id, funder) -> i128
+    FunderContribution(u128, Address),
+    /// Open streaming release: agreement_id -> Stream
+    Stream(u128),
+    /// Protocol fee on releases, in basis points -> u32
+    FeeBps,
+    /// Fee change waiting out its timelock -> PendingFee
+    PendingFee,
+    /// Fees collected and not yet withdrawn: token -> i128
+    AccruedFees(Address),
+    /// Manager inactivity after which fallback claims open, in seconds -> u64
+    StallPeriod,
+    /// Last manager action on an agreement: agreement_id -> u64
+    LastManagerActivity(u128),
+    /// Registered fallback claim: agreement_id -> StallFallback
+    StallFallback(u128),
+    /// Employer that has funded an agreement: employer -> bool
+    KnownEmployer(Address),
+    /// Referrer owning a referral code: code -> Address
+    ReferralCode(Symbol),
+    /// Referral code registered by a referrer: referrer -> Symbol
+    ReferrerCode(Address),
+    /// Referral applied by a new employer: employer -> Referral
+    Referral(Address),
+    /// Referral rewards not yet claimed: (referrer, token) -> i128
+    ReferralRewards(Address, Address),
+    /// Webhook registry notified of releases -> Address
+    WebhookRegistry,
+}
+
+/// Highest protocol fee the admin can set (5%).
+pub const MAX_FEE_BPS: u32 = 500;
+
+/// Delay between proposing a fee change and being able to apply it.
+pub const FEE_TIMELOCK_SECONDS: u64 = 2 * 86_400;
+
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Share of the protocol fee on a referred employer's releases that accrues
+/// to the referrer (10%).
+pub const REFERRAL_SHARE_BPS: u32 = 1_000;
+
+/// How long a referral earns rewards after the code is applied (180 days).
+pub const REFERRAL_PERIOD_SECONDS: u64 = 180 * 86_400;
+
+/// Share of an agreement's balance its employee can claim if the manager
+/// stops interacting with the agreement.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StallFallback {
+    pub employee: Address,
+    pub share_bps: u32,
+}
+
+/// A proposed protocol fee that can be applied from `effective_at`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingFee {
+    pub fee_bps: u32,
+    pub effective_at: u64,
+}
+
+/// A referral code applied by a new employer. Until `expires_at`, part of the
+/// protocol fee on the employer's releases accrues to `referrer`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Referral {
+    pub referrer: Address,
+    pub code: Symbol,
+    pub expires_at: u64,
+}
+
+/// A continuous release of an agreement's funds to one recipient.
+///
+/// `rate_per_second` accrues from `start_time` to `end_time`; `withdrawn_until`
+/// is the point up to which accrued funds have been paid out.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stream {
+    pub to: Address,
+    pub rate_per_second: i128,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub withdrawn_until: u64,
+}
+
+/// Maximum number of distinct funders per agreement, bounding refund work.
+pub const MAX_FUNDERS: u32 = 10;
+
+/// Maximum number of receipts returned by one `get_funding_history` call.
+pub const MAX_HISTORY_PAGE: u32 = 50;
+
+/// Record of one `fund_agreement` deposit.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundingReceipt {
+    pub funder: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// A cancellation signalled by the manager and not yet refunded.
+///
+/// The remaining balance can be refunded once `refundable_at` is reached, or
+/// earlier if both the employer and the employee consent. An open dispute
+/// blocks the refund until the manager resolves it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Cancellation {
+    pub employee: Address,
+    pub signaled_at: u64,
+    pub refundable_at: u64,
+    pub employer_consented: bool,
+    pub employee_consented: bool,
+    pub disputed: bool,
 }
 
-/// Events emitted by the escrow contract
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct FundedEvent {
+pub struct CancellationSignaledEvent {
     pub agreement_id: u128,
-    pub employer: Address,
-    pub amount: i128,
+    pub employee: Address,
+    pub refundable_at: u64,
 }
 
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct ReleasedEvent {
+pub struct RefundConsentedEvent {
+    pub agreement_id: u128,
+    pub party: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RefundDisputeOpenedEvent {
+    pub agreement_id: u128,
+    pub employee: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RefundDisputeResolvedEvent {
+    pub agreement_id: u128,
+    pub refund_allowed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct StreamStartedEvent {
+    pub agreement_id: u128,
+    pub to: Address,
+    pub rate_per_second: i128,
+    pub end_time: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct StreamWithdrawnEvent {
     pub agreement_id: u128,
     pub to: Address,
     pub amount: i128,
@@ -55,12 +214,74 @@ pub struct ReleasedEvent {
 
 #[contracttype]
 #[derive(Clone, Debug)]
-pub struct RefundedEvent {
+pub struct StreamStoppedEvent {
     pub agreement_id: u128,
+    pub paid_out: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct FeeChangeProposedEvent {
+    pub fee_bps: u32,
+    pub effective_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct FeeUpdatedEvent {
+    pub old_fee_bps: u32,
+    pub new_fee_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct FeesWithdrawnEvent {
+    pub token: Address,
     pub to: Address,
     pub amount: i128,
 }
 
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct StalledClaimEvent {
+    pub agreement_id: u128,
+    pub employee: Address,
+    pub amount: i128,
+    pub last_activity: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReferralCodeRegisteredEvent {
+    pub referrer: Address,
+    pub code: Symbol,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReferralAppliedEvent {
+    pub employer: Address,
+    pub referrer: Address,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReferralAccruedEvent {
+    pub agreement_id: u128,
+    pub referrer: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReferralRewardsClaimedEvent {
+    pub referrer: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
 /// Emitted after the authenticated admin rotates the manager address.
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -185,6 +406,10 @@ impl PayrollEscrowContract {
     /// * Amount must be positive
     /// * Caller must have approved sufficient tokens for transfer
     /// * New balance must not overflow i128
+    /// * A new funder must not exceed `MAX_FUNDERS` for the agreement
+    ///
+    /// Each deposit is recorded as a `FundingReceipt` and credited to the
+    /// funder's contribution, which determines their share of a refund.
     ///
     /// # Checks-Effects-Interactions (CEI) Ordering
     ///
@@ -228,6 +453,9 @@ impl PayrollEscrowContract {
             env.storage()
                 .persistent()
                 .set(&StorageKey::AgreementEmployer(agreement_id), &employer);
+            env.storage()
+                .persistent()
+                .set(&StorageKey::KnownEmployer(employer.clone()), &true);
         }
 
         // Compute and validate new balance BEFORE any token transfer
@@ -244,6 +472,7 @@ impl PayrollEscrowContract {
         env.storage()
             .persistent()
             .set(&StorageKey::AgreementBalance(agreement_id), &new_balance);
+        Self::record_funding(&env, agreement_id, &from, amount);
 
         // Get token address
         let token: Address = env
@@ -265,6 +494,13 @@ impl PayrollEscrowContract {
                 amount,
             },
         );
+        Self::notify(
+            &env,
+            event_types::ESCROW_FUNDED,
+            agreement_id,
+            &from,
+            amount,
+        );
     }
 
     /// Releases funds from escrow to a recipient.
@@ -286,6 +522,11 @@ impl PayrollEscrowContract {
     /// * Agreement must have sufficient balance for the requested amount
     /// * Amount must be positive
     ///
+    /// If a protocol fee is set, `fee_bps` of `amount` is withheld for the
+    /// admin and the recipient receives the rest. If the agreement's employer
+    /// applied a referral code that has not expired, `REFERRAL_SHARE_BPS` of
+    /// the fee accrues to the referrer instead.
+    ///
     /// # Access Control
     ///
     /// STRICT: Only the address stored as `Manager` can authorize releases.
@@ -314,6 +555,7 @@ impl PayrollEscrowContract {
             .get(&StorageKey::Manager)
             .expect("Manager not set");
         assert!(caller == manager, "Only manager can release funds");
+        Self::record_manager_activity(&env, agreement_id);
 
         // Validate amount
         assert!(amount > 0, "Amount must be positive");
@@ -339,144 +581,1441 @@ impl PayrollEscrowContract {
             .persistent()
             .set(&StorageKey::AgreementBalance(agreement_id), &new_balance);
 
+        // Withhold the protocol fee
+        let fee = amount * i128::from(Self::get_fee_bps(env.clone())) / BPS_DENOMINATOR;
+        if fee > 0 {
+            let referral_reward = Self::accrue_referral_reward(&env, agreement_id, &token, fee);
+            let fees_key = StorageKey::AccruedFees(token.clone());
+            let accrued: i128 = env.storage().persistent().get(&fees_key).unwrap_or(0);
+            env.storage()
+                .persistent()
+                .set(&fees_key, &(accrued + fee - referral_reward));
+        }
+        let net = amount - fee;
+
         // Transfer tokens
         let token_client = soroban_sdk::token::Client::new(&env, &token);
-        token_client.transfer(&env.current_contract_address(), &to, &amount);
+        token_client.transfer(&env.current_contract_address(), &to, &net);
 
         // Emit event
         env.events().publish(
             ("released", agreement_id),
             ReleasedEvent {
                 agreement_id,
-                to,
-                amount,
+                to: to.clone(),
+                amount: net,
+                fee,
             },
         );
+        Self::notify(&env, event_types::ESCROW_RELEASED, agreement_id, &to, net);
     }
 
-    /// Refunds remaining balance to the employer.
+    /// Links the webhook registry notified of every deposit, release and
+    /// refund, or unlinks it with `None`. The registry holds all webhook state and must also allow
+    /// this contract as a notifier.
     ///
-    /// Only the manager contract can call this function. This is typically used
-    /// when an agreement is cancelled and the grace period has expired.
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `admin` - The admin address (must authenticate)
+    /// * `registry` - The deployed `webhook_contract`, or `None`
+    pub fn set_webhook_registry(env: Env, admin: Address, registry: Option<Address>) {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Admin)
+            .expect("Admin not set");
+        assert!(admin == stored_admin, "Only admin can set webhook registry");
+
+        match registry {
+            Some(registry) => env
+                .storage()
+                .persistent()
+                .set(&StorageKey::WebhookRegistry, &registry),
+            None => env
+                .storage()
+                .persistent()
+                .remove(&StorageKey::WebhookRegistry),
+        }
+    }
+
+    /// Gets the linked webhook registry, if any.
+    pub fn get_webhook_registry(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&StorageKey::WebhookRegistry)
+    }
+
+    /// Sets how long the manager must leave an agreement untouched before its
+    /// employee can claim the fallback share. Zero (the default) disables
+    /// fallback claims.
     ///
     /// # Arguments
     ///
     /// * `env` - The Soroban environment
-    /// * `caller` - The caller address (must be manager, must authenticate)
-    /// * `agreement_id` - The unique identifier for the agreement
+    /// * `admin` - The admin address (must authenticate)
+    /// * `seconds` - The stall period
+    pub fn set_stall_period(env: Env, admin: Address, seconds: u64) {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Admin)
+            .expect("Admin not set");
+        assert!(admin == stored_admin, "Only admin can set stall period");
+
+        env.storage()
+            .persistent()
+            .set(&StorageKey::StallPeriod, &seconds);
+    }
+
+    /// Gets the stall period in seconds (0 if fallback claims are disabled).
+    pub fn get_stall_period(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::StallPeriod)
+            .unwrap_or(0)
+    }
+
+    /// Registers the agreement's employee and the share of its balance they
+    /// can claim with `claim_stalled` if the manager goes quiet. Replaces any
+    /// earlier registration.
     ///
-    /// # Requirements
+    /// # Arguments
     ///
-    /// * Caller must be the authorized manager contract address
-    /// * Agreement must have a positive balance remaining
-    /// * Agreement must have an assigned employer address
+    /// * `env` - The Soroban environment
+    /// * `caller` - The caller address (must be manager, must authenticate)
+    /// * `agreement_id` - The unique identifier for the agreement
+    /// * `employee` - The employee protected by the fallback
+    /// * `share_bps` - Share of the balance paid on a stalled claim, in basis points
+    pub fn set_stall_fallback(
+        env: Env,
+        caller: Address,
+        agreement_id: u128,
+        employee: Address,
+        share_bps: u32,
+    ) {
+        caller.require_auth();
+
+        let manager: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Manager)
+            .expect("Manager not set");
+        assert!(caller == manager, "Only manager can set stall fallback");
+        Self::record_manager_activity(&env, agreement_id);
+        assert!(
+            share_bps > 0 && i128::from(share_bps) <= BPS_DENOMINATOR,
+            "Invalid fallback share"
+        );
+
+        env.storage().persistent().set(
+            &StorageKey::StallFallback(agreement_id),
+            &StallFallback {
+                employee,
+                share_bps,
+            },
+        );
+    }
+
+    /// Gets the fallback registered for an agreement, if any.
+    pub fn get_stall_fallback(env: Env, agreement_id: u128) -> Option<StallFallback> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::StallFallback(agreement_id))
+    }
+
+    /// Gets the time of the manager's last action on an agreement, if any.
+    pub fn get_last_manager_activity(env: Env, agreement_id: u128) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::LastManagerActivity(agreement_id))
+    }
+
+    /// Pays the registered employee their fallback share of the agreement
+    /// balance when the manager has not acted on the agreement for the stall
+    /// period. The fallback can be claimed once.
     ///
-    /// # Access Control
+    /// # Arguments
     ///
-    /// STRICT: Only the address stored as `Manager` can authorize refunds.
+    /// * `env` - The Soroban environment
+    /// * `agreement_id` - The unique identifier for the agreement
     ///
-    /// # Invariants
+    /// # Returns
     ///
-    /// - After a successful refund, the `AgreementBalance` for that `agreement_id` is exactly zero.
-    /// - Funds are only returned to the `AgreementEmployer` address originally recorded.
+    /// The amount paid to the employee
     ///
-    /// # Invariant
+    /// # Requirements
     ///
-    /// Refunded agreement balance is zeroed before transfer so nested calls
-    /// cannot observe and drain the old balance twice.
+    /// * The registered employee must authenticate
+    /// * Fallback claims must be enabled and the stall period elapsed
+    /// * The agreement must have a balance
     ///
     /// # Events
     ///
-    /// Emits `Refunded` event on success.
-    pub fn refund_remaining(env: Env, caller: Address, agreement_id: u128) {
-        caller.require_auth();
-
-        // Check manager authorization
-        let manager: Address = env
+    /// Emits `StalledClaim` on success.
+    pub fn claim_stalled(env: Env, agreement_id: u128) -> i128 {
+        let fallback_key = StorageKey::StallFallback(agreement_id);
+        let fallback: StallFallback = env
             .storage()
             .persistent()
-            .get(&StorageKey::Manager)
-            .expect("Manager not set");
-        assert!(caller == manager, "Only manager can refund funds");
+            .get(&fallback_key)
+            .expect("No stall fallback registered");
+        fallback.employee.require_auth();
 
-        // Get balance
-        let balance: i128 = env
-            .storage()
-            .persistent()
-            .get(&StorageKey::AgreementBalance(agreement_id))
-            .unwrap_or(0);
-        assert!(balance > 0, "No balance to refund");
+        let stall_period = Self::get_stall_period(env.clone());
+        assert!(stall_period > 0, "Stall claims disabled");
+        let last_activity = Self::get_last_manager_activity(env.clone(), agreement_id)
+            .expect("No stall fallback registered");
+        assert!(
+            env.ledger().timestamp() >= last_activity.saturating_add(stall_period),
+            "Manager is not stalled"
+        );
 
-        // Get employer
-        let employer: Address = env
-            .storage()
+        let balance_key = StorageKey::AgreementBalance(agreement_id);
+        let balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        let amount = balance * i128::from(fallback.share_bps) / BPS_DENOMINATOR;
+        assert!(amount > 0, "No balance to claim");
+
+        env.storage()
             .persistent()
-            .get(&StorageKey::AgreementEmployer(agreement_id))
-            .expect("Employer not found");
+            .set(&balance_key, &(balance - amount));
+        env.storage().persistent().remove(&fallback_key);
 
-        // Get token address
         let token: Address = env
             .storage()
             .persistent()
             .get(&StorageKey::Token)
             .expect("Token not set");
+        soroban_sdk::token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &fallback.employee,
+            &amount,
+        );
 
-        let token_client = soroban_sdk::token::Client::new(&env, &token);
-        token_client.transfer(&env.current_contract_address(), &employer, &balance);
-
-        env.storage()
-            .persistent()
-            .set(&StorageKey::AgreementBalance(agreement_id), &0i128);
-
-        // Emit event
         env.events().publish(
-            ("refunded", agreement_id),
-            RefundedEvent {
+            ("stalled_claim", agreement_id),
+            StalledClaimEvent {
                 agreement_id,
-                to: employer,
-                amount: balance,
+                employee: fallback.employee,
+                amount,
+                last_activity,
             },
         );
+        amount
     }
 
-    /// Gets the current balance for an agreement.
+    /// Proposes a new protocol fee on releases. It can be applied with
+    /// `apply_fee_change` once `FEE_TIMELOCK_SECONDS` have passed; a new
+    /// proposal replaces a pending one and restarts the timelock.
     ///
     /// # Arguments
     ///
     /// * `env` - The Soroban environment
-    /// * `agreement_id` - The unique identifier for the agreement
-    ///
-    /// # Returns
+    /// * `admin` - The admin address (must authenticate)
+    /// * `fee_bps` - Fee in basis points, at most `MAX_FEE_BPS`
     ///
-    /// The current balance (0 if no balance exists)
+    /// # Events
     ///
-    /// # Access Control
-    /// Requires caller authentication
-    pub fn get_agreement_balance(env: Env, agreement_id: u128) -> i128 {
-        env.storage()
+    /// Emits `FeeChangeProposed` on success.
+    pub fn propose_fee_change(env: Env, admin: Address, fee_bps: u32) {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
             .persistent()
-            .get(&StorageKey::AgreementBalance(agreement_id))
-            .unwrap_or(0)
+            .get(&StorageKey::Admin)
+            .expect("Admin not set");
+        assert!(admin == stored_admin, "Only admin can change fees");
+        assert!(fee_bps <= MAX_FEE_BPS, "Fee exceeds maximum");
+
+        let effective_at = env
+            .ledger()
+            .timestamp()
+            .saturating_add(FEE_TIMELOCK_SECONDS);
+        env.storage().persistent().set(
+            &StorageKey::PendingFee,
+            &PendingFee {
+                fee_bps,
+                effective_at,
+            },
+        );
+
+        env.events().publish(
+            ("fee_change_proposed",),
+            FeeChangeProposedEvent {
+                fee_bps,
+                effective_at,
+            },
+        );
     }
 
-    /// Gets the employer address for an agreement.
+    /// Applies the pending fee change once its timelock has elapsed.
     ///
     /// # Arguments
     ///
     /// * `env` - The Soroban environment
-    /// * `agreement_id` - The unique identifier for the agreement
-    ///
-    /// # Returns
+    /// * `admin` - The admin address (must authenticate)
     ///
-    /// The employer address if found, None otherwise
+    /// # Events
     ///
-    /// # Access Control
-    /// Requires caller authentication
+    /// Emits `FeeUpdated` on success.
+    pub fn apply_fee_change(env: Env, admin: Address) {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Admin)
+            .expect("Admin not set");
+        assert!(admin == stored_admin, "Only admin can change fees");
+
+        let pending: PendingFee = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::PendingFee)
+            .expect("No pending fee change");
+        assert!(
+            env.ledger().timestamp() >= pending.effective_at,
+            "Fee change is timelocked"
+        );
+
+        let old_fee_bps = Self::get_fee_bps(env.clone());
+        env.storage()
+            .persistent()
+            .set(&StorageKey::FeeBps, &pending.fee_bps);
+        env.storage().persistent().remove(&StorageKey::PendingFee);
+
+        env.events().publish(
+            ("fee_updated",),
+            FeeUpdatedEvent {
+                old_fee_bps,
+                new_fee_bps: pending.fee_bps,
+            },
+        );
+    }
+
+    /// Transfers all fees collected in `token` to `to`.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `admin` - The admin address (must authenticate)
+    /// * `token` - The token whose fees are withdrawn
+    /// * `to` - The fee recipient
+    ///
+    /// # Returns
+    ///
+    /// The amount withdrawn
+    ///
+    /// # Events
+    ///
+    /// Emits `FeesWithdrawn` on success.
+    pub fn withdraw_fees(env: Env, admin: Address, token: Address, to: Address) -> i128 {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Admin)
+            .expect("Admin not set");
+        assert!(admin == stored_admin, "Only admin can withdraw fees");
+
+        let fees_key = StorageKey::AccruedFees(token.clone());
+        let amount: i128 = env.storage().persistent().get(&fees_key).unwrap_or(0);
+        assert!(amount > 0, "No fees to withdraw");
+        env.storage().persistent().remove(&fees_key);
+
+        soroban_sdk::token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &to,
+            &amount,
+        );
+
+        env.events().publish(
+            ("fees_withdrawn",),
+            FeesWithdrawnEvent { token, to, amount },
+        );
+        amount
+    }
+
+    /// Gets the protocol fee on releases in basis points (0 if unset).
+    pub fn get_fee_bps(env: Env) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::FeeBps)
+            .unwrap_or(0)
+    }
+
+    /// Gets the fee change waiting out its timelock, if any.
+    pub fn get_pending_fee(env: Env) -> Option<PendingFee> {
+        env.storage().persistent().get(&StorageKey::PendingFee)
+    }
+
+    /// Gets the fees collected in `token` and not yet withdrawn.
+    pub fn get_accrued_fees(env: Env, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::AccruedFees(token))
+            .unwrap_or(0)
+    }
+
+    /// Registers a referral code for an existing employer.
+    ///
+    /// New employers that apply the code share part of the protocol fee on
+    /// their releases with the referrer for `REFERRAL_PERIOD_SECONDS`.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `referrer` - The employer registering the code (must authenticate)
+    /// * `code` - The referral code
+    ///
+    /// # Requirements
+    ///
+    /// * `referrer` must be the employer of a funded agreement
+    /// * `code` must not be registered yet
+    /// * `referrer` must not have registered a code already
+    ///
+    /// # Events
+    ///
+    /// Emits `ReferralCodeRegistered` on success.
+    pub fn register_referral_code(env: Env, referrer: Address, code: Symbol) {
+        referrer.require_auth();
+
+        assert!(
+            Self::is_known_employer(&env, &referrer),
+            "Only existing employers can register referral codes"
+        );
+        assert!(
+            !env.storage()
+                .persistent()
+                .has(&StorageKey::ReferralCode(code.clone())),
+            "Referral code already registered"
+        );
+        let referrer_code_key = StorageKey::ReferrerCode(referrer.clone());
+        assert!(
+            !env.storage().persistent().has(&referrer_code_key),
+            "Employer already has a referral code"
+        );
+
+        env.storage()
+            .persistent()
+            .set(&StorageKey::ReferralCode(code.clone()), &referrer);
+        env.storage().persistent().set(&referrer_code_key, &code);
+
+        env.events().publish(
+            ("referral_registered",),
+            ReferralCodeRegisteredEvent { referrer, code },
+        );
+    }
+
+    /// Applies a referral code to a new employer.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `employer` - The new employer (must authenticate)
+    /// * `code` - A registered referral code
+    ///
+    /// # Requirements
+    ///
+    /// * `code` must be registered
+    /// * `employer` must not have funded any agreement yet, which also rules
+    ///   out applying one's own code
+    /// * `employer` must not have applied a referral code already
+    ///
+    /// # Events
+    ///
+    /// Emits `ReferralApplied` on success.
+    pub fn apply_referral_code(env: Env, employer: Address, code: Symbol) {
+        employer.require_auth();
+
+        let referrer: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::ReferralCode(code.clone()))
+            .expect("Unknown referral code");
+        assert!(
+            !Self::is_known_employer(&env, &employer),
+            "Only new employers can apply a referral code"
+        );
+        let referral_key = StorageKey::Referral(employer.clone());
+        assert!(
+            !env.storage().persistent().has(&referral_key),
+            "Referral already applied"
+        );
+
+        let expires_at = env.ledger().timestamp() + REFERRAL_PERIOD_SECONDS;
+        env.storage().persistent().set(
+            &referral_key,
+            &Referral {
+                referrer: referrer.clone(),
+                code,
+                expires_at,
+            },
+        );
+
+        env.events().publish(
+            ("referral_applied",),
+            ReferralAppliedEvent {
+                employer,
+                referrer,
+                expires_at,
+            },
+        );
+    }
+
+    /// Transfers all referral rewards accrued in `token` to the referrer.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `referrer` - The referrer (must authenticate)
+    /// * `token` - The token whose rewards are claimed
+    ///
+    /// # Returns
+    ///
+    /// The amount claimed
+    ///
+    /// # Events
+    ///
+    /// Emits `ReferralRewardsClaimed` on success.
+    pub fn claim_referral_rewards(env: Env, referrer: Address, token: Address) -> i128 {
+        referrer.require_auth();
+
+        let rewards_key = StorageKey::ReferralRewards(referrer.clone(), token.clone());
+        let amount: i128 = env.storage().persistent().get(&rewards_key).unwrap_or(0);
+        assert!(amount > 0, "No referral rewards to claim");
+        env.storage().persistent().remove(&rewards_key);
+
+        soroban_sdk::token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &referrer,
+            &amount,
+        );
+
+        env.events().publish(
+            ("referral_claimed",),
+            ReferralRewardsClaimedEvent {
+                referrer,
+                token,
+                amount,
+            },
+        );
+        amount
+    }
+
+    /// Gets the referrer owning `code`, if registered.
+    pub fn get_referral_code_owner(env: Env, code: Symbol) -> Option<Address> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::ReferralCode(code))
+    }
+
+    /// Gets the referral code registered by `referrer`, if any.
+    pub fn get_referrer_code(env: Env, referrer: Address) -> Option<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::ReferrerCode(referrer))
+    }
+
+    /// Gets the referral applied by `employer`, if any, including expired ones.
+    pub fn get_referral(env: Env, employer: Address) -> Option<Referral> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::Referral(employer))
+    }
+
+    /// Gets the referral rewards accrued to `referrer` in `token` and not yet
+    /// claimed.
+    pub fn get_referral_rewards(env: Env, referrer: Address, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::ReferralRewards(referrer, token))
+            .unwrap_or(0)
+    }
+
+    /// Starts streaming an agreement's funds to `to` at `rate_per_second`
+    /// until `end_time`.
+    ///
+    /// The recipient pulls accrued funds with `withdraw_streamed`. Funds stay in
+    /// the agreement balance until withdrawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `caller` - The caller address (must be manager, must authenticate)
+    /// * `agreement_id` - The unique identifier for the agreement
+    /// * `to` - The stream recipient
+    /// * `rate_per_second` - Amount accrued per second
+    /// * `end_time` - Timestamp at which accrual stops
+    ///
+    /// # Requirements
+    ///
+    /// * Caller must be the authorized manager contract address
+    /// * Rate must be positive and `end_time` in the future
+    /// * Agreement must have no open stream
+    /// * Agreement balance must cover the whole stream
+    ///
+    /// # Events
+    ///
+    /// Emits `StreamStarted` on success.
+    pub fn start_stream(
+        env: Env,
+        caller: Address,
+        agreement_id: u128,
+        to: Address,
+        rate_per_second: i128,
+        end_time: u64,
+    ) {
+        caller.require_auth();
+
+        let manager: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Manager)
+            .expect("Manager not set");
+        assert!(caller == manager, "Only manager can start streams");
+        Self::record_manager_activity(&env, agreement_id);
+
+        assert!(rate_per_second > 0, "Rate must be positive");
+        let now = env.ledger().timestamp();
+        assert!(end_time > now, "End time must be in the future");
+        let key = StorageKey::Stream(agreement_id);
+        assert!(
+            !env.storage().persistent().has(&key),
+            "Stream already active"
+        );
+
+        let total = rate_per_second
+            .checked_mul(i128::from(end_time - now))
+            .expect("Stream total overflow");
+        let balance: i128 = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::AgreementBalance(agreement_id))
+            .unwrap_or(0);
+        assert!(balance >= total, "Insufficient balance");
+
+        env.storage().persistent().set(
+            &key,
+            &Stream {
+                to: to.clone(),
+                rate_per_second,
+                start_time: now,
+                end_time,
+                withdrawn_until: now,
+            },
+        );
+
+        env.events().publish(
+            ("stream_started", agreement_id),
+            StreamStartedEvent {
+                agreement_id,
+                to,
+                rate_per_second,
+                end_time,
+            },
+        );
+    }
+
+    /// Pays the stream recipient everything accrued since their last
+    /// withdrawal. The stream is closed once it has ended and been paid out.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `agreement_id` - The unique identifier for the agreement
+    ///
+    /// # Returns
+    ///
+    /// The amount transferred to the recipient
+    ///
+    /// # Requirements
+    ///
+    /// * The stream recipient must authenticate
+    /// * Some amount must have accrued
+    ///
+    /// # Events
+    ///
+    /// Emits `StreamWithdrawn` on success.
+    pub fn withdraw_streamed(env: Env, agreement_id: u128) -> i128 {
+        let stream: Stream = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Stream(agreement_id))
+            .expect("Stream not found");
+        stream.to.require_auth();
+
+        let amount = Self::settle_stream(&env, agreement_id, stream);
+        assert!(amount > 0, "Nothing to withdraw");
+        amount
+    }
+
+    /// Stops an agreement's stream, paying the recipient what has accrued so
+    /// far. The unaccrued remainder stays in the agreement balance.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `caller` - The caller address (must be manager, must authenticate)
+    /// * `agreement_id` - The unique identifier for the agreement
+    ///
+    /// # Returns
+    ///
+    /// The accrued amount paid to the recipient
+    ///
+    /// # Events
+    ///
+    /// Emits `StreamStopped` on success.
+    pub fn stop_stream(env: Env, caller: Address, agreement_id: u128) -> i128 {
+        caller.require_auth();
+
+        let manager: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Manager)
+            .expect("Manager not set");
+        assert!(caller == manager, "Only manager can stop streams");
+        Self::record_manager_activity(&env, agreement_id);
+
+        let mut stream: Stream = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Stream(agreement_id))
+            .expect("Stream not found");
+        stream.end_time = stream.end_time.min(env.ledger().timestamp());
+        let paid_out = Self::settle_stream(&env, agreement_id, stream);
+
+        env.events().publish(
+            ("stream_stopped", agreement_id),
+            StreamStoppedEvent {
+                agreement_id,
+                paid_out,
+            },
+        );
+        paid_out
+    }
+
+    /// Gets the open stream of an agreement, if any.
+    pub fn get_stream(env: Env, agreement_id: u128) -> Option<Stream> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::Stream(agreement_id))
+    }
+
+    /// Gets the amount the stream recipient could withdraw right now.
+    pub fn get_streamable_amount(env: Env, agreement_id: u128) -> i128 {
+        env.storage()
+            .persistent()
+            .get::<_, Stream>(&StorageKey::Stream(agreement_id))
+            .map_or(0, |stream| Self::accrued(&env, agreement_id, &stream).1)
+    }
+
+    /// Sets the dispute window applied to cancellations signalled from now on.
+    ///
+    /// While the window is zero (the default), refunds need no prior
+    /// cancellation signal. Once it is set, `refund_remaining` requires a
+    /// signalled cancellation whose window has elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `admin` - The admin address (must authenticate)
+    /// * `seconds` - Length of the dispute window
+    ///
+    /// # Access Control
+    ///
+    /// Only the stored admin.
+    pub fn set_dispute_window(env: Env, admin: Address, seconds: u64) {
+        admin.require_auth();
+
+        let stored_admin: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Admin)
+            .expect("Admin not set");
+        assert!(admin == stored_admin, "Only admin can set dispute window");
+
+        env.storage()
+            .persistent()
+            .set(&StorageKey::DisputeWindow, &seconds);
+    }
+
+    /// Gets the configured dispute window in seconds (0 if unset).
+    pub fn get_dispute_window(env: Env) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::DisputeWindow)
+            .unwrap_or(0)
+    }
+
+    /// Signals that an agreement is being cancelled, starting its dispute window.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `caller` - The caller address (must be manager, must authenticate)
+    /// * `agreement_id` - The unique identifier for the agreement
+    /// * `employee` - The employee who may consent to or dispute the refund
+    ///
+    /// # Requirements
+    ///
+    /// * Caller must be the authorized manager contract address
+    /// * Agreement must have an assigned employer and no pending cancellation
+    ///
+    /// # Events
+    ///
+    /// Emits `CancellationSignaled` on success.
+    pub fn signal_cancellation(env: Env, caller: Address, agreement_id: u128, employee: Address) {
+        caller.require_auth();
+
+        let manager: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Manager)
+            .expect("Manager not set");
+        assert!(caller == manager, "Only manager can signal cancellation");
+        Self::record_manager_activity(&env, agreement_id);
+        assert!(
+            env.storage()
+                .persistent()
+                .has(&StorageKey::AgreementEmployer(agreement_id)),
+            "Employer not found"
+        );
+        let key = StorageKey::Cancellation(agreement_id);
+        assert!(
+            !env.storage().persistent().has(&key),
+            "Cancellation already signaled"
+        );
+
+        let now = env.ledger().timestamp();
+        let refundable_at = now.saturating_add(Self::get_dispute_window(env.clone()));
+        env.storage().persistent().set(
+            &key,
+            &Cancellation {
+                employee: employee.clone(),
+                signaled_at: now,
+                refundable_at,
+                employer_consented: false,
+                employee_consented: false,
+                disputed: false,
+            },
+        );
+
+        env.events().publish(
+            ("cancellation_signaled", agreement_id),
+            CancellationSignaledEvent {
+                agreement_id,
+                employee,
+                refundable_at,
+            },
+        );
+    }
+
+    /// Records the employer's or employee's consent to refund before the
+    /// dispute window ends. Once both have consented the refund is allowed
+    /// immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `party` - The agreement's employer or the cancellation's employee (must authenticate)
+    /// * `agreement_id` - The unique identifier for the agreement
+    ///
+    /// # Events
+    ///
+    /// Emits `RefundConsented` on success.
+    pub fn consent_to_refund(env: Env, party: Address, agreement_id: u128) {
+        party.require_auth();
+
+        let key = StorageKey::Cancellation(agreement_id);
+        let mut cancellation: Cancellation = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Cancellation not signaled");
+        let employer: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::AgreementEmployer(agreement_id))
+            .expect("Employer not found");
+
+        if party == employer {
+            cancellation.employer_consented = true;
+        } else if party == cancellation.employee {
+            cancellation.employee_consented = true;
+        } else {
+            panic!("Only agreement parties can consent");
+        }
+        env.storage().persistent().set(&key, &cancellation);
+
+        env.events().publish(
+            ("refund_consented", agreement_id),
+            RefundConsentedEvent {
+                agreement_id,
+                party,
+            },
+        );
+    }
+
+    /// Opens a dispute against a signalled cancellation, pausing the refund
+    /// until the manager resolves it.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `agreement_id` - The unique identifier for the agreement
+    ///
+    /// # Requirements
+    ///
+    /// * The cancellation's employee must authenticate
+    /// * The dispute window must still be open and no dispute already open
+    ///
+    /// # Events
+    ///
+    /// Emits `RefundDisputeOpened` on success.
+    pub fn open_refund_dispute(env: Env, agreement_id: u128) {
+        let key = StorageKey::Cancellation(agreement_id);
+        let mut cancellation: Cancellation = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Cancellation not signaled");
+        cancellation.employee.require_auth();
+
+        assert!(!cancellation.disputed, "Refund already disputed");
+        assert!(
+            env.ledger().timestamp() < cancellation.refundable_at,
+            "Dispute window has closed"
+        );
+
+        cancellation.disputed = true;
+        env.storage().persistent().set(&key, &cancellation);
+
+        env.events().publish(
+            ("refund_disputed", agreement_id),
+            RefundDisputeOpenedEvent {
+                agreement_id,
+                employee: cancellation.employee,
+            },
+        );
+    }
+
+    /// Resolves an open refund dispute.
+    ///
+    /// If `allow_refund` is true the refund may proceed immediately. Otherwise
+    /// the cancellation is withdrawn and the agreement stays funded; a new
+    /// cancellation must be signalled to refund it later.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `caller` - The caller address (must be manager, must authenticate)
+    /// * `agreement_id` - The unique identifier for the agreement
+    /// * `allow_refund` - Whether the dispute is decided in favour of the refund
+    ///
+    /// # Events
+    ///
+    /// Emits `RefundDisputeResolved` on success.
+    pub fn resolve_refund_dispute(
+        env: Env,
+        caller: Address,
+        agreement_id: u128,
+        allow_refund: bool,
+    ) {
+        caller.require_auth();
+
+        let manager: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Manager)
+            .expect("Manager not set");
+        assert!(caller == manager, "Only manager can resolve disputes");
+        Self::record_manager_activity(&env, agreement_id);
+
+        let key = StorageKey::Cancellation(agreement_id);
+        let mut cancellation: Cancellation = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("Cancellation not signaled");
+        assert!(cancellation.disputed, "Refund is not disputed");
+
+        if allow_refund {
+            cancellation.disputed = false;
+            cancellation.refundable_at = env.ledger().timestamp();
+            env.storage().persistent().set(&key, &cancellation);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+
+        env.events().publish(
+            ("refund_dispute_resolved", agreement_id),
+            RefundDisputeResolvedEvent {
+                agreement_id,
+                refund_allowed: allow_refund,
+            },
+        );
+    }
+
+    /// Gets the pending cancellation for an agreement, if any.
+    pub fn get_cancellation(env: Env, agreement_id: u128) -> Option<Cancellation> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::Cancellation(agreement_id))
+    }
+
+    /// Refunds remaining balance to the agreement's funders.
+    ///
+    /// Only the manager contract can call this function. This is typically used
+    /// when an agreement is cancelled and the grace period has expired.
+    ///
+    /// The balance is split in proportion to each funder's contribution; the
+    /// last funder receives any rounding remainder. An agreement funded only
+    /// by its employer refunds everything to the employer.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `caller` - The caller address (must be manager, must authenticate)
+    /// * `agreement_id` - The unique identifier for the agreement
+    ///
+    /// # Requirements
+    ///
+    /// * Caller must be the authorized manager contract address
+    /// * Agreement must have a positive balance remaining
+    /// * Agreement must have an assigned employer address
+    /// * If a cancellation was signalled, it must not be disputed and its
+    ///   dispute window must have elapsed unless both parties consented
+    /// * If a dispute window is configured, a cancellation must have been signalled
+    /// * Agreement must have no open stream
+    ///
+    /// # Access Control
+    ///
+    /// STRICT: Only the address stored as `Manager` can authorize refunds.
+    ///
+    /// # Invariants
+    ///
+    /// - After a successful refund, the `AgreementBalance` for that `agreement_id` is exactly zero.
+    /// - Funds are only returned to addresses that funded the agreement, and
+    ///   contributions are reset so a later deposit starts a fresh split.
+    ///
+    /// # Invariant
+    ///
+    /// Refunded agreement balance is zeroed before transfer so nested calls
+    /// cannot observe and drain the old balance twice.
+    ///
+    /// # Events
+    ///
+    /// Emits one `Refunded` event per funder paid.
+    pub fn refund_remaining(env: Env, caller: Address, agreement_id: u128) {
+        caller.require_auth();
+
+        // Check manager authorization
+        let manager: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Manager)
+            .expect("Manager not set");
+        assert!(caller == manager, "Only manager can refund funds");
+        Self::record_manager_activity(&env, agreement_id);
+        assert!(
+            !env.storage()
+                .persistent()
+                .has(&StorageKey::Stream(agreement_id)),
+            "Stream still active"
+        );
+
+        // Enforce the dispute window of a signalled cancellation
+        let cancellation_key = StorageKey::Cancellation(agreement_id);
+        match env
+            .storage()
+            .persistent()
+            .get::<_, Cancellation>(&cancellation_key)
+        {
+            Some(cancellation) => {
+                assert!(!cancellation.disputed, "Refund is under dispute");
+                assert!(
+                    env.ledger().timestamp() >= cancellation.refundable_at
+                        || (cancellation.employer_consented && cancellation.employee_consented),
+                    "Dispute window still open"
+                );
+            }
+            None => assert!(
+                Self::get_dispute_window(env.clone()) == 0,
+                "Cancellation not signaled"
+            ),
+        }
+
+        // Get balance
+        let balance: i128 = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::AgreementBalance(agreement_id))
+            .unwrap_or(0);
+        assert!(balance > 0, "No balance to refund");
+
+        // Funders predating receipt tracking are attributed to the employer
+        let employer: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::AgreementEmployer(agreement_id))
+            .expect("Employer not found");
+        let funders_key = StorageKey::AgreementFunders(agreement_id);
+        let funders: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&funders_key)
+            .unwrap_or(Vec::new(&env));
+        let mut contributions: Vec<(Address, i128)> = Vec::new(&env);
+        let mut total_contributed: i128 = 0;
+        for funder in funders.iter() {
+            let key = StorageKey::FunderContribution(agreement_id, funder.clone());
+            let contribution: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+            env.storage().persistent().remove(&key);
+            total_contributed = total_contributed
+                .checked_add(contribution)
+                .expect("Contribution overflow");
+            contributions.push_back((funder, contribution));
+        }
+        if total_contributed == 0 {
+            contributions = vec![&env, (employer, 1)];
+            total_contributed = 1;
+        }
+
+        // Get token address
+        let token: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Token)
+            .expect("Token not set");
+
+        // Zero the balance and reset contributions before any transfer
+        env.storage()
+            .persistent()
+            .set(&StorageKey::AgreementBalance(agreement_id), &0i128);
+        env.storage().persistent().remove(&funders_key);
+        env.storage().persistent().remove(&cancellation_key);
+
+        let token_client = soroban_sdk::token::Client::new(&env, &token);
+        let last = contributions.len() - 1;
+        let mut refunded: i128 = 0;
+        for (i, (funder, contribution)) in contributions.iter().enumerate() {
+            let amount = if i as u32 == last {
+                balance - refunded
+            } else {
+                balance
+                    .checked_mul(contribution)
+                    .expect("Refund share overflow")
+                    / total_contributed
+            };
+            if amount == 0 {
+                continue;
+            }
+            refunded += amount;
+            token_client.transfer(&env.current_contract_address(), &funder, &amount);
+
+            // Emit event
+            env.events().publish(
+                ("refunded", agreement_id),
+                RefundedEvent {
+                    agreement_id,
+                    to: funder.clone(),
+                    amount,
+                },
+            );
+            Self::notify(
+                &env,
+                event_types::ESCROW_REFUNDED,
+                agreement_id,
+                &funder,
+                amount,
+            );
+        }
+    }
+
+    /// Gets a page of an agreement's funding receipts in deposit order.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `agreement_id` - The unique identifier for the agreement
+    /// * `start` - Index of the first receipt to return
+    /// * `limit` - Maximum number of receipts, capped at `MAX_HISTORY_PAGE`
+    ///
+    /// # Returns
+    ///
+    /// The receipts from `start`, empty if `start` is past the last one
+    pub fn get_funding_history(
+        env: Env,
+        agreement_id: u128,
+        start: u32,
+        limit: u32,
+    ) -> Vec<FundingReceipt> {
+        let count = Self::get_funding_count(env.clone(), agreement_id);
+        let end = start.saturating_add(limit.min(MAX_HISTORY_PAGE)).min(count);
+        let mut receipts = Vec::new(&env);
+        for index in start..end {
+            if let Some(receipt) = env
+                .storage()
+                .persistent()
+                .get(&StorageKey::FundingReceipt(agreement_id, index))
+            {
+                receipts.push_back(receipt);
+            }
+        }
+        receipts
+    }
+
+    /// Gets the number of funding receipts recorded for an agreement.
+    pub fn get_funding_count(env: Env, agreement_id: u128) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::FundingCount(agreement_id))
+            .unwrap_or(0)
+    }
+
+    /// Gets how much `funder` has contributed to the agreement's current
+    /// balance, i.e. since its last refund.
+    pub fn get_funder_contribution(env: Env, agreement_id: u128, funder: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::FunderContribution(agreement_id, funder))
+            .unwrap_or(0)
+    }
+
+    /// Gets the addresses that contributed to the agreement's current balance.
+    pub fn get_agreement_funders(env: Env, agreement_id: u128) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::AgreementFunders(agreement_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Gets the current balance for an agreement.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `agreement_id` - The unique identifier for the agreement
+    ///
+    /// # Returns
+    ///
+    /// The current balance (0 if no balance exists)
+    ///
+    /// # Access Control
+    /// Requires caller authentication
+    pub fn get_agreement_balance(env: Env, agreement_id: u128) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::AgreementBalance(agreement_id))
+            .unwrap_or(0)
+    }
+
+    /// Gets the employer address for an agreement.
+    ///
+    /// # Arguments
+    ///
+    /// * `env` - The Soroban environment
+    /// * `agreement_id` - The unique identifier for the agreement
+    ///
+    /// # Returns
+    ///
+    /// The employer address if found, None otherwise
+    ///
+    /// # Access Control
+    /// Requires caller authentication
     pub fn get_agreement_employer(env: Env, agreement_id: u128) -> Option<Address> {
         env.storage()
             .persistent()
             .get(&StorageKey::AgreementEmployer(agreement_id))
     }
+
+    fn is_known_employer(env: &Env, employer: &Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::KnownEmployer(employer.clone()))
+            .unwrap_or(false)
+    }
+
+    /// Credits the referrer's share of a release fee if the agreement's
+    /// employer has an unexpired referral, returning the amount credited.
+    fn accrue_referral_reward(env: &Env, agreement_id: u128, token: &Address, fee: i128) -> i128 {
+        let Some(employer) = env
+            .storage()
+            .persistent()
+            .get::<_, Address>(&StorageKey::AgreementEmployer(agreement_id))
+        else {
+            return 0;
+        };
+        let Some(referral) = env
+            .storage()
+            .persistent()
+            .get::<_, Referral>(&StorageKey::Referral(employer))
+        else {
+            return 0;
+        };
+        if env.ledger().timestamp() >= referral.expires_at {
+            return 0;
+        }
+        let reward = fee * i128::from(REFERRAL_SHARE_BPS) / BPS_DENOMINATOR;
+        if reward == 0 {
+            return 0;
+        }
+
+        let rewards_key = StorageKey::ReferralRewards(referral.referrer.clone(), token.clone());
+        let accrued: i128 = env.storage().persistent().get(&rewards_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&rewards_key, &(accrued + reward));
+        env.events().publish(
+            ("referral_accrued", agreement_id),
+            ReferralAccruedEvent {
+                agreement_id,
+                referrer: referral.referrer,
+                token: token.clone(),
+                amount: reward,
+            },
+        );
+        reward
+    }
+
+    /// Notifies the linked webhook registry, if any, of `event_type` for the
+    /// agreement's employer, with `subject` as the funder or recipient. A
+    /// failing registry never blocks the caller.
+    fn notify(env: &Env, event_type: &str, agreement_id: u128, subject: &Address, amount: i128) {
+        let Some(registry) = env
+            .storage()
+            .persistent()
+            .get::<_, Address>(&StorageKey::WebhookRegistry)
+        else {
+            return;
+        };
+        let Some(employer) = env
+            .storage()
+            .persistent()
+            .get::<_, Address>(&StorageKey::AgreementEmployer(agreement_id))
+        else {
+            return;
+        };
+        let notification = WebhookNotification {
+            owner: employer,
+            event_type: String::from_str(env, event_type),
+            subject: subject.clone(),
+            reference: agreement_id,
+            amount,
+        };
+        let _ = WebhookRegistryClient::new(env, &registry)
+            .try_notify(&env.current_contract_address(), &notification);
+    }
+
+    fn record_manager_activity(env: &Env, agreement_id: u128) {
+        env.storage().persistent().set(
+            &StorageKey::LastManagerActivity(agreement_id),
+            &env.ledger().timestamp(),
+        );
+    }
+
+    /// Returns the timestamp accrual has reached and the amount accrued since
+    /// `withdrawn_until`, capped at the agreement balance.
+    fn accrued(env: &Env, agreement_id: u128, stream: &Stream) -> (u64, i128) {
+        let until = env.ledger().timestamp().min(stream.end_time);
+        let elapsed = until.saturating_sub(stream.withdrawn_until);
+        let balance: i128 = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::AgreementBalance(agreement_id))
+            .unwrap_or(0);
+        let amount = stream
+            .rate_per_second
+            .saturating_mul(i128::from(elapsed))
+            .min(balance);
+        (until, amount)
+    }
+
+    /// Pays out the stream's accrued amount and closes it once it has ended.
+    fn settle_stream(env: &Env, agreement_id: u128, mut stream: Stream) -> i128 {
+        let (until, amount) = Self::accrued(env, agreement_id, &stream);
+        let key = StorageKey::Stream(agreement_id);
+        stream.withdrawn_until = until;
+        if until >= stream.end_time {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &stream);
+        }
+        if amount == 0 {
+            return 0;
+        }
+
+        let balance_key = StorageKey::AgreementBalance(agreement_id);
+        let balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&balance_key, &(balance - amount));
+
+        let token: Address = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::Token)
+            .expect("Token not set");
+        soroban_sdk::token::Client::new(env, &token).transfer(
+            &env.current_contract_address(),
+            &stream.to,
+            &amount,
+        );
+
+        env.events().publish(
+            ("stream_withdrawn", agreement_id),
+            StreamWithdrawnEvent {
+                agreement_id,
+                to: stream.to,
+                amount,
+            },
+        );
+        amount
+    }
+
+    fn record_funding(env: &Env, agreement_id: u128, funder: &Address, amount: i128) {
+        let count_key = StorageKey::FundingCount(agreement_id);
+        let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        env.storage().persistent().set(
+            &StorageKey::FundingReceipt(agreement_id, count),
+            &FundingReceipt {
+                funder: funder.clone(),
+                amount,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        env.storage().persistent().set(&count_key, &(count + 1));
+
+        let funders_key = StorageKey::AgreementFunders(agreement_id);
+        let mut funders: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&funders_key)
+            .unwrap_or(Vec::new(env));
+        if !funders.contains(funder) {
+            assert!(funders.len() < MAX_FUNDERS, "Too many funders");
+            funders.push_back(funder.clone());
+            env.storage().persistent().set(&funders_key, &funders);
+        }
+
+        let contribution_key = StorageKey::FunderContribution(agreement_id, funder.clone());
+        let contribution: i128 = env
+            .storage()
+            .persistent()
+            .get(&contribution_key)
+            .unwrap_or(0);
+        env.storage().persistent().set(
+            &contribution_key,
+            &contribution
+                .checked_add(amount)
+                .expect("Contribution overflow"),
+        );
+    }
 }
 
 #[cfg(test)]
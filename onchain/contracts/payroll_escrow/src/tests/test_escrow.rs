@@ -1,7 +1,15 @@
-use crate::{ManagerUpdatedEvent, PayrollEscrowContract, PayrollEscrowContractClient};
+use crate::{
+    FundingReceipt, ManagerUpdatedEvent, PayrollEscrowContract, PayrollEscrowContractClient,
+    ReleasedEvent,
+};
 use soroban_sdk::{
-    testutils::{Address as _, Events},
-    vec, Address, Env, IntoVal,
+    symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, BytesN, Env, IntoVal, String, TryFromVal,
+};
+use webhook_contract::{
+    NotificationEvent, RetryConfig, SecurityConfig, SignatureMethod, WebhookRegistration,
+    WebhookRegistry, WebhookRegistryClient,
 };
 
 fn create_token_contract<'a>(e: &Env, admin: &Address) -> soroban_sdk::token::Client<'a> {
@@ -977,3 +985,835 @@ fn test_release_after_refund_fails() {
     client.refund_remaining(&manager, &1);
     client.release(&manager, &1, &recipient, &100);
 }
+
+// ============================================
+// Refund dispute window
+// ============================================
+
+const DISPUTE_WINDOW: u64 = 7 * 86_400;
+
+struct DisputeSetup<'a> {
+    env: Env,
+    client: PayrollEscrowContractClient<'a>,
+    token: soroban_sdk::token::Client<'a>,
+    manager: Address,
+    employer: Address,
+    employee: Address,
+}
+
+/// Funds agreement 1 with 500 and signals its cancellation under a
+/// `DISPUTE_WINDOW` dispute window.
+fn setup_signaled_cancellation<'a>() -> DisputeSetup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    client.set_dispute_window(&admin, &DISPUTE_WINDOW);
+
+    soroban_sdk::token::StellarAssetClient::new(&env, &token.address).mint(&employer, &1000);
+    client.fund_agreement(&employer, &1, &employer, &500);
+    client.signal_cancellation(&manager, &1, &employee);
+
+    DisputeSetup {
+        env,
+        client,
+        token,
+        manager,
+        employer,
+        employee,
+    }
+}
+
+fn advance_time(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|l| l.timestamp += seconds);
+}
+
+#[test]
+fn test_refund_allowed_after_dispute_window() {
+    let s = setup_signaled_cancellation();
+    let cancellation = s.client.get_cancellation(&1).unwrap();
+    assert_eq!(cancellation.employee, s.employee);
+    assert_eq!(cancellation.refundable_at, DISPUTE_WINDOW);
+
+    advance_time(&s.env, DISPUTE_WINDOW);
+    s.client.refund_remaining(&s.manager, &1);
+
+    assert_eq!(s.token.balance(&s.employer), 1000);
+    assert_eq!(s.client.get_cancellation(&1), None);
+}
+
+#[test]
+#[should_panic(expected = "Dispute window still open")]
+fn test_refund_blocked_during_dispute_window() {
+    let s = setup_signaled_cancellation();
+    advance_time(&s.env, DISPUTE_WINDOW - 1);
+    s.client.refund_remaining(&s.manager, &1);
+}
+
+#[test]
+#[should_panic(expected = "Cancellation not signaled")]
+fn test_refund_requires_signal_when_window_configured() {
+    let s = setup_signaled_cancellation();
+    s.client.fund_agreement(&s.employer, &2, &s.employer, &100);
+    s.client.refund_remaining(&s.manager, &2);
+}
+
+#[test]
+fn test_refund_early_with_both_parties_consent() {
+    let s = setup_signaled_cancellation();
+    s.client.consent_to_refund(&s.employer, &1);
+    assert!(s.client.try_refund_remaining(&s.manager, &1).is_err());
+
+    s.client.consent_to_refund(&s.employee, &1);
+    s.client.refund_remaining(&s.manager, &1);
+    assert_eq!(s.client.get_agreement_balance(&1), 0);
+}
+
+#[test]
+#[should_panic(expected = "Only agreement parties can consent")]
+fn test_consent_by_outsider_fails() {
+    let s = setup_signaled_cancellation();
+    s.client.consent_to_refund(&Address::generate(&s.env), &1);
+}
+
+#[test]
+#[should_panic(expected = "Refund is under dispute")]
+fn test_open_dispute_pauses_refund_past_window() {
+    let s = setup_signaled_cancellation();
+    s.client.open_refund_dispute(&1);
+    assert!(s.client.get_cancellation(&1).unwrap().disputed);
+
+    advance_time(&s.env, DISPUTE_WINDOW);
+    s.client.refund_remaining(&s.manager, &1);
+}
+
+#[test]
+#[should_panic(expected = "Dispute window has closed")]
+fn test_open_dispute_after_window_fails() {
+    let s = setup_signaled_cancellation();
+    advance_time(&s.env, DISPUTE_WINDOW);
+    s.client.open_refund_dispute(&1);
+}
+
+#[test]
+fn test_resolve_dispute_allowing_refund() {
+    let s = setup_signaled_cancellation();
+    s.client.open_refund_dispute(&1);
+    s.client.resolve_refund_dispute(&s.manager, &1, &true);
+
+    s.client.refund_remaining(&s.manager, &1);
+    assert_eq!(s.token.balance(&s.employer), 1000);
+}
+
+#[test]
+fn test_resolve_dispute_withdrawing_cancellation() {
+    let s = setup_signaled_cancellation();
+    s.client.open_refund_dispute(&1);
+    s.client.resolve_refund_dispute(&s.manager, &1, &false);
+
+    assert_eq!(s.client.get_cancellation(&1), None);
+    advance_time(&s.env, DISPUTE_WINDOW);
+    assert!(s.client.try_refund_remaining(&s.manager, &1).is_err());
+    assert_eq!(s.client.get_agreement_balance(&1), 500);
+}
+
+#[test]
+#[should_panic(expected = "Only manager can resolve disputes")]
+fn test_resolve_dispute_non_manager_fails() {
+    let s = setup_signaled_cancellation();
+    s.client.open_refund_dispute(&1);
+    s.client.resolve_refund_dispute(&s.employer, &1, &true);
+}
+
+// ============================================
+// Funding receipts
+// ============================================
+
+#[test]
+fn test_funding_history_records_each_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let parent = Address::generate(&env);
+    let subsidiary = Address::generate(&env);
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    let minter = soroban_sdk::token::StellarAssetClient::new(&env, &token.address);
+    minter.mint(&parent, &1000);
+    minter.mint(&subsidiary, &1000);
+
+    env.ledger().set_timestamp(100);
+    client.fund_agreement(&parent, &1, &parent, &300);
+    env.ledger().set_timestamp(200);
+    client.fund_agreement(&subsidiary, &1, &parent, &100);
+    client.fund_agreement(&parent, &1, &parent, &200);
+
+    assert_eq!(client.get_funding_count(&1), 3);
+    assert_eq!(
+        client.get_funding_history(&1, &1, &10),
+        vec![
+            &env,
+            FundingReceipt {
+                funder: subsidiary.clone(),
+                amount: 100,
+                timestamp: 200,
+            },
+            FundingReceipt {
+                funder: parent.clone(),
+                amount: 200,
+                timestamp: 200,
+            },
+        ]
+    );
+    assert_eq!(client.get_funding_history(&1, &3, &10).len(), 0);
+    assert_eq!(client.get_funder_contribution(&1, &parent), 500);
+    assert_eq!(client.get_funder_contribution(&1, &subsidiary), 100);
+    assert_eq!(
+        client.get_agreement_funders(&1),
+        vec![&env, parent.clone(), subsidiary.clone()]
+    );
+}
+
+#[test]
+fn test_refund_split_by_funder_contribution() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let parent = Address::generate(&env);
+    let subsidiary = Address::generate(&env);
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    let minter = soroban_sdk::token::StellarAssetClient::new(&env, &token.address);
+    minter.mint(&parent, &1000);
+    minter.mint(&subsidiary, &1000);
+
+    client.fund_agreement(&parent, &1, &parent, &600);
+    client.fund_agreement(&subsidiary, &1, &parent, &300);
+    // A third of the escrow is paid out; the rest is refunded 2:1
+    client.release(&manager, &1, &Address::generate(&env), &300);
+    client.refund_remaining(&manager, &1);
+
+    assert_eq!(token.balance(&parent), 400 + 400);
+    assert_eq!(token.balance(&subsidiary), 700 + 200);
+    assert_eq!(client.get_agreement_funders(&1).len(), 0);
+    assert_eq!(client.get_funder_contribution(&1, &parent), 0);
+    // Receipts remain as history
+    assert_eq!(client.get_funding_count(&1), 2);
+}
+
+#[test]
+fn test_refund_rounding_remainder_goes_to_last_funder() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let funders = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    let minter = soroban_sdk::token::StellarAssetClient::new(&env, &token.address);
+    for funder in funders.iter() {
+        minter.mint(funder, &10);
+        client.fund_agreement(funder, &1, &funders[0], &10);
+    }
+    client.release(&manager, &1, &Address::generate(&env), &10);
+    client.refund_remaining(&manager, &1);
+
+    assert_eq!(token.balance(&funders[0]), 6);
+    assert_eq!(token.balance(&funders[1]), 6);
+    assert_eq!(token.balance(&funders[2]), 8);
+}
+
+#[test]
+#[should_panic(expected = "Too many funders")]
+fn test_fund_beyond_max_funders_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let employer = Address::generate(&env);
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    let minter = soroban_sdk::token::StellarAssetClient::new(&env, &token.address);
+    for _ in 0..=crate::MAX_FUNDERS {
+        let funder = Address::generate(&env);
+        minter.mint(&funder, &1);
+        client.fund_agreement(&funder, &1, &employer, &1);
+    }
+}
+
+// ============================================
+// Streaming release
+// ============================================
+
+struct StreamSetup<'a> {
+    env: Env,
+    client: PayrollEscrowContractClient<'a>,
+    token: soroban_sdk::token::Client<'a>,
+    manager: Address,
+    employer: Address,
+    recipient: Address,
+}
+
+/// Funds agreement 1 with 1000 and streams 10 per second to a recipient for
+/// 60 seconds, starting at t = 1000.
+fn setup_stream<'a>() -> StreamSetup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1000);
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let employer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token.address).mint(&employer, &1000);
+    client.fund_agreement(&employer, &1, &employer, &1000);
+    client.start_stream(&manager, &1, &recipient, &10, &1060);
+
+    StreamSetup {
+        env,
+        client,
+        token,
+        manager,
+        employer,
+        recipient,
+    }
+}
+
+#[test]
+fn test_withdraw_streamed_pays_accrued_amount() {
+    let s = setup_stream();
+    advance_time(&s.env, 15);
+    assert_eq!(s.client.get_streamable_amount(&1), 150);
+    assert_eq!(s.client.withdraw_streamed(&1), 150);
+    assert_eq!(s.token.balance(&s.recipient), 150);
+    assert_eq!(s.client.get_agreement_balance(&1), 850);
+
+    // Accrual stops at end_time and the stream closes once paid out
+    advance_time(&s.env, 100);
+    assert_eq!(s.client.withdraw_streamed(&1), 450);
+    assert_eq!(s.token.balance(&s.recipient), 600);
+    assert_eq!(s.client.get_stream(&1), None);
+}
+
+#[test]
+#[should_panic(expected = "Nothing to withdraw")]
+fn test_withdraw_streamed_twice_in_same_ledger_fails() {
+    let s = setup_stream();
+    advance_time(&s.env, 5);
+    s.client.withdraw_streamed(&1);
+    s.client.withdraw_streamed(&1);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient balance")]
+fn test_start_stream_exceeding_balance_fails() {
+    let s = setup_stream();
+    s.client.stop_stream(&s.manager, &1);
+    s.client
+        .start_stream(&s.manager, &1, &s.recipient, &100, &1100);
+}
+
+#[test]
+#[should_panic(expected = "Only manager can start streams")]
+fn test_start_stream_non_manager_fails() {
+    let s = setup_stream();
+    s.client
+        .start_stream(&s.employer, &2, &s.recipient, &1, &2000);
+}
+
+#[test]
+fn test_stop_stream_pays_accrued_and_frees_remainder() {
+    let s = setup_stream();
+    advance_time(&s.env, 20);
+    assert_eq!(s.client.stop_stream(&s.manager, &1), 200);
+    assert_eq!(s.token.balance(&s.recipient), 200);
+    assert_eq!(s.client.get_stream(&1), None);
+
+    s.client.refund_remaining(&s.manager, &1);
+    assert_eq!(s.token.balance(&s.employer), 800);
+}
+
+#[test]
+#[should_panic(expected = "Stream still active")]
+fn test_refund_blocked_while_stream_open() {
+    let s = setup_stream();
+    s.client.refund_remaining(&s.manager, &1);
+}
+
+// ============================================
+// Protocol fee
+// ============================================
+
+#[test]
+fn test_release_withholds_fee_after_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token.address).mint(&employer, &1000);
+    client.fund_agreement(&employer, &1, &employer, &1000);
+
+    client.propose_fee_change(&admin, &250);
+    assert!(client.try_apply_fee_change(&admin).is_err());
+    // Releases before the change is applied are fee-free
+    client.release(&manager, &1, &employee, &100);
+    assert_eq!(token.balance(&employee), 100);
+
+    advance_time(&env, crate::FEE_TIMELOCK_SECONDS);
+    client.apply_fee_change(&admin);
+    assert_eq!(client.get_fee_bps(), 250);
+    assert_eq!(client.get_pending_fee(), None);
+
+    client.release(&manager, &1, &employee, &400);
+    assert_eq!(token.balance(&employee), 100 + 390);
+    assert_eq!(client.get_agreement_balance(&1), 500);
+    assert_eq!(client.get_accrued_fees(&token.address), 10);
+
+    assert_eq!(client.withdraw_fees(&admin, &token.address, &treasury), 10);
+    assert_eq!(token.balance(&treasury), 10);
+    assert_eq!(client.get_accrued_fees(&token.address), 0);
+}
+
+#[test]
+fn test_released_event_reports_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token.address).mint(&employer, &1000);
+    client.fund_agreement(&employer, &1, &employer, &1000);
+    client.propose_fee_change(&admin, &crate::MAX_FEE_BPS);
+    advance_time(&env, crate::FEE_TIMELOCK_SECONDS);
+    client.apply_fee_change(&admin);
+
+    client.release(&manager, &1, &employee, &200);
+
+    let (_, _, data) = env.events().all().last().unwrap();
+    let event: ReleasedEvent = data.into_val(&env);
+    assert_eq!(event.amount, 190);
+    assert_eq!(event.fee, 10);
+}
+
+#[test]
+#[should_panic(expected = "Fee exceeds maximum")]
+fn test_propose_fee_above_cap_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &Address::generate(&env));
+
+    client.propose_fee_change(&admin, &(crate::MAX_FEE_BPS + 1));
+}
+
+#[test]
+#[should_panic(expected = "Only admin can change fees")]
+fn test_propose_fee_non_admin_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+
+    client.propose_fee_change(&manager, &100);
+}
+
+// ============================================
+// Stalled manager fallback
+// ============================================
+
+const STALL_PERIOD: u64 = 30 * 86_400;
+
+struct StallSetup<'a> {
+    env: Env,
+    client: PayrollEscrowContractClient<'a>,
+    token: soroban_sdk::token::Client<'a>,
+    admin: Address,
+    manager: Address,
+    employer: Address,
+    employee: Address,
+}
+
+/// Funds agreement 1 with 1000 and registers a 40% fallback for an employee
+/// under a `STALL_PERIOD` stall period.
+fn setup_stall_fallback<'a>() -> StallSetup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    client.set_stall_period(&admin, &STALL_PERIOD);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token.address).mint(&employer, &1000);
+    client.fund_agreement(&employer, &1, &employer, &1000);
+    client.set_stall_fallback(&manager, &1, &employee, &4_000);
+
+    StallSetup {
+        env,
+        client,
+        token,
+        admin,
+        manager,
+        employer,
+        employee,
+    }
+}
+
+#[test]
+fn test_claim_stalled_pays_fallback_share_once() {
+    let s = setup_stall_fallback();
+    advance_time(&s.env, STALL_PERIOD);
+
+    assert_eq!(s.client.claim_stalled(&1), 400);
+    assert_eq!(s.token.balance(&s.employee), 400);
+    assert_eq!(s.client.get_agreement_balance(&1), 600);
+    assert_eq!(s.client.get_stall_fallback(&1), None);
+    assert!(s.client.try_claim_stalled(&1).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Manager is not stalled")]
+fn test_manager_activity_resets_stall_clock() {
+    let s = setup_stall_fallback();
+    advance_time(&s.env, STALL_PERIOD - 1);
+    s.client
+        .release(&s.manager, &1, &Address::generate(&s.env), &100);
+    assert_eq!(
+        s.client.get_last_manager_activity(&1),
+        Some(STALL_PERIOD - 1)
+    );
+
+    advance_time(&s.env, 1);
+    s.client.claim_stalled(&1);
+}
+
+#[test]
+#[should_panic(expected = "Stall claims disabled")]
+fn test_claim_stalled_disabled_by_zero_period() {
+    let s = setup_stall_fallback();
+    s.client.set_stall_period(&s.admin, &0);
+    advance_time(&s.env, STALL_PERIOD);
+    s.client.claim_stalled(&1);
+}
+
+#[test]
+#[should_panic(expected = "Only manager can set stall fallback")]
+fn test_set_stall_fallback_non_manager_fails() {
+    let s = setup_stall_fallback();
+    s.client
+        .set_stall_fallback(&s.employer, &1, &s.employee, &10_000);
+}
+
+// ============================================
+// Referrals
+// ============================================
+
+struct ReferralSetup<'a> {
+    env: Env,
+    client: PayrollEscrowContractClient<'a>,
+    token: soroban_sdk::token::Client<'a>,
+    manager: Address,
+    referrer: Address,
+    employer: Address,
+}
+
+/// Sets a 5% fee, registers code `REF1` for the employer of agreement 1 and
+/// has a new employer apply it.
+fn setup_referral<'a>() -> ReferralSetup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let referrer = Address::generate(&env);
+    let employer = Address::generate(&env);
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    client.propose_fee_change(&admin, &crate::MAX_FEE_BPS);
+    advance_time(&env, crate::FEE_TIMELOCK_SECONDS);
+    client.apply_fee_change(&admin);
+
+    let minter = soroban_sdk::token::StellarAssetClient::new(&env, &token.address);
+    minter.mint(&referrer, &1000);
+    minter.mint(&employer, &10_000);
+    client.fund_agreement(&referrer, &1, &referrer, &1000);
+    client.register_referral_code(&referrer, &symbol_short!("REF1"));
+    client.apply_referral_code(&employer, &symbol_short!("REF1"));
+    client.fund_agreement(&employer, &2, &employer, &10_000);
+
+    ReferralSetup {
+        env,
+        client,
+        token,
+        manager,
+        referrer,
+        employer,
+    }
+}
+
+#[test]
+fn test_referrer_earns_share_of_referred_fees() {
+    let s = setup_referral();
+    let referral = s.client.get_referral(&s.employer).unwrap();
+    assert_eq!(referral.referrer, s.referrer);
+    assert_eq!(
+        referral.expires_at,
+        s.env.ledger().timestamp() + crate::REFERRAL_PERIOD_SECONDS
+    );
+
+    // 5% fee of 4000 is 200, of which 10% goes to the referrer
+    s.client
+        .release(&s.manager, &2, &Address::generate(&s.env), &4000);
+    assert_eq!(
+        s.client.get_referral_rewards(&s.referrer, &s.token.address),
+        20
+    );
+    assert_eq!(s.client.get_accrued_fees(&s.token.address), 180);
+
+    // The referrer's own agreement earns nothing
+    s.client
+        .release(&s.manager, &1, &Address::generate(&s.env), &1000);
+    assert_eq!(
+        s.client.get_referral_rewards(&s.referrer, &s.token.address),
+        20
+    );
+
+    assert_eq!(
+        s.client
+            .claim_referral_rewards(&s.referrer, &s.token.address),
+        20
+    );
+    assert_eq!(s.token.balance(&s.referrer), 20);
+    assert_eq!(
+        s.client.get_referral_rewards(&s.referrer, &s.token.address),
+        0
+    );
+}
+
+#[test]
+fn test_referral_rewards_stop_after_period() {
+    let s = setup_referral();
+    advance_time(&s.env, crate::REFERRAL_PERIOD_SECONDS);
+    s.client
+        .release(&s.manager, &2, &Address::generate(&s.env), &4000);
+    assert_eq!(
+        s.client.get_referral_rewards(&s.referrer, &s.token.address),
+        0
+    );
+    assert_eq!(s.client.get_accrued_fees(&s.token.address), 200);
+}
+
+#[test]
+#[should_panic(expected = "Only existing employers can register referral codes")]
+fn test_register_referral_code_requires_funded_agreement() {
+    let s = setup_referral();
+    s.client
+        .register_referral_code(&Address::generate(&s.env), &symbol_short!("REF2"));
+}
+
+#[test]
+#[should_panic(expected = "Referral code already registered")]
+fn test_register_duplicate_referral_code_fails() {
+    let s = setup_referral();
+    s.client
+        .register_referral_code(&s.employer, &symbol_short!("REF1"));
+}
+
+#[test]
+#[should_panic(expected = "Only new employers can apply a referral code")]
+fn test_existing_employer_cannot_apply_referral_code() {
+    let s = setup_referral();
+    s.client
+        .register_referral_code(&s.employer, &symbol_short!("REF2"));
+    s.client
+        .apply_referral_code(&s.referrer, &symbol_short!("REF2"));
+}
+
+#[test]
+#[should_panic(expected = "No referral rewards to claim")]
+fn test_claim_without_referral_rewards_fails() {
+    let s = setup_referral();
+    s.client
+        .claim_referral_rewards(&s.referrer, &s.token.address);
+}
+
+#[test]
+fn test_escrow_lifecycle_notifies_the_webhook_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+
+    let client = create_payroll_escrow_contract(&env);
+    client.initialize(&admin, &token.address, &manager);
+    soroban_sdk::token::StellarAssetClient::new(&env, &token.address).mint(&employer, &1000);
+    client.fund_agreement(&employer, &1, &employer, &500);
+
+    let registry = WebhookRegistryClient::new(&env, &env.register(WebhookRegistry, ()));
+    registry.initialize(&admin);
+    registry.register_webhook(
+        &employer,
+        &WebhookRegistration {
+            name: String::from_str(&env, "escrow"),
+            description: String::from_str(&env, ""),
+            url: String::from_str(&env, "https://hooks.example.com"),
+            events: vec![&env, String::from_str(&env, "*")],
+            secret_hash: BytesN::from_array(&env, &[1; 32]),
+            retry_config: RetryConfig {
+                max_retries: 3,
+                retry_delay: 5,
+                exponential_backoff: true,
+                max_delay: 60,
+            },
+            security_config: SecurityConfig {
+                signature_method: SignatureMethod::HmacSha256,
+                rate_limit_per_minute: 60,
+                require_tls: true,
+            },
+        },
+    );
+    client.set_webhook_registry(&admin, &Some(registry.address.clone()));
+    let notifications = |env: &Env| {
+        env.events()
+            .all()
+            .iter()
+            .filter(|(contract, _, _)| *contract == registry.address)
+            .count()
+    };
+
+    // The escrow is not an allowed notifier yet: the release still succeeds
+    client.release(&manager, &1, &employee, &100);
+    assert_eq!(notifications(&env), 0);
+    assert_eq!(token.balance(&employee), 100);
+
+    registry.set_notifier(&admin, &client.address, &true);
+    client.release(&manager, &1, &employee, &200);
+    let (_, topics, data) = env
+        .events()
+        .all()
+        .iter()
+        .find(|(contract, _, _)| *contract == registry.address)
+        .unwrap();
+    assert_eq!(
+        String::try_from_val(&env, &topics.get(0).unwrap()).unwrap(),
+        String::from_str(&env, "escrow_released")
+    );
+    let event = NotificationEvent::try_from_val(&env, &data).unwrap();
+    assert_eq!(
+        (event.subject, event.reference, event.amount),
+        (employee, 1, 200)
+    );
+
+    // Deposits and refunds notify the same subscription
+    let notified = |env: &Env| -> soroban_sdk::Vec<(String, Address, i128)> {
+        env.events()
+            .all()
+            .iter()
+            .filter(|(contract, _, _)| *contract == registry.address)
+            .map(|(_, topics, data)| {
+                let event = NotificationEvent::try_from_val(env, &data).unwrap();
+                (
+                    String::try_from_val(env, &topics.get(0).unwrap()).unwrap(),
+                    event.subject,
+                    event.amount,
+                )
+            })
+            .fold(vec![env], |mut sent, notification| {
+                sent.push_back(notification);
+                sent
+            })
+    };
+    client.fund_agreement(&employer, &1, &employer, &50);
+    assert_eq!(
+        notified(&env),
+        vec![
+            &env,
+            (
+                String::from_str(&env, "escrow_funded"),
+                employer.clone(),
+                50
+            )
+        ]
+    );
+    client.refund_remaining(&manager, &1);
+    assert_eq!(
+        notified(&env),
+        vec![
+            &env,
+            (String::from_str(&env, "escrow_refunded"), employer, 250)
+        ]
+    );
+}
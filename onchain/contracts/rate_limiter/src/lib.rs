@@ -4,9 +4,17 @@
 //!
 //! Provides burst-friendly rate limiting with automatic token refills,
 //! global throttling, and admin bypass to ensure security and fairness.
+//!
+//! Bucket usage is kept in temporary storage. Each write gives the entry a
+//! TTL covering the time the bucket needs to refill completely, so when an
+//! entry lapses the bucket would have been full anyway and nothing is lost.
+//! Buckets that never refill use the network's maximum TTL.
 
 use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
 
+/// Approximate ledger close time, used to convert refill time into a TTL.
+const LEDGER_SECONDS: u64 = 5;
+
 #[contracttype]
 #[derive(Clone)]
 enum StorageKey {
@@ -22,13 +30,13 @@ enum StorageKey {
     GlobalBurst,
     /// Global refill rate
     GlobalRefillRate,
-    /// Global usage state
+    /// Global usage state (temporary storage)
     GlobalUsage,
     /// Admin bypass enabled
     AdminBypass,
     /// Per-address override: address -> LimitConfig
     Limit(Address),
-    /// Per-address usage: address -> Usage
+    /// Per-address usage: address -> Usage (temporary storage)
     Usage(Address),
 }
 
@@ -187,7 +195,7 @@ impl RateLimiter {
     /// @dev Only callable by admin.
     pub fn reset_usage(env: Env, addr: Address) {
         Self::require_admin_auth(&env);
-        env.storage().persistent().remove(&StorageKey::Usage(addr));
+        env.storage().temporary().remove(&StorageKey::Usage(addr));
     }
 
     /// Transfers admin rights to a new address.
@@ -219,7 +227,7 @@ impl RateLimiter {
     ///   is effectively full at the configured burst capacity).
     pub fn get_usage(env: Env, addr: Address) -> Option<Usage> {
         env.storage()
-            .persistent()
+            .temporary()
             .get(&StorageKey::Usage(addr.clone()))
             .map(|usage: Usage| {
                 let now = env.ledger().timestamp();
@@ -251,7 +259,7 @@ impl RateLimiter {
 
     fn consume_bucket(env: &Env, key: StorageKey, burst: u32, refill_rate: u32) -> u32 {
         let now = env.ledger().timestamp();
-        let mut usage: Usage = env.storage().persistent().get(&key).unwrap_or(Usage {
+        let mut usage: Usage = env.storage().temporary().get(&key).unwrap_or(Usage {
             last_update: now,
             tokens: burst,
         });
@@ -270,10 +278,24 @@ impl RateLimiter {
         assert!(usage.tokens >= 1, "rate limit exceeded");
         usage.tokens -= 1;
 
-        env.storage().persistent().set(&key, &usage);
+        let ttl = Self::usage_ttl(env, burst, refill_rate);
+        env.storage().temporary().set(&key, &usage);
+        env.storage().temporary().extend_ttl(&key, ttl, ttl);
         usage.tokens
     }
 
+    /// Ledgers until a bucket refills completely from empty, capped at the
+    /// network maximum (also used when the bucket never refills).
+    fn usage_ttl(env: &Env, burst: u32, refill_rate: u32) -> u32 {
+        let max_ttl = env.storage().max_ttl();
+        if refill_rate == 0 {
+            return max_ttl;
+        }
+        let refill_seconds = u64::from(burst).div_ceil(u64::from(refill_rate));
+        let ledgers = refill_seconds.div_ceil(LEDGER_SECONDS) + 1;
+        u32::try_from(ledgers).unwrap_or(u32::MAX).min(max_ttl)
+    }
+
     fn get_limit_config(env: &Env, addr: &Address) -> LimitConfig {
         env.storage()
             .persistent()
@@ -288,3 +288,28 @@ fn test_get_usage_caps_at_burst() {
     assert_eq!(usage.tokens, 5);
     assert_eq!(usage.last_update, 110);
 }
+
+#[test]
+fn test_usage_lapses_once_bucket_would_be_full() {
+    let env = create_env();
+    env.ledger().with_mut(|li| {
+        li.timestamp = 100;
+        li.sequence_number = 1;
+        li.min_temp_entry_ttl = 1;
+    });
+    let (_id, client) = register_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    // Burst 100, refill 1 per second: full again after 100s (20 ledgers)
+    client.initialize(&admin, &100u32, &1u32, &false);
+    assert_eq!(client.check_and_consume(&user), 99);
+    assert!(client.get_usage(&user).is_some());
+
+    env.ledger().with_mut(|li| {
+        li.timestamp += 110;
+        li.sequence_number += 22;
+    });
+    assert_eq!(client.get_usage(&user), None);
+    assert_eq!(client.check_and_consume(&user), 99);
+}
@@ -0,0 +1,208 @@
+//! Per-employer disbursement approval matrix.
+//!
+//! A single `LargePaymentThreshold` treats every payout above it the same,
+//! whoever the employer and however large the payout. The owner can instead
+//! give an employer an approval matrix: tiers of amount ranges, each naming
+//! how many multisig signers must approve a payout in that range and which
+//! RBAC roles must be among them.
+//!
+//! Payouts in a tier that needs approvals must be claimed through
+//! `claim_payroll_multisig` with an executed `LargePayment` operation whose
+//! approvers satisfy the tier; in-token and batch claims in such a tier are
+//! rejected, with the tier read from the base-token amount. Employers without a matrix keep the global
+//! threshold, read as a single tier needing the multisig's own threshold.
+//!
+//! `get_required_approvals` returns the tier a payout falls in, so clients can
+//! show the approval path before submitting.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+use stellopay_interfaces::multisig::{MultisigClient, OperationType};
+
+use crate::storage::{extend_persistent_ttl, PayrollError, StorageKey};
+use crate::validation;
+use rbac_interface::{RbacContractClient, Role};
+
+/// Maximum tiers in an employer's matrix.
+pub const MAX_APPROVAL_TIERS: u32 = 8;
+
+/// Approvals required for payouts from `min_amount` up to the next tier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalTier {
+    /// Smallest payout in the tier.
+    pub min_amount: i128,
+    /// Multisig signers that must approve; 0 means no approval is needed.
+    pub approvals: u32,
+    /// RBAC roles that must each be held by at least one approver.
+    pub roles: Vec<Role>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum ApprovalMatrixKey {
+    /// Approval tiers of an employer, ascending by `min_amount` -> Vec<ApprovalTier>
+    ApprovalMatrix(Address),
+    /// Multisig operation approving the payout in progress -> u128
+    ApprovedOperation,
+}
+
+/// Returns the employer's approval tiers, empty if it has no matrix.
+pub fn get_approval_matrix(env: &Env, employer: &Address) -> Vec<ApprovalTier> {
+    env.storage()
+        .persistent()
+        .get(&ApprovalMatrixKey::ApprovalMatrix(employer.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Replaces the employer's approval tiers. An empty list removes the matrix
+/// and the employer falls back to the global large payment threshold.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - `owner` is not the contract owner
+/// * `PayrollError::InvalidData` - more than [`MAX_APPROVAL_TIERS`] tiers, a
+///   non-positive or out-of-range `min_amount`, tiers not strictly ascending,
+///   or a tier naming more roles than approvals
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_approval_matrix(
+    env: &Env,
+    owner: &Address,
+    employer: &Address,
+    tiers: Vec<ApprovalTier>,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+
+    if tiers.len() > MAX_APPROVAL_TIERS {
+        return Err(PayrollError::InvalidData);
+    }
+    let mut floor = 0;
+    for tier in tiers.iter() {
+        validation::max_amount(tier.min_amount)?;
+        if tier.min_amount <= floor || tier.roles.len() > tier.approvals {
+            return Err(PayrollError::InvalidData);
+        }
+        floor = tier.min_amount;
+    }
+
+    let key = ApprovalMatrixKey::ApprovalMatrix(employer.clone());
+    if tiers.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &tiers);
+        extend_persistent_ttl(env, &key);
+    }
+    Ok(())
+}
+
+/// Returns the tier a payout of `amount` by `employer` falls in. Amounts below
+/// every tier return a tier with `min_amount` 0 and no approvals.
+pub fn get_required_approvals(env: &Env, employer: &Address, amount: i128) -> ApprovalTier {
+    let none = ApprovalTier {
+        min_amount: 0,
+        approvals: 0,
+        roles: Vec::new(env),
+    };
+    let tiers = get_approval_matrix(env, employer);
+    if !tiers.is_empty() {
+        return tiers
+            .iter()
+            .filter(|tier| tier.min_amount <= amount)
+            .last()
+            .unwrap_or(none);
+    }
+
+    let threshold: i128 = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::LargePaymentThreshold)
+        .unwrap_or(0);
+    if threshold <= 0 || amount < threshold {
+        return none;
+    }
+    let approvals = env
+        .storage()
+        .persistent()
+        .get::<_, Address>(&StorageKey::MultisigContract)
+        .map(|multisig| {
+            MultisigClient::new(env, &multisig)
+                .get_effective_threshold(&OperationType::LargePayment)
+        })
+        .unwrap_or(1)
+        .max(1);
+    ApprovalTier {
+        min_amount: threshold,
+        approvals,
+        roles: Vec::new(env),
+    }
+}
+
+/// Checks that a payout of `amount` by `employer` has the approvals its tier
+/// requires, from the operation recorded by [`with_approved_operation`].
+///
+/// # Errors
+/// * `PayrollError::MultisigApprovalRequired` - the tier needs approvals and
+///   there is no approved operation, too few signers approved it, or no
+///   approver holds one of the tier's roles
+pub(crate) fn require_approvals(
+    env: &Env,
+    employer: &Address,
+    amount: i128,
+) -> Result<(), PayrollError> {
+    let tier = get_required_approvals(env, employer, amount);
+    if tier.approvals == 0 {
+        return Ok(());
+    }
+    let operation_id: u128 = env
+        .storage()
+        .temporary()
+        .get(&ApprovalMatrixKey::ApprovedOperation)
+        .ok_or(PayrollError::MultisigApprovalRequired)?;
+    let multisig: Address = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::MultisigContract)
+        .ok_or(PayrollError::MultisigApprovalRequired)?;
+    let approvers = MultisigClient::new(env, &multisig).get_approvals(&operation_id);
+    if approvers.len() < tier.approvals {
+        return Err(PayrollError::MultisigApprovalRequired);
+    }
+    if tier.roles.is_empty() {
+        return Ok(());
+    }
+
+    // Roles can only be checked against a configured RBAC contract.
+    let rbac: Address = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::RbacContract)
+        .ok_or(PayrollError::MultisigApprovalRequired)?;
+    let rbac = RbacContractClient::new(env, &rbac);
+    for role in tier.roles.iter() {
+        if !approvers
+            .iter()
+            .any(|approver| rbac.has_role(&approver, &role))
+        {
+            return Err(PayrollError::MultisigApprovalRequired);
+        }
+    }
+    Ok(())
+}
+
+/// Runs `f` with `operation_id` recorded as the executed multisig operation
+/// approving payouts it makes. The record is cleared before returning.
+pub(crate) fn with_approved_operation<T>(
+    env: &Env,
+    operation_id: u128,
+    f: impl FnOnce() -> T,
+) -> T {
+    let key = ApprovalMatrixKey::ApprovedOperation;
+    env.storage().temporary().set(&key, &operation_id);
+    let result = f();
+    env.storage().temporary().remove(&key);
+    result
+}
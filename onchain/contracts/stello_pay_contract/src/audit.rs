@@ -1,4 +1,14 @@
-use soroban_sdk::{contracttype, Address, Env, IntoVal, Symbol, Val, Vec};
+use soroban_sdk::{
+    contracttype, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Symbol, Val, Vec,
+};
+
+use crate::credentials::{has_credential, CredentialRole};
+use crate::events::{emit_audit_period_anchored, AuditPeriodAnchoredEvent};
+use crate::storage::PayrollError;
+
+/// Length of one audit anchoring period. Period `n` covers ledger timestamps
+/// in `[n * AUDIT_ANCHOR_PERIOD_SECONDS, (n + 1) * AUDIT_ANCHOR_PERIOD_SECONDS)`.
+pub const AUDIT_ANCHOR_PERIOD_SECONDS: u64 = 30 * 24 * 60 * 60;
 
 /// Canonical lifecycle audit events recorded by the payroll contract.
 #[contracttype]
@@ -19,6 +29,10 @@ pub enum AuditEvent {
     /// Contract-level event not tied to an agreement; entries use a sentinel
     /// `agreement_id` of `0` and `subject` is the newly-set arbiter.
     ArbiterSet,
+    /// A payroll payout was disbursed. `actor` is the employer, `subject` the
+    /// employee and `amount` the gross payout. Backs disbursement receipts
+    /// (see [`crate::receipts`]).
+    PayoutDisbursed,
 }
 
 /// Append-only audit entry for critical agreement lifecycle transitions.
@@ -48,6 +62,29 @@ pub struct EmployerAuditPage {
     pub next_start_id: Option<u64>,
 }
 
+/// Rolling hash chain over one employer's audit entries in one period.
+///
+/// `head` starts as 32 zero bytes and each entry extends it to
+/// `sha256(head || audit_leaf_hash(entry))`, so recording an entry costs the
+/// same however busy the period is.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditChain {
+    pub head: BytesN<32>,
+    pub entry_count: u32,
+    /// Audit id of the newest entry in the chain.
+    pub last_entry_id: u64,
+}
+
+/// Chain head committed for one employer's audit entries in one period.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditAnchor {
+    pub root: BytesN<32>,
+    pub entry_count: u32,
+    pub anchored_at: u64,
+}
+
 #[contracttype]
 #[derive(Clone)]
 enum AuditStorageKey {
@@ -55,6 +92,13 @@ enum AuditStorageKey {
     NextAuditEntryId,
     AuditEntry(u64),
     AuditEntryCount,
+    /// Hash chain of `(employer, period)` -> AuditChain
+    PeriodChain(Address, u64),
+    /// Audit id -> id of the previous entry in the same period chain
+    PreviousChainEntry(u64),
+    AuditAnchor(Address, u64),
+    /// ViewAuditTrail permission granted by `employer` to `viewer` -> bool
+    ViewAuditTrail(Address, Address),
 }
 
 impl AuditEvent {
@@ -67,6 +111,7 @@ impl AuditEvent {
             AuditEvent::DisputeResolved => Symbol::new(env, "dispute_resolved"),
             AuditEvent::MultisigConfigChanged => Symbol::new(env, "multisig_config_changed"),
             AuditEvent::ArbiterSet => Symbol::new(env, "arbiter_set"),
+            AuditEvent::PayoutDisbursed => Symbol::new(env, "payout_disbursed"),
         }
     }
 }
@@ -179,9 +224,10 @@ pub fn record_entry(
         .get(&AuditStorageKey::NextAuditEntryId)
         .unwrap_or(1u64);
 
+    let timestamp = env.ledger().timestamp();
     let entry = LifecycleAuditEntry {
         id,
-        timestamp: env.ledger().timestamp(),
+        timestamp,
         actor,
         event,
         agreement_id,
@@ -200,9 +246,207 @@ pub fn record_entry(
         .persistent()
         .set(&AuditStorageKey::AuditEntryCount, &id);
 
+    if agreement_id != 0 {
+        if let Some(agreement) = crate::payroll::get_agreement(env, agreement_id) {
+            extend_period_chain(env, agreement.employer, &entry);
+        }
+    }
+
     id
 }
 
+/// Appends `entry` to its employer's chain for the entry's period.
+fn extend_period_chain(env: &Env, employer: Address, entry: &LifecycleAuditEntry) {
+    let key = AuditStorageKey::PeriodChain(employer, entry.timestamp / AUDIT_ANCHOR_PERIOD_SECONDS);
+    let mut chain = env.storage().persistent().get(&key).unwrap_or(AuditChain {
+        head: BytesN::from_array(env, &[0u8; 32]),
+        entry_count: 0,
+        last_entry_id: 0,
+    });
+    if chain.entry_count > 0 {
+        env.storage().persistent().set(
+            &AuditStorageKey::PreviousChainEntry(entry.id),
+            &chain.last_entry_id,
+        );
+    }
+    chain.head = chain_audit_hash(env, &chain.head, &audit_leaf_hash(env, entry));
+    chain.entry_count += 1;
+    chain.last_entry_id = entry.id;
+    env.storage().persistent().set(&key, &chain);
+}
+
+/// @notice Returns the hash chain of an employer's audit period, if it has entries.
+pub fn get_audit_chain(env: &Env, employer: Address, period: u64) -> Option<AuditChain> {
+    env.storage()
+        .persistent()
+        .get(&AuditStorageKey::PeriodChain(employer, period))
+}
+
+/// @notice Returns up to `limit` audit ids of an employer's period, in chain order, ending
+/// just before `before_id` (or with the newest entry when `before_id` is `0`).
+/// @dev Walks the chain backwards, so auditors page from the newest entry towards the
+/// oldest by passing the first id of each page as the next `before_id`. Together with
+/// `get_audit_entry` this rebuilds the chain and its inclusion proofs off-chain.
+pub fn get_audit_period_entry_ids(
+    env: &Env,
+    employer: Address,
+    period: u64,
+    before_id: u64,
+    limit: u32,
+) -> Vec<u64> {
+    let mut ids = Vec::new(env);
+    let mut next = if before_id == 0 {
+        get_audit_chain(env, employer, period).map(|chain| chain.last_entry_id)
+    } else {
+        env.storage()
+            .persistent()
+            .get(&AuditStorageKey::PreviousChainEntry(before_id))
+    };
+    while let Some(id) = next {
+        if ids.len() >= limit {
+            break;
+        }
+        ids.push_front(id);
+        next = env
+            .storage()
+            .persistent()
+            .get(&AuditStorageKey::PreviousChainEntry(id));
+    }
+    ids
+}
+
+/// @notice Returns the stored anchor for an employer's period, if anchored.
+pub fn get_audit_anchor(env: &Env, employer: Address, period: u64) -> Option<AuditAnchor> {
+    env.storage()
+        .persistent()
+        .get(&AuditStorageKey::AuditAnchor(employer, period))
+}
+
+/// @notice Anchors the head of an employer's audit chain for a finished period.
+/// @dev Permissionless: anyone, typically a keeper, can anchor any employer's period once
+/// it has fully elapsed and its chain can no longer change. Each period is anchored once.
+/// Costs the same however many entries the period holds.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - the period has not finished, is already anchored, or
+///   has no entries
+pub fn anchor_audit_period(
+    env: &Env,
+    employer: Address,
+    period: u64,
+) -> Result<BytesN<32>, PayrollError> {
+    let period_end = period
+        .checked_add(1)
+        .and_then(|p| p.checked_mul(AUDIT_ANCHOR_PERIOD_SECONDS))
+        .ok_or(PayrollError::InvalidData)?;
+    if env.ledger().timestamp() < period_end {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let anchor_key = AuditStorageKey::AuditAnchor(employer.clone(), period);
+    if env.storage().persistent().has(&anchor_key) {
+        return Err(PayrollError::InvalidData);
+    }
+    let chain = get_audit_chain(env, employer.clone(), period).ok_or(PayrollError::InvalidData)?;
+
+    let anchor = AuditAnchor {
+        root: chain.head.clone(),
+        entry_count: chain.entry_count,
+        anchored_at: env.ledger().timestamp(),
+    };
+    env.storage().persistent().set(&anchor_key, &anchor);
+    crate::storage::extend_persistent_ttl(env, &anchor_key);
+
+    emit_audit_period_anchored(
+        env,
+        AuditPeriodAnchoredEvent {
+            employer,
+            period,
+            root: chain.head.clone(),
+            entry_count: anchor.entry_count,
+        },
+    );
+
+    Ok(chain.head)
+}
+
+/// @notice Verifies that an audit entry is included in its employer's anchored period.
+/// @dev The employer is resolved from `entry.agreement_id` and the period from
+/// `entry.timestamp`. `proof` starts with the chain head before the entry, followed by the
+/// leaf hashes of the period's later entries in chain order. Returns `false` when the
+/// period has not been anchored, the proof is empty, or it does not reproduce the anchor.
+pub fn verify_audit_inclusion(
+    env: &Env,
+    entry: LifecycleAuditEntry,
+    proof: Vec<BytesN<32>>,
+) -> bool {
+    let agreement = match crate::payroll::get_agreement(env, entry.agreement_id) {
+        Some(agreement) => agreement,
+        None => return false,
+    };
+    let period = entry.timestamp / AUDIT_ANCHOR_PERIOD_SECONDS;
+    let anchor = match get_audit_anchor(env, agreement.employer, period) {
+        Some(anchor) => anchor,
+        None => return false,
+    };
+    let Some(previous) = proof.first() else {
+        return false;
+    };
+
+    let mut head = chain_audit_hash(env, &previous, &audit_leaf_hash(env, &entry));
+    for leaf in proof.iter().skip(1) {
+        head = chain_audit_hash(env, &head, &leaf);
+    }
+    head == anchor.root
+}
+
+/// @notice Leaf hash of an audit entry in its period chain.
+pub fn audit_leaf_hash(env: &Env, entry: &LifecycleAuditEntry) -> BytesN<32> {
+    env.crypto().sha256(&entry.clone().to_xdr(env)).into()
+}
+
+/// @notice Extends a period chain head with the leaf hash of the next entry.
+pub fn chain_audit_hash(env: &Env, head: &BytesN<32>, leaf: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from(head.clone());
+    preimage.append(&Bytes::from(leaf.clone()));
+    env.crypto().sha256(&preimage).into()
+}
+
+/// @notice Grants an external party the ViewAuditTrail permission over an employer's audit trail.
+/// @dev The viewer must hold a valid auditor credential in the linked credential registry.
+/// The grant only counts while that credential stays valid; see `has_audit_trail_access`.
+pub fn grant_audit_trail_access(env: &Env, employer: Address, viewer: Address) {
+    employer.require_auth();
+    assert!(
+        has_credential(env, &viewer, CredentialRole::Auditor) == Some(true),
+        "Viewer lacks a valid auditor credential"
+    );
+
+    env.storage()
+        .persistent()
+        .set(&AuditStorageKey::ViewAuditTrail(employer, viewer), &true);
+}
+
+/// @notice Withdraws a ViewAuditTrail permission granted by the employer.
+pub fn revoke_audit_trail_access(env: &Env, employer: Address, viewer: Address) {
+    employer.require_auth();
+    env.storage()
+        .persistent()
+        .remove(&AuditStorageKey::ViewAuditTrail(employer, viewer));
+}
+
+/// @notice Returns whether `viewer` currently holds the ViewAuditTrail permission for `employer`.
+/// @dev Requires both the employer's grant and a still-valid auditor credential, so revoked
+/// or expired credentials lose access without the employer acting.
+pub fn has_audit_trail_access(env: &Env, employer: Address, viewer: Address) -> bool {
+    let granted = env
+        .storage()
+        .persistent()
+        .get(&AuditStorageKey::ViewAuditTrail(employer, viewer.clone()))
+        .unwrap_or(false);
+    granted && has_credential(env, &viewer, CredentialRole::Auditor) == Some(true)
+}
+
 fn append_external_log(
     env: &Env,
     actor: &Address,
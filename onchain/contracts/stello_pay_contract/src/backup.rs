@@ -51,6 +51,7 @@ use sha2::Sha256;
 use soroban_sdk::{contracttype, Address, Bytes, Env};
 
 use crate::storage::{Agreement, AgreementMode, AgreementStatus, DisputeStatus, StorageKey};
+use crate::versioned;
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -418,9 +419,7 @@ pub fn restore_agreement(
 /// Requires the contract owner to have called `require_auth` before this
 /// function is invoked (enforced by the caller in `lib.rs`).
 pub fn admin_restore_agreement(env: &Env, agreement: Agreement) {
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement.id), &agreement);
+    versioned::save(env, &StorageKey::Agreement(agreement.id), &agreement);
 }
 
 /// Admin-only: restore an agreement directly from an encrypted envelope.
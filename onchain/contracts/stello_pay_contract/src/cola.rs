@@ -0,0 +1,522 @@
+//! Employer cost-of-living adjustment (COLA) policies.
+//!
+//! An employer sets a yearly raise in basis points and how often it is
+//! applied. Each application raises every salary on the employer's payroll
+//! agreements by the pro-rated share of the yearly rate:
+//!
+//! `raise_bps = bps_per_year * apply_frequency / SECONDS_PER_YEAR`
+//!
+//! so a 300 bps/year policy applied monthly compounds a little over 3% a
+//! year. Both the pro-rated `raise_bps` and each raise are rounded with the
+//! employer's rounding policy (see [`crate::rounding`]). Applications are triggered by a keeper via `apply_cola_policy` once
+//! the policy falls due. A run covers all of the employer's agreements but is
+//! processed in bounded batches: each call adjusts at most `limit` employees
+//! and stores a cursor, and later calls resume from it until the run is
+//! complete. Every salary change is recorded as a [`PayrollAdjustment`].
+//!
+//! A raise takes effect from the period in progress when it is applied.
+//! Periods that elapsed before it and are not yet claimed keep the old
+//! salary: the run records it with the period the raise starts at, and
+//! claims pay each period at the salary in effect when it elapsed.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::events::{emit_payroll_adjusted, PayrollAdjustedEvent};
+use crate::payroll::{elapsed_periods, get_agreement};
+use crate::rounding::{apply_bps, get_rounding_policy, round_div, RoundingMode};
+use crate::sequence::{next_sequence, SequenceCategory};
+use crate::storage::{
+    extend_persistent_ttl, AgreementMode, AgreementStatus, DataKey, EmployeeInfo, PayrollError,
+    StorageKey, MAX_BATCH_SIZE,
+};
+use crate::validation;
+use crate::versioned;
+
+/// Seconds in a (365-day) year, the base `bps_per_year` is pro-rated over.
+pub const SECONDS_PER_YEAR: u64 = 365 * 86_400;
+
+/// Upper bound for `bps_per_year` (50% a year).
+pub const MAX_COLA_BPS_PER_YEAR: u32 = 5_000;
+
+/// Shortest accepted `apply_frequency`.
+pub const MIN_COLA_FREQUENCY_SECONDS: u64 = 86_400;
+
+#[contracttype]
+#[derive(Clone)]
+enum ColaKey {
+    /// Policy for an employer -> ColaPolicy
+    Policy(Address),
+    /// Run in progress for an employer -> ColaRun
+    Run(Address),
+    /// Number of recorded adjustments -> u64
+    AdjustmentCount,
+    /// Adjustment by 1-based id -> PayrollAdjustment
+    Adjustment(u64),
+    /// Salaries an employee earned before raises -> SalaryHistory
+    SalaryHistory(u128, Address),
+}
+
+/// Employer-level cost-of-living adjustment policy.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ColaPolicy {
+    pub bps_per_year: u32,
+    /// Seconds between applications.
+    pub apply_frequency: u64,
+    /// Start of the last completed run, if any.
+    pub last_applied_at: Option<u64>,
+    /// Earliest timestamp at which the next run may start.
+    pub next_due_at: u64,
+}
+
+/// Cursor of a partially processed run.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct ColaRun {
+    started_at: u64,
+    /// Raise applied by this run, fixed when the run starts.
+    raise_bps: u32,
+    /// Position in the employer's agreement list.
+    agreement_pos: u32,
+    /// Next employee index within that agreement.
+    employee_index: u32,
+}
+
+/// Salary an employee earned before a raise.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriorSalary {
+    /// First period paid at a later salary.
+    pub until_period: u32,
+    pub salary: i128,
+}
+
+/// Salaries an employee earned before raises, for periods not yet claimed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct SalaryHistory {
+    /// Salary the last raise set. The history only applies while claims
+    /// pay this salary, so a later manual change or override replaces it.
+    current: i128,
+    /// Ordered by `until_period`.
+    prior: Vec<PriorSalary>,
+}
+
+/// One salary change applied by a COLA run.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayrollAdjustment {
+    pub id: u64,
+    pub employer: Address,
+    pub agreement_id: u128,
+    pub employee: Address,
+    pub old_salary: i128,
+    pub new_salary: i128,
+    pub raise_bps: u32,
+    pub applied_at: u64,
+    /// `Payroll` sequence number of the change (see [`crate::sequence`]).
+    pub sequence: u64,
+}
+
+/// Outcome of one `apply_cola_policy` call.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ColaRunResult {
+    /// Salaries changed by this call.
+    pub adjusted: u32,
+    /// True once the run has covered every agreement of the employer.
+    pub completed: bool,
+}
+
+/// Sets (or replaces) the COLA policy for `employer`.
+///
+/// The next run falls due `apply_frequency` seconds after the last completed
+/// run, or after now for a new policy. A run already in progress keeps the
+/// raise it started with.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `bps_per_year` is zero or above
+///   [`MAX_COLA_BPS_PER_YEAR`], or `apply_frequency` is outside
+///   `MIN_COLA_FREQUENCY_SECONDS..=SECONDS_PER_YEAR`
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn set_cola_policy(
+    env: &Env,
+    employer: &Address,
+    bps_per_year: u32,
+    apply_frequency: u64,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+
+    if bps_per_year == 0 || bps_per_year > MAX_COLA_BPS_PER_YEAR {
+        return Err(PayrollError::InvalidData);
+    }
+    validation::interval(
+        apply_frequency,
+        MIN_COLA_FREQUENCY_SECONDS,
+        SECONDS_PER_YEAR,
+    )?;
+
+    let last_applied_at = get_cola_policy(env, employer).and_then(|p| p.last_applied_at);
+    let base = last_applied_at.unwrap_or_else(|| env.ledger().timestamp());
+    let policy = ColaPolicy {
+        bps_per_year,
+        apply_frequency,
+        last_applied_at,
+        next_due_at: base.saturating_add(apply_frequency),
+    };
+    let key = ColaKey::Policy(employer.clone());
+    env.storage().persistent().set(&key, &policy);
+    crate::storage::extend_persistent_ttl(env, &key);
+    Ok(())
+}
+
+/// Returns the COLA policy for `employer`, if any.
+pub fn get_cola_policy(env: &Env, employer: &Address) -> Option<ColaPolicy> {
+    env.storage()
+        .persistent()
+        .get(&ColaKey::Policy(employer.clone()))
+}
+
+/// Applies the due COLA raise to at most `limit` of `employer`'s employees.
+///
+/// Starts a new run when the policy is due and none is in progress, otherwise
+/// continues the current one. Payroll agreements that are cancelled or
+/// completed are skipped, as are confidential-mode employees (no plaintext
+/// salary to raise).
+///
+/// # Errors
+/// * `PayrollError::ColaPolicyNotFound` - the employer has no policy
+/// * `PayrollError::ColaNotDue` - no run in progress and the policy is not due
+/// * `PayrollError::InvalidData` - `limit` is zero
+/// * `PayrollError::BatchTooLarge` - `limit` exceeds `MAX_BATCH_SIZE`
+///
+/// # Access Control
+/// Permissionless, so a keeper can trigger runs.
+pub fn apply_cola_policy(
+    env: &Env,
+    employer: &Address,
+    limit: u32,
+) -> Result<ColaRunResult, PayrollError> {
+    if limit == 0 {
+        return Err(PayrollError::InvalidData);
+    }
+    if limit > MAX_BATCH_SIZE {
+        return Err(PayrollError::BatchTooLarge);
+    }
+    let mut policy = get_cola_policy(env, employer).ok_or(PayrollError::ColaPolicyNotFound)?;
+    let mode = get_rounding_policy(env, employer);
+    let now = env.ledger().timestamp();
+
+    let run_key = ColaKey::Run(employer.clone());
+    let mut run: ColaRun = match env.storage().persistent().get(&run_key) {
+        Some(run) => run,
+        None if now >= policy.next_due_at => ColaRun {
+            started_at: now,
+            raise_bps: raise_bps(&policy, mode),
+            agreement_pos: 0,
+            employee_index: 0,
+        },
+        None => return Err(PayrollError::ColaNotDue),
+    };
+
+    let agreement_ids: Vec<u128> = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::EmployerAgreements(employer.clone()))
+        .unwrap_or(Vec::new(env));
+
+    let mut processed = 0u32;
+    let mut adjusted = 0u32;
+    while run.agreement_pos < agreement_ids.len() && processed < limit {
+        let agreement_id = agreement_ids.get_unchecked(run.agreement_pos);
+        let employees_key = StorageKey::AgreementEmployees(agreement_id);
+        let mut employees: Vec<EmployeeInfo> = match get_agreement(env, agreement_id) {
+            Some(agreement) if is_adjustable(&agreement.mode, &agreement.status) => env
+                .storage()
+                .persistent()
+                .get(&employees_key)
+                .unwrap_or(Vec::new(env)),
+            _ => Vec::new(env),
+        };
+
+        let mut total_increase = 0i128;
+        while run.employee_index < employees.len() && processed < limit {
+            let index = run.employee_index;
+            let mut info = employees.get_unchecked(index);
+            processed += 1;
+            run.employee_index += 1;
+            if info.salary_per_period <= 0 {
+                continue;
+            }
+            let old_salary = info.salary_per_period;
+            let new_salary = apply_raise(old_salary, run.raise_bps, mode)?;
+            if new_salary == old_salary {
+                continue;
+            }
+            info.salary_per_period = new_salary;
+            employees.set(index, info.clone());
+            total_increase = total_increase
+                .checked_add(new_salary - old_salary)
+                .ok_or(PayrollError::InvalidData)?;
+            record_prior_salary(
+                env,
+                agreement_id,
+                index,
+                &info.address,
+                old_salary,
+                new_salary,
+            );
+            sync_indexed_salary(env, agreement_id, index, &info.address, new_salary);
+            record_adjustment(
+                env,
+                employer,
+                agreement_id,
+                info.address,
+                old_salary,
+                new_salary,
+                run.raise_bps,
+            );
+            adjusted += 1;
+        }
+
+        if total_increase > 0 {
+            env.storage().persistent().set(&employees_key, &employees);
+            if let Some(mut agreement) = get_agreement(env, agreement_id) {
+                agreement.total_amount = agreement
+                    .total_amount
+                    .checked_add(total_increase)
+                    .ok_or(PayrollError::InvalidData)?;
+                versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
+            }
+        }
+        if run.employee_index >= employees.len() {
+            run.agreement_pos += 1;
+            run.employee_index = 0;
+        }
+    }
+
+    let completed = run.agreement_pos >= agreement_ids.len();
+    if completed {
+        env.storage().persistent().remove(&run_key);
+        policy.last_applied_at = Some(run.started_at);
+        policy.next_due_at = run.started_at.saturating_add(policy.apply_frequency);
+        env.storage()
+            .persistent()
+            .set(&ColaKey::Policy(employer.clone()), &policy);
+    } else {
+        env.storage().persistent().set(&run_key, &run);
+    }
+
+    Ok(ColaRunResult {
+        adjusted,
+        completed,
+    })
+}
+
+/// Returns the number of recorded payroll adjustments.
+pub fn get_payroll_adjustment_count(env: &Env) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&ColaKey::AdjustmentCount)
+        .unwrap_or(0)
+}
+
+/// Returns a recorded payroll adjustment by its 1-based id.
+pub fn get_payroll_adjustment(env: &Env, adjustment_id: u64) -> Option<PayrollAdjustment> {
+    env.storage()
+        .persistent()
+        .get(&ColaKey::Adjustment(adjustment_id))
+}
+
+fn raise_bps(policy: &ColaPolicy, mode: RoundingMode) -> u32 {
+    // bps_per_year <= MAX_COLA_BPS_PER_YEAR and apply_frequency <= a year,
+    // so the pro-rated share is at most bps_per_year and fits in u32.
+    round_div(
+        i128::from(policy.bps_per_year) * i128::from(policy.apply_frequency),
+        i128::from(SECONDS_PER_YEAR),
+        mode,
+    ) as u32
+}
+
+fn apply_raise(salary: i128, raise_bps: u32, mode: RoundingMode) -> Result<i128, PayrollError> {
+    salary
+        .checked_add(apply_bps(salary, raise_bps, mode))
+        .ok_or(PayrollError::InvalidData)
+}
+
+fn is_adjustable(mode: &AgreementMode, status: &AgreementStatus) -> bool {
+    *mode == AgreementMode::Payroll
+        && !matches!(
+            status,
+            AgreementStatus::Cancelled | AgreementStatus::Completed
+        )
+}
+
+/// Returns the pay for `periods` periods from `first_period` at `salary`,
+/// paying periods that elapsed before a raise at the salary they were
+/// earned at. `None` if the amount overflows.
+pub(crate) fn pay_for_periods(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+    salary: i128,
+    first_period: u32,
+    periods: u32,
+) -> Option<i128> {
+    let end = first_period.saturating_add(periods);
+    let mut amount: i128 = 0;
+    let mut from = first_period;
+    for prior in prior_salaries(env, agreement_id, employee, salary).iter() {
+        if from >= end {
+            break;
+        }
+        if prior.until_period <= from {
+            continue;
+        }
+        let to = prior.until_period.min(end);
+        amount = amount.checked_add(prior.salary.checked_mul(i128::from(to - from))?)?;
+        from = to;
+    }
+    amount.checked_add(salary.checked_mul(i128::from(end - from))?)
+}
+
+/// Returns the salary `period` was earned at, given the current `salary`.
+pub(crate) fn salary_for_period(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+    salary: i128,
+    period: u32,
+) -> i128 {
+    prior_salaries(env, agreement_id, employee, salary)
+        .iter()
+        .find(|prior| period < prior.until_period)
+        .map_or(salary, |prior| prior.salary)
+}
+
+fn prior_salaries(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+    salary: i128,
+) -> Vec<PriorSalary> {
+    match env
+        .storage()
+        .persistent()
+        .get::<_, SalaryHistory>(&ColaKey::SalaryHistory(agreement_id, employee.clone()))
+    {
+        Some(history) if history.current == salary => history.prior,
+        _ => Vec::new(env),
+    }
+}
+
+/// Keeps the unclaimed periods that elapsed before a raise at `old_salary`.
+fn record_prior_salary(
+    env: &Env,
+    agreement_id: u128,
+    employee_index: u32,
+    employee: &Address,
+    old_salary: i128,
+    new_salary: i128,
+) {
+    let (Some(activated_at), Some(period_duration)) = (
+        DataKey::get_agreement_activation_time(env, agreement_id),
+        DataKey::get_agreement_period_duration(env, agreement_id),
+    ) else {
+        return;
+    };
+    let now = env.ledger().timestamp();
+    if now <= activated_at || period_duration == 0 {
+        return;
+    }
+    let until_period = elapsed_periods(now - activated_at, period_duration);
+    let claimed = DataKey::get_employee_claimed_periods(env, agreement_id, employee_index);
+
+    let key = ColaKey::SalaryHistory(agreement_id, employee.clone());
+    let mut prior = prior_salaries(env, agreement_id, employee, old_salary);
+    // Claimed periods no longer need the salary they were earned at.
+    while prior
+        .first()
+        .is_some_and(|entry| entry.until_period <= claimed)
+    {
+        prior.pop_front();
+    }
+    if until_period > claimed {
+        prior.push_back(PriorSalary {
+            until_period,
+            salary: old_salary,
+        });
+    }
+    if prior.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(
+            &key,
+            &SalaryHistory {
+                current: new_salary,
+                prior,
+            },
+        );
+        extend_persistent_ttl(env, &key);
+    }
+}
+
+/// Keeps the indexed salary used by the claim paths in line with the
+/// agreement's employee list.
+fn sync_indexed_salary(
+    env: &Env,
+    agreement_id: u128,
+    employee_index: u32,
+    employee: &Address,
+    salary: i128,
+) {
+    if DataKey::get_employee(env, agreement_id, employee_index).as_ref() == Some(employee)
+        && DataKey::get_employee_salary(env, agreement_id, employee_index).is_some()
+    {
+        DataKey::set_employee_salary(env, agreement_id, employee_index, salary);
+    }
+}
+
+fn record_adjustment(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    employee: Address,
+    old_salary: i128,
+    new_salary: i128,
+    raise_bps: u32,
+) {
+    let id = get_payroll_adjustment_count(env) + 1;
+    let sequence = next_sequence(env, SequenceCategory::Payroll);
+    let adjustment = PayrollAdjustment {
+        id,
+        employer: employer.clone(),
+        agreement_id,
+        employee: employee.clone(),
+        old_salary,
+        new_salary,
+        raise_bps,
+        applied_at: env.ledger().timestamp(),
+        sequence,
+    };
+    env.storage()
+        .persistent()
+        .set(&ColaKey::Adjustment(id), &adjustment);
+    env.storage()
+        .persistent()
+        .set(&ColaKey::AdjustmentCount, &id);
+
+    emit_payroll_adjusted(
+        env,
+        PayrollAdjustedEvent {
+            adjustment_id: id,
+            agreement_id,
+            employee,
+            old_salary,
+            new_salary,
+            sequence,
+        },
+    );
+}
@@ -0,0 +1,164 @@
+//! Unified pay runs: due salary and vested bonuses in one call.
+//!
+//! `disburse_total_compensation` pays an employee everything one employer
+//! owes them right now. Salary comes from each of the employer's payroll
+//! agreements the employee is on, claimed exactly as `claim_payroll` would.
+//! Bonuses come from the linked bonus_system contract, which claims every
+//! vested payout of the employee's approved incentives from that employer.
+//!
+//! The employee authorizes the whole run once. Their authorization must also
+//! cover the nested `claim_vested_incentives` call, because bonus_system
+//! checks it again on its side.
+//!
+//! Escrow agreements are not included. Their time-based claims are made by
+//! the contributor directly through `claim_time_based`.
+
+use soroban_sdk::{contracttype, vec, Address, Env, IntoVal, Symbol};
+
+use crate::confidential::is_confidential;
+use crate::events::{emit_compensation_disbursed, CompensationDisbursedEvent};
+use crate::pause_exemptions::{is_paused_for, PauseCategory};
+use crate::payroll::{
+    claim_payroll, get_agreement, get_employment_agreements, is_grace_period_active,
+};
+use crate::sequence::{next_sequence, SequenceCategory};
+use crate::storage::{AgreementMode, AgreementStatus, DataKey, PayrollError, StorageKey};
+
+#[contracttype]
+#[derive(Clone)]
+enum CompensationKey {
+    /// Address of the linked bonus_system contract -> Address
+    BonusContract,
+}
+
+/// Amounts paid by one `disburse_total_compensation` run. Each is summed
+/// across tokens; the per-payment events carry the token of every transfer.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CompensationDisbursement {
+    /// Gross salary claimed, before garnishments and savings.
+    pub salary_amount: i128,
+    /// Bonus amount claimed from the bonus contract.
+    pub bonus_amount: i128,
+    /// Number of agreements that paid salary.
+    pub agreements_paid: u32,
+    /// `Incentive` sequence number of the bonus claim (see
+    /// [`crate::sequence`]), if any bonus was paid. Each salary payout has
+    /// its own `Disbursement` number on its payslip.
+    pub incentive_sequence: Option<u64>,
+}
+
+/// Links the bonus_system contract whose incentives are paid out in unified
+/// pay runs.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_bonus_contract(
+    env: &Env,
+    owner: &Address,
+    bonus_contract: &Address,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    env.storage()
+        .persistent()
+        .set(&CompensationKey::BonusContract, bonus_contract);
+    Ok(())
+}
+
+/// Returns the linked bonus_system contract, if any.
+pub fn get_bonus_contract(env: &Env) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&CompensationKey::BonusContract)
+}
+
+/// Pays `employee` the salary due from every claimable payroll agreement with
+/// `employer` and then claims their vested bonuses from that employer.
+///
+/// Agreements that are paused, finished, or have no period due are skipped.
+/// Confidential salaries are skipped too, since only the employer can
+/// disburse them. Bonuses are only claimed when a bonus contract is linked.
+///
+/// # Errors
+/// * `PayrollError::EmergencyPaused` - the contract is paused
+/// * Any error from `claim_payroll` other than `NoPeriodsToClaim`, such as
+///   `InsufficientEscrowBalance` or `MultisigApprovalRequired`
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn disburse_total_compensation(
+    env: &Env,
+    employer: &Address,
+    employee: &Address,
+) -> Result<CompensationDisbursement, PayrollError> {
+    employee.require_auth();
+    if is_paused_for(env, PauseCategory::Compensation) {
+        return Err(PayrollError::EmergencyPaused);
+    }
+
+    let mut disbursement = CompensationDisbursement::default();
+    for agreement_id in get_employment_agreements(env, employer, employee).iter() {
+        let Some(agreement) = get_agreement(env, agreement_id) else {
+            continue;
+        };
+        let claimable = match agreement.status {
+            AgreementStatus::Active => true,
+            AgreementStatus::Cancelled => is_grace_period_active(env, agreement_id),
+            _ => false,
+        };
+        if agreement.mode != AgreementMode::Payroll || !claimable {
+            continue;
+        }
+
+        for index in 0..DataKey::get_employee_count(env, agreement_id) {
+            if DataKey::get_employee(env, agreement_id, index).as_ref() != Some(employee)
+                || is_confidential(env, agreement_id, index)
+            {
+                continue;
+            }
+            let paid_before = DataKey::get_agreement_paid_amount(env, agreement_id);
+            match claim_payroll(env, employee, agreement_id, index) {
+                Ok(()) => {
+                    let paid = DataKey::get_agreement_paid_amount(env, agreement_id) - paid_before;
+                    disbursement.salary_amount = disbursement
+                        .salary_amount
+                        .checked_add(paid)
+                        .ok_or(PayrollError::InvalidData)?;
+                    disbursement.agreements_paid += 1;
+                }
+                Err(PayrollError::NoPeriodsToClaim) => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    if let Some(bonus_contract) = get_bonus_contract(env) {
+        disbursement.bonus_amount = env.invoke_contract::<i128>(
+            &bonus_contract,
+            &Symbol::new(env, "claim_vested_incentives"),
+            vec![env, employee.into_val(env), employer.into_val(env)],
+        );
+        if disbursement.bonus_amount > 0 {
+            disbursement.incentive_sequence = Some(next_sequence(env, SequenceCategory::Incentive));
+        }
+    }
+
+    emit_compensation_disbursed(
+        env,
+        CompensationDisbursedEvent {
+            employer: employer.clone(),
+            employee: employee.clone(),
+            salary_amount: disbursement.salary_amount,
+            bonus_amount: disbursement.bonus_amount,
+            incentive_sequence: disbursement.incentive_sequence,
+        },
+    );
+    Ok(disbursement)
+}
@@ -0,0 +1,301 @@
+//! Confidential (commit–reveal) salary amounts for payroll agreements.
+//!
+//! In confidential mode the per-period salary of an employee is never kept in
+//! plaintext contract storage. The employer stores a salted SHA-256
+//! commitment instead, and reveals the real amount (plus salt) only at
+//! disbursement time, where it is verified against the stored commitment.
+//!
+//! The commitment preimage is bound to the agreement and employee slot so a
+//! commitment cannot be replayed for a different employee:
+//!
+//! `sha256(agreement_id (16 bytes BE) || employee_index (4 bytes BE) || amount (16 bytes BE) || salt (32 bytes))`
+//!
+//! Note that token transfers and payout events remain public; this only keeps
+//! casual observers from reading every salary out of the storage snapshot.
+
+use soroban_sdk::{contracttype, Bytes, BytesN, Env, Vec};
+
+use crate::approval_matrix;
+use crate::disbursement_windows::ensure_disbursement_allowed;
+use crate::events::{emit_payroll_claimed, PayrollClaimedEvent};
+use crate::hourly::is_hourly;
+use crate::metrics::{payout_timing, push_payout};
+use crate::pause_exemptions::{is_paused_for, PauseCategory};
+use crate::payroll::{get_agreement, is_grace_period_active, transfer_from_contract};
+use crate::payslip::{settle_payout, PayoutGross};
+use crate::reputation::record_payout_timing;
+use crate::sequence::{next_sequence, SequenceCategory};
+use crate::stats;
+use crate::storage::{
+    AgreementMode, AgreementStatus, DataKey, EmployeeInfo, PayrollError, StorageKey,
+};
+use crate::validation;
+use crate::versioned;
+use crate::webhooks::notify_payout;
+use crate::yield_strategy;
+
+#[contracttype]
+#[derive(Clone)]
+enum ConfidentialKey {
+    /// Salary commitment for `(agreement_id, employee_index)` -> BytesN<32>
+    SalaryCommitment(u128, u32),
+}
+
+/// Computes the salary commitment for an employee slot.
+///
+/// Exposed for off-chain tooling and tests. Callers should compute this
+/// locally rather than through an RPC simulation, which would disclose the
+/// amount to the RPC provider.
+pub fn compute_salary_commitment(
+    env: &Env,
+    agreement_id: u128,
+    employee_index: u32,
+    amount: i128,
+    salt: &BytesN<32>,
+) -> BytesN<32> {
+    let mut preimage = Bytes::new(env);
+    preimage.extend_from_array(&agreement_id.to_be_bytes());
+    preimage.extend_from_array(&employee_index.to_be_bytes());
+    preimage.extend_from_array(&amount.to_be_bytes());
+    preimage.append(&Bytes::from(salt.clone()));
+    env.crypto().sha256(&preimage).into()
+}
+
+/// Returns the stored salary commitment for an employee slot, if any.
+pub fn get_salary_commitment(
+    env: &Env,
+    agreement_id: u128,
+    employee_index: u32,
+) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&ConfidentialKey::SalaryCommitment(
+            agreement_id,
+            employee_index,
+        ))
+}
+
+/// Returns true when the employee slot is in confidential mode.
+pub fn is_confidential(env: &Env, agreement_id: u128, employee_index: u32) -> bool {
+    env.storage()
+        .persistent()
+        .has(&ConfidentialKey::SalaryCommitment(
+            agreement_id,
+            employee_index,
+        ))
+}
+
+/// Switches an employee slot to confidential mode by storing a salary commitment.
+///
+/// Any plaintext salary previously stored for the slot is scrubbed: the
+/// indexed `DataKey::EmployeeSalary` entry is removed, and the matching
+/// `EmployeeInfo` in the agreement's employee list is zeroed (with its amount
+/// removed from `Agreement::total_amount`). Calling this again replaces the
+/// commitment, which is how an employer rotates the salt or changes the salary.
+///
+/// # Arguments
+/// * `agreement_id` - Payroll agreement ID
+/// * `employee_index` - 0-based index of the employee within the agreement
+/// * `commitment` - Output of [`compute_salary_commitment`]
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn set_salary_commitment(
+    env: &Env,
+    agreement_id: u128,
+    employee_index: u32,
+    commitment: BytesN<32>,
+) -> Result<(), PayrollError> {
+    let mut agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    agreement.employer.require_auth();
+
+    // Hourly pay is computed from attested hours, never from a revealed salary.
+    if agreement.mode != AgreementMode::Payroll || is_hourly(env, agreement_id) {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+    if employee_index >= DataKey::get_employee_count(env, agreement_id) {
+        return Err(PayrollError::InvalidEmployeeIndex);
+    }
+    let employee = DataKey::get_employee(env, agreement_id, employee_index)
+        .ok_or(PayrollError::InvalidEmployeeIndex)?;
+
+    env.storage()
+        .persistent()
+        .remove(&DataKey::EmployeeSalary(agreement_id, employee_index));
+
+    let employees_key = StorageKey::AgreementEmployees(agreement_id);
+    let employees: Vec<EmployeeInfo> = env
+        .storage()
+        .persistent()
+        .get(&employees_key)
+        .unwrap_or(Vec::new(env));
+    let mut scrubbed: Vec<EmployeeInfo> = Vec::new(env);
+    for mut info in employees.iter() {
+        if info.address == employee && info.salary_per_period != 0 {
+            agreement.total_amount -= info.salary_per_period;
+            info.salary_per_period = 0;
+        }
+        scrubbed.push_back(info);
+    }
+    env.storage().persistent().set(&employees_key, &scrubbed);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
+
+    let key = ConfidentialKey::SalaryCommitment(agreement_id, employee_index);
+    env.storage().persistent().set(&key, &commitment);
+    crate::storage::extend_persistent_ttl(env, &key);
+
+    Ok(())
+}
+
+/// Disburses all elapsed, unclaimed periods to a confidential-mode employee.
+///
+/// The employer reveals `amount` (salary per period) and `salt`; the pair must
+/// hash to the stored commitment. Period accounting, escrow checks, and the
+/// large-payment multisig threshold are identical to `claim_payroll`.
+///
+/// # Arguments
+/// * `agreement_id` - Payroll agreement ID
+/// * `employee_index` - 0-based index of the employee within the agreement
+/// * `amount` - Revealed salary per period
+/// * `salt` - Salt used when computing the commitment
+///
+/// # Returns
+/// Total amount transferred to the employee.
+///
+/// # Errors
+/// * `PayrollError::SalaryCommitmentMismatch` - no commitment, or the reveal does not match
+/// * `PayrollError::NoPeriodsToClaim` - nothing has accrued since the last disbursement
+/// * `PayrollError::InsufficientEscrowBalance` - escrow cannot cover the payout
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn disburse_confidential_payroll(
+    env: &Env,
+    agreement_id: u128,
+    employee_index: u32,
+    amount: i128,
+    salt: BytesN<32>,
+) -> Result<i128, PayrollError> {
+    crate::payroll::acquire_reentrancy_guard(env)?;
+    let result = disburse_inner(env, agreement_id, employee_index, amount, salt);
+    crate::payroll::release_reentrancy_guard(env);
+    result
+}
+
+fn disburse_inner(
+    env: &Env,
+    agreement_id: u128,
+    employee_index: u32,
+    amount: i128,
+    salt: BytesN<32>,
+) -> Result<i128, PayrollError> {
+    if is_paused_for(env, PauseCategory::PayrollClaims) {
+        return Err(PayrollError::EmergencyPaused);
+    }
+
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    agreement.employer.require_auth();
+    ensure_disbursement_allowed(env, &agreement.employer)?;
+
+    if agreement.mode != AgreementMode::Payroll || is_hourly(env, agreement_id) {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+    let can_pay = match agreement.status {
+        AgreementStatus::Active => true,
+        AgreementStatus::Cancelled => is_grace_period_active(env, agreement_id),
+        _ => false,
+    };
+    if !can_pay {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let commitment = get_salary_commitment(env, agreement_id, employee_index)
+        .ok_or(PayrollError::SalaryCommitmentMismatch)?;
+    validation::max_amount(amount)?;
+    if amount <= 0
+        || compute_salary_commitment(env, agreement_id, employee_index, amount, &salt) != commitment
+    {
+        return Err(PayrollError::SalaryCommitmentMismatch);
+    }
+
+    let employee = DataKey::get_employee(env, agreement_id, employee_index)
+        .ok_or(PayrollError::InvalidEmployeeIndex)?;
+    let activation_time = DataKey::get_agreement_activation_time(env, agreement_id)
+        .ok_or(PayrollError::AgreementNotActivated)?;
+    let period_duration = DataKey::get_agreement_period_duration(env, agreement_id)
+        .ok_or(PayrollError::AgreementNotFound)?;
+    let token =
+        DataKey::get_agreement_token(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+
+    let now = env.ledger().timestamp();
+    if now < activation_time {
+        return Err(PayrollError::InvalidData);
+    }
+    let total_elapsed_periods =
+        crate::payroll::elapsed_periods(now - activation_time, period_duration);
+    let claimed_periods = DataKey::get_employee_claimed_periods(env, agreement_id, employee_index);
+    if total_elapsed_periods <= claimed_periods {
+        return Err(PayrollError::NoPeriodsToClaim);
+    }
+    let periods_to_pay = total_elapsed_periods - claimed_periods;
+
+    let payout = amount
+        .checked_mul(periods_to_pay as i128)
+        .ok_or(PayrollError::InvalidData)?;
+
+    approval_matrix::require_approvals(env, &agreement.employer, payout)?;
+
+    let liquid = yield_strategy::ensure_liquid(env, agreement_id, &token, payout);
+    let escrow_balance = DataKey::get_agreement_escrow_balance(env, agreement_id, &token);
+    if liquid < payout {
+        return Err(PayrollError::InsufficientEscrowBalance);
+    }
+
+    // Effects before the external transfer (checks-effects-interactions).
+    DataKey::set_agreement_escrow_balance(env, agreement_id, &token, escrow_balance - payout);
+    DataKey::set_employee_claimed_periods(
+        env,
+        agreement_id,
+        employee_index,
+        claimed_periods + periods_to_pay,
+    );
+    let timing = payout_timing(
+        env,
+        activation_time,
+        period_duration,
+        claimed_periods,
+        periods_to_pay,
+    );
+    push_payout(env, &employee, &agreement.employer, &token, payout, &timing);
+    record_payout_timing(env, &agreement.employer, timing);
+    notify_payout(env, &agreement.employer, &employee, agreement_id, payout);
+    let new_paid = DataKey::get_agreement_paid_amount(env, agreement_id)
+        .checked_add(payout)
+        .ok_or(PayrollError::InvalidData)?;
+    DataKey::set_agreement_paid_amount(env, agreement_id, new_paid);
+
+    // Garnishments and savings are settled first; the employee gets the rest.
+    stats::record_disbursement(env, &token, payout);
+    let sequence = next_sequence(env, SequenceCategory::Disbursement);
+    let net = settle_payout(
+        env,
+        agreement_id,
+        &employee,
+        &PayoutGross::new(&token, payout),
+        claimed_periods + periods_to_pay,
+        sequence,
+    );
+    transfer_from_contract(env, &token, &employee, net);
+
+    emit_payroll_claimed(
+        env,
+        PayrollClaimedEvent {
+            agreement_id,
+            employee: employee.clone(),
+            amount: payout,
+            sequence,
+        },
+    );
+
+    Ok(payout)
+}
@@ -0,0 +1,72 @@
+//! Checks against the governance credential registry.
+//!
+//! Governance issues non-transferable credentials to vetted auditors and
+//! mediators. Once the owner links the governance contract as the credential
+//! registry, assigning an arbiter requires a valid mediator credential and
+//! granting audit trail access requires a valid auditor credential. Validity
+//! is checked against the registry each time, so revocation and expiry apply
+//! immediately.
+//!
+//! Without a linked registry, arbiter assignment is unchecked as before and
+//! audit trail access cannot be granted. Payroll imports need a
+//! `PayrollAttestor` credential and are refused without a registry.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+use crate::storage::{PayrollError, StorageKey};
+
+/// Mirror of governance::CredentialRole — names must match for XDR decoding.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CredentialRole {
+    Auditor,
+    Mediator,
+    PayrollAttestor,
+}
+
+#[contractclient(name = "CredentialRegistryClient")]
+pub trait CredentialRegistryInterface {
+    fn has_valid_credential(env: Env, holder: Address, role: CredentialRole) -> bool;
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum CredentialKey {
+    /// Governance contract issuing credentials -> Address
+    Registry,
+}
+
+/// Links the contract issuing auditor and mediator credentials.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_credential_registry(
+    env: &Env,
+    owner: &Address,
+    registry: &Address,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    env.storage()
+        .persistent()
+        .set(&CredentialKey::Registry, registry);
+    Ok(())
+}
+
+/// Returns the linked credential registry, if any.
+pub fn get_credential_registry(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&CredentialKey::Registry)
+}
+
+/// Returns whether `holder` has a valid credential for `role`, or `None`
+/// when no registry is linked.
+pub(crate) fn has_credential(env: &Env, holder: &Address, role: CredentialRole) -> Option<bool> {
+    let registry = get_credential_registry(env)?;
+    Some(CredentialRegistryClient::new(env, &registry).has_valid_credential(holder, &role))
+}
@@ -0,0 +1,797 @@
+//! Currency groups and FX exposure reporting for multi-currency employers.
+//!
+//! An employer can group payroll agreements paid in the same token into a
+//! currency group and later switch the whole group to another token in one
+//! call. The migration converts every salary, the agreement totals and the
+//! amount paid so far at an employer-supplied rate, and returns the escrow
+//! still held in the old token to the employer, who then funds the escrow in
+//! the new token.
+//!
+//! [`migrate_payroll_token`] switches the token for a batch of employees
+//! instead, so an employer can roll a deprecated token out a few agreements
+//! at a time. It converts the escrow too: the old-token escrow goes back to
+//! the employer and the converted amount is deposited in the new token in the
+//! same call, so claims keep working throughout. A conversion rate that moves
+//! pay more than the deviation threshold away from 1:1 needs sign-off from
+//! the contract owner or from every affected employee, given beforehand with
+//! [`approve_token_migration`].
+//!
+//! `get_fx_exposure` summarizes an employer's upcoming obligations per token,
+//! so treasuries can see how much of each currency the next 1, 3 and 6
+//! periods will need next to what is already escrowed.
+
+use soroban_sdk::{contracttype, token::Client as TokenClient, Address, Env, Vec};
+
+use crate::events::{
+    emit_currency_group_migrated, emit_payroll_token_migrated, CurrencyGroupMigratedEvent,
+    PayrollTokenMigratedEvent,
+};
+use crate::hourly::get_hourly_config;
+use crate::pause_exemptions::{is_paused_for, PauseCategory};
+use crate::payroll::{
+    get_agreement, get_agreement_employees, get_employer_agreements, transfer_from_contract,
+    FX_SCALE,
+};
+use crate::sequence::{next_sequence, SequenceCategory};
+use crate::storage::{
+    Agreement, AgreementMode, AgreementStatus, DataKey, EmployeeInfo, PayrollError, StorageKey,
+    MAX_BATCH_SIZE,
+};
+use crate::validation;
+use crate::versioned;
+use crate::yield_strategy;
+
+/// Most agreements a currency group can hold, so a migration fits in one
+/// transaction.
+pub const MAX_CURRENCY_GROUP_SIZE: u32 = MAX_BATCH_SIZE;
+
+/// Deviation from a 1:1 conversion, in basis points, up to which
+/// [`migrate_payroll_token`] needs no sign-off until the owner sets another.
+pub const DEFAULT_MIGRATION_DEVIATION_BPS: u32 = 100;
+
+const BPS_DENOMINATOR: u32 = 10_000;
+
+#[contracttype]
+#[derive(Clone)]
+enum CurrencyGroupKey {
+    /// Last assigned group id -> u64
+    NextGroupId,
+    /// Group by id -> CurrencyGroup
+    Group(u64),
+    /// Group an agreement belongs to -> u64
+    AgreementGroup(u128),
+    /// Owner-set deviation threshold in basis points -> u32
+    MigrationThreshold,
+    /// Sign-off `(employer, signer)` -> TokenMigrationTerms
+    MigrationApproval(Address, Address),
+    /// Number of recorded payroll token migrations -> u64
+    MigrationCount,
+    /// Payroll token migration by 1-based id -> PayrollTokenMigration
+    Migration(u64),
+}
+
+/// Payroll agreements of one employer, paid in the same token, that switch
+/// token together.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CurrencyGroup {
+    pub id: u64,
+    pub employer: Address,
+    pub token: Address,
+    pub agreements: Vec<u128>,
+}
+
+/// An employer's upcoming obligations in one token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FxExposure {
+    pub token: Address,
+    /// Escrow currently held for the employer's agreements in this token.
+    pub escrow_balance: i128,
+    pub next_1_period: i128,
+    pub next_3_periods: i128,
+    pub next_6_periods: i128,
+}
+
+/// Terms of a payroll token migration an employee or the owner signs off.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenMigrationTerms {
+    pub old_token: Address,
+    pub new_token: Address,
+    /// New token units per old unit, in basis points.
+    pub conversion_rate_bps: u32,
+}
+
+/// One agreement switched to another token by `migrate_payroll_token`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayrollTokenMigration {
+    pub id: u64,
+    pub employer: Address,
+    pub agreement_id: u128,
+    pub old_token: Address,
+    pub new_token: Address,
+    pub conversion_rate_bps: u32,
+    /// Escrow returned to the employer in the old token.
+    pub old_escrow: i128,
+    /// Escrow deposited by the employer in the new token.
+    pub new_escrow: i128,
+    pub migrated_at: u64,
+    /// `Payroll` sequence number of the change (see [`crate::sequence`]).
+    pub sequence: u64,
+}
+
+/// Creates an empty currency group for agreements paid in `token`.
+///
+/// # Returns
+/// The new group's id
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn create_currency_group(env: &Env, employer: &Address, token: &Address) -> u64 {
+    employer.require_auth();
+    let id: u64 = env
+        .storage()
+        .persistent()
+        .get(&CurrencyGroupKey::NextGroupId)
+        .unwrap_or(0)
+        + 1;
+    env.storage()
+        .persistent()
+        .set(&CurrencyGroupKey::NextGroupId, &id);
+    save_group(
+        env,
+        &CurrencyGroup {
+            id,
+            employer: employer.clone(),
+            token: token.clone(),
+            agreements: Vec::new(env),
+        },
+    );
+    id
+}
+
+/// Adds one of the employer's payroll agreements to a currency group.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - group not found, the agreement is paid in
+///   another token, or it already belongs to a group
+/// * `PayrollError::Unauthorized` - caller does not own the group or the
+///   agreement
+/// * `PayrollError::AgreementNotFound` - agreement does not exist
+/// * `PayrollError::InvalidAgreementMode` - not a payroll agreement
+/// * `PayrollError::BatchTooLarge` - the group already holds
+///   [`MAX_CURRENCY_GROUP_SIZE`] agreements
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn add_to_currency_group(
+    env: &Env,
+    employer: &Address,
+    group_id: u64,
+    agreement_id: u128,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    let mut group = owned_group(env, employer, group_id)?;
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    if agreement.mode != AgreementMode::Payroll {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+    let group_key = CurrencyGroupKey::AgreementGroup(agreement_id);
+    if agreement.token != group.token || env.storage().persistent().has(&group_key) {
+        return Err(PayrollError::InvalidData);
+    }
+    if group.agreements.len() >= MAX_CURRENCY_GROUP_SIZE {
+        return Err(PayrollError::BatchTooLarge);
+    }
+    group.agreements.push_back(agreement_id);
+    save_group(env, &group);
+    env.storage().persistent().set(&group_key, &group_id);
+    Ok(())
+}
+
+/// Removes an agreement from a currency group.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - group not found, or the agreement is not
+///   in it
+/// * `PayrollError::Unauthorized` - caller does not own the group
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn remove_from_currency_group(
+    env: &Env,
+    employer: &Address,
+    group_id: u64,
+    agreement_id: u128,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    let mut group = owned_group(env, employer, group_id)?;
+    let pos = group
+        .agreements
+        .first_index_of(agreement_id)
+        .ok_or(PayrollError::InvalidData)?;
+    group.agreements.remove(pos);
+    save_group(env, &group);
+    env.storage()
+        .persistent()
+        .remove(&CurrencyGroupKey::AgreementGroup(agreement_id));
+    Ok(())
+}
+
+/// Returns a currency group by id.
+pub fn get_currency_group(env: &Env, group_id: u64) -> Option<CurrencyGroup> {
+    env.storage()
+        .persistent()
+        .get(&CurrencyGroupKey::Group(group_id))
+}
+
+/// Returns the id of the currency group an agreement belongs to, if any.
+pub fn get_agreement_currency_group(env: &Env, agreement_id: u128) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&CurrencyGroupKey::AgreementGroup(agreement_id))
+}
+
+/// Switches every agreement of a currency group to `new_token`.
+///
+/// `conversion_rate` is the number of `new_token` units per old token unit,
+/// scaled by 1e6 like the rates set with `set_exchange_rate`. Salaries,
+/// `total_amount` and `paid_amount` are converted at that rate, rounding
+/// down. The escrow left in the old token is returned to the employer.
+/// Cancelled and completed agreements keep their token and leave the group.
+///
+/// # Returns
+/// The number of agreements migrated
+///
+/// # Errors
+/// * `PayrollError::EmergencyPaused` - the contract is paused
+/// * `PayrollError::InvalidData` - group not found, `new_token` is the
+///   group's token, or `conversion_rate` is not positive
+/// * `PayrollError::Unauthorized` - caller does not own the group
+/// * `PayrollError::InvalidAgreementMode` - an agreement has confidential
+///   salaries, which cannot be converted
+/// * `PayrollError::ExchangeRateOverflow` - a converted amount overflows
+/// * `PayrollError::ExchangeRateInvalid` - a salary converts to zero
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn migrate_group_token(
+    env: &Env,
+    employer: &Address,
+    group_id: u64,
+    new_token: &Address,
+    conversion_rate: i128,
+) -> Result<u32, PayrollError> {
+    employer.require_auth();
+    if is_paused_for(env, PauseCategory::TokenMigrations) {
+        return Err(PayrollError::EmergencyPaused);
+    }
+    let mut group = owned_group(env, employer, group_id)?;
+    if conversion_rate <= 0 || *new_token == group.token {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let mut to_migrate = Vec::new(env);
+    let mut remaining = Vec::new(env);
+    for agreement_id in group.agreements.iter() {
+        let Some(agreement) = get_agreement(env, agreement_id) else {
+            continue;
+        };
+        if matches!(
+            agreement.status,
+            AgreementStatus::Cancelled | AgreementStatus::Completed
+        ) {
+            env.storage()
+                .persistent()
+                .remove(&CurrencyGroupKey::AgreementGroup(agreement_id));
+            continue;
+        }
+        let employee_count = DataKey::get_employee_count(env, agreement_id);
+        if (0..employee_count)
+            .any(|index| crate::confidential::is_confidential(env, agreement_id, index))
+        {
+            return Err(PayrollError::InvalidAgreementMode);
+        }
+        remaining.push_back(agreement_id);
+        to_migrate.push_back(agreement);
+    }
+
+    let old_token = group.token.clone();
+    for agreement in to_migrate.iter() {
+        migrate_agreement(env, agreement, &old_token, new_token, conversion_rate)?;
+    }
+
+    group.token = new_token.clone();
+    group.agreements = remaining;
+    save_group(env, &group);
+
+    emit_currency_group_migrated(
+        env,
+        CurrencyGroupMigratedEvent {
+            group_id,
+            employer: employer.clone(),
+            old_token,
+            new_token: new_token.clone(),
+            conversion_rate,
+            agreements: to_migrate.len(),
+        },
+    );
+    Ok(to_migrate.len())
+}
+
+/// Sets the largest deviation from a 1:1 conversion, in basis points, that
+/// [`migrate_payroll_token`] applies without sign-off.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+/// * `PayrollError::InvalidData` - `max_deviation_bps` is above 10_000
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_migration_threshold(
+    env: &Env,
+    owner: &Address,
+    max_deviation_bps: u32,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    if max_deviation_bps > BPS_DENOMINATOR {
+        return Err(PayrollError::InvalidData);
+    }
+    env.storage()
+        .persistent()
+        .set(&CurrencyGroupKey::MigrationThreshold, &max_deviation_bps);
+    Ok(())
+}
+
+/// Returns the deviation threshold of [`migrate_payroll_token`], in basis
+/// points.
+pub fn get_migration_threshold(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&CurrencyGroupKey::MigrationThreshold)
+        .unwrap_or(DEFAULT_MIGRATION_DEVIATION_BPS)
+}
+
+/// Signs off a payroll token migration of `employer` on `terms`, replacing
+/// the signer's previous sign-off. It counts when the signer is the owner or
+/// an employee the migration affects, and is used up by the migration.
+///
+/// # Access Control
+/// Requires signer authentication
+pub fn approve_token_migration(
+    env: &Env,
+    signer: &Address,
+    employer: &Address,
+    terms: TokenMigrationTerms,
+) {
+    signer.require_auth();
+    env.storage().persistent().set(
+        &CurrencyGroupKey::MigrationApproval(employer.clone(), signer.clone()),
+        &terms,
+    );
+}
+
+/// Returns the signer's pending sign-off of a migration by `employer`.
+pub fn get_token_migration_approval(
+    env: &Env,
+    employer: &Address,
+    signer: &Address,
+) -> Option<TokenMigrationTerms> {
+    env.storage()
+        .persistent()
+        .get(&CurrencyGroupKey::MigrationApproval(
+            employer.clone(),
+            signer.clone(),
+        ))
+}
+
+/// Switches the payroll of `employees` from `old_token` to `new_token`.
+///
+/// Every open payroll agreement of the employer paid in `old_token` with a
+/// listed employee is migrated as [`migrate_group_token`] does, at
+/// `conversion_rate_bps` new token units per old unit. The token is set per
+/// agreement, so all of an agreement's employees must be listed. The escrow
+/// returned in the old token is converted at the same rate and deposited by
+/// the employer in the new token. Migrated agreements leave their currency
+/// group, and each one is recorded as a [`PayrollTokenMigration`].
+///
+/// When the rate deviates from 1:1 by more than
+/// [`get_migration_threshold`], the owner or every affected
+/// employee must have signed off these terms with
+/// [`approve_token_migration`]; the sign-offs are used up.
+///
+/// # Returns
+/// The number of agreements migrated
+///
+/// # Errors
+/// * `PayrollError::EmergencyPaused` - the contract is paused
+/// * `PayrollError::BatchTooLarge` - more than `MAX_BATCH_SIZE` employees
+/// * `PayrollError::InvalidData` - `conversion_rate_bps` is zero, the tokens
+///   are the same, no agreement matches, or an agreement has employees that
+///   are not listed
+/// * `PayrollError::NoEmployee` - a listed employee is on none of the
+///   employer's agreements in `old_token`
+/// * `PayrollError::InvalidAgreementMode` - an agreement has confidential
+///   salaries
+/// * `PayrollError::Unauthorized` - sign-off is required and missing
+/// * `PayrollError::ExchangeRateOverflow` - a converted amount overflows
+/// * `PayrollError::ExchangeRateInvalid` - a salary converts to zero
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn migrate_payroll_token(
+    env: &Env,
+    employer: &Address,
+    employees: Vec<Address>,
+    old_token: &Address,
+    new_token: &Address,
+    conversion_rate_bps: u32,
+) -> Result<u32, PayrollError> {
+    employer.require_auth();
+    if is_paused_for(env, PauseCategory::TokenMigrations) {
+        return Err(PayrollError::EmergencyPaused);
+    }
+    validation::items(&employees)?;
+    if conversion_rate_bps == 0 || new_token == old_token {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let mut to_migrate: Vec<Agreement> = Vec::new(env);
+    let mut affected: Vec<Address> = Vec::new(env);
+    for agreement_id in get_employer_agreements(env, employer).iter() {
+        let Some(agreement) = get_agreement(env, agreement_id) else {
+            continue;
+        };
+        if agreement.mode != AgreementMode::Payroll
+            || agreement.token != *old_token
+            || matches!(
+                agreement.status,
+                AgreementStatus::Cancelled | AgreementStatus::Completed
+            )
+        {
+            continue;
+        }
+        let staff = get_agreement_employees(env, agreement_id);
+        let listed = staff.iter().filter(|e| employees.contains(e)).count() as u32;
+        if listed == 0 {
+            continue;
+        }
+        if listed != staff.len() {
+            return Err(PayrollError::InvalidData);
+        }
+        let employee_count = DataKey::get_employee_count(env, agreement_id);
+        if (0..employee_count)
+            .any(|index| crate::confidential::is_confidential(env, agreement_id, index))
+        {
+            return Err(PayrollError::InvalidAgreementMode);
+        }
+        for employee in staff.iter() {
+            if !affected.contains(&employee) {
+                affected.push_back(employee);
+            }
+        }
+        to_migrate.push_back(agreement);
+    }
+    if to_migrate.is_empty() {
+        return Err(PayrollError::InvalidData);
+    }
+    if employees.iter().any(|e| !affected.contains(&e)) {
+        return Err(PayrollError::NoEmployee);
+    }
+
+    let terms = TokenMigrationTerms {
+        old_token: old_token.clone(),
+        new_token: new_token.clone(),
+        conversion_rate_bps,
+    };
+    if conversion_rate_bps.abs_diff(BPS_DENOMINATOR) > get_migration_threshold(env) {
+        require_sign_off(env, employer, &terms, &affected)?;
+    }
+
+    let conversion_rate = i128::from(conversion_rate_bps) * FX_SCALE / i128::from(BPS_DENOMINATOR);
+    for agreement in to_migrate.iter() {
+        let agreement_id = agreement.id;
+        let old_escrow = migrate_agreement(env, agreement, old_token, new_token, conversion_rate)?;
+        let new_escrow = convert(old_escrow, conversion_rate)?;
+        if new_escrow > 0 {
+            TokenClient::new(env, new_token).transfer(
+                employer,
+                env.current_contract_address(),
+                &new_escrow,
+            );
+            let balance = DataKey::get_agreement_escrow_balance(env, agreement_id, new_token)
+                .checked_add(new_escrow)
+                .ok_or(PayrollError::ExchangeRateOverflow)?;
+            DataKey::set_agreement_escrow_balance(env, agreement_id, new_token, balance);
+        }
+        leave_group(env, agreement_id);
+        record_migration(env, employer, agreement_id, &terms, old_escrow, new_escrow);
+    }
+
+    emit_payroll_token_migrated(
+        env,
+        PayrollTokenMigratedEvent {
+            employer: employer.clone(),
+            old_token: old_token.clone(),
+            new_token: new_token.clone(),
+            conversion_rate_bps,
+            agreements: to_migrate.len(),
+            employees: affected.len(),
+        },
+    );
+    Ok(to_migrate.len())
+}
+
+/// Returns the number of recorded payroll token migrations.
+pub fn get_token_migration_count(env: &Env) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&CurrencyGroupKey::MigrationCount)
+        .unwrap_or(0)
+}
+
+/// Returns a recorded payroll token migration by its 1-based id.
+pub fn get_token_migration(env: &Env, migration_id: u64) -> Option<PayrollTokenMigration> {
+    env.storage()
+        .persistent()
+        .get(&CurrencyGroupKey::Migration(migration_id))
+}
+
+/// Summarizes the employer's obligations per token for its next 1, 3 and 6
+/// periods, counted per agreement in that agreement's own period length.
+///
+/// Cancelled and completed agreements are left out. Payroll agreements owe
+/// the sum of their salaries per period; hourly ones are counted at their
+/// hour cap, the most a period can cost. Escrow agreements owe their amount
+/// per period until their last period. Confidential salaries are not known
+/// to the contract and are not included.
+pub fn get_fx_exposure(env: &Env, employer: &Address) -> Vec<FxExposure> {
+    let mut report: Vec<FxExposure> = Vec::new(env);
+    for agreement_id in get_employer_agreements(env, employer).iter() {
+        let Some(agreement) = get_agreement(env, agreement_id) else {
+            continue;
+        };
+        if matches!(
+            agreement.status,
+            AgreementStatus::Cancelled | AgreementStatus::Completed
+        ) {
+            continue;
+        }
+        let escrow_balance =
+            DataKey::get_agreement_escrow_balance(env, agreement_id, &agreement.token);
+        let pos = match report.iter().position(|e| e.token == agreement.token) {
+            Some(pos) => pos as u32,
+            None => {
+                report.push_back(FxExposure {
+                    token: agreement.token.clone(),
+                    escrow_balance: 0,
+                    next_1_period: 0,
+                    next_3_periods: 0,
+                    next_6_periods: 0,
+                });
+                report.len() - 1
+            }
+        };
+        let mut entry = report.get_unchecked(pos);
+        entry.escrow_balance = entry.escrow_balance.saturating_add(escrow_balance);
+        entry.next_1_period = entry
+            .next_1_period
+            .saturating_add(obligation(env, &agreement, 1));
+        entry.next_3_periods = entry
+            .next_3_periods
+            .saturating_add(obligation(env, &agreement, 3));
+        entry.next_6_periods = entry
+            .next_6_periods
+            .saturating_add(obligation(env, &agreement, 6));
+        report.set(pos, entry);
+    }
+    report
+}
+
+fn owned_group(
+    env: &Env,
+    employer: &Address,
+    group_id: u64,
+) -> Result<CurrencyGroup, PayrollError> {
+    let group = get_currency_group(env, group_id).ok_or(PayrollError::InvalidData)?;
+    if group.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    Ok(group)
+}
+
+fn save_group(env: &Env, group: &CurrencyGroup) {
+    env.storage()
+        .persistent()
+        .set(&CurrencyGroupKey::Group(group.id), group);
+}
+
+/// Amount `agreement` owes over its next `periods` periods.
+fn obligation(env: &Env, agreement: &Agreement, periods: u32) -> i128 {
+    match agreement.mode {
+        AgreementMode::Payroll => {
+            let employees: Vec<EmployeeInfo> = env
+                .storage()
+                .persistent()
+                .get(&StorageKey::AgreementEmployees(agreement.id))
+                .unwrap_or(Vec::new(env));
+            let hours = get_hourly_config(env, agreement.id)
+                .map_or(1, |config| i128::from(config.max_hours_per_period));
+            employees
+                .iter()
+                .fold(0i128, |sum, e| sum.saturating_add(e.salary_per_period))
+                .saturating_mul(hours)
+                .saturating_mul(i128::from(periods))
+        }
+        AgreementMode::Escrow => {
+            let left = agreement
+                .num_periods
+                .unwrap_or(0)
+                .saturating_sub(agreement.claimed_periods.unwrap_or(0));
+            agreement
+                .amount_per_period
+                .unwrap_or(0)
+                .saturating_mul(i128::from(periods.min(left)))
+        }
+    }
+}
+
+fn convert(amount: i128, conversion_rate: i128) -> Result<i128, PayrollError> {
+    Ok(amount
+        .checked_mul(conversion_rate)
+        .ok_or(PayrollError::ExchangeRateOverflow)?
+        / FX_SCALE)
+}
+
+fn migrate_agreement(
+    env: &Env,
+    mut agreement: Agreement,
+    old_token: &Address,
+    new_token: &Address,
+    conversion_rate: i128,
+) -> Result<i128, PayrollError> {
+    let agreement_id = agreement.id;
+
+    let employees_key = StorageKey::AgreementEmployees(agreement_id);
+    let mut employees: Vec<EmployeeInfo> = env
+        .storage()
+        .persistent()
+        .get(&employees_key)
+        .unwrap_or(Vec::new(env));
+    for index in 0..employees.len() {
+        let mut info = employees.get_unchecked(index);
+        let salary = convert(info.salary_per_period, conversion_rate)?;
+        if salary == 0 && info.salary_per_period > 0 {
+            return Err(PayrollError::ExchangeRateInvalid);
+        }
+        info.salary_per_period = salary;
+        employees.set(index, info);
+    }
+    env.storage().persistent().set(&employees_key, &employees);
+
+    // The indexed salaries read by the claim paths are converted too.
+    for index in 0..DataKey::get_employee_count(env, agreement_id) {
+        if let Some(salary) = DataKey::get_employee_salary(env, agreement_id, index) {
+            let converted = convert(salary, conversion_rate)?;
+            if converted == 0 && salary > 0 {
+                return Err(PayrollError::ExchangeRateInvalid);
+            }
+            DataKey::set_employee_salary(env, agreement_id, index, converted);
+        }
+    }
+
+    let paid = DataKey::get_agreement_paid_amount(env, agreement_id);
+    if paid != 0 {
+        DataKey::set_agreement_paid_amount(env, agreement_id, convert(paid, conversion_rate)?);
+    }
+    if DataKey::get_agreement_token(env, agreement_id).is_some() {
+        DataKey::set_agreement_token(env, agreement_id, new_token);
+    }
+
+    agreement.total_amount = convert(agreement.total_amount, conversion_rate)?;
+    agreement.paid_amount = convert(agreement.paid_amount, conversion_rate)?;
+    agreement.token = new_token.clone();
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
+
+    let escrow = yield_strategy::release_escrow(env, agreement_id, old_token);
+    if escrow > 0 {
+        DataKey::set_agreement_escrow_balance(env, agreement_id, old_token, 0);
+        transfer_from_contract(env, old_token, &agreement.employer, escrow);
+    }
+    Ok(escrow.max(0))
+}
+
+/// Uses up the owner's sign-off of `terms`, or else every affected
+/// employee's.
+fn require_sign_off(
+    env: &Env,
+    employer: &Address,
+    terms: &TokenMigrationTerms,
+    employees: &Vec<Address>,
+) -> Result<(), PayrollError> {
+    let owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if let Some(owner) = owner {
+        if take_approval(env, employer, &owner, terms) {
+            return Ok(());
+        }
+    }
+    for employee in employees.iter() {
+        if !take_approval(env, employer, &employee, terms) {
+            return Err(PayrollError::Unauthorized);
+        }
+    }
+    Ok(())
+}
+
+fn take_approval(
+    env: &Env,
+    employer: &Address,
+    signer: &Address,
+    terms: &TokenMigrationTerms,
+) -> bool {
+    if get_token_migration_approval(env, employer, signer).as_ref() != Some(terms) {
+        return false;
+    }
+    env.storage()
+        .persistent()
+        .remove(&CurrencyGroupKey::MigrationApproval(
+            employer.clone(),
+            signer.clone(),
+        ));
+    true
+}
+
+/// Takes an agreement out of its currency group, whose token it no longer
+/// matches.
+fn leave_group(env: &Env, agreement_id: u128) {
+    let Some(group_id) = get_agreement_currency_group(env, agreement_id) else {
+        return;
+    };
+    if let Some(mut group) = get_currency_group(env, group_id) {
+        if let Some(pos) = group.agreements.first_index_of(agreement_id) {
+            group.agreements.remove(pos);
+            save_group(env, &group);
+        }
+    }
+    env.storage()
+        .persistent()
+        .remove(&CurrencyGroupKey::AgreementGroup(agreement_id));
+}
+
+fn record_migration(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    terms: &TokenMigrationTerms,
+    old_escrow: i128,
+    new_escrow: i128,
+) {
+    let id = get_token_migration_count(env) + 1;
+    let migration = PayrollTokenMigration {
+        id,
+        employer: employer.clone(),
+        agreement_id,
+        old_token: terms.old_token.clone(),
+        new_token: terms.new_token.clone(),
+        conversion_rate_bps: terms.conversion_rate_bps,
+        old_escrow,
+        new_escrow,
+        migrated_at: env.ledger().timestamp(),
+        sequence: next_sequence(env, SequenceCategory::Payroll),
+    };
+    env.storage()
+        .persistent()
+        .set(&CurrencyGroupKey::Migration(id), &migration);
+    env.storage()
+        .persistent()
+        .set(&CurrencyGroupKey::MigrationCount, &id);
+}
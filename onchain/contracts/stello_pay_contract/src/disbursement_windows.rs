@@ -0,0 +1,237 @@
+//! Employer-level disbursement windows.
+//!
+//! An employer can restrict when their agreements pay out:
+//!
+//! - **Business hours**: a daily UTC window, optionally on weekdays only.
+//! - **Blackouts**: absolute `[start, end)` periods with no payouts at all,
+//!   such as a month-end close or an audit freeze.
+//!
+//! Payroll claims (on every claim path), time-based escrow claims, milestone
+//! claims and confidential disbursements fail with
+//! `PayrollError::TimelockActive` outside the permitted windows.
+//! [`get_next_allowed_disbursement_time`] resolves the earliest moment they
+//! will succeed again. Invoices and reimbursements are approved by the
+//! employer, who controls their timing, and are not restricted.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::storage::{PayrollError, MAX_BATCH_SIZE};
+
+const DAY_SECONDS: u64 = 86_400;
+
+/// Most blackouts an employer can have scheduled at once.
+pub const MAX_BLACKOUTS: u32 = MAX_BATCH_SIZE;
+
+#[contracttype]
+#[derive(Clone)]
+enum DisbursementWindowKey {
+    /// Windows of an employer -> (Option<BusinessHours>, Vec<BlackoutWindow>)
+    Windows(Address),
+}
+
+/// Daily window, in seconds after UTC midnight, during which payouts run.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BusinessHours {
+    pub open: u32,
+    /// Exclusive; at most 86_400.
+    pub close: u32,
+    /// Also exclude Saturdays and Sundays (UTC).
+    pub weekdays_only: bool,
+}
+
+/// Period `[start, end)` with no payouts.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlackoutWindow {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Sets or clears the employer's business hours.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `open` is not before `close`, or `close`
+///   is past the end of the day
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn set_business_hours(
+    env: &Env,
+    employer: &Address,
+    hours: Option<BusinessHours>,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    if let Some(hours) = &hours {
+        if hours.open >= hours.close || u64::from(hours.close) > DAY_SECONDS {
+            return Err(PayrollError::InvalidData);
+        }
+    }
+    let (_, blackouts) = load_windows(env, employer);
+    save_windows(env, employer, hours, blackouts);
+    Ok(())
+}
+
+/// Schedules a blackout from `start` until `end`. Blackouts that have ended
+/// are dropped.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `start` is not before `end`, or `end` has
+///   already passed
+/// * `PayrollError::BatchTooLarge` - [`MAX_BLACKOUTS`] are already scheduled
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn add_blackout(
+    env: &Env,
+    employer: &Address,
+    start: u64,
+    end: u64,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    if start >= end || end <= env.ledger().timestamp() {
+        return Err(PayrollError::InvalidData);
+    }
+    let (hours, mut blackouts) = load_windows(env, employer);
+    if blackouts.len() >= MAX_BLACKOUTS {
+        return Err(PayrollError::BatchTooLarge);
+    }
+    blackouts.push_back(BlackoutWindow { start, end });
+    save_windows(env, employer, hours, blackouts);
+    Ok(())
+}
+
+/// Removes the blackout starting at `start`.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no scheduled blackout starts at `start`
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn remove_blackout(env: &Env, employer: &Address, start: u64) -> Result<(), PayrollError> {
+    employer.require_auth();
+    let (hours, mut blackouts) = load_windows(env, employer);
+    let index = blackouts
+        .iter()
+        .position(|blackout| blackout.start == start)
+        .ok_or(PayrollError::InvalidData)?;
+    blackouts.remove(index as u32);
+    save_windows(env, employer, hours, blackouts);
+    Ok(())
+}
+
+/// Returns the employer's business hours, if set.
+pub fn get_business_hours(env: &Env, employer: &Address) -> Option<BusinessHours> {
+    load_windows(env, employer).0
+}
+
+/// Returns the employer's blackouts that have not ended yet, in the order
+/// they were added.
+pub fn get_blackouts(env: &Env, employer: &Address) -> Vec<BlackoutWindow> {
+    load_windows(env, employer).1
+}
+
+/// Returns the earliest moment, now or later, at which the employer's
+/// agreements can pay out.
+pub fn get_next_allowed_disbursement_time(env: &Env, employer: &Address) -> u64 {
+    let (hours, blackouts) = load_windows(env, employer);
+    next_allowed_time(&hours, &blackouts, env.ledger().timestamp())
+}
+
+/// Earliest moment at or after `at` outside every blackout and, if set,
+/// within business hours.
+pub(crate) fn next_allowed_time(
+    hours: &Option<BusinessHours>,
+    blackouts: &Vec<BlackoutWindow>,
+    mut at: u64,
+) -> u64 {
+    // Every pass that moves `at` either only aligns it to business hours,
+    // after which the next pass settles, or moves it past a blackout, which
+    // it cannot re-enter.
+    for _ in 0..2 * blackouts.len() + 2 {
+        let mut next = at;
+        if let Some(hours) = hours {
+            next = next_business_time(hours, next);
+        }
+        for blackout in blackouts.iter() {
+            if blackout.start <= next && next < blackout.end {
+                next = blackout.end;
+            }
+        }
+        if next == at {
+            break;
+        }
+        at = next;
+    }
+    at
+}
+
+/// Fails unless the employer's agreements can pay out now.
+///
+/// # Errors
+/// * `PayrollError::TimelockActive` - outside business hours or in a blackout
+pub(crate) fn ensure_disbursement_allowed(
+    env: &Env,
+    employer: &Address,
+) -> Result<(), PayrollError> {
+    if get_next_allowed_disbursement_time(env, employer) > env.ledger().timestamp() {
+        return Err(PayrollError::TimelockActive);
+    }
+    Ok(())
+}
+
+/// Loads the employer's business hours and the blackouts not yet ended.
+pub(crate) fn load_windows(
+    env: &Env,
+    employer: &Address,
+) -> (Option<BusinessHours>, Vec<BlackoutWindow>) {
+    let (hours, stored): (Option<BusinessHours>, Vec<BlackoutWindow>) = env
+        .storage()
+        .persistent()
+        .get(&DisbursementWindowKey::Windows(employer.clone()))
+        .unwrap_or((None, Vec::new(env)));
+    let now = env.ledger().timestamp();
+    let mut blackouts = Vec::new(env);
+    for blackout in stored.iter() {
+        if blackout.end > now {
+            blackouts.push_back(blackout);
+        }
+    }
+    (hours, blackouts)
+}
+
+fn save_windows(
+    env: &Env,
+    employer: &Address,
+    hours: Option<BusinessHours>,
+    blackouts: Vec<BlackoutWindow>,
+) {
+    env.storage().persistent().set(
+        &DisbursementWindowKey::Windows(employer.clone()),
+        &(hours, blackouts),
+    );
+}
+
+/// Earliest moment at or after `at` within business hours.
+fn next_business_time(hours: &BusinessHours, at: u64) -> u64 {
+    let day = at / DAY_SECONDS;
+    let second = at % DAY_SECONDS;
+    if is_business_day(hours, day) {
+        if second < u64::from(hours.open) {
+            return day * DAY_SECONDS + u64::from(hours.open);
+        }
+        if second < u64::from(hours.close) {
+            return at;
+        }
+    }
+    let mut next_day = day + 1;
+    while !is_business_day(hours, next_day) {
+        next_day += 1;
+    }
+    next_day * DAY_SECONDS + u64::from(hours.open)
+}
+
+fn is_business_day(hours: &BusinessHours, day: u64) -> bool {
+    // Day 0 (1970-01-01) was a Thursday; Monday is weekday 0.
+    !hours.weekdays_only || (day + 3) % 7 < 5
+}
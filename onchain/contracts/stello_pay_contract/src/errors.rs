@@ -0,0 +1,422 @@
+//! Error metadata.
+//!
+//! Every [`PayrollError`] code maps to an [`ErrorInfo`] so CLIs and frontends
+//! render the same message for the same failure. Severance insurance and the
+//! other modules of this contract report through `PayrollError` too, so one
+//! catalog covers them all.
+//!
+//! The English `description` is for logs and developer tooling. User-facing
+//! text should be looked up by `remediation`, a stable key into the
+//! per-language catalogs in `docs/error-catalog/`; several codes share a key
+//! when the fix is the same.
+
+use soroban_sdk::{contracttype, Env, String, Symbol};
+
+use crate::storage::PayrollError;
+
+/// What kind of problem an error reports.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ErrorCategory {
+    /// The caller is not allowed to perform the operation.
+    Auth,
+    /// Balances, transfers or amounts do not add up.
+    Funds,
+    /// The operation is too early or too frequent; retrying later may work.
+    Timing,
+    /// The agreement or contract configuration does not allow the operation.
+    Config,
+    /// An argument is malformed or names something that does not exist.
+    Input,
+    /// The agreement or record is not in a state that allows the operation.
+    State,
+}
+
+/// Structured description of an error code, as returned by `get_error_info`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ErrorInfo {
+    /// Numeric `PayrollError` code
+    pub code: u32,
+    /// Variant name, e.g. `InsufficientEscrowBalance`
+    pub name: String,
+    pub category: ErrorCategory,
+    /// Short English description
+    pub description: String,
+    /// Key of the suggested fix in the error catalogs
+    pub remediation: Symbol,
+}
+
+use ErrorCategory::{Auth, Config, Funds, Input, State, Timing};
+use PayrollError as E;
+
+/// `(error, name, category, description, remediation)` for every variant.
+const CATALOG: [(PayrollError, &str, ErrorCategory, &str, &str); 50] = [
+    (
+        E::DisputeAlreadyRaised,
+        "DisputeAlreadyRaised",
+        State,
+        "A dispute is already open for this agreement",
+        "wait_for_dispute_resolution",
+    ),
+    (
+        E::NotInGracePeriod,
+        "NotInGracePeriod",
+        Timing,
+        "The agreement is not in its grace period",
+        "wait_for_grace_period",
+    ),
+    (
+        E::NotParty,
+        "NotParty",
+        Auth,
+        "The caller is not a party to the agreement",
+        "sign_as_agreement_party",
+    ),
+    (
+        E::NotArbiter,
+        "NotArbiter",
+        Auth,
+        "The caller is not the arbiter",
+        "sign_as_arbiter",
+    ),
+    (
+        E::InvalidPayout,
+        "InvalidPayout",
+        Funds,
+        "The payout split exceeds the funds locked in the agreement",
+        "reduce_payout",
+    ),
+    (
+        E::ActiveDispute,
+        "ActiveDispute",
+        State,
+        "The operation is blocked while a dispute is open",
+        "wait_for_dispute_resolution",
+    ),
+    (
+        E::AgreementNotFound,
+        "AgreementNotFound",
+        Input,
+        "No agreement exists with this id",
+        "check_agreement_id",
+    ),
+    (
+        E::NoDispute,
+        "NoDispute",
+        State,
+        "The agreement has no open dispute",
+        "raise_dispute_first",
+    ),
+    (
+        E::NoEmployee,
+        "NoEmployee",
+        Input,
+        "The employee is not part of the agreement",
+        "check_employee",
+    ),
+    (
+        E::NotActivated,
+        "NotActivated",
+        State,
+        "The agreement has not been activated",
+        "activate_agreement",
+    ),
+    (
+        E::Unauthorized,
+        "Unauthorized",
+        Auth,
+        "The caller is not allowed to perform this operation",
+        "sign_with_authorized_account",
+    ),
+    (
+        E::InvalidEmployeeIndex,
+        "InvalidEmployeeIndex",
+        Input,
+        "The employee index is out of range",
+        "check_employee",
+    ),
+    (
+        E::InvalidData,
+        "InvalidData",
+        Input,
+        "An argument or stored record is malformed or inconsistent",
+        "check_arguments",
+    ),
+    (
+        E::TransferFailed,
+        "TransferFailed",
+        Funds,
+        "The token transfer failed",
+        "check_token_balance",
+    ),
+    (
+        E::InsufficientEscrowBalance,
+        "InsufficientEscrowBalance",
+        Funds,
+        "The escrow does not cover the payment",
+        "fund_escrow",
+    ),
+    (
+        E::NoPeriodsToClaim,
+        "NoPeriodsToClaim",
+        Timing,
+        "No new period has elapsed since the last claim",
+        "wait_for_next_period",
+    ),
+    (
+        E::AgreementNotActivated,
+        "AgreementNotActivated",
+        State,
+        "The agreement has not been activated",
+        "activate_agreement",
+    ),
+    (
+        E::InvalidAgreementMode,
+        "InvalidAgreementMode",
+        Config,
+        "The operation does not apply to this agreement's mode",
+        "use_matching_agreement_mode",
+    ),
+    (
+        E::AgreementPaused,
+        "AgreementPaused",
+        State,
+        "The agreement is paused",
+        "resume_agreement",
+    ),
+    (
+        E::AllPeriodsClaimed,
+        "AllPeriodsClaimed",
+        State,
+        "Every period of the agreement has been claimed",
+        "no_action_needed",
+    ),
+    (
+        E::ZeroAmountPerPeriod,
+        "ZeroAmountPerPeriod",
+        Config,
+        "The amount per period must be positive",
+        "set_positive_amount",
+    ),
+    (
+        E::ZeroPeriodDuration,
+        "ZeroPeriodDuration",
+        Config,
+        "The period duration must be positive",
+        "set_positive_period_duration",
+    ),
+    (
+        E::ZeroNumPeriods,
+        "ZeroNumPeriods",
+        Config,
+        "The number of periods must be positive",
+        "set_positive_period_count",
+    ),
+    (
+        E::EmergencyPaused,
+        "EmergencyPaused",
+        State,
+        "The contract is under an emergency pause",
+        "wait_for_emergency_unpause",
+    ),
+    (
+        E::NotGuardian,
+        "NotGuardian",
+        Auth,
+        "The caller is not an emergency guardian",
+        "sign_as_guardian",
+    ),
+    (
+        E::TimelockActive,
+        "TimelockActive",
+        Timing,
+        "A timelock has not expired yet",
+        "wait_for_timelock",
+    ),
+    (
+        E::InvalidTimelock,
+        "InvalidTimelock",
+        Config,
+        "The timelock duration is invalid",
+        "check_timelock",
+    ),
+    (
+        E::MultisigApprovalRequired,
+        "MultisigApprovalRequired",
+        Auth,
+        "The operation needs multisig approval",
+        "collect_multisig_approvals",
+    ),
+    (
+        E::ExchangeRateNotFound,
+        "ExchangeRateNotFound",
+        Config,
+        "No exchange rate is configured for the currency pair",
+        "configure_exchange_rate",
+    ),
+    (
+        E::ExchangeRateOverflow,
+        "ExchangeRateOverflow",
+        Funds,
+        "The currency conversion overflowed",
+        "reduce_payout",
+    ),
+    (
+        E::ExchangeRateInvalid,
+        "ExchangeRateInvalid",
+        Config,
+        "The exchange rate is not positive",
+        "configure_exchange_rate",
+    ),
+    (
+        E::GraceExtensionInvalid,
+        "GraceExtensionInvalid",
+        Input,
+        "The grace period extension is invalid",
+        "check_grace_extension",
+    ),
+    (
+        E::GraceExtensionCapExceeded,
+        "GraceExtensionCapExceeded",
+        Config,
+        "The extension exceeds the configured cap",
+        "check_grace_extension",
+    ),
+    (
+        E::RateLimited,
+        "RateLimited",
+        Timing,
+        "The caller made too many requests",
+        "retry_later",
+    ),
+    (
+        E::BatchTooLarge,
+        "BatchTooLarge",
+        Input,
+        "The batch has too many items",
+        "split_batch",
+    ),
+    (
+        E::MilestoneAmountInvalid,
+        "MilestoneAmountInvalid",
+        Config,
+        "Milestone amounts must be positive",
+        "set_positive_amount",
+    ),
+    (
+        E::MilestoneAgreementInvalidStatus,
+        "MilestoneAgreementInvalidStatus",
+        State,
+        "The milestone agreement's status does not allow this operation",
+        "check_agreement_status",
+    ),
+    (
+        E::MilestoneNotFound,
+        "MilestoneNotFound",
+        Input,
+        "No milestone exists with this id",
+        "check_milestone_id",
+    ),
+    (
+        E::MilestoneAlreadyApproved,
+        "MilestoneAlreadyApproved",
+        State,
+        "The milestone has already been approved",
+        "no_action_needed",
+    ),
+    (
+        E::MilestoneNotApproved,
+        "MilestoneNotApproved",
+        State,
+        "The milestone has not been approved yet",
+        "wait_for_milestone_approval",
+    ),
+    (
+        E::MilestoneAlreadyClaimed,
+        "MilestoneAlreadyClaimed",
+        State,
+        "The milestone has already been claimed",
+        "no_action_needed",
+    ),
+    (
+        E::EmployeeAlreadyExists,
+        "EmployeeAlreadyExists",
+        Input,
+        "The employee is already part of the agreement",
+        "check_employee",
+    ),
+    (
+        E::ReentrancyDetected,
+        "ReentrancyDetected",
+        State,
+        "A claim re-entered the contract during a token transfer",
+        "check_token_contract",
+    ),
+    (
+        E::InvalidArbiter,
+        "InvalidArbiter",
+        Input,
+        "The arbiter cannot be the caller or the current arbiter",
+        "choose_another_arbiter",
+    ),
+    (
+        E::MilestoneAlreadyRejected,
+        "MilestoneAlreadyRejected",
+        State,
+        "The milestone has already been rejected",
+        "no_action_needed",
+    ),
+    (
+        E::MilestoneAlreadyApprovedCannotReject,
+        "MilestoneAlreadyApprovedCannotReject",
+        State,
+        "An approved milestone cannot be rejected",
+        "no_action_needed",
+    ),
+    (
+        E::MilestoneAlreadyClaimedCannotReject,
+        "MilestoneAlreadyClaimedCannotReject",
+        State,
+        "A claimed milestone cannot be rejected",
+        "no_action_needed",
+    ),
+    (
+        E::SalaryCommitmentMismatch,
+        "SalaryCommitmentMismatch",
+        Input,
+        "The revealed salary does not match its commitment",
+        "check_salary_reveal",
+    ),
+    (
+        E::ColaPolicyNotFound,
+        "ColaPolicyNotFound",
+        Config,
+        "The employer has no cost-of-living adjustment policy",
+        "configure_cola_policy",
+    ),
+    (
+        E::ColaNotDue,
+        "ColaNotDue",
+        Timing,
+        "The cost-of-living adjustment is not due yet",
+        "wait_for_cola_due_date",
+    ),
+];
+
+/// Returns the metadata of error `code`, or `None` for codes this contract
+/// never returns.
+pub fn get_error_info(env: &Env, code: u32) -> Option<ErrorInfo> {
+    CATALOG
+        .iter()
+        .find(|(error, ..)| *error as u32 == code)
+        .map(
+            |(error, name, category, description, remediation)| ErrorInfo {
+                code: *error as u32,
+                name: String::from_str(env, name),
+                category: *category,
+                description: String::from_str(env, description),
+                remediation: Symbol::new(env, remediation),
+            },
+        )
+}
@@ -1,5 +1,97 @@
-use crate::storage::AgreementMode;
-use soroban_sdk::{contractevent, Address, Env};
+//! Contract events and their emit helpers.
+//!
+//! Every helper publishes at an [`EventLevel`], and the owner picks with
+//! `set_event_verbosity` which levels are published, so high-volume
+//! deployments can cut per-transaction event costs:
+//!
+//! | Verbosity | Published levels |
+//! |-----------|------------------|
+//! | `Full` (default) | Critical, Standard, Detail |
+//! | `Minimal` | Critical, Standard |
+//! | `CriticalOnly` | Critical |
+//!
+//! Critical events (funds leaving the contract, disputes, ownership, upgrades,
+//! migrations and audit records) are always published. Every event that
+//! carries a sequence number is at least Standard, so below `Full` sequences
+//! stay gapless; under `CriticalOnly` indexers should expect gaps in the
+//! `Payroll` category.
+
+use crate::storage::{AgreementMode, PayrollError, StorageKey};
+use soroban_sdk::{contractevent, contracttype, Address, BytesN, Env, Event};
+
+/// Which event levels are published; see the module docs.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EventVerbosity {
+    Full,
+    Minimal,
+    CriticalOnly,
+}
+
+/// How important an event is. Lower levels are dropped first.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum EventLevel {
+    /// Funds leaving the contract and changes of control or audit records.
+    Critical,
+    /// Lifecycle and configuration changes.
+    Standard,
+    /// Details repeated by another event or readable from storage.
+    Detail,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum EventKey {
+    /// Event verbosity -> EventVerbosity
+    Verbosity,
+}
+
+/// Returns the event verbosity, `Full` unless the owner lowered it.
+pub fn get_event_verbosity(env: &Env) -> EventVerbosity {
+    env.storage()
+        .instance()
+        .get(&EventKey::Verbosity)
+        .unwrap_or(EventVerbosity::Full)
+}
+
+/// Sets which event levels are published from now on.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - `owner` is not the contract owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_event_verbosity(
+    env: &Env,
+    owner: &Address,
+    verbosity: EventVerbosity,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    if verbosity == EventVerbosity::Full {
+        env.storage().instance().remove(&EventKey::Verbosity);
+    } else {
+        env.storage()
+            .instance()
+            .set(&EventKey::Verbosity, &verbosity);
+    }
+    Ok(())
+}
+
+/// Publishes `event` if the configured verbosity includes `level`.
+fn publish(env: &Env, level: EventLevel, event: &impl Event) {
+    let lowest = match get_event_verbosity(env) {
+        EventVerbosity::Full => EventLevel::Detail,
+        EventVerbosity::Minimal => EventLevel::Standard,
+        EventVerbosity::CriticalOnly => EventLevel::Critical,
+    };
+    if level <= lowest {
+        event.publish(env);
+    }
+}
 
 #[contractevent]
 #[derive(Clone, Debug)]
@@ -23,6 +115,19 @@ pub struct MilestoneClaimed {
     pub milestone_id: u32,
     pub amount: i128,
     pub to: Address,
+    pub sequence: u64,
+}
+
+pub fn emit_milestone_added(env: &Env, event: MilestoneAdded) {
+    publish(env, EventLevel::Detail, &event);
+}
+
+pub fn emit_milestone_approved(env: &Env, event: MilestoneApproved) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+pub fn emit_milestone_claimed(env: &Env, event: MilestoneClaimed) {
+    publish(env, EventLevel::Critical, &event);
 }
 
 /// Event: Agreement created
@@ -32,6 +137,7 @@ pub struct AgreementCreatedEvent {
     pub agreement_id: u128,
     pub employer: Address,
     pub mode: AgreementMode,
+    pub sequence: u64,
 }
 
 /// Event: Agreement activated
@@ -39,6 +145,7 @@ pub struct AgreementCreatedEvent {
 #[derive(Clone, Debug)]
 pub struct AgreementActivatedEvent {
     pub agreement_id: u128,
+    pub sequence: u64,
 }
 
 /// Event: Employee added to agreement
@@ -48,6 +155,7 @@ pub struct EmployeeAddedEvent {
     pub agreement_id: u128,
     pub employee: Address,
     pub salary_per_period: i128,
+    pub sequence: u64,
 }
 
 /// Event: Payroll claimed by employee
@@ -57,6 +165,7 @@ pub struct PayrollClaimedEvent {
     pub agreement_id: u128,
     pub employee: Address,
     pub amount: i128,
+    pub sequence: u64,
 }
 
 /// Event: Agreement paused
@@ -64,6 +173,7 @@ pub struct PayrollClaimedEvent {
 #[derive(Clone, Debug)]
 pub struct AgreementPausedEvent {
     pub agreement_id: u128,
+    pub sequence: u64,
 }
 
 /// Event: Agreement resumed
@@ -71,6 +181,7 @@ pub struct AgreementPausedEvent {
 #[derive(Clone, Debug)]
 pub struct AgreementResumedEvent {
     pub agreement_id: u128,
+    pub sequence: u64,
 }
 
 /// Event: Payment sent
@@ -82,6 +193,7 @@ pub struct PaymentSentEvent {
     pub to: Address,
     pub amount: i128,
     pub token: Address,
+    pub sequence: u64,
 }
 
 /// Event: Payment received
@@ -92,6 +204,7 @@ pub struct PaymentReceivedEvent {
     pub to: Address,
     pub amount: i128,
     pub token: Address,
+    pub sequence: u64,
 }
 
 /// Event: Contract storage migration applied
@@ -103,19 +216,19 @@ pub struct ContractMigratedEvent {
 }
 
 pub fn emit_contract_migrated(env: &Env, event: ContractMigratedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Critical, &event);
 }
 
 pub fn emit_agreement_created(env: &Env, event: AgreementCreatedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Standard, &event);
 }
 
 pub fn emit_agreement_activated(env: &Env, event: AgreementActivatedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Standard, &event);
 }
 
 pub fn emit_employee_added(env: &Env, event: EmployeeAddedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Standard, &event);
 }
 
 /// Event: ArbiterSet
@@ -126,7 +239,7 @@ pub struct ArbiterSetEvent {
 }
 
 pub fn emit_set_arbiter(env: &Env, event: ArbiterSetEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Standard, &event);
 }
 
 /// Event: ArbiteDisputeRaisedrSet
@@ -137,7 +250,7 @@ pub struct DisputeRaisedEvent {
 }
 
 pub fn emit_dsipute_raised(env: &Env, event: DisputeRaisedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Critical, &event);
 }
 
 /// Event: ArbiteDisputeRaisedrSet
@@ -150,26 +263,26 @@ pub struct DisputeResolvedEvent {
 }
 
 pub fn emit_dsipute_resolved(env: &Env, event: DisputeResolvedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Critical, &event);
 }
 pub fn emit_payroll_claimed(env: &Env, event: PayrollClaimedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Critical, &event);
 }
 
 pub fn emit_agreement_paused(env: &Env, event: AgreementPausedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Standard, &event);
 }
 
 pub fn emit_agreement_resumed(env: &Env, event: AgreementResumedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Standard, &event);
 }
 
 pub fn emit_payment_sent(env: &Env, event: PaymentSentEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Critical, &event);
 }
 
 pub fn emit_payment_received(env: &Env, event: PaymentReceivedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Detail, &event);
 }
 
 /// Event: Agreement cancelled
@@ -177,10 +290,11 @@ pub fn emit_payment_received(env: &Env, event: PaymentReceivedEvent) {
 #[derive(Clone, Debug)]
 pub struct AgreementCancelledEvent {
     pub agreement_id: u128,
+    pub sequence: u64,
 }
 
 pub fn emit_agreement_cancelled(env: &Env, event: AgreementCancelledEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Critical, &event);
 }
 
 /// Event: Grace period finalized
@@ -188,10 +302,11 @@ pub fn emit_agreement_cancelled(env: &Env, event: AgreementCancelledEvent) {
 #[derive(Clone, Debug)]
 pub struct GracePeriodFinalizedEvent {
     pub agreement_id: u128,
+    pub sequence: u64,
 }
 
 pub fn emit_grace_period_finalized(env: &Env, event: GracePeriodFinalizedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Critical, &event);
 }
 
 /// Event: Grace period extended (audit trail for employer or owner).
@@ -208,7 +323,7 @@ pub struct GracePeriodExtendedEvent {
 }
 
 pub fn emit_grace_period_extended(env: &Env, event: GracePeriodExtendedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Standard, &event);
 }
 
 /// Event: Batch payroll claimed
@@ -222,7 +337,7 @@ pub struct BatchPayrollClaimedEvent {
 }
 
 pub fn emit_batch_payroll_claimed(env: &Env, event: BatchPayrollClaimedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Critical, &event);
 }
 
 /// Event: Batch milestone claimed
@@ -236,7 +351,7 @@ pub struct BatchMilestoneClaimedEvent {
 }
 
 pub fn emit_batch_milestone_claimed(env: &Env, event: BatchMilestoneClaimedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Critical, &event);
 }
 
 /// Event: Milestone agreement funded by employer.
@@ -254,7 +369,7 @@ pub struct MilestoneFundedEvent {
 }
 
 pub fn emit_milestone_funded(env: &Env, event: MilestoneFundedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Standard, &event);
 }
 
 /// Event: Exchange rate set via `set_exchange_rate` or `set_exchange_rate_admin`.
@@ -272,7 +387,7 @@ pub struct ExchangeRateChangedEvent {
 }
 
 pub fn emit_exchange_rate_changed(env: &Env, event: ExchangeRateChangedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Standard, &event);
 }
 
 /// Event: multisig approval configuration changed via `set_multisig_config`.
@@ -291,7 +406,7 @@ pub struct MultisigConfigChangedEvent {
 }
 
 pub fn emit_multisig_config_changed(env: &Env, event: MultisigConfigChangedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Critical, &event);
 }
 
 /// Event: A milestone was rejected by the employer.
@@ -316,5 +431,614 @@ pub struct MilestoneRejectedEvent {
 
 /// Emits a [`MilestoneRejectedEvent`] for the given rejection.
 pub fn emit_milestone_rejected(env: &Env, event: MilestoneRejectedEvent) {
-    event.publish(env);
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: An employer's audit period was anchored to its chain head.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AuditPeriodAnchoredEvent {
+    pub employer: Address,
+    pub period: u64,
+    pub root: soroban_sdk::BytesN<32>,
+    pub entry_count: u32,
+}
+
+pub fn emit_audit_period_anchored(env: &Env, event: AuditPeriodAnchoredEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A cost-of-living raise was applied to an employee's salary.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PayrollAdjustedEvent {
+    pub adjustment_id: u64,
+    pub agreement_id: u128,
+    pub employee: Address,
+    pub old_salary: i128,
+    pub new_salary: i128,
+    pub sequence: u64,
+}
+
+pub fn emit_payroll_adjusted(env: &Env, event: PayrollAdjustedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: A garnishment deduction was withheld from a payout and paid to the
+/// order's recipient.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct GarnishmentWithheldEvent {
+    pub order_id: u64,
+    pub agreement_id: u128,
+    pub employee: Address,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+pub fn emit_garnishment_withheld(env: &Env, event: GarnishmentWithheldEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A unified pay run paid an employee's due salary and vested bonuses
+/// from one employer.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CompensationDisbursedEvent {
+    pub employer: Address,
+    pub employee: Address,
+    pub salary_amount: i128,
+    pub bonus_amount: i128,
+    /// `Incentive` sequence number, if bonuses were paid.
+    pub incentive_sequence: Option<u64>,
+}
+
+pub fn emit_compensation_disbursed(env: &Env, event: CompensationDisbursedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: Income was added to the protocol treasury.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct TreasuryCreditedEvent {
+    pub token: Address,
+    pub amount: i128,
+}
+
+pub fn emit_treasury_credited(env: &Env, event: TreasuryCreditedEvent) {
+    publish(env, EventLevel::Detail, &event);
+}
+
+/// Event: A treasury spend was proposed and is waiting out its timelock.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct TreasurySpendProposedEvent {
+    pub proposal_id: u128,
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub executable_at: u64,
+}
+
+pub fn emit_treasury_spend_proposed(env: &Env, event: TreasurySpendProposedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: A treasury spend proposal was executed.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct TreasurySpendExecutedEvent {
+    pub proposal_id: u128,
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+}
+
+pub fn emit_treasury_spend_executed(env: &Env, event: TreasurySpendExecutedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: An approved lender funded a salary loan.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct LoanFundedEvent {
+    pub loan_id: u64,
+    pub lender: Address,
+    pub employee: Address,
+    pub principal: i128,
+    pub amount_due: i128,
+}
+
+pub fn emit_loan_funded(env: &Env, event: LoanFundedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A payout routed its pledged share to a salary loan's lender.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct LoanRepaidEvent {
+    pub loan_id: u64,
+    pub agreement_id: u128,
+    pub lender: Address,
+    pub amount: i128,
+    pub outstanding: i128,
+}
+
+pub fn emit_loan_repaid(env: &Env, event: LoanRepaidEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A salary loan defaulted with `outstanding` still owed.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct LoanDefaultedEvent {
+    pub loan_id: u64,
+    pub employee: Address,
+    pub outstanding: i128,
+}
+
+pub fn emit_loan_defaulted(env: &Env, event: LoanDefaultedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A contributor submitted an invoice against an escrow agreement.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct InvoiceSubmittedEvent {
+    pub agreement_id: u128,
+    pub number: u32,
+    pub contributor: Address,
+    pub amount: i128,
+    pub due_date: u64,
+}
+
+pub fn emit_invoice_submitted(env: &Env, event: InvoiceSubmittedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: An approved invoice was paid from escrow.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct InvoicePaidEvent {
+    pub agreement_id: u128,
+    pub number: u32,
+    pub contributor: Address,
+    pub amount: i128,
+}
+
+pub fn emit_invoice_paid(env: &Env, event: InvoicePaidEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: The employer disputed an invoice.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct InvoiceDisputedEvent {
+    pub agreement_id: u128,
+    pub number: u32,
+    pub employer: Address,
+}
+
+pub fn emit_invoice_disputed(env: &Env, event: InvoiceDisputedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: An employee submitted an expense reimbursement request.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ReimbursementSubmittedEvent {
+    pub request_id: u64,
+    pub employee: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+pub fn emit_reimbursement_submitted(env: &Env, event: ReimbursementSubmittedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: An approved reimbursement was paid from the employer's pool.
+///
+/// Emitted instead of the salary `PaymentSentEvent` / `PaymentReceivedEvent`
+/// so indexers keep reimbursements apart from pay.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ReimbursementPaidEvent {
+    pub request_id: u64,
+    pub employer: Address,
+    pub employee: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub payslip_id: u64,
+    pub sequence: u64,
+}
+
+pub fn emit_reimbursement_paid(env: &Env, event: ReimbursementPaidEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A reimbursement request was rejected.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ReimbursementRejectedEvent {
+    pub request_id: u64,
+    pub employer: Address,
+    pub rejected_by: Address,
+}
+
+pub fn emit_reimbursement_rejected(env: &Env, event: ReimbursementRejectedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: A timekeeper attested an employee's hours for a period of an
+/// hourly agreement.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct HoursAttestedEvent {
+    pub agreement_id: u128,
+    pub employee: Address,
+    pub period: u32,
+    pub hours: u32,
+}
+
+pub fn emit_hours_attested(env: &Env, event: HoursAttestedEvent) {
+    publish(env, EventLevel::Detail, &event);
+}
+
+/// Event: A currency group was switched to a new token.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct CurrencyGroupMigratedEvent {
+    pub group_id: u64,
+    pub employer: Address,
+    pub old_token: Address,
+    pub new_token: Address,
+    pub conversion_rate: i128,
+    /// Agreements moved to the new token.
+    pub agreements: u32,
+}
+
+pub fn emit_currency_group_migrated(env: &Env, event: CurrencyGroupMigratedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: An employee registered or replaced their action-signing key.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct SigningKeyRegisteredEvent {
+    pub employee: Address,
+    pub public_key: BytesN<32>,
+}
+
+pub fn emit_signing_key_registered(env: &Env, event: SigningKeyRegisteredEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: A relayed action signed by an employee was executed.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct SignedActionExecutedEvent {
+    pub employee: Address,
+    pub nonce: u64,
+    pub relayer: Address,
+}
+
+pub fn emit_signed_action_executed(env: &Env, event: SignedActionExecutedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: The owner renounced ownership, leaving the contract to a guardian.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct OwnershipRenouncedEvent {
+    pub former_owner: Address,
+    pub guardian: Address,
+}
+
+pub fn emit_ownership_renounced(env: &Env, event: OwnershipRenouncedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A governance-approved WASM hash was added to the upgrade allowlist.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct UpgradeRatifiedEvent {
+    pub wasm_hash: BytesN<32>,
+}
+
+pub fn emit_upgrade_ratified(env: &Env, event: UpgradeRatifiedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: An employer bought severance cover for an employee.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct SeveranceCoverPurchasedEvent {
+    pub agreement_id: u128,
+    pub employer: Address,
+    pub employee: Address,
+    pub severance_amount: i128,
+    pub premium: i128,
+}
+
+pub fn emit_severance_cover_purchased(env: &Env, event: SeveranceCoverPurchasedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A terminated employee's severance was paid.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct SeveranceSettledEvent {
+    pub agreement_id: u128,
+    pub employer: Address,
+    pub employee: Address,
+    pub from_employer: i128,
+    /// Paid by the insurance pool and owed back by the employer.
+    pub from_pool: i128,
+}
+
+pub fn emit_severance_settled(env: &Env, event: SeveranceSettledEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: An employer repaid severance the insurance pool paid for it.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct SeveranceLiabilityRepaidEvent {
+    pub employer: Address,
+    pub amount: i128,
+    pub remaining: i128,
+}
+
+pub fn emit_severance_liability_repaid(env: &Env, event: SeveranceLiabilityRepaidEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: An employer extended a job offer and locked its signing bonus.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct OfferExtendedEvent {
+    pub offer_id: u64,
+    pub employer: Address,
+    pub candidate: Address,
+    pub agreement_id: u128,
+    pub signing_bonus: i128,
+    pub accept_by: u64,
+}
+
+pub fn emit_offer_extended(env: &Env, event: OfferExtendedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: A candidate accepted a job offer.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct OfferAcceptedEvent {
+    pub offer_id: u64,
+    pub candidate: Address,
+}
+
+pub fn emit_offer_accepted(env: &Env, event: OfferAcceptedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: A candidate claimed an offer's signing bonus after onboarding.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct OfferBonusClaimedEvent {
+    pub offer_id: u64,
+    pub candidate: Address,
+    pub amount: i128,
+}
+
+pub fn emit_offer_bonus_claimed(env: &Env, event: OfferBonusClaimedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A lapsed or cancelled offer's signing bonus went back to the employer.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct OfferRefundedEvent {
+    pub offer_id: u64,
+    pub employer: Address,
+    pub amount: i128,
+}
+
+pub fn emit_offer_refunded(env: &Env, event: OfferRefundedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A user registered or replaced their passkey.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PasskeyRegisteredEvent {
+    pub user: Address,
+    pub public_key: BytesN<65>,
+}
+
+pub fn emit_passkey_registered(env: &Env, event: PasskeyRegisteredEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: An employer's sponsorship pool paid a relayer's withdrawal fee.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct WithdrawalSponsoredEvent {
+    pub employer: Address,
+    pub employee: Address,
+    pub relayer: Address,
+    pub fee: i128,
+}
+
+pub fn emit_withdrawal_sponsored(env: &Env, event: WithdrawalSponsoredEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: An employer switched a batch of employees' payroll to another token.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PayrollTokenMigratedEvent {
+    pub employer: Address,
+    pub old_token: Address,
+    pub new_token: Address,
+    pub conversion_rate_bps: u32,
+    pub agreements: u32,
+    pub employees: u32,
+}
+
+pub fn emit_payroll_token_migrated(env: &Env, event: PayrollTokenMigratedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: An employer breached its punctuality SLA for the configured number
+/// of consecutive windows.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct SlaBreachedEvent {
+    pub employer: Address,
+    /// Window that completed the breach streak.
+    pub window: u64,
+    pub on_time_bps: u32,
+    pub alerts: u32,
+    pub required_prefunding_periods: u32,
+}
+
+pub fn emit_sla_breached(env: &Env, event: SlaBreachedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: An employer routed idle escrow of an agreement to a yield strategy.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct YieldDepositedEvent {
+    pub agreement_id: u128,
+    pub strategy: Address,
+    pub amount: i128,
+}
+
+pub fn emit_yield_deposited(env: &Env, event: YieldDepositedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: An agreement's strategy position was closed. `earned` is the yield
+/// credited to the employer, negative for a loss taken from the escrow.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct YieldWithdrawnEvent {
+    pub agreement_id: u128,
+    pub strategy: Address,
+    pub principal: i128,
+    pub earned: i128,
+}
+
+pub fn emit_yield_withdrawn(env: &Env, event: YieldWithdrawnEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: A receipt was issued for a payroll payout.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ReceiptIssuedEvent {
+    #[topic]
+    pub employee: Address,
+    pub receipt_hash: BytesN<32>,
+    pub audit_id: u64,
+}
+
+pub fn emit_receipt_issued(env: &Env, event: ReceiptIssuedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: An employer began winding down. `escrowed` was pulled from it to
+/// cover final payouts due by `final_payout_at`.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct WinddownStartedEvent {
+    #[topic]
+    pub employer: Address,
+    pub agreements: u32,
+    pub escrowed: i128,
+    pub final_payout_at: u64,
+}
+
+pub fn emit_winddown_started(env: &Env, event: WinddownStartedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: An employer's wind-down completed and its residual escrow was
+/// returned.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct WinddownCompletedEvent {
+    #[topic]
+    pub employer: Address,
+    pub agreements: u32,
+}
+
+pub fn emit_winddown_completed(env: &Env, event: WinddownCompletedEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: Payrolls were imported from an attested off-chain export.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PayrollImportedEvent {
+    #[topic]
+    pub employer: Address,
+    pub attestor: Address,
+    pub agreements: u32,
+    pub export_hash: BytesN<32>,
+}
+
+pub fn emit_payroll_imported(env: &Env, event: PayrollImportedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: An employer started streaming salary to an employee.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct StreamCreatedEvent {
+    #[topic]
+    pub employee: Address,
+    pub agreement_id: u128,
+    pub employer: Address,
+    pub token: Address,
+    pub rate_per_second: i128,
+    pub deposit: i128,
+}
+
+pub fn emit_stream_created(env: &Env, event: StreamCreatedEvent) {
+    publish(env, EventLevel::Standard, &event);
+}
+
+/// Event: An employee withdrew streamed salary accrued up to
+/// `withdrawn_through`.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct StreamWithdrawnEvent {
+    #[topic]
+    pub employee: Address,
+    pub agreement_id: u128,
+    pub employer: Address,
+    pub amount: i128,
+    pub withdrawn_through: u64,
+}
+
+pub fn emit_stream_withdrawn(env: &Env, event: StreamWithdrawnEvent) {
+    publish(env, EventLevel::Critical, &event);
+}
+
+/// Event: An employer stopped a salary stream, paying out what had accrued.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct StreamStoppedEvent {
+    #[topic]
+    pub employee: Address,
+    pub agreement_id: u128,
+    pub employer: Address,
+    pub paid: i128,
+}
+
+pub fn emit_stream_stopped(env: &Env, event: StreamStoppedEvent) {
+    publish(env, EventLevel::Critical, &event);
 }
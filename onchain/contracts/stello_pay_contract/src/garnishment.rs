@@ -0,0 +1,303 @@
+//! Garnishment orders (mandatory deductions from an employee's pay).
+//!
+//! The owner or a compliance admin registers an order against an employee:
+//! a recipient, the token it applies to, a deduction per payout (basis points
+//! of the gross or a fixed amount) and a total cap. Every periodic payroll
+//! payout in that token then routes the deduction to the recipient before the
+//! employee is paid, until the cap is reached and the order completes.
+//!
+//! A payout claimed in another token than the agreement's base token is
+//! garnished on its base-token gross: the deduction is worked out in the
+//! order's token and paid to the recipient in the payout token, converted at
+//! the payout's own rate. Choosing the payout token never skips an order.
+//!
+//! Several orders can be active for one employee; they are applied in
+//! registration order, each against what is left of the payout, so the
+//! employee's net never goes negative. Each deduction is listed on the
+//! payout's [`Payslip`](crate::payslip::Payslip).
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::events::{emit_garnishment_withheld, GarnishmentWithheldEvent};
+use crate::payroll::transfer_from_contract;
+use crate::payslip::{PayoutGross, PayslipDeduction};
+use crate::rounding::{apply_bps, RoundingMode};
+use crate::storage::{PayrollError, StorageKey};
+use crate::validation;
+
+/// Maximum number of active orders per employee, bounding the work added to
+/// every payout.
+pub const MAX_GARNISHMENTS_PER_EMPLOYEE: u32 = 5;
+
+const BPS_DENOMINATOR: i128 = 10_000;
+
+#[contracttype]
+#[derive(Clone)]
+enum GarnishmentKey {
+    /// Address allowed to manage orders alongside the owner -> Address
+    ComplianceAdmin,
+    /// Next order id -> u64
+    NextOrderId,
+    /// Order by id -> GarnishmentOrder
+    Order(u64),
+    /// Active order ids for an employee, in registration order -> Vec<u64>
+    EmployeeOrders(Address),
+}
+
+/// How much an order withholds from each payout.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GarnishmentDeduction {
+    /// Basis points of the gross payout (10000 = 100%).
+    Bps(u32),
+    /// Fixed amount per payout.
+    Fixed(i128),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GarnishmentOrder {
+    pub id: u64,
+    pub employee: Address,
+    pub recipient: Address,
+    pub token: Address,
+    pub deduction: GarnishmentDeduction,
+    pub total_cap: i128,
+    pub total_withheld: i128,
+    /// False once the cap is reached or the order is released.
+    pub active: bool,
+    pub registered_at: u64,
+}
+
+/// Sets the compliance admin allowed to manage garnishment orders.
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_compliance_admin(
+    env: &Env,
+    owner: &Address,
+    admin: &Address,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    env.storage()
+        .persistent()
+        .set(&GarnishmentKey::ComplianceAdmin, admin);
+    Ok(())
+}
+
+/// Returns the compliance admin, if set.
+pub fn get_compliance_admin(env: &Env) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&GarnishmentKey::ComplianceAdmin)
+}
+
+/// Registers a garnishment order against `employee`.
+///
+/// # Returns
+/// The new order id.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is neither owner nor compliance admin
+/// * `PayrollError::InvalidData` - non-positive cap or deduction, cap above
+///   `MAX_AMOUNT`, bps above 10000, or the employee already has
+///   `MAX_GARNISHMENTS_PER_EMPLOYEE` orders
+///
+/// # Access Control
+/// Requires owner or compliance admin authentication
+pub fn register_garnishment(
+    env: &Env,
+    caller: &Address,
+    employee: &Address,
+    recipient: &Address,
+    token: &Address,
+    deduction: GarnishmentDeduction,
+    total_cap: i128,
+) -> Result<u64, PayrollError> {
+    require_manager(env, caller)?;
+
+    let valid_deduction = match deduction {
+        GarnishmentDeduction::Bps(bps) => bps > 0 && i128::from(bps) <= BPS_DENOMINATOR,
+        GarnishmentDeduction::Fixed(amount) => amount > 0,
+    };
+    if !valid_deduction || recipient == employee {
+        return Err(PayrollError::InvalidData);
+    }
+    validation::amount(total_cap)?;
+
+    let orders_key = GarnishmentKey::EmployeeOrders(employee.clone());
+    let mut order_ids: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&orders_key)
+        .unwrap_or(Vec::new(env));
+    if order_ids.len() >= MAX_GARNISHMENTS_PER_EMPLOYEE {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let id: u64 = env
+        .storage()
+        .persistent()
+        .get(&GarnishmentKey::NextOrderId)
+        .unwrap_or(1);
+    env.storage()
+        .persistent()
+        .set(&GarnishmentKey::NextOrderId, &(id + 1));
+
+    let order = GarnishmentOrder {
+        id,
+        employee: employee.clone(),
+        recipient: recipient.clone(),
+        token: token.clone(),
+        deduction,
+        total_cap,
+        total_withheld: 0,
+        active: true,
+        registered_at: env.ledger().timestamp(),
+    };
+    env.storage()
+        .persistent()
+        .set(&GarnishmentKey::Order(id), &order);
+    order_ids.push_back(id);
+    env.storage().persistent().set(&orders_key, &order_ids);
+
+    Ok(id)
+}
+
+/// Releases an active order before its cap is reached (e.g. the debt was
+/// settled off-chain).
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is neither owner nor compliance admin
+/// * `PayrollError::InvalidData` - unknown or already inactive order
+///
+/// # Access Control
+/// Requires owner or compliance admin authentication
+pub fn release_garnishment(env: &Env, caller: &Address, order_id: u64) -> Result<(), PayrollError> {
+    require_manager(env, caller)?;
+    let mut order = get_garnishment(env, order_id)
+        .filter(|order| order.active)
+        .ok_or(PayrollError::InvalidData)?;
+    order.active = false;
+    env.storage()
+        .persistent()
+        .set(&GarnishmentKey::Order(order_id), &order);
+    remove_active(env, &order.employee, order_id);
+    Ok(())
+}
+
+/// Returns a garnishment order by id.
+pub fn get_garnishment(env: &Env, order_id: u64) -> Option<GarnishmentOrder> {
+    env.storage()
+        .persistent()
+        .get(&GarnishmentKey::Order(order_id))
+}
+
+/// Returns the ids of `employee`'s active orders, in the order they apply.
+pub fn get_employee_garnishments(env: &Env, employee: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&GarnishmentKey::EmployeeOrders(employee.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Withholds all due deductions from `payout` and pays them to their
+/// recipients in the payout token, rounding `Bps` deductions with `mode`.
+/// Orders in the token the payout was converted from are worked out on its
+/// base-token gross. Order totals are updated before any transfer.
+///
+/// # Returns
+/// The deductions made in the payout token, for the payslip; their sum never
+/// exceeds the gross.
+pub(crate) fn withhold_garnishments(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+    payout: &PayoutGross,
+    mode: RoundingMode,
+) -> Vec<PayslipDeduction> {
+    let mut deductions: Vec<PayslipDeduction> = Vec::new(env);
+    let mut net = payout.amount;
+
+    for order_id in get_employee_garnishments(env, employee).iter() {
+        let Some(mut order) = get_garnishment(env, order_id) else {
+            continue;
+        };
+        let Some(gross) = payout.in_token(&order.token) else {
+            continue;
+        };
+        if net <= 0 {
+            continue;
+        }
+        let due = match order.deduction {
+            GarnishmentDeduction::Bps(bps) => apply_bps(gross, bps, mode),
+            GarnishmentDeduction::Fixed(amount) => amount,
+        };
+        // Worked out in the order's token, paid in the payout token.
+        let withheld = due
+            .min(order.total_cap - order.total_withheld)
+            .min(payout.to_token(&order.token, net));
+        let amount = payout.to_paid(&order.token, withheld);
+        if withheld <= 0 || amount <= 0 {
+            continue;
+        }
+
+        order.total_withheld += withheld;
+        if order.total_withheld >= order.total_cap {
+            order.active = false;
+            remove_active(env, employee, order_id);
+        }
+        env.storage()
+            .persistent()
+            .set(&GarnishmentKey::Order(order_id), &order);
+        net -= amount;
+        deductions.push_back(PayslipDeduction {
+            order_id,
+            recipient: order.recipient.clone(),
+            amount,
+        });
+    }
+
+    for deduction in deductions.iter() {
+        transfer_from_contract(env, &payout.token, &deduction.recipient, deduction.amount);
+        emit_garnishment_withheld(
+            env,
+            GarnishmentWithheldEvent {
+                order_id: deduction.order_id,
+                agreement_id,
+                employee: employee.clone(),
+                recipient: deduction.recipient,
+                amount: deduction.amount,
+            },
+        );
+    }
+
+    deductions
+}
+
+fn require_manager(env: &Env, caller: &Address) -> Result<(), PayrollError> {
+    caller.require_auth();
+    let owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if owner.as_ref() == Some(caller) || get_compliance_admin(env).as_ref() == Some(caller) {
+        Ok(())
+    } else {
+        Err(PayrollError::Unauthorized)
+    }
+}
+
+fn remove_active(env: &Env, employee: &Address, order_id: u64) {
+    let key = GarnishmentKey::EmployeeOrders(employee.clone());
+    let mut order_ids = get_employee_garnishments(env, employee);
+    if let Some(pos) = order_ids.first_index_of(order_id) {
+        order_ids.remove(pos);
+    }
+    if order_ids.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &order_ids);
+    }
+}
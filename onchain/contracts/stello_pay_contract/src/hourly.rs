@@ -0,0 +1,236 @@
+//! Hourly payroll gated by timekeeper attestations.
+//!
+//! Before activation, an employer can switch a payroll agreement to hourly
+//! pay. Each employee's salary per period is then their hourly rate, and a
+//! period pays `rate × attested hours` instead of a fixed salary.
+//!
+//! A timekeeper (a person or an oracle contract) designated by the employer
+//! attests each employee's hours once a period has ended, capped at the
+//! agreement's maximum hours per period. Attestations are final. Claims pay
+//! consecutive attested periods from the first unclaimed one and stop at the
+//! first period without an attestation, so hours cannot be skipped or paid
+//! twice.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::cola;
+use crate::events::{emit_hours_attested, HoursAttestedEvent};
+use crate::payroll::{elapsed_periods, get_agreement, get_employee_agreements};
+use crate::storage::{AgreementMode, AgreementStatus, DataKey, PayrollError};
+
+#[contracttype]
+#[derive(Clone)]
+enum HourlyKey {
+    /// Hourly pay settings of an agreement -> HourlyConfig
+    Config(u128),
+    /// Hours attested for `(agreement, employee, period)` -> u32
+    Hours(u128, Address, u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HourlyConfig {
+    /// Address allowed to attest hours.
+    pub timekeeper: Address,
+    /// Most hours that can be attested for one employee in one period.
+    pub max_hours_per_period: u32,
+}
+
+/// Switches a payroll agreement to hourly pay, or updates its cap and
+/// timekeeper, before activation.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - agreement does not exist
+/// * `PayrollError::Unauthorized` - caller is not the agreement's employer
+/// * `PayrollError::InvalidAgreementMode` - not a payroll agreement
+/// * `PayrollError::InvalidData` - the agreement has been activated, or
+///   `max_hours_per_period` is zero
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn enable_hourly_pay(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    timekeeper: &Address,
+    max_hours_per_period: u32,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    if agreement.mode != AgreementMode::Payroll {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+    if agreement.status != AgreementStatus::Created || max_hours_per_period == 0 {
+        return Err(PayrollError::InvalidData);
+    }
+    env.storage().persistent().set(
+        &HourlyKey::Config(agreement_id),
+        &HourlyConfig {
+            timekeeper: timekeeper.clone(),
+            max_hours_per_period,
+        },
+    );
+    Ok(())
+}
+
+/// Replaces the timekeeper of an hourly agreement.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - agreement does not exist
+/// * `PayrollError::Unauthorized` - caller is not the agreement's employer
+/// * `PayrollError::InvalidAgreementMode` - the agreement is not hourly
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn set_hourly_timekeeper(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    timekeeper: &Address,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    let mut config =
+        get_hourly_config(env, agreement_id).ok_or(PayrollError::InvalidAgreementMode)?;
+    config.timekeeper = timekeeper.clone();
+    env.storage()
+        .persistent()
+        .set(&HourlyKey::Config(agreement_id), &config);
+    Ok(())
+}
+
+/// Returns the hourly pay settings of an agreement, if it is hourly.
+pub fn get_hourly_config(env: &Env, agreement_id: u128) -> Option<HourlyConfig> {
+    env.storage()
+        .persistent()
+        .get(&HourlyKey::Config(agreement_id))
+}
+
+/// Records the hours an employee worked in an ended period (0-based from
+/// activation).
+///
+/// # Errors
+/// * `PayrollError::InvalidAgreementMode` - the agreement is not hourly
+/// * `PayrollError::Unauthorized` - caller is not the agreement's timekeeper
+/// * `PayrollError::NoEmployee` - employee is not on the agreement
+/// * `PayrollError::AgreementNotActivated` - the agreement is not activated
+/// * `PayrollError::InvalidData` - the period has not ended, `hours` exceeds
+///   the cap, or the period is already attested
+///
+/// # Access Control
+/// Requires timekeeper authentication
+pub fn attest_hours(
+    env: &Env,
+    timekeeper: &Address,
+    agreement_id: u128,
+    employee: &Address,
+    period: u32,
+    hours: u32,
+) -> Result<(), PayrollError> {
+    timekeeper.require_auth();
+    let config = get_hourly_config(env, agreement_id).ok_or(PayrollError::InvalidAgreementMode)?;
+    if config.timekeeper != *timekeeper {
+        return Err(PayrollError::Unauthorized);
+    }
+    if !get_employee_agreements(env, employee).contains(agreement_id) {
+        return Err(PayrollError::NoEmployee);
+    }
+    let activation_time = DataKey::get_agreement_activation_time(env, agreement_id)
+        .ok_or(PayrollError::AgreementNotActivated)?;
+    let period_duration = DataKey::get_agreement_period_duration(env, agreement_id)
+        .ok_or(PayrollError::AgreementNotFound)?;
+    let ended_periods = elapsed_periods(
+        env.ledger().timestamp().saturating_sub(activation_time),
+        period_duration,
+    );
+    let key = HourlyKey::Hours(agreement_id, employee.clone(), period);
+    if period >= ended_periods
+        || hours > config.max_hours_per_period
+        || env.storage().persistent().has(&key)
+    {
+        return Err(PayrollError::InvalidData);
+    }
+    env.storage().persistent().set(&key, &hours);
+
+    emit_hours_attested(
+        env,
+        HoursAttestedEvent {
+            agreement_id,
+            employee: employee.clone(),
+            period,
+            hours,
+        },
+    );
+    Ok(())
+}
+
+/// Returns the hours attested for an employee in a period, if any.
+pub fn get_attested_hours(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+    period: u32,
+) -> Option<u32> {
+    env.storage()
+        .persistent()
+        .get(&HourlyKey::Hours(agreement_id, employee.clone(), period))
+}
+
+/// Returns whether an agreement pays by attested hours.
+pub(crate) fn is_hourly(env: &Env, agreement_id: u128) -> bool {
+    env.storage()
+        .persistent()
+        .has(&HourlyKey::Config(agreement_id))
+}
+
+/// Computes the payout of up to `max_periods` periods starting at
+/// `first_period`. Fixed-salary agreements pay `rate` per period. Hourly
+/// agreements pay `rate` per attested hour and stop at the first unattested
+/// period. Periods that elapsed before a COLA raise are paid at the rate
+/// they were earned at (see [`crate::cola`]).
+///
+/// # Returns
+/// The number of periods paid and the amount.
+///
+/// # Errors
+/// * `PayrollError::NoPeriodsToClaim` - hourly, and `first_period` is not
+///   attested
+/// * `PayrollError::InvalidData` - the amount overflows
+pub(crate) fn period_payout(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+    rate: i128,
+    first_period: u32,
+    max_periods: u32,
+) -> Result<(u32, i128), PayrollError> {
+    if !is_hourly(env, agreement_id) {
+        let amount =
+            cola::pay_for_periods(env, agreement_id, employee, rate, first_period, max_periods)
+                .ok_or(PayrollError::InvalidData)?;
+        return Ok((max_periods, amount));
+    }
+    let mut periods = 0;
+    let mut amount: i128 = 0;
+    while periods < max_periods {
+        let period = first_period + periods;
+        let Some(attested) = get_attested_hours(env, agreement_id, employee, period) else {
+            break;
+        };
+        amount = cola::salary_for_period(env, agreement_id, employee, rate, period)
+            .checked_mul(i128::from(attested))
+            .and_then(|pay| amount.checked_add(pay))
+            .ok_or(PayrollError::InvalidData)?;
+        periods += 1;
+    }
+    if periods == 0 {
+        return Err(PayrollError::NoPeriodsToClaim);
+    }
+    Ok((periods, amount))
+}
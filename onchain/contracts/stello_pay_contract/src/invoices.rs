@@ -0,0 +1,454 @@
+//! Invoices for escrow-mode agreements.
+//!
+//! Besides time-based claims, the contributor on an escrow agreement can bill
+//! for work by submitting an invoice: an amount, a hash of the off-chain
+//! description and a due date. Invoices are numbered per agreement from 1.
+//!
+//! The employer approves an invoice, which pays it from the agreement's escrow
+//! straight away, or disputes it. A disputed invoice stays open, so it can
+//! still be approved once the parties agree, or the contributor can withdraw
+//! it and submit a corrected one. Approved invoices count towards the
+//! agreement's paid amount, which can never exceed its total.
+//!
+//! At most [`MAX_OPEN_INVOICES`] invoices can be open per agreement, which
+//! bounds the aging report.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::events::{
+    emit_invoice_disputed, emit_invoice_paid, emit_invoice_submitted, emit_payment_received,
+    emit_payment_sent, InvoiceDisputedEvent, InvoicePaidEvent, InvoiceSubmittedEvent,
+    PaymentReceivedEvent, PaymentSentEvent,
+};
+use crate::pause_exemptions::{is_paused_for, PauseCategory};
+use crate::payroll::{
+    get_agreement, is_grace_period_active, transfer_from_contract, unindex_agreement_employees,
+};
+use crate::sequence::{next_sequence, SequenceCategory};
+use crate::stats;
+use crate::storage::{
+    Agreement, AgreementMode, AgreementStatus, DataKey, EmployeeInfo, PayrollError, StorageKey,
+};
+use crate::validation;
+use crate::versioned;
+use crate::yield_strategy;
+
+/// Most submitted or disputed invoices one agreement can have at a time.
+pub const MAX_OPEN_INVOICES: u32 = 20;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[contracttype]
+#[derive(Clone)]
+enum InvoiceKey {
+    /// Invoices submitted against an agreement -> u32
+    Count(u128),
+    /// Invoice by agreement and number -> Invoice
+    Invoice(u128, u32),
+    /// Numbers of submitted or disputed invoices, oldest first -> Vec<u32>
+    Open(u128),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InvoiceStatus {
+    /// Awaiting the employer's decision.
+    Submitted,
+    /// Contested by the employer; can still be approved or withdrawn.
+    Disputed,
+    /// Approved and paid from escrow.
+    Paid,
+    /// Withdrawn by the contributor.
+    Withdrawn,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Invoice {
+    pub agreement_id: u128,
+    pub number: u32,
+    pub contributor: Address,
+    pub amount: i128,
+    /// Hash of the off-chain invoice description.
+    pub description_hash: BytesN<32>,
+    pub due_date: u64,
+    pub submitted_at: u64,
+    pub status: InvoiceStatus,
+    /// When the invoice was paid or withdrawn.
+    pub settled_at: Option<u64>,
+}
+
+/// Open invoice amounts of an agreement, grouped by how far past due they
+/// are at `as_of`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InvoiceAgingReport {
+    pub as_of: u64,
+    pub open_count: u32,
+    pub open_amount: i128,
+    /// Part of `open_amount` the employer has disputed.
+    pub disputed_amount: i128,
+    /// Not yet due.
+    pub current: i128,
+    pub overdue_1_30_days: i128,
+    pub overdue_31_60_days: i128,
+    pub overdue_61_90_days: i128,
+    pub overdue_over_90_days: i128,
+}
+
+fn get_open(env: &Env, agreement_id: u128) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&InvoiceKey::Open(agreement_id))
+        .unwrap_or(Vec::new(env))
+}
+
+fn set_invoice(env: &Env, invoice: &Invoice) {
+    env.storage().persistent().set(
+        &InvoiceKey::Invoice(invoice.agreement_id, invoice.number),
+        invoice,
+    );
+}
+
+/// Stores a paid or withdrawn invoice and drops it from the open list.
+fn settle_invoice(env: &Env, invoice: &mut Invoice, status: InvoiceStatus) {
+    invoice.status = status;
+    invoice.settled_at = Some(env.ledger().timestamp());
+    set_invoice(env, invoice);
+
+    let mut open = get_open(env, invoice.agreement_id);
+    if let Some(index) = open.first_index_of(invoice.number) {
+        open.remove(index);
+    }
+    env.storage()
+        .persistent()
+        .set(&InvoiceKey::Open(invoice.agreement_id), &open);
+}
+
+/// Loads an escrow agreement that can currently be invoiced against: active,
+/// or cancelled but still in its grace period.
+fn get_invoiceable_agreement(env: &Env, agreement_id: u128) -> Result<Agreement, PayrollError> {
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.mode != AgreementMode::Escrow {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+    match agreement.status {
+        AgreementStatus::Active => Ok(agreement),
+        AgreementStatus::Created => Err(PayrollError::AgreementNotActivated),
+        AgreementStatus::Paused => Err(PayrollError::AgreementPaused),
+        AgreementStatus::Cancelled if is_grace_period_active(env, agreement_id) => Ok(agreement),
+        _ => Err(PayrollError::NotInGracePeriod),
+    }
+}
+
+fn get_contributor(env: &Env, agreement_id: u128) -> Result<Address, PayrollError> {
+    let employees: Vec<EmployeeInfo> = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::AgreementEmployees(agreement_id))
+        .unwrap_or(Vec::new(env));
+    Ok(employees.get(0).ok_or(PayrollError::NoEmployee)?.address)
+}
+
+/// Submits an invoice against an escrow agreement.
+///
+/// # Returns
+/// The invoice number within the agreement.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - agreement does not exist
+/// * `PayrollError::InvalidAgreementMode` - agreement is not in escrow mode
+/// * `PayrollError::AgreementNotActivated` / `AgreementPaused` /
+///   `NotInGracePeriod` - agreement cannot be invoiced in its current state
+/// * `PayrollError::Unauthorized` - caller is not the agreement's contributor
+/// * `PayrollError::InvalidData` - `amount` is not positive or exceeds what is
+///   left of the agreement total, `due_date` has passed, or
+///   [`MAX_OPEN_INVOICES`] are already open
+///
+/// # Access Control
+/// Requires contributor authentication
+pub fn submit_invoice(
+    env: &Env,
+    contributor: &Address,
+    agreement_id: u128,
+    amount: i128,
+    description_hash: BytesN<32>,
+    due_date: u64,
+) -> Result<u32, PayrollError> {
+    contributor.require_auth();
+    let agreement = get_invoiceable_agreement(env, agreement_id)?;
+    if get_contributor(env, agreement_id)? != *contributor {
+        return Err(PayrollError::Unauthorized);
+    }
+    validation::amount(amount)?;
+    let now = env.ledger().timestamp();
+    if amount > agreement.total_amount - agreement.paid_amount || due_date < now {
+        return Err(PayrollError::InvalidData);
+    }
+    let mut open = get_open(env, agreement_id);
+    if open.len() >= MAX_OPEN_INVOICES {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let number = get_invoice_count(env, agreement_id) + 1;
+    env.storage()
+        .persistent()
+        .set(&InvoiceKey::Count(agreement_id), &number);
+    set_invoice(
+        env,
+        &Invoice {
+            agreement_id,
+            number,
+            contributor: contributor.clone(),
+            amount,
+            description_hash,
+            due_date,
+            submitted_at: now,
+            status: InvoiceStatus::Submitted,
+            settled_at: None,
+        },
+    );
+    open.push_back(number);
+    env.storage()
+        .persistent()
+        .set(&InvoiceKey::Open(agreement_id), &open);
+
+    emit_invoice_submitted(
+        env,
+        InvoiceSubmittedEvent {
+            agreement_id,
+            number,
+            contributor: contributor.clone(),
+            amount,
+            due_date,
+        },
+    );
+    Ok(number)
+}
+
+/// Approves a submitted or disputed invoice and pays it from the agreement's
+/// escrow. The agreement completes once its total has been paid.
+///
+/// # Errors
+/// * `PayrollError::EmergencyPaused` - the contract is paused
+/// * `PayrollError::AgreementNotFound` / `InvalidAgreementMode` /
+///   `AgreementNotActivated` / `AgreementPaused` / `NotInGracePeriod` - as for
+///   [`submit_invoice`]
+/// * `PayrollError::Unauthorized` - caller is not the agreement's employer
+/// * `PayrollError::InvalidData` - invoice does not exist, is not open, or
+///   exceeds what is left of the agreement total
+/// * `PayrollError::InsufficientEscrowBalance` - escrow cannot cover the
+///   invoice
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn approve_invoice(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    number: u32,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    if is_paused_for(env, PauseCategory::Invoices) {
+        return Err(PayrollError::EmergencyPaused);
+    }
+    let mut agreement = get_invoiceable_agreement(env, agreement_id)?;
+    if agreement.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    let mut invoice = get_invoice(env, agreement_id, number).ok_or(PayrollError::InvalidData)?;
+    if !matches!(
+        invoice.status,
+        InvoiceStatus::Submitted | InvoiceStatus::Disputed
+    ) {
+        return Err(PayrollError::InvalidData);
+    }
+    // Time-based claims may have used up the total since submission.
+    let paid_amount = agreement
+        .paid_amount
+        .checked_add(invoice.amount)
+        .filter(|paid| *paid <= agreement.total_amount)
+        .ok_or(PayrollError::InvalidData)?;
+    let liquid = yield_strategy::ensure_liquid(env, agreement_id, &agreement.token, invoice.amount);
+    let escrow_balance = DataKey::get_agreement_escrow_balance(env, agreement_id, &agreement.token);
+    if liquid < invoice.amount {
+        return Err(PayrollError::InsufficientEscrowBalance);
+    }
+
+    settle_invoice(env, &mut invoice, InvoiceStatus::Paid);
+    DataKey::set_agreement_escrow_balance(
+        env,
+        agreement_id,
+        &agreement.token,
+        escrow_balance - invoice.amount,
+    );
+    agreement.paid_amount = paid_amount;
+    DataKey::set_agreement_paid_amount(env, agreement_id, paid_amount);
+    if paid_amount == agreement.total_amount {
+        if agreement.status == AgreementStatus::Active {
+            stats::record_deactivated(env);
+        }
+        agreement.status = AgreementStatus::Completed;
+        unindex_agreement_employees(env, agreement_id);
+    }
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
+
+    transfer_from_contract(env, &agreement.token, &invoice.contributor, invoice.amount);
+    stats::record_disbursement(env, &agreement.token, invoice.amount);
+    let sequence = next_sequence(env, SequenceCategory::Disbursement);
+
+    emit_invoice_paid(
+        env,
+        InvoicePaidEvent {
+            agreement_id,
+            number,
+            contributor: invoice.contributor.clone(),
+            amount: invoice.amount,
+        },
+    );
+    emit_payment_sent(
+        env,
+        PaymentSentEvent {
+            agreement_id,
+            from: agreement.employer,
+            to: invoice.contributor.clone(),
+            amount: invoice.amount,
+            token: agreement.token.clone(),
+            sequence,
+        },
+    );
+    emit_payment_received(
+        env,
+        PaymentReceivedEvent {
+            agreement_id,
+            to: invoice.contributor,
+            amount: invoice.amount,
+            token: agreement.token,
+            sequence,
+        },
+    );
+    Ok(())
+}
+
+/// Disputes a submitted invoice. It stays open until approved or withdrawn.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - agreement does not exist
+/// * `PayrollError::Unauthorized` - caller is not the agreement's employer
+/// * `PayrollError::InvalidData` - invoice does not exist or is not in
+///   `Submitted` status
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn dispute_invoice(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    number: u32,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    let mut invoice = get_invoice(env, agreement_id, number).ok_or(PayrollError::InvalidData)?;
+    if invoice.status != InvoiceStatus::Submitted {
+        return Err(PayrollError::InvalidData);
+    }
+    invoice.status = InvoiceStatus::Disputed;
+    set_invoice(env, &invoice);
+
+    emit_invoice_disputed(
+        env,
+        InvoiceDisputedEvent {
+            agreement_id,
+            number,
+            employer: employer.clone(),
+        },
+    );
+    Ok(())
+}
+
+/// Withdraws a submitted or disputed invoice.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - invoice does not exist or is not open
+/// * `PayrollError::Unauthorized` - caller did not submit the invoice
+///
+/// # Access Control
+/// Requires contributor authentication
+pub fn withdraw_invoice(
+    env: &Env,
+    contributor: &Address,
+    agreement_id: u128,
+    number: u32,
+) -> Result<(), PayrollError> {
+    contributor.require_auth();
+    let mut invoice = get_invoice(env, agreement_id, number).ok_or(PayrollError::InvalidData)?;
+    if invoice.contributor != *contributor {
+        return Err(PayrollError::Unauthorized);
+    }
+    if !matches!(
+        invoice.status,
+        InvoiceStatus::Submitted | InvoiceStatus::Disputed
+    ) {
+        return Err(PayrollError::InvalidData);
+    }
+    settle_invoice(env, &mut invoice, InvoiceStatus::Withdrawn);
+    Ok(())
+}
+
+/// Returns an invoice by agreement and number.
+pub fn get_invoice(env: &Env, agreement_id: u128, number: u32) -> Option<Invoice> {
+    env.storage()
+        .persistent()
+        .get(&InvoiceKey::Invoice(agreement_id, number))
+}
+
+/// Returns how many invoices have been submitted against an agreement, which
+/// is also the latest invoice number.
+pub fn get_invoice_count(env: &Env, agreement_id: u128) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&InvoiceKey::Count(agreement_id))
+        .unwrap_or(0)
+}
+
+/// Returns the numbers of an agreement's submitted or disputed invoices,
+/// oldest first.
+pub fn get_open_invoices(env: &Env, agreement_id: u128) -> Vec<u32> {
+    get_open(env, agreement_id)
+}
+
+/// Groups an agreement's open invoices by days past their due date, as of
+/// the current ledger time. An invoice one second past due is one day
+/// overdue.
+pub fn get_invoice_aging_report(env: &Env, agreement_id: u128) -> InvoiceAgingReport {
+    let now = env.ledger().timestamp();
+    let mut report = InvoiceAgingReport {
+        as_of: now,
+        ..Default::default()
+    };
+    for number in get_open(env, agreement_id).iter() {
+        let Some(invoice) = get_invoice(env, agreement_id, number) else {
+            continue;
+        };
+        report.open_count += 1;
+        report.open_amount += invoice.amount;
+        if invoice.status == InvoiceStatus::Disputed {
+            report.disputed_amount += invoice.amount;
+        }
+        let overdue_days = now
+            .saturating_sub(invoice.due_date)
+            .div_ceil(SECONDS_PER_DAY);
+        let bucket = match overdue_days {
+            0 => &mut report.current,
+            1..=30 => &mut report.overdue_1_30_days,
+            31..=60 => &mut report.overdue_31_60_days,
+            61..=90 => &mut report.overdue_61_90_days,
+            _ => &mut report.overdue_over_90_days,
+        };
+        *bucket += invoice.amount;
+    }
+    report
+}
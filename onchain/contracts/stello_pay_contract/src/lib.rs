@@ -1,18 +1,96 @@
 #![no_std]
+pub mod approval_matrix;
 pub mod audit;
 pub mod backup;
+pub mod cola;
+pub mod compensation;
+pub mod confidential;
+pub mod credentials;
+pub mod currency_groups;
+pub mod disbursement_windows;
+pub mod errors;
 pub mod events;
+pub mod garnishment;
+pub mod hourly;
+pub mod invoices;
+pub mod loans;
+pub mod meta_tx;
+pub mod metadata;
+pub mod metrics;
+pub mod offers;
+pub mod ownership;
+pub mod pause_exemptions;
+pub mod payout_calendar;
 mod payroll;
+pub mod payroll_import;
+pub mod payslip;
+pub mod receipts;
+pub mod reimbursements;
+pub mod reputation;
+pub mod resource_estimate;
+pub mod rounding;
+pub mod savings;
+pub mod sequence;
+pub mod severance;
+pub mod sla;
+pub mod sponsorship;
+pub mod stats;
 pub mod storage;
-
-use events::{emit_contract_migrated, ContractMigratedEvent};
+pub mod streaming;
+pub mod subsidiaries;
+#[cfg(feature = "testutils")]
+pub mod testutils;
+pub mod treasury;
+pub mod ttl;
+pub mod validation;
+pub mod versioned;
+pub mod wallet;
+pub mod webhooks;
+pub mod winddown;
+pub mod yield_strategy;
+
+use approval_matrix::ApprovalTier;
+use cola::{ColaPolicy, ColaRunResult, PayrollAdjustment};
+use compensation::CompensationDisbursement;
+use currency_groups::{CurrencyGroup, FxExposure, PayrollTokenMigration, TokenMigrationTerms};
+use disbursement_windows::{BlackoutWindow, BusinessHours};
+use errors::ErrorInfo;
+use events::{emit_contract_migrated, ContractMigratedEvent, EventVerbosity};
+use garnishment::{GarnishmentDeduction, GarnishmentOrder};
+use hourly::HourlyConfig;
+use invoices::{Invoice, InvoiceAgingReport};
+use loans::SalaryLoan;
+use meta_tx::SignedRequest;
+use offers::JobOffer;
+use ownership::OwnershipInfo;
+use pause_exemptions::PauseCategory;
+use payout_calendar::PayoutCalendarEntry;
+use payroll_import::{ImportedHistory, ImportedRecord};
+use payslip::Payslip;
 use rbac_interface::{RbacContractClient, Role};
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Vec};
+use reimbursements::ReimbursementRequest;
+use reputation::{EmployerReputation, ReputationStake, ReputationStakeConfig};
+use resource_estimate::{PayrollOperation, ResourceEstimate, ResourceProfile};
+use rounding::RoundingMode;
+use savings::{SavingsPenaltyConfig, SavingsPlan, SavingsVault};
+use sequence::SequenceCategory;
+use severance::{SeveranceCover, SeveranceInsuranceConfig, SeverancePool, SeveranceSettlement};
+use sla::{SlaConfig, SlaStatus};
+use soroban_sdk::{contract, contractimpl, Address, Bytes, BytesN, Env, Map, String, Vec};
+use sponsorship::SponsorshipPolicy;
+use stats::ProtocolStats;
 use storage::{
     Agreement, BatchEscrowCreateResult, BatchMilestoneResult, BatchPayrollCreateResult,
     BatchPayrollResult, DisputeStatus, EscrowCreateParams, GracePeriodExtensionPolicy, Milestone,
     PayrollCreateParams, PayrollError, StorageKey,
 };
+use streaming::SalaryStream;
+use subsidiaries::{GroupAction, GroupMetrics};
+use treasury::{SpendProposal, TreasuryBalance};
+use versioned::MigrationProgress;
+use wallet::Payroll;
+use winddown::EmployerWinddown;
+use yield_strategy::YieldPosition;
 
 use crate::audit::LifecycleAuditEntry;
 
@@ -36,6 +114,12 @@ pub struct PayrollContract;
 #[contractimpl]
 impl PayrollContract {
     fn require_upgrade_admin(env: &Env, operator: &Address) {
+        if let Some(guardian) = ownership::get_guardian(env) {
+            operator.require_auth();
+            assert!(*operator == guardian, "Unauthorized");
+            return;
+        }
+
         if let Some(rbac_addr) = env
             .storage()
             .persistent()
@@ -69,7 +153,7 @@ impl PayrollContract {
     /// Sets the initial administrative authority for the contract.
     pub fn initialize(env: Env, owner: Address) {
         owner.require_auth();
-        if env.storage().persistent().has(&StorageKey::Owner) {
+        if env.storage().persistent().has(&StorageKey::Owner) || ownership::is_renounced(&env) {
             panic!("Already initialized");
         }
         env.storage().persistent().set(&StorageKey::Owner, &owner);
@@ -150,7 +234,10 @@ impl PayrollContract {
     /// * `operator` - The address initiating the upgrade, which must possess administrative authority.
     ///
     /// # Access Control
-    /// - If an RBAC contract is configured, the `operator` must possess the `Admin` role.
+    /// - Once ownership is renounced, the `operator` must be the guardian and
+    ///   `new_wasm_hash` must be on the ratified upgrade allowlist. The hash is
+    ///   removed from the allowlist.
+    /// - Otherwise, if an RBAC contract is configured, the `operator` must possess the `Admin` role.
     /// - Otherwise, the `operator` must be the stored contract owner.
     /// - `operator.require_auth()` is called to verify authorization signature.
     ///
@@ -159,6 +246,12 @@ impl PayrollContract {
     /// - The new bytecode must correctly preserve existing storage keys/layouts to prevent state corruption.
     pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>, operator: Address) {
         Self::require_upgrade_admin(&env, &operator);
+        if ownership::is_renounced(&env) {
+            assert!(
+                ownership::take_ratified_upgrade(&env, &new_wasm_hash),
+                "Upgrade not ratified"
+            );
+        }
         env.deployer().update_current_contract_wasm(new_wasm_hash);
     }
 
@@ -170,6 +263,7 @@ impl PayrollContract {
     ///
     /// # Access Control
     /// Requires admin authorization via RBAC when configured (or owner auth when RBAC is unset).
+    /// Once ownership is renounced, requires the guardian.
     pub fn migrate_state(env: Env, operator: Address, from_version: u32) {
         Self::require_upgrade_admin(&env, &operator);
 
@@ -194,6 +288,14 @@ impl PayrollContract {
                     env.storage().persistent().get(&StorageKey::Agreement(i));
                 i += 1;
             }
+            // Agreements created with a nonce are not counter-numbered.
+            let derived = versioned::derived_agreement_count(&env).min(10 - cap as u32);
+            for position in 0..derived {
+                if let Some(id) = versioned::derived_agreement_id(&env, position) {
+                    let _maybe: Option<Agreement> =
+                        env.storage().persistent().get(&StorageKey::Agreement(id));
+                }
+            }
 
             env.storage()
                 .persistent()
@@ -211,6 +313,54 @@ impl PayrollContract {
         panic!("Unsupported migration version");
     }
 
+    /// Rewrites up to `limit` agreements still stored in an older schema.
+    ///
+    /// Agreements are also upgraded lazily the first time they are read;
+    /// this converts the rest ahead of time. Each call resumes where the
+    /// previous one stopped. See [`versioned`].
+    ///
+    /// # Arguments
+    /// * `operator` - upgrade admin
+    /// * `limit` - most agreement IDs to visit (1 to `MAX_BATCH_SIZE`)
+    ///
+    /// # Errors
+    /// - `InvalidData` if `limit` is zero or above `MAX_BATCH_SIZE`
+    ///
+    /// # Access Control
+    /// Same as [`Self::migrate_state`].
+    pub fn migrate_batch(
+        env: Env,
+        operator: Address,
+        limit: u32,
+    ) -> Result<MigrationProgress, PayrollError> {
+        Self::require_upgrade_admin(&env, &operator);
+        versioned::migrate_agreements(&env, limit)
+    }
+
+    /// Adds up to `limit` agreements created before the per-employee index
+    /// to [`Self::get_employee_agreements`].
+    ///
+    /// Each call resumes where the previous one stopped; agreements that are
+    /// already indexed or settled are skipped. See [`versioned`].
+    ///
+    /// # Arguments
+    /// * `operator` - upgrade admin
+    /// * `limit` - most agreement IDs to visit (1 to `MAX_BATCH_SIZE`)
+    ///
+    /// # Errors
+    /// - `InvalidData` if `limit` is zero or above `MAX_BATCH_SIZE`
+    ///
+    /// # Access Control
+    /// Same as [`Self::migrate_state`].
+    pub fn migrate_employee_index(
+        env: Env,
+        operator: Address,
+        limit: u32,
+    ) -> Result<MigrationProgress, PayrollError> {
+        Self::require_upgrade_admin(&env, &operator);
+        versioned::migrate_employee_index(&env, limit)
+    }
+
     /// Creates a payroll agreement for multiple employees.
     ///
     /// # Arguments
@@ -236,6 +386,22 @@ impl PayrollContract {
         payroll::create_payroll_agreement(&env, employer, token, grace_period_seconds)
     }
 
+    /// Creates a payroll agreement whose ID is derived from `employer` and
+    /// `nonce` (see `compute_agreement_id`), so it can be known before
+    /// submission. Panics with `InvalidData` if the nonce was already used.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn create_nonced_payroll_agreement(
+        env: Env,
+        employer: Address,
+        token: Address,
+        grace_period_seconds: u64,
+        nonce: u64,
+    ) -> u128 {
+        payroll::create_nonced_payroll_agreement(&env, employer, token, grace_period_seconds, nonce)
+    }
+
     /// Creates multiple payroll agreements in a single transaction.
     ///
     /// # Arguments
@@ -258,6 +424,43 @@ impl PayrollContract {
         payroll::batch_create_payroll_agreements(&env, employer, items)
     }
 
+    /// Creates an active payroll agreement for each record of an off-chain
+    /// payroll export signed by `attestor`, anchored at the employee's last
+    /// payment, and returns their IDs in record order. The signed message is
+    /// `import_payload(employer, records)`.
+    ///
+    /// # Errors
+    /// * `Unauthorized` - `attestor` lacks a valid `PayrollAttestor`
+    ///   credential or a registered signing key, or the employer is winding
+    ///   down
+    /// * `InvalidData` - no records, the export was already imported, or a
+    ///   record is invalid
+    /// * `BatchTooLarge` - more than `MAX_BATCH_SIZE` records
+    ///
+    /// # Access Control
+    /// Requires employer authentication and the attestor's signature
+    pub fn import_payroll_records(
+        env: Env,
+        employer: Address,
+        attestor: Address,
+        records: Vec<ImportedRecord>,
+        signature: BytesN<64>,
+    ) -> Result<Vec<u128>, PayrollError> {
+        payroll_import::import_payroll_records(&env, &employer, &attestor, records, signature)
+    }
+
+    /// Returns the bytes an attestor signs to approve importing `records` for
+    /// `employer`.
+    pub fn import_payload(env: Env, employer: Address, records: Vec<ImportedRecord>) -> Bytes {
+        payroll_import::import_payload(&env, &employer, &records)
+    }
+
+    /// Returns the payment history imported with `agreement_id`, if it was
+    /// imported.
+    pub fn get_imported_history(env: Env, agreement_id: u128) -> Option<ImportedHistory> {
+        payroll_import::get_imported_history(&env, agreement_id)
+    }
+
     /// Creates an escrow agreement for a single contributor.
     ///
     /// # Arguments
@@ -297,6 +500,42 @@ impl PayrollContract {
         )
     }
 
+    /// Creates an escrow agreement whose ID is derived from `employer` and
+    /// `nonce` (see `compute_agreement_id`). Fails with `InvalidData` if the
+    /// nonce was already used.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_nonced_escrow_agreement(
+        env: Env,
+        employer: Address,
+        contributor: Address,
+        token: Address,
+        amount_per_period: i128,
+        period_seconds: u64,
+        num_periods: u32,
+        nonce: u64,
+    ) -> Result<u128, PayrollError> {
+        payroll::create_nonced_escrow_agreement(
+            &env,
+            employer,
+            contributor,
+            token,
+            amount_per_period,
+            period_seconds,
+            num_periods,
+            nonce,
+        )
+    }
+
+    /// Returns the ID an agreement created by `employer` with `nonce` gets:
+    /// the first 16 bytes (big-endian) of
+    /// `sha256(xdr(employer) || nonce_be_bytes)` with the top bit set.
+    pub fn compute_agreement_id(env: Env, employer: Address, nonce: u64) -> u128 {
+        payroll::compute_agreement_id(&env, &employer, nonce)
+    }
+
     /// Creates multiple escrow agreements in a single transaction.
     ///
     /// # Arguments
@@ -552,6 +791,8 @@ impl PayrollContract {
     /// # Requirements
     /// - Agreement must be in Created status
     /// - Caller must be the employer
+    /// - For payroll agreements, the escrow must cover the employer's
+    ///   required pre-funding periods (see `get_sla_status`)
     pub fn activate_agreement(env: Env, agreement_id: u128) {
         payroll::activate_agreement(&env, agreement_id);
     }
@@ -584,6 +825,88 @@ impl PayrollContract {
         payroll::get_agreement_employees(&env, agreement_id)
     }
 
+    /// Returns the IDs of all agreements created by `employer`.
+    pub fn get_employer_agreements(env: Env, employer: Address) -> Vec<u128> {
+        payroll::get_employer_agreements(&env, &employer)
+    }
+
+    /// Returns the IDs of the agreements `employee` is on, across employers,
+    /// until they are completed, finalized after cancellation or wound down.
+    pub fn get_employee_agreements(env: Env, employee: Address) -> Vec<u128> {
+        payroll::get_employee_agreements(&env, &employee)
+    }
+
+    /// Returns the IDs of the agreements through which `employer` pays
+    /// `employee`.
+    pub fn get_employment_agreements(env: Env, employer: Address, employee: Address) -> Vec<u128> {
+        payroll::get_employment_agreements(&env, &employer, &employee)
+    }
+
+    /// Returns `employee`'s position on payroll agreement `agreement_id`,
+    /// whatever its status, or `None` if they are not on it or it has not been
+    /// activated.
+    pub fn get_payroll(env: Env, employee: Address, agreement_id: u128) -> Option<Payroll> {
+        wallet::get_payroll(&env, &employee, agreement_id)
+    }
+
+    /// Returns every payroll position `employee` holds that is active, paused,
+    /// or in its grace period, across agreements and employers.
+    pub fn get_employee_payrolls(env: Env, employee: Address) -> Vec<Payroll> {
+        wallet::get_employee_payrolls(&env, &employee)
+    }
+
+    /// Returns each employee's current payroll position, in the order given:
+    /// their first payroll agreement that is active, paused, or in its grace
+    /// period. `None` for employees without one.
+    ///
+    /// # Errors
+    /// * `BatchTooLarge` - more than `MAX_BATCH_SIZE` employees
+    pub fn get_payrolls(
+        env: Env,
+        employees: Vec<Address>,
+    ) -> Result<Vec<Option<Payroll>>, PayrollError> {
+        wallet::get_payrolls(&env, employees)
+    }
+
+    /// Returns the salary due to each employee now on the position
+    /// `get_payrolls` returns for them, or 0.
+    ///
+    /// # Errors
+    /// * `BatchTooLarge` - more than `MAX_BATCH_SIZE` employees
+    pub fn get_claimable_balances(
+        env: Env,
+        employees: Vec<Address>,
+    ) -> Result<Vec<i128>, PayrollError> {
+        wallet::get_claimable_balances(&env, employees)
+    }
+
+    /// Returns when the next period of each employee's position ends, or
+    /// `None` if no more pay will accrue on it for now.
+    ///
+    /// # Errors
+    /// * `BatchTooLarge` - more than `MAX_BATCH_SIZE` employees
+    pub fn get_next_payout_times(
+        env: Env,
+        employees: Vec<Address>,
+    ) -> Result<Vec<Option<u64>>, PayrollError> {
+        wallet::get_next_payout_times(&env, employees)
+    }
+
+    /// Extends the TTL of up to `limit` of `employer`'s agreements and their
+    /// payroll, balance and index entries, resuming from where the previous
+    /// call stopped. Returns the number of agreements bumped.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn bump_employer_entries(
+        env: Env,
+        caller: Address,
+        employer: Address,
+        limit: u32,
+    ) -> Result<u32, PayrollError> {
+        ttl::bump_employer_entries(&env, &caller, &employer, limit)
+    }
+
     /// Set Arbiter
     ///
     /// # Arguments
@@ -624,6 +947,24 @@ impl PayrollContract {
         audit::get_audit_logger(&env)
     }
 
+    /// @notice Grants an external auditor the ViewAuditTrail permission over the employer's
+    /// audit trail.
+    /// @dev The viewer must hold a valid auditor credential in the linked credential registry.
+    pub fn grant_audit_trail_access(env: Env, employer: Address, viewer: Address) {
+        audit::grant_audit_trail_access(&env, employer, viewer);
+    }
+
+    /// @notice Withdraws a ViewAuditTrail permission granted by the employer.
+    pub fn revoke_audit_trail_access(env: Env, employer: Address, viewer: Address) {
+        audit::revoke_audit_trail_access(&env, employer, viewer);
+    }
+
+    /// @notice Returns whether `viewer` holds the ViewAuditTrail permission for `employer`.
+    /// @dev Access lapses as soon as the viewer's auditor credential is revoked or expires.
+    pub fn has_audit_trail_access(env: Env, employer: Address, viewer: Address) -> bool {
+        audit::has_audit_trail_access(&env, employer, viewer)
+    }
+
     /// @notice Returns the number of lifecycle audit entries appended locally.
     pub fn get_audit_entry_count(env: Env) -> u64 {
         audit::get_audit_entry_count(&env)
@@ -650,6 +991,56 @@ impl PayrollContract {
         audit::get_audit_entries_by_employer(&env, employer, start_id, limit)
     }
 
+    /// @notice Anchors the head of an employer's audit chain for a finished period.
+    /// @dev Permissionless. Periods are `AUDIT_ANCHOR_PERIOD_SECONDS` long and can be
+    /// anchored once, after they end. See `audit::AuditChain` for the chain layout.
+    ///
+    /// # Errors
+    /// * `InvalidData` - the period has not finished, is already anchored, or has no entries
+    pub fn anchor_audit_period(
+        env: Env,
+        employer: Address,
+        period: u64,
+    ) -> Result<BytesN<32>, PayrollError> {
+        audit::anchor_audit_period(&env, employer, period)
+    }
+
+    /// @notice Returns the stored anchor for an employer's audit period, if any.
+    pub fn get_audit_anchor(
+        env: Env,
+        employer: Address,
+        period: u64,
+    ) -> Option<audit::AuditAnchor> {
+        audit::get_audit_anchor(&env, employer, period)
+    }
+
+    /// @notice Returns the hash chain of an employer's audit period, if it has entries.
+    pub fn get_audit_chain(env: Env, employer: Address, period: u64) -> Option<audit::AuditChain> {
+        audit::get_audit_chain(&env, employer, period)
+    }
+
+    /// @notice Returns up to `limit` audit ids of an employer's audit period, in chain
+    /// order, ending just before `before_id` (`0` for the newest entry).
+    pub fn get_audit_period_entry_ids(
+        env: Env,
+        employer: Address,
+        period: u64,
+        before_id: u64,
+        limit: u32,
+    ) -> Vec<u64> {
+        audit::get_audit_period_entry_ids(&env, employer, period, before_id, limit)
+    }
+
+    /// @notice Proves an audit entry against its employer's anchored period.
+    /// @dev Read-only; returns `false` if the period is not anchored or the proof is invalid.
+    pub fn verify_audit_inclusion(
+        env: Env,
+        entry: LifecycleAuditEntry,
+        proof: Vec<BytesN<32>>,
+    ) -> bool {
+        audit::verify_audit_inclusion(&env, entry, proof)
+    }
+
     /// Raise Dispute
     ///
     /// # Arguments
@@ -754,6 +1145,37 @@ impl PayrollContract {
         payroll::get_multisig_contract(&env)
     }
 
+    /// Replaces an employer's disbursement approval tiers. Payouts in a tier
+    /// needing approvals must be claimed with `claim_payroll_multisig`. An
+    /// empty list falls back to the global large payment threshold.
+    ///
+    /// # Errors
+    /// * `Unauthorized` - `owner` is not the contract owner
+    /// * `InvalidData` - too many tiers, tiers not strictly ascending by a
+    ///   positive `min_amount`, or a tier naming more roles than approvals
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_approval_matrix(
+        env: Env,
+        owner: Address,
+        employer: Address,
+        tiers: Vec<ApprovalTier>,
+    ) -> Result<(), PayrollError> {
+        approval_matrix::set_approval_matrix(&env, &owner, &employer, tiers)
+    }
+
+    /// Returns an employer's disbursement approval tiers, empty if it has none.
+    pub fn get_approval_matrix(env: Env, employer: Address) -> Vec<ApprovalTier> {
+        approval_matrix::get_approval_matrix(&env, &employer)
+    }
+
+    /// Returns the approvals a payout of `amount` by `employer` needs before it
+    /// can be claimed. A tier with no approvals means it can be claimed directly.
+    pub fn get_required_approvals(env: Env, employer: Address, amount: i128) -> ApprovalTier {
+        approval_matrix::get_required_approvals(&env, &employer, amount)
+    }
+
     /// Retrieves current dispute status for an agreement by ID
     ///
     /// # Returns
@@ -890,7 +1312,7 @@ impl PayrollContract {
     /// # Security
     /// - Requires `caller` to be the employee at `employee_index` (`Unauthorized` otherwise).
     /// - Rejects claims when the contract is emergency-paused or the agreement is paused.
-    /// - Large payments above `LargePaymentThreshold` require `claim_payroll_multisig`.
+    /// - Payouts needing approvals under `get_required_approvals` require `claim_payroll_multisig`.
     /// - Enforces checks-effects-interactions: escrow balance, `claimed_periods`,
     ///   and `paid_amount` are persisted BEFORE the external token transfer.
     /// - Protected by a transient reentrancy guard (temporary storage, cleared per
@@ -988,106 +1410,1971 @@ impl PayrollContract {
         payroll::batch_claim_payroll(&env, &caller, agreement_id, employee_indices)
     }
 
-    /// Get claimed periods for an employee
+    /// Switches an employee to confidential mode by storing a salted salary commitment.
     ///
-    /// # Arguments
-    /// * `env` - Contract environment
-    /// * `agreement_id` - ID of the agreement
-    /// * `employee_index` - Index of the employee in the agreement
+    /// The plaintext salary for the slot is removed from storage; from then on the
+    /// employee is paid via `disburse_confidential_payroll` rather than `claim_payroll`.
     ///
-    /// # Returns
-    /// Number of periods claimed
+    /// # Arguments
+    /// * `agreement_id` - Payroll agreement ID
+    /// * `employee_index` - 0-based index of the employee within the agreement
+    /// * `commitment` - `sha256(agreement_id || employee_index || amount || salt)`
     ///
     /// # Access Control
-    /// Requires caller authentication
-    pub fn get_employee_claimed_periods(env: Env, agreement_id: u128, employee_index: u32) -> u32 {
-        payroll::get_employee_claimed_periods(&env, agreement_id, employee_index)
+    /// Requires employer authentication
+    pub fn set_salary_commitment(
+        env: Env,
+        agreement_id: u128,
+        employee_index: u32,
+        commitment: BytesN<32>,
+    ) -> Result<(), PayrollError> {
+        confidential::set_salary_commitment(&env, agreement_id, employee_index, commitment)
     }
 
-    /// Pauses an active agreement, preventing claims.
-    ///
-    /// # Arguments
-    /// * `agreement_id` - ID of the agreement to pause
-    ///
-    /// # State Transition
-    /// Active -> Paused
+    /// Links the `payroll_analytics` contract every payout is pushed to, or
+    /// unlinks it with `None`. Employee metrics and employer reporting totals
+    /// are queried there. See [`metrics`] for what is pushed.
     ///
-    /// # Requirements
-    /// - Agreement must be in Active status
-    /// - Caller must be the employer
+    /// # Errors
+    /// * `PayrollError::Unauthorized` - `owner` is not the contract owner
     ///
-    /// # Behavior
-    /// - Paused agreements cannot have claims processed
-    /// - Agreement state is preserved
-    /// - Can be resumed later or cancelled
-    pub fn pause_agreement(env: Env, agreement_id: u128) -> Result<(), PayrollError> {
-        // Try new-style agreement first (payroll/escrow)
-        if payroll::get_agreement(&env, agreement_id).is_some() {
-            payroll::pause_agreement(&env, agreement_id);
-            return Ok(());
-        }
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_analytics_contract(
+        env: Env,
+        owner: Address,
+        analytics: Option<Address>,
+    ) -> Result<(), PayrollError> {
+        metrics::set_analytics_contract(&env, &owner, analytics)
+    }
 
-        // Fall back to milestone-based agreement
-        payroll::pause_milestone_agreement(env, agreement_id)
+    /// Returns the linked analytics contract, if any.
+    pub fn get_analytics_contract(env: Env) -> Option<Address> {
+        metrics::get_analytics_contract(&env)
     }
 
-    /// Resumes a paused agreement, allowing claims again.
+    /// Links the `webhook_contract` registry every payout is notified to, or
+    /// unlinks it with `None`. See [`webhooks`] for what is sent.
     ///
-    /// # Arguments
-    /// * `agreement_id` - ID of the agreement to resume
-    ///
-    /// # State Transition
-    /// Paused -> Active
-    ///
-    /// # Requirements
-    /// - Agreement must be in Paused status
-    /// - Caller must be the employer
+    /// # Errors
+    /// * `PayrollError::Unauthorized` - `owner` is not the contract owner
     ///
-    /// # Behavior
-    /// - Agreement returns to Active status
-    /// - Claims can be processed again
-    /// - All agreement data is preserved
-    pub fn resume_agreement(env: Env, agreement_id: u128) -> Result<(), PayrollError> {
-        // Try new-style agreement first (payroll/escrow)
-        if payroll::get_agreement(&env, agreement_id).is_some() {
-            payroll::resume_agreement(&env, agreement_id);
-            return Ok(());
-        }
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_webhook_registry(
+        env: Env,
+        owner: Address,
+        registry: Option<Address>,
+    ) -> Result<(), PayrollError> {
+        webhooks::set_webhook_registry(&env, &owner, registry)
+    }
 
-        // Fall back to milestone-based agreement
-        payroll::resume_milestone_agreement(env, agreement_id)
+    /// Returns the linked webhook registry, if any.
+    pub fn get_webhook_registry(env: Env) -> Option<Address> {
+        webhooks::get_webhook_registry(&env)
     }
 
-    /// Claims time-based payments for an escrow agreement based on elapsed periods.
-    ///
-    /// # Arguments
-    /// * `agreement_id` - ID of the escrow agreement
-    ///
-    /// # Returns
-    /// * `Ok(())` on success
-    /// * `Err(PayrollError)` on failure
-    ///
-    /// # Requirements
-    /// - Agreement must be Active and activated
-    /// - Agreement must be Escrow mode
-    /// - Caller must be the contributor
-    /// - Cannot claim more than total periods
-    /// - Works during grace period
-    pub fn claim_time_based(env: Env, agreement_id: u128) -> Result<(), storage::PayrollError> {
-        payroll::claim_time_based(&env, agreement_id)
+    /// Describes a `PayrollError` code: its name, category, an English
+    /// description and the key of the suggested fix in the error catalogs.
+    /// Returns `None` for codes this contract never returns.
+    pub fn get_error_info(env: Env, code: u32) -> Option<ErrorInfo> {
+        errors::get_error_info(&env, code)
     }
 
-    /// Gets the number of claimed periods for a time-based escrow agreement.
-    ///
-    /// # Arguments
-    /// * `agreement_id` - ID of the agreement
+    /// Returns protocol-wide totals for dashboards: employers, active
+    /// payrolls, amount disbursed per token and, when a bonus contract is
+    /// linked, the incentive payouts claimed from it.
+    pub fn get_protocol_stats(env: Env) -> ProtocolStats {
+        stats::get_protocol_stats(&env)
+    }
+
+    /// Links the governance contract whose yield strategy allow-list
+    /// [`Self::deposit_to_strategy`] checks.
     ///
-    /// # Returns
-    /// Number of claimed periods, or 0 if not a time-based agreement
+    /// # Errors
+    /// * `PayrollError::Unauthorized` - `owner` is not the contract owner
     ///
     /// # Access Control
-    /// Requires caller authentication
-    pub fn get_claimed_periods(env: Env, agreement_id: u128) -> u32 {
+    /// Requires owner authentication
+    pub fn set_strategy_governance(
+        env: Env,
+        owner: Address,
+        governance: Address,
+    ) -> Result<(), PayrollError> {
+        yield_strategy::set_strategy_governance(&env, &owner, &governance)
+    }
+
+    /// Returns the linked strategy governance contract, if any.
+    pub fn get_strategy_governance(env: Env) -> Option<Address> {
+        yield_strategy::get_strategy_governance(&env)
+    }
+
+    /// Routes `amount` of an agreement's idle escrow to an allow-listed yield
+    /// strategy. The amount stays in the escrow balance as principal and is
+    /// withdrawn automatically before a payout needs it. See
+    /// [`yield_strategy`].
+    ///
+    /// # Errors
+    /// * `PayrollError::AgreementNotFound` - the agreement does not exist
+    /// * `PayrollError::Unauthorized` - `employer` does not own the agreement,
+    ///   or `strategy` is not allow-listed
+    /// * `PayrollError::EmergencyPaused` - the contract is paused
+    /// * `PayrollError::InvalidData` - no governance is linked, the amount or
+    ///   agreement status is invalid, or the strategy does not match
+    /// * `PayrollError::InsufficientEscrowBalance` - not enough idle escrow
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn deposit_to_strategy(
+        env: Env,
+        employer: Address,
+        agreement_id: u128,
+        strategy: Address,
+        amount: i128,
+    ) -> Result<(), PayrollError> {
+        yield_strategy::deposit_to_strategy(&env, &employer, agreement_id, &strategy, amount)
+    }
+
+    /// Closes an agreement's strategy position. The principal returns to the
+    /// escrow and the yield is credited to the employer; returns the yield,
+    /// negative for a loss.
+    ///
+    /// # Errors
+    /// * `PayrollError::AgreementNotFound` - the agreement does not exist
+    /// * `PayrollError::Unauthorized` - `employer` does not own the agreement
+    /// * `PayrollError::InvalidData` - the agreement has no position
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn withdraw_from_strategy(
+        env: Env,
+        employer: Address,
+        agreement_id: u128,
+    ) -> Result<i128, PayrollError> {
+        yield_strategy::withdraw_from_strategy(&env, &employer, agreement_id)
+    }
+
+    /// Pays out the yield the employer earned in `token` and returns it.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn withdraw_earned_yield(env: Env, employer: Address, token: Address) -> i128 {
+        yield_strategy::withdraw_earned_yield(&env, &employer, &token)
+    }
+
+    /// Returns an agreement's strategy position, if any.
+    pub fn get_yield_position(env: Env, agreement_id: u128) -> Option<YieldPosition> {
+        yield_strategy::get_yield_position(&env, agreement_id)
+    }
+
+    /// Returns the current value of an agreement's strategy position.
+    pub fn get_yield_position_value(env: Env, agreement_id: u128) -> i128 {
+        yield_strategy::get_position_value(&env, agreement_id)
+    }
+
+    /// Returns the yield the employer earned in `token` and has not withdrawn.
+    pub fn get_earned_yield(env: Env, employer: Address, token: Address) -> i128 {
+        yield_strategy::get_earned_yield(&env, &employer, &token)
+    }
+
+    /// Sets (or clears, with `None`) the employer's reporting currency. Payouts
+    /// on the employer's agreements are then converted into it at the stored
+    /// oracle rate and summed by the analytics contract.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn set_reporting_currency(env: Env, employer: Address, currency: Option<Address>) {
+        metrics::set_reporting_currency(&env, &employer, currency)
+    }
+
+    /// Returns the employer's reporting currency, if set.
+    pub fn get_reporting_currency(env: Env, employer: Address) -> Option<Address> {
+        metrics::get_reporting_currency(&env, &employer)
+    }
+
+    /// Sets the compliance admin who may manage garnishment orders alongside
+    /// the owner.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_compliance_admin(
+        env: Env,
+        owner: Address,
+        admin: Address,
+    ) -> Result<(), PayrollError> {
+        garnishment::set_compliance_admin(&env, &owner, &admin)
+    }
+
+    /// Returns the compliance admin, if set.
+    pub fn get_compliance_admin(env: Env) -> Option<Address> {
+        garnishment::get_compliance_admin(&env)
+    }
+
+    /// Registers a garnishment order: every periodic payout to `employee` in
+    /// `token` routes `deduction` to `recipient` until `total_cap` has been
+    /// withheld. Returns the order id.
+    ///
+    /// # Access Control
+    /// Requires owner or compliance admin authentication
+    pub fn register_garnishment(
+        env: Env,
+        caller: Address,
+        employee: Address,
+        recipient: Address,
+        token: Address,
+        deduction: GarnishmentDeduction,
+        total_cap: i128,
+    ) -> Result<u64, PayrollError> {
+        garnishment::register_garnishment(
+            &env, &caller, &employee, &recipient, &token, deduction, total_cap,
+        )
+    }
+
+    /// Releases an active garnishment order before its cap is reached.
+    ///
+    /// # Access Control
+    /// Requires owner or compliance admin authentication
+    pub fn release_garnishment(
+        env: Env,
+        caller: Address,
+        order_id: u64,
+    ) -> Result<(), PayrollError> {
+        garnishment::release_garnishment(&env, &caller, order_id)
+    }
+
+    /// Returns a garnishment order by id.
+    pub fn get_garnishment(env: Env, order_id: u64) -> Option<GarnishmentOrder> {
+        garnishment::get_garnishment(&env, order_id)
+    }
+
+    /// Returns the ids of the employee's active garnishment orders.
+    pub fn get_employee_garnishments(env: Env, employee: Address) -> Vec<u64> {
+        garnishment::get_employee_garnishments(&env, &employee)
+    }
+
+    /// Elects to save `bps` of each payout into a vault locked for
+    /// `lock_duration` seconds per term.
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn set_savings_plan(
+        env: Env,
+        employee: Address,
+        bps: u32,
+        lock_duration: u64,
+    ) -> Result<(), PayrollError> {
+        savings::set_savings_plan(&env, &employee, bps, lock_duration)
+    }
+
+    /// Stops saving from future payouts.
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn cancel_savings_plan(env: Env, employee: Address) {
+        savings::cancel_savings_plan(&env, &employee)
+    }
+
+    /// Returns the employee's savings plan, if any.
+    pub fn get_savings_plan(env: Env, employee: Address) -> Option<SavingsPlan> {
+        savings::get_savings_plan(&env, &employee)
+    }
+
+    /// Returns the employee's savings vault for `token`.
+    pub fn get_savings_vault(env: Env, employee: Address, token: Address) -> SavingsVault {
+        savings::get_savings_vault(&env, &employee, &token)
+    }
+
+    /// Sets the early savings withdrawal penalty and the insurance pool it is
+    /// paid to.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_savings_penalty(
+        env: Env,
+        owner: Address,
+        penalty_bps: u32,
+        insurance_pool: Address,
+    ) -> Result<(), PayrollError> {
+        savings::set_savings_penalty(&env, &owner, penalty_bps, &insurance_pool)
+    }
+
+    /// Returns the early savings withdrawal penalty configuration, if set.
+    pub fn get_savings_penalty(env: Env) -> Option<SavingsPenaltyConfig> {
+        savings::get_savings_penalty(&env)
+    }
+
+    /// Withdraws `amount` of savings in `token`. Withdrawing locked savings
+    /// requires `accept_penalty` and pays the penalty to the insurance pool,
+    /// or to the treasury if the pool is this contract.
+    /// Returns the amount the employee received.
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn withdraw_savings(
+        env: Env,
+        employee: Address,
+        token: Address,
+        amount: i128,
+        accept_penalty: bool,
+    ) -> Result<i128, PayrollError> {
+        savings::withdraw_savings(&env, &employee, &token, amount, accept_penalty)
+    }
+
+    /// Registers the ed25519 key the employee signs relayed actions with,
+    /// replacing any previous key. See `execute_signed_action`.
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn register_signing_key(env: Env, employee: Address, public_key: BytesN<32>) {
+        meta_tx::register_signing_key(&env, &employee, public_key)
+    }
+
+    /// Removes the employee's signing key, disabling signed actions.
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn revoke_signing_key(env: Env, employee: Address) {
+        meta_tx::revoke_signing_key(&env, &employee)
+    }
+
+    /// Returns the employee's signing key, if registered.
+    pub fn get_signing_key(env: Env, employee: Address) -> Option<BytesN<32>> {
+        meta_tx::get_signing_key(&env, &employee)
+    }
+
+    /// Registers the user's passkey, an uncompressed SEC-1 secp256r1 public
+    /// key, replacing any previous one. See `execute_passkey_action`.
+    ///
+    /// # Errors
+    /// * `InvalidData` - the key is not in uncompressed form
+    ///
+    /// # Access Control
+    /// Requires user authentication
+    pub fn register_passkey(
+        env: Env,
+        user: Address,
+        public_key: BytesN<65>,
+    ) -> Result<(), PayrollError> {
+        meta_tx::register_passkey(&env, &user, public_key)
+    }
+
+    /// Removes the user's passkey, disabling passkey-signed actions.
+    ///
+    /// # Access Control
+    /// Requires user authentication
+    pub fn revoke_passkey(env: Env, user: Address) {
+        meta_tx::revoke_passkey(&env, &user)
+    }
+
+    /// Returns the user's passkey, if registered.
+    pub fn get_passkey(env: Env, user: Address) -> Option<BytesN<65>> {
+        meta_tx::get_passkey(&env, &user)
+    }
+
+    /// Returns the WebAuthn challenge a passkey signs to authorize `request`.
+    pub fn passkey_challenge(env: Env, request: SignedRequest) -> BytesN<32> {
+        meta_tx::passkey_challenge(&env, &request)
+    }
+
+    /// Verifies a WebAuthn passkey assertion over `request` and runs its
+    /// action as the signer. The relayer pays the fee.
+    ///
+    /// # Errors
+    /// * `EmergencyPaused` - the contract is paused
+    /// * `Unauthorized` - the signer has no passkey
+    /// * `InvalidData` - the request has expired or has the wrong nonce, or
+    ///   the assertion is malformed or for another challenge
+    /// * Any error of the action itself
+    ///
+    /// # Access Control
+    /// Requires relayer authentication and a valid passkey assertion
+    pub fn execute_passkey_action(
+        env: Env,
+        relayer: Address,
+        request: SignedRequest,
+        authenticator_data: Bytes,
+        client_data_json: Bytes,
+        signature: BytesN<64>,
+    ) -> Result<(), PayrollError> {
+        meta_tx::execute_passkey_action(
+            &env,
+            &relayer,
+            request,
+            authenticator_data,
+            client_data_json,
+            signature,
+        )
+    }
+
+    /// Sets the employer's terms for sponsoring relayed withdrawals.
+    ///
+    /// # Errors
+    /// * `InvalidData` - invalid fee, cap or period, or a token change while
+    ///   the pool holds funds
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn set_sponsorship_policy(
+        env: Env,
+        employer: Address,
+        policy: SponsorshipPolicy,
+    ) -> Result<(), PayrollError> {
+        sponsorship::set_sponsorship_policy(&env, &employer, policy)
+    }
+
+    /// Returns the employer's sponsorship policy, if set.
+    pub fn get_sponsorship_policy(env: Env, employer: Address) -> Option<SponsorshipPolicy> {
+        sponsorship::get_sponsorship_policy(&env, &employer)
+    }
+
+    /// Adds tokens to the employer's sponsorship pool.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn fund_sponsorship_pool(
+        env: Env,
+        employer: Address,
+        amount: i128,
+    ) -> Result<(), PayrollError> {
+        sponsorship::fund_sponsorship_pool(&env, &employer, amount)
+    }
+
+    /// Returns tokens from the employer's sponsorship pool.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn withdraw_sponsorship_pool(
+        env: Env,
+        employer: Address,
+        amount: i128,
+    ) -> Result<(), PayrollError> {
+        sponsorship::withdraw_sponsorship_pool(&env, &employer, amount)
+    }
+
+    /// Returns the balance of the employer's sponsorship pool.
+    pub fn get_sponsorship_pool(env: Env, employer: Address) -> i128 {
+        sponsorship::get_sponsorship_pool(&env, &employer)
+    }
+
+    /// Returns how many of the employee's withdrawals the employer has
+    /// sponsored in the current period.
+    pub fn get_sponsored_withdrawals(env: Env, employer: Address, employee: Address) -> u32 {
+        sponsorship::get_sponsored_withdrawals(&env, &employer, &employee)
+    }
+
+    /// Runs an employee's signed `ClaimPayroll` request and pays the relayer
+    /// `fee`, up to the policy's `max_fee`, from the employer's sponsorship
+    /// pool.
+    ///
+    /// # Errors
+    /// * `InvalidData` - not a payroll claim, no policy, or `fee` above the cap
+    /// * `RateLimited` - the employee's sponsored withdrawals for the period
+    ///   are used up
+    /// * `InsufficientEscrowBalance` - the pool cannot cover `fee`
+    /// * Any error of `execute_signed_action`
+    ///
+    /// # Access Control
+    /// Requires relayer authentication and a valid employee signature
+    pub fn sponsored_withdraw(
+        env: Env,
+        relayer: Address,
+        request: SignedRequest,
+        signature: BytesN<64>,
+        fee: i128,
+    ) -> Result<(), PayrollError> {
+        sponsorship::sponsored_withdraw(&env, &relayer, request, signature, fee)
+    }
+
+    /// Returns the nonce the employee's next signed request must carry.
+    pub fn get_signing_nonce(env: Env, employee: Address) -> u64 {
+        meta_tx::get_signing_nonce(&env, &employee)
+    }
+
+    /// Returns the bytes the employee signs to authorize `request`.
+    pub fn signed_action_payload(env: Env, request: SignedRequest) -> Bytes {
+        meta_tx::signed_action_payload(&env, &request)
+    }
+
+    /// Runs an action the employee signed off-chain, relayed and paid for by
+    /// `relayer`. The request must carry the employee's next nonce and not be
+    /// expired; an invalid signature aborts the call.
+    ///
+    /// # Errors
+    /// * `Unauthorized` - the employee has no signing key
+    /// * `InvalidData` - the request has expired or has the wrong nonce
+    /// * Any error of the action itself
+    ///
+    /// # Access Control
+    /// Requires relayer authentication and a valid employee signature
+    pub fn execute_signed_action(
+        env: Env,
+        relayer: Address,
+        request: SignedRequest,
+        signature: BytesN<64>,
+    ) -> Result<(), PayrollError> {
+        meta_tx::execute_signed_action(&env, &relayer, request, signature)
+    }
+
+    /// Links the governance contract issuing auditor and mediator
+    /// credentials.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_credential_registry(
+        env: Env,
+        owner: Address,
+        registry: Address,
+    ) -> Result<(), PayrollError> {
+        credentials::set_credential_registry(&env, &owner, &registry)
+    }
+
+    /// Returns the linked credential registry, if any.
+    pub fn get_credential_registry(env: Env) -> Option<Address> {
+        credentials::get_credential_registry(&env)
+    }
+
+    /// Sets a metadata entry on an employer, or on one of its employees when
+    /// `employee` is given. See [`metadata`].
+    ///
+    /// # Errors
+    /// - `InvalidData` if the key is empty or longer than
+    ///   `MAX_METADATA_KEY_LEN`, or the value is longer than `MAX_STRING_LEN`
+    /// - `BatchTooLarge` if the key is new and `MAX_METADATA_ENTRIES` are set
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn set_employer_metadata(
+        env: Env,
+        employer: Address,
+        employee: Option<Address>,
+        key: String,
+        value: String,
+    ) -> Result<(), PayrollError> {
+        metadata::set_metadata(&env, &employer, &employee, &key, &value)
+    }
+
+    /// Removes a metadata entry. Removing a key that is not set is a no-op.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn remove_employer_metadata(
+        env: Env,
+        employer: Address,
+        employee: Option<Address>,
+        key: String,
+    ) {
+        metadata::remove_metadata(&env, &employer, &employee, &key)
+    }
+
+    /// Returns a metadata entry, if set.
+    pub fn get_employer_metadata(
+        env: Env,
+        employer: Address,
+        employee: Option<Address>,
+        key: String,
+    ) -> Option<String> {
+        metadata::get_metadata(&env, &employer, &employee, &key)
+    }
+
+    /// Returns every metadata entry of an employer or employee.
+    pub fn list_employer_metadata(
+        env: Env,
+        employer: Address,
+        employee: Option<Address>,
+    ) -> Map<String, String> {
+        metadata::list_metadata(&env, &employer, &employee)
+    }
+
+    /// Approves or removes a lender allowed to fund salary loans.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_loan_lender(
+        env: Env,
+        owner: Address,
+        lender: Address,
+        approved: bool,
+    ) -> Result<(), PayrollError> {
+        loans::set_loan_lender(&env, &owner, &lender, approved)
+    }
+
+    /// Returns whether `lender` may fund salary loans.
+    pub fn is_loan_lender(env: Env, lender: Address) -> bool {
+        loans::is_loan_lender(&env, &lender)
+    }
+
+    /// Requests a loan repaid from a pledged share of up to `pledged_payouts`
+    /// future payouts of a payroll agreement. Returns the loan id.
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn request_salary_loan(
+        env: Env,
+        employee: Address,
+        agreement_id: u128,
+        principal: i128,
+        interest_bps: u32,
+        pledge_bps: u32,
+        pledged_payouts: u32,
+    ) -> Result<u64, PayrollError> {
+        loans::request_salary_loan(
+            &env,
+            &employee,
+            agreement_id,
+            principal,
+            interest_bps,
+            pledge_bps,
+            pledged_payouts,
+        )
+    }
+
+    /// Withdraws an unfunded loan request.
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn cancel_loan_request(
+        env: Env,
+        employee: Address,
+        loan_id: u64,
+    ) -> Result<(), PayrollError> {
+        loans::cancel_loan_request(&env, &employee, loan_id)
+    }
+
+    /// Funds a requested salary loan, paying the principal to the employee.
+    ///
+    /// # Access Control
+    /// Requires approved lender authentication
+    pub fn fund_salary_loan(env: Env, lender: Address, loan_id: u64) -> Result<(), PayrollError> {
+        loans::fund_salary_loan(&env, &lender, loan_id)
+    }
+
+    /// Marks a salary loan as defaulted once its agreement's payroll has
+    /// ended early.
+    ///
+    /// # Access Control
+    /// Requires the loan's lender authentication
+    pub fn declare_loan_default(
+        env: Env,
+        lender: Address,
+        loan_id: u64,
+    ) -> Result<(), PayrollError> {
+        loans::declare_loan_default(&env, &lender, loan_id)
+    }
+
+    /// Returns a salary loan by id.
+    pub fn get_salary_loan(env: Env, loan_id: u64) -> Option<SalaryLoan> {
+        loans::get_salary_loan(&env, loan_id)
+    }
+
+    /// Returns the employee's requested or active loan against an agreement.
+    pub fn get_open_loan(env: Env, employee: Address, agreement_id: u128) -> Option<u64> {
+        loans::get_open_loan(&env, &employee, agreement_id)
+    }
+
+    /// Switches a payroll agreement to hourly pay before activation. Each
+    /// employee's salary per period becomes their hourly rate.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn enable_hourly_pay(
+        env: Env,
+        employer: Address,
+        agreement_id: u128,
+        timekeeper: Address,
+        max_hours_per_period: u32,
+    ) -> Result<(), PayrollError> {
+        hourly::enable_hourly_pay(
+            &env,
+            &employer,
+            agreement_id,
+            &timekeeper,
+            max_hours_per_period,
+        )
+    }
+
+    /// Replaces the timekeeper of an hourly agreement.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn set_hourly_timekeeper(
+        env: Env,
+        employer: Address,
+        agreement_id: u128,
+        timekeeper: Address,
+    ) -> Result<(), PayrollError> {
+        hourly::set_hourly_timekeeper(&env, &employer, agreement_id, &timekeeper)
+    }
+
+    /// Returns the hourly pay settings of an agreement, if it is hourly.
+    pub fn get_hourly_config(env: Env, agreement_id: u128) -> Option<HourlyConfig> {
+        hourly::get_hourly_config(&env, agreement_id)
+    }
+
+    /// Records the hours an employee worked in an ended period of an hourly
+    /// agreement.
+    ///
+    /// # Access Control
+    /// Requires timekeeper authentication
+    pub fn attest_hours(
+        env: Env,
+        timekeeper: Address,
+        agreement_id: u128,
+        employee: Address,
+        period: u32,
+        hours: u32,
+    ) -> Result<(), PayrollError> {
+        hourly::attest_hours(&env, &timekeeper, agreement_id, &employee, period, hours)
+    }
+
+    /// Returns the hours attested for an employee in a period, if any.
+    pub fn get_attested_hours(
+        env: Env,
+        agreement_id: u128,
+        employee: Address,
+        period: u32,
+    ) -> Option<u32> {
+        hourly::get_attested_hours(&env, agreement_id, &employee, period)
+    }
+
+    /// Submits an invoice against an escrow agreement. Returns its number
+    /// within the agreement.
+    ///
+    /// # Access Control
+    /// Requires contributor authentication
+    pub fn submit_invoice(
+        env: Env,
+        contributor: Address,
+        agreement_id: u128,
+        amount: i128,
+        description_hash: BytesN<32>,
+        due_date: u64,
+    ) -> Result<u32, PayrollError> {
+        invoices::submit_invoice(
+            &env,
+            &contributor,
+            agreement_id,
+            amount,
+            description_hash,
+            due_date,
+        )
+    }
+
+    /// Approves an open invoice and pays it from the agreement's escrow.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn approve_invoice(
+        env: Env,
+        employer: Address,
+        agreement_id: u128,
+        number: u32,
+    ) -> Result<(), PayrollError> {
+        invoices::approve_invoice(&env, &employer, agreement_id, number)
+    }
+
+    /// Disputes a submitted invoice.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn dispute_invoice(
+        env: Env,
+        employer: Address,
+        agreement_id: u128,
+        number: u32,
+    ) -> Result<(), PayrollError> {
+        invoices::dispute_invoice(&env, &employer, agreement_id, number)
+    }
+
+    /// Withdraws a submitted or disputed invoice.
+    ///
+    /// # Access Control
+    /// Requires contributor authentication
+    pub fn withdraw_invoice(
+        env: Env,
+        contributor: Address,
+        agreement_id: u128,
+        number: u32,
+    ) -> Result<(), PayrollError> {
+        invoices::withdraw_invoice(&env, &contributor, agreement_id, number)
+    }
+
+    /// Returns an invoice by agreement and number.
+    pub fn get_invoice(env: Env, agreement_id: u128, number: u32) -> Option<Invoice> {
+        invoices::get_invoice(&env, agreement_id, number)
+    }
+
+    /// Returns how many invoices have been submitted against an agreement.
+    pub fn get_invoice_count(env: Env, agreement_id: u128) -> u32 {
+        invoices::get_invoice_count(&env, agreement_id)
+    }
+
+    /// Returns the numbers of an agreement's open invoices, oldest first.
+    pub fn get_open_invoices(env: Env, agreement_id: u128) -> Vec<u32> {
+        invoices::get_open_invoices(&env, agreement_id)
+    }
+
+    /// Returns an agreement's open invoice amounts grouped by days overdue.
+    pub fn get_invoice_aging_report(env: Env, agreement_id: u128) -> InvoiceAgingReport {
+        invoices::get_invoice_aging_report(&env, agreement_id)
+    }
+
+    /// Designates or removes an approver for the employer's reimbursements.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn set_reimbursement_approver(
+        env: Env,
+        employer: Address,
+        approver: Address,
+        approved: bool,
+    ) {
+        reimbursements::set_reimbursement_approver(&env, &employer, &approver, approved)
+    }
+
+    /// Returns whether `approver` may decide the employer's reimbursements.
+    pub fn is_reimbursement_approver(env: Env, employer: Address, approver: Address) -> bool {
+        reimbursements::is_reimbursement_approver(&env, &employer, &approver)
+    }
+
+    /// Adds tokens to the employer's reimbursement pool.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn fund_reimbursement_pool(
+        env: Env,
+        employer: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), PayrollError> {
+        reimbursements::fund_reimbursement_pool(&env, &employer, &token, amount)
+    }
+
+    /// Returns tokens from the employer's reimbursement pool.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn withdraw_reimbursement_pool(
+        env: Env,
+        employer: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), PayrollError> {
+        reimbursements::withdraw_reimbursement_pool(&env, &employer, &token, amount)
+    }
+
+    /// Returns the balance of the employer's reimbursement pool in `token`.
+    pub fn get_reimbursement_pool(env: Env, employer: Address, token: Address) -> i128 {
+        reimbursements::get_reimbursement_pool(&env, &employer, &token)
+    }
+
+    /// Submits an expense reimbursement request backed by a receipt hash.
+    /// Returns the request id.
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn submit_reimbursement(
+        env: Env,
+        employee: Address,
+        amount: i128,
+        token: Address,
+        receipt_hash: BytesN<32>,
+    ) -> Result<u64, PayrollError> {
+        reimbursements::submit_reimbursement(&env, &employee, amount, &token, receipt_hash)
+    }
+
+    /// Withdraws a pending reimbursement request.
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn cancel_reimbursement(
+        env: Env,
+        employee: Address,
+        request_id: u64,
+    ) -> Result<(), PayrollError> {
+        reimbursements::cancel_reimbursement(&env, &employee, request_id)
+    }
+
+    /// Approves a pending reimbursement and pays it from the employer's pool.
+    ///
+    /// # Access Control
+    /// Requires employer or approver authentication
+    pub fn approve_reimbursement(
+        env: Env,
+        approver: Address,
+        employer: Address,
+        request_id: u64,
+    ) -> Result<(), PayrollError> {
+        reimbursements::approve_reimbursement(&env, &approver, &employer, request_id)
+    }
+
+    /// Rejects a pending reimbursement.
+    ///
+    /// # Access Control
+    /// Requires employer or approver authentication
+    pub fn reject_reimbursement(
+        env: Env,
+        approver: Address,
+        employer: Address,
+        request_id: u64,
+    ) -> Result<(), PayrollError> {
+        reimbursements::reject_reimbursement(&env, &approver, &employer, request_id)
+    }
+
+    /// Returns a reimbursement request by id.
+    pub fn get_reimbursement(env: Env, request_id: u64) -> Option<ReimbursementRequest> {
+        reimbursements::get_reimbursement(&env, request_id)
+    }
+
+    /// Returns the reimbursement request a receipt hash backs, if any.
+    pub fn get_reimbursement_by_receipt(env: Env, receipt_hash: BytesN<32>) -> Option<u64> {
+        reimbursements::get_reimbursement_by_receipt(&env, receipt_hash)
+    }
+
+    /// Returns an employer's reputation score and tier, derived from their
+    /// on-time payment rate, dispute outcomes, defaults and staking.
+    pub fn get_employer_reputation(env: Env, employer: Address) -> EmployerReputation {
+        reputation::get_employer_reputation(&env, &employer)
+    }
+
+    /// Sets the employer's payment punctuality SLA. Penalties and pre-funding
+    /// from earlier alerts are kept.
+    ///
+    /// # Errors
+    /// * `InvalidData` - invalid target, window or breach count
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn set_sla_config(
+        env: Env,
+        employer: Address,
+        config: SlaConfig,
+    ) -> Result<(), PayrollError> {
+        sla::set_sla_config(&env, &employer, config)
+    }
+
+    /// Returns the employer's SLA, if set.
+    pub fn get_sla_config(env: Env, employer: Address) -> Option<SlaConfig> {
+        sla::get_sla_config(&env, &employer)
+    }
+
+    /// Returns the employer's SLA tracking state as last stored.
+    pub fn get_sla_status(env: Env, employer: Address) -> SlaStatus {
+        sla::get_sla_status(&env, &employer)
+    }
+
+    /// Evaluates the employer's ended SLA windows, raising any alert due, and
+    /// returns the updated status. Callable by anyone.
+    ///
+    /// # Errors
+    /// * `InvalidData` - the employer has no SLA
+    pub fn check_sla(env: Env, employer: Address) -> Result<SlaStatus, PayrollError> {
+        sla::check_sla(&env, &employer)
+    }
+
+    /// Sets the token employers stake and the stake that boosts their tier.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_reputation_stake_config(
+        env: Env,
+        owner: Address,
+        token: Address,
+        boost_amount: i128,
+    ) -> Result<(), PayrollError> {
+        reputation::set_reputation_stake_config(&env, &owner, &token, boost_amount)
+    }
+
+    /// Returns the reputation stake configuration, if set.
+    pub fn get_reputation_stake_config(env: Env) -> Option<ReputationStakeConfig> {
+        reputation::get_reputation_stake_config(&env)
+    }
+
+    /// Adds `amount` of the stake token to the employer's reputation stake.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn stake_reputation(env: Env, employer: Address, amount: i128) -> Result<(), PayrollError> {
+        reputation::stake_reputation(&env, &employer, amount)
+    }
+
+    /// Starts the unstake cooldown for the employer's whole stake. Returns
+    /// when it can be withdrawn.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn request_unstake(env: Env, employer: Address) -> Result<u64, PayrollError> {
+        reputation::request_unstake(&env, &employer)
+    }
+
+    /// Returns the employer's stake after the unstake cooldown. Returns the
+    /// amount withdrawn.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn withdraw_stake(env: Env, employer: Address) -> Result<i128, PayrollError> {
+        reputation::withdraw_stake(&env, &employer)
+    }
+
+    /// Returns the employer's reputation stake, if any.
+    pub fn get_reputation_stake(env: Env, employer: Address) -> Option<ReputationStake> {
+        reputation::get_reputation_stake(&env, &employer)
+    }
+
+    /// Extends a job offer for a payroll agreement, locking `signing_bonus`
+    /// of the agreement's token. Returns the offer ID.
+    ///
+    /// # Errors
+    /// * `InvalidData` - the agreement is neither created nor active, the
+    ///   bonus is invalid, or `accept_by` is not in the future
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn extend_offer(
+        env: Env,
+        employer: Address,
+        candidate: Address,
+        agreement_id: u128,
+        signing_bonus: i128,
+        accept_by: u64,
+    ) -> Result<u64, PayrollError> {
+        offers::extend_offer(
+            &env,
+            &employer,
+            &candidate,
+            agreement_id,
+            signing_bonus,
+            accept_by,
+        )
+    }
+
+    /// Returns a job offer by ID.
+    pub fn get_offer(env: Env, offer_id: u64) -> Option<JobOffer> {
+        offers::get_offer(&env, offer_id)
+    }
+
+    /// Accepts a pending job offer before its deadline.
+    ///
+    /// # Access Control
+    /// Requires candidate authentication
+    pub fn accept_offer(env: Env, candidate: Address, offer_id: u64) -> Result<(), PayrollError> {
+        offers::accept_offer(&env, &candidate, offer_id)
+    }
+
+    /// Pays an accepted offer's signing bonus once the candidate is on the
+    /// active agreement. Returns the bonus paid.
+    ///
+    /// # Errors
+    /// * `AgreementNotActivated` - the agreement is not active yet
+    /// * `NoEmployee` - the candidate has not been added to the agreement
+    ///
+    /// # Access Control
+    /// Requires candidate authentication
+    pub fn claim_offer_bonus(
+        env: Env,
+        candidate: Address,
+        offer_id: u64,
+    ) -> Result<i128, PayrollError> {
+        offers::claim_offer_bonus(&env, &candidate, offer_id)
+    }
+
+    /// Returns a lapsed offer's signing bonus, or that of an offer whose
+    /// agreement was cancelled, to the employer. Returns the amount refunded.
+    ///
+    /// # Errors
+    /// * `InvalidData` - no refund is due
+    pub fn refund_offer(env: Env, offer_id: u64) -> Result<i128, PayrollError> {
+        offers::refund_offer(&env, offer_id)
+    }
+
+    /// Starts streaming `rate_per_second` of the agreement's token to
+    /// `employee`, moving `deposit` from the employer into the agreement's
+    /// escrow to pay for it.
+    ///
+    /// # Errors
+    /// * `AgreementNotFound` - the agreement does not exist
+    /// * `Unauthorized` - caller is not the agreement's employer, or the
+    ///   employer is winding down
+    /// * `InvalidAgreementMode` - the agreement is not a payroll
+    /// * `InvalidData` - the agreement is settled, the employee already has a
+    ///   stream on it, or the rate or deposit is invalid
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn create_salary_stream(
+        env: Env,
+        employer: Address,
+        agreement_id: u128,
+        employee: Address,
+        rate_per_second: i128,
+        deposit: i128,
+    ) -> Result<(), PayrollError> {
+        streaming::create_salary_stream(
+            &env,
+            &employer,
+            agreement_id,
+            &employee,
+            rate_per_second,
+            deposit,
+        )
+    }
+
+    /// Adds `amount` to the escrow that pays `employee`'s stream on the
+    /// agreement.
+    ///
+    /// # Errors
+    /// * `InvalidData` - the employee has no stream on the agreement, or
+    ///   `amount` is invalid
+    /// * `Unauthorized` - caller is not the stream's employer
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn fund_salary_stream(
+        env: Env,
+        employer: Address,
+        agreement_id: u128,
+        employee: Address,
+        amount: i128,
+    ) -> Result<(), PayrollError> {
+        streaming::fund_salary_stream(&env, &employer, agreement_id, &employee, amount)
+    }
+
+    /// Pays `employee` the salary accrued on their stream on the agreement
+    /// since the last withdrawal, as far as the escrow covers it, after
+    /// garnishments, loan repayments and savings. Returns the gross amount.
+    ///
+    /// # Errors
+    /// * `EmergencyPaused` - payroll claims are paused
+    /// * `InvalidData` - the employee has no stream on the agreement
+    /// * `NoPeriodsToClaim` - nothing has accrued
+    /// * `InsufficientEscrowBalance` - the escrow does not cover a second
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn withdraw_streamed(
+        env: Env,
+        employee: Address,
+        agreement_id: u128,
+    ) -> Result<i128, PayrollError> {
+        streaming::withdraw_streamed(&env, &employee, agreement_id)
+    }
+
+    /// Stops `employee`'s stream on the agreement, paying them everything
+    /// accrued. Returns the gross amount paid.
+    ///
+    /// # Errors
+    /// * `InvalidData` - the employee has no stream on the agreement
+    /// * `Unauthorized` - caller is not the stream's employer
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn stop_salary_stream(
+        env: Env,
+        employer: Address,
+        agreement_id: u128,
+        employee: Address,
+    ) -> Result<i128, PayrollError> {
+        streaming::stop_salary_stream(&env, &employer, agreement_id, &employee)
+    }
+
+    /// Returns `employee`'s salary stream on the agreement, if any.
+    pub fn get_salary_stream(
+        env: Env,
+        agreement_id: u128,
+        employee: Address,
+    ) -> Option<SalaryStream> {
+        streaming::get_salary_stream(&env, agreement_id, &employee)
+    }
+
+    /// Returns what `withdraw_streamed` would pay `employee` on the
+    /// agreement now.
+    pub fn get_streamed_balance(env: Env, agreement_id: u128, employee: Address) -> i128 {
+        streaming::get_streamed_balance(&env, agreement_id, &employee)
+    }
+
+    /// Sets the severance insurance token, base premium and coverage share.
+    ///
+    /// # Errors
+    /// * `InvalidData` - a basis-point value is zero or above 10 000, or the
+    ///   token changes while the pool is in use
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_severance_insurance(
+        env: Env,
+        owner: Address,
+        token: Address,
+        base_premium_bps: u32,
+        coverage_bps: u32,
+    ) -> Result<(), PayrollError> {
+        severance::set_severance_insurance(&env, &owner, &token, base_premium_bps, coverage_bps)
+    }
+
+    /// Returns the severance insurance terms, if set.
+    pub fn get_severance_insurance(env: Env) -> Option<SeveranceInsuranceConfig> {
+        severance::get_severance_insurance(&env)
+    }
+
+    /// Returns the severance insurance pool's balance and open cover.
+    pub fn get_severance_pool(env: Env) -> SeverancePool {
+        severance::get_severance_pool(&env)
+    }
+
+    /// Adds `amount` of the insurance token to the severance insurance pool.
+    ///
+    /// # Access Control
+    /// Requires `funder` authentication
+    pub fn fund_severance_pool(
+        env: Env,
+        funder: Address,
+        amount: i128,
+    ) -> Result<(), PayrollError> {
+        severance::fund_severance_pool(&env, &funder, amount)
+    }
+
+    /// Returns the premium `employer` would pay now to cover
+    /// `severance_amount`, priced from its reputation and pool utilization.
+    ///
+    /// # Errors
+    /// * `InsufficientEscrowBalance` - the pool cannot back the cover
+    pub fn quote_severance_premium(
+        env: Env,
+        employer: Address,
+        severance_amount: i128,
+    ) -> Result<i128, PayrollError> {
+        severance::quote_severance_premium(&env, &employer, severance_amount)
+    }
+
+    /// Buys severance cover for an employee of the agreement. Returns the
+    /// premium paid into the pool.
+    ///
+    /// # Errors
+    /// * `NoEmployee` - `employee` is not on the agreement
+    /// * `InsufficientEscrowBalance` - the pool cannot back the cover
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn buy_severance_cover(
+        env: Env,
+        employer: Address,
+        agreement_id: u128,
+        employee: Address,
+        severance_amount: i128,
+    ) -> Result<i128, PayrollError> {
+        severance::buy_severance_cover(&env, &employer, agreement_id, &employee, severance_amount)
+    }
+
+    /// Returns an employee's severance cover under the agreement, if any.
+    pub fn get_severance_cover(
+        env: Env,
+        agreement_id: u128,
+        employee: Address,
+    ) -> Option<SeveranceCover> {
+        severance::get_severance_cover(&env, agreement_id, &employee)
+    }
+
+    /// Pays a terminated employee's covered severance once the agreement is
+    /// cancelled: from the employer first, then from the insurance pool,
+    /// which records what it paid as an employer liability.
+    ///
+    /// # Errors
+    /// * `InvalidAgreementMode` - the agreement is not cancelled
+    ///
+    /// # Access Control
+    /// Requires employee authentication
+    pub fn settle_severance(
+        env: Env,
+        agreement_id: u128,
+        employee: Address,
+    ) -> Result<SeveranceSettlement, PayrollError> {
+        severance::settle_severance(&env, agreement_id, &employee)
+    }
+
+    /// Releases an employee's severance cover after the agreement completed.
+    ///
+    /// # Errors
+    /// * `InvalidAgreementMode` - the agreement is not completed
+    pub fn release_severance_cover(
+        env: Env,
+        agreement_id: u128,
+        employee: Address,
+    ) -> Result<(), PayrollError> {
+        severance::release_severance_cover(&env, agreement_id, &employee)
+    }
+
+    /// Returns the severance insurance payouts `employer` has yet to repay.
+    pub fn get_severance_liability(env: Env, employer: Address) -> i128 {
+        severance::get_severance_liability(&env, &employer)
+    }
+
+    /// Repays up to `amount` of the employer's severance liability into the
+    /// pool. Returns the liability left.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn repay_severance_liability(
+        env: Env,
+        employer: Address,
+        amount: i128,
+    ) -> Result<i128, PayrollError> {
+        severance::repay_severance_liability(&env, &employer, amount)
+    }
+
+    /// Transfers `amount` of `token` from `from` into the protocol treasury.
+    ///
+    /// # Access Control
+    /// Requires `from` authentication
+    pub fn deposit_to_treasury(
+        env: Env,
+        from: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<(), PayrollError> {
+        treasury::deposit_to_treasury(&env, &from, &token, amount)
+    }
+
+    /// Returns the treasury balance of every token it has held.
+    pub fn get_treasury_balances(env: Env) -> Vec<TreasuryBalance> {
+        treasury::get_treasury_balances(&env)
+    }
+
+    /// Links the governance contract whose executed `TreasurySpend`
+    /// proposals approve treasury spends.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_treasury_governance(
+        env: Env,
+        owner: Address,
+        governance: Address,
+    ) -> Result<(), PayrollError> {
+        treasury::set_treasury_governance(&env, &owner, &governance)
+    }
+
+    /// Returns the linked treasury governance contract, if any.
+    pub fn get_treasury_governance(env: Env) -> Option<Address> {
+        treasury::get_treasury_governance(&env)
+    }
+
+    /// Queues the treasury spend approved by governance proposal
+    /// `proposal_id`, executable after the treasury timelock.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn propose_spend(env: Env, owner: Address, proposal_id: u128) -> Result<(), PayrollError> {
+        treasury::propose_spend(&env, &owner, proposal_id)
+    }
+
+    /// Executes a queued treasury spend whose timelock has passed.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn execute_spend(env: Env, owner: Address, proposal_id: u128) -> Result<(), PayrollError> {
+        treasury::execute_spend(&env, &owner, proposal_id)
+    }
+
+    /// Cancels a queued treasury spend.
+    ///
+    /// # Access Control
+    /// Requires owner or emergency guardian authentication
+    pub fn cancel_spend(env: Env, caller: Address, proposal_id: u128) -> Result<(), PayrollError> {
+        treasury::cancel_spend(&env, &caller, proposal_id)
+    }
+
+    /// Returns a queued treasury spend, if any.
+    pub fn get_spend_proposal(env: Env, proposal_id: u128) -> Option<SpendProposal> {
+        treasury::get_spend_proposal(&env, proposal_id)
+    }
+
+    /// Links the bonus_system contract used by `disburse_total_compensation`.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_bonus_contract(
+        env: Env,
+        owner: Address,
+        bonus_contract: Address,
+    ) -> Result<(), PayrollError> {
+        compensation::set_bonus_contract(&env, &owner, &bonus_contract)
+    }
+
+    /// Returns the linked bonus_system contract, if any.
+    pub fn get_bonus_contract(env: Env) -> Option<Address> {
+        compensation::get_bonus_contract(&env)
+    }
+
+    /// Pays `employee` all salary currently due from `employer`'s payroll
+    /// agreements and claims their vested bonuses from `employer` in the
+    /// linked bonus contract, emitting one combined event.
+    ///
+    /// # Access Control
+    /// Requires employee authentication, covering the nested bonus claim
+    pub fn disburse_total_compensation(
+        env: Env,
+        employer: Address,
+        employee: Address,
+    ) -> Result<CompensationDisbursement, PayrollError> {
+        compensation::disburse_total_compensation(&env, &employer, &employee)
+    }
+
+    /// Returns the number of payslips recorded for `employee`.
+    pub fn get_payslip_count(env: Env, employee: Address) -> u64 {
+        payslip::get_payslip_count(&env, &employee)
+    }
+
+    /// Returns payslip `id` (1-based) of `employee`.
+    pub fn get_payslip(env: Env, employee: Address, id: u64) -> Option<Payslip> {
+        payslip::get_payslip(&env, &employee, id)
+    }
+
+    /// Resolves a disbursement receipt hash, as written on a salary payslip,
+    /// to the audit record of its payout. Returns `None` for hashes this
+    /// contract never issued. See [`receipts`].
+    pub fn verify_receipt(env: Env, receipt_hash: BytesN<32>) -> Option<LifecycleAuditEntry> {
+        receipts::verify_receipt(&env, &receipt_hash)
+    }
+
+    /// Returns the last sequence number assigned in `category`, or 0 if none.
+    /// Records and events of a category carry consecutive numbers, so
+    /// indexers can detect gaps and check they are caught up.
+    pub fn get_latest_sequence(env: Env, category: SequenceCategory) -> u64 {
+        sequence::get_latest_sequence(&env, category)
+    }
+
+    /// Sets the employer's cost-of-living adjustment policy: a yearly raise of
+    /// `bps_per_year`, applied pro rata every `apply_frequency` seconds.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn set_cola_policy(
+        env: Env,
+        employer: Address,
+        bps_per_year: u32,
+        apply_frequency: u64,
+    ) -> Result<(), PayrollError> {
+        cola::set_cola_policy(&env, &employer, bps_per_year, apply_frequency)
+    }
+
+    /// Returns the employer's COLA policy, if any.
+    pub fn get_cola_policy(env: Env, employer: Address) -> Option<ColaPolicy> {
+        cola::get_cola_policy(&env, &employer)
+    }
+
+    /// Applies the employer's due COLA raise to at most `limit` employees,
+    /// resuming a run left unfinished by an earlier call. Each salary change
+    /// is recorded as a `PayrollAdjustment`.
+    ///
+    /// # Access Control
+    /// Permissionless (keeper-triggered)
+    pub fn apply_cola_policy(
+        env: Env,
+        employer: Address,
+        limit: u32,
+    ) -> Result<ColaRunResult, PayrollError> {
+        cola::apply_cola_policy(&env, &employer, limit)
+    }
+
+    /// Returns the number of recorded payroll adjustments.
+    pub fn get_payroll_adjustment_count(env: Env) -> u64 {
+        cola::get_payroll_adjustment_count(&env)
+    }
+
+    /// Returns a payroll adjustment by its 1-based id.
+    pub fn get_payroll_adjustment(env: Env, adjustment_id: u64) -> Option<PayrollAdjustment> {
+        cola::get_payroll_adjustment(&env, adjustment_id)
+    }
+
+    /// Creates an empty currency group for payroll agreements paid in `token`
+    /// and returns its id.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn create_currency_group(env: Env, employer: Address, token: Address) -> u64 {
+        currency_groups::create_currency_group(&env, &employer, &token)
+    }
+
+    /// Adds one of the employer's payroll agreements, paid in the group's
+    /// token, to a currency group.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn add_to_currency_group(
+        env: Env,
+        employer: Address,
+        group_id: u64,
+        agreement_id: u128,
+    ) -> Result<(), PayrollError> {
+        currency_groups::add_to_currency_group(&env, &employer, group_id, agreement_id)
+    }
+
+    /// Removes an agreement from a currency group.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn remove_from_currency_group(
+        env: Env,
+        employer: Address,
+        group_id: u64,
+        agreement_id: u128,
+    ) -> Result<(), PayrollError> {
+        currency_groups::remove_from_currency_group(&env, &employer, group_id, agreement_id)
+    }
+
+    /// Returns a currency group by id.
+    pub fn get_currency_group(env: Env, group_id: u64) -> Option<CurrencyGroup> {
+        currency_groups::get_currency_group(&env, group_id)
+    }
+
+    /// Returns the currency group an agreement belongs to, if any.
+    pub fn get_agreement_currency_group(env: Env, agreement_id: u128) -> Option<u64> {
+        currency_groups::get_agreement_currency_group(&env, agreement_id)
+    }
+
+    /// Switches every agreement of a currency group to `new_token`,
+    /// converting salaries and totals at `conversion_rate` (new token units
+    /// per old unit, scaled by 1e6). Old-token escrow is returned to the
+    /// employer. Returns the number of agreements migrated.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn migrate_group_token(
+        env: Env,
+        employer: Address,
+        group_id: u64,
+        new_token: Address,
+        conversion_rate: i128,
+    ) -> Result<u32, PayrollError> {
+        currency_groups::migrate_group_token(&env, &employer, group_id, &new_token, conversion_rate)
+    }
+
+    /// Switches the payroll of `employees` from `old_token` to `new_token`,
+    /// converting salaries, totals and escrow at `conversion_rate_bps`. The
+    /// employer receives the old-token escrow back and deposits the converted
+    /// amount in the new token. Returns the number of agreements migrated.
+    ///
+    /// # Errors
+    /// * `InvalidData` - bad rate or tokens, no matching agreement, or an
+    ///   agreement with employees that are not listed
+    /// * `NoEmployee` - a listed employee has no agreement in `old_token`
+    /// * `Unauthorized` - the rate deviates past the threshold without
+    ///   sign-off from the owner or every affected employee
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn migrate_payroll_token(
+        env: Env,
+        employer: Address,
+        employees: Vec<Address>,
+        old_token: Address,
+        new_token: Address,
+        conversion_rate_bps: u32,
+    ) -> Result<u32, PayrollError> {
+        currency_groups::migrate_payroll_token(
+            &env,
+            &employer,
+            employees,
+            &old_token,
+            &new_token,
+            conversion_rate_bps,
+        )
+    }
+
+    /// Signs off a payroll token migration by `employer` on `terms`. Used up
+    /// by the migration.
+    ///
+    /// # Access Control
+    /// Requires signer authentication
+    pub fn approve_token_migration(
+        env: Env,
+        signer: Address,
+        employer: Address,
+        terms: TokenMigrationTerms,
+    ) {
+        currency_groups::approve_token_migration(&env, &signer, &employer, terms)
+    }
+
+    /// Returns the signer's pending sign-off of a migration by `employer`.
+    pub fn get_token_migration_approval(
+        env: Env,
+        employer: Address,
+        signer: Address,
+    ) -> Option<TokenMigrationTerms> {
+        currency_groups::get_token_migration_approval(&env, &employer, &signer)
+    }
+
+    /// Sets the rate deviation from 1:1, in basis points, above which payroll
+    /// token migrations need sign-off.
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_migration_threshold(
+        env: Env,
+        owner: Address,
+        max_deviation_bps: u32,
+    ) -> Result<(), PayrollError> {
+        currency_groups::set_migration_threshold(&env, &owner, max_deviation_bps)
+    }
+
+    /// Returns the deviation threshold of payroll token migrations.
+    pub fn get_migration_threshold(env: Env) -> u32 {
+        currency_groups::get_migration_threshold(&env)
+    }
+
+    /// Returns the number of recorded payroll token migrations.
+    pub fn get_token_migration_count(env: Env) -> u64 {
+        currency_groups::get_token_migration_count(&env)
+    }
+
+    /// Returns a recorded payroll token migration by its 1-based id.
+    pub fn get_token_migration(env: Env, migration_id: u64) -> Option<PayrollTokenMigration> {
+        currency_groups::get_token_migration(&env, migration_id)
+    }
+
+    /// Returns the employer's obligations per token for the next 1, 3 and 6
+    /// periods, next to the escrow held in each token.
+    pub fn get_fx_exposure(env: Env, employer: Address) -> Vec<FxExposure> {
+        currency_groups::get_fx_exposure(&env, &employer)
+    }
+
+    /// Makes `child` a subsidiary employer of `parent`. See [`subsidiaries`].
+    ///
+    /// # Errors
+    /// * `PayrollError::InvalidData` - `child` already has a parent, the link
+    ///   would make a cycle, or the group would exceed
+    ///   `subsidiaries::MAX_GROUP_SIZE` employers
+    ///
+    /// # Access Control
+    /// Requires authentication of both `parent` and `child`
+    pub fn link_subsidiary(env: Env, parent: Address, child: Address) -> Result<(), PayrollError> {
+        subsidiaries::link_subsidiary(&env, &parent, &child)
+    }
+
+    /// Removes `child` from its parent's group.
+    ///
+    /// # Errors
+    /// * `PayrollError::InvalidData` - `child` has no parent
+    /// * `PayrollError::Unauthorized` - `caller` is neither `child` nor its
+    ///   parent
+    ///
+    /// # Access Control
+    /// Requires `caller` authentication
+    pub fn unlink_subsidiary(
+        env: Env,
+        caller: Address,
+        child: Address,
+    ) -> Result<(), PayrollError> {
+        subsidiaries::unlink_subsidiary(&env, &caller, &child)
+    }
+
+    /// Returns the employer's parent, if it is a subsidiary.
+    pub fn get_parent_employer(env: Env, employer: Address) -> Option<Address> {
+        subsidiaries::get_parent(&env, &employer)
+    }
+
+    /// Returns the employer's direct subsidiaries.
+    pub fn get_subsidiaries(env: Env, parent: Address) -> Vec<Address> {
+        subsidiaries::get_subsidiaries(&env, &parent)
+    }
+
+    /// Grants or revokes `operator` the right to pause, resume and cancel the
+    /// agreements of every employer below `parent`.
+    ///
+    /// # Access Control
+    /// Requires `parent` authentication
+    pub fn set_group_operator(env: Env, parent: Address, operator: Address, enabled: bool) {
+        subsidiaries::set_group_operator(&env, &parent, &operator, enabled)
+    }
+
+    /// Returns whether `operator` is a group operator of `parent`.
+    pub fn is_group_operator(env: Env, parent: Address, operator: Address) -> bool {
+        subsidiaries::is_group_operator(&env, &parent, &operator)
+    }
+
+    /// Pauses an agreement of a subsidiary employer below `caller`.
+    ///
+    /// # Errors
+    /// * `PayrollError::AgreementNotFound` - the agreement does not exist
+    /// * `PayrollError::Unauthorized` - `caller` is not a parent, or a group
+    ///   operator of a parent, of the agreement's employer
+    ///
+    /// # Access Control
+    /// Requires `caller` authentication
+    pub fn pause_subsidiary_agreement(
+        env: Env,
+        caller: Address,
+        agreement_id: u128,
+    ) -> Result<(), PayrollError> {
+        subsidiaries::operate_subsidiary_agreement(&env, &caller, agreement_id, GroupAction::Pause)
+    }
+
+    /// Resumes an agreement of a subsidiary employer below `caller`.
+    ///
+    /// # Errors
+    /// Same as [`Self::pause_subsidiary_agreement`].
+    ///
+    /// # Access Control
+    /// Requires `caller` authentication
+    pub fn resume_subsidiary_agreement(
+        env: Env,
+        caller: Address,
+        agreement_id: u128,
+    ) -> Result<(), PayrollError> {
+        subsidiaries::operate_subsidiary_agreement(&env, &caller, agreement_id, GroupAction::Resume)
+    }
+
+    /// Cancels an agreement of a subsidiary employer below `caller`.
+    ///
+    /// # Errors
+    /// Same as [`Self::pause_subsidiary_agreement`].
+    ///
+    /// # Access Control
+    /// Requires `caller` authentication
+    pub fn cancel_subsidiary_agreement(
+        env: Env,
+        caller: Address,
+        agreement_id: u128,
+    ) -> Result<(), PayrollError> {
+        subsidiaries::operate_subsidiary_agreement(&env, &caller, agreement_id, GroupAction::Cancel)
+    }
+
+    /// Returns the consolidated liability of `parent` and every employer
+    /// below it: escrow held and obligations for the next 1, 3 and 6
+    /// periods, per token.
+    pub fn get_group_liability(env: Env, parent: Address) -> Vec<FxExposure> {
+        subsidiaries::get_group_liability(&env, &parent)
+    }
+
+    /// Returns consolidated metrics of `parent` and every employer below it.
+    pub fn get_group_metrics(env: Env, parent: Address) -> GroupMetrics {
+        subsidiaries::get_group_metrics(&env, &parent)
+    }
+
+    /// Sets how the employer's shares are rounded: garnishment, loan and
+    /// savings shares of its payouts, its severance premiums and its COLA
+    /// raises. The net payout always takes the rounding dust.
+    ///
+    /// # Access Control
+    /// Requires `employer` authentication
+    pub fn set_rounding_policy(env: Env, employer: Address, mode: RoundingMode) {
+        rounding::set_rounding_policy(&env, &employer, mode)
+    }
+
+    /// Returns the employer's rounding policy, `Floor` by default.
+    pub fn get_rounding_policy(env: Env, employer: Address) -> RoundingMode {
+        rounding::get_rounding_policy(&env, &employer)
+    }
+
+    /// Sets or clears the daily UTC window in which the employer's agreements
+    /// pay out. Claims outside it fail with `TimelockActive`.
+    ///
+    /// # Errors
+    /// * `InvalidData` - `open` is not before `close`, or `close` is past the
+    ///   end of the day
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn set_business_hours(
+        env: Env,
+        employer: Address,
+        hours: Option<BusinessHours>,
+    ) -> Result<(), PayrollError> {
+        disbursement_windows::set_business_hours(&env, &employer, hours)
+    }
+
+    /// Schedules a blackout `[start, end)` during which the employer's
+    /// agreements do not pay out, e.g. a month-end close.
+    ///
+    /// # Errors
+    /// * `InvalidData` - `start` is not before `end`, or `end` has passed
+    /// * `BatchTooLarge` - `MAX_BLACKOUTS` blackouts are already scheduled
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn add_blackout(
+        env: Env,
+        employer: Address,
+        start: u64,
+        end: u64,
+    ) -> Result<(), PayrollError> {
+        disbursement_windows::add_blackout(&env, &employer, start, end)
+    }
+
+    /// Removes the employer's blackout starting at `start`.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn remove_blackout(env: Env, employer: Address, start: u64) -> Result<(), PayrollError> {
+        disbursement_windows::remove_blackout(&env, &employer, start)
+    }
+
+    /// Returns the employer's business hours, if set.
+    pub fn get_business_hours(env: Env, employer: Address) -> Option<BusinessHours> {
+        disbursement_windows::get_business_hours(&env, &employer)
+    }
+
+    /// Returns the employer's blackouts that have not ended yet.
+    pub fn get_blackouts(env: Env, employer: Address) -> Vec<BlackoutWindow> {
+        disbursement_windows::get_blackouts(&env, &employer)
+    }
+
+    /// Returns the earliest moment, now or later, at which the employer's
+    /// agreements can pay out (`get_next_allowed_disbursement_time`, shortened
+    /// to fit Soroban's 32-character function name limit).
+    pub fn get_next_disbursement_time(env: Env, employer: Address) -> u64 {
+        disbursement_windows::get_next_allowed_disbursement_time(&env, &employer)
+    }
+
+    /// Returns the payouts the employer's active agreements will owe for
+    /// periods ending in `[from_ts, to_ts)`, ordered by when they can be paid
+    /// under the employer's business hours and blackouts.
+    ///
+    /// # Errors
+    /// * `InvalidData` - `from_ts` is not before `to_ts`
+    /// * `BatchTooLarge` - more payouts than `MAX_CALENDAR_ENTRIES` fall in
+    ///   the window
+    pub fn get_payout_calendar(
+        env: Env,
+        employer: Address,
+        from_ts: u64,
+        to_ts: u64,
+    ) -> Result<Vec<PayoutCalendarEntry>, PayrollError> {
+        payout_calendar::get_payout_calendar(&env, &employer, from_ts, to_ts)
+    }
+
+    /// Returns the salary commitment for a confidential-mode employee, if any.
+    pub fn get_salary_commitment(
+        env: Env,
+        agreement_id: u128,
+        employee_index: u32,
+    ) -> Option<BytesN<32>> {
+        confidential::get_salary_commitment(&env, agreement_id, employee_index)
+    }
+
+    /// Disburses elapsed periods to a confidential-mode employee.
+    ///
+    /// The employer reveals the per-period `amount` and `salt`, which must match the
+    /// stored commitment.
+    ///
+    /// # Returns
+    /// Total amount transferred.
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn disburse_confidential_payroll(
+        env: Env,
+        agreement_id: u128,
+        employee_index: u32,
+        amount: i128,
+        salt: BytesN<32>,
+    ) -> Result<i128, PayrollError> {
+        confidential::disburse_confidential_payroll(
+            &env,
+            agreement_id,
+            employee_index,
+            amount,
+            salt,
+        )
+    }
+
+    /// Get claimed periods for an employee
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `agreement_id` - ID of the agreement
+    /// * `employee_index` - Index of the employee in the agreement
+    ///
+    /// # Returns
+    /// Number of periods claimed
+    ///
+    /// # Access Control
+    /// Requires caller authentication
+    pub fn get_employee_claimed_periods(env: Env, agreement_id: u128, employee_index: u32) -> u32 {
+        payroll::get_employee_claimed_periods(&env, agreement_id, employee_index)
+    }
+
+    /// Pauses an active agreement, preventing claims.
+    ///
+    /// # Arguments
+    /// * `agreement_id` - ID of the agreement to pause
+    ///
+    /// # State Transition
+    /// Active -> Paused
+    ///
+    /// # Requirements
+    /// - Agreement must be in Active status
+    /// - Caller must be the employer
+    ///
+    /// # Behavior
+    /// - Paused agreements cannot have claims processed
+    /// - Agreement state is preserved
+    /// - Can be resumed later or cancelled
+    pub fn pause_agreement(env: Env, agreement_id: u128) -> Result<(), PayrollError> {
+        // Try new-style agreement first (payroll/escrow)
+        if payroll::get_agreement(&env, agreement_id).is_some() {
+            payroll::pause_agreement(&env, agreement_id);
+            return Ok(());
+        }
+
+        // Fall back to milestone-based agreement
+        payroll::pause_milestone_agreement(env, agreement_id)
+    }
+
+    /// Resumes a paused agreement, allowing claims again.
+    ///
+    /// # Arguments
+    /// * `agreement_id` - ID of the agreement to resume
+    ///
+    /// # State Transition
+    /// Paused -> Active
+    ///
+    /// # Requirements
+    /// - Agreement must be in Paused status
+    /// - Caller must be the employer
+    ///
+    /// # Behavior
+    /// - Agreement returns to Active status
+    /// - Claims can be processed again
+    /// - All agreement data is preserved
+    pub fn resume_agreement(env: Env, agreement_id: u128) -> Result<(), PayrollError> {
+        // Try new-style agreement first (payroll/escrow)
+        if payroll::get_agreement(&env, agreement_id).is_some() {
+            payroll::resume_agreement(&env, agreement_id);
+            return Ok(());
+        }
+
+        // Fall back to milestone-based agreement
+        payroll::resume_milestone_agreement(env, agreement_id)
+    }
+
+    /// Claims time-based payments for an escrow agreement based on elapsed periods.
+    ///
+    /// # Arguments
+    /// * `agreement_id` - ID of the escrow agreement
+    ///
+    /// # Returns
+    /// * `Ok(())` on success
+    /// * `Err(PayrollError)` on failure
+    ///
+    /// # Requirements
+    /// - Agreement must be Active and activated
+    /// - Agreement must be Escrow mode
+    /// - Caller must be the contributor
+    /// - Cannot claim more than total periods
+    /// - Works during grace period
+    pub fn claim_time_based(env: Env, agreement_id: u128) -> Result<(), storage::PayrollError> {
+        payroll::claim_time_based(&env, agreement_id)
+    }
+
+    /// Gets the number of claimed periods for a time-based escrow agreement.
+    ///
+    /// # Arguments
+    /// * `agreement_id` - ID of the agreement
+    ///
+    /// # Returns
+    /// Number of claimed periods, or 0 if not a time-based agreement
+    ///
+    /// # Access Control
+    /// Requires caller authentication
+    pub fn get_claimed_periods(env: Env, agreement_id: u128) -> u32 {
         payroll::get_claimed_periods(&env, agreement_id)
     }
 
@@ -1156,6 +3443,43 @@ impl PayrollContract {
         payroll::get_grace_period_end(&env, agreement_id)
     }
 
+    /// Begins winding down `employer`: freezes agreement creation, cancels
+    /// its created, active and paused agreements, and pulls from the employer
+    /// whatever their escrows lack to cover the final payouts due by the end
+    /// of each grace period.
+    ///
+    /// # Errors
+    /// * `InvalidData` - a wind-down is already in progress
+    ///
+    /// # Access Control
+    /// Requires employer authentication
+    pub fn begin_employer_winddown(
+        env: Env,
+        employer: Address,
+    ) -> Result<EmployerWinddown, PayrollError> {
+        winddown::begin_employer_winddown(&env, &employer)
+    }
+
+    /// Completes `employer`'s wind-down once its final payouts are done:
+    /// returns residual escrow, removes its agreements from the employer and
+    /// employee indexes, and lifts the creation freeze. Returns the number of
+    /// agreements closed.
+    ///
+    /// # Errors
+    /// * `InvalidData` - no wind-down is in progress, or an agreement is
+    ///   disputed or still owes final pay within its grace period
+    ///
+    /// # Access Control
+    /// Anyone may call this
+    pub fn complete_employer_winddown(env: Env, employer: Address) -> Result<u32, PayrollError> {
+        winddown::complete_employer_winddown(&env, &employer)
+    }
+
+    /// Returns `employer`'s wind-down, if it ever began one.
+    pub fn get_employer_winddown(env: Env, employer: Address) -> Option<EmployerWinddown> {
+        winddown::get_employer_winddown(&env, &employer)
+    }
+
     /// Extends the effective grace / dispute window for a **cancelled** agreement.
     ///
     /// # Authorization
@@ -1275,6 +3599,27 @@ impl PayrollContract {
         payroll::emergency_pause(&env)
     }
 
+    /// Immediately activates a time-boxed and/or annotated emergency pause (owner only)
+    ///
+    /// # Arguments
+    /// * `duration_seconds` - Optional duration after which the pause lapses on the next
+    ///   interaction; `None` keeps the contract paused until `emergency_unpause`
+    /// * `reason` - Optional reason surfaced to integrators via `get_pause_info`
+    ///
+    /// # Errors
+    /// * `PayrollError::InvalidData` - `duration_seconds` is zero or overflows the
+    ///   timestamp, or `reason` is longer than `MAX_STRING_LEN`
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn emergency_pause_with_terms(
+        env: Env,
+        duration_seconds: Option<u64>,
+        reason: Option<soroban_sdk::String>,
+    ) -> Result<(), storage::PayrollError> {
+        payroll::emergency_pause_with_terms(&env, duration_seconds, reason)
+    }
+
     /// Unpauses contract after emergency resolved
     ///
     /// # Access Control
@@ -1311,6 +3656,204 @@ impl PayrollContract {
         payroll::get_emergency_pause_state(&env)
     }
 
+    /// Gets why and until when the contract is paused
+    ///
+    /// # Returns
+    /// PauseInfo with `is_paused` resolved against `resume_at`; all fields are
+    /// empty when the contract is not (or no longer) paused
+    pub fn get_pause_info(env: Env) -> storage::PauseInfo {
+        payroll::get_pause_info(&env)
+    }
+
+    /// Sets the categories of operations that keep running during an
+    /// emergency pause, such as employees claiming pay they have already
+    /// earned. Replaces the previous list; an empty list exempts nothing.
+    ///
+    /// # Errors
+    /// * `PayrollError::Unauthorized` - `owner` is not the contract owner
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_pause_exemptions(
+        env: Env,
+        owner: Address,
+        categories: Vec<PauseCategory>,
+    ) -> Result<(), PayrollError> {
+        pause_exemptions::set_pause_exemptions(&env, &owner, categories)
+    }
+
+    /// Returns the categories of operations exempt from emergency pauses.
+    pub fn get_pause_exemptions(env: Env) -> Vec<PauseCategory> {
+        pause_exemptions::get_pause_exemptions(&env)
+    }
+
+    /// Whether operations of `category` are currently stopped by an
+    /// emergency pause.
+    pub fn is_paused_for(env: Env, category: PauseCategory) -> bool {
+        pause_exemptions::is_paused_for(&env, category)
+    }
+
+    /// Sets which events are published: `Full` (default), `Minimal`, which
+    /// drops detail events, or `CriticalOnly`. Critical events, such as
+    /// payouts, disputes, ownership changes and audit records, are always
+    /// published.
+    ///
+    /// # Errors
+    /// * `PayrollError::Unauthorized` - `owner` is not the contract owner
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_event_verbosity(
+        env: Env,
+        owner: Address,
+        verbosity: EventVerbosity,
+    ) -> Result<(), PayrollError> {
+        events::set_event_verbosity(&env, &owner, verbosity)
+    }
+
+    /// Returns the event verbosity.
+    pub fn get_event_verbosity(env: Env) -> EventVerbosity {
+        events::get_event_verbosity(&env)
+    }
+
+    // ============================================================================
+    // Ownership
+    // ============================================================================
+
+    /// Links the governance contract whose approved upgrades can be ratified.
+    ///
+    /// # Errors
+    /// * `PayrollError::Unauthorized` - caller is not the owner
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_upgrade_governance(
+        env: Env,
+        owner: Address,
+        governance: Address,
+    ) -> Result<(), PayrollError> {
+        ownership::set_upgrade_governance(&env, &owner, &governance)
+    }
+
+    /// Removes the owner for good. Afterwards `guardian` can only pause for
+    /// a limited time, lift pauses and upgrade to ratified WASM hashes.
+    ///
+    /// # Errors
+    /// * `PayrollError::Unauthorized` - caller is not the owner
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn renounce_ownership(
+        env: Env,
+        caller: Address,
+        guardian: Address,
+    ) -> Result<(), PayrollError> {
+        ownership::renounce_ownership(&env, &caller, &guardian)
+    }
+
+    /// Returns the operating mode and who holds the owner and guardian roles.
+    pub fn get_ownership_info(env: Env) -> OwnershipInfo {
+        ownership::get_ownership_info(&env)
+    }
+
+    /// Adds the WASM hash the upgrade governance contract approves for this
+    /// contract to the ratified upgrade allowlist.
+    ///
+    /// # Errors
+    /// * `PayrollError::InvalidData` - no upgrade governance is linked
+    /// * `PayrollError::Unauthorized` - governance does not approve `wasm_hash`
+    /// * `PayrollError::BatchTooLarge` - the allowlist is full
+    pub fn ratify_upgrade(env: Env, wasm_hash: BytesN<32>) -> Result<(), PayrollError> {
+        ownership::ratify_upgrade(&env, &wasm_hash)
+    }
+
+    /// Returns the ratified WASM hashes that have not been used yet.
+    pub fn get_ratified_upgrades(env: Env) -> Vec<BytesN<32>> {
+        ownership::get_ratified_upgrades(&env)
+    }
+
+    /// Pauses the contract for `duration_seconds`, at most 30 days.
+    ///
+    /// # Errors
+    /// * `PayrollError::NotGuardian` - ownership is not renounced, or caller
+    ///   is not the guardian
+    /// * `PayrollError::InvalidData` - `duration_seconds` is zero or too long
+    ///
+    /// # Access Control
+    /// Requires guardian authentication
+    pub fn guardian_pause(
+        env: Env,
+        guardian: Address,
+        duration_seconds: u64,
+        reason: Option<soroban_sdk::String>,
+    ) -> Result<(), PayrollError> {
+        ownership::guardian_pause(&env, &guardian, duration_seconds, reason)
+    }
+
+    /// Lifts the emergency pause, whoever activated it.
+    ///
+    /// # Errors
+    /// * `PayrollError::NotGuardian` - ownership is not renounced, or caller
+    ///   is not the guardian
+    ///
+    /// # Access Control
+    /// Requires guardian authentication
+    pub fn guardian_unpause(env: Env, guardian: Address) -> Result<(), PayrollError> {
+        ownership::guardian_unpause(&env, &guardian)
+    }
+
+    // ============================================================================
+    // Resource Estimates
+    // ============================================================================
+
+    /// Records a measured resource profile for an operation at a contract version
+    ///
+    /// # Arguments
+    /// * `contract_version` - Storage schema version the profile was measured on
+    /// * `operation` - Unit of work the profile describes
+    /// * `profile` - Measured CPU, memory and ledger entry footprint
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn set_resource_profile(
+        env: Env,
+        contract_version: u32,
+        operation: PayrollOperation,
+        profile: ResourceProfile,
+    ) {
+        resource_estimate::set_resource_profile(&env, contract_version, operation, profile);
+    }
+
+    /// Gets the resource profile for an operation at a contract version
+    pub fn get_resource_profile(
+        env: Env,
+        contract_version: u32,
+        operation: PayrollOperation,
+    ) -> Option<ResourceProfile> {
+        resource_estimate::get_resource_profile(&env, contract_version, operation)
+    }
+
+    /// Estimates a `batch_claim_payroll` call for `batch_size` employees
+    ///
+    /// # Returns
+    /// Estimate built from the current version's profiles, or `None` if a
+    /// required profile has not been recorded
+    pub fn estimate_batch_gas(env: Env, batch_size: u32) -> Option<ResourceEstimate> {
+        resource_estimate::estimate_batch_gas(&env, batch_size)
+    }
+
+    /// Estimates paying every employee on an employer's active payroll agreements
+    ///
+    /// Accounts for the employer's real agreement and employee counts and the
+    /// number of distinct tokens they pay in.
+    ///
+    /// # Returns
+    /// Estimate built from the current version's profiles, or `None` if a
+    /// required profile has not been recorded
+    pub fn estimate_payroll_run(env: Env, employer: Address) -> Option<ResourceEstimate> {
+        resource_estimate::estimate_payroll_run(&env, employer)
+    }
+
     // ============================================================================
     // Encrypted Backup & Recovery
     // ============================================================================
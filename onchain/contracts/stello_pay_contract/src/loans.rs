@@ -0,0 +1,395 @@
+//! Loans collateralized by future salary payouts.
+//!
+//! An employee requests a loan against one of their payroll agreements,
+//! pledging a share (basis points) of up to `pledged_payouts` future payouts
+//! from it. Any lender approved by the owner can fund the request, which pays
+//! the principal straight to the employee.
+//!
+//! From then on every payout from that agreement in the loan's token routes
+//! the pledged share, after garnishments and before savings, to the lender
+//! until principal plus the flat interest is repaid. Each repayment is listed
+//! on the payout's [`Payslip`](crate::payslip::Payslip). A payout claimed in
+//! another token repays the same share of its gross in the loan's token,
+//! paid to the lender in the payout token at the payout's own rate.
+//!
+//! A loan defaults when its pledged payouts run out before it is repaid, or
+//! when the lender declares a default because the payroll ended early: the
+//! agreement completed, or was cancelled and its grace period has passed.
+//! Routing stops on default and the outstanding amount is left to the lender
+//! to recover off-chain.
+
+use soroban_sdk::{contracttype, token::Client as TokenClient, Address, Env};
+
+use crate::events::{
+    emit_loan_defaulted, emit_loan_funded, emit_loan_repaid, LoanDefaultedEvent, LoanFundedEvent,
+    LoanRepaidEvent,
+};
+use crate::payroll::{get_agreement, get_employee_agreements, transfer_from_contract};
+use crate::payslip::PayoutGross;
+use crate::rounding::{apply_bps, RoundingMode};
+use crate::storage::{AgreementMode, AgreementStatus, PayrollError, StorageKey};
+use crate::validation;
+
+/// Most future payouts one loan can pledge.
+pub const MAX_PLEDGED_PAYOUTS: u32 = 24;
+
+/// Largest share of a payout that can be pledged (50%).
+pub const MAX_LOAN_PLEDGE_BPS: u32 = 5_000;
+
+/// Highest flat interest on a loan, relative to its principal (50%).
+pub const MAX_LOAN_INTEREST_BPS: u32 = 5_000;
+
+const BPS_DENOMINATOR: i128 = 10_000;
+
+#[contracttype]
+#[derive(Clone)]
+enum LoanKey {
+    /// Lender approved by the owner -> bool
+    ApprovedLender(Address),
+    /// Next loan id -> u64
+    NextLoanId,
+    /// Loan by id -> SalaryLoan
+    Loan(u64),
+    /// Requested or active loan of an employee against an agreement -> u64
+    OpenLoan(Address, u128),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LoanStatus {
+    /// Waiting for a lender.
+    Requested,
+    /// Funded; payouts repay it.
+    Active,
+    Repaid,
+    Defaulted,
+    /// Withdrawn by the employee before funding.
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SalaryLoan {
+    pub id: u64,
+    pub employee: Address,
+    pub agreement_id: u128,
+    pub token: Address,
+    pub principal: i128,
+    /// Principal plus flat interest.
+    pub amount_due: i128,
+    pub repaid: i128,
+    /// Share of each pledged payout routed to the lender, in basis points.
+    pub pledge_bps: u32,
+    /// Pledged payouts not yet made.
+    pub payouts_remaining: u32,
+    pub lender: Option<Address>,
+    pub status: LoanStatus,
+    pub requested_at: u64,
+}
+
+/// Approves or removes a lender allowed to fund salary loans.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_loan_lender(
+    env: &Env,
+    owner: &Address,
+    lender: &Address,
+    approved: bool,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    let key = LoanKey::ApprovedLender(lender.clone());
+    if approved {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+    Ok(())
+}
+
+/// Returns whether `lender` may fund salary loans.
+pub fn is_loan_lender(env: &Env, lender: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get(&LoanKey::ApprovedLender(lender.clone()))
+        .unwrap_or(false)
+}
+
+/// Returns a loan by id.
+pub fn get_salary_loan(env: &Env, loan_id: u64) -> Option<SalaryLoan> {
+    env.storage().persistent().get(&LoanKey::Loan(loan_id))
+}
+
+/// Returns the id of the employee's requested or active loan against an
+/// agreement, if any.
+pub fn get_open_loan(env: &Env, employee: &Address, agreement_id: u128) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&LoanKey::OpenLoan(employee.clone(), agreement_id))
+}
+
+/// Requests a loan of `principal` against future payouts from an agreement.
+///
+/// # Returns
+/// The loan id.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - unknown agreement
+/// * `PayrollError::InvalidAgreementMode` - not a payroll agreement
+/// * `PayrollError::NoEmployee` - employee is not on the agreement
+/// * `PayrollError::InvalidData` - the agreement is not active, the employee
+///   already has an open loan against it, or an amount or limit is out of
+///   range
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn request_salary_loan(
+    env: &Env,
+    employee: &Address,
+    agreement_id: u128,
+    principal: i128,
+    interest_bps: u32,
+    pledge_bps: u32,
+    pledged_payouts: u32,
+) -> Result<u64, PayrollError> {
+    employee.require_auth();
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.mode != AgreementMode::Payroll {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+    if !get_employee_agreements(env, employee).contains(agreement_id) {
+        return Err(PayrollError::NoEmployee);
+    }
+    validation::amount(principal)?;
+    if agreement.status != AgreementStatus::Active
+        || get_open_loan(env, employee, agreement_id).is_some()
+        || interest_bps > MAX_LOAN_INTEREST_BPS
+        || pledge_bps == 0
+        || pledge_bps > MAX_LOAN_PLEDGE_BPS
+        || pledged_payouts == 0
+        || pledged_payouts > MAX_PLEDGED_PAYOUTS
+    {
+        return Err(PayrollError::InvalidData);
+    }
+    let amount_due = principal
+        .checked_mul(i128::from(interest_bps))
+        .and_then(|scaled| principal.checked_add(scaled / BPS_DENOMINATOR))
+        .ok_or(PayrollError::InvalidData)?;
+
+    let id: u64 = env
+        .storage()
+        .persistent()
+        .get(&LoanKey::NextLoanId)
+        .unwrap_or(1);
+    env.storage()
+        .persistent()
+        .set(&LoanKey::NextLoanId, &(id + 1));
+
+    let loan = SalaryLoan {
+        id,
+        employee: employee.clone(),
+        agreement_id,
+        token: agreement.token,
+        principal,
+        amount_due,
+        repaid: 0,
+        pledge_bps,
+        payouts_remaining: pledged_payouts,
+        lender: None,
+        status: LoanStatus::Requested,
+        requested_at: env.ledger().timestamp(),
+    };
+    env.storage().persistent().set(&LoanKey::Loan(id), &loan);
+    env.storage()
+        .persistent()
+        .set(&LoanKey::OpenLoan(employee.clone(), agreement_id), &id);
+    Ok(id)
+}
+
+/// Withdraws a loan request that has not been funded.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - unknown loan, or it is no longer requested
+/// * `PayrollError::Unauthorized` - caller is not the borrowing employee
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn cancel_loan_request(
+    env: &Env,
+    employee: &Address,
+    loan_id: u64,
+) -> Result<(), PayrollError> {
+    employee.require_auth();
+    let mut loan = get_salary_loan(env, loan_id)
+        .filter(|loan| loan.status == LoanStatus::Requested)
+        .ok_or(PayrollError::InvalidData)?;
+    if loan.employee != *employee {
+        return Err(PayrollError::Unauthorized);
+    }
+    loan.status = LoanStatus::Cancelled;
+    close_loan(env, &loan);
+    Ok(())
+}
+
+/// Funds a requested loan, paying the principal from the lender to the
+/// employee.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not an approved lender
+/// * `PayrollError::InvalidData` - unknown loan, or it is no longer requested
+///
+/// # Access Control
+/// Requires lender authentication
+pub fn fund_salary_loan(env: &Env, lender: &Address, loan_id: u64) -> Result<(), PayrollError> {
+    lender.require_auth();
+    if !is_loan_lender(env, lender) {
+        return Err(PayrollError::Unauthorized);
+    }
+    let mut loan = get_salary_loan(env, loan_id)
+        .filter(|loan| loan.status == LoanStatus::Requested)
+        .ok_or(PayrollError::InvalidData)?;
+
+    loan.lender = Some(lender.clone());
+    loan.status = LoanStatus::Active;
+    env.storage()
+        .persistent()
+        .set(&LoanKey::Loan(loan_id), &loan);
+    TokenClient::new(env, &loan.token).transfer(lender, &loan.employee, &loan.principal);
+    emit_loan_funded(
+        env,
+        LoanFundedEvent {
+            loan_id,
+            lender: lender.clone(),
+            employee: loan.employee,
+            principal: loan.principal,
+            amount_due: loan.amount_due,
+        },
+    );
+    Ok(())
+}
+
+/// Marks an active loan as defaulted because the agreement's payroll ended
+/// before it was repaid.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - unknown loan, it is not active, or the
+///   agreement can still pay out
+/// * `PayrollError::Unauthorized` - caller is not the loan's lender
+///
+/// # Access Control
+/// Requires lender authentication
+pub fn declare_loan_default(env: &Env, lender: &Address, loan_id: u64) -> Result<(), PayrollError> {
+    lender.require_auth();
+    let mut loan = get_salary_loan(env, loan_id)
+        .filter(|loan| loan.status == LoanStatus::Active)
+        .ok_or(PayrollError::InvalidData)?;
+    if loan.lender.as_ref() != Some(lender) {
+        return Err(PayrollError::Unauthorized);
+    }
+    let agreement = get_agreement(env, loan.agreement_id).ok_or(PayrollError::InvalidData)?;
+    let payroll_ended = match agreement.status {
+        AgreementStatus::Completed => true,
+        AgreementStatus::Cancelled => agreement.cancelled_at.is_some_and(|cancelled_at| {
+            env.ledger().timestamp() >= cancelled_at.saturating_add(agreement.grace_period_seconds)
+        }),
+        _ => false,
+    };
+    if !payroll_ended {
+        return Err(PayrollError::InvalidData);
+    }
+    default_loan(env, &mut loan);
+    Ok(())
+}
+
+/// Pays the pledged share of `payout` towards the employee's active loan
+/// against the agreement, out of the `available` part of the payout. The
+/// share is worked out in the loan's token, on the base-token gross if the
+/// payout was converted from it, and rounded with `mode`.
+///
+/// # Returns
+/// The amount paid to the lender in the payout token, which the caller must
+/// not transfer to the employee.
+pub(crate) fn repay_from_payout(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+    payout: &PayoutGross,
+    available: i128,
+    mode: RoundingMode,
+) -> i128 {
+    let Some(mut loan) = get_open_loan(env, employee, agreement_id)
+        .and_then(|loan_id| get_salary_loan(env, loan_id))
+        .filter(|loan| loan.status == LoanStatus::Active)
+    else {
+        return 0;
+    };
+    let (Some(lender), Some(gross)) = (loan.lender.clone(), payout.in_token(&loan.token)) else {
+        return 0;
+    };
+
+    let pledged = apply_bps(gross, loan.pledge_bps, mode);
+    let repaid = pledged
+        .min(loan.amount_due - loan.repaid)
+        .min(payout.to_token(&loan.token, available))
+        .max(0);
+    let amount = payout.to_paid(&loan.token, repaid);
+    loan.repaid += repaid;
+    loan.payouts_remaining -= 1;
+    if loan.repaid >= loan.amount_due {
+        loan.status = LoanStatus::Repaid;
+        close_loan(env, &loan);
+    } else if loan.payouts_remaining > 0 {
+        env.storage()
+            .persistent()
+            .set(&LoanKey::Loan(loan.id), &loan);
+    }
+
+    if amount > 0 {
+        transfer_from_contract(env, &payout.token, &lender, amount);
+        emit_loan_repaid(
+            env,
+            LoanRepaidEvent {
+                loan_id: loan.id,
+                agreement_id,
+                lender,
+                amount,
+                outstanding: loan.amount_due - loan.repaid,
+            },
+        );
+    }
+    if loan.status == LoanStatus::Active && loan.payouts_remaining == 0 {
+        default_loan(env, &mut loan);
+    }
+    amount
+}
+
+fn default_loan(env: &Env, loan: &mut SalaryLoan) {
+    loan.status = LoanStatus::Defaulted;
+    close_loan(env, loan);
+    emit_loan_defaulted(
+        env,
+        LoanDefaultedEvent {
+            loan_id: loan.id,
+            employee: loan.employee.clone(),
+            outstanding: loan.amount_due - loan.repaid,
+        },
+    );
+}
+
+/// Stores a loan that reached a final status and frees the employee to
+/// borrow against the agreement again.
+fn close_loan(env: &Env, loan: &SalaryLoan) {
+    env.storage()
+        .persistent()
+        .set(&LoanKey::Loan(loan.id), loan);
+    env.storage()
+        .persistent()
+        .remove(&LoanKey::OpenLoan(loan.employee.clone(), loan.agreement_id));
+}
@@ -0,0 +1,371 @@
+//! Employee actions authorized by an off-chain ed25519 signature.
+//!
+//! Employees without XLM for fees register an ed25519 public key once, then
+//! sign [`SignedRequest`]s off-chain. Anyone can relay a signed request with
+//! [`execute_signed_action`] and pay the fee; the contract checks the
+//! signature against the registered key and runs the action as the employee.
+//!
+//! The signed message is [`signed_action_payload`]: the XDR of this contract's
+//! address followed by the request, so a signature is only valid for one
+//! contract. Each request carries the employee's next nonce, which is used up
+//! when the request executes, and an expiry timestamp after which it can no
+//! longer be relayed. A signature can therefore be used at most once, in
+//! order, and only until it expires.
+//!
+//! Users can also sign with a passkey: a secp256r1 key held by a WebAuthn
+//! authenticator. They register the key with [`register_passkey`] and the
+//! relayer submits the authenticator's assertion to
+//! [`execute_passkey_action`]. The WebAuthn challenge is
+//! [`passkey_challenge`], the SHA-256 of the same payload, so passkey and
+//! ed25519 requests share nonces and expiry rules.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, Bytes, BytesN, Env};
+
+use crate::events::{
+    emit_passkey_registered, emit_signed_action_executed, emit_signing_key_registered,
+    PasskeyRegisteredEvent, SignedActionExecutedEvent, SigningKeyRegisteredEvent,
+};
+use crate::pause_exemptions::{is_paused_for, PauseCategory};
+use crate::payroll::claim_payroll;
+use crate::reimbursements::pay_reimbursement;
+use crate::savings::{remove_savings_plan, store_savings_plan, withdraw_from_vault};
+use crate::storage::PayrollError;
+
+/// Longest accepted WebAuthn `clientDataJSON`, in bytes.
+pub const MAX_CLIENT_DATA_LEN: u32 = 1024;
+
+/// Length of the authenticator data up to and including the sign counter.
+const AUTHENTICATOR_DATA_MIN_LEN: u32 = 37;
+
+/// Offset of the flags byte in the authenticator data, after the RP ID hash.
+const FLAGS_OFFSET: u32 = 32;
+
+/// "User present" bit of the authenticator data flags.
+const FLAG_USER_PRESENT: u8 = 0x01;
+
+/// Unpadded base64url length of a 32-byte challenge.
+const CHALLENGE_B64_LEN: usize = 43;
+
+#[contracttype]
+#[derive(Clone)]
+enum MetaTxKey {
+    /// Registered signing key of an employee -> BytesN<32>
+    SigningKey(Address),
+    /// Next nonce an employee's signed request must carry -> u64
+    Nonce(Address),
+    /// Registered passkey of a user, SEC-1 uncompressed -> BytesN<65>
+    Passkey(Address),
+}
+
+/// Action an employee can authorize by signature.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SignedAction {
+    /// `claim_payroll(agreement_id, employee_index)`
+    ClaimPayroll(u128, u32),
+    /// `withdraw_savings(token, amount, accept_penalty)`
+    WithdrawSavings(Address, i128, bool),
+    /// `set_savings_plan(bps, lock_duration)`
+    SetSavingsPlan(u32, u64),
+    /// `cancel_savings_plan()`
+    CancelSavingsPlan,
+    /// `approve_reimbursement(employer, request_id)`, signed by the approver
+    ApproveReimbursement(Address, u64),
+}
+
+/// A request an employee, or an approver, signs off-chain.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignedRequest {
+    /// Signer the action runs as: the employee, or the approver of an
+    /// approval.
+    pub employee: Address,
+    pub action: SignedAction,
+    /// Must equal [`get_signing_nonce`] when the request executes.
+    pub nonce: u64,
+    /// Last ledger timestamp at which the request can execute.
+    pub expiry: u64,
+}
+
+/// Registers `public_key` as the employee's signing key, replacing any
+/// previous key. Nonces carry over, so requests signed with the old key that
+/// already executed cannot be replayed under the new one.
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn register_signing_key(env: &Env, employee: &Address, public_key: BytesN<32>) {
+    employee.require_auth();
+    env.storage()
+        .persistent()
+        .set(&MetaTxKey::SigningKey(employee.clone()), &public_key);
+    emit_signing_key_registered(
+        env,
+        SigningKeyRegisteredEvent {
+            employee: employee.clone(),
+            public_key,
+        },
+    );
+}
+
+/// Removes the employee's signing key. Signed requests are rejected until a
+/// new key is registered.
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn revoke_signing_key(env: &Env, employee: &Address) {
+    employee.require_auth();
+    env.storage()
+        .persistent()
+        .remove(&MetaTxKey::SigningKey(employee.clone()));
+}
+
+/// Returns the employee's registered signing key, if any.
+pub fn get_signing_key(env: &Env, employee: &Address) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&MetaTxKey::SigningKey(employee.clone()))
+}
+
+/// Returns the nonce the employee's next signed request must carry.
+pub fn get_signing_nonce(env: &Env, employee: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&MetaTxKey::Nonce(employee.clone()))
+        .unwrap_or(0)
+}
+
+/// Returns the bytes the employee signs for `request`.
+pub fn signed_action_payload(env: &Env, request: &SignedRequest) -> Bytes {
+    let mut payload = env.current_contract_address().to_xdr(env);
+    payload.append(&request.clone().to_xdr(env));
+    payload
+}
+
+/// Registers `public_key`, an uncompressed SEC-1 secp256r1 key, as the
+/// user's passkey, replacing any previous one. Nonces are shared with the
+/// ed25519 signing key.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `public_key` is not in uncompressed form
+///
+/// # Access Control
+/// Requires user authentication
+pub fn register_passkey(
+    env: &Env,
+    user: &Address,
+    public_key: BytesN<65>,
+) -> Result<(), PayrollError> {
+    user.require_auth();
+    if public_key.get(0) != Some(0x04) {
+        return Err(PayrollError::InvalidData);
+    }
+    env.storage()
+        .persistent()
+        .set(&MetaTxKey::Passkey(user.clone()), &public_key);
+    emit_passkey_registered(
+        env,
+        PasskeyRegisteredEvent {
+            user: user.clone(),
+            public_key,
+        },
+    );
+    Ok(())
+}
+
+/// Removes the user's passkey.
+///
+/// # Access Control
+/// Requires user authentication
+pub fn revoke_passkey(env: &Env, user: &Address) {
+    user.require_auth();
+    env.storage()
+        .persistent()
+        .remove(&MetaTxKey::Passkey(user.clone()));
+}
+
+/// Returns the user's registered passkey, if any.
+pub fn get_passkey(env: &Env, user: &Address) -> Option<BytesN<65>> {
+    env.storage()
+        .persistent()
+        .get(&MetaTxKey::Passkey(user.clone()))
+}
+
+/// Returns the WebAuthn challenge a passkey signs for `request`: the
+/// SHA-256 of [`signed_action_payload`].
+pub fn passkey_challenge(env: &Env, request: &SignedRequest) -> BytesN<32> {
+    env.crypto()
+        .sha256(&signed_action_payload(env, request))
+        .into()
+}
+
+/// Verifies `signature` over `request` and runs its action as the employee.
+/// The relayer pays the fee and needs no relation to the employee.
+///
+/// The action runs exactly as if the employee had called it, with the same
+/// checks; for example, a payroll claim still fails if the employee is not at
+/// `employee_index`.
+///
+/// # Errors
+/// * `PayrollError::EmergencyPaused` - the contract is paused
+/// * `PayrollError::Unauthorized` - the employee has no signing key
+/// * `PayrollError::InvalidData` - the request has expired or its nonce is not
+///   the employee's next nonce
+/// * Any error of the action itself
+///
+/// An invalid signature aborts the call.
+///
+/// # Access Control
+/// Requires relayer authentication and a valid employee signature
+pub fn execute_signed_action(
+    env: &Env,
+    relayer: &Address,
+    request: SignedRequest,
+    signature: BytesN<64>,
+) -> Result<(), PayrollError> {
+    relayer.require_auth();
+    if is_paused_for(env, PauseCategory::RelayedActions) {
+        return Err(PayrollError::EmergencyPaused);
+    }
+    let public_key = get_signing_key(env, &request.employee).ok_or(PayrollError::Unauthorized)?;
+    check_request(env, &request)?;
+    env.crypto().ed25519_verify(
+        &public_key,
+        &signed_action_payload(env, &request),
+        &signature,
+    );
+    run(env, relayer, request)
+}
+
+/// Verifies a WebAuthn assertion over `request` made with the signer's
+/// passkey, and runs its action as the signer.
+///
+/// `authenticator_data`, `client_data_json` and `signature` are the
+/// authenticator's response to a `navigator.credentials.get()` call whose
+/// challenge was [`passkey_challenge`]. The signature is the raw 64-byte
+/// `r || s`, with `s` in low form. The contract checks that
+/// `client_data_json` is a `webauthn.get` response for that challenge, that
+/// the user was present, and verifies the secp256r1 signature over
+/// `authenticator_data || sha256(client_data_json)`. The origin and relying
+/// party are left to the relayer.
+///
+/// # Errors
+/// * `PayrollError::EmergencyPaused` - the contract is paused
+/// * `PayrollError::Unauthorized` - the signer has no passkey
+/// * `PayrollError::InvalidData` - the request has expired or has the wrong
+///   nonce, or the assertion is malformed, is for another challenge or lacks
+///   user presence
+/// * Any error of the action itself
+///
+/// An invalid signature aborts the call.
+///
+/// # Access Control
+/// Requires relayer authentication and a valid passkey assertion
+pub fn execute_passkey_action(
+    env: &Env,
+    relayer: &Address,
+    request: SignedRequest,
+    authenticator_data: Bytes,
+    client_data_json: Bytes,
+    signature: BytesN<64>,
+) -> Result<(), PayrollError> {
+    relayer.require_auth();
+    if is_paused_for(env, PauseCategory::RelayedActions) {
+        return Err(PayrollError::EmergencyPaused);
+    }
+    let public_key = get_passkey(env, &request.employee).ok_or(PayrollError::Unauthorized)?;
+    check_request(env, &request)?;
+
+    if authenticator_data.len() < AUTHENTICATOR_DATA_MIN_LEN
+        || authenticator_data.get_unchecked(FLAGS_OFFSET) & FLAG_USER_PRESENT == 0
+    {
+        return Err(PayrollError::InvalidData);
+    }
+    check_client_data(&client_data_json, &passkey_challenge(env, &request))?;
+
+    let mut signed = authenticator_data;
+    signed.extend_from_array(&env.crypto().sha256(&client_data_json).to_array());
+    env.crypto()
+        .secp256r1_verify(&public_key, &env.crypto().sha256(&signed), &signature);
+    run(env, relayer, request)
+}
+
+/// Checks that `client_data_json` is a `webauthn.get` response for
+/// `challenge`.
+fn check_client_data(client_data_json: &Bytes, challenge: &BytesN<32>) -> Result<(), PayrollError> {
+    let len = client_data_json.len();
+    if len > MAX_CLIENT_DATA_LEN {
+        return Err(PayrollError::InvalidData);
+    }
+    let mut json = [0u8; MAX_CLIENT_DATA_LEN as usize];
+    let json = &mut json[..len as usize];
+    client_data_json.copy_into_slice(json);
+
+    const PREFIX: &[u8] = b"\"challenge\":\"";
+    let mut expected = [0u8; PREFIX.len() + CHALLENGE_B64_LEN + 1];
+    expected[..PREFIX.len()].copy_from_slice(PREFIX);
+    URL_SAFE_NO_PAD
+        .encode_slice(
+            challenge.to_array(),
+            &mut expected[PREFIX.len()..PREFIX.len() + CHALLENGE_B64_LEN],
+        )
+        .map_err(|_| PayrollError::InvalidData)?;
+    expected[PREFIX.len() + CHALLENGE_B64_LEN] = b'"';
+
+    if !contains(json, b"\"type\":\"webauthn.get\"") || !contains(json, &expected) {
+        return Err(PayrollError::InvalidData);
+    }
+    Ok(())
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+/// Checks that `request` has not expired and carries the signer's next nonce.
+fn check_request(env: &Env, request: &SignedRequest) -> Result<(), PayrollError> {
+    if env.ledger().timestamp() > request.expiry
+        || request.nonce != get_signing_nonce(env, &request.employee)
+    {
+        return Err(PayrollError::InvalidData);
+    }
+    Ok(())
+}
+
+/// Uses up the request's nonce and runs its action as the signer.
+fn run(env: &Env, relayer: &Address, request: SignedRequest) -> Result<(), PayrollError> {
+    let employee = request.employee.clone();
+    // Use the nonce up before running the action, so the action cannot
+    // re-enter with the same request.
+    env.storage()
+        .persistent()
+        .set(&MetaTxKey::Nonce(employee.clone()), &(request.nonce + 1));
+
+    match request.action {
+        SignedAction::ClaimPayroll(agreement_id, employee_index) => {
+            claim_payroll(env, &employee, agreement_id, employee_index)?
+        }
+        SignedAction::WithdrawSavings(token, amount, accept_penalty) => {
+            withdraw_from_vault(env, &employee, &token, amount, accept_penalty)?;
+        }
+        SignedAction::SetSavingsPlan(bps, lock_duration) => {
+            store_savings_plan(env, &employee, bps, lock_duration)?
+        }
+        SignedAction::CancelSavingsPlan => remove_savings_plan(env, &employee),
+        SignedAction::ApproveReimbursement(employer, request_id) => {
+            pay_reimbursement(env, &employee, &employer, request_id)?
+        }
+    }
+
+    emit_signed_action_executed(
+        env,
+        SignedActionExecutedEvent {
+            employee,
+            nonce: request.nonce,
+            relayer: relayer.clone(),
+        },
+    );
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! Per-employer key-value metadata.
+//!
+//! Employers attach integration data such as ERP references, cost-center
+//! codes or external IDs to themselves or to one of their employees. Each
+//! subject (the employer, or an employer-employee pair) holds a string map of
+//! at most [`MAX_METADATA_ENTRIES`] entries. Keys are at most
+//! [`MAX_METADATA_KEY_LEN`] bytes and values at most
+//! [`MAX_STRING_LEN`](crate::validation::MAX_STRING_LEN) bytes, so the whole
+//! map stays small enough to read in one call.
+//!
+//! Only the employer can write its metadata. Anyone can read it; it is not a
+//! place for confidential data.
+
+use soroban_sdk::{contracttype, Address, Env, Map, String};
+
+use crate::storage::{extend_persistent_ttl, PayrollError};
+use crate::validation::{self, ValidationError};
+
+/// Most entries one employer or employee can carry.
+pub const MAX_METADATA_ENTRIES: u32 = 32;
+
+/// Longest accepted metadata key, in bytes.
+pub const MAX_METADATA_KEY_LEN: u32 = 64;
+
+#[contracttype]
+#[derive(Clone)]
+enum MetadataKey {
+    /// Entries of an employer, or of one of its employees -> Map<String, String>
+    Entries(Address, Option<Address>),
+}
+
+fn entries_key(employer: &Address, employee: &Option<Address>) -> MetadataKey {
+    MetadataKey::Entries(employer.clone(), employee.clone())
+}
+
+/// Returns every entry for `employer`, or for `employee` under `employer`.
+pub fn list_metadata(
+    env: &Env,
+    employer: &Address,
+    employee: &Option<Address>,
+) -> Map<String, String> {
+    let key = entries_key(employer, employee);
+    let entries = env.storage().persistent().get(&key);
+    if entries.is_some() {
+        extend_persistent_ttl(env, &key);
+    }
+    entries.unwrap_or(Map::new(env))
+}
+
+/// Returns the value stored under `key`, if any.
+pub fn get_metadata(
+    env: &Env,
+    employer: &Address,
+    employee: &Option<Address>,
+    key: &String,
+) -> Option<String> {
+    list_metadata(env, employer, employee).get(key.clone())
+}
+
+/// Sets `key` to `value`, replacing any previous value.
+///
+/// # Errors
+/// - `InvalidData` if `key` is empty or longer than `MAX_METADATA_KEY_LEN`,
+///   or `value` is longer than `MAX_STRING_LEN`
+/// - `BatchTooLarge` if `key` is new and the subject already holds
+///   `MAX_METADATA_ENTRIES` entries
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn set_metadata(
+    env: &Env,
+    employer: &Address,
+    employee: &Option<Address>,
+    key: &String,
+    value: &String,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    if key.is_empty() || key.len() > MAX_METADATA_KEY_LEN {
+        return Err(PayrollError::InvalidData);
+    }
+    validation::string(value)?;
+
+    let mut entries = list_metadata(env, employer, employee);
+    if !entries.contains_key(key.clone()) && entries.len() >= MAX_METADATA_ENTRIES {
+        return Err(ValidationError::TooManyItems.into());
+    }
+    entries.set(key.clone(), value.clone());
+    env.storage()
+        .persistent()
+        .set(&entries_key(employer, employee), &entries);
+    Ok(())
+}
+
+/// Removes `key`. Removing a key that is not set is a no-op.
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn remove_metadata(env: &Env, employer: &Address, employee: &Option<Address>, key: &String) {
+    employer.require_auth();
+    let mut entries = list_metadata(env, employer, employee);
+    if entries.remove(key.clone()).is_none() {
+        return;
+    }
+    let storage_key = entries_key(employer, employee);
+    if entries.is_empty() {
+        env.storage().persistent().remove(&storage_key);
+    } else {
+        env.storage().persistent().set(&storage_key, &entries);
+    }
+}
@@ -0,0 +1,198 @@
+//! Payout timing and the analytics feed.
+//!
+//! Every periodic payroll payout (`claim_payroll`, `claim_payroll_in_token`,
+//! `batch_claim_payroll` and confidential disbursements) is classified here.
+//! Each paid period counts as one payout. Period `k` of an agreement falls
+//! due at `activation_time + k * period_duration`; a period paid less than
+//! [`ON_TIME_TOLERANCE_SECONDS`] after it fell due is on time, anything later
+//! is late. Because periods accrue in order, a claim covering several periods
+//! always pays the late (oldest) ones first, so the counts and the lateness
+//! sum are computed in closed form instead of per period. Employer reputation
+//! and SLAs consume the result directly.
+//!
+//! Per-employee metrics and employer reporting totals live in the separate
+//! `payroll_analytics` contract, which keeps their storage out of this one.
+//! When the owner links one, each payout is pushed to it as a compact
+//! [`PayoutRecord`]. Employers may set a reporting currency; each payout on
+//! their agreements is then converted from the agreement token into that
+//! currency at the stored oracle rate (`DataKey::ExchangeRate`, subject to the
+//! usual staleness check) before being pushed, so dashboards can show one
+//! fiat-equivalent figure across payroll tokens. A payout that cannot be
+//! converted is pushed without a converted amount. A failing analytics call
+//! never blocks the payout itself.
+
+use analytics_interface::{PayoutRecord, PayrollAnalyticsClient};
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::payroll::convert_amount;
+use crate::storage::{PayrollError, StorageKey};
+
+/// Grace after a period falls due within which its payout is still on time.
+pub const ON_TIME_TOLERANCE_SECONDS: u64 = 86_400;
+
+/// Lateness from which a period counts as defaulted rather than just late.
+pub const DEFAULT_THRESHOLD_SECONDS: u64 = 30 * 86_400;
+
+#[contracttype]
+#[derive(Clone)]
+enum MetricsKey {
+    /// Linked analytics contract -> Address
+    Analytics,
+    /// Reporting currency chosen by an employer -> Address
+    ReportingCurrency(Address),
+}
+
+/// How the periods covered by one payout were paid.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct PayoutTiming {
+    pub on_time: u32,
+    /// Late periods, including defaulted ones.
+    pub late: u32,
+    /// Periods paid [`DEFAULT_THRESHOLD_SECONDS`] or more after falling due.
+    pub defaulted: u32,
+    /// Sum of the lateness of the late periods, in seconds.
+    pub seconds_late: u64,
+}
+
+/// Classifies a payout covering periods `claimed_periods + 1 ..=
+/// claimed_periods + periods_paid` of an agreement activated at
+/// `activation_time`.
+pub(crate) fn payout_timing(
+    env: &Env,
+    activation_time: u64,
+    period_duration: u64,
+    claimed_periods: u32,
+    periods_paid: u32,
+) -> PayoutTiming {
+    if periods_paid == 0 || period_duration == 0 {
+        return PayoutTiming::default();
+    }
+    let now = env.ledger().timestamp();
+    let elapsed = u128::from(now.saturating_sub(activation_time));
+    let period = u128::from(period_duration);
+    let first = u128::from(claimed_periods) + 1;
+    let last = u128::from(claimed_periods) + u128::from(periods_paid);
+
+    let latest_late = latest_period_late_by(elapsed, period, last, ON_TIME_TOLERANCE_SECONDS);
+    let late = latest_late.saturating_sub(first - 1);
+    let defaulted = latest_period_late_by(elapsed, period, last, DEFAULT_THRESHOLD_SECONDS)
+        .saturating_sub(first - 1);
+    let on_time = u128::from(periods_paid) - late;
+    // Sum of (elapsed - k * period) over the late periods first..=latest_late;
+    // every k * period there is at most `elapsed`, so this cannot overflow.
+    let seconds_late = if late == 0 {
+        0
+    } else {
+        late * elapsed - period * ((first + latest_late) * late / 2)
+    };
+
+    PayoutTiming {
+        on_time: saturate_u32(on_time),
+        late: saturate_u32(late),
+        defaulted: saturate_u32(defaulted),
+        seconds_late: u64::try_from(seconds_late).unwrap_or(u64::MAX),
+    }
+}
+
+/// Last period, up to `last`, paid at least `lateness` seconds after falling
+/// due. Period k qualifies when elapsed - k * period >= lateness, i.e. for
+/// every k up to (elapsed - lateness) / period.
+fn latest_period_late_by(elapsed: u128, period: u128, last: u128, lateness: u64) -> u128 {
+    elapsed
+        .checked_sub(u128::from(lateness))
+        .map_or(0, |span| span / period)
+        .min(last)
+}
+
+fn saturate_u32(value: u128) -> u32 {
+    u32::try_from(value).unwrap_or(u32::MAX)
+}
+
+/// Links the analytics contract payouts are pushed to, or unlinks it with
+/// `None`.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - `owner` is not the contract owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_analytics_contract(
+    env: &Env,
+    owner: &Address,
+    analytics: Option<Address>,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    match analytics {
+        Some(analytics) => env
+            .storage()
+            .persistent()
+            .set(&MetricsKey::Analytics, &analytics),
+        None => env.storage().persistent().remove(&MetricsKey::Analytics),
+    }
+    Ok(())
+}
+
+/// Returns the linked analytics contract, if any.
+pub fn get_analytics_contract(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&MetricsKey::Analytics)
+}
+
+/// Pushes a payout of `amount` in `token` to the linked analytics contract,
+/// converted into the employer's reporting currency if it has one. No-op
+/// without an analytics contract.
+pub(crate) fn push_payout(
+    env: &Env,
+    employee: &Address,
+    employer: &Address,
+    token: &Address,
+    amount: i128,
+    timing: &PayoutTiming,
+) {
+    let Some(analytics) = get_analytics_contract(env) else {
+        return;
+    };
+    let reporting_currency = get_reporting_currency(env, employer);
+    let reporting_amount = reporting_currency
+        .as_ref()
+        .and_then(|currency| convert_amount(env, token, currency, amount).ok());
+    let record = PayoutRecord {
+        employee: employee.clone(),
+        employer: employer.clone(),
+        amount,
+        on_time: timing.on_time,
+        late: timing.late,
+        seconds_late: timing.seconds_late,
+        reporting_currency,
+        reporting_amount,
+    };
+    let _ = PayrollAnalyticsClient::new(env, &analytics).try_record_payout(&record);
+}
+
+/// Sets or clears the reporting currency for `employer`, restarting its
+/// reporting totals in the analytics contract.
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn set_reporting_currency(env: &Env, employer: &Address, currency: Option<Address>) {
+    employer.require_auth();
+    let key = MetricsKey::ReportingCurrency(employer.clone());
+    match &currency {
+        Some(currency) => env.storage().persistent().set(&key, currency),
+        None => env.storage().persistent().remove(&key),
+    }
+    if let Some(analytics) = get_analytics_contract(env) {
+        let _ = PayrollAnalyticsClient::new(env, &analytics)
+            .try_set_reporting_currency(employer, &currency);
+    }
+}
+
+/// Returns the reporting currency of `employer`, if set.
+pub fn get_reporting_currency(env: &Env, employer: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&MetricsKey::ReportingCurrency(employer.clone()))
+}
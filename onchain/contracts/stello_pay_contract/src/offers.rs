@@ -0,0 +1,262 @@
+//! Escrow-backed job offers with a signing bonus.
+//!
+//! An employer extends an offer by locking a signing bonus, in the token of
+//! the payroll agreement the candidate is being hired onto. The candidate
+//! accepts on-chain before the offer's deadline, and claims the bonus once
+//! onboarding is done: the agreement is active and the candidate is one of
+//! its employees.
+//!
+//! An offer that is not accepted in time, or whose agreement is cancelled
+//! before the bonus is claimed, is refunded to the employer. Contracts cannot
+//! act on their own, so the refund is permissionless: the employer, the
+//! candidate or a keeper can trigger it as soon as it is due.
+
+use soroban_sdk::{contracttype, token::Client as TokenClient, Address, Env};
+
+use crate::events::{
+    emit_offer_accepted, emit_offer_bonus_claimed, emit_offer_extended, emit_offer_refunded,
+    OfferAcceptedEvent, OfferBonusClaimedEvent, OfferExtendedEvent, OfferRefundedEvent,
+};
+use crate::payroll::{get_agreement, get_agreement_employees, transfer_from_contract};
+use crate::storage::{AgreementStatus, PayrollError};
+use crate::validation;
+
+#[contracttype]
+#[derive(Clone)]
+enum OfferKey {
+    /// Last assigned offer ID -> u64
+    NextId,
+    /// Offer by ID -> JobOffer
+    Offer(u64),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OfferStatus {
+    /// Extended and awaiting the candidate.
+    Pending,
+    /// Accepted; the bonus is claimable once the agreement is active.
+    Accepted,
+    Claimed,
+    Refunded,
+}
+
+/// A job offer and the signing bonus locked behind it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JobOffer {
+    pub id: u64,
+    pub employer: Address,
+    pub candidate: Address,
+    /// Payroll agreement the candidate is hired onto.
+    pub agreement_id: u128,
+    /// The agreement's token when the offer was extended.
+    pub token: Address,
+    pub signing_bonus: i128,
+    /// Latest time the candidate can accept.
+    pub accept_by: u64,
+    pub status: OfferStatus,
+}
+
+fn save(env: &Env, offer: &JobOffer) {
+    env.storage()
+        .persistent()
+        .set(&OfferKey::Offer(offer.id), offer);
+}
+
+fn load(env: &Env, offer_id: u64) -> Result<JobOffer, PayrollError> {
+    get_offer(env, offer_id).ok_or(PayrollError::InvalidData)
+}
+
+/// Extends an offer to `candidate` for `agreement_id`, locking
+/// `signing_bonus` of the agreement's token until it is claimed or refunded.
+/// Returns the offer ID.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - no such agreement
+/// * `PayrollError::Unauthorized` - caller is not the agreement's employer
+/// * `PayrollError::InvalidData` - the agreement is neither `Created` nor
+///   `Active`, `signing_bonus` is not positive or above `MAX_AMOUNT`, or
+///   `accept_by` is not in the future or more than `MAX_DURATION_SECONDS`
+///   away
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn extend_offer(
+    env: &Env,
+    employer: &Address,
+    candidate: &Address,
+    agreement_id: u128,
+    signing_bonus: i128,
+    accept_by: u64,
+) -> Result<u64, PayrollError> {
+    employer.require_auth();
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    if !matches!(
+        agreement.status,
+        AgreementStatus::Created | AgreementStatus::Active
+    ) {
+        return Err(PayrollError::InvalidData);
+    }
+    validation::amount(signing_bonus)?;
+    validation::interval(
+        accept_by.saturating_sub(env.ledger().timestamp()),
+        1,
+        validation::MAX_DURATION_SECONDS,
+    )?;
+
+    TokenClient::new(env, &agreement.token).transfer(
+        employer,
+        env.current_contract_address(),
+        &signing_bonus,
+    );
+    let id: u64 = env
+        .storage()
+        .persistent()
+        .get(&OfferKey::NextId)
+        .unwrap_or(0)
+        + 1;
+    env.storage().persistent().set(&OfferKey::NextId, &id);
+    save(
+        env,
+        &JobOffer {
+            id,
+            employer: employer.clone(),
+            candidate: candidate.clone(),
+            agreement_id,
+            token: agreement.token.clone(),
+            signing_bonus,
+            accept_by,
+            status: OfferStatus::Pending,
+        },
+    );
+    emit_offer_extended(
+        env,
+        OfferExtendedEvent {
+            offer_id: id,
+            employer: employer.clone(),
+            candidate: candidate.clone(),
+            agreement_id,
+            signing_bonus,
+            accept_by,
+        },
+    );
+    Ok(id)
+}
+
+/// Returns an offer by ID.
+pub fn get_offer(env: &Env, offer_id: u64) -> Option<JobOffer> {
+    env.storage().persistent().get(&OfferKey::Offer(offer_id))
+}
+
+/// Accepts a pending offer before its deadline.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no such offer, it is not pending, or its
+///   deadline has passed
+/// * `PayrollError::Unauthorized` - caller is not the candidate
+///
+/// # Access Control
+/// Requires candidate authentication
+pub fn accept_offer(env: &Env, candidate: &Address, offer_id: u64) -> Result<(), PayrollError> {
+    candidate.require_auth();
+    let mut offer = load(env, offer_id)?;
+    if offer.candidate != *candidate {
+        return Err(PayrollError::Unauthorized);
+    }
+    if offer.status != OfferStatus::Pending || env.ledger().timestamp() > offer.accept_by {
+        return Err(PayrollError::InvalidData);
+    }
+    offer.status = OfferStatus::Accepted;
+    save(env, &offer);
+    emit_offer_accepted(
+        env,
+        OfferAcceptedEvent {
+            offer_id,
+            candidate: candidate.clone(),
+        },
+    );
+    Ok(())
+}
+
+/// Pays an accepted offer's signing bonus to the candidate once its
+/// agreement is active and the candidate is on it. Returns the bonus paid.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no such offer, or it is not accepted
+/// * `PayrollError::Unauthorized` - caller is not the candidate
+/// * `PayrollError::AgreementNotActivated` - the agreement is not active
+/// * `PayrollError::NoEmployee` - the candidate is not on the agreement
+///
+/// # Access Control
+/// Requires candidate authentication
+pub fn claim_offer_bonus(
+    env: &Env,
+    candidate: &Address,
+    offer_id: u64,
+) -> Result<i128, PayrollError> {
+    candidate.require_auth();
+    let mut offer = load(env, offer_id)?;
+    if offer.candidate != *candidate {
+        return Err(PayrollError::Unauthorized);
+    }
+    if offer.status != OfferStatus::Accepted {
+        return Err(PayrollError::InvalidData);
+    }
+    let agreement =
+        get_agreement(env, offer.agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.status != AgreementStatus::Active {
+        return Err(PayrollError::AgreementNotActivated);
+    }
+    if !get_agreement_employees(env, offer.agreement_id).contains(candidate) {
+        return Err(PayrollError::NoEmployee);
+    }
+
+    offer.status = OfferStatus::Claimed;
+    save(env, &offer);
+    transfer_from_contract(env, &offer.token, candidate, offer.signing_bonus);
+    emit_offer_bonus_claimed(
+        env,
+        OfferBonusClaimedEvent {
+            offer_id,
+            candidate: candidate.clone(),
+            amount: offer.signing_bonus,
+        },
+    );
+    Ok(offer.signing_bonus)
+}
+
+/// Returns an offer's signing bonus to the employer when it is due: the
+/// offer lapsed unaccepted, or its agreement was cancelled before the bonus
+/// was claimed. Returns the amount refunded.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no such offer, it was already claimed or
+///   refunded, or no refund is due yet
+pub fn refund_offer(env: &Env, offer_id: u64) -> Result<i128, PayrollError> {
+    let mut offer = load(env, offer_id)?;
+    let agreement =
+        get_agreement(env, offer.agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    let lapsed = offer.status == OfferStatus::Pending && env.ledger().timestamp() > offer.accept_by;
+    let cancelled = matches!(offer.status, OfferStatus::Pending | OfferStatus::Accepted)
+        && agreement.status == AgreementStatus::Cancelled;
+    if !lapsed && !cancelled {
+        return Err(PayrollError::InvalidData);
+    }
+
+    offer.status = OfferStatus::Refunded;
+    save(env, &offer);
+    transfer_from_contract(env, &offer.token, &offer.employer, offer.signing_bonus);
+    emit_offer_refunded(
+        env,
+        OfferRefundedEvent {
+            offer_id,
+            employer: offer.employer,
+            amount: offer.signing_bonus,
+        },
+    );
+    Ok(offer.signing_bonus)
+}
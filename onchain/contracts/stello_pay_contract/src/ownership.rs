@@ -0,0 +1,303 @@
+//! Progressive decentralization: renouncing ownership to a guardian.
+//!
+//! Once the protocol no longer needs an operator, the owner can call
+//! [`renounce_ownership`]. The owner record is deleted, so every owner-gated
+//! function fails from then on, and the contract cannot be initialized again.
+//! The contract records the new, reduced-trust [`OperatingMode::Guarded`]
+//! mode and the guardian's address. The guardian can only do two things:
+//!
+//! - **Emergency pauses**: pause for at most [`GUARDIAN_MAX_PAUSE_SECONDS`]
+//!   at a time, and lift a pause. This includes a pause activated by the
+//!   emergency guardian multisig, which no owner can lift any more.
+//! - **Pre-approved upgrades**: upgrade to a WASM hash on the ratified
+//!   allowlist. Each ratified hash can be used once.
+//!
+//! A hash is ratified by copying it from the upgrade governance contract,
+//! which the owner links before renouncing. Anyone can call
+//! [`ratify_upgrade`], which only succeeds for the hash governance currently
+//! approves for this contract. Without a linked governance contract, no
+//! upgrade is possible once ownership is renounced.
+//!
+//! RBAC admins lose their upgrade rights on renouncement as well.
+
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env, String, Vec};
+
+use crate::events::{
+    emit_ownership_renounced, emit_upgrade_ratified, OwnershipRenouncedEvent, UpgradeRatifiedEvent,
+};
+use crate::payroll::{activate_emergency_pause, lift_emergency_pause};
+use crate::storage::{PayrollError, StorageKey, MAX_BATCH_SIZE};
+
+/// Longest pause the guardian can activate at once (30 days).
+pub const GUARDIAN_MAX_PAUSE_SECONDS: u64 = 30 * 86_400;
+
+/// Most ratified upgrades that can wait on the allowlist at once.
+pub const MAX_RATIFIED_UPGRADES: u32 = MAX_BATCH_SIZE;
+
+/// Subset of the governance contract's interface used to ratify upgrades.
+#[contractclient(name = "UpgradeGovernanceClient")]
+pub trait UpgradeGovernanceInterface {
+    fn get_approved_upgrade(env: Env, target: Address) -> Option<BytesN<32>>;
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum OwnershipKey {
+    /// Governance contract approving upgrades -> Address
+    UpgradeGovernance,
+    /// Set once ownership is renounced -> GuardedMode
+    Guarded,
+    /// Ratified WASM hashes not yet used -> Vec<BytesN<32>>
+    RatifiedUpgrades,
+}
+
+/// Trust model the contract currently operates under.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OperatingMode {
+    /// Not initialized yet.
+    Uninitialized,
+    /// An owner administers the contract.
+    Owned,
+    /// Ownership was renounced. Only the guardian's limited powers remain.
+    Guarded,
+}
+
+/// Record of the renouncement.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct GuardedMode {
+    guardian: Address,
+    former_owner: Address,
+    renounced_at: u64,
+}
+
+/// Who administers the contract, and how.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnershipInfo {
+    pub mode: OperatingMode,
+    pub owner: Option<Address>,
+    /// Set in `Guarded` mode.
+    pub guardian: Option<Address>,
+    /// Set in `Guarded` mode.
+    pub former_owner: Option<Address>,
+    /// Set in `Guarded` mode.
+    pub renounced_at: Option<u64>,
+    pub upgrade_governance: Option<Address>,
+}
+
+fn require_owner(env: &Env, owner: &Address) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    Ok(())
+}
+
+fn guarded_mode(env: &Env) -> Option<GuardedMode> {
+    env.storage().persistent().get(&OwnershipKey::Guarded)
+}
+
+/// Fails unless ownership is renounced and `guardian` is the guardian.
+///
+/// # Errors
+/// * `PayrollError::NotGuardian` - ownership is not renounced, or `guardian`
+///   is not the guardian
+fn require_guardian(env: &Env, guardian: &Address) -> Result<(), PayrollError> {
+    guardian.require_auth();
+    match guarded_mode(env) {
+        Some(mode) if mode.guardian == *guardian => Ok(()),
+        _ => Err(PayrollError::NotGuardian),
+    }
+}
+
+/// Links the governance contract whose approved upgrades can be ratified.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_upgrade_governance(
+    env: &Env,
+    owner: &Address,
+    governance: &Address,
+) -> Result<(), PayrollError> {
+    require_owner(env, owner)?;
+    env.storage()
+        .persistent()
+        .set(&OwnershipKey::UpgradeGovernance, governance);
+    Ok(())
+}
+
+/// Removes the owner for good and hands the guardian powers to `guardian`.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn renounce_ownership(
+    env: &Env,
+    caller: &Address,
+    guardian: &Address,
+) -> Result<(), PayrollError> {
+    require_owner(env, caller)?;
+    env.storage().persistent().remove(&StorageKey::Owner);
+    env.storage().persistent().set(
+        &OwnershipKey::Guarded,
+        &GuardedMode {
+            guardian: guardian.clone(),
+            former_owner: caller.clone(),
+            renounced_at: env.ledger().timestamp(),
+        },
+    );
+    emit_ownership_renounced(
+        env,
+        OwnershipRenouncedEvent {
+            former_owner: caller.clone(),
+            guardian: guardian.clone(),
+        },
+    );
+    Ok(())
+}
+
+/// Returns whether ownership has been renounced.
+pub fn is_renounced(env: &Env) -> bool {
+    env.storage().persistent().has(&OwnershipKey::Guarded)
+}
+
+/// Returns the guardian, once ownership has been renounced.
+pub fn get_guardian(env: &Env) -> Option<Address> {
+    guarded_mode(env).map(|mode| mode.guardian)
+}
+
+/// Returns the current operating mode and who holds which role.
+pub fn get_ownership_info(env: &Env) -> OwnershipInfo {
+    let owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    let upgrade_governance = env
+        .storage()
+        .persistent()
+        .get(&OwnershipKey::UpgradeGovernance);
+    match guarded_mode(env) {
+        Some(mode) => OwnershipInfo {
+            mode: OperatingMode::Guarded,
+            owner,
+            guardian: Some(mode.guardian),
+            former_owner: Some(mode.former_owner),
+            renounced_at: Some(mode.renounced_at),
+            upgrade_governance,
+        },
+        None => OwnershipInfo {
+            mode: if owner.is_some() {
+                OperatingMode::Owned
+            } else {
+                OperatingMode::Uninitialized
+            },
+            owner,
+            guardian: None,
+            former_owner: None,
+            renounced_at: None,
+            upgrade_governance,
+        },
+    }
+}
+
+/// Adds the WASM hash governance currently approves for this contract to the
+/// upgrade allowlist. Callable by anyone, since governance has already
+/// ratified the hash.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no upgrade governance is linked
+/// * `PayrollError::Unauthorized` - governance does not approve `wasm_hash`
+/// * `PayrollError::BatchTooLarge` - [`MAX_RATIFIED_UPGRADES`] are already waiting
+pub fn ratify_upgrade(env: &Env, wasm_hash: &BytesN<32>) -> Result<(), PayrollError> {
+    let governance: Address = env
+        .storage()
+        .persistent()
+        .get(&OwnershipKey::UpgradeGovernance)
+        .ok_or(PayrollError::InvalidData)?;
+    let approved = UpgradeGovernanceClient::new(env, &governance)
+        .get_approved_upgrade(&env.current_contract_address());
+    if approved.as_ref() != Some(wasm_hash) {
+        return Err(PayrollError::Unauthorized);
+    }
+    let mut ratified = get_ratified_upgrades(env);
+    if ratified.contains(wasm_hash) {
+        return Ok(());
+    }
+    if ratified.len() >= MAX_RATIFIED_UPGRADES {
+        return Err(PayrollError::BatchTooLarge);
+    }
+    ratified.push_back(wasm_hash.clone());
+    env.storage()
+        .persistent()
+        .set(&OwnershipKey::RatifiedUpgrades, &ratified);
+    emit_upgrade_ratified(
+        env,
+        UpgradeRatifiedEvent {
+            wasm_hash: wasm_hash.clone(),
+        },
+    );
+    Ok(())
+}
+
+/// Returns the ratified WASM hashes that have not been used yet.
+pub fn get_ratified_upgrades(env: &Env) -> Vec<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&OwnershipKey::RatifiedUpgrades)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Removes `wasm_hash` from the allowlist, returning whether it was on it.
+pub(crate) fn take_ratified_upgrade(env: &Env, wasm_hash: &BytesN<32>) -> bool {
+    let mut ratified = get_ratified_upgrades(env);
+    let Some(index) = ratified.first_index_of(wasm_hash) else {
+        return false;
+    };
+    ratified.remove(index);
+    env.storage()
+        .persistent()
+        .set(&OwnershipKey::RatifiedUpgrades, &ratified);
+    true
+}
+
+/// Pauses the contract for `duration_seconds`.
+///
+/// # Errors
+/// * `PayrollError::NotGuardian` - ownership is not renounced, or caller is
+///   not the guardian
+/// * `PayrollError::InvalidData` - `duration_seconds` is zero or longer than
+///   [`GUARDIAN_MAX_PAUSE_SECONDS`]
+///
+/// # Access Control
+/// Requires guardian authentication
+pub fn guardian_pause(
+    env: &Env,
+    guardian: &Address,
+    duration_seconds: u64,
+    reason: Option<String>,
+) -> Result<(), PayrollError> {
+    require_guardian(env, guardian)?;
+    if duration_seconds > GUARDIAN_MAX_PAUSE_SECONDS {
+        return Err(PayrollError::InvalidData);
+    }
+    activate_emergency_pause(env, guardian.clone(), Some(duration_seconds), reason)
+}
+
+/// Lifts the emergency pause, whoever activated it.
+///
+/// # Errors
+/// * `PayrollError::NotGuardian` - ownership is not renounced, or caller is
+///   not the guardian
+///
+/// # Access Control
+/// Requires guardian authentication
+pub fn guardian_unpause(env: &Env, guardian: &Address) -> Result<(), PayrollError> {
+    require_guardian(env, guardian)?;
+    lift_emergency_pause(env);
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! Operations that keep running during an emergency pause.
+//!
+//! An emergency pause stops every operation that moves funds out of the
+//! contract. Pausing for an incident in one area should not freeze workers'
+//! access to pay they have already earned, so the owner can exempt whole
+//! categories of operations with `set_pause_exemptions`. Exempt operations
+//! run as if the contract were not paused; everything else still fails with
+//! `EmergencyPaused`.
+//!
+//! Exemptions only matter while a pause is in effect and survive pausing and
+//! unpausing, so they can be configured ahead of an incident.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::payroll::is_emergency_paused;
+use crate::storage::{extend_persistent_ttl, PayrollError, StorageKey};
+
+/// Category of pausable operations.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PauseCategory {
+    /// Employees claiming accrued salary: `claim_payroll`, FX and
+    /// confidential claims, and streamed withdrawals.
+    PayrollClaims,
+    /// Contributors claiming escrow periods and approved milestones.
+    EscrowClaims,
+    /// Paying approved expense reimbursements.
+    Reimbursements,
+    /// Approving and paying contributor invoices.
+    Invoices,
+    /// Total-compensation disbursements.
+    Compensation,
+    /// Extending an agreement's grace period.
+    GraceExtensions,
+    /// Migrating payrolls to another token.
+    TokenMigrations,
+    /// Relayed signed and passkey actions.
+    RelayedActions,
+    /// Moving idle escrow into yield strategies.
+    YieldDeposits,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum PauseExemptionKey {
+    /// Categories exempt from emergency pauses -> Vec<PauseCategory>
+    Exemptions,
+}
+
+/// Returns the categories that keep running during an emergency pause.
+pub fn get_pause_exemptions(env: &Env) -> Vec<PauseCategory> {
+    env.storage()
+        .persistent()
+        .get(&PauseExemptionKey::Exemptions)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Replaces the categories that keep running during an emergency pause.
+/// An empty list makes the pause stop everything again.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - `owner` is not the contract owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_pause_exemptions(
+    env: &Env,
+    owner: &Address,
+    categories: Vec<PauseCategory>,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+
+    let mut exemptions = Vec::new(env);
+    for category in categories.iter() {
+        if !exemptions.contains(category) {
+            exemptions.push_back(category);
+        }
+    }
+    let key = PauseExemptionKey::Exemptions;
+    if exemptions.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &exemptions);
+        extend_persistent_ttl(env, &key);
+    }
+    Ok(())
+}
+
+/// Whether operations of `category` are stopped: an emergency pause is in
+/// effect and the category is not exempt.
+pub fn is_paused_for(env: &Env, category: PauseCategory) -> bool {
+    is_emergency_paused(env) && !get_pause_exemptions(env).contains(category)
+}
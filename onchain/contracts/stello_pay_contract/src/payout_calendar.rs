@@ -0,0 +1,185 @@
+//! Projected payout calendar.
+//!
+//! `get_payout_calendar` lists every payout an employer's active agreements
+//! will owe in a time window: one entry per employee and period, with the
+//! moment the period's pay accrues and the earliest moment it can actually be
+//! paid under the employer's business hours and blackouts. Finance can plan
+//! cash movements from it, and the CLI converts it to iCal.
+//!
+//! The calendar is a projection from current state. Paused agreements and
+//! agreements not yet activated pay nothing until they run again and are
+//! left out, as are periods already claimed. Hourly employees are projected
+//! at their attestation cap, and confidential salaries, which are not stored
+//! in plaintext, are not projected at all.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::confidential::is_confidential;
+use crate::disbursement_windows::{load_windows, next_allowed_time};
+use crate::hourly::get_hourly_config;
+use crate::payroll::{effective_salary, get_agreement, get_employer_agreements};
+use crate::storage::{
+    Agreement, AgreementMode, AgreementStatus, DataKey, EmployeeInfo, PayrollError, StorageKey,
+};
+
+/// Most entries a single calendar query returns.
+pub const MAX_CALENDAR_ENTRIES: u32 = 100;
+
+/// A projected payout of one period to one employee.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutCalendarEntry {
+    pub agreement_id: u128,
+    pub employee: Address,
+    pub token: Address,
+    /// 1-based period of the agreement the payout settles.
+    pub period: u32,
+    /// When the period ends and its pay becomes claimable.
+    pub due_at: u64,
+    /// Earliest moment the payout can run, after business hours and blackouts.
+    pub payable_at: u64,
+    pub amount: i128,
+}
+
+/// Pay schedule of one payee of an agreement.
+#[contracttype]
+#[derive(Clone)]
+struct PayeeSchedule {
+    payee: Address,
+    activated_at: u64,
+    period_seconds: u64,
+    /// Periods already claimed.
+    claimed: u32,
+    /// Last period the agreement pays.
+    last: u32,
+    amount: i128,
+}
+
+/// Returns the payouts the employer's agreements will owe for periods ending
+/// in `[from_ts, to_ts)`, ordered by `payable_at`.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `from_ts` is not before `to_ts`
+/// * `PayrollError::BatchTooLarge` - more than [`MAX_CALENDAR_ENTRIES`]
+///   payouts fall in the window; query a shorter one
+pub fn get_payout_calendar(
+    env: &Env,
+    employer: &Address,
+    from_ts: u64,
+    to_ts: u64,
+) -> Result<Vec<PayoutCalendarEntry>, PayrollError> {
+    if from_ts >= to_ts {
+        return Err(PayrollError::InvalidData);
+    }
+    let (hours, blackouts) = load_windows(env, employer);
+    let mut calendar: Vec<PayoutCalendarEntry> = Vec::new(env);
+
+    for agreement_id in get_employer_agreements(env, employer).iter() {
+        let Some(agreement) = get_agreement(env, agreement_id) else {
+            continue;
+        };
+        if agreement.status != AgreementStatus::Active {
+            continue;
+        }
+        for schedule in payees(env, &agreement).iter() {
+            if schedule.period_seconds == 0 {
+                continue;
+            }
+            // First unclaimed period ending at or after `from_ts`.
+            let elapsed = from_ts.saturating_sub(schedule.activated_at);
+            let mut period = schedule
+                .claimed
+                .saturating_add(1)
+                .max(u32::try_from(elapsed.div_ceil(schedule.period_seconds)).unwrap_or(u32::MAX));
+            while period <= schedule.last {
+                let due_at = schedule
+                    .activated_at
+                    .saturating_add(schedule.period_seconds.saturating_mul(period.into()));
+                if due_at >= to_ts {
+                    break;
+                }
+                if calendar.len() >= MAX_CALENDAR_ENTRIES {
+                    return Err(PayrollError::BatchTooLarge);
+                }
+                let entry = PayoutCalendarEntry {
+                    agreement_id,
+                    employee: schedule.payee.clone(),
+                    token: agreement.token.clone(),
+                    period,
+                    due_at,
+                    payable_at: next_allowed_time(&hours, &blackouts, due_at),
+                    amount: schedule.amount,
+                };
+                let pos = calendar
+                    .iter()
+                    .position(|e| e.payable_at > entry.payable_at)
+                    .map_or(calendar.len(), |pos| pos as u32);
+                calendar.insert(pos, entry);
+                period += 1;
+            }
+        }
+    }
+    Ok(calendar)
+}
+
+/// Pay schedules of the payees of an active agreement.
+fn payees(env: &Env, agreement: &Agreement) -> Vec<PayeeSchedule> {
+    let mut payees = Vec::new(env);
+    match agreement.mode {
+        AgreementMode::Payroll => {
+            let (Some(activated_at), Some(period_seconds)) = (
+                DataKey::get_agreement_activation_time(env, agreement.id),
+                DataKey::get_agreement_period_duration(env, agreement.id),
+            ) else {
+                return payees;
+            };
+            let hours = get_hourly_config(env, agreement.id)
+                .map_or(1, |config| i128::from(config.max_hours_per_period));
+            let last = agreement.num_periods.unwrap_or(u32::MAX);
+            for index in 0..DataKey::get_employee_count(env, agreement.id) {
+                if is_confidential(env, agreement.id, index) {
+                    continue;
+                }
+                let Some(employee) = DataKey::get_employee(env, agreement.id, index) else {
+                    continue;
+                };
+                let Ok(salary) = effective_salary(env, agreement.id, index, &employee) else {
+                    continue;
+                };
+                payees.push_back(PayeeSchedule {
+                    payee: employee,
+                    activated_at,
+                    period_seconds,
+                    claimed: DataKey::get_employee_claimed_periods(env, agreement.id, index),
+                    last,
+                    amount: salary.saturating_mul(hours),
+                });
+            }
+        }
+        AgreementMode::Escrow => {
+            let (Some(activated_at), Some(period_seconds), Some(amount)) = (
+                agreement.activated_at,
+                agreement.period_seconds,
+                agreement.amount_per_period,
+            ) else {
+                return payees;
+            };
+            let contributor = env
+                .storage()
+                .persistent()
+                .get::<_, Vec<EmployeeInfo>>(&StorageKey::AgreementEmployees(agreement.id))
+                .and_then(|employees| employees.first());
+            if let Some(contributor) = contributor {
+                payees.push_back(PayeeSchedule {
+                    payee: contributor.address,
+                    activated_at,
+                    period_seconds,
+                    claimed: agreement.claimed_periods.unwrap_or(0),
+                    last: agreement.num_periods.unwrap_or(0),
+                    amount,
+                });
+            }
+        }
+    }
+    payees
+}
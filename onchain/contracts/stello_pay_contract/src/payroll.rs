@@ -1,77 +1,55 @@
 use soroban_sdk::token::TokenClient;
+use soroban_sdk::xdr::ToXdr;
 use soroban_sdk::{Address, Env, String, Vec};
 
+use crate::approval_matrix;
 use crate::audit::{record_entry, AuditEvent};
+use crate::credentials::{has_credential, CredentialRole};
+use crate::disbursement_windows::ensure_disbursement_allowed;
 use crate::events::{
     emit_agreement_activated, emit_agreement_cancelled, emit_agreement_created,
-    emit_agreement_paused, emit_agreement_resumed, emit_dsipute_raised, emit_dsipute_resolved,
-    emit_employee_added, emit_exchange_rate_changed, emit_grace_period_extended,
-    emit_grace_period_finalized, emit_milestone_funded, emit_milestone_rejected,
-    emit_multisig_config_changed, emit_payment_received, emit_payment_sent, emit_payroll_claimed,
-    emit_set_arbiter, AgreementActivatedEvent, AgreementCancelledEvent, AgreementCreatedEvent,
-    AgreementPausedEvent, AgreementResumedEvent, ArbiterSetEvent, BatchMilestoneClaimedEvent,
-    BatchPayrollClaimedEvent, DisputeRaisedEvent, DisputeResolvedEvent, EmployeeAddedEvent,
-    ExchangeRateChangedEvent, GracePeriodExtendedEvent, GracePeriodFinalizedEvent, MilestoneAdded,
-    MilestoneApproved, MilestoneClaimed, MilestoneFundedEvent, MilestoneRejectedEvent,
-    MultisigConfigChangedEvent, PaymentReceivedEvent, PaymentSentEvent, PayrollClaimedEvent,
+    emit_agreement_paused, emit_agreement_resumed, emit_batch_milestone_claimed,
+    emit_batch_payroll_claimed, emit_dsipute_raised, emit_dsipute_resolved, emit_employee_added,
+    emit_exchange_rate_changed, emit_grace_period_extended, emit_grace_period_finalized,
+    emit_milestone_added, emit_milestone_approved, emit_milestone_claimed, emit_milestone_funded,
+    emit_milestone_rejected, emit_multisig_config_changed, emit_payment_received,
+    emit_payment_sent, emit_payroll_claimed, emit_set_arbiter, AgreementActivatedEvent,
+    AgreementCancelledEvent, AgreementCreatedEvent, AgreementPausedEvent, AgreementResumedEvent,
+    ArbiterSetEvent, BatchMilestoneClaimedEvent, BatchPayrollClaimedEvent, DisputeRaisedEvent,
+    DisputeResolvedEvent, EmployeeAddedEvent, ExchangeRateChangedEvent, GracePeriodExtendedEvent,
+    GracePeriodFinalizedEvent, MilestoneAdded, MilestoneApproved, MilestoneClaimed,
+    MilestoneFundedEvent, MilestoneRejectedEvent, MultisigConfigChangedEvent, PaymentReceivedEvent,
+    PaymentSentEvent, PayrollClaimedEvent,
+};
+use crate::hourly::period_payout;
+use crate::metrics::{payout_timing, push_payout};
+use crate::pause_exemptions::{is_paused_for, PauseCategory};
+use crate::payslip::{settle_payout, PayoutGross};
+use crate::reputation::{record_dispute_outcome, record_payout_timing};
+use crate::sequence::{next_sequence, SequenceCategory};
+use crate::stats;
+use crate::storage::{
+    extend_instance_ttl, extend_persistent_ttl, tiered_has, tiered_remove, tiered_set,
 };
 use crate::storage::{
     Agreement, AgreementMode, AgreementStatus, BatchEscrowCreateResult, BatchMilestoneResult,
     BatchPayrollCreateResult, BatchPayrollResult, DataKey, DisputeStatus, EmployeeInfo,
     EscrowCreateParams, EscrowCreateResult, GracePeriodExtensionPolicy, Milestone,
     MilestoneClaimResult, MilestoneKey, PaymentType, PayrollClaimResult, PayrollCreateParams,
-    PayrollCreateResult, PayrollError, StorageKey, MAX_BATCH_SIZE,
+    PayrollCreateResult, PayrollError, StorageKey,
 };
+use crate::validation;
+use crate::versioned;
+use crate::webhooks::notify_payout;
+use crate::winddown;
+use crate::yield_strategy;
 use soroban_sdk::{
     auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
-    contractclient, contracttype, panic_with_error, token, IntoVal, Symbol, Val,
+    panic_with_error, token, IntoVal, Symbol, Val,
 };
-
-/// Minimal interface for cross-contract calls into the deployed multisig contract.
-#[contractclient(name = "MultisigClient")]
-trait MultisigInterface {
-    fn get_operation(env: Env, operation_id: u128) -> Option<Operation>;
-}
-
-#[contractclient(name = "RateLimiterClient")]
-trait RateLimiterInterface {
-    fn check_and_consume(env: Env, subject: Address) -> u32;
-}
-
-#[contractclient(name = "SalaryAdjustmentClient")]
-trait SalaryAdjustmentInterface {
-    fn get_employee_salary(env: Env, employee: Address) -> Option<i128>;
-}
-
-/// Mirror of multisig::OperationStatus — names must match for XDR decoding.
-#[contracttype]
-#[derive(Clone, PartialEq)]
-enum OperationStatus {
-    Pending,
-    Executed,
-    Cancelled,
-}
-
-/// Minimal mirror of multisig::Operation for cross-contract reads.
-#[contracttype]
-#[derive(Clone)]
-struct Operation {
-    pub id: u128,
-    pub kind: OperationKind,
-    pub creator: Address,
-    pub status: OperationStatus,
-    pub created_at: u64,
-    pub executed_at: Option<u64>,
-}
-
-/// Mirror of multisig::OperationKind — names must match for XDR decoding.
-#[contracttype]
-#[derive(Clone)]
-enum OperationKind {
-    ContractUpgrade(Address, soroban_sdk::BytesN<32>),
-    LargePayment(Address, Address, i128),
-    DisputeResolution(Address, u128, i128, i128),
-}
+use stellopay_interfaces::multisig::{MultisigClient, OperationKind, OperationStatus};
+use stellopay_interfaces::rate_limiter::RateLimiterClient;
+use stellopay_interfaces::salary_adjustment::SalaryAdjustmentClient;
 
 /// Configures the multisig integration for this payroll contract.
 ///
@@ -90,6 +68,8 @@ pub fn set_multisig_config(
     large_payment_threshold: i128,
     dispute_resolution_threshold: i128,
 ) -> Result<(), PayrollError> {
+    validation::max_amount(large_payment_threshold)?;
+    validation::max_amount(dispute_resolution_threshold)?;
     let stored_owner: Address = env
         .storage()
         .persistent()
@@ -203,13 +183,11 @@ fn enforce_rate_limit(env: &Env, caller: &Address) -> Result<(), PayrollError> {
 /// token during `transfer`). The guard is kept in *temporary* storage (see
 /// [`StorageKey::ReentrancyGuard`]) so it is automatically cleared at the end
 /// of the transaction even if a panic strands it mid-call.
-fn acquire_reentrancy_guard(env: &Env) -> Result<(), PayrollError> {
-    if env.storage().temporary().has(&StorageKey::ReentrancyGuard) {
+pub(crate) fn acquire_reentrancy_guard(env: &Env) -> Result<(), PayrollError> {
+    if tiered_has(env, &StorageKey::ReentrancyGuard) {
         return Err(PayrollError::ReentrancyDetected);
     }
-    env.storage()
-        .temporary()
-        .set(&StorageKey::ReentrancyGuard, &true);
+    tiered_set(env, &StorageKey::ReentrancyGuard, &true);
     Ok(())
 }
 
@@ -217,14 +195,12 @@ fn acquire_reentrancy_guard(env: &Env) -> Result<(), PayrollError> {
 ///
 /// Must be called on every return path of the guarded function so the guard
 /// never outlives a single top-level call.
-fn release_reentrancy_guard(env: &Env) {
-    env.storage()
-        .temporary()
-        .remove(&StorageKey::ReentrancyGuard);
+pub(crate) fn release_reentrancy_guard(env: &Env) {
+    tiered_remove(env, &StorageKey::ReentrancyGuard);
 }
 
 /// Fixed-point scaling factor for FX rates: 1e6 precision.
-const FX_SCALE: i128 = 1_000_000;
+pub(crate) const FX_SCALE: i128 = 1_000_000;
 
 /// Minimum converted amount (in quote-token base units) below which the
 /// conversion is treated as pure dust and rejected.
@@ -243,6 +219,9 @@ pub fn create_milestone_agreement(
     token: Address,
 ) -> u128 {
     employer.require_auth();
+    if let Err(err) = winddown::ensure_not_winding_down(&env, &employer) {
+        panic_with_error!(&env, err);
+    }
 
     let mut counter: u128 = env
         .storage()
@@ -337,6 +316,7 @@ pub fn fund_milestone_agreement(env: &Env, agreement_id: u128, from: Address, am
     from.require_auth();
 
     assert!(amount > 0, "Amount must be positive");
+    validation::require(env, validation::max_amount(amount));
 
     let status: AgreementStatus = env
         .storage()
@@ -394,6 +374,7 @@ pub fn fund_milestone_agreement(env: &Env, agreement_id: u128, from: Address, am
 /// * `PayrollError::AgreementNotFound` — the milestone agreement does not exist.
 /// * `PayrollError::MilestoneAgreementInvalidStatus` — the agreement is not in `Created` status.
 /// * `PayrollError::MilestoneAmountInvalid` — `amount` is not strictly positive.
+/// * `PayrollError::InvalidData` — `amount` is above `MAX_AMOUNT`.
 pub fn add_milestone(env: Env, agreement_id: u128, amount: i128) -> Result<(), PayrollError> {
     let status: AgreementStatus = env
         .storage()
@@ -407,6 +388,7 @@ pub fn add_milestone(env: Env, agreement_id: u128, amount: i128) -> Result<(), P
     if amount <= 0 {
         return Err(PayrollError::MilestoneAmountInvalid);
     }
+    validation::max_amount(amount)?;
 
     let employer: Address = env
         .storage()
@@ -458,12 +440,14 @@ pub fn add_milestone(env: Env, agreement_id: u128, amount: i128) -> Result<(), P
         );
     }
 
-    MilestoneAdded {
-        agreement_id,
-        milestone_id,
-        amount,
-    }
-    .publish(&env);
+    emit_milestone_added(
+        &env,
+        MilestoneAdded {
+            agreement_id,
+            milestone_id,
+            amount,
+        },
+    );
 
     Ok(())
 }
@@ -618,11 +602,13 @@ pub fn approve_milestone(
         return Err(PayrollError::InsufficientEscrowBalance);
     }
 
-    MilestoneApproved {
-        agreement_id,
-        milestone_id,
-    }
-    .publish(&env);
+    emit_milestone_approved(
+        &env,
+        MilestoneApproved {
+            agreement_id,
+            milestone_id,
+        },
+    );
 
     Ok(())
 }
@@ -649,6 +635,7 @@ pub fn approve_milestone(
 /// * `PayrollError::MilestoneAlreadyRejected`         — milestone was already rejected.
 /// * `PayrollError::MilestoneAlreadyApprovedCannotReject` — milestone is already approved.
 /// * `PayrollError::MilestoneAlreadyClaimedCannotReject`  — milestone is already claimed.
+/// * `PayrollError::InvalidData`                      — `reason` is longer than `MAX_STRING_LEN`.
 ///
 /// # Events
 /// Emits [`MilestoneRejectedEvent`] on success.
@@ -658,6 +645,7 @@ pub fn reject_milestone(
     milestone_id: u32,
     reason: String,
 ) -> Result<(), PayrollError> {
+    validation::string(&reason)?;
     // Auth: only the employer may reject a milestone.
     let employer: Address = env
         .storage()
@@ -762,7 +750,7 @@ pub fn claim_milestone(
     milestone_id: u32,
 ) -> Result<(), PayrollError> {
     // Check emergency pause
-    if is_emergency_paused(&env) {
+    if is_paused_for(&env, PauseCategory::EscrowClaims) {
         return Err(PayrollError::EmergencyPaused);
     }
 
@@ -772,6 +760,12 @@ pub fn claim_milestone(
         .get(&MilestoneKey::Contributor(agreement_id))
         .ok_or(PayrollError::AgreementNotFound)?;
     contributor.require_auth();
+    let employer: Address = env
+        .storage()
+        .persistent()
+        .get(&MilestoneKey::Employer(agreement_id))
+        .ok_or(PayrollError::AgreementNotFound)?;
+    ensure_disbursement_allowed(&env, &employer)?;
 
     // Check if agreement is paused
     let status: AgreementStatus = env
@@ -858,14 +852,18 @@ pub fn claim_milestone(
         &contributor,
         &amount,
     );
+    stats::record_disbursement(&env, &token_address, amount);
 
-    MilestoneClaimed {
-        agreement_id,
-        milestone_id,
-        amount,
-        to: contributor.clone(),
-    }
-    .publish(&env);
+    emit_milestone_claimed(
+        &env,
+        MilestoneClaimed {
+            agreement_id,
+            milestone_id,
+            amount,
+            to: contributor.clone(),
+            sequence: next_sequence(&env, SequenceCategory::Disbursement),
+        },
+    );
 
     let all_claimed = all_milestones_claimed(&env, agreement_id, count);
     if all_claimed {
@@ -920,13 +918,17 @@ pub fn batch_claim_milestones(
         .get(&MilestoneKey::Contributor(agreement_id))
         .ok_or(PayrollError::AgreementNotFound)?;
     contributor.require_auth();
+    let employer: Address = env
+        .storage()
+        .persistent()
+        .get(&MilestoneKey::Employer(agreement_id))
+        .ok_or(PayrollError::AgreementNotFound)?;
+    ensure_disbursement_allowed(env, &employer)?;
 
     if milestone_ids.is_empty() {
         return Err(PayrollError::InvalidData);
     }
-    if milestone_ids.len() > MAX_BATCH_SIZE {
-        return Err(PayrollError::BatchTooLarge);
-    }
+    validation::items(&milestone_ids)?;
 
     // Shared pre-flight
     let status: AgreementStatus = env
@@ -1064,14 +1066,16 @@ pub fn batch_claim_milestones(
         successful_claims += 1;
 
         // Event — identical to claim_milestone
-        #[allow(clippy::needless_borrow)]
-        MilestoneClaimed {
-            agreement_id,
-            milestone_id,
-            amount,
-            to: contributor.clone(),
-        }
-        .publish(&env);
+        emit_milestone_claimed(
+            env,
+            MilestoneClaimed {
+                agreement_id,
+                milestone_id,
+                amount,
+                to: contributor.clone(),
+                sequence: next_sequence(env, SequenceCategory::Disbursement),
+            },
+        );
 
         results.push_back(MilestoneClaimResult {
             milestone_id,
@@ -1081,6 +1085,8 @@ pub fn batch_claim_milestones(
         });
     }
 
+    stats::record_disbursement(env, &token, total_claimed);
+
     if all_milestones_claimed(env, agreement_id, count) {
         env.storage().persistent().set(
             &MilestoneKey::Status(agreement_id),
@@ -1088,14 +1094,15 @@ pub fn batch_claim_milestones(
         );
     }
 
-    #[allow(clippy::needless_borrow)]
-    BatchMilestoneClaimedEvent {
-        agreement_id,
-        total_claimed,
-        successful_claims,
-        failed_claims,
-    }
-    .publish(&env);
+    emit_batch_milestone_claimed(
+        env,
+        BatchMilestoneClaimedEvent {
+            agreement_id,
+            total_claimed,
+            successful_claims,
+            failed_claims,
+        },
+    );
 
     Ok(BatchMilestoneResult {
         agreement_id,
@@ -1198,16 +1205,40 @@ pub fn create_payroll_agreement(
     grace_period_seconds: u64,
 ) -> u128 {
     employer.require_auth();
-    create_payroll_agreement_internal(env, employer, token, grace_period_seconds)
+    create_payroll_agreement_internal(env, employer, token, grace_period_seconds, None)
 }
 
-fn create_payroll_agreement_internal(
+/// Creates a payroll agreement whose ID is derived from `employer` and
+/// `nonce` (see [`compute_agreement_id`]) instead of the global counter, so
+/// integrators can know the ID before submitting.
+///
+/// # Access Control
+/// Requires employer authentication
+///
+/// # Panics
+/// With `PayrollError::InvalidData` if the employer already used `nonce`.
+pub fn create_nonced_payroll_agreement(
     env: &Env,
     employer: Address,
     token: Address,
     grace_period_seconds: u64,
+    nonce: u64,
 ) -> u128 {
-    let agreement_id = get_next_agreement_id(env);
+    employer.require_auth();
+    create_payroll_agreement_internal(env, employer, token, grace_period_seconds, Some(nonce))
+}
+
+pub(crate) fn create_payroll_agreement_internal(
+    env: &Env,
+    employer: Address,
+    token: Address,
+    grace_period_seconds: u64,
+    nonce: Option<u64>,
+) -> u128 {
+    let agreement_id = match new_agreement_id(env, &employer, nonce) {
+        Ok(id) => id,
+        Err(err) => panic_with_error!(env, err),
+    };
 
     let agreement = Agreement {
         id: agreement_id,
@@ -1229,9 +1260,7 @@ fn create_payroll_agreement_internal(
         claimed_periods: None,
     };
 
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement_id), &agreement);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
 
     let employees: Vec<EmployeeInfo> = Vec::new(env);
     env.storage()
@@ -1246,6 +1275,7 @@ fn create_payroll_agreement_internal(
             agreement_id,
             employer: employer.clone(),
             mode: AgreementMode::Payroll,
+            sequence: next_sequence(env, SequenceCategory::Payroll),
         },
     );
     record_entry(
@@ -1289,9 +1319,7 @@ pub fn batch_create_payroll_agreements(
     if items.is_empty() {
         return Err(PayrollError::InvalidData);
     }
-    if items.len() > MAX_BATCH_SIZE {
-        return Err(PayrollError::BatchTooLarge);
-    }
+    validation::items(&items)?;
 
     let mut agreement_ids: Vec<u128> = Vec::new(env);
     let mut results: Vec<PayrollCreateResult> = Vec::new(env);
@@ -1304,6 +1332,7 @@ pub fn batch_create_payroll_agreements(
             employer.clone(),
             params.token.clone(),
             params.grace_period_seconds,
+            None,
         );
         agreement_ids.push_back(id);
         results.push_back(PayrollCreateResult {
@@ -1356,9 +1385,44 @@ pub fn create_escrow_agreement(
         amount_per_period,
         period_seconds,
         num_periods,
+        None,
+    )
+}
+
+/// Creates an escrow agreement whose ID is derived from `employer` and
+/// `nonce` (see [`compute_agreement_id`]) instead of the global counter.
+///
+/// # Errors
+/// Same as [`create_escrow_agreement`], plus `PayrollError::InvalidData` if
+/// the employer already used `nonce`.
+///
+/// # Access Control
+/// Requires employer authentication
+#[allow(clippy::too_many_arguments)]
+pub fn create_nonced_escrow_agreement(
+    env: &Env,
+    employer: Address,
+    contributor: Address,
+    token: Address,
+    amount_per_period: i128,
+    period_seconds: u64,
+    num_periods: u32,
+    nonce: u64,
+) -> Result<u128, PayrollError> {
+    employer.require_auth();
+    create_escrow_agreement_internal(
+        env,
+        employer,
+        contributor,
+        token,
+        amount_per_period,
+        period_seconds,
+        num_periods,
+        Some(nonce),
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_escrow_agreement_internal(
     env: &Env,
     employer: Address,
@@ -1367,19 +1431,28 @@ fn create_escrow_agreement_internal(
     amount_per_period: i128,
     period_seconds: u64,
     num_periods: u32,
+    nonce: Option<u64>,
 ) -> Result<u128, PayrollError> {
     if amount_per_period <= 0 {
         return Err(PayrollError::ZeroAmountPerPeriod);
     }
+    validation::max_amount(amount_per_period)?;
     if period_seconds == 0 {
         return Err(PayrollError::ZeroPeriodDuration);
     }
+    validation::duration(period_seconds)?;
     if num_periods == 0 {
         return Err(PayrollError::ZeroNumPeriods);
     }
 
-    let agreement_id = get_next_agreement_id(env);
-    let total_amount = amount_per_period * (num_periods as i128);
+    let total_amount = amount_per_period
+        .checked_mul(num_periods as i128)
+        .ok_or(PayrollError::InvalidData)?;
+    let total_duration = period_seconds
+        .checked_mul(num_periods as u64)
+        .ok_or(PayrollError::InvalidData)?;
+
+    let agreement_id = new_agreement_id(env, &employer, nonce)?;
 
     let agreement = Agreement {
         id: agreement_id,
@@ -1392,7 +1465,7 @@ fn create_escrow_agreement_internal(
         created_at: env.ledger().timestamp(),
         activated_at: None,
         cancelled_at: None,
-        grace_period_seconds: period_seconds * (num_periods as u64),
+        grace_period_seconds: total_duration,
         dispute_status: DisputeStatus::None,
         dispute_raised_at: None,
         amount_per_period: Some(amount_per_period),
@@ -1401,9 +1474,7 @@ fn create_escrow_agreement_internal(
         claimed_periods: Some(0),
     };
 
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement_id), &agreement);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
 
     // Add the contributor as the sole employee
     let mut employees: Vec<EmployeeInfo> = Vec::new(env);
@@ -1417,6 +1488,7 @@ fn create_escrow_agreement_internal(
         .set(&StorageKey::AgreementEmployees(agreement_id), &employees);
 
     add_to_employer_agreements(env, &employer, agreement_id);
+    add_to_employee_agreements(env, &contributor, agreement_id);
 
     emit_agreement_created(
         env,
@@ -1424,6 +1496,7 @@ fn create_escrow_agreement_internal(
             agreement_id,
             employer,
             mode: AgreementMode::Escrow,
+            sequence: next_sequence(env, SequenceCategory::Payroll),
         },
     );
 
@@ -1433,6 +1506,7 @@ fn create_escrow_agreement_internal(
             agreement_id,
             employee: contributor,
             salary_per_period: amount_per_period,
+            sequence: next_sequence(env, SequenceCategory::Payroll),
         },
     );
 
@@ -1468,9 +1542,7 @@ pub fn batch_create_escrow_agreements(
     if items.is_empty() {
         return Err(PayrollError::InvalidData);
     }
-    if items.len() > MAX_BATCH_SIZE {
-        return Err(PayrollError::BatchTooLarge);
-    }
+    validation::items(&items)?;
 
     let mut agreement_ids: Vec<u128> = Vec::new(env);
     let mut results: Vec<EscrowCreateResult> = Vec::new(env);
@@ -1486,6 +1558,7 @@ pub fn batch_create_escrow_agreements(
             params.amount_per_period,
             params.period_seconds,
             params.num_periods,
+            None,
         ) {
             Ok(id) => {
                 agreement_ids.push_back(id);
@@ -1547,6 +1620,7 @@ pub fn add_employee_to_agreement(
     );
 
     assert!(salary_per_period > 0, "Salary must be positive");
+    validation::require(env, validation::max_amount(salary_per_period));
 
     let mut employees: Vec<EmployeeInfo> = env
         .storage()
@@ -1572,12 +1646,11 @@ pub fn add_employee_to_agreement(
 
     agreement.total_amount += salary_per_period;
 
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement_id), &agreement);
-    env.storage()
-        .persistent()
-        .set(&StorageKey::AgreementEmployees(agreement_id), &employees);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
+    let employees_key = StorageKey::AgreementEmployees(agreement_id);
+    env.storage().persistent().set(&employees_key, &employees);
+    extend_persistent_ttl(env, &employees_key);
+    add_to_employee_agreements(env, &employee, agreement_id);
 
     emit_employee_added(
         env,
@@ -1585,6 +1658,7 @@ pub fn add_employee_to_agreement(
             agreement_id,
             employee,
             salary_per_period,
+            sequence: next_sequence(env, SequenceCategory::Payroll),
         },
     );
 }
@@ -1620,16 +1694,33 @@ pub fn activate_agreement(env: &Env, agreement_id: u128) {
             !employees.is_empty(),
             "Payroll agreement must have at least one employee to activate"
         );
+
+        let required_periods = crate::sla::required_prefunding_periods(env, &agreement.employer);
+        if required_periods > 0 {
+            let payroll = employees
+                .iter()
+                .fold(0i128, |sum, e| sum.saturating_add(e.salary_per_period));
+            let escrow = DataKey::get_agreement_escrow_balance(env, agreement_id, &agreement.token);
+            assert!(
+                escrow >= payroll.saturating_mul(i128::from(required_periods)),
+                "Escrow below the required pre-funding"
+            );
+        }
     }
 
     agreement.status = AgreementStatus::Active;
     agreement.activated_at = Some(env.ledger().timestamp());
 
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement_id), &agreement);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
+    stats::record_activated(env);
 
-    emit_agreement_activated(env, AgreementActivatedEvent { agreement_id });
+    emit_agreement_activated(
+        env,
+        AgreementActivatedEvent {
+            agreement_id,
+            sequence: next_sequence(env, SequenceCategory::Payroll),
+        },
+    );
     record_entry(
         env,
         agreement.employer,
@@ -1648,7 +1739,8 @@ pub fn activate_agreement(env: &Env, agreement_id: u128) {
 /// * `arbiter` - Address of the arbiter to add
 ///
 /// # Access Control
-/// Requires caller authentication
+/// Requires caller authentication. If a credential registry is linked, the
+/// arbiter must hold a valid mediator credential.
 pub fn set_arbiter(env: &Env, caller: Address, arbiter: Address) -> bool {
     caller.require_auth();
 
@@ -1664,6 +1756,12 @@ pub fn set_arbiter(env: &Env, caller: Address, arbiter: Address) -> bool {
         }
     }
 
+    // Validation: once a credential registry is linked, arbiters must hold a
+    // valid mediator credential.
+    if has_credential(env, &arbiter, CredentialRole::Mediator) == Some(false) {
+        panic_with_error!(env, PayrollError::InvalidArbiter);
+    }
+
     let arbiter_for_log = arbiter.clone();
     env.storage()
         .persistent()
@@ -1784,7 +1882,7 @@ pub fn extend_grace_period(
     additional_seconds: u64,
 ) -> Result<(), PayrollError> {
     caller.require_auth();
-    if is_emergency_paused(env) {
+    if is_paused_for(env, PauseCategory::GraceExtensions) {
         return Err(PayrollError::EmergencyPaused);
     }
     if additional_seconds == 0 {
@@ -1897,11 +1995,12 @@ pub fn raise_dispute(env: &Env, caller: Address, agreement_id: u128) -> Result<(
 
     agreement.dispute_status = DisputeStatus::Raised;
     agreement.dispute_raised_at = Some(now);
+    if agreement.status == AgreementStatus::Active {
+        stats::record_deactivated(env);
+    }
     agreement.status = AgreementStatus::Disputed;
 
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement_id), &agreement);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
 
     emit_dsipute_raised(env, DisputeRaisedEvent { agreement_id });
     record_entry(
@@ -1944,6 +2043,8 @@ pub fn resolve_dispute(
     pay_employee: i128,
     refund_employer: i128,
 ) -> Result<(), PayrollError> {
+    validation::max_amount(pay_employee)?;
+    validation::max_amount(refund_employer)?;
     // If a DisputeResolution threshold is configured and the total payout meets
     // it, reject and require the caller to use resolve_dispute_multisig instead.
     let total_payout = pay_employee + refund_employer;
@@ -1978,6 +2079,8 @@ pub fn resolve_dispute_multisig(
     refund_employer: i128,
     multisig_operation_id: u128,
 ) -> Result<(), PayrollError> {
+    validation::max_amount(pay_employee)?;
+    validation::max_amount(refund_employer)?;
     let multisig_addr = env
         .storage()
         .persistent()
@@ -2045,9 +2148,10 @@ fn resolve_dispute_core(
     if total_payout > total_locked {
         return Err(PayrollError::InvalidPayout);
     }
+    let liquid = yield_strategy::ensure_liquid(env, agreement_id, &agreement.token, total_payout);
     let escrow_balance = DataKey::get_agreement_escrow_balance(env, agreement_id, &agreement.token);
     let escrow_tracked = escrow_balance > 0;
-    if escrow_tracked && total_payout > escrow_balance {
+    if escrow_tracked && total_payout > liquid {
         return Err(PayrollError::InvalidPayout);
     }
 
@@ -2110,9 +2214,9 @@ fn resolve_dispute_core(
 
     agreement.dispute_status = DisputeStatus::Resolved;
     agreement.status = AgreementStatus::Completed;
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement_id), &agreement);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
+    unindex_agreement_employees(env, agreement_id);
+    record_dispute_outcome(env, &agreement.employer, pay_employee > 0);
 
     emit_dsipute_resolved(
         env,
@@ -2263,6 +2367,7 @@ pub fn convert_currency(
     to_token: Address,
     amount: i128,
 ) -> Result<i128, PayrollError> {
+    validation::max_amount(amount)?;
     convert_amount(env, &from_token, &to_token, amount)
 }
 
@@ -2270,29 +2375,32 @@ pub fn convert_currency(
 ///
 /// Bumps the agreement entry's TTL on read (see [`crate::storage::extend_persistent_ttl`])
 /// so an active agreement that is accessed but not rewritten for a long time is
-/// not archived under Soroban's state-archival model.
+/// not archived under Soroban's state-archival model. An agreement stored in
+/// an older schema is upgraded on read (see [`crate::versioned`]).
 ///
 /// # Returns
 /// Some(Agreement) if found, None otherwise
 pub fn get_agreement(env: &Env, agreement_id: u128) -> Option<Agreement> {
     let key = StorageKey::Agreement(agreement_id);
-    let agreement = env.storage().persistent().get(&key);
+    let agreement = versioned::load(env, &key);
     if agreement.is_some() {
-        crate::storage::extend_persistent_ttl(env, &key);
+        versioned::extend_ttl(env, &key);
     }
     agreement
 }
 
-/// Retrieves all employees for an agreement
+/// Retrieves all employees for an agreement, bumping the list's TTL
 ///
 /// # Returns
 /// Vector of employee addresses
 pub fn get_agreement_employees(env: &Env, agreement_id: u128) -> Vec<Address> {
+    let key = StorageKey::AgreementEmployees(agreement_id);
     let employees: Vec<EmployeeInfo> = env
         .storage()
         .persistent()
-        .get(&StorageKey::AgreementEmployees(agreement_id))
+        .get(&key)
         .unwrap_or(Vec::new(env));
+    extend_persistent_ttl(env, &key);
 
     let mut addresses = Vec::new(env);
     for emp in employees.iter() {
@@ -2301,6 +2409,37 @@ pub fn get_agreement_employees(env: &Env, agreement_id: u128) -> Vec<Address> {
     addresses
 }
 
+/// Retrieves the IDs of all agreements created by `employer`
+pub fn get_employer_agreements(env: &Env, employer: &Address) -> Vec<u128> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::EmployerAgreements(employer.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Retrieves the IDs of the agreements `employee` is on, across all
+/// employers, in the order they were added. An agreement leaves the list
+/// once it is completed, its cancellation is finalized or its employer's
+/// wind-down closes it.
+pub fn get_employee_agreements(env: &Env, employee: &Address) -> Vec<u128> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::EmployeeAgreements(employee.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Retrieves the IDs of the agreements through which `employer` pays
+/// `employee`
+pub fn get_employment_agreements(env: &Env, employer: &Address, employee: &Address) -> Vec<u128> {
+    let mut ids = Vec::new(env);
+    for agreement_id in get_employee_agreements(env, employee).iter() {
+        if get_agreement(env, agreement_id).is_some_and(|a| a.employer == *employer) {
+            ids.push_back(agreement_id);
+        }
+    }
+    ids
+}
+
 // -----------------------------------------------------------------------------
 // Payroll claiming (feature/payroll-claiming)
 // -----------------------------------------------------------------------------
@@ -2338,6 +2477,7 @@ pub fn claim_payroll(
     agreement_id: u128,
     employee_index: u32,
 ) -> Result<(), PayrollError> {
+    extend_instance_ttl(env);
     // Guard the entire claim against cross-contract reentrancy (e.g. a hostile
     // token re-entering during `transfer`). The guard is released on every
     // return path; a panic clears it automatically via temporary storage.
@@ -2356,7 +2496,7 @@ fn claim_payroll_inner(
     enforce_rate_limit(env, caller)?;
 
     // Check emergency pause
-    if is_emergency_paused(env) {
+    if is_paused_for(env, PauseCategory::PayrollClaims) {
         return Err(PayrollError::EmergencyPaused);
     }
 
@@ -2368,6 +2508,7 @@ fn claim_payroll_inner(
 
     // Get agreement and check status
     let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    ensure_disbursement_allowed(env, &agreement.employer)?;
 
     // Check if agreement is paused
     if agreement.status == AgreementStatus::Paused {
@@ -2401,6 +2542,11 @@ fn claim_payroll_inner(
         return Err(PayrollError::Unauthorized);
     }
 
+    // Confidential salaries are only disbursed by the employer's reveal.
+    if crate::confidential::is_confidential(env, agreement_id, employee_index) {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+
     // Get agreement activation time
     let activation_time = DataKey::get_agreement_activation_time(env, agreement_id)
         .ok_or(PayrollError::AgreementNotActivated)?;
@@ -2424,7 +2570,7 @@ fn claim_payroll_inner(
     let elapsed_time = current_time - activation_time;
 
     // Calculate total elapsed periods
-    let total_elapsed_periods = (elapsed_time / period_duration) as u32;
+    let total_elapsed_periods = elapsed_periods(elapsed_time, period_duration);
 
     // Get employee's claimed periods
     let claimed_periods = DataKey::get_employee_claimed_periods(env, agreement_id, employee_index);
@@ -2445,40 +2591,26 @@ fn claim_payroll_inner(
     let periods_to_pay = total_elapsed_periods - claimed_periods;
 
     // Get employee salary per period, checking for dynamic adjustment overrides.
-    let mut salary_per_period = DataKey::get_employee_salary(env, agreement_id, employee_index)
-        .ok_or(PayrollError::AgreementNotFound)?;
+    let salary_per_period = effective_salary(env, agreement_id, employee_index, &employee)?;
 
-    if let Some(salary_adj_addr) = env
-        .storage()
-        .persistent()
-        .get::<_, Address>(&StorageKey::SalaryAdjustmentContract)
-    {
-        let client = SalaryAdjustmentClient::new(env, &salary_adj_addr);
-        if let Some(adjusted_salary) = client.get_employee_salary(&employee) {
-            salary_per_period = adjusted_salary;
-        }
-    }
-
-    // Calculate total amount to pay
-    let amount = salary_per_period
-        .checked_mul(periods_to_pay as i128)
-        .ok_or(PayrollError::InvalidData)?;
+    // Calculate total amount to pay; hourly agreements only pay attested periods.
+    let (periods_to_pay, amount) = period_payout(
+        env,
+        agreement_id,
+        &employee,
+        salary_per_period,
+        claimed_periods,
+        periods_to_pay,
+    )?;
 
-    // If a LargePayment threshold is configured and this claim meets it,
-    // reject and require the caller to use claim_payroll_multisig instead.
-    if let Some(threshold) = env
-        .storage()
-        .persistent()
-        .get::<_, i128>(&StorageKey::LargePaymentThreshold)
-    {
-        if threshold > 0 && amount >= threshold {
-            return Err(PayrollError::MultisigApprovalRequired);
-        }
-    }
+    // Claims in an approval tier must come through claim_payroll_multisig
+    // with an operation the tier's approvers signed.
+    approval_matrix::require_approvals(env, &agreement.employer, amount)?;
 
     // Check escrow balance
+    let liquid = yield_strategy::ensure_liquid(env, agreement_id, &token, amount);
     let escrow_balance = DataKey::get_agreement_escrow_balance(env, agreement_id, &token);
-    if escrow_balance < amount {
+    if liquid < amount {
         return Err(PayrollError::InsufficientEscrowBalance);
     }
 
@@ -2496,6 +2628,16 @@ fn claim_payroll_inner(
 
     let new_claimed_periods = claimed_periods + periods_to_pay;
     DataKey::set_employee_claimed_periods(env, agreement_id, employee_index, new_claimed_periods);
+    let timing = payout_timing(
+        env,
+        activation_time,
+        period_duration,
+        claimed_periods,
+        periods_to_pay,
+    );
+    push_payout(env, &employee, &agreement.employer, &token, amount, &timing);
+    record_payout_timing(env, &agreement.employer, timing);
+    notify_payout(env, &agreement.employer, &employee, agreement_id, amount);
 
     let current_paid = DataKey::get_agreement_paid_amount(env, agreement_id);
     let new_paid = current_paid
@@ -2503,6 +2645,18 @@ fn claim_payroll_inner(
         .ok_or(PayrollError::InvalidData)?;
     DataKey::set_agreement_paid_amount(env, agreement_id, new_paid);
 
+    // Garnishments and savings are settled first; the employee gets the rest.
+    stats::record_disbursement(env, &token, amount);
+    let sequence = next_sequence(env, SequenceCategory::Disbursement);
+    let net = settle_payout(
+        env,
+        agreement_id,
+        &employee,
+        &PayoutGross::new(&token, amount),
+        new_claimed_periods,
+        sequence,
+    );
+
     // === INTERACTION: transfer tokens from escrow to employee ===
     //
     // IMPORTANT: Token `transfer(from=contract_address, ...)` requires `from.require_auth()`.
@@ -2520,14 +2674,14 @@ fn claim_payroll_inner(
                     [
                         contract_address.clone().into_val(env),
                         employee.clone().into_val(env),
-                        amount.into_val(env),
+                        net.into_val(env),
                     ],
                 ),
             },
             sub_invocations: Vec::new(env),
         })],
     ));
-    token_client.transfer(&contract_address, &employee, &amount);
+    token_client.transfer(&contract_address, &employee, &net);
 
     // Emit events
     emit_payroll_claimed(
@@ -2536,27 +2690,32 @@ fn claim_payroll_inner(
             agreement_id,
             employee: employee.clone(),
             amount,
+            sequence,
         },
     );
 
-    #[allow(clippy::needless_borrow)]
-    PaymentSentEvent {
-        agreement_id,
-        from: contract_address,
-        to: employee.clone(),
-        amount,
-        token: token.clone(),
-    }
-    .publish(&env);
+    emit_payment_sent(
+        env,
+        PaymentSentEvent {
+            agreement_id,
+            from: contract_address,
+            to: employee.clone(),
+            amount: net,
+            token: token.clone(),
+            sequence,
+        },
+    );
 
-    #[allow(clippy::needless_borrow)]
-    PaymentReceivedEvent {
-        agreement_id,
-        to: employee,
-        amount,
-        token: token.clone(),
-    }
-    .publish(&env);
+    emit_payment_received(
+        env,
+        PaymentReceivedEvent {
+            agreement_id,
+            to: employee,
+            amount: net,
+            token: token.clone(),
+            sequence,
+        },
+    );
 
     Ok(())
 }
@@ -2572,6 +2731,8 @@ fn claim_payroll_inner(
 /// # Access Control
 /// Requires employee authentication and a valid Executed multisig LargePayment operation
 /// whose `to` field matches the caller and `amount` matches the computed payout.
+/// The operation's approvers must satisfy the payout's approval tier (see
+/// [`approval_matrix::get_required_approvals`]).
 pub fn claim_payroll_multisig(
     env: &Env,
     caller: &Address,
@@ -2585,8 +2746,7 @@ pub fn claim_payroll_multisig(
         .get::<_, Address>(&StorageKey::MultisigContract)
         .ok_or(PayrollError::MultisigApprovalRequired)?;
 
-    // Temporarily clear the threshold so claim_payroll_core can proceed.
-    // We verify the multisig op here before delegating.
+    // Verify the multisig op here before delegating.
     let client = MultisigClient::new(env, &multisig_addr);
     let op = client
         .get_operation(&multisig_operation_id)
@@ -2600,21 +2760,10 @@ pub fn claim_payroll_multisig(
         _ => return Err(PayrollError::MultisigApprovalRequired),
     }
 
-    // Bypass the threshold guard by temporarily removing it, run claim, then restore.
-    let saved_threshold: Option<i128> = env
-        .storage()
-        .persistent()
-        .get(&StorageKey::LargePaymentThreshold);
-    env.storage()
-        .persistent()
-        .remove(&StorageKey::LargePaymentThreshold);
-    let result = claim_payroll(env, caller, agreement_id, employee_index);
-    if let Some(t) = saved_threshold {
-        env.storage()
-            .persistent()
-            .set(&StorageKey::LargePaymentThreshold, &t);
-    }
-    result
+    // The claim checks the operation's approvers against the payout's tier.
+    approval_matrix::with_approved_operation(env, multisig_operation_id, || {
+        claim_payroll(env, caller, agreement_id, employee_index)
+    })
 }
 
 /// Claims payroll for an employee but settles the payout in a caller-specified
@@ -2632,6 +2781,7 @@ pub fn claim_payroll_in_token(
     employee_index: u32,
     payout_token: Address,
 ) -> Result<(), PayrollError> {
+    extend_instance_ttl(env);
     // Reentrancy guard mirrors `claim_payroll`; released on every return path.
     acquire_reentrancy_guard(env)?;
     let result =
@@ -2657,6 +2807,7 @@ fn claim_payroll_in_token_inner(
 
     // Get agreement and check status
     let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    ensure_disbursement_allowed(env, &agreement.employer)?;
 
     // Check if agreement is paused
     if agreement.status == AgreementStatus::Paused {
@@ -2690,6 +2841,11 @@ fn claim_payroll_in_token_inner(
         return Err(PayrollError::Unauthorized);
     }
 
+    // Confidential salaries are only disbursed by the employer's reveal.
+    if crate::confidential::is_confidential(env, agreement_id, employee_index) {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+
     // Get agreement activation time
     let activation_time = DataKey::get_agreement_activation_time(env, agreement_id)
         .ok_or(PayrollError::AgreementNotActivated)?;
@@ -2720,7 +2876,7 @@ fn claim_payroll_in_token_inner(
     let elapsed_time = current_time - activation_time;
 
     // Calculate total elapsed periods
-    let total_elapsed_periods = (elapsed_time / period_duration) as u32;
+    let total_elapsed_periods = elapsed_periods(elapsed_time, period_duration);
 
     // Get employee's claimed periods
     let claimed_periods = DataKey::get_employee_claimed_periods(env, agreement_id, employee_index);
@@ -2737,17 +2893,27 @@ fn claim_payroll_in_token_inner(
         .ok_or(PayrollError::AgreementNotFound)?;
 
     // Calculate total amount to pay in base currency
-    let amount_base = salary_per_period
-        .checked_mul(periods_to_pay as i128)
-        .ok_or(PayrollError::InvalidData)?;
+    let (periods_to_pay, amount_base) = period_payout(
+        env,
+        agreement_id,
+        &employee,
+        salary_per_period,
+        claimed_periods,
+        periods_to_pay,
+    )?;
+
+    // Tiers are in the base currency; a tiered claim must come through
+    // claim_payroll_multisig in the base token.
+    approval_matrix::require_approvals(env, &agreement.employer, amount_base)?;
 
     // Convert to payout currency using configured FX rate.
     let amount_payout = convert_amount(env, &base_token, &payout_token, amount_base)?;
 
     // Check escrow balance for payout token
+    let liquid = yield_strategy::ensure_liquid(env, agreement_id, &payout_token, amount_payout);
     let escrow_balance_payout =
         DataKey::get_agreement_escrow_balance(env, agreement_id, &payout_token);
-    if escrow_balance_payout < amount_payout {
+    if liquid < amount_payout {
         return Err(PayrollError::InsufficientEscrowBalance);
     }
 
@@ -2762,6 +2928,29 @@ fn claim_payroll_in_token_inner(
 
     let new_claimed_periods = claimed_periods + periods_to_pay;
     DataKey::set_employee_claimed_periods(env, agreement_id, employee_index, new_claimed_periods);
+    let timing = payout_timing(
+        env,
+        activation_time,
+        period_duration,
+        claimed_periods,
+        periods_to_pay,
+    );
+    push_payout(
+        env,
+        &employee,
+        &agreement.employer,
+        &base_token,
+        amount_base,
+        &timing,
+    );
+    record_payout_timing(env, &agreement.employer, timing);
+    notify_payout(
+        env,
+        &agreement.employer,
+        &employee,
+        agreement_id,
+        amount_base,
+    );
 
     let current_paid = DataKey::get_agreement_paid_amount(env, agreement_id);
     let new_paid = current_paid
@@ -2769,6 +2958,18 @@ fn claim_payroll_in_token_inner(
         .ok_or(PayrollError::InvalidData)?;
     DataKey::set_agreement_paid_amount(env, agreement_id, new_paid);
 
+    // Garnishments and savings are settled first; the employee gets the rest.
+    stats::record_disbursement(env, &payout_token, amount_payout);
+    let sequence = next_sequence(env, SequenceCategory::Disbursement);
+    let net = settle_payout(
+        env,
+        agreement_id,
+        &employee,
+        &PayoutGross::converted(&payout_token, amount_payout, &base_token, amount_base),
+        new_claimed_periods,
+        sequence,
+    );
+
     // === INTERACTION: transfer tokens from escrow to employee in payout currency ===
     //
     // Token `transfer(from=contract_address, ...)` requires `from.require_auth()`.
@@ -2785,14 +2986,14 @@ fn claim_payroll_in_token_inner(
                     [
                         contract_address.clone().into_val(env),
                         employee.clone().into_val(env),
-                        amount_payout.into_val(env),
+                        net.into_val(env),
                     ],
                 ),
             },
             sub_invocations: Vec::new(env),
         })],
     ));
-    token_client.transfer(&contract_address, &employee, &amount_payout);
+    token_client.transfer(&contract_address, &employee, &net);
 
     // Emit events: `PayrollClaimed` remains in base currency units, while the
     // payment events reflect the actual payout asset and amount.
@@ -2802,27 +3003,32 @@ fn claim_payroll_in_token_inner(
             agreement_id,
             employee: employee.clone(),
             amount: amount_base,
+            sequence,
         },
     );
 
-    #[allow(clippy::needless_borrow)]
-    PaymentSentEvent {
-        agreement_id,
-        from: contract_address,
-        to: employee.clone(),
-        amount: amount_payout,
-        token: payout_token.clone(),
-    }
-    .publish(&env);
+    emit_payment_sent(
+        env,
+        PaymentSentEvent {
+            agreement_id,
+            from: contract_address,
+            to: employee.clone(),
+            amount: net,
+            token: payout_token.clone(),
+            sequence,
+        },
+    );
 
-    #[allow(clippy::needless_borrow)]
-    PaymentReceivedEvent {
-        agreement_id,
-        to: employee,
-        amount: amount_payout,
-        token: payout_token,
-    }
-    .publish(&env);
+    emit_payment_received(
+        env,
+        PaymentReceivedEvent {
+            agreement_id,
+            to: employee,
+            amount: net,
+            token: payout_token,
+            sequence,
+        },
+    );
 
     Ok(())
 }
@@ -2869,6 +3075,7 @@ pub fn batch_claim_payroll(
     agreement_id: u128,
     employee_indices: Vec<u32>,
 ) -> Result<BatchPayrollResult, PayrollError> {
+    extend_instance_ttl(env);
     // Reentrancy guard covers the whole batch (each per-employee transfer is a
     // potential reentry point); released on every return path.
     acquire_reentrancy_guard(env)?;
@@ -2892,11 +3099,10 @@ fn batch_claim_payroll_inner(
     if employee_indices.is_empty() {
         return Err(PayrollError::InvalidData);
     }
-    if employee_indices.len() > MAX_BATCH_SIZE {
-        return Err(PayrollError::BatchTooLarge);
-    }
+    validation::items(&employee_indices)?;
 
     let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    ensure_disbursement_allowed(env, &agreement.employer)?;
 
     if agreement.mode != AgreementMode::Payroll {
         return Err(PayrollError::InvalidAgreementMode);
@@ -2928,9 +3134,12 @@ fn batch_claim_payroll_inner(
         return Err(PayrollError::InvalidData);
     }
 
-    let total_elapsed_periods = ((current_time - activation_time) / period_duration) as u32;
+    let total_elapsed_periods = elapsed_periods(current_time - activation_time, period_duration);
 
-    // Load escrow balance once; update in-memory, write back once at the end
+    // Load escrow balance once; update in-memory, write back once at the end.
+    // The batch total is not known up front, so a strategy position is closed;
+    // claims are checked against the escrow held here.
+    let mut liquid = yield_strategy::ensure_all_liquid(env, agreement_id, &token);
     let mut escrow_balance = DataKey::get_agreement_escrow_balance(env, agreement_id, &token);
 
     let token_client = token::Client::new(env, &token);
@@ -3047,23 +3256,42 @@ fn batch_claim_payroll_inner(
             }
         }
 
-        // Overflow-safe amount
-        let amount = match salary_per_period.checked_mul(periods_to_pay as i128) {
-            Some(a) => a,
-            None => {
+        // Overflow-safe amount; hourly agreements only pay attested periods.
+        let (periods_to_pay, amount) = match period_payout(
+            env,
+            agreement_id,
+            &employee,
+            salary_per_period,
+            claimed_periods,
+            periods_to_pay,
+        ) {
+            Ok(payout) => payout,
+            Err(err) => {
                 failed_claims += 1;
                 results.push_back(PayrollClaimResult {
                     employee_index,
                     success: false,
                     amount_claimed: 0,
-                    error_code: PayrollError::InvalidData as u32,
+                    error_code: err as u32,
                 });
                 continue;
             }
         };
 
+        // Claims in an approval tier must come through claim_payroll_multisig.
+        if let Err(err) = approval_matrix::require_approvals(env, &agreement.employer, amount) {
+            failed_claims += 1;
+            results.push_back(PayrollClaimResult {
+                employee_index,
+                success: false,
+                amount_claimed: 0,
+                error_code: err as u32,
+            });
+            continue;
+        }
+
         // Check in-memory escrow
-        if escrow_balance < amount {
+        if liquid < amount {
             failed_claims += 1;
             results.push_back(PayrollClaimResult {
                 employee_index,
@@ -3080,6 +3308,7 @@ fn batch_claim_payroll_inner(
         // so a hostile token cannot re-enter and observe stale per-employee
         // state. The transaction-level reentrancy guard is the primary defense.
         escrow_balance -= amount;
+        liquid -= amount;
         total_claimed += amount;
         successful_claims += 1;
 
@@ -3089,12 +3318,34 @@ fn batch_claim_payroll_inner(
             employee_index,
             claimed_periods + periods_to_pay,
         );
+        let timing = payout_timing(
+            env,
+            activation_time,
+            period_duration,
+            claimed_periods,
+            periods_to_pay,
+        );
+        push_payout(env, &employee, &agreement.employer, &token, amount, &timing);
+        record_payout_timing(env, &agreement.employer, timing);
+        notify_payout(env, &agreement.employer, &employee, agreement_id, amount);
 
         let new_paid = DataKey::get_agreement_paid_amount(env, agreement_id)
             .checked_add(amount)
             .unwrap_or(DataKey::get_agreement_paid_amount(env, agreement_id));
         DataKey::set_agreement_paid_amount(env, agreement_id, new_paid);
 
+        // Garnishments and savings are settled first; the employee gets the rest.
+        stats::record_disbursement(env, &token, amount);
+        let sequence = next_sequence(env, SequenceCategory::Disbursement);
+        let net = settle_payout(
+            env,
+            agreement_id,
+            &employee,
+            &PayoutGross::new(&token, amount),
+            claimed_periods + periods_to_pay,
+            sequence,
+        );
+
         // === INTERACTION: transfer tokens from escrow to employee ===
         env.authorize_as_current_contract(Vec::from_array(
             env,
@@ -3107,14 +3358,14 @@ fn batch_claim_payroll_inner(
                         [
                             contract_address.clone().into_val(env),
                             employee.clone().into_val(env),
-                            amount.into_val(env),
+                            net.into_val(env),
                         ],
                     ),
                 },
                 sub_invocations: Vec::new(env),
             })],
         ));
-        token_client.transfer(&contract_address, &employee, &amount);
+        token_client.transfer(&contract_address, &employee, &net);
 
         // Events — identical to claim_payroll
         emit_payroll_claimed(
@@ -3123,25 +3374,30 @@ fn batch_claim_payroll_inner(
                 agreement_id,
                 employee: employee.clone(),
                 amount,
+                sequence,
+            },
+        );
+        emit_payment_sent(
+            env,
+            PaymentSentEvent {
+                agreement_id,
+                from: contract_address.clone(),
+                to: employee.clone(),
+                amount: net,
+                token: token.clone(),
+                sequence,
+            },
+        );
+        emit_payment_received(
+            env,
+            PaymentReceivedEvent {
+                agreement_id,
+                to: employee.clone(),
+                amount: net,
+                token: token.clone(),
+                sequence,
             },
         );
-        #[allow(clippy::needless_borrow)]
-        PaymentSentEvent {
-            agreement_id,
-            from: contract_address.clone(),
-            to: employee.clone(),
-            amount,
-            token: token.clone(),
-        }
-        .publish(&env);
-        #[allow(clippy::needless_borrow)]
-        PaymentReceivedEvent {
-            agreement_id,
-            to: employee.clone(),
-            amount,
-            token: token.clone(),
-        }
-        .publish(&env);
 
         results.push_back(PayrollClaimResult {
             employee_index,
@@ -3153,14 +3409,15 @@ fn batch_claim_payroll_inner(
 
     DataKey::set_agreement_escrow_balance(env, agreement_id, &token, escrow_balance);
 
-    #[allow(clippy::needless_borrow)]
-    BatchPayrollClaimedEvent {
-        agreement_id,
-        total_claimed,
-        successful_claims,
-        failed_claims,
-    }
-    .publish(&env);
+    emit_batch_payroll_claimed(
+        env,
+        BatchPayrollClaimedEvent {
+            agreement_id,
+            total_claimed,
+            successful_claims,
+            failed_claims,
+        },
+    );
 
     Ok(BatchPayrollResult {
         agreement_id,
@@ -3203,12 +3460,15 @@ pub fn get_employee_claimed_periods(env: &Env, agreement_id: u128, employee_inde
 /// - Cannot claim more than total periods
 /// - Works during grace period
 pub fn claim_time_based(env: &Env, agreement_id: u128) -> Result<(), PayrollError> {
+    extend_instance_ttl(env);
+
     // Check emergency pause
-    if is_emergency_paused(env) {
+    if is_paused_for(env, PauseCategory::EscrowClaims) {
         return Err(PayrollError::EmergencyPaused);
     }
 
     let mut agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    ensure_disbursement_allowed(env, &agreement.employer)?;
 
     // Check agreement mode
     if agreement.mode != AgreementMode::Escrow {
@@ -3276,7 +3536,7 @@ pub fn claim_time_based(env: &Env, agreement_id: u128) -> Result<(), PayrollErro
 
     let current_time = env.ledger().timestamp();
     let elapsed_seconds = current_time - activated_at;
-    let periods_elapsed = (elapsed_seconds / period_seconds) as u32;
+    let periods_elapsed = elapsed_periods(elapsed_seconds, period_seconds);
 
     let periods_to_pay = if periods_elapsed > num_periods {
         num_periods - claimed_periods
@@ -3293,8 +3553,9 @@ pub fn claim_time_based(env: &Env, agreement_id: u128) -> Result<(), PayrollErro
         .ok_or(PayrollError::InvalidData)?;
 
     // Check escrow balance
+    let liquid = yield_strategy::ensure_liquid(env, agreement_id, &agreement.token, amount);
     let escrow_balance = DataKey::get_agreement_escrow_balance(env, agreement_id, &agreement.token);
-    if escrow_balance < amount {
+    if liquid < amount {
         return Err(PayrollError::InsufficientEscrowBalance);
     }
 
@@ -3322,6 +3583,7 @@ pub fn claim_time_based(env: &Env, agreement_id: u128) -> Result<(), PayrollErro
         })],
     ));
     token_client.transfer(&contract_address, &contributor, &amount);
+    stats::record_disbursement(env, &agreement.token, amount);
 
     // Update escrow balance
     let new_escrow_balance = escrow_balance - amount;
@@ -3337,13 +3599,16 @@ pub fn claim_time_based(env: &Env, agreement_id: u128) -> Result<(), PayrollErro
     DataKey::set_agreement_paid_amount(env, agreement_id, agreement.paid_amount);
 
     if claimed_periods >= num_periods {
+        if agreement.status == AgreementStatus::Active {
+            stats::record_deactivated(env);
+        }
         agreement.status = AgreementStatus::Completed;
+        unindex_agreement_employees(env, agreement_id);
     }
 
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement_id), &agreement);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
 
+    let sequence = next_sequence(env, SequenceCategory::Disbursement);
     emit_payment_sent(
         env,
         PaymentSentEvent {
@@ -3352,6 +3617,7 @@ pub fn claim_time_based(env: &Env, agreement_id: u128) -> Result<(), PayrollErro
             to: contributor.clone(),
             amount,
             token: agreement.token.clone(),
+            sequence,
         },
     );
 
@@ -3362,6 +3628,7 @@ pub fn claim_time_based(env: &Env, agreement_id: u128) -> Result<(), PayrollErro
             to: contributor,
             amount,
             token: agreement.token,
+            sequence,
         },
     );
 
@@ -3388,10 +3655,77 @@ pub fn get_claimed_periods(env: &Env, agreement_id: u128) -> u32 {
 // Helpers
 // -----------------------------------------------------------------------------
 
+/// Transfers `amount` of `token` held by this contract to `to`.
+///
+/// Token `transfer(from=contract_address, ...)` requires `from.require_auth()`,
+/// so the nested invocation is pre-authorized via `authorize_as_current_contract`
+/// exactly as in the claim and refund paths.
+pub(crate) fn transfer_from_contract(env: &Env, token: &Address, to: &Address, amount: i128) {
+    let contract_address = env.current_contract_address();
+    env.authorize_as_current_contract(Vec::from_array(
+        env,
+        [InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: token.clone(),
+                fn_name: Symbol::new(env, "transfer"),
+                args: Vec::<Val>::from_array(
+                    env,
+                    [
+                        contract_address.clone().into_val(env),
+                        to.clone().into_val(env),
+                        amount.into_val(env),
+                    ],
+                ),
+            },
+            sub_invocations: Vec::new(env),
+        })],
+    ));
+    token::Client::new(env, token).transfer(&contract_address, to, &amount);
+}
+
+/// Derives the ID of an agreement created by `employer` with `nonce`.
+///
+/// The ID is the first 16 bytes, read big-endian, of
+/// `sha256(xdr(employer) || nonce as 8 big-endian bytes)`, with the top bit
+/// set. Counter-assigned IDs never reach 2^127, so the two never collide.
+pub fn compute_agreement_id(env: &Env, employer: &Address, nonce: u64) -> u128 {
+    let mut preimage = employer.clone().to_xdr(env);
+    preimage.extend_from_array(&nonce.to_be_bytes());
+    let digest = env.crypto().sha256(&preimage).to_array();
+    let mut id_bytes = [0u8; 16];
+    id_bytes.copy_from_slice(&digest[..16]);
+    u128::from_be_bytes(id_bytes) | (1 << 127)
+}
+
+/// Assigns the ID for a new agreement: derived from `nonce` when given,
+/// otherwise the next counter value.
+fn new_agreement_id(
+    env: &Env,
+    employer: &Address,
+    nonce: Option<u64>,
+) -> Result<u128, PayrollError> {
+    winddown::ensure_not_winding_down(env, employer)?;
+    let Some(nonce) = nonce else {
+        return Ok(get_next_agreement_id(env));
+    };
+    let agreement_id = compute_agreement_id(env, employer, nonce);
+    if env
+        .storage()
+        .persistent()
+        .has(&StorageKey::Agreement(agreement_id))
+    {
+        return Err(PayrollError::InvalidData);
+    }
+    versioned::record_derived_agreement(env, agreement_id);
+    extend_instance_ttl(env);
+    Ok(agreement_id)
+}
+
 fn get_next_agreement_id(env: &Env) -> u128 {
     let key = StorageKey::NextAgreementId;
     let id: u128 = env.storage().persistent().get(&key).unwrap_or(1);
     env.storage().persistent().set(&key, &(id + 1));
+    extend_instance_ttl(env);
     id
 }
 
@@ -3400,7 +3734,7 @@ fn get_next_agreement_id(env: &Env) -> u128 {
 ///
 /// The rate is interpreted as `quote_per_base * FX_SCALE`, where `from_token`
 /// is the base and `to_token` is the quote.
-fn convert_amount(
+pub(crate) fn convert_amount(
     env: &Env,
     from_token: &Address,
     to_token: &Address,
@@ -3468,10 +3802,13 @@ fn convert_amount(
 /// - Agreement state is preserved
 /// - Can be resumed later or cancelled
 pub fn pause_agreement(env: &Env, agreement_id: u128) {
-    let mut agreement = get_agreement(env, agreement_id).expect("Agreement not found");
-
+    let agreement = get_agreement(env, agreement_id).expect("Agreement not found");
     agreement.employer.require_auth();
+    pause_agreement_inner(env, agreement_id, agreement);
+}
 
+/// Pauses `agreement` once the caller is authorized.
+pub(crate) fn pause_agreement_inner(env: &Env, agreement_id: u128, mut agreement: Agreement) {
     assert!(
         agreement.status == AgreementStatus::Active,
         "Can only pause Active agreements"
@@ -3479,11 +3816,16 @@ pub fn pause_agreement(env: &Env, agreement_id: u128) {
 
     agreement.status = AgreementStatus::Paused;
 
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement_id), &agreement);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
+    stats::record_deactivated(env);
 
-    emit_agreement_paused(env, AgreementPausedEvent { agreement_id });
+    emit_agreement_paused(
+        env,
+        AgreementPausedEvent {
+            agreement_id,
+            sequence: next_sequence(env, SequenceCategory::Payroll),
+        },
+    );
 }
 
 /// Resumes a paused agreement, allowing claims again
@@ -3507,10 +3849,13 @@ pub fn pause_agreement(env: &Env, agreement_id: u128) {
 /// - Claims can be processed again
 /// - All agreement data is preserved
 pub fn resume_agreement(env: &Env, agreement_id: u128) {
-    let mut agreement = get_agreement(env, agreement_id).expect("Agreement not found");
-
+    let agreement = get_agreement(env, agreement_id).expect("Agreement not found");
     agreement.employer.require_auth();
+    resume_agreement_inner(env, agreement_id, agreement);
+}
 
+/// Resumes `agreement` once the caller is authorized.
+pub(crate) fn resume_agreement_inner(env: &Env, agreement_id: u128, mut agreement: Agreement) {
     assert!(
         agreement.status == AgreementStatus::Paused,
         "Can only resume Paused agreements"
@@ -3518,11 +3863,16 @@ pub fn resume_agreement(env: &Env, agreement_id: u128) {
 
     agreement.status = AgreementStatus::Active;
 
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement_id), &agreement);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
+    stats::record_activated(env);
 
-    emit_agreement_resumed(env, AgreementResumedEvent { agreement_id });
+    emit_agreement_resumed(
+        env,
+        AgreementResumedEvent {
+            agreement_id,
+            sequence: next_sequence(env, SequenceCategory::Payroll),
+        },
+    );
 }
 
 /// Pauses a milestone-based agreement, preventing claims
@@ -3575,7 +3925,13 @@ pub fn pause_milestone_agreement(env: Env, agreement_id: u128) -> Result<(), Pay
         &AgreementStatus::Paused,
     );
 
-    AgreementPausedEvent { agreement_id }.publish(&env);
+    emit_agreement_paused(
+        &env,
+        AgreementPausedEvent {
+            agreement_id,
+            sequence: next_sequence(&env, SequenceCategory::Payroll),
+        },
+    );
 
     Ok(())
 }
@@ -3630,11 +3986,51 @@ pub fn resume_milestone_agreement(env: Env, agreement_id: u128) -> Result<(), Pa
         &AgreementStatus::Active,
     );
 
-    AgreementResumedEvent { agreement_id }.publish(&env);
+    emit_agreement_resumed(
+        &env,
+        AgreementResumedEvent {
+            agreement_id,
+            sequence: next_sequence(&env, SequenceCategory::Payroll),
+        },
+    );
 
     Ok(())
 }
 
+/// Returns the salary per period paid to `employee` at `employee_index`,
+/// preferring the salary adjustment contract's override when one is linked.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - no salary is indexed for the slot
+pub(crate) fn effective_salary(
+    env: &Env,
+    agreement_id: u128,
+    employee_index: u32,
+    employee: &Address,
+) -> Result<i128, PayrollError> {
+    let salary = DataKey::get_employee_salary(env, agreement_id, employee_index)
+        .ok_or(PayrollError::AgreementNotFound)?;
+    if let Some(salary_adj_addr) = env
+        .storage()
+        .persistent()
+        .get::<_, Address>(&StorageKey::SalaryAdjustmentContract)
+    {
+        let client = SalaryAdjustmentClient::new(env, &salary_adj_addr);
+        if let Some(adjusted_salary) = client.get_employee_salary(employee) {
+            return Ok(adjusted_salary);
+        }
+    }
+    Ok(salary)
+}
+
+/// Whole periods elapsed in `elapsed_seconds`, saturating at `u32::MAX`.
+///
+/// Period counters are `u32`; a plain `as u32` cast would wrap for very short
+/// periods over long spans and make already-claimed periods look unclaimed.
+pub(crate) fn elapsed_periods(elapsed_seconds: u64, period_seconds: u64) -> u32 {
+    u32::try_from(elapsed_seconds / period_seconds).unwrap_or(u32::MAX)
+}
+
 fn add_to_employer_agreements(env: &Env, employer: &Address, agreement_id: u128) {
     let key = StorageKey::EmployerAgreements(employer.clone());
     let mut agreements: Vec<u128> = env
@@ -3642,8 +4038,74 @@ fn add_to_employer_agreements(env: &Env, employer: &Address, agreement_id: u128)
         .persistent()
         .get(&key)
         .unwrap_or(Vec::new(env));
+    if agreements.is_empty() {
+        stats::record_employer(env);
+    }
+    agreements.push_back(agreement_id);
+    env.storage().persistent().set(&key, &agreements);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Adds the agreement to the employee's index. Returns false if it was
+/// already there.
+fn add_to_employee_agreements(env: &Env, employee: &Address, agreement_id: u128) -> bool {
+    let key = StorageKey::EmployeeAgreements(employee.clone());
+    let mut agreements: Vec<u128> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    if agreements.contains(agreement_id) {
+        return false;
+    }
     agreements.push_back(agreement_id);
     env.storage().persistent().set(&key, &agreements);
+    extend_persistent_ttl(env, &key);
+    true
+}
+
+fn remove_from_employee_agreements(env: &Env, employee: &Address, agreement_id: u128) {
+    let key = StorageKey::EmployeeAgreements(employee.clone());
+    let Some(mut agreements) = env.storage().persistent().get::<_, Vec<u128>>(&key) else {
+        return;
+    };
+    if let Some(index) = agreements.first_index_of(agreement_id) {
+        agreements.remove(index);
+    }
+    if agreements.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &agreements);
+    }
+}
+
+/// Adds an agreement that is not yet settled to each of its employees'
+/// indexes. Returns whether any index changed. Backfills agreements created
+/// before the index existed.
+pub(crate) fn index_agreement_employees(env: &Env, agreement_id: u128) -> bool {
+    let Some(agreement) = get_agreement(env, agreement_id) else {
+        return false;
+    };
+    let settled = match agreement.status {
+        AgreementStatus::Completed => true,
+        AgreementStatus::Cancelled => !is_grace_period_active(env, agreement_id),
+        _ => false,
+    };
+    if settled {
+        return false;
+    }
+    let mut changed = false;
+    for employee in get_agreement_employees(env, agreement_id).iter() {
+        changed |= add_to_employee_agreements(env, &employee, agreement_id);
+    }
+    changed
+}
+
+/// Removes a settled agreement from its employees' indexes.
+pub(crate) fn unindex_agreement_employees(env: &Env, agreement_id: u128) {
+    for employee in get_agreement_employees(env, agreement_id).iter() {
+        remove_from_employee_agreements(env, &employee, agreement_id);
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -3668,23 +4130,33 @@ fn add_to_employer_agreements(env: &Env, employer: &Address, agreement_id: u128)
 /// - Claims are allowed during grace period
 /// - Refunds are prevented until grace period expires
 pub fn cancel_agreement(env: &Env, agreement_id: u128) {
-    let mut agreement = get_agreement(env, agreement_id).expect("Agreement not found");
-
+    let agreement = get_agreement(env, agreement_id).expect("Agreement not found");
     agreement.employer.require_auth();
+    cancel_agreement_inner(env, agreement_id, agreement);
+}
 
+/// Cancels `agreement` once the caller is authorized.
+pub(crate) fn cancel_agreement_inner(env: &Env, agreement_id: u128, mut agreement: Agreement) {
     assert!(
         agreement.status == AgreementStatus::Active || agreement.status == AgreementStatus::Created,
         "Can only cancel Active or Created agreements"
     );
 
+    if agreement.status == AgreementStatus::Active {
+        stats::record_deactivated(env);
+    }
     agreement.status = AgreementStatus::Cancelled;
     agreement.cancelled_at = Some(env.ledger().timestamp());
 
-    env.storage()
-        .persistent()
-        .set(&StorageKey::Agreement(agreement_id), &agreement);
+    versioned::save(env, &StorageKey::Agreement(agreement_id), &agreement);
 
-    emit_agreement_cancelled(env, AgreementCancelledEvent { agreement_id });
+    emit_agreement_cancelled(
+        env,
+        AgreementCancelledEvent {
+            agreement_id,
+            sequence: next_sequence(env, SequenceCategory::Payroll),
+        },
+    );
     record_entry(
         env,
         agreement.employer,
@@ -3740,7 +4212,7 @@ pub fn finalize_grace_period(env: &Env, agreement_id: u128) {
 
     // Refund remaining balance using escrow contract if available
     // For now, we'll use the existing escrow balance tracking
-    let escrow_balance = DataKey::get_agreement_escrow_balance(env, agreement_id, &agreement.token);
+    let escrow_balance = yield_strategy::release_escrow(env, agreement_id, &agreement.token);
 
     if escrow_balance > 0 {
         // Token `transfer(from=contract_address, ...)` requires contract auth.
@@ -3770,8 +4242,15 @@ pub fn finalize_grace_period(env: &Env, agreement_id: u128) {
         // Clear escrow balance
         DataKey::set_agreement_escrow_balance(env, agreement_id, &agreement.token, 0);
     }
+    unindex_agreement_employees(env, agreement_id);
 
-    emit_grace_period_finalized(env, GracePeriodFinalizedEvent { agreement_id });
+    emit_grace_period_finalized(
+        env,
+        GracePeriodFinalizedEvent {
+            agreement_id,
+            sequence: next_sequence(env, SequenceCategory::Payroll),
+        },
+    );
 }
 
 /// Checks if the grace period is currently active for a cancelled agreement.
@@ -3844,10 +4323,25 @@ pub fn is_emergency_paused(env: &Env) -> bool {
     env.storage()
         .persistent()
         .get::<StorageKey, crate::storage::EmergencyPause>(&StorageKey::EmergencyPause)
-        .map(|p| p.is_paused)
+        .map(|p| pause_in_effect(env, &p))
         .unwrap_or(false)
 }
 
+/// A pause with a `resume_at` in the past has lapsed, even if the stored
+/// record still says `is_paused` (nobody has interacted since it expired).
+fn pause_in_effect(env: &Env, pause: &crate::storage::EmergencyPause) -> bool {
+    pause.is_paused
+        && get_emergency_pause_terms(env)
+            .and_then(|terms| terms.resume_at)
+            .map_or(true, |resume_at| env.ledger().timestamp() < resume_at)
+}
+
+fn get_emergency_pause_terms(env: &Env) -> Option<crate::storage::EmergencyPauseTerms> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::EmergencyPauseTerms)
+}
+
 /// Adds emergency guardians (multi-sig addresses)
 ///
 /// # Arguments
@@ -3859,6 +4353,7 @@ pub fn is_emergency_paused(env: &Env) -> bool {
 pub fn set_emergency_guardians(env: &Env, guardians: Vec<Address>) {
     let owner: Address = env.storage().persistent().get(&StorageKey::Owner).unwrap();
     owner.require_auth();
+    validation::require(env, validation::items(&guardians));
     env.storage()
         .persistent()
         .set(&StorageKey::EmergencyGuardians, &guardians);
@@ -3878,6 +4373,10 @@ pub fn get_emergency_guardians(env: &Env) -> Option<Vec<Address>> {
 /// * `caller` - Guardian proposing the pause
 /// * `timelock_seconds` - Delay before pause activates (0 for immediate)
 ///
+/// # Errors
+/// * `PayrollError::NotGuardian` - caller is not an emergency guardian
+/// * `PayrollError::InvalidData` - `timelock_seconds` exceeds `MAX_DURATION_SECONDS`
+///
 /// # Access Control
 /// Requires guardian authentication
 pub fn propose_emergency_pause(
@@ -3886,6 +4385,7 @@ pub fn propose_emergency_pause(
     timelock_seconds: u64,
 ) -> Result<(), PayrollError> {
     caller.require_auth();
+    validation::duration(timelock_seconds)?;
 
     let guardians: Vec<Address> = env
         .storage()
@@ -3987,6 +4487,9 @@ fn execute_emergency_pause(env: &Env) -> Result<(), PayrollError> {
     env.storage()
         .persistent()
         .set(&StorageKey::EmergencyPause, &pending);
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::EmergencyPauseTerms);
     env.storage().persistent().remove(&StorageKey::PendingPause);
     env.storage()
         .persistent()
@@ -4005,17 +4508,75 @@ fn execute_emergency_pause(env: &Env) -> Result<(), PayrollError> {
 pub fn emergency_pause(env: &Env) -> Result<(), PayrollError> {
     let owner: Address = env.storage().persistent().get(&StorageKey::Owner).unwrap();
     owner.require_auth();
+    activate_emergency_pause(env, owner, None, None)
+}
+
+/// Immediately activates emergency pause with a duration and reason (owner only)
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `duration_seconds` - If set, the pause lapses automatically this many
+///   seconds from now; `None` pauses until `emergency_unpause`
+/// * `reason` - Optional human-readable reason shown via `get_pause_info`
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `duration_seconds` is zero or overflows the
+///   timestamp, or `reason` is longer than `MAX_STRING_LEN`
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn emergency_pause_with_terms(
+    env: &Env,
+    duration_seconds: Option<u64>,
+    reason: Option<String>,
+) -> Result<(), PayrollError> {
+    let owner: Address = env.storage().persistent().get(&StorageKey::Owner).unwrap();
+    owner.require_auth();
+    activate_emergency_pause(env, owner, duration_seconds, reason)
+}
+
+/// Pauses the contract on behalf of `paused_by`, who has already been
+/// authorized.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `duration_seconds` is zero or overflows the
+///   timestamp, or `reason` is longer than `MAX_STRING_LEN`
+pub(crate) fn activate_emergency_pause(
+    env: &Env,
+    paused_by: Address,
+    duration_seconds: Option<u64>,
+    reason: Option<String>,
+) -> Result<(), PayrollError> {
+    if let Some(reason) = &reason {
+        validation::string(reason)?;
+    }
+    let now = env.ledger().timestamp();
+    let resume_at = match duration_seconds {
+        Some(0) => return Err(PayrollError::InvalidData),
+        Some(duration) => Some(now.checked_add(duration).ok_or(PayrollError::InvalidData)?),
+        None => None,
+    };
 
     let pause_state = crate::storage::EmergencyPause {
         is_paused: true,
-        paused_at: Some(env.ledger().timestamp()),
-        paused_by: Some(owner),
+        paused_at: Some(now),
+        paused_by: Some(paused_by),
         timelock_end: None,
     };
 
     env.storage()
         .persistent()
         .set(&StorageKey::EmergencyPause, &pause_state);
+    if resume_at.is_some() || reason.is_some() {
+        env.storage().persistent().set(
+            &StorageKey::EmergencyPauseTerms,
+            &crate::storage::EmergencyPauseTerms { resume_at, reason },
+        );
+    } else {
+        env.storage()
+            .persistent()
+            .remove(&StorageKey::EmergencyPauseTerms);
+    }
 
     Ok(())
 }
@@ -4030,7 +4591,12 @@ pub fn emergency_pause(env: &Env) -> Result<(), PayrollError> {
 pub fn emergency_unpause(env: &Env) -> Result<(), PayrollError> {
     let owner: Address = env.storage().persistent().get(&StorageKey::Owner).unwrap();
     owner.require_auth();
+    lift_emergency_pause(env);
+    Ok(())
+}
 
+/// Clears the emergency pause. The caller has already been authorized.
+pub(crate) fn lift_emergency_pause(env: &Env) {
     let pause_state = crate::storage::EmergencyPause {
         is_paused: false,
         paused_at: None,
@@ -4041,11 +4607,35 @@ pub fn emergency_unpause(env: &Env) -> Result<(), PayrollError> {
     env.storage()
         .persistent()
         .set(&StorageKey::EmergencyPause, &pause_state);
-
-    Ok(())
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::EmergencyPauseTerms);
 }
 
 /// Gets emergency pause state
 pub fn get_emergency_pause_state(env: &Env) -> Option<crate::storage::EmergencyPause> {
     env.storage().persistent().get(&StorageKey::EmergencyPause)
 }
+
+/// Gets the effective pause status, resolving lapsed time-boxed pauses
+pub fn get_pause_info(env: &Env) -> crate::storage::PauseInfo {
+    match get_emergency_pause_state(env) {
+        Some(pause) if pause_in_effect(env, &pause) => {
+            let terms = get_emergency_pause_terms(env);
+            crate::storage::PauseInfo {
+                is_paused: true,
+                paused_at: pause.paused_at,
+                paused_by: pause.paused_by,
+                resume_at: terms.as_ref().and_then(|t| t.resume_at),
+                reason: terms.and_then(|t| t.reason),
+            }
+        }
+        _ => crate::storage::PauseInfo {
+            is_paused: false,
+            paused_at: None,
+            paused_by: None,
+            resume_at: None,
+            reason: None,
+        },
+    }
+}
@@ -0,0 +1,206 @@
+//! Attested import of payrolls from an off-chain payroll system.
+//!
+//! A company migrating to the contract exports each employee's pay terms and
+//! payment history from its old system and has the export signed by an
+//! attestor: an address holding a governance-issued `PayrollAttestor`
+//! credential, signing with the ed25519 key it registered through
+//! `register_signing_key`. `import_payroll_records` checks the signature and
+//! creates one active payroll agreement per record.
+//!
+//! Each imported payroll is anchored at the employee's last payment, so the
+//! first period on-chain ends one period after it and the pay schedule
+//! carries on without a gap, and the grace period after a cancellation is
+//! one period. The payment history itself is kept as an [`ImportedHistory`]
+//! marker per agreement. Imported payrolls are created like any other: their
+//! escrow still has to be funded before employees can claim.
+//!
+//! The signed message is [`import_payload`]: the XDR of this contract's
+//! address, the employer and the records. An export can only be imported
+//! once.
+
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, Bytes, BytesN, Env, Vec};
+
+use crate::credentials::{has_credential, CredentialRole};
+use crate::events::{emit_payroll_imported, PayrollImportedEvent};
+use crate::meta_tx::get_signing_key;
+use crate::payroll::{
+    activate_agreement, add_employee_to_agreement, create_payroll_agreement_internal,
+};
+use crate::storage::{extend_persistent_ttl, Agreement, DataKey, PayrollError, StorageKey};
+use crate::validation;
+use crate::versioned;
+use crate::winddown::ensure_not_winding_down;
+
+/// An employee's pay terms and payment history in the previous system.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImportedRecord {
+    pub employee: Address,
+    pub token: Address,
+    pub salary_per_period: i128,
+    pub period_seconds: u64,
+    /// When the previous system last paid the employee.
+    pub last_paid_at: u64,
+    pub last_paid_amount: i128,
+    /// Everything the previous system paid the employee, including the last
+    /// payment.
+    pub paid_to_date: i128,
+}
+
+/// Payment history carried over for an imported payroll.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ImportedHistory {
+    pub attestor: Address,
+    pub imported_at: u64,
+    pub last_paid_at: u64,
+    pub last_paid_amount: i128,
+    pub paid_to_date: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum ImportKey {
+    /// Payment history of an imported payroll agreement -> ImportedHistory
+    History(u128),
+    /// SHA-256 of an import payload already used -> u64 (import timestamp)
+    Export(BytesN<32>),
+}
+
+/// Returns the bytes an attestor signs to approve importing `records` for
+/// `employer`.
+pub fn import_payload(env: &Env, employer: &Address, records: &Vec<ImportedRecord>) -> Bytes {
+    let mut payload = env.current_contract_address().to_xdr(env);
+    payload.append(&employer.clone().to_xdr(env));
+    payload.append(&records.clone().to_xdr(env));
+    payload
+}
+
+/// Creates an active payroll agreement for each record of an attested
+/// export, anchored at the employee's last payment, and returns their IDs in
+/// record order.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - no credential registry is linked, the
+///   attestor lacks a valid `PayrollAttestor` credential or has no signing
+///   key, or the employer is winding down
+/// * `PayrollError::InvalidData` - no records, the export was already
+///   imported, a salary or period is not positive, a payment is dated in the
+///   future, or the paid amounts are negative or inconsistent
+/// * `PayrollError::BatchTooLarge` - more than `MAX_BATCH_SIZE` records
+///
+/// An invalid signature aborts the call.
+///
+/// # Access Control
+/// Requires employer authentication and the attestor's signature
+pub fn import_payroll_records(
+    env: &Env,
+    employer: &Address,
+    attestor: &Address,
+    records: Vec<ImportedRecord>,
+    signature: BytesN<64>,
+) -> Result<Vec<u128>, PayrollError> {
+    employer.require_auth();
+    ensure_not_winding_down(env, employer)?;
+    if records.is_empty() {
+        return Err(PayrollError::InvalidData);
+    }
+    validation::items(&records)?;
+    if has_credential(env, attestor, CredentialRole::PayrollAttestor) != Some(true) {
+        return Err(PayrollError::Unauthorized);
+    }
+    let public_key = get_signing_key(env, attestor).ok_or(PayrollError::Unauthorized)?;
+
+    let now = env.ledger().timestamp();
+    for record in records.iter() {
+        validation::max_amount(record.salary_per_period)?;
+        validation::max_amount(record.paid_to_date)?;
+        validation::duration(record.period_seconds)?;
+        if record.salary_per_period <= 0
+            || record.period_seconds == 0
+            || record.last_paid_at > now
+            || record.last_paid_amount < 0
+            || record.paid_to_date < record.last_paid_amount
+        {
+            return Err(PayrollError::InvalidData);
+        }
+    }
+
+    let payload = import_payload(env, employer, &records);
+    let export_hash: BytesN<32> = env.crypto().sha256(&payload).into();
+    let export_key = ImportKey::Export(export_hash.clone());
+    if env.storage().persistent().has(&export_key) {
+        return Err(PayrollError::InvalidData);
+    }
+    env.crypto()
+        .ed25519_verify(&public_key, &payload, &signature);
+    env.storage().persistent().set(&export_key, &now);
+    extend_persistent_ttl(env, &export_key);
+
+    let mut agreement_ids = Vec::new(env);
+    for record in records.iter() {
+        let agreement_id = create_payroll_agreement_internal(
+            env,
+            employer.clone(),
+            record.token.clone(),
+            record.period_seconds,
+            None,
+        );
+        add_employee_to_agreement(
+            env,
+            agreement_id,
+            record.employee.clone(),
+            record.salary_per_period,
+        );
+        activate_agreement(env, agreement_id);
+
+        // Periods count from the last payment made by the previous system.
+        let key = StorageKey::Agreement(agreement_id);
+        let mut agreement: Agreement = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or(PayrollError::AgreementNotFound)?;
+        agreement.period_seconds = Some(record.period_seconds);
+        versioned::save(env, &key, &agreement);
+        DataKey::set_employee_count(env, agreement_id, 1);
+        DataKey::set_agreement_activation_time(env, agreement_id, record.last_paid_at);
+        DataKey::set_agreement_period_duration(env, agreement_id, record.period_seconds);
+        DataKey::set_agreement_token(env, agreement_id, &record.token);
+        DataKey::set_employee(env, agreement_id, 0, &record.employee);
+        DataKey::set_employee_salary(env, agreement_id, 0, record.salary_per_period);
+
+        let history_key = ImportKey::History(agreement_id);
+        env.storage().persistent().set(
+            &history_key,
+            &ImportedHistory {
+                attestor: attestor.clone(),
+                imported_at: now,
+                last_paid_at: record.last_paid_at,
+                last_paid_amount: record.last_paid_amount,
+                paid_to_date: record.paid_to_date,
+            },
+        );
+        extend_persistent_ttl(env, &history_key);
+        agreement_ids.push_back(agreement_id);
+    }
+
+    emit_payroll_imported(
+        env,
+        PayrollImportedEvent {
+            employer: employer.clone(),
+            attestor: attestor.clone(),
+            agreements: agreement_ids.len(),
+            export_hash,
+        },
+    );
+    Ok(agreement_ids)
+}
+
+/// Returns the payment history imported with a payroll agreement, if it was
+/// imported.
+pub fn get_imported_history(env: &Env, agreement_id: u128) -> Option<ImportedHistory> {
+    env.storage()
+        .persistent()
+        .get(&ImportKey::History(agreement_id))
+}
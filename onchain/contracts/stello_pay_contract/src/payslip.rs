@@ -0,0 +1,276 @@
+//! Per-payout payslip records.
+//!
+//! Every periodic payroll payout is settled through [`settle_payout`], which
+//! applies garnishment deductions (see [`crate::garnishment`]), repays any
+//! salary loan pledged against the agreement (see [`crate::loans`]), moves the
+//! employee's savings share into their vault (see [`crate::savings`]) and
+//! writes one payslip listing the gross amount, each deduction, the loan
+//! repayment, the amount saved and the net amount actually transferred.
+//!
+//! Salary payslips carry the payout's receipt hash (see [`crate::receipts`]).
+//!
+//! Approved expense reimbursements (see [`crate::reimbursements`]) are
+//! recorded on their own payslips, categorised apart from salary. Payslips are
+//! numbered per employee from 1.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+use crate::garnishment::withhold_garnishments;
+use crate::loans::repay_from_payout;
+use crate::receipts::issue_receipt;
+use crate::rounding::{agreement_policy, round_div, RoundingMode};
+use crate::savings::retain_savings;
+
+#[contracttype]
+#[derive(Clone)]
+enum PayslipKey {
+    /// Number of payslips for an employee -> u64
+    Count(Address),
+    /// Payslip `(employee, id)` -> Payslip
+    Payslip(Address, u64),
+}
+
+/// An amount withheld from a payout and paid to a third party.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayslipDeduction {
+    /// Garnishment order the deduction was made for.
+    pub order_id: u64,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+/// What a payslip pays for.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PayslipCategory {
+    /// A periodic payroll payout.
+    Salary,
+    /// An approved expense reimbursement; never has deductions.
+    Reimbursement,
+}
+
+/// Record of one payout to an employee.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payslip {
+    pub id: u64,
+    pub category: PayslipCategory,
+    pub agreement_id: u128,
+    /// Token the payout was made in.
+    pub token: Address,
+    pub gross: i128,
+    pub deductions: Vec<PayslipDeduction>,
+    /// Amount paid to the lender of a salary loan.
+    pub loan_repayment: i128,
+    /// Amount moved into the employee's savings vault.
+    pub saved: i128,
+    /// `gross` minus deductions, loan repayment and savings; the amount the
+    /// employee received.
+    pub net: i128,
+    pub paid_at: u64,
+    /// `Disbursement` sequence number of the payout (see [`crate::sequence`]).
+    pub sequence: u64,
+    /// Receipt hash of a salary payout, for `verify_receipt`.
+    pub receipt: Option<BytesN<32>>,
+}
+
+/// Returns the number of payslips recorded for `employee`.
+pub fn get_payslip_count(env: &Env, employee: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&PayslipKey::Count(employee.clone()))
+        .unwrap_or(0)
+}
+
+/// Returns payslip `id` (1-based) of `employee`, if any.
+pub fn get_payslip(env: &Env, employee: &Address, id: u64) -> Option<Payslip> {
+    env.storage()
+        .persistent()
+        .get(&PayslipKey::Payslip(employee.clone(), id))
+}
+
+/// Gross amount of a payout, in the token it is paid in.
+///
+/// A payout converted from the agreement's base token also carries the
+/// base-token gross it was converted from, so deductions denominated in the
+/// base token apply to it at the payout's own rate.
+pub(crate) struct PayoutGross {
+    pub token: Address,
+    pub amount: i128,
+    /// Base token and the gross in it, for a converted payout.
+    base: Option<(Address, i128)>,
+}
+
+impl PayoutGross {
+    /// A payout of `amount` in `token`.
+    pub(crate) fn new(token: &Address, amount: i128) -> Self {
+        PayoutGross {
+            token: token.clone(),
+            amount,
+            base: None,
+        }
+    }
+
+    /// A payout of `amount` in `token`, converted from `base_amount` of
+    /// `base_token`.
+    pub(crate) fn converted(
+        token: &Address,
+        amount: i128,
+        base_token: &Address,
+        base_amount: i128,
+    ) -> Self {
+        PayoutGross {
+            token: token.clone(),
+            amount,
+            base: Some((base_token.clone(), base_amount)),
+        }
+    }
+
+    /// The gross in `token`, if the payout is paid in it or converted from
+    /// it.
+    pub(crate) fn in_token(&self, token: &Address) -> Option<i128> {
+        if *token == self.token {
+            return Some(self.amount);
+        }
+        match &self.base {
+            Some((base_token, base_amount)) if base_token == token => Some(*base_amount),
+            _ => None,
+        }
+    }
+
+    /// Converts `amount` of `token` into the paid token at the payout's rate,
+    /// rounding down. `token` must be one [`Self::in_token`] accepts.
+    pub(crate) fn to_paid(&self, token: &Address, amount: i128) -> i128 {
+        self.convert(token, amount, true)
+    }
+
+    /// Converts `amount` of the paid token into `token` at the payout's rate,
+    /// rounding down. `token` must be one [`Self::in_token`] accepts.
+    pub(crate) fn to_token(&self, token: &Address, amount: i128) -> i128 {
+        self.convert(token, amount, false)
+    }
+
+    fn convert(&self, token: &Address, amount: i128, to_paid: bool) -> i128 {
+        let Some((_, base_amount)) = self
+            .base
+            .as_ref()
+            .filter(|(base_token, _)| base_token == token && *token != self.token)
+        else {
+            return amount;
+        };
+        let (from, to) = if to_paid {
+            (*base_amount, self.amount)
+        } else {
+            (self.amount, *base_amount)
+        };
+        if from <= 0 {
+            return 0;
+        }
+        round_div(amount.saturating_mul(to), from, RoundingMode::Floor)
+    }
+}
+
+/// Settles `payout`, covering pay periods up to `period`: pays
+/// garnishment deductions and the pledged loan repayment, retains the
+/// savings share, issues the receipt and records the payslip under the
+/// payout's `sequence` number.
+///
+/// Called at the interaction point of a claim, after its own effects are
+/// persisted.
+///
+/// # Returns
+/// The net amount the caller must transfer to the employee.
+pub(crate) fn settle_payout(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+    payout: &PayoutGross,
+    period: u32,
+    sequence: u64,
+) -> i128 {
+    let token = &payout.token;
+    let gross = payout.amount;
+    let mode = agreement_policy(env, agreement_id);
+    let deductions = withhold_garnishments(env, agreement_id, employee, payout, mode);
+    let withheld: i128 = deductions.iter().map(|d| d.amount).sum();
+    let loan_repayment =
+        repay_from_payout(env, agreement_id, employee, payout, gross - withheld, mode);
+    let saved = retain_savings(
+        env,
+        employee,
+        token,
+        gross - withheld - loan_repayment,
+        mode,
+    );
+    // The net leg takes whatever the rounded shares leave, so the legs always
+    // sum to `gross`.
+    let net = gross - withheld - loan_repayment - saved;
+    let receipt = issue_receipt(env, agreement_id, employee, token, gross, period);
+
+    store_payslip(
+        env,
+        employee,
+        Payslip {
+            id: 0,
+            category: PayslipCategory::Salary,
+            agreement_id,
+            token: token.clone(),
+            gross,
+            deductions,
+            loan_repayment,
+            saved,
+            net,
+            paid_at: env.ledger().timestamp(),
+            sequence,
+            receipt: Some(receipt),
+        },
+    );
+    net
+}
+
+/// Records the payslip of an approved reimbursement of `amount`, paid in full
+/// to the employee.
+///
+/// # Returns
+/// The payslip id.
+pub(crate) fn record_reimbursement(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+    token: &Address,
+    amount: i128,
+    sequence: u64,
+) -> u64 {
+    store_payslip(
+        env,
+        employee,
+        Payslip {
+            id: 0,
+            category: PayslipCategory::Reimbursement,
+            agreement_id,
+            token: token.clone(),
+            gross: amount,
+            deductions: Vec::new(env),
+            loan_repayment: 0,
+            saved: 0,
+            net: amount,
+            paid_at: env.ledger().timestamp(),
+            sequence,
+            receipt: None,
+        },
+    )
+}
+
+/// Numbers and stores the employee's next payslip.
+fn store_payslip(env: &Env, employee: &Address, mut payslip: Payslip) -> u64 {
+    let id = get_payslip_count(env, employee) + 1;
+    payslip.id = id;
+    env.storage()
+        .persistent()
+        .set(&PayslipKey::Payslip(employee.clone(), id), &payslip);
+    env.storage()
+        .persistent()
+        .set(&PayslipKey::Count(employee.clone()), &id);
+    id
+}
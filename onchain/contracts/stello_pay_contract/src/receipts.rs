@@ -0,0 +1,101 @@
+//! Verifiable disbursement receipts.
+//!
+//! Every payroll payout settled through [`crate::payslip::settle_payout`]
+//! appends a `PayoutDisbursed` entry to the audit log and gets a receipt: the
+//! SHA-256 of the XDR-encoded [`DisbursementReceipt`] (employer, employee,
+//! token, gross amount, pay period and audit id). The hash is stored, emitted
+//! and written on the payslip, so an employee can hand it to a landlord or
+//! lender as a proof-of-income reference; `verify_receipt` resolves it back to
+//! the audit record.
+
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, BytesN, Env};
+
+use crate::audit::{get_audit_entry, record_entry, AuditEvent, LifecycleAuditEntry};
+use crate::events::{emit_receipt_issued, ReceiptIssuedEvent};
+use crate::payroll::get_agreement;
+use crate::storage::extend_persistent_ttl;
+
+#[contracttype]
+#[derive(Clone)]
+enum ReceiptKey {
+    /// Receipt hash -> audit id of the payout
+    Receipt(BytesN<32>),
+}
+
+/// Preimage of a receipt hash.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisbursementReceipt {
+    pub employer: Address,
+    pub employee: Address,
+    pub token: Address,
+    /// Gross payout, before deductions.
+    pub amount: i128,
+    /// Last pay period the payout covers, counted from 1.
+    pub period: u32,
+    pub audit_id: u64,
+}
+
+/// Hash identifying `receipt`.
+pub fn receipt_hash(env: &Env, receipt: &DisbursementReceipt) -> BytesN<32> {
+    env.crypto().sha256(&receipt.clone().to_xdr(env)).into()
+}
+
+/// Records the payout in the audit log and issues its receipt.
+///
+/// # Returns
+/// The receipt hash.
+pub(crate) fn issue_receipt(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+    token: &Address,
+    amount: i128,
+    period: u32,
+) -> BytesN<32> {
+    let employer = get_agreement(env, agreement_id)
+        .map(|agreement| agreement.employer)
+        .unwrap_or_else(|| employee.clone());
+    let audit_id = record_entry(
+        env,
+        employer.clone(),
+        AuditEvent::PayoutDisbursed,
+        agreement_id,
+        Some(employee.clone()),
+        Some(amount),
+    );
+    let hash = receipt_hash(
+        env,
+        &DisbursementReceipt {
+            employer,
+            employee: employee.clone(),
+            token: token.clone(),
+            amount,
+            period,
+            audit_id,
+        },
+    );
+    let key = ReceiptKey::Receipt(hash.clone());
+    env.storage().persistent().set(&key, &audit_id);
+    extend_persistent_ttl(env, &key);
+
+    emit_receipt_issued(
+        env,
+        ReceiptIssuedEvent {
+            employee: employee.clone(),
+            receipt_hash: hash.clone(),
+            audit_id,
+        },
+    );
+    hash
+}
+
+/// Returns the audit record of the payout `receipt_hash` was issued for, or
+/// `None` if this contract never issued it.
+pub fn verify_receipt(env: &Env, receipt_hash: &BytesN<32>) -> Option<LifecycleAuditEntry> {
+    let audit_id: u64 = env
+        .storage()
+        .persistent()
+        .get(&ReceiptKey::Receipt(receipt_hash.clone()))?;
+    get_audit_entry(env, audit_id)
+}
@@ -0,0 +1,422 @@
+//! Expense reimbursements paid from an employer's reimbursement pool.
+//!
+//! An employee submits a reimbursement request with the amount, token and a
+//! hash of the receipt. Each receipt hash can back only one request at a
+//! time, so the same receipt cannot be reimbursed twice.
+//!
+//! The request is approved or rejected by an employer of the employee, or by
+//! an approver that employer has designated; nobody can approve their own
+//! request. Approval pays the amount from the employer's pool in that token,
+//! which the employer funds in advance.
+//!
+//! Reimbursements are kept apart from salary: they bypass garnishments, loan
+//! repayments and savings, are recorded on a payslip categorised as
+//! [`PayslipCategory::Reimbursement`](crate::payslip::PayslipCategory), and
+//! emit their own event instead of the salary payment events.
+
+use soroban_sdk::{contracttype, token::Client as TokenClient, Address, BytesN, Env};
+
+use crate::events::{
+    emit_reimbursement_paid, emit_reimbursement_rejected, emit_reimbursement_submitted,
+    ReimbursementPaidEvent, ReimbursementRejectedEvent, ReimbursementSubmittedEvent,
+};
+use crate::pause_exemptions::{is_paused_for, PauseCategory};
+use crate::payroll::{get_employment_agreements, transfer_from_contract};
+use crate::payslip::record_reimbursement;
+use crate::sequence::{next_sequence, SequenceCategory};
+use crate::stats;
+use crate::storage::PayrollError;
+use crate::validation;
+
+#[contracttype]
+#[derive(Clone)]
+enum ReimbursementKey {
+    /// Approver designated by an employer `(employer, approver)` -> bool
+    Approver(Address, Address),
+    /// Reimbursement pool of an employer `(employer, token)` -> i128
+    Pool(Address, Address),
+    /// Next request id -> u64
+    NextRequestId,
+    /// Request by id -> ReimbursementRequest
+    Request(u64),
+    /// Request a receipt hash backs -> u64
+    Receipt(BytesN<32>),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReimbursementStatus {
+    Pending,
+    Paid,
+    Rejected,
+    /// Withdrawn by the employee; the receipt can be submitted again.
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReimbursementRequest {
+    pub id: u64,
+    pub employee: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub receipt_hash: BytesN<32>,
+    pub status: ReimbursementStatus,
+    pub submitted_at: u64,
+    /// Employer whose pool paid, or who rejected, the request.
+    pub employer: Option<Address>,
+    /// Employer or approver who decided the request.
+    pub decided_by: Option<Address>,
+    pub decided_at: Option<u64>,
+}
+
+/// Designates or removes an approver for the employer's reimbursements.
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn set_reimbursement_approver(
+    env: &Env,
+    employer: &Address,
+    approver: &Address,
+    approved: bool,
+) {
+    employer.require_auth();
+    let key = ReimbursementKey::Approver(employer.clone(), approver.clone());
+    if approved {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+/// Returns whether `approver` may decide the employer's reimbursements.
+pub fn is_reimbursement_approver(env: &Env, employer: &Address, approver: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get(&ReimbursementKey::Approver(
+            employer.clone(),
+            approver.clone(),
+        ))
+        .unwrap_or(false)
+}
+
+/// Returns the balance of the employer's reimbursement pool in `token`.
+pub fn get_reimbursement_pool(env: &Env, employer: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&ReimbursementKey::Pool(employer.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+fn set_pool(env: &Env, employer: &Address, token: &Address, balance: i128) {
+    env.storage().persistent().set(
+        &ReimbursementKey::Pool(employer.clone(), token.clone()),
+        &balance,
+    );
+}
+
+/// Adds `amount` of `token` to the employer's reimbursement pool.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `amount` is not positive or above `MAX_AMOUNT`
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn fund_reimbursement_pool(
+    env: &Env,
+    employer: &Address,
+    token: &Address,
+    amount: i128,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    validation::amount(amount)?;
+    let balance = get_reimbursement_pool(env, employer, token)
+        .checked_add(amount)
+        .ok_or(PayrollError::InvalidData)?;
+    TokenClient::new(env, token).transfer(employer, env.current_contract_address(), &amount);
+    set_pool(env, employer, token, balance);
+    Ok(())
+}
+
+/// Returns `amount` of `token` from the employer's reimbursement pool.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `amount` is not positive or above `MAX_AMOUNT`
+/// * `PayrollError::InsufficientEscrowBalance` - the pool holds less
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn withdraw_reimbursement_pool(
+    env: &Env,
+    employer: &Address,
+    token: &Address,
+    amount: i128,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    validation::amount(amount)?;
+    let balance = get_reimbursement_pool(env, employer, token);
+    if balance < amount {
+        return Err(PayrollError::InsufficientEscrowBalance);
+    }
+    set_pool(env, employer, token, balance - amount);
+    transfer_from_contract(env, token, employer, amount);
+    Ok(())
+}
+
+/// Submits a reimbursement request backed by a receipt hash.
+///
+/// # Returns
+/// The request id.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `amount` is not positive or above
+///   `MAX_AMOUNT`, or the receipt already backs another request
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn submit_reimbursement(
+    env: &Env,
+    employee: &Address,
+    amount: i128,
+    token: &Address,
+    receipt_hash: BytesN<32>,
+) -> Result<u64, PayrollError> {
+    employee.require_auth();
+    validation::amount(amount)?;
+    let receipt_key = ReimbursementKey::Receipt(receipt_hash.clone());
+    if env.storage().persistent().has(&receipt_key) {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let id: u64 = env
+        .storage()
+        .persistent()
+        .get(&ReimbursementKey::NextRequestId)
+        .unwrap_or(1);
+    env.storage()
+        .persistent()
+        .set(&ReimbursementKey::NextRequestId, &(id + 1));
+    env.storage().persistent().set(&receipt_key, &id);
+    set_request(
+        env,
+        &ReimbursementRequest {
+            id,
+            employee: employee.clone(),
+            token: token.clone(),
+            amount,
+            receipt_hash,
+            status: ReimbursementStatus::Pending,
+            submitted_at: env.ledger().timestamp(),
+            employer: None,
+            decided_by: None,
+            decided_at: None,
+        },
+    );
+
+    emit_reimbursement_submitted(
+        env,
+        ReimbursementSubmittedEvent {
+            request_id: id,
+            employee: employee.clone(),
+            token: token.clone(),
+            amount,
+        },
+    );
+    Ok(id)
+}
+
+/// Withdraws a pending request and frees its receipt hash.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - request does not exist or is not pending
+/// * `PayrollError::Unauthorized` - caller did not submit the request
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn cancel_reimbursement(
+    env: &Env,
+    employee: &Address,
+    request_id: u64,
+) -> Result<(), PayrollError> {
+    employee.require_auth();
+    let mut request = get_reimbursement(env, request_id).ok_or(PayrollError::InvalidData)?;
+    if request.employee != *employee {
+        return Err(PayrollError::Unauthorized);
+    }
+    if request.status != ReimbursementStatus::Pending {
+        return Err(PayrollError::InvalidData);
+    }
+    request.status = ReimbursementStatus::Cancelled;
+    set_request(env, &request);
+    env.storage()
+        .persistent()
+        .remove(&ReimbursementKey::Receipt(request.receipt_hash));
+    Ok(())
+}
+
+/// Approves a pending request and pays it from the employer's pool.
+///
+/// # Errors
+/// * `PayrollError::EmergencyPaused` - the contract is paused
+/// * `PayrollError::InvalidData` - request does not exist or is not pending
+/// * `PayrollError::Unauthorized` - caller is neither `employer` nor one of
+///   its approvers, or submitted the request
+/// * `PayrollError::NoEmployee` - `employer` has no agreement with the
+///   employee
+/// * `PayrollError::InsufficientEscrowBalance` - the pool cannot cover the
+///   amount
+///
+/// # Access Control
+/// Requires employer or approver authentication
+pub fn approve_reimbursement(
+    env: &Env,
+    approver: &Address,
+    employer: &Address,
+    request_id: u64,
+) -> Result<(), PayrollError> {
+    approver.require_auth();
+    pay_reimbursement(env, approver, employer, request_id)
+}
+
+/// [`approve_reimbursement`] once `approver` is authenticated, also reached
+/// through a signed action.
+pub(crate) fn pay_reimbursement(
+    env: &Env,
+    approver: &Address,
+    employer: &Address,
+    request_id: u64,
+) -> Result<(), PayrollError> {
+    if is_paused_for(env, PauseCategory::Reimbursements) {
+        return Err(PayrollError::EmergencyPaused);
+    }
+    let (mut request, agreement_id) = decidable_request(env, approver, employer, request_id)?;
+    let balance = get_reimbursement_pool(env, employer, &request.token);
+    if balance < request.amount {
+        return Err(PayrollError::InsufficientEscrowBalance);
+    }
+
+    set_pool(env, employer, &request.token, balance - request.amount);
+    decide(
+        env,
+        &mut request,
+        approver,
+        employer,
+        ReimbursementStatus::Paid,
+    );
+    let sequence = next_sequence(env, SequenceCategory::Disbursement);
+    let payslip_id = record_reimbursement(
+        env,
+        agreement_id,
+        &request.employee,
+        &request.token,
+        request.amount,
+        sequence,
+    );
+    transfer_from_contract(env, &request.token, &request.employee, request.amount);
+    stats::record_disbursement(env, &request.token, request.amount);
+
+    emit_reimbursement_paid(
+        env,
+        ReimbursementPaidEvent {
+            request_id,
+            employer: employer.clone(),
+            employee: request.employee,
+            token: request.token,
+            amount: request.amount,
+            payslip_id,
+            sequence,
+        },
+    );
+    Ok(())
+}
+
+/// Rejects a pending request. Its receipt cannot be submitted again.
+///
+/// # Errors
+/// Same as [`approve_reimbursement`], except for the pause and pool checks.
+///
+/// # Access Control
+/// Requires employer or approver authentication
+pub fn reject_reimbursement(
+    env: &Env,
+    approver: &Address,
+    employer: &Address,
+    request_id: u64,
+) -> Result<(), PayrollError> {
+    approver.require_auth();
+    let (mut request, _) = decidable_request(env, approver, employer, request_id)?;
+    decide(
+        env,
+        &mut request,
+        approver,
+        employer,
+        ReimbursementStatus::Rejected,
+    );
+
+    emit_reimbursement_rejected(
+        env,
+        ReimbursementRejectedEvent {
+            request_id,
+            employer: employer.clone(),
+            rejected_by: approver.clone(),
+        },
+    );
+    Ok(())
+}
+
+/// Returns a reimbursement request by id.
+pub fn get_reimbursement(env: &Env, request_id: u64) -> Option<ReimbursementRequest> {
+    env.storage()
+        .persistent()
+        .get(&ReimbursementKey::Request(request_id))
+}
+
+/// Returns the request a receipt hash backs, if any.
+pub fn get_reimbursement_by_receipt(env: &Env, receipt_hash: BytesN<32>) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&ReimbursementKey::Receipt(receipt_hash))
+}
+
+fn set_request(env: &Env, request: &ReimbursementRequest) {
+    env.storage()
+        .persistent()
+        .set(&ReimbursementKey::Request(request.id), request);
+}
+
+/// Loads a pending request `approver` may decide for `employer`, with the
+/// employer's first agreement with the employee.
+fn decidable_request(
+    env: &Env,
+    approver: &Address,
+    employer: &Address,
+    request_id: u64,
+) -> Result<(ReimbursementRequest, u128), PayrollError> {
+    let request = get_reimbursement(env, request_id).ok_or(PayrollError::InvalidData)?;
+    if request.status != ReimbursementStatus::Pending {
+        return Err(PayrollError::InvalidData);
+    }
+    if (approver != employer && !is_reimbursement_approver(env, employer, approver))
+        || *approver == request.employee
+    {
+        return Err(PayrollError::Unauthorized);
+    }
+    let agreement_id = get_employment_agreements(env, employer, &request.employee)
+        .first()
+        .ok_or(PayrollError::NoEmployee)?;
+    Ok((request, agreement_id))
+}
+
+fn decide(
+    env: &Env,
+    request: &mut ReimbursementRequest,
+    approver: &Address,
+    employer: &Address,
+    status: ReimbursementStatus,
+) {
+    request.status = status;
+    request.employer = Some(employer.clone());
+    request.decided_by = Some(approver.clone());
+    request.decided_at = Some(env.ledger().timestamp());
+    set_request(env, request);
+}
@@ -0,0 +1,345 @@
+//! Employer reputation, with staking to boost the reputation tier.
+//!
+//! Every periodic payout on an employer's agreements feeds a running record
+//! of on-time, late and defaulted periods, using the same due-date rules as
+//! the employee payout metrics. Every dispute resolved by the arbiter is
+//! recorded too: the employer loses it when any amount is awarded to the
+//! employees.
+//!
+//! The score runs from 0 to [`MAX_REPUTATION_SCORE`]:
+//! * up to 600 points for the share of periods paid on time,
+//! * up to 400 points for the share of resolved disputes not lost,
+//! * minus [`DEFAULT_PENALTY_POINTS`] per defaulted period,
+//! * minus the penalty points of any SLA alerts (see [`crate::sla`]).
+//!
+//! A component with no history yet scores its maximum, so a new employer
+//! starts at the top and is judged by what happens next. Its tier is
+//! `Unrated` until it has any history.
+//!
+//! Employers can stake the owner-configured stake token. A stake of at least
+//! the configured boost amount raises the tier by one step. Unstaking starts
+//! an [`UNSTAKE_COOLDOWN_SECONDS`] cooldown during which the stake no longer
+//! counts, so a tier cannot be borrowed for the length of one negotiation.
+
+use soroban_sdk::{contracttype, token::Client as TokenClient, Address, Env};
+
+use crate::metrics::PayoutTiming;
+use crate::payroll::transfer_from_contract;
+use crate::sla::{record_sla_periods, sla_penalty_points};
+use crate::storage::{PayrollError, StorageKey};
+use crate::validation;
+
+/// Highest possible reputation score.
+pub const MAX_REPUTATION_SCORE: u32 = 1_000;
+
+/// Points deducted from the score per defaulted period.
+pub const DEFAULT_PENALTY_POINTS: u32 = 100;
+
+/// Delay between requesting an unstake and withdrawing it (7 days).
+pub const UNSTAKE_COOLDOWN_SECONDS: u64 = 7 * 86_400;
+
+const PAYMENT_POINTS: u64 = 600;
+const DISPUTE_POINTS: u64 = 400;
+const SILVER_MIN_SCORE: u32 = 600;
+const GOLD_MIN_SCORE: u32 = 800;
+
+#[contracttype]
+#[derive(Clone)]
+enum ReputationKey {
+    /// Payment and dispute history of an employer -> EmployerTrackRecord
+    Record(Address),
+    /// Stake of an employer -> ReputationStake
+    Stake(Address),
+    /// Stake token and boost amount -> ReputationStakeConfig
+    StakeConfig,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct EmployerTrackRecord {
+    on_time_periods: u32,
+    late_periods: u32,
+    defaulted_periods: u32,
+    disputes_resolved: u32,
+    disputes_lost: u32,
+}
+
+/// Token employers stake and the stake that boosts their tier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationStakeConfig {
+    pub token: Address,
+    pub boost_amount: i128,
+}
+
+/// Tokens an employer has staked.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReputationStake {
+    pub token: Address,
+    pub amount: i128,
+    /// Set once an unstake is requested: when the stake can be withdrawn.
+    pub withdrawable_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReputationTier {
+    /// No payouts or disputes recorded yet, and no boosting stake.
+    Unrated,
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+}
+
+/// An employer's reputation and the history it is derived from.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmployerReputation {
+    pub score: u32,
+    pub tier: ReputationTier,
+    /// Whether an active stake raised `tier` by one step.
+    pub stake_boosted: bool,
+    pub on_time_periods: u32,
+    /// Late periods, including defaulted ones.
+    pub late_periods: u32,
+    pub defaulted_periods: u32,
+    pub disputes_resolved: u32,
+    pub disputes_lost: u32,
+    /// Stake that counts towards the boost; zero while unstaking.
+    pub active_stake: i128,
+}
+
+fn get_record(env: &Env, employer: &Address) -> EmployerTrackRecord {
+    env.storage()
+        .persistent()
+        .get(&ReputationKey::Record(employer.clone()))
+        .unwrap_or_default()
+}
+
+fn set_record(env: &Env, employer: &Address, record: &EmployerTrackRecord) {
+    env.storage()
+        .persistent()
+        .set(&ReputationKey::Record(employer.clone()), record);
+}
+
+/// Adds the periods of one payout to the employer's record.
+pub(crate) fn record_payout_timing(env: &Env, employer: &Address, timing: PayoutTiming) {
+    if timing.on_time == 0 && timing.late == 0 {
+        return;
+    }
+    let mut record = get_record(env, employer);
+    record.on_time_periods = record.on_time_periods.saturating_add(timing.on_time);
+    record.late_periods = record.late_periods.saturating_add(timing.late);
+    record.defaulted_periods = record.defaulted_periods.saturating_add(timing.defaulted);
+    set_record(env, employer, &record);
+    record_sla_periods(env, employer, timing.on_time, timing.late);
+}
+
+/// Adds a resolved dispute to the employer's record.
+pub(crate) fn record_dispute_outcome(env: &Env, employer: &Address, employer_lost: bool) {
+    let mut record = get_record(env, employer);
+    record.disputes_resolved = record.disputes_resolved.saturating_add(1);
+    if employer_lost {
+        record.disputes_lost = record.disputes_lost.saturating_add(1);
+    }
+    set_record(env, employer, &record);
+}
+
+/// Sets the token employers stake and the stake needed for a tier boost.
+///
+/// Existing stakes in a different token stop counting but can still be
+/// unstaked and withdrawn.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+/// * `PayrollError::InvalidData` - `boost_amount` is not positive or above `MAX_AMOUNT`
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_reputation_stake_config(
+    env: &Env,
+    owner: &Address,
+    token: &Address,
+    boost_amount: i128,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    validation::amount(boost_amount)?;
+    env.storage().persistent().set(
+        &ReputationKey::StakeConfig,
+        &ReputationStakeConfig {
+            token: token.clone(),
+            boost_amount,
+        },
+    );
+    Ok(())
+}
+
+/// Returns the reputation stake configuration, if set.
+pub fn get_reputation_stake_config(env: &Env) -> Option<ReputationStakeConfig> {
+    env.storage().persistent().get(&ReputationKey::StakeConfig)
+}
+
+/// Returns the employer's stake, if any.
+pub fn get_reputation_stake(env: &Env, employer: &Address) -> Option<ReputationStake> {
+    env.storage()
+        .persistent()
+        .get(&ReputationKey::Stake(employer.clone()))
+}
+
+/// Adds `amount` of the configured stake token to the employer's stake.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no stake token is configured, `amount` is
+///   not positive or above `MAX_AMOUNT`, an unstake is pending, or the existing stake is in a
+///   previously configured token
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn stake_reputation(env: &Env, employer: &Address, amount: i128) -> Result<(), PayrollError> {
+    employer.require_auth();
+    let config = get_reputation_stake_config(env).ok_or(PayrollError::InvalidData)?;
+    validation::amount(amount)?;
+    let mut stake = get_reputation_stake(env, employer).unwrap_or(ReputationStake {
+        token: config.token.clone(),
+        amount: 0,
+        withdrawable_at: None,
+    });
+    if stake.withdrawable_at.is_some() || stake.token != config.token {
+        return Err(PayrollError::InvalidData);
+    }
+
+    TokenClient::new(env, &config.token).transfer(
+        employer,
+        env.current_contract_address(),
+        &amount,
+    );
+    stake.amount = stake
+        .amount
+        .checked_add(amount)
+        .ok_or(PayrollError::InvalidData)?;
+    env.storage()
+        .persistent()
+        .set(&ReputationKey::Stake(employer.clone()), &stake);
+    Ok(())
+}
+
+/// Starts the cooldown for withdrawing the employer's whole stake. The stake
+/// stops counting towards the tier immediately.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no stake, or an unstake is already pending
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn request_unstake(env: &Env, employer: &Address) -> Result<u64, PayrollError> {
+    employer.require_auth();
+    let mut stake = get_reputation_stake(env, employer).ok_or(PayrollError::InvalidData)?;
+    if stake.withdrawable_at.is_some() {
+        return Err(PayrollError::InvalidData);
+    }
+    let withdrawable_at = env.ledger().timestamp() + UNSTAKE_COOLDOWN_SECONDS;
+    stake.withdrawable_at = Some(withdrawable_at);
+    env.storage()
+        .persistent()
+        .set(&ReputationKey::Stake(employer.clone()), &stake);
+    Ok(withdrawable_at)
+}
+
+/// Returns the employer's stake once its unstake cooldown has passed.
+///
+/// # Returns
+/// The amount returned.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no stake, or no unstake requested
+/// * `PayrollError::TimelockActive` - the cooldown has not passed
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn withdraw_stake(env: &Env, employer: &Address) -> Result<i128, PayrollError> {
+    employer.require_auth();
+    let stake = get_reputation_stake(env, employer).ok_or(PayrollError::InvalidData)?;
+    let withdrawable_at = stake.withdrawable_at.ok_or(PayrollError::InvalidData)?;
+    if env.ledger().timestamp() < withdrawable_at {
+        return Err(PayrollError::TimelockActive);
+    }
+    env.storage()
+        .persistent()
+        .remove(&ReputationKey::Stake(employer.clone()));
+    transfer_from_contract(env, &stake.token, employer, stake.amount);
+    Ok(stake.amount)
+}
+
+/// Returns the employer's reputation score and tier with the history behind
+/// them.
+pub fn get_employer_reputation(env: &Env, employer: &Address) -> EmployerReputation {
+    let record = get_record(env, employer);
+
+    let periods = u64::from(record.on_time_periods) + u64::from(record.late_periods);
+    let payment_points = (PAYMENT_POINTS * u64::from(record.on_time_periods))
+        .checked_div(periods)
+        .unwrap_or(PAYMENT_POINTS);
+    let dispute_points = (DISPUTE_POINTS
+        * u64::from(record.disputes_resolved - record.disputes_lost))
+    .checked_div(u64::from(record.disputes_resolved))
+    .unwrap_or(DISPUTE_POINTS);
+    // At most 1000, so the narrowing cannot truncate.
+    let score = ((payment_points + dispute_points) as u32)
+        .saturating_sub(
+            record
+                .defaulted_periods
+                .saturating_mul(DEFAULT_PENALTY_POINTS),
+        )
+        .saturating_sub(sla_penalty_points(env, employer));
+
+    let active_stake = match (
+        get_reputation_stake(env, employer),
+        get_reputation_stake_config(env),
+    ) {
+        (Some(stake), Some(config))
+            if stake.withdrawable_at.is_none() && stake.token == config.token =>
+        {
+            Some((stake.amount, config.boost_amount))
+        }
+        _ => None,
+    };
+    let stake_boosted = active_stake.is_some_and(|(amount, boost)| amount >= boost);
+
+    let base_tier = if periods == 0 && record.disputes_resolved == 0 {
+        ReputationTier::Unrated
+    } else if score >= GOLD_MIN_SCORE {
+        ReputationTier::Gold
+    } else if score >= SILVER_MIN_SCORE {
+        ReputationTier::Silver
+    } else {
+        ReputationTier::Bronze
+    };
+    let tier = if !stake_boosted {
+        base_tier
+    } else {
+        match base_tier {
+            ReputationTier::Unrated => ReputationTier::Bronze,
+            ReputationTier::Bronze => ReputationTier::Silver,
+            ReputationTier::Silver => ReputationTier::Gold,
+            ReputationTier::Gold | ReputationTier::Platinum => ReputationTier::Platinum,
+        }
+    };
+
+    EmployerReputation {
+        score,
+        tier,
+        stake_boosted,
+        on_time_periods: record.on_time_periods,
+        late_periods: record.late_periods,
+        defaulted_periods: record.defaulted_periods,
+        disputes_resolved: record.disputes_resolved,
+        disputes_lost: record.disputes_lost,
+        active_stake: active_stake.map_or(0, |(amount, _)| amount),
+    }
+}
@@ -0,0 +1,195 @@
+//! Resource estimates for payroll runs, derived from owner-supplied profiles.
+//!
+//! Fixed constants go stale with every SDK bump and contract release, so the
+//! owner instead records measured per-operation resource profiles for each
+//! contract version (typically taken from `cargo bench` / `tests/gas_benchmarks.rs`
+//! on the release build). Estimates are the sum of the relevant profiles
+//! scaled by the real shape of the work: agreements, employees and tokens.
+//!
+//! Estimates are only as good as the profiles; they are meant for sizing
+//! batches and fee budgets, not as an upper bound enforced by the host.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::storage::{AgreementMode, AgreementStatus, EmployeeInfo, StorageKey};
+
+/// Unit of work a resource profile is measured for.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PayrollOperation {
+    /// Fixed per-invocation overhead (auth, pause and reentrancy checks).
+    InvocationBase,
+    /// Loading and updating one agreement.
+    PerAgreement,
+    /// Accounting and transfer for one employee claim.
+    PerEmployee,
+    /// First access to a distinct token contract within the invocation.
+    PerToken,
+}
+
+/// Measured cost of one [`PayrollOperation`].
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResourceProfile {
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+    pub read_entries: u32,
+    pub write_entries: u32,
+}
+
+/// Aggregated estimate together with the shape it was computed for.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResourceEstimate {
+    pub contract_version: u32,
+    pub agreement_count: u32,
+    pub employee_count: u32,
+    pub token_count: u32,
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+    pub read_entries: u32,
+    pub write_entries: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum ResourceKey {
+    /// Profile for `(contract_version, operation)`.
+    Profile(u32, PayrollOperation),
+}
+
+/// Stores a measured resource profile for an operation at a contract version.
+///
+/// Profiles can be recorded for a version ahead of `migrate_state` so that
+/// estimates are available as soon as the new version goes live.
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_resource_profile(
+    env: &Env,
+    contract_version: u32,
+    operation: PayrollOperation,
+    profile: ResourceProfile,
+) {
+    let owner: Address = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::Owner)
+        .expect("Owner not set");
+    owner.require_auth();
+
+    let key = ResourceKey::Profile(contract_version, operation);
+    env.storage().persistent().set(&key, &profile);
+    crate::storage::extend_persistent_ttl(env, &key);
+}
+
+/// Returns the stored resource profile for an operation at a contract version.
+pub fn get_resource_profile(
+    env: &Env,
+    contract_version: u32,
+    operation: PayrollOperation,
+) -> Option<ResourceProfile> {
+    env.storage()
+        .persistent()
+        .get(&ResourceKey::Profile(contract_version, operation))
+}
+
+/// Estimates a `batch_claim_payroll` call of `batch_size` employees on a
+/// single agreement and token, using the current contract version's profiles.
+///
+/// # Returns
+/// `None` if any of the required profiles is missing for the current version.
+pub fn estimate_batch_gas(env: &Env, batch_size: u32) -> Option<ResourceEstimate> {
+    estimate(env, 1, batch_size, 1)
+}
+
+/// Estimates claiming every employee across an employer's active payroll
+/// agreements, counting distinct tokens so that multi-token employers pay for
+/// each additional token contract touched.
+///
+/// # Returns
+/// `None` if any of the required profiles is missing for the current version.
+pub fn estimate_payroll_run(env: &Env, employer: Address) -> Option<ResourceEstimate> {
+    let agreement_ids: Vec<u128> = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::EmployerAgreements(employer))
+        .unwrap_or(Vec::new(env));
+
+    let mut agreement_count = 0u32;
+    let mut employee_count = 0u32;
+    let mut tokens: Vec<Address> = Vec::new(env);
+
+    for id in agreement_ids.iter() {
+        let agreement = match crate::payroll::get_agreement(env, id) {
+            Some(agreement) => agreement,
+            None => continue,
+        };
+        if agreement.mode != AgreementMode::Payroll || agreement.status != AgreementStatus::Active {
+            continue;
+        }
+
+        let employees: Vec<EmployeeInfo> = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::AgreementEmployees(id))
+            .unwrap_or(Vec::new(env));
+        // Claim indexes may be seeded independently of the employee list.
+        let indexed = crate::storage::DataKey::get_employee_count(env, id);
+
+        agreement_count += 1;
+        employee_count = employee_count.saturating_add(employees.len().max(indexed));
+        if !tokens.contains(&agreement.token) {
+            tokens.push_back(agreement.token);
+        }
+    }
+
+    estimate(env, agreement_count, employee_count, tokens.len())
+}
+
+fn estimate(
+    env: &Env,
+    agreement_count: u32,
+    employee_count: u32,
+    token_count: u32,
+) -> Option<ResourceEstimate> {
+    let contract_version: u32 = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::ContractVersion)
+        .unwrap_or(0);
+
+    let mut total = ResourceEstimate {
+        contract_version,
+        agreement_count,
+        employee_count,
+        token_count,
+        cpu_instructions: 0,
+        memory_bytes: 0,
+        read_entries: 0,
+        write_entries: 0,
+    };
+
+    for (operation, units) in [
+        (PayrollOperation::InvocationBase, 1u32),
+        (PayrollOperation::PerAgreement, agreement_count),
+        (PayrollOperation::PerEmployee, employee_count),
+        (PayrollOperation::PerToken, token_count),
+    ] {
+        let profile = get_resource_profile(env, contract_version, operation)?;
+        total.cpu_instructions = total
+            .cpu_instructions
+            .saturating_add(profile.cpu_instructions.saturating_mul(units as u64));
+        total.memory_bytes = total
+            .memory_bytes
+            .saturating_add(profile.memory_bytes.saturating_mul(units as u64));
+        total.read_entries = total
+            .read_entries
+            .saturating_add(profile.read_entries.saturating_mul(units));
+        total.write_entries = total
+            .write_entries
+            .saturating_add(profile.write_entries.saturating_mul(units));
+    }
+
+    Some(total)
+}
@@ -0,0 +1,112 @@
+//! Per-employer rounding policy.
+//!
+//! Wherever the contract takes a basis-point share of an amount the exact
+//! result is usually fractional. Each employer chooses how those shares are
+//! rounded with `set_rounding_policy`:
+//! * garnishment deductions, loan repayments and savings shares split off a
+//!   payout of the employer's agreements;
+//! * severance insurance premiums the employer pays;
+//! * COLA raises applied to the employer's salaries.
+//!
+//! The default, [`RoundingMode::Floor`], is how every share was rounded
+//! before policies existed.
+//!
+//! Only the shares are rounded. The net amount paid to the employee is always
+//! the last leg of a payout and is gross less every other leg, so it takes the
+//! rounding dust and the legs always sum to the gross.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::payroll::get_agreement;
+use crate::storage::extend_persistent_ttl;
+
+/// Basis-point denominator for shares.
+pub const BPS_DENOMINATOR: i128 = 10_000;
+
+/// How a fractional share is rounded to a whole token unit.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoundingMode {
+    /// Towards negative infinity.
+    Floor,
+    /// Towards positive infinity.
+    Ceil,
+    /// To the nearest unit, with ties to the even one (banker's rounding).
+    HalfEven,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum RoundingKey {
+    /// Rounding policy of an employer -> RoundingMode
+    RoundingPolicy(Address),
+}
+
+/// Returns the employer's rounding policy, `Floor` if it never set one.
+pub fn get_rounding_policy(env: &Env, employer: &Address) -> RoundingMode {
+    env.storage()
+        .persistent()
+        .get(&RoundingKey::RoundingPolicy(employer.clone()))
+        .unwrap_or(RoundingMode::Floor)
+}
+
+/// Sets the employer's rounding policy. It applies to every share computed
+/// from then on; past payouts are not recomputed.
+///
+/// # Access Control
+/// Requires `employer` authentication
+pub fn set_rounding_policy(env: &Env, employer: &Address, mode: RoundingMode) {
+    employer.require_auth();
+    let key = RoundingKey::RoundingPolicy(employer.clone());
+    if mode == RoundingMode::Floor {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &mode);
+        extend_persistent_ttl(env, &key);
+    }
+}
+
+/// Returns the rounding policy of the agreement's employer.
+pub(crate) fn agreement_policy(env: &Env, agreement_id: u128) -> RoundingMode {
+    get_agreement(env, agreement_id)
+        .map(|agreement| get_rounding_policy(env, &agreement.employer))
+        .unwrap_or(RoundingMode::Floor)
+}
+
+/// Returns `numerator / denominator` rounded with `mode`.
+///
+/// # Panics
+/// If `denominator` is not positive.
+pub fn round_div(numerator: i128, denominator: i128, mode: RoundingMode) -> i128 {
+    assert!(denominator > 0, "denominator must be positive");
+    let quotient = numerator.div_euclid(denominator);
+    let remainder = numerator.rem_euclid(denominator);
+    if remainder == 0 {
+        return quotient;
+    }
+    let round_up = match mode {
+        RoundingMode::Floor => false,
+        RoundingMode::Ceil => true,
+        RoundingMode::HalfEven => {
+            let rest = denominator - remainder;
+            remainder > rest || (remainder == rest && quotient % 2 != 0)
+        }
+    };
+    quotient + i128::from(round_up)
+}
+
+/// Returns `bps` basis points of `value` rounded with `mode`, saturating at
+/// the `i128` bounds.
+///
+/// The share is exact before rounding, so a `Floor` share equals
+/// `value * bps / 10_000` whenever that product fits in an `i128`.
+pub fn apply_bps(value: i128, bps: u32, mode: RoundingMode) -> i128 {
+    let bps = i128::from(bps);
+    // value = whole * 10_000 + part, and whole * bps is already integral, so
+    // only part's share needs rounding. |part| < 10_000 keeps it in range.
+    let whole = value / BPS_DENOMINATOR;
+    let part = value % BPS_DENOMINATOR;
+    whole
+        .saturating_mul(bps)
+        .saturating_add(round_div(part * bps, BPS_DENOMINATOR, mode))
+}
@@ -0,0 +1,291 @@
+//! Employee savings plans with a time-locked vault.
+//!
+//! An employee elects to save a share of each payout (in basis points) for a
+//! chosen lock duration. When a payout is settled, that share of what is left
+//! after garnishments stays in the contract, credited to the employee's vault
+//! for the payout token.
+//!
+//! Each vault holds an `available` balance and a `locked` balance with one
+//! `unlock_at`. A deposit into an empty locked balance starts a new term
+//! ending `lock_duration` later; deposits made during a term join it. Once the
+//! term ends, the locked balance becomes available (checked lazily whenever
+//! the vault is touched) and the next deposit starts a fresh term.
+//!
+//! `withdraw_savings` pays from the available balance first. Dipping into the
+//! locked balance is an early withdrawal: it must be explicitly accepted and
+//! is charged the owner-configured penalty, which is paid to the configured
+//! insurance pool address. Without that configuration, locked savings cannot
+//! be withdrawn before `unlock_at`. Setting the insurance pool to this
+//! contract's own address keeps penalties in the protocol treasury.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::payroll::transfer_from_contract;
+use crate::rounding::{apply_bps, RoundingMode};
+use crate::storage::{PayrollError, StorageKey};
+use crate::treasury;
+use crate::validation;
+
+/// Largest share of a payout an employee may save (50%).
+pub const MAX_SAVINGS_BPS: u32 = 5_000;
+
+/// Longest accepted lock duration (~5 years).
+pub const MAX_SAVINGS_LOCK_SECONDS: u64 = 5 * 365 * 86_400;
+
+const BPS_DENOMINATOR: i128 = 10_000;
+
+#[contracttype]
+#[derive(Clone)]
+enum SavingsKey {
+    /// Savings plan of an employee -> SavingsPlan
+    Plan(Address),
+    /// Vault of an employee for a token -> SavingsVault
+    Vault(Address, Address),
+    /// Early-withdrawal penalty configuration -> SavingsPenaltyConfig
+    PenaltyConfig,
+}
+
+/// An employee's savings election.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavingsPlan {
+    /// Share of each payout to save, in basis points.
+    pub bps: u32,
+    pub lock_duration: u64,
+}
+
+/// Savings held for one employee in one token.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SavingsVault {
+    pub available: i128,
+    pub locked: i128,
+    /// End of the current term; meaningless while `locked` is zero.
+    pub unlock_at: u64,
+}
+
+/// Penalty charged on early withdrawals and where it is paid.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavingsPenaltyConfig {
+    pub penalty_bps: u32,
+    pub insurance_pool: Address,
+}
+
+/// Sets the employee's savings plan, replacing any existing one. The new lock
+/// duration applies to terms started after this call.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `bps` is zero or above [`MAX_SAVINGS_BPS`],
+///   or `lock_duration` is zero or above [`MAX_SAVINGS_LOCK_SECONDS`]
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn set_savings_plan(
+    env: &Env,
+    employee: &Address,
+    bps: u32,
+    lock_duration: u64,
+) -> Result<(), PayrollError> {
+    employee.require_auth();
+    store_savings_plan(env, employee, bps, lock_duration)
+}
+
+/// [`set_savings_plan`] without the authentication check, for callers that
+/// authorized the employee another way.
+pub(crate) fn store_savings_plan(
+    env: &Env,
+    employee: &Address,
+    bps: u32,
+    lock_duration: u64,
+) -> Result<(), PayrollError> {
+    if bps == 0
+        || bps > MAX_SAVINGS_BPS
+        || lock_duration == 0
+        || lock_duration > MAX_SAVINGS_LOCK_SECONDS
+    {
+        return Err(PayrollError::InvalidData);
+    }
+    env.storage().persistent().set(
+        &SavingsKey::Plan(employee.clone()),
+        &SavingsPlan { bps, lock_duration },
+    );
+    Ok(())
+}
+
+/// Stops saving from future payouts. Existing vault balances are unaffected.
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn cancel_savings_plan(env: &Env, employee: &Address) {
+    employee.require_auth();
+    remove_savings_plan(env, employee);
+}
+
+/// [`cancel_savings_plan`] without the authentication check.
+pub(crate) fn remove_savings_plan(env: &Env, employee: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&SavingsKey::Plan(employee.clone()));
+}
+
+/// Returns the employee's savings plan, if any.
+pub fn get_savings_plan(env: &Env, employee: &Address) -> Option<SavingsPlan> {
+    env.storage()
+        .persistent()
+        .get(&SavingsKey::Plan(employee.clone()))
+}
+
+/// Returns the employee's vault for `token`, with an elapsed term already
+/// moved into `available`.
+pub fn get_savings_vault(env: &Env, employee: &Address, token: &Address) -> SavingsVault {
+    let mut vault: SavingsVault = env
+        .storage()
+        .persistent()
+        .get(&SavingsKey::Vault(employee.clone(), token.clone()))
+        .unwrap_or_default();
+    if vault.locked > 0 && env.ledger().timestamp() >= vault.unlock_at {
+        vault.available += vault.locked;
+        vault.locked = 0;
+    }
+    vault
+}
+
+/// Configures the early-withdrawal penalty and the insurance pool it is paid
+/// to.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+/// * `PayrollError::InvalidData` - `penalty_bps` above 10000
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_savings_penalty(
+    env: &Env,
+    owner: &Address,
+    penalty_bps: u32,
+    insurance_pool: &Address,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    if i128::from(penalty_bps) > BPS_DENOMINATOR {
+        return Err(PayrollError::InvalidData);
+    }
+    env.storage().persistent().set(
+        &SavingsKey::PenaltyConfig,
+        &SavingsPenaltyConfig {
+            penalty_bps,
+            insurance_pool: insurance_pool.clone(),
+        },
+    );
+    Ok(())
+}
+
+/// Returns the early-withdrawal penalty configuration, if set.
+pub fn get_savings_penalty(env: &Env) -> Option<SavingsPenaltyConfig> {
+    env.storage().persistent().get(&SavingsKey::PenaltyConfig)
+}
+
+/// Withdraws `amount` of the employee's savings in `token`.
+///
+/// The available balance is used first. Any remainder comes out of the
+/// locked balance, which requires `accept_penalty`; the penalty on that part
+/// goes to the insurance pool and the employee receives the rest.
+///
+/// # Returns
+/// The amount transferred to the employee.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `amount` is not positive or exceeds the vault
+/// * `PayrollError::TimelockActive` - locked savings are needed but
+///   `accept_penalty` is false or no penalty is configured
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn withdraw_savings(
+    env: &Env,
+    employee: &Address,
+    token: &Address,
+    amount: i128,
+    accept_penalty: bool,
+) -> Result<i128, PayrollError> {
+    employee.require_auth();
+    withdraw_from_vault(env, employee, token, amount, accept_penalty)
+}
+
+/// [`withdraw_savings`] without the authentication check.
+pub(crate) fn withdraw_from_vault(
+    env: &Env,
+    employee: &Address,
+    token: &Address,
+    amount: i128,
+    accept_penalty: bool,
+) -> Result<i128, PayrollError> {
+    validation::amount(amount)?;
+    let mut vault = get_savings_vault(env, employee, token);
+    if amount > vault.available + vault.locked {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let from_available = amount.min(vault.available);
+    let early = amount - from_available;
+    let mut penalty = 0;
+    let mut pool = None;
+    if early > 0 {
+        let config = get_savings_penalty(env)
+            .filter(|_| accept_penalty)
+            .ok_or(PayrollError::TimelockActive)?;
+        penalty = early * i128::from(config.penalty_bps) / BPS_DENOMINATOR;
+        pool = Some(config.insurance_pool);
+    }
+
+    vault.available -= from_available;
+    vault.locked -= early;
+    env.storage()
+        .persistent()
+        .set(&SavingsKey::Vault(employee.clone(), token.clone()), &vault);
+
+    let payout = amount - penalty;
+    if let Some(pool) = pool.filter(|_| penalty > 0) {
+        if pool == env.current_contract_address() {
+            treasury::credit(env, token, penalty);
+        } else {
+            transfer_from_contract(env, token, &pool, penalty);
+        }
+    }
+    transfer_from_contract(env, token, employee, payout);
+    Ok(payout)
+}
+
+/// Credits the employee's savings share of `amount` in `token`, rounded with
+/// `mode`, to their vault.
+///
+/// # Returns
+/// The amount retained, which the caller must not transfer to the employee.
+pub(crate) fn retain_savings(
+    env: &Env,
+    employee: &Address,
+    token: &Address,
+    amount: i128,
+    mode: RoundingMode,
+) -> i128 {
+    let Some(plan) = get_savings_plan(env, employee) else {
+        return 0;
+    };
+    let saved = apply_bps(amount, plan.bps, mode);
+    if saved <= 0 {
+        return 0;
+    }
+    let mut vault = get_savings_vault(env, employee, token);
+    if vault.locked == 0 {
+        vault.unlock_at = env.ledger().timestamp().saturating_add(plan.lock_duration);
+    }
+    vault.locked += saved;
+    env.storage()
+        .persistent()
+        .set(&SavingsKey::Vault(employee.clone(), token.clone()), &vault);
+    saved
+}
@@ -0,0 +1,52 @@
+//! Per-category sequence numbers for indexers.
+//!
+//! Every significant state change takes the next number of its category, and
+//! the record or event describing the change carries it as `sequence`.
+//! Numbers start at 1 and increase by exactly one per change, so an indexer
+//! that has seen sequence `n` of a category and then sees `n + 2` knows it
+//! missed one, and can compare its last seen number with
+//! [`get_latest_sequence`] to decide whether it is up to date.
+//!
+//! The counters live in instance storage: they are bumped on every payout, and
+//! instance entries are already loaded and kept alive with the contract.
+//!
+//! | Category | Changes |
+//! |----------|---------|
+//! | `Payroll` | Agreement created, employee added, activated, paused, resumed, cancelled, grace period finalized; COLA salary adjustments |
+//! | `Disbursement` | Every payout: payroll claims (all claim paths, including confidential disbursements), time-based escrow claims, milestone claims, invoice payments and reimbursements |
+//! | `Incentive` | Vested bonuses claimed through a unified pay run |
+
+use soroban_sdk::{contracttype, Env};
+
+/// Kind of state change a sequence number counts.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SequenceCategory {
+    Payroll,
+    Disbursement,
+    Incentive,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum SequenceKey {
+    /// Last assigned number of a category -> u64
+    Latest(SequenceCategory),
+}
+
+/// Returns the last sequence number assigned in `category`, or 0 if none.
+pub fn get_latest_sequence(env: &Env, category: SequenceCategory) -> u64 {
+    env.storage()
+        .instance()
+        .get(&SequenceKey::Latest(category))
+        .unwrap_or(0)
+}
+
+/// Assigns and returns the next sequence number of `category`.
+pub(crate) fn next_sequence(env: &Env, category: SequenceCategory) -> u64 {
+    let sequence = get_latest_sequence(env, category) + 1;
+    env.storage()
+        .instance()
+        .set(&SequenceKey::Latest(category), &sequence);
+    sequence
+}
@@ -0,0 +1,551 @@
+//! Severance insurance backed by a shared insurance pool.
+//!
+//! An employer buys cover for the severance it owes one employee of an
+//! agreement. When the agreement is cancelled, the employee settles the
+//! cover: the severance is first drawn from the employer, up to its token
+//! balance and the allowance it granted this contract, and any shortfall is
+//! paid from the pool, up to the cover's `max_payout`. What the pool pays is
+//! recorded as a liability of the employer (subrogation), which it repays
+//! into the pool with `repay_severance_liability`.
+//!
+//! Premiums are priced per cover as
+//!
+//! ```text
+//! premium_bps = base_premium_bps * tier_pct / 100 * (10_000 + utilization_bps) / 10_000
+//! ```
+//!
+//! where `tier_pct` falls as the employer's reputation tier rises and
+//! `utilization_bps` is the pool's outstanding cover, including the new one,
+//! against its balance. Cover that would take utilization above 100% is
+//! refused, so every open cover is backed by the pool when it is bought. The
+//! premium is rounded with the employer's rounding policy (see
+//! [`crate::rounding`]).
+
+use soroban_sdk::{contracttype, token::Client as TokenClient, Address, Env};
+use webhook_interface::event_types;
+
+use crate::events::{
+    emit_severance_cover_purchased, emit_severance_liability_repaid, emit_severance_settled,
+    SeveranceCoverPurchasedEvent, SeveranceLiabilityRepaidEvent, SeveranceSettledEvent,
+};
+use crate::payroll::{get_agreement, get_agreement_employees, transfer_from_contract};
+use crate::reputation::{get_employer_reputation, ReputationTier};
+use crate::rounding::{apply_bps, get_rounding_policy};
+use crate::storage::{AgreementStatus, PayrollError, StorageKey};
+use crate::validation;
+use crate::webhooks::notify;
+
+/// Basis-point denominator for premiums, coverage and utilization.
+const BPS: i128 = 10_000;
+
+#[contracttype]
+#[derive(Clone)]
+enum SeveranceKey {
+    /// Insurance configuration -> SeveranceInsuranceConfig
+    Config,
+    /// Pool balance and open cover -> SeverancePool
+    Pool,
+    /// Cover of an employee under an agreement -> SeveranceCover
+    Cover(u128, Address),
+    /// Pool payouts an employer has yet to repay -> i128
+    Liability(Address),
+}
+
+/// Owner-set terms of the severance insurance product.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeveranceInsuranceConfig {
+    /// Token premiums, severance and pool payouts are paid in.
+    pub token: Address,
+    /// Premium for an unrated-equivalent employer on an empty pool, in basis
+    /// points of the severance amount.
+    pub base_premium_bps: u32,
+    /// Share of the severance amount the pool pays at most, in basis points.
+    pub coverage_bps: u32,
+}
+
+/// Funds held for severance insurance and the cover they back.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SeverancePool {
+    pub balance: i128,
+    /// Sum of `max_payout` over open covers.
+    pub exposure: i128,
+}
+
+/// Severance cover for one employee of an agreement.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeveranceCover {
+    pub agreement_id: u128,
+    pub employer: Address,
+    pub employee: Address,
+    /// Severance owed to the employee on termination.
+    pub severance_amount: i128,
+    /// Most the pool pays towards it.
+    pub max_payout: i128,
+    pub premium: i128,
+    /// Whether the cover was settled or released; it can then not be used
+    /// again.
+    pub closed: bool,
+}
+
+/// How a settled severance was funded.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeveranceSettlement {
+    pub from_employer: i128,
+    pub from_pool: i128,
+}
+
+fn require_owner(env: &Env, owner: &Address) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    Ok(())
+}
+
+fn config(env: &Env) -> Result<SeveranceInsuranceConfig, PayrollError> {
+    get_severance_insurance(env).ok_or(PayrollError::InvalidData)
+}
+
+fn set_pool(env: &Env, pool: &SeverancePool) {
+    env.storage().persistent().set(&SeveranceKey::Pool, pool);
+}
+
+fn cover_key(agreement_id: u128, employee: &Address) -> SeveranceKey {
+    SeveranceKey::Cover(agreement_id, employee.clone())
+}
+
+/// Premium multiplier of a reputation tier, in percent.
+fn tier_pct(tier: ReputationTier) -> i128 {
+    match tier {
+        ReputationTier::Unrated => 150,
+        ReputationTier::Bronze => 125,
+        ReputationTier::Silver => 100,
+        ReputationTier::Gold => 85,
+        ReputationTier::Platinum => 70,
+    }
+}
+
+/// Sets the severance insurance terms.
+///
+/// The token can only change while the pool is empty and no cover is open.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+/// * `PayrollError::InvalidData` - a basis-point value is zero or above
+///   10 000, or the token changes while the pool is in use
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_severance_insurance(
+    env: &Env,
+    owner: &Address,
+    token: &Address,
+    base_premium_bps: u32,
+    coverage_bps: u32,
+) -> Result<(), PayrollError> {
+    require_owner(env, owner)?;
+    validation::interval(base_premium_bps.into(), 1, BPS as u64)?;
+    validation::interval(coverage_bps.into(), 1, BPS as u64)?;
+    if let Some(current) = get_severance_insurance(env) {
+        if current.token != *token && get_severance_pool(env) != SeverancePool::default() {
+            return Err(PayrollError::InvalidData);
+        }
+    }
+    env.storage().persistent().set(
+        &SeveranceKey::Config,
+        &SeveranceInsuranceConfig {
+            token: token.clone(),
+            base_premium_bps,
+            coverage_bps,
+        },
+    );
+    Ok(())
+}
+
+/// Returns the severance insurance terms, if set.
+pub fn get_severance_insurance(env: &Env) -> Option<SeveranceInsuranceConfig> {
+    env.storage().persistent().get(&SeveranceKey::Config)
+}
+
+/// Returns the pool balance and open cover.
+pub fn get_severance_pool(env: &Env) -> SeverancePool {
+    env.storage()
+        .persistent()
+        .get(&SeveranceKey::Pool)
+        .unwrap_or_default()
+}
+
+/// Transfers `amount` of the insurance token from `funder` into the pool.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - insurance is not configured, or `amount`
+///   is not positive or above `MAX_AMOUNT`
+///
+/// # Access Control
+/// Requires `funder` authentication
+pub fn fund_severance_pool(env: &Env, funder: &Address, amount: i128) -> Result<(), PayrollError> {
+    funder.require_auth();
+    validation::amount(amount)?;
+    let config = config(env)?;
+    TokenClient::new(env, &config.token).transfer(funder, env.current_contract_address(), &amount);
+    let mut pool = get_severance_pool(env);
+    pool.balance += amount;
+    set_pool(env, &pool);
+    Ok(())
+}
+
+/// Returns the premium `employer` would pay to cover `severance_amount`
+/// now, and the pool's maximum payout for that cover.
+fn price(
+    env: &Env,
+    config: &SeveranceInsuranceConfig,
+    pool: &SeverancePool,
+    employer: &Address,
+    severance_amount: i128,
+) -> Result<(i128, i128), PayrollError> {
+    validation::amount(severance_amount)?;
+    let max_payout = bps_of(severance_amount, config.coverage_bps.into())?;
+    let exposure = pool.exposure + max_payout;
+    if pool.balance == 0 || exposure > pool.balance {
+        return Err(PayrollError::InsufficientEscrowBalance);
+    }
+    let utilization_bps =
+        exposure.checked_mul(BPS).ok_or(PayrollError::InvalidData)? / pool.balance;
+    let tier = get_employer_reputation(env, employer).tier;
+    let premium_bps =
+        i128::from(config.base_premium_bps) * tier_pct(tier) / 100 * (BPS + utilization_bps) / BPS;
+    // At most 3 * base_premium_bps (150% tier, 100% utilization), so the
+    // narrowing cannot truncate.
+    let premium = apply_bps(
+        severance_amount,
+        premium_bps as u32,
+        get_rounding_policy(env, employer),
+    );
+    Ok((premium, max_payout))
+}
+
+/// Returns `bps` basis points of `amount`.
+fn bps_of(amount: i128, bps: i128) -> Result<i128, PayrollError> {
+    amount
+        .checked_mul(bps)
+        .map(|scaled| scaled / BPS)
+        .ok_or(PayrollError::InvalidData)
+}
+
+/// Returns the premium `employer` would pay now to cover `severance_amount`.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - insurance is not configured, or
+///   `severance_amount` is not positive or above `MAX_AMOUNT`
+/// * `PayrollError::InsufficientEscrowBalance` - the pool cannot back the
+///   cover
+pub fn quote_severance_premium(
+    env: &Env,
+    employer: &Address,
+    severance_amount: i128,
+) -> Result<i128, PayrollError> {
+    let config = config(env)?;
+    let (premium, _) = price(
+        env,
+        &config,
+        &get_severance_pool(env),
+        employer,
+        severance_amount,
+    )?;
+    Ok(premium)
+}
+
+/// Buys severance cover for `employee` of `agreement_id`, paying the premium
+/// into the pool. Returns the premium paid.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - no such agreement
+/// * `PayrollError::Unauthorized` - caller is not the agreement's employer
+/// * `PayrollError::NoEmployee` - `employee` is not on the agreement
+/// * `PayrollError::InvalidData` - insurance is not configured, the
+///   agreement is not `Created` or `Active`, the employee is already
+///   covered, or `severance_amount` is not positive or above `MAX_AMOUNT`
+/// * `PayrollError::InsufficientEscrowBalance` - the pool cannot back the
+///   cover
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn buy_severance_cover(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    employee: &Address,
+    severance_amount: i128,
+) -> Result<i128, PayrollError> {
+    employer.require_auth();
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    if !matches!(
+        agreement.status,
+        AgreementStatus::Created | AgreementStatus::Active
+    ) {
+        return Err(PayrollError::InvalidData);
+    }
+    if !get_agreement_employees(env, agreement_id).contains(employee) {
+        return Err(PayrollError::NoEmployee);
+    }
+    let key = cover_key(agreement_id, employee);
+    if env.storage().persistent().has(&key) {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let config = config(env)?;
+    let mut pool = get_severance_pool(env);
+    let (premium, max_payout) = price(env, &config, &pool, employer, severance_amount)?;
+    if premium > 0 {
+        TokenClient::new(env, &config.token).transfer(
+            employer,
+            env.current_contract_address(),
+            &premium,
+        );
+    }
+    pool.balance += premium;
+    pool.exposure += max_payout;
+    set_pool(env, &pool);
+
+    env.storage().persistent().set(
+        &key,
+        &SeveranceCover {
+            agreement_id,
+            employer: employer.clone(),
+            employee: employee.clone(),
+            severance_amount,
+            max_payout,
+            premium,
+            closed: false,
+        },
+    );
+    emit_severance_cover_purchased(
+        env,
+        SeveranceCoverPurchasedEvent {
+            agreement_id,
+            employer: employer.clone(),
+            employee: employee.clone(),
+            severance_amount,
+            premium,
+        },
+    );
+    notify(
+        env,
+        event_types::INSURANCE_COVER_PURCHASED,
+        employer,
+        employee,
+        agreement_id,
+        max_payout,
+    );
+    Ok(premium)
+}
+
+/// Returns the severance cover of `employee` under `agreement_id`, if any.
+pub fn get_severance_cover(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+) -> Option<SeveranceCover> {
+    env.storage()
+        .persistent()
+        .get(&cover_key(agreement_id, employee))
+}
+
+fn open_cover(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+) -> Result<SeveranceCover, PayrollError> {
+    match get_severance_cover(env, agreement_id, employee) {
+        Some(cover) if !cover.closed => Ok(cover),
+        _ => Err(PayrollError::InvalidData),
+    }
+}
+
+/// Closes `cover` and frees its share of the pool's exposure.
+fn close(env: &Env, pool: &mut SeverancePool, mut cover: SeveranceCover) {
+    pool.exposure -= cover.max_payout;
+    set_pool(env, pool);
+    cover.closed = true;
+    env.storage()
+        .persistent()
+        .set(&cover_key(cover.agreement_id, &cover.employee), &cover);
+}
+
+/// Pays `employee` the severance covered under `agreement_id` once the
+/// agreement has been cancelled.
+///
+/// The employer pays first, up to its balance and the allowance it granted
+/// this contract. The pool pays the shortfall, up to the cover's
+/// `max_payout`, and the amount is added to the employer's liability.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no open cover, or insurance is not
+///   configured
+/// * `PayrollError::AgreementNotFound` - no such agreement
+/// * `PayrollError::InvalidAgreementMode` - the agreement is not cancelled
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn settle_severance(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+) -> Result<SeveranceSettlement, PayrollError> {
+    employee.require_auth();
+    let cover = open_cover(env, agreement_id, employee)?;
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.status != AgreementStatus::Cancelled {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+    let config = config(env)?;
+    let token = TokenClient::new(env, &config.token);
+    let contract = env.current_contract_address();
+
+    let available = token
+        .balance(&cover.employer)
+        .min(token.allowance(&cover.employer, &contract));
+    let from_employer = available.clamp(0, cover.severance_amount);
+    if from_employer > 0 {
+        token.transfer_from(&contract, &cover.employer, employee, &from_employer);
+    }
+
+    let mut pool = get_severance_pool(env);
+    let from_pool = (cover.severance_amount - from_employer)
+        .min(cover.max_payout)
+        .min(pool.balance);
+    if from_pool > 0 {
+        pool.balance -= from_pool;
+        transfer_from_contract(env, &config.token, employee, from_pool);
+        let liability = get_severance_liability(env, &cover.employer) + from_pool;
+        env.storage()
+            .persistent()
+            .set(&SeveranceKey::Liability(cover.employer.clone()), &liability);
+    }
+    emit_severance_settled(
+        env,
+        SeveranceSettledEvent {
+            agreement_id,
+            employer: cover.employer.clone(),
+            employee: employee.clone(),
+            from_employer,
+            from_pool,
+        },
+    );
+    notify(
+        env,
+        event_types::INSURANCE_CLAIM_SETTLED,
+        &cover.employer,
+        employee,
+        agreement_id,
+        from_employer + from_pool,
+    );
+    close(env, &mut pool, cover);
+    Ok(SeveranceSettlement {
+        from_employer,
+        from_pool,
+    })
+}
+
+/// Releases the cover of `employee` once `agreement_id` has completed
+/// without a termination, freeing its share of the pool. The premium is not
+/// refunded.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no open cover
+/// * `PayrollError::AgreementNotFound` - no such agreement
+/// * `PayrollError::InvalidAgreementMode` - the agreement is not completed
+pub fn release_severance_cover(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+) -> Result<(), PayrollError> {
+    let cover = open_cover(env, agreement_id, employee)?;
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.status != AgreementStatus::Completed {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+    notify(
+        env,
+        event_types::INSURANCE_COVER_RELEASED,
+        &cover.employer,
+        employee,
+        agreement_id,
+        cover.max_payout,
+    );
+    close(env, &mut get_severance_pool(env), cover);
+    Ok(())
+}
+
+/// Returns the pool payouts `employer` has yet to repay.
+pub fn get_severance_liability(env: &Env, employer: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&SeveranceKey::Liability(employer.clone()))
+        .unwrap_or(0)
+}
+
+/// Repays up to `amount` of the employer's liability into the pool. Returns
+/// the liability left.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - insurance is not configured, `amount` is
+///   not positive or above `MAX_AMOUNT`, or the employer owes nothing
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn repay_severance_liability(
+    env: &Env,
+    employer: &Address,
+    amount: i128,
+) -> Result<i128, PayrollError> {
+    employer.require_auth();
+    validation::amount(amount)?;
+    let config = config(env)?;
+    let liability = get_severance_liability(env, employer);
+    if liability == 0 {
+        return Err(PayrollError::InvalidData);
+    }
+    let repaid = amount.min(liability);
+    TokenClient::new(env, &config.token).transfer(
+        employer,
+        env.current_contract_address(),
+        &repaid,
+    );
+    let mut pool = get_severance_pool(env);
+    pool.balance += repaid;
+    set_pool(env, &pool);
+
+    let remaining = liability - repaid;
+    let key = SeveranceKey::Liability(employer.clone());
+    if remaining == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &remaining);
+    }
+    emit_severance_liability_repaid(
+        env,
+        SeveranceLiabilityRepaidEvent {
+            employer: employer.clone(),
+            amount: repaid,
+            remaining,
+        },
+    );
+    notify(
+        env,
+        event_types::INSURANCE_LIABILITY_REPAID,
+        employer,
+        employer,
+        0,
+        repaid,
+    );
+    Ok(remaining)
+}
@@ -0,0 +1,214 @@
+//! Employer service-level agreements (SLAs) on payment punctuality.
+//!
+//! An employer commits to paying at least `target_on_time_bps` of its payroll
+//! periods on time, judged over consecutive windows of `window_seconds`
+//! counted from when the SLA was set. Every payout feeds the current window
+//! with the on-time and late periods it covers, by the same due-date rules
+//! as employer reputation (see [`crate::metrics`]).
+//!
+//! A window is evaluated once it has ended, on the next payout or when anyone
+//! calls [`check_sla`]. A window with payouts below target is a breach; one
+//! meeting it ends the breach streak, and windows without payouts are
+//! skipped. After `breach_windows` consecutive breaches the contract raises
+//! an alert: it emits `SlaBreachedEvent`, takes `penalty_points` off the
+//! employer's reputation score and raises the payroll pre-funding the
+//! employer must hold by `prefunding_step` periods. The streak then starts
+//! over.
+//!
+//! Penalties and the pre-funding requirement are kept when the SLA is
+//! replaced. A payroll agreement can only be activated while its escrow
+//! covers the required number of periods of its salaries.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::events::{emit_sla_breached, SlaBreachedEvent};
+use crate::storage::PayrollError;
+use crate::validation;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+#[contracttype]
+#[derive(Clone)]
+enum SlaKey {
+    /// SLA of an employer -> SlaConfig
+    Config(Address),
+    /// Tracking state of an employer -> SlaStatus
+    Status(Address),
+}
+
+/// An employer's punctuality commitment and what breaching it costs.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SlaConfig {
+    /// Share of periods to pay on time, in basis points.
+    pub target_on_time_bps: u32,
+    /// Length of an evaluation window in seconds.
+    pub window_seconds: u64,
+    /// Consecutive breached windows that raise an alert.
+    pub breach_windows: u32,
+    /// Reputation points deducted per alert.
+    pub penalty_points: u32,
+    /// Pre-funding periods added per alert; 0 leaves pre-funding unchanged.
+    pub prefunding_step: u32,
+}
+
+/// Punctuality tracked against an employer's SLA.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SlaStatus {
+    /// When the SLA was last set; windows are counted from here.
+    pub started_at: u64,
+    /// Index of the window being filled.
+    pub window: u64,
+    /// On-time periods paid in that window.
+    pub on_time: u32,
+    /// Late periods paid in that window.
+    pub late: u32,
+    pub consecutive_breaches: u32,
+    pub alerts: u32,
+    /// Reputation points deducted so far.
+    pub penalty_points: u32,
+    /// Periods of salary a payroll agreement's escrow must cover to activate.
+    pub required_prefunding_periods: u32,
+}
+
+/// Sets the employer's SLA, replacing any previous one. Tracking restarts
+/// with a new window; accumulated penalties and pre-funding are kept.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `target_on_time_bps` is zero or above
+///   10_000, `window_seconds` is zero or above `MAX_DURATION_SECONDS`, or
+///   `breach_windows` is zero
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn set_sla_config(
+    env: &Env,
+    employer: &Address,
+    config: SlaConfig,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    if config.target_on_time_bps == 0
+        || u64::from(config.target_on_time_bps) > BPS_DENOMINATOR
+        || config.breach_windows == 0
+    {
+        return Err(PayrollError::InvalidData);
+    }
+    validation::interval(config.window_seconds, 1, validation::MAX_DURATION_SECONDS)?;
+
+    let status = SlaStatus {
+        started_at: env.ledger().timestamp(),
+        window: 0,
+        on_time: 0,
+        late: 0,
+        consecutive_breaches: 0,
+        ..get_sla_status(env, employer)
+    };
+    env.storage()
+        .persistent()
+        .set(&SlaKey::Config(employer.clone()), &config);
+    set_status(env, employer, &status);
+    Ok(())
+}
+
+/// Returns the employer's SLA, if set.
+pub fn get_sla_config(env: &Env, employer: &Address) -> Option<SlaConfig> {
+    env.storage()
+        .persistent()
+        .get(&SlaKey::Config(employer.clone()))
+}
+
+/// Returns the employer's SLA tracking state as last stored; windows that
+/// have ended since are evaluated by [`check_sla`].
+pub fn get_sla_status(env: &Env, employer: &Address) -> SlaStatus {
+    env.storage()
+        .persistent()
+        .get(&SlaKey::Status(employer.clone()))
+        .unwrap_or_default()
+}
+
+fn set_status(env: &Env, employer: &Address, status: &SlaStatus) {
+    env.storage()
+        .persistent()
+        .set(&SlaKey::Status(employer.clone()), status);
+}
+
+/// Evaluates the employer's ended windows and returns the updated status.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - the employer has no SLA
+///
+/// # Access Control
+/// Permissionless, so a keeper can raise alerts without waiting for a payout.
+pub fn check_sla(env: &Env, employer: &Address) -> Result<SlaStatus, PayrollError> {
+    let config = get_sla_config(env, employer).ok_or(PayrollError::InvalidData)?;
+    let mut status = get_sla_status(env, employer);
+    roll(env, employer, &config, &mut status);
+    set_status(env, employer, &status);
+    Ok(status)
+}
+
+/// Adds the periods of one payout to the employer's current SLA window. No-op
+/// without an SLA.
+pub(crate) fn record_sla_periods(env: &Env, employer: &Address, on_time: u32, late: u32) {
+    let Some(config) = get_sla_config(env, employer) else {
+        return;
+    };
+    let mut status = get_sla_status(env, employer);
+    roll(env, employer, &config, &mut status);
+    status.on_time = status.on_time.saturating_add(on_time);
+    status.late = status.late.saturating_add(late);
+    set_status(env, employer, &status);
+}
+
+/// Reputation points the employer has lost to SLA alerts.
+pub(crate) fn sla_penalty_points(env: &Env, employer: &Address) -> u32 {
+    get_sla_status(env, employer).penalty_points
+}
+
+/// Periods of salary a payroll agreement of the employer must hold in escrow
+/// to activate.
+pub(crate) fn required_prefunding_periods(env: &Env, employer: &Address) -> u32 {
+    get_sla_status(env, employer).required_prefunding_periods
+}
+
+/// Closes the window being filled if it has ended, and raises an alert when
+/// it completes a breach streak.
+fn roll(env: &Env, employer: &Address, config: &SlaConfig, status: &mut SlaStatus) {
+    let current =
+        env.ledger().timestamp().saturating_sub(status.started_at) / config.window_seconds;
+    if current <= status.window {
+        return;
+    }
+    let periods = u64::from(status.on_time) + u64::from(status.late);
+    // Windows without payouts are skipped
+    if let Some(on_time_bps) = (u64::from(status.on_time) * BPS_DENOMINATOR).checked_div(periods) {
+        if on_time_bps >= u64::from(config.target_on_time_bps) {
+            status.consecutive_breaches = 0;
+        } else {
+            status.consecutive_breaches += 1;
+            if status.consecutive_breaches >= config.breach_windows {
+                status.consecutive_breaches = 0;
+                status.alerts = status.alerts.saturating_add(1);
+                status.penalty_points = status.penalty_points.saturating_add(config.penalty_points);
+                status.required_prefunding_periods = status
+                    .required_prefunding_periods
+                    .saturating_add(config.prefunding_step);
+                emit_sla_breached(
+                    env,
+                    SlaBreachedEvent {
+                        employer: employer.clone(),
+                        window: status.window,
+                        // At most BPS_DENOMINATOR, so the narrowing cannot truncate.
+                        on_time_bps: on_time_bps as u32,
+                        alerts: status.alerts,
+                        required_prefunding_periods: status.required_prefunding_periods,
+                    },
+                );
+            }
+        }
+    }
+    status.window = current;
+    status.on_time = 0;
+    status.late = 0;
+}
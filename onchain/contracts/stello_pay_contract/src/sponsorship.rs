@@ -0,0 +1,249 @@
+//! Employer-sponsored fees for relayed payroll withdrawals.
+//!
+//! An employer funds a sponsorship pool and sets a [`SponsorshipPolicy`].
+//! A relayer that submits an employee's signed `ClaimPayroll` request with
+//! [`sponsored_withdraw`] is paid back its fee from the pool of the
+//! agreement's employer, up to the policy's `max_fee`.
+//!
+//! To keep a relayer, or an employee colluding with one, from draining the
+//! pool, each employee gets at most `max_per_period` sponsored withdrawals
+//! per `period`. Periods are fixed windows of `period` seconds counted from
+//! timestamp zero. Past the cap the request can still be relayed, unsponsored,
+//! through `execute_signed_action`.
+
+use soroban_sdk::{contracttype, token::Client as TokenClient, Address, BytesN, Env};
+
+use crate::events::{emit_withdrawal_sponsored, WithdrawalSponsoredEvent};
+use crate::meta_tx::{execute_signed_action, SignedAction, SignedRequest};
+use crate::payroll::{get_agreement, transfer_from_contract};
+use crate::storage::PayrollError;
+use crate::validation;
+
+#[contracttype]
+#[derive(Clone)]
+enum SponsorshipKey {
+    /// Sponsorship policy of an employer -> SponsorshipPolicy
+    Policy(Address),
+    /// Sponsorship pool of an employer, in the policy token -> i128
+    Pool(Address),
+    /// Sponsored withdrawals `(employer, employee)` -> SponsorshipUsage
+    Usage(Address, Address),
+}
+
+/// An employer's terms for sponsoring relayed withdrawals.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SponsorshipPolicy {
+    /// Token fees are paid back in.
+    pub token: Address,
+    /// Largest fee paid back per withdrawal.
+    pub max_fee: i128,
+    /// Sponsored withdrawals per employee per period.
+    pub max_per_period: u32,
+    /// Period length in seconds.
+    pub period: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct SponsorshipUsage {
+    /// Index of the period `count` applies to.
+    window: u64,
+    count: u32,
+}
+
+/// Returns the employer's sponsorship policy, if set.
+pub fn get_sponsorship_policy(env: &Env, employer: &Address) -> Option<SponsorshipPolicy> {
+    env.storage()
+        .persistent()
+        .get(&SponsorshipKey::Policy(employer.clone()))
+}
+
+/// Returns the balance of the employer's sponsorship pool.
+pub fn get_sponsorship_pool(env: &Env, employer: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&SponsorshipKey::Pool(employer.clone()))
+        .unwrap_or(0)
+}
+
+fn set_pool(env: &Env, employer: &Address, balance: i128) {
+    env.storage()
+        .persistent()
+        .set(&SponsorshipKey::Pool(employer.clone()), &balance);
+}
+
+fn load_policy(env: &Env, employer: &Address) -> Result<SponsorshipPolicy, PayrollError> {
+    get_sponsorship_policy(env, employer).ok_or(PayrollError::InvalidData)
+}
+
+/// Sets the employer's sponsorship policy, replacing any previous one.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `max_fee` is not positive or above
+///   `MAX_AMOUNT`, `max_per_period` is zero, `period` is zero or above
+///   `MAX_DURATION_SECONDS`, or the token changes while the pool holds funds
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn set_sponsorship_policy(
+    env: &Env,
+    employer: &Address,
+    policy: SponsorshipPolicy,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    validation::amount(policy.max_fee)?;
+    validation::interval(policy.period, 1, validation::MAX_DURATION_SECONDS)?;
+    if policy.max_per_period == 0 {
+        return Err(PayrollError::InvalidData);
+    }
+    if let Some(current) = get_sponsorship_policy(env, employer) {
+        if current.token != policy.token && get_sponsorship_pool(env, employer) > 0 {
+            return Err(PayrollError::InvalidData);
+        }
+    }
+    env.storage()
+        .persistent()
+        .set(&SponsorshipKey::Policy(employer.clone()), &policy);
+    Ok(())
+}
+
+/// Adds `amount` of the policy token to the employer's sponsorship pool.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no policy is set, or `amount` is not
+///   positive or above `MAX_AMOUNT`
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn fund_sponsorship_pool(
+    env: &Env,
+    employer: &Address,
+    amount: i128,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    let policy = load_policy(env, employer)?;
+    validation::amount(amount)?;
+    let balance = get_sponsorship_pool(env, employer)
+        .checked_add(amount)
+        .ok_or(PayrollError::InvalidData)?;
+    TokenClient::new(env, &policy.token).transfer(
+        employer,
+        env.current_contract_address(),
+        &amount,
+    );
+    set_pool(env, employer, balance);
+    Ok(())
+}
+
+/// Returns `amount` from the employer's sponsorship pool.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no policy is set, or `amount` is not
+///   positive or above `MAX_AMOUNT`
+/// * `PayrollError::InsufficientEscrowBalance` - the pool holds less
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn withdraw_sponsorship_pool(
+    env: &Env,
+    employer: &Address,
+    amount: i128,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    let policy = load_policy(env, employer)?;
+    validation::amount(amount)?;
+    let balance = get_sponsorship_pool(env, employer);
+    if balance < amount {
+        return Err(PayrollError::InsufficientEscrowBalance);
+    }
+    set_pool(env, employer, balance - amount);
+    transfer_from_contract(env, &policy.token, employer, amount);
+    Ok(())
+}
+
+/// Returns how many of the employee's withdrawals the employer has sponsored
+/// in the current period.
+pub fn get_sponsored_withdrawals(env: &Env, employer: &Address, employee: &Address) -> u32 {
+    let Some(policy) = get_sponsorship_policy(env, employer) else {
+        return 0;
+    };
+    usage(env, employer, employee, &policy).count
+}
+
+fn usage(
+    env: &Env,
+    employer: &Address,
+    employee: &Address,
+    policy: &SponsorshipPolicy,
+) -> SponsorshipUsage {
+    let window = env.ledger().timestamp() / policy.period;
+    env.storage()
+        .persistent()
+        .get(&SponsorshipKey::Usage(employer.clone(), employee.clone()))
+        .filter(|usage: &SponsorshipUsage| usage.window == window)
+        .unwrap_or(SponsorshipUsage { window, count: 0 })
+}
+
+/// Runs an employee's signed `ClaimPayroll` request like
+/// `execute_signed_action`, then pays the relayer `fee` from the sponsorship
+/// pool of the agreement's employer.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - the action is not `ClaimPayroll`, the
+///   employer has no policy, or `fee` is not positive or above `max_fee`
+/// * `PayrollError::AgreementNotFound` - no such agreement
+/// * `PayrollError::RateLimited` - the employee used up this period's
+///   sponsored withdrawals
+/// * `PayrollError::InsufficientEscrowBalance` - the pool cannot cover `fee`
+/// * Any error of `execute_signed_action`
+///
+/// # Access Control
+/// Requires relayer authentication and a valid employee signature
+pub fn sponsored_withdraw(
+    env: &Env,
+    relayer: &Address,
+    request: SignedRequest,
+    signature: BytesN<64>,
+    fee: i128,
+) -> Result<(), PayrollError> {
+    let SignedAction::ClaimPayroll(agreement_id, _) = request.action else {
+        return Err(PayrollError::InvalidData);
+    };
+    let employer = get_agreement(env, agreement_id)
+        .ok_or(PayrollError::AgreementNotFound)?
+        .employer;
+    let policy = load_policy(env, &employer)?;
+    if fee <= 0 || fee > policy.max_fee {
+        return Err(PayrollError::InvalidData);
+    }
+    let employee = request.employee.clone();
+    let mut usage = usage(env, &employer, &employee, &policy);
+    if usage.count >= policy.max_per_period {
+        return Err(PayrollError::RateLimited);
+    }
+    let balance = get_sponsorship_pool(env, &employer);
+    if balance < fee {
+        return Err(PayrollError::InsufficientEscrowBalance);
+    }
+
+    execute_signed_action(env, relayer, request, signature)?;
+
+    usage.count += 1;
+    env.storage().persistent().set(
+        &SponsorshipKey::Usage(employer.clone(), employee.clone()),
+        &usage,
+    );
+    set_pool(env, &employer, balance - fee);
+    transfer_from_contract(env, &policy.token, relayer, fee);
+    emit_withdrawal_sponsored(
+        env,
+        WithdrawalSponsoredEvent {
+            employer,
+            employee,
+            relayer: relayer.clone(),
+            fee,
+        },
+    );
+    Ok(())
+}
@@ -0,0 +1,109 @@
+//! Protocol-wide statistics.
+//!
+//! Explorers and the project website show ecosystem traction from
+//! [`ProtocolStats`] instead of indexing every event. The totals are kept up
+//! to date as agreements are created and change status, and as payments go
+//! out, so `get_protocol_stats` is a single read:
+//!
+//! - an employer counts once, when its first payroll or escrow agreement is
+//!   created;
+//! - an agreement counts as an active payroll while its status is `Active`;
+//! - every disbursement (salary, escrow and milestone claims, invoices and
+//!   reimbursements) adds the gross amount to the total of the token paid.
+//!
+//! Incentives live in the linked bonus_system contract, which counts their
+//! claimed payouts itself; the getter reads that count from it.
+
+use soroban_sdk::{contracttype, Address, Env, Map};
+use stellopay_interfaces::bonus::BonusSystemClient;
+
+use crate::compensation::get_bonus_contract;
+use crate::storage::extend_persistent_ttl;
+
+#[contracttype]
+#[derive(Clone)]
+enum StatsKey {
+    /// Running protocol totals -> ProtocolStats
+    Totals,
+}
+
+/// Protocol-wide totals, as returned by `get_protocol_stats`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolStats {
+    /// Employers that created at least one agreement.
+    pub total_employers: u32,
+    /// Agreements currently in `Active` status.
+    pub active_payrolls: u32,
+    /// Gross amount disbursed, per token.
+    pub disbursed: Map<Address, i128>,
+    /// Incentive payouts claimed from the linked bonus contract; `None`
+    /// without one or when it cannot be read.
+    pub incentives_claimed: Option<u64>,
+}
+
+fn read(env: &Env) -> ProtocolStats {
+    env.storage()
+        .persistent()
+        .get(&StatsKey::Totals)
+        .unwrap_or(ProtocolStats {
+            total_employers: 0,
+            active_payrolls: 0,
+            disbursed: Map::new(env),
+            incentives_claimed: None,
+        })
+}
+
+fn update(env: &Env, change: impl FnOnce(&mut ProtocolStats)) {
+    let mut stats = read(env);
+    change(&mut stats);
+    env.storage().persistent().set(&StatsKey::Totals, &stats);
+    extend_persistent_ttl(env, &StatsKey::Totals);
+}
+
+/// Counts a new employer.
+pub(crate) fn record_employer(env: &Env) {
+    update(env, |stats| {
+        stats.total_employers = stats.total_employers.saturating_add(1)
+    });
+}
+
+/// Counts an agreement entering `Active` status.
+pub(crate) fn record_activated(env: &Env) {
+    update(env, |stats| {
+        stats.active_payrolls = stats.active_payrolls.saturating_add(1)
+    });
+}
+
+/// Counts an agreement leaving `Active` status.
+pub(crate) fn record_deactivated(env: &Env) {
+    update(env, |stats| {
+        stats.active_payrolls = stats.active_payrolls.saturating_sub(1)
+    });
+}
+
+/// Adds a disbursement of `amount` of `token`.
+pub(crate) fn record_disbursement(env: &Env, token: &Address, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    update(env, |stats| {
+        let total = stats.disbursed.get(token.clone()).unwrap_or(0);
+        stats
+            .disbursed
+            .set(token.clone(), total.saturating_add(amount));
+    });
+}
+
+/// Returns the protocol totals, with the claimed incentive payouts read from
+/// the linked bonus contract.
+pub fn get_protocol_stats(env: &Env) -> ProtocolStats {
+    let mut stats = read(env);
+    stats.incentives_claimed = get_bonus_contract(env).and_then(|bonus| {
+        BonusSystemClient::new(env, &bonus)
+            .try_get_total_claimed_payouts()
+            .ok()
+            .and_then(Result::ok)
+    });
+    stats
+}
@@ -1,4 +1,8 @@
-use soroban_sdk::{contracterror, contracttype, Address, Env, Vec};
+use soroban_sdk::{
+    contracterror, contracttype, Address, Env, IntoVal, String, TryFromVal, Val, Vec,
+};
+
+pub use stellopay_interfaces::agreement::AgreementMode;
 
 /// Maximum caller-supplied batch size accepted by batch entrypoints.
 ///
@@ -9,6 +13,13 @@ use soroban_sdk::{contracterror, contracttype, Address, Env, Vec};
 /// late Soroban resource exhaustion after partial state changes.
 pub const MAX_BATCH_SIZE: u32 = 20;
 
+/// Largest accepted `period_seconds` for a new agreement (~136 years).
+///
+/// Period counts (`num_periods`, claimed periods) are tracked as `u32`. Capping
+/// the period length at `u32::MAX` seconds keeps durations derived from them
+/// (e.g. `period_seconds * num_periods`) comfortably inside `u64`.
+pub const MAX_PERIOD_SECONDS: u64 = u32::MAX as u64;
+
 /// Number of ledgers below which a long-lived persistent entry is bumped.
 ///
 /// Under Soroban's state-archival model, persistent entries that are not bumped
@@ -45,6 +56,76 @@ where
     }
 }
 
+/// TTL (in ledgers) given to temporary entries when they are written.
+///
+/// ~1 day at 5s/ledger: long enough for a keeper to resume multi-call work,
+/// short enough that abandoned entries stop costing rent soon after.
+pub const TEMPORARY_TTL_LEDGERS: u32 = 17_280;
+
+/// Storage tier a key is meant to live in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageTier {
+    /// Long-lived state that must survive until explicitly removed; kept
+    /// alive with [`extend_persistent_ttl`].
+    Persistent,
+    /// Ephemeral state that may disappear once its TTL lapses without
+    /// affecting correctness (guards, restartable cursors).
+    Temporary,
+}
+
+/// Implemented by storage key types to declare the tier of each key, so
+/// reads and writes through [`tiered_get`] and friends cannot pick the wrong
+/// one.
+pub trait TieredKey: IntoVal<Env, Val> {
+    fn tier(&self) -> StorageTier;
+}
+
+/// Reads `key` from its declared tier.
+pub fn tiered_get<K: TieredKey, V: TryFromVal<Env, Val>>(env: &Env, key: &K) -> Option<V> {
+    match key.tier() {
+        StorageTier::Persistent => env.storage().persistent().get(key),
+        StorageTier::Temporary => env.storage().temporary().get(key),
+    }
+}
+
+/// Returns whether `key` exists in its declared tier.
+pub fn tiered_has<K: TieredKey>(env: &Env, key: &K) -> bool {
+    match key.tier() {
+        StorageTier::Persistent => env.storage().persistent().has(key),
+        StorageTier::Temporary => env.storage().temporary().has(key),
+    }
+}
+
+/// Writes `key` to its declared tier. Temporary entries are given
+/// [`TEMPORARY_TTL_LEDGERS`] from the time of the write.
+pub fn tiered_set<K: TieredKey, V: IntoVal<Env, Val>>(env: &Env, key: &K, value: &V) {
+    match key.tier() {
+        StorageTier::Persistent => env.storage().persistent().set(key, value),
+        StorageTier::Temporary => {
+            let storage = env.storage().temporary();
+            storage.set(key, value);
+            storage.extend_ttl(key, TEMPORARY_TTL_LEDGERS, TEMPORARY_TTL_LEDGERS);
+        }
+    }
+}
+
+/// Removes `key` from its declared tier.
+pub fn tiered_remove<K: TieredKey>(env: &Env, key: &K) {
+    match key.tier() {
+        StorageTier::Persistent => env.storage().persistent().remove(key),
+        StorageTier::Temporary => env.storage().temporary().remove(key),
+    }
+}
+
+/// Bumps the TTL of the contract instance (code and instance storage) using the
+/// same threshold and target as [`extend_persistent_ttl`], so the contract does
+/// not expire ahead of the agreement records it keeps alive.
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(PERSISTENT_TTL_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Milestone {
@@ -131,16 +212,6 @@ impl Milestone {
     }
 }
 
-/// Operating mode for agreements
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub enum AgreementMode {
-    /// Escrow mode for freelance/contract work
-    Escrow,
-    /// Payroll mode for traditional employee payroll
-    Payroll,
-}
-
 /// Lifecycle states for agreements
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -210,6 +281,8 @@ pub enum StorageKey {
     NextAgreementId,
     /// List of agreement IDs for an employer
     EmployerAgreements(Address),
+    /// List of agreement IDs an employee is on, across all employers
+    EmployeeAgreements(Address),
     /// Dispute Status
     DisputeStatus(u128),
     DisputeRaisedAt(u128),
@@ -222,6 +295,8 @@ pub enum StorageKey {
     PendingPause,
     /// Pause approvals
     PauseApprovals,
+    /// Duration and reason of the active emergency pause, if any were given
+    EmergencyPauseTerms,
     /// Global admin allowed to update FX rates (e.g. an oracle contract)
     ExchangeRateAdmin,
     /// Optional max age (seconds) for using an FX rate. If set, any rate older
@@ -256,6 +331,15 @@ pub enum StorageKey {
     ReentrancyGuard,
 }
 
+impl TieredKey for StorageKey {
+    fn tier(&self) -> StorageTier {
+        match self {
+            StorageKey::ReentrancyGuard => StorageTier::Temporary,
+            _ => StorageTier::Persistent,
+        }
+    }
+}
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub enum DisputeStatus {
@@ -443,6 +527,13 @@ pub enum PayrollError {
     MilestoneAlreadyApprovedCannotReject = 46,
     /// Cannot reject a milestone that has already been claimed.
     MilestoneAlreadyClaimedCannotReject = 47,
+    /// No salary commitment is stored for the employee, or the revealed
+    /// amount and salt do not hash to it.
+    SalaryCommitmentMismatch = 48,
+    /// The employer has no cost-of-living adjustment policy.
+    ColaPolicyNotFound = 49,
+    /// The COLA policy is not due yet and no run is in progress.
+    ColaNotDue = 50,
 }
 
 /// Caps for how much a cancelled agreement's grace/dispute window may be extended on-chain.
@@ -469,6 +560,33 @@ pub struct EmergencyPause {
     pub timelock_end: Option<u64>,
 }
 
+/// Optional terms attached to the active emergency pause.
+///
+/// Kept under its own key rather than on `EmergencyPause` so pause records
+/// written before time-boxed pauses existed still decode after an upgrade.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmergencyPauseTerms {
+    /// Timestamp at which the pause lapses on its own, if it was time-boxed
+    pub resume_at: Option<u64>,
+    /// Human-readable reason supplied when pausing
+    pub reason: Option<String>,
+}
+
+/// Effective pause status for integrators, as returned by `get_pause_info`.
+///
+/// Unlike the raw `EmergencyPause` record, `is_paused` already accounts for a
+/// lapsed `resume_at`, so a time-boxed pause reads as unpaused once it expires.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PauseInfo {
+    pub is_paused: bool,
+    pub paused_at: Option<u64>,
+    pub paused_by: Option<Address>,
+    pub resume_at: Option<u64>,
+    pub reason: Option<String>,
+}
+
 /// Storage keys for the payroll claiming system.
 #[derive(Clone)]
 #[contracttype]
@@ -529,6 +647,12 @@ pub enum DataKey {
     ExchangeRate(Address, Address),
 }
 
+impl TieredKey for DataKey {
+    fn tier(&self) -> StorageTier {
+        StorageTier::Persistent
+    }
+}
+
 impl DataKey {
     /// Get the number of employees in an agreement
     pub fn get_employee_count(env: &Env, agreement_id: u128) -> u32 {
@@ -542,16 +666,21 @@ impl DataKey {
         env.storage().persistent().set(&key, &count);
     }
 
-    /// Get employee address at a specific index in an agreement
+    /// Get employee address at a specific index in an agreement, bumping its TTL
     pub fn get_employee(env: &Env, agreement_id: u128, employee_index: u32) -> Option<Address> {
         let key: DataKey = DataKey::AgreementEmployee(agreement_id, employee_index);
-        env.storage().persistent().get(&key)
+        let employee = env.storage().persistent().get(&key);
+        if employee.is_some() {
+            extend_persistent_ttl(env, &key);
+        }
+        employee
     }
 
-    /// Set employee address at a specific index in an agreement
+    /// Set employee address at a specific index in an agreement, bumping its TTL.
     pub fn set_employee(env: &Env, agreement_id: u128, employee_index: u32, employee: &Address) {
         let key: DataKey = DataKey::AgreementEmployee(agreement_id, employee_index);
         env.storage().persistent().set(&key, employee);
+        extend_persistent_ttl(env, &key);
     }
 
     /// Get salary per period for an employee at a specific index
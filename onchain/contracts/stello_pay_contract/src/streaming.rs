@@ -0,0 +1,351 @@
+//! Salary streaming with per-second accrual.
+//!
+//! Instead of paying per period, an employer can stream salary to an
+//! employee on one of their payroll agreements: pay accrues every second at a
+//! fixed rate, and the employee withdraws whatever has accrued whenever they
+//! like with `withdraw_streamed`.
+//!
+//! Streams are paid from the agreement's escrow, which they share with its
+//! periodic claims. A withdrawal pays whole seconds only, as far as the
+//! escrow covers them. Time it does not cover stays owed and is paid once the
+//! employer tops the escrow up with `fund_salary_stream`. Stopping a stream
+//! settles it: the employee is paid everything accrued, with any shortfall
+//! drawn from the employer. Whatever escrow is left stays with the agreement.
+//!
+//! Streamed pay is settled like any other payroll payout (see
+//! [`crate::payslip`]): garnishments, loan repayments and savings are taken
+//! from it and it gets a receipt and a payslip.
+//!
+//! An employee has at most one stream per agreement.
+
+use soroban_sdk::{contracttype, token::Client as TokenClient, Address, Env};
+
+use crate::disbursement_windows::ensure_disbursement_allowed;
+use crate::events::{
+    emit_stream_created, emit_stream_stopped, emit_stream_withdrawn, StreamCreatedEvent,
+    StreamStoppedEvent, StreamWithdrawnEvent,
+};
+use crate::pause_exemptions::{is_paused_for, PauseCategory};
+use crate::payroll::{get_agreement, transfer_from_contract};
+use crate::payslip::{settle_payout, PayoutGross};
+use crate::sequence::{next_sequence, SequenceCategory};
+use crate::stats;
+use crate::storage::{
+    extend_persistent_ttl, AgreementMode, AgreementStatus, DataKey, PayrollError,
+};
+use crate::validation;
+use crate::winddown::ensure_not_winding_down;
+use crate::yield_strategy;
+
+#[contracttype]
+#[derive(Clone)]
+enum StreamKey {
+    /// Salary stream `(agreement_id, employee)` -> SalaryStream
+    Stream(u128, Address),
+}
+
+/// A salary streamed to one employee from an agreement's escrow.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SalaryStream {
+    pub agreement_id: u128,
+    pub employer: Address,
+    pub employee: Address,
+    /// The agreement's token.
+    pub token: Address,
+    /// Salary accrued per second.
+    pub rate_per_second: i128,
+    pub started_at: u64,
+    /// Salary has been withdrawn for every second before this timestamp.
+    pub last_withdraw_at: u64,
+    /// Total withdrawn so far.
+    pub withdrawn: i128,
+}
+
+fn save(env: &Env, stream: &SalaryStream) {
+    let key = StreamKey::Stream(stream.agreement_id, stream.employee.clone());
+    env.storage().persistent().set(&key, stream);
+    extend_persistent_ttl(env, &key);
+}
+
+fn load(env: &Env, agreement_id: u128, employee: &Address) -> Result<SalaryStream, PayrollError> {
+    get_salary_stream(env, agreement_id, employee).ok_or(PayrollError::InvalidData)
+}
+
+/// Seconds accrued on the stream and not yet withdrawn.
+fn accrued_seconds(env: &Env, stream: &SalaryStream) -> u64 {
+    env.ledger()
+        .timestamp()
+        .saturating_sub(stream.last_withdraw_at)
+}
+
+/// Seconds `escrow` can pay for right now, and what they pay.
+fn withdrawable(env: &Env, stream: &SalaryStream, escrow: i128) -> (u64, i128) {
+    let funded = u64::try_from(escrow.max(0) / stream.rate_per_second).unwrap_or(u64::MAX);
+    let seconds = accrued_seconds(env, stream).min(funded);
+    (seconds, i128::from(seconds) * stream.rate_per_second)
+}
+
+/// Moves `amount` from the employer into the agreement's escrow.
+fn deposit(env: &Env, stream: &SalaryStream, amount: i128) -> Result<(), PayrollError> {
+    let escrow = DataKey::get_agreement_escrow_balance(env, stream.agreement_id, &stream.token);
+    let escrow = escrow
+        .checked_add(amount)
+        .ok_or(PayrollError::InvalidData)?;
+    TokenClient::new(env, &stream.token).transfer(
+        &stream.employer,
+        env.current_contract_address(),
+        &amount,
+    );
+    DataKey::set_agreement_escrow_balance(env, stream.agreement_id, &stream.token, escrow);
+    Ok(())
+}
+
+/// Settles a streamed payout of `gross` held by this contract and pays the
+/// employee the net amount.
+fn pay(env: &Env, stream: &SalaryStream, gross: i128) {
+    stats::record_disbursement(env, &stream.token, gross);
+    let sequence = next_sequence(env, SequenceCategory::Disbursement);
+    // Streams have no pay periods.
+    let net = settle_payout(
+        env,
+        stream.agreement_id,
+        &stream.employee,
+        &PayoutGross::new(&stream.token, gross),
+        0,
+        sequence,
+    );
+    if net > 0 {
+        transfer_from_contract(env, &stream.token, &stream.employee, net);
+    }
+}
+
+/// Starts streaming `rate_per_second` of the agreement's token to
+/// `employee`, moving `deposit` from the employer into the agreement's
+/// escrow to pay for it.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - the agreement does not exist
+/// * `PayrollError::Unauthorized` - caller is not the agreement's employer,
+///   or the employer is winding down
+/// * `PayrollError::InvalidAgreementMode` - the agreement is not a payroll
+/// * `PayrollError::InvalidData` - the agreement is settled, the employee
+///   already has a stream on it, the rate or deposit is not positive, or
+///   either is above `MAX_AMOUNT`
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn create_salary_stream(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    employee: &Address,
+    rate_per_second: i128,
+    deposit_amount: i128,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    ensure_not_winding_down(env, employer)?;
+    validation::amount(rate_per_second)?;
+    validation::amount(deposit_amount)?;
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    if agreement.mode != AgreementMode::Payroll {
+        return Err(PayrollError::InvalidAgreementMode);
+    }
+    if matches!(
+        agreement.status,
+        AgreementStatus::Completed | AgreementStatus::Cancelled
+    ) || get_salary_stream(env, agreement_id, employee).is_some()
+    {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let now = env.ledger().timestamp();
+    let stream = SalaryStream {
+        agreement_id,
+        employer: employer.clone(),
+        employee: employee.clone(),
+        token: agreement.token,
+        rate_per_second,
+        started_at: now,
+        last_withdraw_at: now,
+        withdrawn: 0,
+    };
+    deposit(env, &stream, deposit_amount)?;
+    save(env, &stream);
+    emit_stream_created(
+        env,
+        StreamCreatedEvent {
+            employee: employee.clone(),
+            agreement_id,
+            employer: employer.clone(),
+            token: stream.token,
+            rate_per_second,
+            deposit: deposit_amount,
+        },
+    );
+    Ok(())
+}
+
+/// Adds `amount` to the escrow that pays `employee`'s stream on the
+/// agreement.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - the employee has no stream on the
+///   agreement, or `amount` is not positive or above `MAX_AMOUNT`
+/// * `PayrollError::Unauthorized` - caller is not the stream's employer
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn fund_salary_stream(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    employee: &Address,
+    amount: i128,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    validation::amount(amount)?;
+    let stream = load(env, agreement_id, employee)?;
+    if stream.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    deposit(env, &stream, amount)
+}
+
+/// Pays `employee` the salary accrued on their stream on the agreement since
+/// the last withdrawal, as far as the escrow covers it. Returns the gross
+/// amount paid.
+///
+/// # Errors
+/// * `PayrollError::EmergencyPaused` - payroll claims are paused
+/// * `PayrollError::InvalidData` - the employee has no stream on the
+///   agreement
+/// * `PayrollError::NoPeriodsToClaim` - nothing has accrued
+/// * `PayrollError::InsufficientEscrowBalance` - the escrow held here does
+///   not cover a single second
+/// * Any error of the employer's disbursement windows
+///
+/// # Access Control
+/// Requires employee authentication
+pub fn withdraw_streamed(
+    env: &Env,
+    employee: &Address,
+    agreement_id: u128,
+) -> Result<i128, PayrollError> {
+    employee.require_auth();
+    if is_paused_for(env, PauseCategory::PayrollClaims) {
+        return Err(PayrollError::EmergencyPaused);
+    }
+    let mut stream = load(env, agreement_id, employee)?;
+    ensure_disbursement_allowed(env, &stream.employer)?;
+    let accrued = i128::from(accrued_seconds(env, &stream))
+        .checked_mul(stream.rate_per_second)
+        .ok_or(PayrollError::InvalidData)?;
+    if accrued == 0 {
+        return Err(PayrollError::NoPeriodsToClaim);
+    }
+    let liquid = yield_strategy::ensure_liquid(env, agreement_id, &stream.token, accrued);
+    let (seconds, amount) = withdrawable(env, &stream, liquid);
+    if amount == 0 {
+        return Err(PayrollError::InsufficientEscrowBalance);
+    }
+
+    // Effects before the payout's transfers.
+    let escrow = DataKey::get_agreement_escrow_balance(env, agreement_id, &stream.token);
+    DataKey::set_agreement_escrow_balance(env, agreement_id, &stream.token, escrow - amount);
+    stream.last_withdraw_at += seconds;
+    stream.withdrawn += amount;
+    save(env, &stream);
+
+    pay(env, &stream, amount);
+    emit_stream_withdrawn(
+        env,
+        StreamWithdrawnEvent {
+            employee: employee.clone(),
+            agreement_id,
+            employer: stream.employer,
+            amount,
+            withdrawn_through: stream.last_withdraw_at,
+        },
+    );
+    Ok(amount)
+}
+
+/// Stops `employee`'s stream on the agreement and pays them everything
+/// accrued, drawing any part the escrow held here does not cover from the
+/// employer. Returns the gross amount paid.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - the employee has no stream on the
+///   agreement
+/// * `PayrollError::Unauthorized` - caller is not the stream's employer
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn stop_salary_stream(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    employee: &Address,
+) -> Result<i128, PayrollError> {
+    employer.require_auth();
+    let stream = load(env, agreement_id, employee)?;
+    if stream.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+
+    let owed = i128::from(accrued_seconds(env, &stream))
+        .checked_mul(stream.rate_per_second)
+        .ok_or(PayrollError::InvalidData)?;
+    let liquid = yield_strategy::ensure_liquid(env, agreement_id, &stream.token, owed);
+    let from_escrow = owed.min(liquid.max(0));
+    let escrow = DataKey::get_agreement_escrow_balance(env, agreement_id, &stream.token);
+    DataKey::set_agreement_escrow_balance(env, agreement_id, &stream.token, escrow - from_escrow);
+    env.storage()
+        .persistent()
+        .remove(&StreamKey::Stream(agreement_id, employee.clone()));
+
+    if owed > from_escrow {
+        TokenClient::new(env, &stream.token).transfer(
+            employer,
+            env.current_contract_address(),
+            &(owed - from_escrow),
+        );
+    }
+    if owed > 0 {
+        pay(env, &stream, owed);
+    }
+    emit_stream_stopped(
+        env,
+        StreamStoppedEvent {
+            employee: employee.clone(),
+            agreement_id,
+            employer: employer.clone(),
+            paid: owed,
+        },
+    );
+    Ok(owed)
+}
+
+/// Returns the employee's salary stream on the agreement, if any.
+pub fn get_salary_stream(
+    env: &Env,
+    agreement_id: u128,
+    employee: &Address,
+) -> Option<SalaryStream> {
+    env.storage()
+        .persistent()
+        .get(&StreamKey::Stream(agreement_id, employee.clone()))
+}
+
+/// Returns what `withdraw_streamed` would pay the employee now, as far as
+/// the agreement's escrow covers it; 0 if they have no stream on it.
+pub fn get_streamed_balance(env: &Env, agreement_id: u128, employee: &Address) -> i128 {
+    get_salary_stream(env, agreement_id, employee).map_or(0, |stream| {
+        let escrow = DataKey::get_agreement_escrow_balance(env, agreement_id, &stream.token);
+        withdrawable(env, &stream, escrow).1
+    })
+}
@@ -0,0 +1,287 @@
+//! Employer organization hierarchy.
+//!
+//! A holding company can link the employers of its subsidiaries with
+//! `link_subsidiary`, which both sides sign. Subsidiaries can have
+//! subsidiaries of their own, and a group (a top-level parent with everything
+//! below it) holds at most [`MAX_GROUP_SIZE`] employers.
+//!
+//! Links give the parent:
+//! * consolidated views: the group's liability per token (escrow held and the
+//!   obligations coming due, as in `get_fx_exposure`) and group metrics;
+//! * the right to pause, resume and cancel the payrolls of any employer
+//!   below it. The parent can grant the same right to group operators, such
+//!   as a group payroll team, with `set_group_operator`.
+//!
+//! Either side can remove a link at any time, which ends the parent's rights
+//! over that subsidiary and everything below it.
+
+use soroban_sdk::{contracttype, Address, Env, Map, Vec};
+
+use crate::currency_groups::{get_fx_exposure, FxExposure};
+use crate::payroll::{
+    cancel_agreement_inner, get_agreement, get_employer_agreements, pause_agreement_inner,
+    resume_agreement_inner,
+};
+use crate::storage::{extend_persistent_ttl, AgreementStatus, DataKey, PayrollError};
+
+/// Most employers one group can hold, so consolidated views fit in one call.
+pub const MAX_GROUP_SIZE: u32 = 25;
+
+#[contracttype]
+#[derive(Clone)]
+enum SubsidiaryKey {
+    /// Parent of an employer -> Address
+    Parent(Address),
+    /// Direct subsidiaries of an employer -> Vec<Address>
+    Subsidiaries(Address),
+    /// Group operator granted by a parent `(parent, operator)` -> bool
+    Operator(Address, Address),
+}
+
+/// Consolidated metrics of an employer and every employer below it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupMetrics {
+    pub employers: u32,
+    pub agreements: u32,
+    /// Agreements in `Active` status.
+    pub active_agreements: u32,
+    pub employees: u32,
+    /// Amount paid out so far, per token.
+    pub paid: Map<Address, i128>,
+}
+
+/// Returns the employer's parent, if it is a subsidiary.
+pub fn get_parent(env: &Env, employer: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&SubsidiaryKey::Parent(employer.clone()))
+}
+
+/// Returns the employer's direct subsidiaries.
+pub fn get_subsidiaries(env: &Env, parent: &Address) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&SubsidiaryKey::Subsidiaries(parent.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+fn set_subsidiaries(env: &Env, parent: &Address, subsidiaries: &Vec<Address>) {
+    let key = SubsidiaryKey::Subsidiaries(parent.clone());
+    if subsidiaries.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, subsidiaries);
+        extend_persistent_ttl(env, &key);
+    }
+}
+
+/// Returns `employer` followed by every employer below it, parents before
+/// their subsidiaries.
+pub fn get_group_members(env: &Env, employer: &Address) -> Vec<Address> {
+    let mut members = Vec::from_array(env, [employer.clone()]);
+    let mut next = 0;
+    while next < members.len() {
+        let member = members.get_unchecked(next);
+        members.append(&get_subsidiaries(env, &member));
+        next += 1;
+    }
+    members
+}
+
+fn top_parent(env: &Env, employer: &Address) -> Address {
+    let mut top = employer.clone();
+    while let Some(parent) = get_parent(env, &top) {
+        top = parent;
+    }
+    top
+}
+
+/// Makes `child` a subsidiary of `parent`.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `child` is `parent` or one of its
+///   parents, `child` already has a parent, or the joined group would
+///   exceed [`MAX_GROUP_SIZE`]
+///
+/// # Access Control
+/// Requires authentication of both `parent` and `child`
+pub fn link_subsidiary(env: &Env, parent: &Address, child: &Address) -> Result<(), PayrollError> {
+    parent.require_auth();
+    child.require_auth();
+    if get_parent(env, child).is_some() {
+        return Err(PayrollError::InvalidData);
+    }
+    // `child` has no parent, so it is the top of its own group; linking it
+    // below one of its members would make a cycle.
+    let top = top_parent(env, parent);
+    if top == *child {
+        return Err(PayrollError::InvalidData);
+    }
+    let size = get_group_members(env, &top).len() + get_group_members(env, child).len();
+    if size > MAX_GROUP_SIZE {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let key = SubsidiaryKey::Parent(child.clone());
+    env.storage().persistent().set(&key, parent);
+    extend_persistent_ttl(env, &key);
+    let mut subsidiaries = get_subsidiaries(env, parent);
+    subsidiaries.push_back(child.clone());
+    set_subsidiaries(env, parent, &subsidiaries);
+    Ok(())
+}
+
+/// Removes `child` from its parent's group, together with its own
+/// subsidiaries.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `child` has no parent
+/// * `PayrollError::Unauthorized` - `caller` is neither `child` nor its parent
+///
+/// # Access Control
+/// Requires `caller` authentication
+pub fn unlink_subsidiary(env: &Env, caller: &Address, child: &Address) -> Result<(), PayrollError> {
+    caller.require_auth();
+    let parent = get_parent(env, child).ok_or(PayrollError::InvalidData)?;
+    if *caller != parent && caller != child {
+        return Err(PayrollError::Unauthorized);
+    }
+    env.storage()
+        .persistent()
+        .remove(&SubsidiaryKey::Parent(child.clone()));
+    let mut subsidiaries = get_subsidiaries(env, &parent);
+    if let Some(index) = subsidiaries.first_index_of(child) {
+        subsidiaries.remove(index);
+    }
+    set_subsidiaries(env, &parent, &subsidiaries);
+    Ok(())
+}
+
+/// Grants (`enabled = true`) or revokes the right of `operator` to operate
+/// the payrolls of every employer below `parent`.
+///
+/// # Access Control
+/// Requires `parent` authentication
+pub fn set_group_operator(env: &Env, parent: &Address, operator: &Address, enabled: bool) {
+    parent.require_auth();
+    let key = SubsidiaryKey::Operator(parent.clone(), operator.clone());
+    if enabled {
+        env.storage().persistent().set(&key, &true);
+        extend_persistent_ttl(env, &key);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+/// Whether `operator` was granted group operator rights by `parent`.
+pub fn is_group_operator(env: &Env, parent: &Address, operator: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&SubsidiaryKey::Operator(parent.clone(), operator.clone()))
+}
+
+/// Whether `caller` may operate the payrolls of `employer`: it is a parent of
+/// `employer` at any level, or a group operator of one.
+pub fn can_operate(env: &Env, caller: &Address, employer: &Address) -> bool {
+    let mut current = employer.clone();
+    while let Some(parent) = get_parent(env, &current) {
+        if parent == *caller || is_group_operator(env, &parent, caller) {
+            return true;
+        }
+        current = parent;
+    }
+    false
+}
+
+/// Group operation on a subsidiary's agreement.
+#[derive(Clone, Copy)]
+pub enum GroupAction {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+/// Pauses, resumes or cancels an agreement of an employer below `caller`
+/// in the hierarchy, with the same rules as when its employer does it.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - the agreement does not exist
+/// * `PayrollError::Unauthorized` - `caller` may not operate the employer
+///
+/// # Access Control
+/// Requires `caller` authentication
+pub fn operate_subsidiary_agreement(
+    env: &Env,
+    caller: &Address,
+    agreement_id: u128,
+    action: GroupAction,
+) -> Result<(), PayrollError> {
+    caller.require_auth();
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if !can_operate(env, caller, &agreement.employer) {
+        return Err(PayrollError::Unauthorized);
+    }
+    match action {
+        GroupAction::Pause => pause_agreement_inner(env, agreement_id, agreement),
+        GroupAction::Resume => resume_agreement_inner(env, agreement_id, agreement),
+        GroupAction::Cancel => cancel_agreement_inner(env, agreement_id, agreement),
+    }
+    Ok(())
+}
+
+/// Consolidated liability of `parent` and every employer below it, per
+/// token: escrow held and obligations for the next 1, 3 and 6 periods.
+pub fn get_group_liability(env: &Env, parent: &Address) -> Vec<FxExposure> {
+    let mut report: Vec<FxExposure> = Vec::new(env);
+    for member in get_group_members(env, parent).iter() {
+        for exposure in get_fx_exposure(env, &member).iter() {
+            match report.iter().position(|e| e.token == exposure.token) {
+                Some(pos) => {
+                    let mut entry = report.get_unchecked(pos as u32);
+                    entry.escrow_balance =
+                        entry.escrow_balance.saturating_add(exposure.escrow_balance);
+                    entry.next_1_period =
+                        entry.next_1_period.saturating_add(exposure.next_1_period);
+                    entry.next_3_periods =
+                        entry.next_3_periods.saturating_add(exposure.next_3_periods);
+                    entry.next_6_periods =
+                        entry.next_6_periods.saturating_add(exposure.next_6_periods);
+                    report.set(pos as u32, entry);
+                }
+                None => report.push_back(exposure),
+            }
+        }
+    }
+    report
+}
+
+/// Consolidated metrics of `parent` and every employer below it.
+pub fn get_group_metrics(env: &Env, parent: &Address) -> GroupMetrics {
+    let members = get_group_members(env, parent);
+    let mut metrics = GroupMetrics {
+        employers: members.len(),
+        agreements: 0,
+        active_agreements: 0,
+        employees: 0,
+        paid: Map::new(env),
+    };
+    for member in members.iter() {
+        for agreement_id in get_employer_agreements(env, &member).iter() {
+            let Some(agreement) = get_agreement(env, agreement_id) else {
+                continue;
+            };
+            metrics.agreements += 1;
+            if agreement.status == AgreementStatus::Active {
+                metrics.active_agreements += 1;
+            }
+            metrics.employees += DataKey::get_employee_count(env, agreement_id);
+            let paid = metrics.paid.get(agreement.token.clone()).unwrap_or(0);
+            metrics.paid.set(
+                agreement.token,
+                paid.saturating_add(DataKey::get_agreement_paid_amount(env, agreement_id)),
+            );
+        }
+    }
+    metrics
+}
@@ -0,0 +1,464 @@
+//! Deterministic fixtures for tests against this contract.
+//!
+//! Enabled by the `testutils` feature, for integrators' own test suites:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! stello_pay_contract = { path = "...", features = ["testutils"] }
+//! ```
+//!
+//! [`Fixture::new`] deploys and initializes the payroll contract and a linked
+//! bonus_system contract, and creates a Stellar asset to pay in. Builders
+//! then add realistic state in a few lines:
+//!
+//! - [`PayrollBuilder`]: an active payroll agreement with funded escrow,
+//!   ready for `claim_payroll`.
+//! - [`AgreementBuilder`]: a funded time-based escrow agreement.
+//! - [`IncentiveBuilder`]: a funded bonus_system incentive, approved by
+//!   default.
+//!
+//! Every fixture starts at [`FIXTURE_START`] with auths mocked, so addresses,
+//! IDs and timestamps are the same on every run. [`warp`] and [`warp_to`]
+//! move the ledger clock.
+//!
+//! ```ignore
+//! let f = Fixture::new();
+//! let alice = f.address();
+//! let id = f.payroll().employee(&alice, 1_000).build();
+//! f.warp(2 * DAY);
+//! f.client.claim_payroll(&alice, &id, &0);
+//! assert_eq!(f.token.balance(&alice), 2_000);
+//! ```
+
+use bonus_system::{BonusSystemContract, BonusSystemContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env, Vec,
+};
+
+use crate::storage::{Agreement, DataKey, StorageKey};
+use crate::versioned;
+use crate::{PayrollContract, PayrollContractClient};
+
+/// Ledger time every fixture starts at: Monday 2024-01-01 00:00 UTC.
+pub const FIXTURE_START: u64 = 1_704_067_200;
+
+/// Seconds per day, the default payroll and escrow period.
+pub const DAY: u64 = 86_400;
+
+/// Ledgers a token allowance from [`TestToken::approve`] stays valid for.
+const ALLOWANCE_LEDGERS: u32 = 100_000;
+
+/// Moves the ledger clock forward by `seconds`.
+///
+/// Only the timestamp moves. The ledger sequence stays put, so storage
+/// entries do not expire however far a test warps.
+pub fn warp(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|l| l.timestamp += seconds);
+}
+
+/// Sets the ledger clock to `timestamp`. See [`warp`].
+pub fn warp_to(env: &Env, timestamp: u64) {
+    env.ledger().with_mut(|l| l.timestamp = timestamp);
+}
+
+/// A Stellar asset contract with mint and allowance helpers.
+pub struct TestToken<'a> {
+    pub address: Address,
+    pub client: TokenClient<'a>,
+    admin: StellarAssetClient<'a>,
+}
+
+impl<'a> TestToken<'a> {
+    /// Registers a new Stellar asset contract.
+    pub fn new(env: &Env) -> Self {
+        let address = env
+            .register_stellar_asset_contract_v2(Address::generate(env))
+            .address();
+        TestToken {
+            client: TokenClient::new(env, &address),
+            admin: StellarAssetClient::new(env, &address),
+            address,
+        }
+    }
+
+    /// Mints `amount` to `to`.
+    pub fn mint(&self, to: &Address, amount: i128) {
+        self.admin.mint(to, &amount);
+    }
+
+    /// Lets `spender` transfer up to `amount` from `from`.
+    pub fn approve(&self, from: &Address, spender: &Address, amount: i128) {
+        let expiration = self.client.env.ledger().sequence() + ALLOWANCE_LEDGERS;
+        self.client.approve(from, spender, &amount, &expiration);
+    }
+
+    pub fn balance(&self, of: &Address) -> i128 {
+        self.client.balance(of)
+    }
+}
+
+/// Deployed contracts and the accounts that administer them.
+pub struct Fixture<'a> {
+    pub env: Env,
+    pub client: PayrollContractClient<'a>,
+    pub bonus: BonusSystemContractClient<'a>,
+    /// Owner of both contracts.
+    pub owner: Address,
+    /// Default employer of the builders.
+    pub employer: Address,
+    pub token: TestToken<'a>,
+}
+
+impl Fixture<'static> {
+    /// Deploys the payroll and bonus contracts at [`FIXTURE_START`] with all
+    /// auths mocked, and links them for unified pay runs.
+    pub fn new() -> Self {
+        Self::deploy(true)
+    }
+
+    /// Like [`Fixture::new`], but leaves the bonus contract unlinked, as
+    /// for a deployment that does not pay bonuses.
+    pub fn without_bonus_link() -> Self {
+        Self::deploy(false)
+    }
+
+    fn deploy(link_bonus: bool) -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        warp_to(&env, FIXTURE_START);
+
+        let client = PayrollContractClient::new(&env, &env.register(PayrollContract, ()));
+        let bonus = BonusSystemContractClient::new(&env, &env.register(BonusSystemContract, ()));
+        let owner = Address::generate(&env);
+        client.initialize(&owner);
+        bonus.initialize(&owner);
+        if link_bonus {
+            client.set_bonus_contract(&owner, &bonus.address);
+        }
+
+        Fixture {
+            token: TestToken::new(&env),
+            employer: Address::generate(&env),
+            env,
+            client,
+            bonus,
+            owner,
+        }
+    }
+}
+
+impl Default for Fixture<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Fixture<'a> {
+    /// Generates a fresh address.
+    pub fn address(&self) -> Address {
+        Address::generate(&self.env)
+    }
+
+    /// Moves the ledger clock forward by `seconds`.
+    pub fn warp(&self, seconds: u64) {
+        warp(&self.env, seconds);
+    }
+
+    /// Sets the ledger clock to `timestamp`.
+    pub fn warp_to(&self, timestamp: u64) {
+        warp_to(&self.env, timestamp);
+    }
+
+    /// Starts a payroll agreement of the default employer.
+    pub fn payroll(&self) -> PayrollBuilder<'_, 'a> {
+        PayrollBuilder {
+            fixture: self,
+            employer: self.employer.clone(),
+            token: self.token.address.clone(),
+            employees: Vec::new(&self.env),
+            period_seconds: DAY,
+            grace_period_seconds: DAY,
+            funded_periods: 10,
+            hourly: None,
+        }
+    }
+
+    /// Starts a time-based escrow agreement of the default employer.
+    pub fn agreement(&self, contributor: &Address) -> AgreementBuilder<'_, 'a> {
+        AgreementBuilder {
+            fixture: self,
+            employer: self.employer.clone(),
+            contributor: contributor.clone(),
+            amount_per_period: 1_000,
+            period_seconds: DAY,
+            num_periods: 4,
+            activate: true,
+        }
+    }
+
+    /// Starts a one-time bonus from the default employer, vested now.
+    pub fn incentive(&self, employee: &Address) -> IncentiveBuilder<'_, 'a> {
+        IncentiveBuilder {
+            fixture: self,
+            employer: self.employer.clone(),
+            employee: employee.clone(),
+            approver: self.owner.clone(),
+            amount: 500,
+            payouts: 1,
+            start: self.env.ledger().timestamp(),
+            interval_seconds: DAY,
+            approve: true,
+        }
+    }
+}
+
+/// Builds an active payroll agreement whose escrow covers
+/// `funded_periods` of salary for every employee.
+pub struct PayrollBuilder<'f, 'a> {
+    fixture: &'f Fixture<'a>,
+    employer: Address,
+    token: Address,
+    employees: Vec<(Address, i128)>,
+    period_seconds: u64,
+    grace_period_seconds: u64,
+    funded_periods: u32,
+    hourly: Option<(Address, u32)>,
+}
+
+impl PayrollBuilder<'_, '_> {
+    pub fn employer(mut self, employer: &Address) -> Self {
+        self.employer = employer.clone();
+        self
+    }
+
+    /// Pays in `token`, a Stellar asset such as another [`TestToken`],
+    /// instead of the fixture's token.
+    pub fn token(mut self, token: &Address) -> Self {
+        self.token = token.clone();
+        self
+    }
+
+    /// Adds an employee paid `salary_per_period`.
+    pub fn employee(mut self, employee: &Address, salary_per_period: i128) -> Self {
+        self.employees
+            .push_back((employee.clone(), salary_per_period));
+        self
+    }
+
+    pub fn period_seconds(mut self, period_seconds: u64) -> Self {
+        self.period_seconds = period_seconds;
+        self
+    }
+
+    pub fn grace_period_seconds(mut self, grace_period_seconds: u64) -> Self {
+        self.grace_period_seconds = grace_period_seconds;
+        self
+    }
+
+    pub fn funded_periods(mut self, funded_periods: u32) -> Self {
+        self.funded_periods = funded_periods;
+        self
+    }
+
+    /// Pays each salary per hour attested by `timekeeper`, up to
+    /// `max_hours_per_period`.
+    pub fn hourly(mut self, timekeeper: &Address, max_hours_per_period: u32) -> Self {
+        self.hourly = Some((timekeeper.clone(), max_hours_per_period));
+        self
+    }
+
+    /// Creates, funds and activates the agreement, returning its ID.
+    ///
+    /// # Panics
+    /// If no employee was added.
+    pub fn build(self) -> u128 {
+        let f = self.fixture;
+        let env = &f.env;
+        let token = &self.token;
+        let id =
+            f.client
+                .create_payroll_agreement(&self.employer, token, &self.grace_period_seconds);
+        let mut payroll = 0;
+        for (employee, salary) in self.employees.iter() {
+            f.client.add_employee_to_agreement(&id, &employee, &salary);
+            payroll += salary;
+        }
+        if let Some((timekeeper, max_hours)) = &self.hourly {
+            f.client
+                .enable_hourly_pay(&self.employer, &id, timekeeper, max_hours);
+        }
+        f.client.activate_agreement(&id);
+
+        // Escrow funding and the per-employee claim indexes have no public
+        // entry point of their own yet; they are written directly.
+        let escrow = payroll * i128::from(self.funded_periods);
+        let now = env.ledger().timestamp();
+        env.as_contract(&f.client.address, || {
+            let key = StorageKey::Agreement(id);
+            let mut agreement: Agreement = versioned::load(env, &key).unwrap();
+            agreement.period_seconds = Some(self.period_seconds);
+            versioned::save(env, &key, &agreement);
+            DataKey::set_employee_count(env, id, self.employees.len());
+            DataKey::set_agreement_activation_time(env, id, now);
+            DataKey::set_agreement_period_duration(env, id, self.period_seconds);
+            DataKey::set_agreement_token(env, id, token);
+            DataKey::set_agreement_escrow_balance(env, id, token, escrow);
+            for (index, (employee, salary)) in self.employees.iter().enumerate() {
+                DataKey::set_employee(env, id, index as u32, &employee);
+                DataKey::set_employee_salary(env, id, index as u32, salary);
+            }
+        });
+        StellarAssetClient::new(env, token).mint(&f.client.address, &escrow);
+        id
+    }
+}
+
+/// Builds a fully funded time-based escrow agreement.
+pub struct AgreementBuilder<'f, 'a> {
+    fixture: &'f Fixture<'a>,
+    employer: Address,
+    contributor: Address,
+    amount_per_period: i128,
+    period_seconds: u64,
+    num_periods: u32,
+    activate: bool,
+}
+
+impl AgreementBuilder<'_, '_> {
+    pub fn employer(mut self, employer: &Address) -> Self {
+        self.employer = employer.clone();
+        self
+    }
+
+    pub fn amount_per_period(mut self, amount_per_period: i128) -> Self {
+        self.amount_per_period = amount_per_period;
+        self
+    }
+
+    pub fn period_seconds(mut self, period_seconds: u64) -> Self {
+        self.period_seconds = period_seconds;
+        self
+    }
+
+    pub fn num_periods(mut self, num_periods: u32) -> Self {
+        self.num_periods = num_periods;
+        self
+    }
+
+    /// Leaves the agreement in `Created` status.
+    pub fn inactive(mut self) -> Self {
+        self.activate = false;
+        self
+    }
+
+    /// Creates and funds the agreement, returning its ID.
+    pub fn build(self) -> u128 {
+        let f = self.fixture;
+        let env = &f.env;
+        let token = &f.token.address;
+        let id = f.client.create_escrow_agreement(
+            &self.employer,
+            &self.contributor,
+            token,
+            &self.amount_per_period,
+            &self.period_seconds,
+            &self.num_periods,
+        );
+        let escrow = self.amount_per_period * i128::from(self.num_periods);
+        env.as_contract(&f.client.address, || {
+            DataKey::set_agreement_escrow_balance(env, id, token, escrow);
+        });
+        f.token.mint(&f.client.address, escrow);
+        if self.activate {
+            f.client.activate_agreement(&id);
+        }
+        id
+    }
+}
+
+/// Builds a bonus_system incentive, escrowed from a freshly minted employer
+/// balance.
+pub struct IncentiveBuilder<'f, 'a> {
+    fixture: &'f Fixture<'a>,
+    employer: Address,
+    employee: Address,
+    approver: Address,
+    amount: i128,
+    payouts: u32,
+    start: u64,
+    interval_seconds: u64,
+    approve: bool,
+}
+
+impl IncentiveBuilder<'_, '_> {
+    pub fn employer(mut self, employer: &Address) -> Self {
+        self.employer = employer.clone();
+        self
+    }
+
+    pub fn approver(mut self, approver: &Address) -> Self {
+        self.approver = approver.clone();
+        self
+    }
+
+    /// Amount of each payout.
+    pub fn amount(mut self, amount: i128) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    /// Makes the incentive recurring: `payouts` payouts, `interval_seconds`
+    /// apart.
+    pub fn recurring(mut self, payouts: u32, interval_seconds: u64) -> Self {
+        self.payouts = payouts;
+        self.interval_seconds = interval_seconds;
+        self
+    }
+
+    /// When the first payout vests.
+    pub fn vesting_at(mut self, start: u64) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Leaves the incentive pending approval.
+    pub fn pending(mut self) -> Self {
+        self.approve = false;
+        self
+    }
+
+    /// Creates the incentive, returning its ID.
+    pub fn build(self) -> u128 {
+        let f = self.fixture;
+        let token = &f.token.address;
+        f.token
+            .mint(&self.employer, self.amount * i128::from(self.payouts));
+        let id = if self.payouts == 1 {
+            f.bonus.create_one_time_bonus(
+                &self.employer,
+                &self.employee,
+                &self.approver,
+                token,
+                &self.amount,
+                &self.start,
+            )
+        } else {
+            f.bonus.create_recurring_incentive(
+                &self.employer,
+                &self.employee,
+                &self.approver,
+                token,
+                &self.amount,
+                &self.payouts,
+                &self.start,
+                &self.interval_seconds,
+            )
+        };
+        if self.approve {
+            f.bonus.approve_incentive(&self.approver, &id);
+        }
+        id
+    }
+}
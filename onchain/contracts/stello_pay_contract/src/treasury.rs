@@ -0,0 +1,322 @@
+//! Protocol treasury with timelocked, governance-gated spending.
+//!
+//! The treasury is a set of per-token balances held by this contract,
+//! accounted separately from agreement escrows. It receives:
+//! * protocol fees, paid in with `deposit_to_treasury` by whichever contract
+//!   or operator collects them, and
+//! * early savings withdrawal penalties, when the savings insurance pool is
+//!   set to this contract's own address.
+//!
+//! Spending goes through governance. A `ProposalKind::TreasurySpend`
+//! proposal on the linked governance contract approves a transfer; once it
+//! is executed, the owner queues it here with `propose_spend`, and it can
+//! only be executed [`TREASURY_TIMELOCK_SECONDS`] later. Until then the owner
+//! or any emergency guardian can cancel it, so guardians have a window to
+//! veto a compromised owner key. Each approved spend is paid at most once.
+
+use soroban_sdk::{contracttype, token::Client as TokenClient, Address, Env, Vec};
+use stellopay_interfaces::treasury::SpendRegistryClient;
+
+use crate::events::{
+    emit_treasury_credited, emit_treasury_spend_executed, emit_treasury_spend_proposed,
+    TreasuryCreditedEvent, TreasurySpendExecutedEvent, TreasurySpendProposedEvent,
+};
+use crate::payroll::transfer_from_contract;
+use crate::storage::{PayrollError, StorageKey};
+use crate::validation;
+
+/// Delay between proposing and executing a spend (2 days).
+pub const TREASURY_TIMELOCK_SECONDS: u64 = 2 * 86_400;
+
+#[contracttype]
+#[derive(Clone)]
+enum TreasuryKey {
+    /// Treasury balance of a token -> i128
+    Balance(Address),
+    /// Tokens the treasury has ever held, in first-credit order -> Vec<Address>
+    Tokens,
+    /// Governance contract approving spends -> Address
+    Governance,
+    /// Pending spend, by governance proposal ID -> SpendProposal
+    Spend(u128),
+    /// Spend executed or cancelled, by governance proposal ID -> bool
+    Settled(u128),
+}
+
+/// Treasury balance of one token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreasuryBalance {
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// A governance-approved treasury transfer awaiting its timelock.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendProposal {
+    /// Governance proposal that approved the spend.
+    pub id: u128,
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+    /// Earliest time `execute_spend` is accepted.
+    pub executable_at: u64,
+}
+
+fn require_owner(env: &Env, owner: &Address) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    Ok(())
+}
+
+fn balance_of(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&TreasuryKey::Balance(token.clone()))
+        .unwrap_or(0)
+}
+
+/// Adds `amount` of `token`, already held by this contract, to the treasury.
+pub(crate) fn credit(env: &Env, token: &Address, amount: i128) {
+    let balance: Option<i128> = env
+        .storage()
+        .persistent()
+        .get(&TreasuryKey::Balance(token.clone()));
+    if balance.is_none() {
+        let mut tokens: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&TreasuryKey::Tokens)
+            .unwrap_or(Vec::new(env));
+        tokens.push_back(token.clone());
+        env.storage()
+            .persistent()
+            .set(&TreasuryKey::Tokens, &tokens);
+    }
+    env.storage().persistent().set(
+        &TreasuryKey::Balance(token.clone()),
+        &(balance.unwrap_or(0) + amount),
+    );
+    emit_treasury_credited(
+        env,
+        TreasuryCreditedEvent {
+            token: token.clone(),
+            amount,
+        },
+    );
+}
+
+/// Transfers `amount` of `token` from `from` into the treasury. Used to pay
+/// in protocol fees collected elsewhere.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - `amount` is not positive or above `MAX_AMOUNT`
+///
+/// # Access Control
+/// Requires `from` authentication
+pub fn deposit_to_treasury(
+    env: &Env,
+    from: &Address,
+    token: &Address,
+    amount: i128,
+) -> Result<(), PayrollError> {
+    from.require_auth();
+    validation::amount(amount)?;
+    TokenClient::new(env, token).transfer(from, env.current_contract_address(), &amount);
+    credit(env, token, amount);
+    Ok(())
+}
+
+/// Returns the treasury balance of every token it has held, including
+/// tokens since spent down to zero.
+pub fn get_treasury_balances(env: &Env) -> Vec<TreasuryBalance> {
+    let tokens: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&TreasuryKey::Tokens)
+        .unwrap_or(Vec::new(env));
+    let mut balances = Vec::new(env);
+    for token in tokens.iter() {
+        let amount = balance_of(env, &token);
+        balances.push_back(TreasuryBalance { token, amount });
+    }
+    balances
+}
+
+/// Links the governance contract whose executed proposals approve spends.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_treasury_governance(
+    env: &Env,
+    owner: &Address,
+    governance: &Address,
+) -> Result<(), PayrollError> {
+    require_owner(env, owner)?;
+    env.storage()
+        .persistent()
+        .set(&TreasuryKey::Governance, governance);
+    Ok(())
+}
+
+/// Returns the linked treasury governance contract, if any.
+pub fn get_treasury_governance(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&TreasuryKey::Governance)
+}
+
+/// Queues the spend approved by governance proposal `proposal_id`. It
+/// becomes executable after [`TREASURY_TIMELOCK_SECONDS`].
+///
+/// The balance is checked again on execution, so spends may exceed the
+/// current balance in anticipation of income.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner, or the proposal
+///   approved no spend from this contract's treasury
+/// * `PayrollError::InvalidData` - no governance is linked, the amount is not
+///   positive or above `MAX_AMOUNT`, or the spend was already queued,
+///   executed or cancelled
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn propose_spend(env: &Env, owner: &Address, proposal_id: u128) -> Result<(), PayrollError> {
+    require_owner(env, owner)?;
+    let governance = get_treasury_governance(env).ok_or(PayrollError::InvalidData)?;
+    let spend = SpendRegistryClient::new(env, &governance)
+        .get_approved_spend(&proposal_id)
+        .filter(|spend| spend.treasury == env.current_contract_address())
+        .ok_or(PayrollError::Unauthorized)?;
+    validation::amount(spend.amount)?;
+    if env
+        .storage()
+        .persistent()
+        .has(&TreasuryKey::Spend(proposal_id))
+        || env
+            .storage()
+            .persistent()
+            .has(&TreasuryKey::Settled(proposal_id))
+    {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let proposal = SpendProposal {
+        id: proposal_id,
+        token: spend.token,
+        to: spend.to,
+        amount: spend.amount,
+        executable_at: env.ledger().timestamp() + TREASURY_TIMELOCK_SECONDS,
+    };
+    env.storage()
+        .persistent()
+        .set(&TreasuryKey::Spend(proposal_id), &proposal);
+    emit_treasury_spend_proposed(
+        env,
+        TreasurySpendProposedEvent {
+            proposal_id,
+            token: proposal.token,
+            to: proposal.to,
+            amount: proposal.amount,
+            executable_at: proposal.executable_at,
+        },
+    );
+    Ok(())
+}
+
+/// Executes a queued spend once its timelock has passed.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+/// * `PayrollError::InvalidData` - no such queued spend
+/// * `PayrollError::TimelockActive` - the timelock has not passed
+/// * `PayrollError::InsufficientEscrowBalance` - the treasury holds less than
+///   `amount` of the token
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn execute_spend(env: &Env, owner: &Address, proposal_id: u128) -> Result<(), PayrollError> {
+    require_owner(env, owner)?;
+    let proposal: SpendProposal = env
+        .storage()
+        .persistent()
+        .get(&TreasuryKey::Spend(proposal_id))
+        .ok_or(PayrollError::InvalidData)?;
+    if env.ledger().timestamp() < proposal.executable_at {
+        return Err(PayrollError::TimelockActive);
+    }
+    let balance = balance_of(env, &proposal.token);
+    if balance < proposal.amount {
+        return Err(PayrollError::InsufficientEscrowBalance);
+    }
+
+    settle(env, proposal_id);
+    env.storage().persistent().set(
+        &TreasuryKey::Balance(proposal.token.clone()),
+        &(balance - proposal.amount),
+    );
+    transfer_from_contract(env, &proposal.token, &proposal.to, proposal.amount);
+    emit_treasury_spend_executed(
+        env,
+        TreasurySpendExecutedEvent {
+            proposal_id,
+            token: proposal.token,
+            to: proposal.to,
+            amount: proposal.amount,
+        },
+    );
+    Ok(())
+}
+
+/// Cancels a queued spend; it cannot be queued again.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is neither the owner nor an
+///   emergency guardian
+/// * `PayrollError::InvalidData` - no such queued spend
+///
+/// # Access Control
+/// Requires owner or emergency guardian authentication
+pub fn cancel_spend(env: &Env, caller: &Address, proposal_id: u128) -> Result<(), PayrollError> {
+    caller.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    let guardians: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::EmergencyGuardians)
+        .unwrap_or(Vec::new(env));
+    if stored_owner.as_ref() != Some(caller) && !guardians.contains(caller) {
+        return Err(PayrollError::Unauthorized);
+    }
+    if !env
+        .storage()
+        .persistent()
+        .has(&TreasuryKey::Spend(proposal_id))
+    {
+        return Err(PayrollError::InvalidData);
+    }
+    settle(env, proposal_id);
+    Ok(())
+}
+
+/// Removes a pending spend and keeps it from being queued again.
+fn settle(env: &Env, proposal_id: u128) {
+    env.storage()
+        .persistent()
+        .remove(&TreasuryKey::Spend(proposal_id));
+    env.storage()
+        .persistent()
+        .set(&TreasuryKey::Settled(proposal_id), &true);
+}
+
+/// Returns a queued spend, if any.
+pub fn get_spend_proposal(env: &Env, proposal_id: u128) -> Option<SpendProposal> {
+    env.storage()
+        .persistent()
+        .get(&TreasuryKey::Spend(proposal_id))
+}
@@ -0,0 +1,131 @@
+//! State-archival maintenance for long-lived payroll entries.
+//!
+//! Entries are bumped on access (see [`crate::storage::extend_persistent_ttl`]),
+//! but an employer whose agreements go untouched for months (e.g. a payroll
+//! funded up front and claimed rarely) could still see them archived.
+//! `bump_employer_entries` lets an admin sweep an employer's agreements and
+//! extend every payroll, balance and index entry they rely on.
+//!
+//! Sweeps are processed in bounded batches: each call bumps at most `limit`
+//! agreements and stores a per-employer cursor; the next call resumes from it
+//! and the cursor wraps back to the start once the last agreement is reached.
+//! The cursor lives in temporary storage: if it lapses, the next sweep simply
+//! starts over.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::payroll::get_agreement;
+use crate::storage::{
+    extend_instance_ttl, extend_persistent_ttl, tiered_get, tiered_remove, tiered_set, DataKey,
+    EmployeeInfo, PayrollError, StorageKey, StorageTier, TieredKey, MAX_BATCH_SIZE,
+};
+
+#[contracttype]
+#[derive(Clone)]
+enum TtlKey {
+    /// Position of the next agreement to bump for an employer -> u32
+    Cursor(Address),
+}
+
+impl TieredKey for TtlKey {
+    fn tier(&self) -> StorageTier {
+        StorageTier::Temporary
+    }
+}
+
+/// Extends the TTL of up to `limit` of `employer`'s agreements and the entries
+/// they rely on: the agreement record, its employee list and per-employee
+/// indexes, its escrow balance and configuration, and each employee's
+/// agreement index. The employer's own agreement index and the contract
+/// instance are bumped on every call.
+///
+/// # Returns
+/// The number of agreements bumped (zero if the employer has none).
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+/// * `PayrollError::InvalidData` - `limit` is zero
+/// * `PayrollError::BatchTooLarge` - `limit` exceeds `MAX_BATCH_SIZE`
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn bump_employer_entries(
+    env: &Env,
+    caller: &Address,
+    employer: &Address,
+    limit: u32,
+) -> Result<u32, PayrollError> {
+    caller.require_auth();
+    let owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if owner.as_ref() != Some(caller) {
+        return Err(PayrollError::Unauthorized);
+    }
+    if limit == 0 {
+        return Err(PayrollError::InvalidData);
+    }
+    if limit > MAX_BATCH_SIZE {
+        return Err(PayrollError::BatchTooLarge);
+    }
+
+    extend_instance_ttl(env);
+    let employer_key = StorageKey::EmployerAgreements(employer.clone());
+    extend_persistent_ttl(env, &employer_key);
+    let agreement_ids: Vec<u128> = env
+        .storage()
+        .persistent()
+        .get(&employer_key)
+        .unwrap_or(Vec::new(env));
+
+    let cursor_key = TtlKey::Cursor(employer.clone());
+    let mut pos: u32 = tiered_get(env, &cursor_key).unwrap_or(0);
+    if pos >= agreement_ids.len() {
+        pos = 0;
+    }
+
+    let mut bumped = 0u32;
+    while pos < agreement_ids.len() && bumped < limit {
+        bump_agreement_entries(env, agreement_ids.get_unchecked(pos));
+        pos += 1;
+        bumped += 1;
+    }
+
+    if pos >= agreement_ids.len() {
+        tiered_remove(env, &cursor_key);
+    } else {
+        tiered_set(env, &cursor_key, &pos);
+    }
+    Ok(bumped)
+}
+
+fn bump_agreement_entries(env: &Env, agreement_id: u128) {
+    // Reading the agreement bumps its record.
+    let Some(agreement) = get_agreement(env, agreement_id) else {
+        return;
+    };
+
+    let employees_key = StorageKey::AgreementEmployees(agreement_id);
+    extend_persistent_ttl(env, &employees_key);
+    let employees: Vec<EmployeeInfo> = env
+        .storage()
+        .persistent()
+        .get(&employees_key)
+        .unwrap_or(Vec::new(env));
+    for employee in employees.iter() {
+        extend_persistent_ttl(env, &StorageKey::EmployeeAgreements(employee.address));
+    }
+
+    extend_persistent_ttl(env, &DataKey::AgreementEmployeeCount(agreement_id));
+    extend_persistent_ttl(env, &DataKey::AgreementActivationTime(agreement_id));
+    extend_persistent_ttl(env, &DataKey::AgreementPeriodDuration(agreement_id));
+    extend_persistent_ttl(env, &DataKey::AgreementToken(agreement_id));
+    extend_persistent_ttl(env, &DataKey::AgreementPaidAmount(agreement_id));
+    extend_persistent_ttl(
+        env,
+        &DataKey::AgreementEscrowBalance(agreement_id, agreement.token),
+    );
+    for index in 0..DataKey::get_employee_count(env, agreement_id) {
+        extend_persistent_ttl(env, &DataKey::AgreementEmployee(agreement_id, index));
+        extend_persistent_ttl(env, &DataKey::EmployeeSalary(agreement_id, index));
+        extend_persistent_ttl(env, &DataKey::EmployeeClaimedPeriods(agreement_id, index));
+    }
+}
@@ -0,0 +1,114 @@
+//! Shared bounds for caller-supplied entrypoint inputs.
+//!
+//! Every public entrypoint that accepts a token amount, a time span, a string
+//! or a vector runs it through one of the checks below before touching state,
+//! so arbitrary (fuzzed) inputs are rejected up front with a typed error
+//! instead of overflowing or exhausting resources halfway through a call.
+//!
+//! - **Amounts** must be positive and at most [`MAX_AMOUNT`]. Capping at half
+//!   of `i128::MAX` leaves room to add any two accepted amounts without
+//!   overflow. Paths that already report a more specific error for
+//!   non-positive amounts only apply the cap ([`max_amount`]).
+//! - **Intervals** must fall inside the range the caller passes. Open-ended
+//!   durations are capped at [`MAX_DURATION_SECONDS`] ([`duration`]).
+//! - **Strings** are at most [`MAX_STRING_LEN`] bytes.
+//! - **Vectors** hold at most [`MAX_ITEMS`] elements.
+//!
+//! Failures are reported as [`ValidationError`], which converts into the
+//! `PayrollError` the entrypoint returns: `BatchTooLarge` for oversized
+//! vectors and `InvalidData` for everything else.
+
+use soroban_sdk::{panic_with_error, Env, String, Vec};
+
+use crate::storage::{PayrollError, MAX_BATCH_SIZE, MAX_PERIOD_SECONDS};
+
+/// Largest accepted token amount.
+pub const MAX_AMOUNT: i128 = i128::MAX / 2;
+
+/// Longest accepted open-ended duration (~136 years).
+pub const MAX_DURATION_SECONDS: u64 = MAX_PERIOD_SECONDS;
+
+/// Longest accepted string, in bytes.
+pub const MAX_STRING_LEN: u32 = 256;
+
+/// Most elements accepted in a caller-supplied vector.
+pub const MAX_ITEMS: u32 = MAX_BATCH_SIZE;
+
+/// Why an input was rejected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+    /// Amount is zero or negative.
+    AmountNotPositive,
+    /// Amount is above [`MAX_AMOUNT`].
+    AmountTooLarge,
+    /// Interval or duration is outside its accepted range.
+    IntervalOutOfRange,
+    /// String is longer than [`MAX_STRING_LEN`].
+    StringTooLong,
+    /// Vector has more than [`MAX_ITEMS`] elements.
+    TooManyItems,
+}
+
+impl From<ValidationError> for PayrollError {
+    fn from(err: ValidationError) -> Self {
+        match err {
+            ValidationError::TooManyItems => PayrollError::BatchTooLarge,
+            ValidationError::AmountNotPositive
+            | ValidationError::AmountTooLarge
+            | ValidationError::IntervalOutOfRange
+            | ValidationError::StringTooLong => PayrollError::InvalidData,
+        }
+    }
+}
+
+/// Checks that `amount` is positive and at most [`MAX_AMOUNT`].
+pub fn amount(amount: i128) -> Result<(), ValidationError> {
+    if amount <= 0 {
+        return Err(ValidationError::AmountNotPositive);
+    }
+    max_amount(amount)
+}
+
+/// Checks that `amount` is at most [`MAX_AMOUNT`].
+pub fn max_amount(amount: i128) -> Result<(), ValidationError> {
+    if amount > MAX_AMOUNT {
+        return Err(ValidationError::AmountTooLarge);
+    }
+    Ok(())
+}
+
+/// Checks that `seconds` lies within `min..=max`.
+pub fn interval(seconds: u64, min: u64, max: u64) -> Result<(), ValidationError> {
+    if !(min..=max).contains(&seconds) {
+        return Err(ValidationError::IntervalOutOfRange);
+    }
+    Ok(())
+}
+
+/// Checks that `seconds` is at most [`MAX_DURATION_SECONDS`].
+pub fn duration(seconds: u64) -> Result<(), ValidationError> {
+    interval(seconds, 0, MAX_DURATION_SECONDS)
+}
+
+/// Checks that `value` is at most [`MAX_STRING_LEN`] bytes long.
+pub fn string(value: &String) -> Result<(), ValidationError> {
+    if value.len() > MAX_STRING_LEN {
+        return Err(ValidationError::StringTooLong);
+    }
+    Ok(())
+}
+
+/// Checks that `items` has at most [`MAX_ITEMS`] elements.
+pub fn items<T>(items: &Vec<T>) -> Result<(), ValidationError> {
+    if items.len() > MAX_ITEMS {
+        return Err(ValidationError::TooManyItems);
+    }
+    Ok(())
+}
+
+/// Unwraps a check for entrypoints that panic rather than return errors.
+pub fn require(env: &Env, check: Result<(), ValidationError>) {
+    if let Err(err) = check {
+        panic_with_error!(env, PayrollError::from(err));
+    }
+}
@@ -0,0 +1,259 @@
+//! Versioned storage records with lazy migration.
+//!
+//! A record type that implements [`Versioned`] carries a schema version,
+//! stored in its own entry next to the record. That lets a later release add
+//! or change fields without breaking data written by an earlier one:
+//!
+//! - **Lazy migration**: [`load`] reads whatever layout the stored version
+//!   says. If it is older than [`Versioned::SCHEMA_VERSION`], the record is
+//!   upgraded with [`Versioned::upgrade`] and written back in the current
+//!   layout, so each record is converted at most once, the first time it is
+//!   accessed.
+//! - **Proactive migration**: the `migrate_batch(limit)` admin entrypoint
+//!   converts up to `limit` agreements per call ([`migrate_agreements`]),
+//!   resuming from a stored cursor, so records that are rarely read can be
+//!   brought up to date ahead of a release that drops an old layout.
+//!
+//! A record without a version entry was written before versioning existed
+//! and is read as schema version 0. The record itself is stored unchanged,
+//! so a release that predates versioning can still read it as long as the
+//! layout has not changed.
+//!
+//! [`Agreement`] is versioned. Agreements created with a nonce have hashed
+//! IDs, so they are also recorded in creation order
+//! ([`record_derived_agreement`]). Batches visit the sequentially numbered
+//! agreements first and then the recorded ones, each from its own cursor.
+//!
+//! The same batching backfills derived indexes a release adds:
+//! `migrate_employee_index(limit)` ([`migrate_employee_index`]) adds
+//! agreements created before the per-employee `EmployeeAgreements` index to
+//! it.
+//!
+//! # Adding a field
+//!
+//! Copy the current struct to a `...V<n>` type, add the field to the live
+//! struct, bump `SCHEMA_VERSION` and add an `upgrade` arm that decodes the
+//! old type and fills in the new field.
+
+use soroban_sdk::{contracttype, panic_with_error, Env, IntoVal, TryFromVal, Val};
+
+use crate::payroll::index_agreement_employees;
+use crate::storage::{extend_persistent_ttl, Agreement, PayrollError, StorageKey};
+use crate::validation::{self, MAX_ITEMS};
+
+/// A storage record that carries a schema version.
+pub trait Versioned: Sized + IntoVal<Env, Val> + TryFromVal<Env, Val> {
+    /// Version new records are written with.
+    const SCHEMA_VERSION: u32;
+
+    /// Converts `raw`, stored under an older `version`, to the current
+    /// layout. Returns `None` for versions this release cannot read.
+    fn upgrade(env: &Env, version: u32, raw: Val) -> Option<Self>;
+}
+
+impl Versioned for Agreement {
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn upgrade(env: &Env, version: u32, raw: Val) -> Option<Self> {
+        match version {
+            // v0 -> v1: same layout, only the version entry is new.
+            0 => Agreement::try_from_val(env, &raw).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Result of one [`migrate_agreements`] or [`migrate_employee_index`] call.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigrationProgress {
+    /// Agreement IDs visited by this call.
+    pub scanned: u32,
+    /// Records that were rewritten in the current layout, or agreements
+    /// added to an index.
+    pub migrated: u32,
+    /// First agreement ID the next call will visit.
+    pub next_id: u128,
+    /// Whether every agreement, sequentially numbered or created with a
+    /// nonce, has been visited.
+    pub complete: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum VersionedKey {
+    /// Schema version of the record stored under the key.
+    Schema(StorageKey),
+    /// Next agreement ID for `migrate_batch` to visit.
+    AgreementCursor,
+    /// Next agreement ID for `migrate_employee_index` to visit.
+    EmployeeIndexCursor,
+    /// Number of agreements created with a nonce -> u32
+    DerivedAgreementCount,
+    /// Nonce-derived agreement ID by 0-based creation order -> u128
+    DerivedAgreementId(u32),
+    /// Next `DerivedAgreementId` position for `migrate_batch` to visit.
+    DerivedAgreementCursor,
+    /// Next `DerivedAgreementId` position for `migrate_employee_index` to
+    /// visit.
+    DerivedEmployeeIndexCursor,
+}
+
+/// Reads the schema version of the record under `key`, if any.
+pub fn stored_version(env: &Env, key: &StorageKey) -> Option<u32> {
+    let storage = env.storage().persistent();
+    if !storage.has(key) {
+        return None;
+    }
+    Some(storage.get(&VersionedKey::Schema(key.clone())).unwrap_or(0))
+}
+
+/// Writes `value` under `key` in the current layout.
+pub fn save<T: Versioned>(env: &Env, key: &StorageKey, value: &T) {
+    let storage = env.storage().persistent();
+    storage.set(key, value);
+    storage.set(&VersionedKey::Schema(key.clone()), &T::SCHEMA_VERSION);
+}
+
+/// Reads the record under `key`, upgrading and rewriting it if it is stored
+/// in an older layout.
+///
+/// # Panics
+/// With `InvalidData` if the record cannot be decoded, e.g. because it was
+/// written by a newer release.
+pub fn load<T: Versioned>(env: &Env, key: &StorageKey) -> Option<T> {
+    let storage = env.storage().persistent();
+    let raw: Val = storage.get(key)?;
+    let version = storage.get(&VersionedKey::Schema(key.clone())).unwrap_or(0);
+    if version == T::SCHEMA_VERSION {
+        return match T::try_from_val(env, &raw) {
+            Ok(value) => Some(value),
+            Err(_) => panic_with_error!(env, PayrollError::InvalidData),
+        };
+    }
+    let upgraded = match T::upgrade(env, version, raw) {
+        Some(value) if version < T::SCHEMA_VERSION => value,
+        _ => panic_with_error!(env, PayrollError::InvalidData),
+    };
+    save(env, key, &upgraded);
+    Some(upgraded)
+}
+
+/// Bumps the TTL of the record under `key` and of its version entry.
+pub fn extend_ttl(env: &Env, key: &StorageKey) {
+    extend_persistent_ttl(env, key);
+    extend_persistent_ttl(env, &VersionedKey::Schema(key.clone()));
+}
+
+/// Records an agreement created with a nonce so that batches visit it.
+pub(crate) fn record_derived_agreement(env: &Env, agreement_id: u128) {
+    let storage = env.storage().persistent();
+    let position = derived_agreement_count(env);
+    let key = VersionedKey::DerivedAgreementId(position);
+    storage.set(&key, &agreement_id);
+    extend_persistent_ttl(env, &key);
+    storage.set(&VersionedKey::DerivedAgreementCount, &(position + 1));
+    extend_persistent_ttl(env, &VersionedKey::DerivedAgreementCount);
+}
+
+/// Returns how many agreements were created with a nonce.
+pub(crate) fn derived_agreement_count(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&VersionedKey::DerivedAgreementCount)
+        .unwrap_or(0)
+}
+
+/// Returns the ID of the agreement created with a nonce at `position`, in
+/// creation order.
+pub(crate) fn derived_agreement_id(env: &Env, position: u32) -> Option<u128> {
+    env.storage()
+        .persistent()
+        .get(&VersionedKey::DerivedAgreementId(position))
+}
+
+/// Rewrites up to `limit` agreements stored in an older layout, resuming
+/// where the previous call stopped.
+///
+/// # Errors
+/// - `InvalidData` if `limit` is zero or above `MAX_ITEMS`
+pub fn migrate_agreements(env: &Env, limit: u32) -> Result<MigrationProgress, PayrollError> {
+    let cursors = (
+        VersionedKey::AgreementCursor,
+        VersionedKey::DerivedAgreementCursor,
+    );
+    visit_agreements(env, &cursors, limit, |id| {
+        let key = StorageKey::Agreement(id);
+        if matches!(stored_version(env, &key), Some(v) if v < Agreement::SCHEMA_VERSION) {
+            load::<Agreement>(env, &key);
+            return true;
+        }
+        false
+    })
+}
+
+/// Adds up to `limit` agreements that are not yet settled to their
+/// employees' `EmployeeAgreements` index, resuming where the previous call
+/// stopped. Agreements already indexed are left as they are.
+///
+/// # Errors
+/// - `InvalidData` if `limit` is zero or above `MAX_ITEMS`
+pub fn migrate_employee_index(env: &Env, limit: u32) -> Result<MigrationProgress, PayrollError> {
+    let cursors = (
+        VersionedKey::EmployeeIndexCursor,
+        VersionedKey::DerivedEmployeeIndexCursor,
+    );
+    visit_agreements(env, &cursors, limit, |id| {
+        index_agreement_employees(env, id)
+    })
+}
+
+/// Calls `visit` on up to `limit` agreement IDs, counting the calls that
+/// return true. Sequentially numbered IDs come first, from the first cursor
+/// in `cursors`; agreements created with a nonce follow, from the position
+/// under the second.
+fn visit_agreements(
+    env: &Env,
+    cursors: &(VersionedKey, VersionedKey),
+    limit: u32,
+    mut visit: impl FnMut(u128) -> bool,
+) -> Result<MigrationProgress, PayrollError> {
+    validation::interval(u64::from(limit), 1, u64::from(MAX_ITEMS))?;
+
+    let (cursor_key, derived_cursor_key) = cursors;
+    let storage = env.storage().persistent();
+    let end: u128 = storage.get(&StorageKey::NextAgreementId).unwrap_or(1);
+    let derived_end = derived_agreement_count(env);
+    let mut id: u128 = storage.get(cursor_key).unwrap_or(1);
+    let mut position: u32 = storage.get(derived_cursor_key).unwrap_or(0);
+    let mut scanned = 0;
+    let mut migrated = 0;
+    while scanned < limit && id < end {
+        if visit(id) {
+            migrated += 1;
+        }
+        scanned += 1;
+        id += 1;
+    }
+    while scanned < limit && position < derived_end {
+        if derived_agreement_id(env, position).is_some_and(&mut visit) {
+            migrated += 1;
+        }
+        scanned += 1;
+        position += 1;
+    }
+    storage.set(cursor_key, &id);
+    storage.set(derived_cursor_key, &position);
+
+    let next_id = if id < end {
+        id
+    } else {
+        derived_agreement_id(env, position).unwrap_or(id)
+    };
+    Ok(MigrationProgress {
+        scanned,
+        migrated,
+        next_id,
+        complete: id >= end && position >= derived_end,
+    })
+}
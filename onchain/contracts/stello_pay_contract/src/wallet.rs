@@ -0,0 +1,206 @@
+//! Batched read views for wallets.
+//!
+//! A wallet showing a team roster or a multi-account portfolio needs the same
+//! few values for many employees. These views take a list of employees and
+//! answer for each of them in one call, in the order given, instead of one
+//! RPC round trip per employee and value.
+//!
+//! Each employee is represented by their current payroll position: the first
+//! of their payroll-mode agreements, in `get_employee_agreements` order, that
+//! is active, paused, or cancelled but still in its grace period. An employee
+//! can hold several payrolls at once, from one employer or many:
+//! `get_employee_payrolls` lists all of their current positions, and
+//! `get_payroll` looks one up by agreement ID.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::hourly::period_payout;
+use crate::payroll::{
+    effective_salary, elapsed_periods, get_agreement, get_employee_agreements,
+    get_grace_period_end, is_grace_period_active,
+};
+use crate::storage::{AgreementMode, AgreementStatus, DataKey, PayrollError};
+use crate::validation;
+
+/// An employee's position on a payroll agreement.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payroll {
+    pub agreement_id: u128,
+    pub employee_index: u32,
+    pub employer: Address,
+    pub token: Address,
+    pub status: AgreementStatus,
+    /// `None` when the salary is confidential.
+    pub salary_per_period: Option<i128>,
+    pub period_seconds: u64,
+    pub claimed_periods: u32,
+    pub activated_at: u64,
+}
+
+/// Returns each employee's current payroll position, or `None` if they have
+/// none.
+///
+/// # Errors
+/// * `PayrollError::BatchTooLarge` - more than `MAX_BATCH_SIZE` employees
+pub fn get_payrolls(
+    env: &Env,
+    employees: Vec<Address>,
+) -> Result<Vec<Option<Payroll>>, PayrollError> {
+    validation::items(&employees)?;
+    let mut payrolls = Vec::new(env);
+    for employee in employees.iter() {
+        payrolls.push_back(current_payroll(env, &employee));
+    }
+    Ok(payrolls)
+}
+
+/// Returns the salary due to each employee now on their current payroll
+/// position, which a claim would pay if the escrow covers it; 0 if they have
+/// no position, it is paused, or nothing is due.
+///
+/// # Errors
+/// * `PayrollError::BatchTooLarge` - more than `MAX_BATCH_SIZE` employees
+pub fn get_claimable_balances(
+    env: &Env,
+    employees: Vec<Address>,
+) -> Result<Vec<i128>, PayrollError> {
+    validation::items(&employees)?;
+    let mut balances = Vec::new(env);
+    for employee in employees.iter() {
+        let claimable = current_payroll(env, &employee)
+            .filter(|payroll| accrues(env, payroll))
+            .map_or(0, |payroll| claimable(env, &employee, &payroll));
+        balances.push_back(claimable);
+    }
+    Ok(balances)
+}
+
+/// Returns when the next period of each employee's current payroll position
+/// ends, adding to what they can claim. `None` if they have no position, it is
+/// paused, or its grace period ends first.
+///
+/// # Errors
+/// * `PayrollError::BatchTooLarge` - more than `MAX_BATCH_SIZE` employees
+pub fn get_next_payout_times(
+    env: &Env,
+    employees: Vec<Address>,
+) -> Result<Vec<Option<u64>>, PayrollError> {
+    validation::items(&employees)?;
+    let mut times = Vec::new(env);
+    for employee in employees.iter() {
+        let next = current_payroll(env, &employee)
+            .filter(|payroll| accrues(env, payroll))
+            .and_then(|payroll| next_payout_time(env, &payroll));
+        times.push_back(next);
+    }
+    Ok(times)
+}
+
+/// Returns the employee's position on payroll agreement `agreement_id`,
+/// whatever its status, or `None` if they are not on it or it has not been
+/// activated.
+pub fn get_payroll(env: &Env, employee: &Address, agreement_id: u128) -> Option<Payroll> {
+    let agreement = get_agreement(env, agreement_id)?;
+    if agreement.mode != AgreementMode::Payroll {
+        return None;
+    }
+    let (Some(activated_at), Some(period_seconds)) = (
+        DataKey::get_agreement_activation_time(env, agreement_id),
+        DataKey::get_agreement_period_duration(env, agreement_id),
+    ) else {
+        return None;
+    };
+    let index = (0..DataKey::get_employee_count(env, agreement_id)).find(|&index| {
+        DataKey::get_employee(env, agreement_id, index).as_ref() == Some(employee)
+    })?;
+    Some(Payroll {
+        agreement_id,
+        employee_index: index,
+        employer: agreement.employer,
+        token: agreement.token,
+        status: agreement.status,
+        salary_per_period: effective_salary(env, agreement_id, index, employee).ok(),
+        period_seconds,
+        claimed_periods: DataKey::get_employee_claimed_periods(env, agreement_id, index),
+        activated_at,
+    })
+}
+
+/// Returns every current payroll position the employee holds, in
+/// `get_employee_agreements` order.
+pub fn get_employee_payrolls(env: &Env, employee: &Address) -> Vec<Payroll> {
+    let mut payrolls = Vec::new(env);
+    for agreement_id in get_employee_agreements(env, employee).iter() {
+        if let Some(payroll) = get_payroll(env, employee, agreement_id) {
+            if is_current(env, &payroll) {
+                payrolls.push_back(payroll);
+            }
+        }
+    }
+    payrolls
+}
+
+fn current_payroll(env: &Env, employee: &Address) -> Option<Payroll> {
+    get_employee_agreements(env, employee)
+        .iter()
+        .filter_map(|agreement_id| get_payroll(env, employee, agreement_id))
+        .find(|payroll| is_current(env, payroll))
+}
+
+/// Whether the position is active, paused, or cancelled but still in its grace
+/// period.
+fn is_current(env: &Env, payroll: &Payroll) -> bool {
+    match payroll.status {
+        AgreementStatus::Active | AgreementStatus::Paused => true,
+        AgreementStatus::Cancelled => is_grace_period_active(env, payroll.agreement_id),
+        _ => false,
+    }
+}
+
+/// Whether pay is currently accruing on the position.
+fn accrues(env: &Env, payroll: &Payroll) -> bool {
+    payroll.status != AgreementStatus::Paused
+        && env.ledger().timestamp() >= payroll.activated_at
+        && payroll.period_seconds > 0
+}
+
+fn claimable(env: &Env, employee: &Address, payroll: &Payroll) -> i128 {
+    let Some(salary) = payroll.salary_per_period else {
+        return 0;
+    };
+    let elapsed = elapsed_periods(
+        env.ledger().timestamp() - payroll.activated_at,
+        payroll.period_seconds,
+    );
+    if elapsed <= payroll.claimed_periods {
+        return 0;
+    }
+    period_payout(
+        env,
+        payroll.agreement_id,
+        employee,
+        salary,
+        payroll.claimed_periods,
+        elapsed - payroll.claimed_periods,
+    )
+    .map_or(0, |(_, amount)| amount)
+}
+
+fn next_payout_time(env: &Env, payroll: &Payroll) -> Option<u64> {
+    let elapsed = elapsed_periods(
+        env.ledger().timestamp() - payroll.activated_at,
+        payroll.period_seconds,
+    );
+    let next = u64::from(elapsed)
+        .checked_add(1)?
+        .checked_mul(payroll.period_seconds)?
+        .checked_add(payroll.activated_at)?;
+    if payroll.status == AgreementStatus::Cancelled {
+        let grace_end = get_grace_period_end(env, payroll.agreement_id)?;
+        if next >= grace_end {
+            return None;
+        }
+    }
+    Some(next)
+}
@@ -0,0 +1,100 @@
+//! Webhook notifications.
+//!
+//! Webhook endpoints, filters, secrets and delivery statistics live in the
+//! separately deployed `webhook_contract` registry, so payouts write no
+//! webhook storage here. When the owner links a registry, every periodic
+//! payout notifies it with a `payroll_claimed` [`WebhookNotification`] for
+//! the employer, naming the employee, the agreement and the amount.
+//! Severance insurance notifies its cover and claim changes the same way.
+//! The registry decides whether anyone subscribed; a failing notification
+//! never blocks the operation itself.
+
+use soroban_sdk::{contracttype, Address, Env, String};
+use webhook_interface::{WebhookNotification, WebhookRegistryClient};
+
+use crate::storage::{PayrollError, StorageKey};
+
+pub use webhook_interface::event_types::PAYROLL_CLAIMED;
+
+#[contracttype]
+#[derive(Clone)]
+enum WebhookKey {
+    /// Linked webhook registry -> Address
+    Registry,
+}
+
+/// Links the webhook registry payouts are notified to, or unlinks it with
+/// `None`. The registry must also allow this contract as a notifier.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - `owner` is not the contract owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_webhook_registry(
+    env: &Env,
+    owner: &Address,
+    registry: Option<Address>,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    match registry {
+        Some(registry) => env
+            .storage()
+            .persistent()
+            .set(&WebhookKey::Registry, &registry),
+        None => env.storage().persistent().remove(&WebhookKey::Registry),
+    }
+    Ok(())
+}
+
+/// Returns the linked webhook registry, if any.
+pub fn get_webhook_registry(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&WebhookKey::Registry)
+}
+
+/// Notifies the linked registry of a payout of `amount` to `employee` on
+/// agreement `agreement_id`. No-op without a registry.
+pub(crate) fn notify_payout(
+    env: &Env,
+    employer: &Address,
+    employee: &Address,
+    agreement_id: u128,
+    amount: i128,
+) {
+    notify(
+        env,
+        PAYROLL_CLAIMED,
+        employer,
+        employee,
+        agreement_id,
+        amount,
+    );
+}
+
+/// Notifies the linked registry of `event_type` for `owner`'s webhooks. No-op
+/// without a registry.
+pub(crate) fn notify(
+    env: &Env,
+    event_type: &str,
+    owner: &Address,
+    subject: &Address,
+    reference: u128,
+    amount: i128,
+) {
+    let Some(registry) = get_webhook_registry(env) else {
+        return;
+    };
+    let notification = WebhookNotification {
+        owner: owner.clone(),
+        event_type: String::from_str(env, event_type),
+        subject: subject.clone(),
+        reference,
+        amount,
+    };
+    let _ = WebhookRegistryClient::new(env, &registry)
+        .try_notify(&env.current_contract_address(), &notification);
+}
@@ -0,0 +1,295 @@
+//! Employer wind-down.
+//!
+//! A company leaving the platform calls `begin_employer_winddown`. From then
+//! on it cannot create agreements, and every agreement it runs is cancelled
+//! so its employees move into the grace period, where they claim their final
+//! pay as usual. The final payout of each agreement is what accrues by the
+//! end of its grace period; any part of it the escrow does not cover is
+//! pulled from the employer up front, so every employee can be paid in full.
+//!
+//! Once nothing is left to claim, or every grace period has ended, anyone can
+//! call `complete_employer_winddown`. It returns each agreement's residual
+//! escrow to the employer, drops the agreements from the employer and
+//! employee indexes, and lifts the creation freeze.
+//!
+//! Confidential salaries are not stored in plaintext and are not escrowed
+//! for; the employer settles them with `disburse_confidential_payroll`
+//! before the grace period ends, and agreements with confidential salaries
+//! keep the wind-down open until then. Disputed agreements keep it open until
+//! the dispute is resolved.
+
+use soroban_sdk::{contracttype, token::Client as TokenClient, Address, Env, Vec};
+
+use crate::confidential::is_confidential;
+use crate::events::{
+    emit_winddown_completed, emit_winddown_started, WinddownCompletedEvent, WinddownStartedEvent,
+};
+use crate::hourly::get_hourly_config;
+use crate::payroll::{
+    cancel_agreement_inner, effective_salary, elapsed_periods, get_agreement,
+    get_employer_agreements, get_grace_period_end, is_grace_period_active, resume_agreement_inner,
+    transfer_from_contract, unindex_agreement_employees,
+};
+use crate::storage::{
+    extend_persistent_ttl, Agreement, AgreementMode, AgreementStatus, DataKey, PayrollError,
+    StorageKey,
+};
+use crate::yield_strategy;
+
+/// Progress of an employer's wind-down.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmployerWinddown {
+    pub started_at: u64,
+    /// When the last grace period ends; the wind-down can complete by then.
+    pub final_payout_at: u64,
+    /// Total pulled from the employer to cover final payouts, across tokens.
+    pub escrowed: i128,
+    /// `None` while final payouts are outstanding.
+    pub completed_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum WinddownKey {
+    /// Wind-down of an employer -> EmployerWinddown
+    Winddown(Address),
+}
+
+/// Returns the employer's wind-down, if it ever began one.
+pub fn get_employer_winddown(env: &Env, employer: &Address) -> Option<EmployerWinddown> {
+    env.storage()
+        .persistent()
+        .get(&WinddownKey::Winddown(employer.clone()))
+}
+
+/// Fails if the employer is winding down and may not create agreements.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - the employer's wind-down is in progress
+pub(crate) fn ensure_not_winding_down(env: &Env, employer: &Address) -> Result<(), PayrollError> {
+    match get_employer_winddown(env, employer) {
+        Some(winddown) if winddown.completed_at.is_none() => Err(PayrollError::Unauthorized),
+        _ => Ok(()),
+    }
+}
+
+/// Starts winding down `employer`: freezes agreement creation, cancels its
+/// created, active and paused agreements, and tops up each escrow to cover
+/// the final payouts.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - a wind-down is already in progress
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn begin_employer_winddown(
+    env: &Env,
+    employer: &Address,
+) -> Result<EmployerWinddown, PayrollError> {
+    employer.require_auth();
+    if ensure_not_winding_down(env, employer).is_err() {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let now = env.ledger().timestamp();
+    let mut winddown = EmployerWinddown {
+        started_at: now,
+        final_payout_at: now,
+        escrowed: 0,
+        completed_at: None,
+    };
+    let mut cancelled = 0u32;
+    for agreement_id in get_employer_agreements(env, employer).iter() {
+        let Some(mut agreement) = get_agreement(env, agreement_id) else {
+            continue;
+        };
+        match agreement.status {
+            AgreementStatus::Created | AgreementStatus::Active => {}
+            AgreementStatus::Paused => {
+                resume_agreement_inner(env, agreement_id, agreement.clone());
+                agreement.status = AgreementStatus::Active;
+            }
+            _ => continue,
+        }
+        cancel_agreement_inner(env, agreement_id, agreement);
+        cancelled += 1;
+
+        let Some(agreement) = get_agreement(env, agreement_id) else {
+            continue;
+        };
+        let Some(grace_end) = get_grace_period_end(env, agreement_id) else {
+            continue;
+        };
+        winddown.final_payout_at = winddown.final_payout_at.max(grace_end);
+
+        let token = escrow_token(env, &agreement);
+        let liquid = yield_strategy::ensure_all_liquid(env, agreement_id, &token);
+        let balance = DataKey::get_agreement_escrow_balance(env, agreement_id, &token);
+        let shortfall = final_payout_due(env, &agreement, grace_end) - liquid;
+        if shortfall > 0 {
+            TokenClient::new(env, &token).transfer(
+                employer,
+                env.current_contract_address(),
+                &shortfall,
+            );
+            DataKey::set_agreement_escrow_balance(env, agreement_id, &token, balance + shortfall);
+            winddown.escrowed = winddown
+                .escrowed
+                .checked_add(shortfall)
+                .ok_or(PayrollError::InvalidData)?;
+        }
+    }
+
+    let key = WinddownKey::Winddown(employer.clone());
+    env.storage().persistent().set(&key, &winddown);
+    extend_persistent_ttl(env, &key);
+    emit_winddown_started(
+        env,
+        WinddownStartedEvent {
+            employer: employer.clone(),
+            agreements: cancelled,
+            escrowed: winddown.escrowed,
+            final_payout_at: winddown.final_payout_at,
+        },
+    );
+    Ok(winddown)
+}
+
+/// Completes the employer's wind-down: returns each agreement's residual
+/// escrow to the employer, removes its agreements from the employer and
+/// employee indexes, and lifts the creation freeze. Returns the number of
+/// agreements closed.
+///
+/// # Errors
+/// * `PayrollError::InvalidData` - no wind-down is in progress, or an
+///   agreement is disputed or still owes final pay within its grace period
+///
+/// # Access Control
+/// Anyone may call this once the final payouts are done.
+pub fn complete_employer_winddown(env: &Env, employer: &Address) -> Result<u32, PayrollError> {
+    let key = WinddownKey::Winddown(employer.clone());
+    let mut winddown: EmployerWinddown = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .filter(|winddown: &EmployerWinddown| winddown.completed_at.is_none())
+        .ok_or(PayrollError::InvalidData)?;
+
+    let agreements = get_employer_agreements(env, employer);
+    let mut closing: Vec<Agreement> = Vec::new(env);
+    for agreement_id in agreements.iter() {
+        let Some(agreement) = get_agreement(env, agreement_id) else {
+            continue;
+        };
+        let settled = match agreement.status {
+            AgreementStatus::Completed => true,
+            AgreementStatus::Cancelled => match get_grace_period_end(env, agreement_id) {
+                Some(grace_end) => {
+                    !is_grace_period_active(env, agreement_id)
+                        || (final_payout_due(env, &agreement, grace_end) == 0
+                            && !has_confidential_salaries(env, agreement_id))
+                }
+                None => true,
+            },
+            _ => false,
+        };
+        if !settled {
+            return Err(PayrollError::InvalidData);
+        }
+        closing.push_back(agreement);
+    }
+
+    for agreement in closing.iter() {
+        let token = escrow_token(env, &agreement);
+        let residual = yield_strategy::release_escrow(env, agreement.id, &token);
+        if residual > 0 {
+            DataKey::set_agreement_escrow_balance(env, agreement.id, &token, 0);
+            transfer_from_contract(env, &token, employer, residual);
+        }
+        unindex_agreement_employees(env, agreement.id);
+    }
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::EmployerAgreements(employer.clone()));
+
+    winddown.completed_at = Some(env.ledger().timestamp());
+    env.storage().persistent().set(&key, &winddown);
+    extend_persistent_ttl(env, &key);
+    emit_winddown_completed(
+        env,
+        WinddownCompletedEvent {
+            employer: employer.clone(),
+            agreements: closing.len(),
+        },
+    );
+    Ok(closing.len())
+}
+
+/// Token the agreement's escrow and payouts are held in.
+fn escrow_token(env: &Env, agreement: &Agreement) -> Address {
+    DataKey::get_agreement_token(env, agreement.id).unwrap_or(agreement.token.clone())
+}
+
+/// Pay still owed on a cancelled agreement by the end of its grace period,
+/// the last moment it can be claimed. Hourly employees are counted at their
+/// attestation cap; confidential salaries are left out.
+fn final_payout_due(env: &Env, agreement: &Agreement, grace_end: u64) -> i128 {
+    let last_claim_at = grace_end.saturating_sub(1);
+    match agreement.mode {
+        AgreementMode::Payroll => {
+            let (Some(activated_at), Some(period_seconds)) = (
+                DataKey::get_agreement_activation_time(env, agreement.id),
+                DataKey::get_agreement_period_duration(env, agreement.id),
+            ) else {
+                return 0;
+            };
+            if period_seconds == 0 || last_claim_at < activated_at {
+                return 0;
+            }
+            let mut periods = elapsed_periods(last_claim_at - activated_at, period_seconds);
+            if let Some(num_periods) = agreement.num_periods {
+                periods = periods.min(num_periods);
+            }
+            let hours = get_hourly_config(env, agreement.id)
+                .map_or(1, |config| i128::from(config.max_hours_per_period));
+            let mut due: i128 = 0;
+            for index in 0..DataKey::get_employee_count(env, agreement.id) {
+                if is_confidential(env, agreement.id, index) {
+                    continue;
+                }
+                let Some(employee) = DataKey::get_employee(env, agreement.id, index) else {
+                    continue;
+                };
+                let Ok(salary) = effective_salary(env, agreement.id, index, &employee) else {
+                    continue;
+                };
+                let claimed = DataKey::get_employee_claimed_periods(env, agreement.id, index);
+                let owed = i128::from(periods.saturating_sub(claimed));
+                due = due.saturating_add(owed.saturating_mul(salary).saturating_mul(hours));
+            }
+            due
+        }
+        AgreementMode::Escrow => {
+            let (Some(activated_at), Some(period_seconds), Some(amount)) = (
+                agreement.activated_at,
+                agreement.period_seconds,
+                agreement.amount_per_period,
+            ) else {
+                return 0;
+            };
+            if period_seconds == 0 || last_claim_at < activated_at {
+                return 0;
+            }
+            let periods = elapsed_periods(last_claim_at - activated_at, period_seconds)
+                .min(agreement.num_periods.unwrap_or(0));
+            let owed = periods.saturating_sub(agreement.claimed_periods.unwrap_or(0));
+            i128::from(owed).saturating_mul(amount)
+        }
+    }
+}
+
+fn has_confidential_salaries(env: &Env, agreement_id: u128) -> bool {
+    (0..DataKey::get_employee_count(env, agreement_id))
+        .any(|index| is_confidential(env, agreement_id, index))
+}
@@ -0,0 +1,407 @@
+//! Opt-in yield on idle escrow.
+//!
+//! An employer may route part of an agreement's escrow to a yield strategy
+//! between pay periods. Strategies implement the adapter in
+//! [`stellopay_interfaces::strategy`] and must be on the governance
+//! contract's allow-list (`ProposalKind::YieldStrategy`) when funds go in;
+//! withdrawals work even after a strategy is removed, so a revoked strategy
+//! can always be exited.
+//!
+//! This contract holds one position per strategy. Agreements own shares of
+//! it, so each agreement's value, and with it its yield, is tracked
+//! separately. The deployed amount stays part of the agreement's escrow
+//! balance as principal. Yield never does: when a position is closed, the
+//! principal returns to the escrow and the yield goes to the employer's
+//! earned-yield balance, which the employer withdraws separately. A strategy
+//! loss is taken from the escrow.
+//!
+//! Withdrawal before disbursement is conservative: before any payout,
+//! refund or migration would need more of the agreement's escrow than is
+//! held here, the whole position is closed first. Payouts therefore never
+//! depend on the strategy returning funds at the exact amount. If the
+//! strategy does not pay out, the position stays open and payouts are
+//! limited to the escrow held here; a refund then releases the position, and
+//! closing it later pays the employer directly.
+
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contracttype, Address, Env, IntoVal, Symbol, Val, Vec,
+};
+use stellopay_interfaces::strategy::{StrategyRegistryClient, YieldStrategyClient};
+
+use crate::events::{
+    emit_yield_deposited, emit_yield_withdrawn, YieldDepositedEvent, YieldWithdrawnEvent,
+};
+use crate::pause_exemptions::{is_paused_for, PauseCategory};
+use crate::payroll::{get_agreement, transfer_from_contract};
+use crate::storage::{extend_persistent_ttl, AgreementStatus, DataKey, PayrollError, StorageKey};
+
+#[contracttype]
+#[derive(Clone)]
+enum YieldKey {
+    /// Governance contract holding the strategy allow-list -> Address
+    Governance,
+    /// Position of an agreement -> YieldPosition
+    Position(u128),
+    /// Shares issued by this contract in a strategy -> i128
+    TotalShares(Address),
+    /// Yield an employer earned in a token and has not withdrawn -> i128
+    Earned(Address, Address),
+    /// Position left open when its agreement's escrow was refunded -> bool
+    Released(u128),
+}
+
+/// An agreement's share of this contract's position in a strategy.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YieldPosition {
+    pub strategy: Address,
+    pub token: Address,
+    /// Escrow deployed to the strategy and not yet returned.
+    pub principal: i128,
+    pub shares: i128,
+}
+
+/// Links the governance contract whose allow-list strategies must be on.
+///
+/// # Errors
+/// * `PayrollError::Unauthorized` - caller is not the owner
+///
+/// # Access Control
+/// Requires owner authentication
+pub fn set_strategy_governance(
+    env: &Env,
+    owner: &Address,
+    governance: &Address,
+) -> Result<(), PayrollError> {
+    owner.require_auth();
+    let stored_owner: Option<Address> = env.storage().persistent().get(&StorageKey::Owner);
+    if stored_owner.as_ref() != Some(owner) {
+        return Err(PayrollError::Unauthorized);
+    }
+    env.storage()
+        .persistent()
+        .set(&YieldKey::Governance, governance);
+    Ok(())
+}
+
+/// Returns the linked strategy governance contract, if any.
+pub fn get_strategy_governance(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&YieldKey::Governance)
+}
+
+/// Returns the agreement's strategy position, if it has one.
+pub fn get_yield_position(env: &Env, agreement_id: u128) -> Option<YieldPosition> {
+    env.storage()
+        .persistent()
+        .get(&YieldKey::Position(agreement_id))
+}
+
+/// Returns the yield `employer` earned in `token` and has not withdrawn.
+pub fn get_earned_yield(env: &Env, employer: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&YieldKey::Earned(employer.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+/// Current value of an agreement's position, principal plus yield, or 0 if
+/// the strategy does not report its balance.
+pub fn get_position_value(env: &Env, agreement_id: u128) -> i128 {
+    get_yield_position(env, agreement_id)
+        .and_then(|position| value_of(env, &position))
+        .unwrap_or(0)
+}
+
+fn total_shares(env: &Env, strategy: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&YieldKey::TotalShares(strategy.clone()))
+        .unwrap_or(0)
+}
+
+fn set_total_shares(env: &Env, strategy: &Address, shares: i128) {
+    let key = YieldKey::TotalShares(strategy.clone());
+    env.storage().persistent().set(&key, &shares);
+    extend_persistent_ttl(env, &key);
+}
+
+fn value_of(env: &Env, position: &YieldPosition) -> Option<i128> {
+    let total = total_shares(env, &position.strategy);
+    if total == 0 {
+        return Some(0);
+    }
+    let Ok(Ok(held)) = YieldStrategyClient::new(env, &position.strategy)
+        .try_balance(&env.current_contract_address())
+    else {
+        return None;
+    };
+    Some(
+        held.checked_mul(position.shares)
+            .map_or(0, |product| product / total),
+    )
+}
+
+/// Routes `amount` of the agreement's idle escrow to `strategy`.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - the agreement does not exist
+/// * `PayrollError::Unauthorized` - `employer` does not own the agreement, or
+///   `strategy` is not on the governance allow-list
+/// * `PayrollError::EmergencyPaused` - the contract is paused
+/// * `PayrollError::InvalidData` - no governance is linked, `amount` is not
+///   positive, the agreement is cancelled, disputed or completed, the
+///   strategy holds another token, or the agreement already uses another
+///   strategy
+/// * `PayrollError::InsufficientEscrowBalance` - less than `amount` of the
+///   escrow is held by this contract
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn deposit_to_strategy(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+    strategy: &Address,
+    amount: i128,
+) -> Result<(), PayrollError> {
+    employer.require_auth();
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    if is_paused_for(env, PauseCategory::YieldDeposits) {
+        return Err(PayrollError::EmergencyPaused);
+    }
+    if amount <= 0
+        || !matches!(
+            agreement.status,
+            AgreementStatus::Created | AgreementStatus::Active | AgreementStatus::Paused
+        )
+    {
+        return Err(PayrollError::InvalidData);
+    }
+    let governance = get_strategy_governance(env).ok_or(PayrollError::InvalidData)?;
+    if !StrategyRegistryClient::new(env, &governance).is_strategy_approved(strategy) {
+        return Err(PayrollError::Unauthorized);
+    }
+    let client = YieldStrategyClient::new(env, strategy);
+    if client.asset() != agreement.token {
+        return Err(PayrollError::InvalidData);
+    }
+
+    let mut position = match get_yield_position(env, agreement_id) {
+        Some(position) if position.strategy != *strategy => return Err(PayrollError::InvalidData),
+        Some(position) => position,
+        None => YieldPosition {
+            strategy: strategy.clone(),
+            token: agreement.token.clone(),
+            principal: 0,
+            shares: 0,
+        },
+    };
+    let escrow = DataKey::get_agreement_escrow_balance(env, agreement_id, &agreement.token);
+    if escrow - position.principal < amount {
+        return Err(PayrollError::InsufficientEscrowBalance);
+    }
+
+    // Shares are priced at the position's value before this deposit.
+    let contract = env.current_contract_address();
+    let held = client.balance(&contract);
+    let total = total_shares(env, strategy);
+    let shares = if total == 0 || held == 0 {
+        amount
+    } else {
+        amount.checked_mul(total).ok_or(PayrollError::InvalidData)? / held
+    };
+    position.principal += amount;
+    position.shares += shares;
+    let key = YieldKey::Position(agreement_id);
+    env.storage().persistent().set(&key, &position);
+    extend_persistent_ttl(env, &key);
+    set_total_shares(env, strategy, total + shares);
+
+    // The strategy pulls the tokens, so this contract pre-authorizes that
+    // nested transfer.
+    env.authorize_as_current_contract(Vec::from_array(
+        env,
+        [InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: agreement.token.clone(),
+                fn_name: Symbol::new(env, "transfer"),
+                args: Vec::<Val>::from_array(
+                    env,
+                    [
+                        contract.clone().into_val(env),
+                        strategy.clone().into_val(env),
+                        amount.into_val(env),
+                    ],
+                ),
+            },
+            sub_invocations: Vec::new(env),
+        })],
+    ));
+    client.deposit(&contract, &amount);
+
+    emit_yield_deposited(
+        env,
+        YieldDepositedEvent {
+            agreement_id,
+            strategy: strategy.clone(),
+            amount,
+        },
+    );
+    Ok(())
+}
+
+/// Closes the agreement's position, returning its principal to the escrow
+/// and crediting the yield to the employer. Returns the yield, negative for
+/// a loss.
+///
+/// # Errors
+/// * `PayrollError::AgreementNotFound` - the agreement does not exist
+/// * `PayrollError::Unauthorized` - `employer` does not own the agreement
+/// * `PayrollError::InvalidData` - the agreement has no position
+/// * `PayrollError::TransferFailed` - the strategy did not pay the position
+///   out; it stays open
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn withdraw_from_strategy(
+    env: &Env,
+    employer: &Address,
+    agreement_id: u128,
+) -> Result<i128, PayrollError> {
+    employer.require_auth();
+    let agreement = get_agreement(env, agreement_id).ok_or(PayrollError::AgreementNotFound)?;
+    if agreement.employer != *employer {
+        return Err(PayrollError::Unauthorized);
+    }
+    let position = get_yield_position(env, agreement_id).ok_or(PayrollError::InvalidData)?;
+    close(env, agreement_id, &agreement.employer, position).ok_or(PayrollError::TransferFailed)
+}
+
+/// Pays the yield `employer` earned in `token` out to them and returns it.
+///
+/// # Access Control
+/// Requires employer authentication
+pub fn withdraw_earned_yield(env: &Env, employer: &Address, token: &Address) -> i128 {
+    employer.require_auth();
+    let earned = get_earned_yield(env, employer, token);
+    if earned > 0 {
+        env.storage()
+            .persistent()
+            .remove(&YieldKey::Earned(employer.clone(), token.clone()));
+        transfer_from_contract(env, token, employer, earned);
+    }
+    earned
+}
+
+/// Makes sure `amount` of the agreement's `token` escrow is held by this
+/// contract, closing its position first if not, and returns the escrow held
+/// here. Call before a payout and check it against the returned amount, not
+/// the escrow balance: a loss lowers the escrow, and a position the strategy
+/// does not pay out stays open with its principal left out.
+pub(crate) fn ensure_liquid(env: &Env, agreement_id: u128, token: &Address, amount: i128) -> i128 {
+    let escrow = DataKey::get_agreement_escrow_balance(env, agreement_id, token);
+    let Some(position) = get_yield_position(env, agreement_id) else {
+        return escrow;
+    };
+    if position.token != *token || is_released(env, agreement_id) {
+        return escrow;
+    }
+    let liquid = escrow - position.principal;
+    if liquid >= amount {
+        return liquid;
+    }
+    let closed = get_agreement(env, agreement_id)
+        .and_then(|agreement| close(env, agreement_id, &agreement.employer, position));
+    match closed {
+        Some(_) => DataKey::get_agreement_escrow_balance(env, agreement_id, token),
+        None => liquid,
+    }
+}
+
+/// Closes the agreement's position, if any, before its whole `token` escrow
+/// leaves, and returns the escrow held here.
+pub(crate) fn ensure_all_liquid(env: &Env, agreement_id: u128, token: &Address) -> i128 {
+    ensure_liquid(env, agreement_id, token, i128::MAX)
+}
+
+/// Prepares the agreement's whole `token` escrow for a refund and returns
+/// the part held here, which the caller pays out before zeroing the escrow.
+/// A position the strategy does not pay out is released: it leaves the
+/// escrow, and closing it later pays the employer directly.
+pub(crate) fn release_escrow(env: &Env, agreement_id: u128, token: &Address) -> i128 {
+    let liquid = ensure_all_liquid(env, agreement_id, token);
+    if get_yield_position(env, agreement_id).is_some_and(|position| position.token == *token) {
+        let key = YieldKey::Released(agreement_id);
+        env.storage().persistent().set(&key, &true);
+        extend_persistent_ttl(env, &key);
+        DataKey::set_agreement_escrow_balance(env, agreement_id, token, 0);
+    }
+    liquid
+}
+
+fn is_released(env: &Env, agreement_id: u128) -> bool {
+    env.storage()
+        .persistent()
+        .has(&YieldKey::Released(agreement_id))
+}
+
+/// Closes the position and returns its yield, or `None`, leaving it open,
+/// if the strategy does not pay it out.
+fn close(
+    env: &Env,
+    agreement_id: u128,
+    employer: &Address,
+    position: YieldPosition,
+) -> Option<i128> {
+    let value = value_of(env, &position)?;
+    if value > 0 {
+        let withdrawn = YieldStrategyClient::new(env, &position.strategy)
+            .try_withdraw(&env.current_contract_address(), &value);
+        if !matches!(withdrawn, Ok(Ok(()))) {
+            return None;
+        }
+    }
+    let total = total_shares(env, &position.strategy);
+    env.storage()
+        .persistent()
+        .remove(&YieldKey::Position(agreement_id));
+    set_total_shares(env, &position.strategy, total - position.shares);
+
+    let earned = value - position.principal;
+    if is_released(env, agreement_id) {
+        env.storage()
+            .persistent()
+            .remove(&YieldKey::Released(agreement_id));
+        if value > 0 {
+            transfer_from_contract(env, &position.token, employer, value);
+        }
+    } else if earned > 0 {
+        let key = YieldKey::Earned(employer.clone(), position.token.clone());
+        let total_earned = get_earned_yield(env, employer, &position.token) + earned;
+        env.storage().persistent().set(&key, &total_earned);
+        extend_persistent_ttl(env, &key);
+    } else if earned < 0 {
+        let escrow = DataKey::get_agreement_escrow_balance(env, agreement_id, &position.token);
+        DataKey::set_agreement_escrow_balance(
+            env,
+            agreement_id,
+            &position.token,
+            (escrow + earned).max(0),
+        );
+    }
+
+    emit_yield_withdrawn(
+        env,
+        YieldWithdrawnEvent {
+            agreement_id,
+            strategy: position.strategy,
+            principal: position.principal,
+            earned,
+        },
+    );
+    Some(earned)
+}
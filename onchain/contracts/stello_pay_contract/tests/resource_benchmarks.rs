@@ -0,0 +1,279 @@
+//! CPU and memory benchmarks for key entrypoints.
+//!
+//! Measures Soroban CPU instructions and memory bytes via
+//! [`soroban_sdk::testutils::budget`] for single and batch disbursements, an
+//! audit history append and backup creation, and compares them against the
+//! committed baselines in `benchmarks/stello_pay_contract_resources.json`.
+//! CI fails when either measure exceeds its baseline by more than 5%.
+//!
+//! # Running
+//!
+//! ```bash
+//! cd onchain
+//! cargo test -p stello_pay_contract resource_benchmark -- --nocapture
+//! ```
+//!
+//! # Updating baselines
+//!
+//! After an intentional contract change that increases resource usage:
+//!
+//! ```bash
+//! UPDATE_RESOURCE_BASELINES=1 cargo test -p stello_pay_contract resource_benchmark -- --nocapture
+//! ```
+
+#![cfg(test)]
+
+use soroban_sdk::{Env, Vec};
+use stello_pay_contract::{
+    audit::{record_entry, AuditEvent},
+    backup::backup_agreement,
+    storage::MAX_BATCH_SIZE,
+    testutils::{Fixture, DAY},
+};
+
+const REGRESSION_TOLERANCE_PCT: u64 = 5;
+
+const BASELINE_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../../benchmarks/stello_pay_contract_resources.json"
+);
+
+const BATCH_SIZES: [u32; 2] = [5, MAX_BATCH_SIZE];
+
+// ---------------------------------------------------------------------------
+// Baseline I/O
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Usage {
+    cpu_instructions: u64,
+    memory_bytes: u64,
+}
+
+fn parse_u64_field(json: &str, key: &str) -> u64 {
+    let needle = format!("\"{key}\": ");
+    let start = json
+        .find(&needle)
+        .unwrap_or_else(|| panic!("missing field {key} in baseline JSON"))
+        + needle.len();
+    json[start..]
+        .split(|c: char| !c.is_ascii_digit())
+        .next()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(|| panic!("invalid numeric value for {key}"))
+}
+
+fn load_baselines() -> std::vec::Vec<(String, Usage)> {
+    let json = std::fs::read_to_string(BASELINE_PATH)
+        .unwrap_or_else(|e| panic!("failed to read {BASELINE_PATH}: {e}"));
+    json.split("{ \"name\": \"")
+        .skip(1)
+        .map(|block| {
+            let name = block.split('"').next().unwrap().to_string();
+            let usage = Usage {
+                cpu_instructions: parse_u64_field(block, "cpu_instructions"),
+                memory_bytes: parse_u64_field(block, "memory_bytes"),
+            };
+            (name, usage)
+        })
+        .collect()
+}
+
+fn write_baselines(measured: &[(String, Usage)]) {
+    let body = format!(
+        r#"{{
+  "version": 1,
+  "sdk_version": "23.5.2",
+  "captured_at": "2026-10-15",
+  "regression_tolerance_pct": {REGRESSION_TOLERANCE_PCT},
+  "host": "soroban-sdk test host (native Rust, not WASM)",
+  "description": "CPU instructions and memory bytes per entrypoint call, measured with the Soroban budget API",
+  "entrypoints": [
+{cases}
+  ]
+}}
+"#,
+        cases = measured
+            .iter()
+            .map(|(name, u)| format!(
+                "    {{ \"name\": \"{name}\", \"cpu_instructions\": {}, \"memory_bytes\": {} }},",
+                u.cpu_instructions, u.memory_bytes
+            ))
+            .collect::<std::vec::Vec<_>>()
+            .join("\n"),
+    );
+    std::fs::write(BASELINE_PATH, body)
+        .unwrap_or_else(|e| panic!("failed to write {BASELINE_PATH}: {e}"));
+}
+
+// ---------------------------------------------------------------------------
+// Budget measurement (soroban_sdk::testutils::budget)
+// ---------------------------------------------------------------------------
+
+/// Resets the Soroban budget tracker, runs `f`, and returns its CPU and
+/// memory cost.
+fn measure<F: FnOnce()>(env: &Env, f: F) -> Usage {
+    env.cost_estimate().budget().reset_default();
+    f();
+    let budget = env.cost_estimate().budget();
+    Usage {
+        cpu_instructions: budget.cpu_instruction_cost(),
+        memory_bytes: budget.memory_bytes_cost(),
+    }
+}
+
+fn assert_within_tolerance(name: &str, measured: Usage, baseline: Usage) {
+    for (label, measured, baseline) in [
+        (
+            "cpu_instructions",
+            measured.cpu_instructions,
+            baseline.cpu_instructions,
+        ),
+        ("memory_bytes", measured.memory_bytes, baseline.memory_bytes),
+    ] {
+        let max_allowed = baseline + (baseline * REGRESSION_TOLERANCE_PCT / 100);
+        assert!(
+            measured <= max_allowed,
+            "{name}: measured {measured} {label} exceeds baseline {baseline} + {REGRESSION_TOLERANCE_PCT}% (= {max_allowed})"
+        );
+        println!("{name} {label}: measured={measured} baseline={baseline} max={max_allowed}");
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Scenarios
+// ---------------------------------------------------------------------------
+
+/// Milestone agreement with `n` approved, funded milestones.
+fn funded_milestones(f: &Fixture, n: u32) -> (u128, Vec<u32>) {
+    let amount = 1_000;
+    let contributor = f.address();
+    let id = f
+        .client
+        .create_milestone_agreement(&f.employer, &contributor, &f.token.address);
+    let mut ids = Vec::new(&f.env);
+    for milestone in 1..=n {
+        f.client.add_milestone(&id, &amount);
+        ids.push_back(milestone);
+    }
+    let total = amount * i128::from(n);
+    f.token.mint(&f.employer, total);
+    f.client.fund_milestone_agreement(&id, &f.employer, &total);
+    for milestone in ids.iter() {
+        f.client.approve_milestone(&id, &milestone);
+    }
+    (id, ids)
+}
+
+fn claim_payroll() -> Usage {
+    let f = Fixture::new();
+    let employee = f.address();
+    let id = f.payroll().employee(&employee, 1_000).build();
+    f.warp(DAY);
+    measure(&f.env, || {
+        f.client.claim_payroll(&employee, &id, &0);
+    })
+}
+
+fn claim_milestone() -> Usage {
+    let f = Fixture::new();
+    let (id, _) = funded_milestones(&f, 1);
+    measure(&f.env, || {
+        f.client.claim_milestone(&id, &1);
+    })
+}
+
+fn batch_claim_milestones(n: u32) -> Usage {
+    let f = Fixture::new();
+    let (id, ids) = funded_milestones(&f, n);
+    measure(&f.env, || {
+        let result = f.client.batch_claim_milestones(&id, &ids);
+        assert_eq!(result.successful_claims, n);
+    })
+}
+
+fn audit_append() -> Usage {
+    let f = Fixture::new();
+    let id = f.payroll().employee(&f.address(), 1_000).build();
+    measure(&f.env, || {
+        f.env.as_contract(&f.client.address, || {
+            record_entry(
+                &f.env,
+                f.employer.clone(),
+                AuditEvent::AgreementActivated,
+                id,
+                None,
+                None,
+            );
+        });
+    })
+}
+
+fn backup_creation() -> Usage {
+    let f = Fixture::new();
+    let id = f.payroll().employee(&f.address(), 1_000).build();
+    let agreement = f.client.get_agreement(&id).unwrap();
+    measure(&f.env, || {
+        let envelope = backup_agreement(&f.env, &agreement, b"passphrase", &[7; 16], &[9; 12]);
+        assert!(!envelope.is_empty());
+    })
+}
+
+fn measure_all() -> std::vec::Vec<(String, Usage)> {
+    let mut measured = std::vec![
+        ("claim_payroll".to_string(), claim_payroll()),
+        ("claim_milestone".to_string(), claim_milestone()),
+    ];
+    for n in BATCH_SIZES {
+        measured.push((
+            format!("batch_claim_milestones/{n}"),
+            batch_claim_milestones(n),
+        ));
+    }
+    measured.push(("audit_append".to_string(), audit_append()));
+    measured.push(("backup_agreement".to_string(), backup_creation()));
+    measured
+}
+
+// ---------------------------------------------------------------------------
+// Benchmark tests
+// ---------------------------------------------------------------------------
+
+/// Measures every scenario and compares it against its committed baseline.
+#[test]
+fn resource_benchmark_entrypoints() {
+    let measured = measure_all();
+    if std::env::var("UPDATE_RESOURCE_BASELINES").ok().as_deref() == Some("1") {
+        write_baselines(&measured);
+        return;
+    }
+
+    let baselines = load_baselines();
+    assert_eq!(
+        baselines
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<std::vec::Vec<_>>(),
+        measured
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<std::vec::Vec<_>>(),
+        "baseline file must define one entry per scenario, in order"
+    );
+    for ((name, usage), (_, baseline)) in measured.iter().zip(baselines.iter()) {
+        assert_within_tolerance(name, *usage, *baseline);
+    }
+}
+
+/// A batch claim must cost less per milestone than claiming one at a time.
+#[test]
+fn resource_benchmark_batching_amortizes_per_call_cost() {
+    let single = claim_milestone();
+    let batch = batch_claim_milestones(MAX_BATCH_SIZE);
+    assert!(
+        batch.cpu_instructions < single.cpu_instructions * u64::from(MAX_BATCH_SIZE),
+        "batch of {MAX_BATCH_SIZE} used {} instructions, {MAX_BATCH_SIZE} single claims {}",
+        batch.cpu_instructions,
+        single.cpu_instructions * u64::from(MAX_BATCH_SIZE)
+    );
+}
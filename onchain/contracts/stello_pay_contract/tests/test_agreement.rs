@@ -6,8 +6,8 @@
 #![cfg(test)]
 #![allow(deprecated)]
 
-use soroban_sdk::{testutils::Address as _, Address, Env};
-use stello_pay_contract::storage::{AgreementMode, AgreementStatus};
+use soroban_sdk::{testutils::Address as _, vec, Address, Env};
+use stello_pay_contract::storage::{AgreementMode, AgreementStatus, PayrollError};
 use stello_pay_contract::{PayrollContract, PayrollContractClient};
 
 // ============================================================================
@@ -501,6 +501,126 @@ fn test_get_agreement_mode() {
     );
 }
 
+/// A contractor on agreements with several employers can find each of them.
+#[test]
+fn test_employee_agreements_across_employers() {
+    let env = create_test_env();
+    let (_contract_id, client) = setup_contract(&env);
+    let first_employer = create_test_address(&env);
+    let second_employer = create_test_address(&env);
+    let token = create_test_address(&env);
+    let contractor = create_test_address(&env);
+    let other_employee = create_test_address(&env);
+
+    let first_id = client.create_payroll_agreement(&first_employer, &token, &604800u64);
+    client.add_employee_to_agreement(&first_id, &contractor, &1000i128);
+    client.add_employee_to_agreement(&first_id, &other_employee, &1000i128);
+    let second_id = client.create_payroll_agreement(&second_employer, &token, &604800u64);
+    client.add_employee_to_agreement(&second_id, &contractor, &2000i128);
+    let escrow_id = client.create_escrow_agreement(
+        &first_employer,
+        &contractor,
+        &token,
+        &500i128,
+        &86400u64,
+        &4u32,
+    );
+
+    assert_eq!(
+        client.get_employee_agreements(&contractor),
+        vec![&env, first_id, second_id, escrow_id]
+    );
+    assert_eq!(
+        client.get_employee_agreements(&other_employee),
+        vec![&env, first_id]
+    );
+    assert_eq!(
+        client.get_employer_agreements(&first_employer),
+        vec![&env, first_id, escrow_id]
+    );
+    assert_eq!(
+        client.get_employment_agreements(&first_employer, &contractor),
+        vec![&env, first_id, escrow_id]
+    );
+    assert_eq!(
+        client.get_employment_agreements(&second_employer, &contractor),
+        vec![&env, second_id]
+    );
+    assert!(client
+        .get_employment_agreements(&second_employer, &other_employee)
+        .is_empty());
+}
+
+/// Agreements created with a nonce get the precomputable derived ID.
+#[test]
+fn test_nonced_agreement_ids_are_precomputable() {
+    let env = create_test_env();
+    let (_contract_id, client) = setup_contract(&env);
+    let employer = create_test_address(&env);
+    let contributor = create_test_address(&env);
+    let token = create_test_address(&env);
+
+    let payroll_id = client.compute_agreement_id(&employer, &1u64);
+    let escrow_id = client.compute_agreement_id(&employer, &2u64);
+    assert_ne!(payroll_id, escrow_id);
+    assert_ne!(payroll_id, client.compute_agreement_id(&contributor, &1u64));
+
+    assert_eq!(
+        client.create_nonced_payroll_agreement(&employer, &token, &604800u64, &1u64),
+        payroll_id
+    );
+    assert_eq!(
+        client.create_nonced_escrow_agreement(
+            &employer,
+            &contributor,
+            &token,
+            &1000i128,
+            &86400u64,
+            &4u32,
+            &2u64,
+        ),
+        escrow_id
+    );
+    assert_eq!(client.get_agreement(&payroll_id).unwrap().id, payroll_id);
+    assert_eq!(
+        client.get_agreement(&escrow_id).unwrap().mode,
+        AgreementMode::Escrow
+    );
+
+    // Counter-assigned IDs are unaffected
+    assert_eq!(
+        client.create_payroll_agreement(&employer, &token, &604800u64),
+        1
+    );
+}
+
+/// A nonce cannot be reused by the same employer.
+#[test]
+fn test_nonced_agreement_rejects_reused_nonce() {
+    let env = create_test_env();
+    let (_contract_id, client) = setup_contract(&env);
+    let employer = create_test_address(&env);
+    let contributor = create_test_address(&env);
+    let token = create_test_address(&env);
+
+    client.create_nonced_payroll_agreement(&employer, &token, &604800u64, &1u64);
+    assert_eq!(
+        client.try_create_nonced_escrow_agreement(
+            &employer,
+            &contributor,
+            &token,
+            &1000i128,
+            &86400u64,
+            &4u32,
+            &1u64,
+        ),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert!(client
+        .try_create_nonced_payroll_agreement(&employer, &token, &604800u64, &1u64)
+        .is_err());
+}
+
 // ============================================================================
 // Edge cases
 // ============================================================================
@@ -0,0 +1,213 @@
+#![cfg(test)]
+
+use multisig::{MultisigContract, MultisigContractClient, OperationKind};
+use rbac::{RbacContract, RbacContractClient, Role};
+use soroban_sdk::{vec, Address, Vec};
+use stello_pay_contract::{
+    approval_matrix::ApprovalTier,
+    storage::{DataKey, PayrollError},
+    testutils::{Fixture, TestToken, DAY},
+};
+
+fn tier(min_amount: i128, approvals: u32, roles: Vec<Role>) -> ApprovalTier {
+    ApprovalTier {
+        min_amount,
+        approvals,
+        roles,
+    }
+}
+
+/// Deploys a 2-of-3 multisig and links it with the global threshold disabled.
+fn setup_multisig<'a>(f: &Fixture<'a>) -> (MultisigContractClient<'a>, Vec<Address>) {
+    let ms = MultisigContractClient::new(&f.env, &f.env.register(MultisigContract, ()));
+    let signers = vec![&f.env, f.address(), f.address(), f.address()];
+    ms.initialize(&f.address(), &signers, &2, &None);
+    f.client.set_multisig_config(&f.owner, &ms.address, &0, &0);
+    (ms, signers)
+}
+
+/// Has the first two signers approve paying `amount` to `to`. Executing the
+/// operation also pays `to` from the multisig wallet.
+fn approve_payment(
+    f: &Fixture,
+    ms: &MultisigContractClient,
+    signers: &Vec<Address>,
+    to: &Address,
+    amount: i128,
+) -> u128 {
+    f.token.mint(&ms.address, amount);
+    let kind = OperationKind::LargePayment(f.token.address.clone(), to.clone(), amount);
+    let op = ms.propose_operation(&signers.get(0).unwrap(), &kind);
+    ms.approve_operation(&signers.get(1).unwrap(), &op);
+    op
+}
+
+#[test]
+fn required_approvals_follow_the_employer_matrix() {
+    let f = Fixture::new();
+    setup_multisig(&f);
+    let tiers = vec![
+        &f.env,
+        tier(500, 2, vec![&f.env]),
+        tier(5_000, 2, vec![&f.env, Role::Admin]),
+    ];
+
+    assert_eq!(
+        f.client
+            .try_set_approval_matrix(&f.address(), &f.employer, &tiers),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    for invalid in [
+        vec![&f.env, tier(0, 1, vec![&f.env])],
+        vec![
+            &f.env,
+            tier(500, 1, vec![&f.env]),
+            tier(500, 2, vec![&f.env]),
+        ],
+        vec![&f.env, tier(500, 0, vec![&f.env, Role::Admin])],
+    ] {
+        assert_eq!(
+            f.client
+                .try_set_approval_matrix(&f.owner, &f.employer, &invalid),
+            Err(Ok(PayrollError::InvalidData))
+        );
+    }
+    f.client.set_approval_matrix(&f.owner, &f.employer, &tiers);
+    assert_eq!(f.client.get_approval_matrix(&f.employer), tiers);
+
+    assert_eq!(
+        f.client.get_required_approvals(&f.employer, &499),
+        tier(0, 0, vec![&f.env])
+    );
+    assert_eq!(
+        f.client.get_required_approvals(&f.employer, &4_999),
+        tiers.get(0).unwrap()
+    );
+    assert_eq!(
+        f.client.get_required_approvals(&f.employer, &1_000_000),
+        tiers.get(1).unwrap()
+    );
+
+    // Other employers keep the global threshold, approved by the multisig's own
+    let other = f.address();
+    assert_eq!(
+        f.client.get_required_approvals(&other, &1_000_000),
+        tier(0, 0, vec![&f.env])
+    );
+    let ms = f.client.get_multisig_contract().unwrap();
+    f.client.set_multisig_config(&f.owner, &ms, &700, &0);
+    assert_eq!(
+        f.client.get_required_approvals(&other, &700),
+        tier(700, 2, vec![&f.env])
+    );
+
+    f.client
+        .set_approval_matrix(&f.owner, &f.employer, &vec![&f.env]);
+    assert!(f.client.get_approval_matrix(&f.employer).is_empty());
+    assert_eq!(
+        f.client.get_required_approvals(&f.employer, &699),
+        tier(0, 0, vec![&f.env])
+    );
+}
+
+#[test]
+fn claims_need_the_approvals_of_their_tier() {
+    let f = Fixture::new();
+    let (ms, signers) = setup_multisig(&f);
+    let employee = f.address();
+    let executive = f.address();
+    let payroll = f
+        .payroll()
+        .employee(&employee, 1_000)
+        .employee(&executive, 5_000)
+        .build();
+    f.client.set_approval_matrix(
+        &f.owner,
+        &f.employer,
+        &vec![
+            &f.env,
+            tier(500, 2, vec![&f.env]),
+            tier(5_000, 2, vec![&f.env, Role::Admin]),
+        ],
+    );
+    f.warp(DAY);
+
+    // Tiered payouts cannot be claimed directly
+    assert_eq!(
+        f.client.try_claim_payroll(&employee, &payroll, &0),
+        Err(Ok(PayrollError::MultisigApprovalRequired))
+    );
+    let op = approve_payment(&f, &ms, &signers, &employee, 1_000);
+    f.client
+        .claim_payroll_multisig(&employee, &payroll, &0, &op);
+    assert_eq!(f.token.balance(&employee), 2_000);
+
+    // The top tier also needs an admin among the approvers
+    let op = approve_payment(&f, &ms, &signers, &executive, 5_000);
+    assert_eq!(
+        f.client
+            .try_claim_payroll_multisig(&executive, &payroll, &1, &op),
+        Err(Ok(PayrollError::MultisigApprovalRequired))
+    );
+    let rbac = RbacContractClient::new(&f.env, &f.env.register(RbacContract, ()));
+    let rbac_owner = f.address();
+    rbac.initialize(&rbac_owner);
+    f.client.set_rbac_contract(&f.owner, &rbac.address);
+    rbac.grant_role(&rbac_owner, &signers.get(1).unwrap(), &Role::Admin);
+    f.client
+        .claim_payroll_multisig(&executive, &payroll, &1, &op);
+    assert_eq!(f.token.balance(&executive), 10_000);
+
+    // Raising a tier's count rejects operations with fewer approvers
+    f.client.set_approval_matrix(
+        &f.owner,
+        &f.employer,
+        &vec![&f.env, tier(500, 3, vec![&f.env])],
+    );
+    f.warp(DAY);
+    let op = approve_payment(&f, &ms, &signers, &employee, 1_000);
+    assert_eq!(
+        f.client
+            .try_claim_payroll_multisig(&employee, &payroll, &0, &op),
+        Err(Ok(PayrollError::MultisigApprovalRequired))
+    );
+}
+
+#[test]
+fn in_token_and_batch_claims_cannot_bypass_the_matrix() {
+    let f = Fixture::new();
+    setup_multisig(&f);
+    let employee = f.address();
+    let payroll = f.payroll().employee(&employee, 1_000).build();
+    let payout = TestToken::new(&f.env);
+    payout.mint(&f.client.address, 10_000);
+    f.env.as_contract(&f.client.address, || {
+        DataKey::set_agreement_escrow_balance(&f.env, payroll, &payout.address, 10_000);
+    });
+    f.client
+        .set_exchange_rate(&f.owner, &f.token.address, &payout.address, &2_000_000);
+    f.client.set_approval_matrix(
+        &f.owner,
+        &f.employer,
+        &vec![&f.env, tier(500, 2, vec![&f.env])],
+    );
+    f.warp(DAY);
+
+    // The tier is read from the base amount, whatever the payout token
+    assert_eq!(
+        f.client
+            .try_claim_payroll_in_token(&employee, &payroll, &0, &payout.address),
+        Err(Ok(PayrollError::MultisigApprovalRequired))
+    );
+    let batch = f
+        .client
+        .batch_claim_payroll(&employee, &payroll, &vec![&f.env, 0]);
+    assert_eq!(batch.failed_claims, 1);
+    assert_eq!(batch.total_claimed, 0);
+    assert_eq!(
+        batch.results.get(0).unwrap().error_code,
+        PayrollError::MultisigApprovalRequired as u32
+    );
+    assert_eq!(f.token.balance(&employee), 0);
+    assert_eq!(payout.balance(&employee), 0);
+}
@@ -0,0 +1,133 @@
+#![cfg(test)]
+
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::{vec, Address, BytesN, Env};
+use stello_pay_contract::audit::{audit_leaf_hash, chain_audit_hash, AUDIT_ANCHOR_PERIOD_SECONDS};
+use stello_pay_contract::storage::PayrollError;
+use stello_pay_contract::{PayrollContract, PayrollContractClient};
+
+fn setup() -> (Env, PayrollContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let payroll_id = env.register(PayrollContract, ());
+    let payroll_client = PayrollContractClient::new(&env, &payroll_id);
+    let owner = Address::generate(&env);
+    payroll_client.initialize(&owner);
+
+    (env, payroll_client, owner)
+}
+
+fn finish_period(env: &Env, period: u64) {
+    env.ledger()
+        .with_mut(|l| l.timestamp = (period + 1) * AUDIT_ANCHOR_PERIOD_SECONDS);
+}
+
+#[test]
+fn anchors_employer_entries_and_verifies_inclusion() {
+    let (env, payroll_client, _owner) = setup();
+    let employer = Address::generate(&env);
+    let other = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    // Employer: created, created, cancelled (3 entries). Other employer: 1 entry.
+    let a1 = payroll_client.create_payroll_agreement(&employer, &token, &3600);
+    payroll_client.create_payroll_agreement(&other, &token, &3600);
+    payroll_client.create_payroll_agreement(&employer, &token, &3600);
+    payroll_client.cancel_agreement(&a1);
+
+    assert_eq!(
+        payroll_client.get_audit_period_entry_ids(&employer, &0, &0, &10),
+        vec![&env, 1u64, 3, 4]
+    );
+    // Pages walk back from the newest entry
+    assert_eq!(
+        payroll_client.get_audit_period_entry_ids(&employer, &0, &0, &2),
+        vec![&env, 3u64, 4]
+    );
+    assert_eq!(
+        payroll_client.get_audit_period_entry_ids(&employer, &0, &3, &2),
+        vec![&env, 1u64]
+    );
+
+    finish_period(&env, 0);
+    let root = payroll_client.anchor_audit_period(&employer, &0);
+
+    let anchor = payroll_client.get_audit_anchor(&employer, &0).unwrap();
+    assert_eq!(anchor.root, root);
+    assert_eq!(anchor.entry_count, 3);
+
+    let e1 = payroll_client.get_audit_entry(&1).unwrap();
+    let e3 = payroll_client.get_audit_entry(&3).unwrap();
+    let e4 = payroll_client.get_audit_entry(&4).unwrap();
+    let l1 = audit_leaf_hash(&env, &e1);
+    let l3 = audit_leaf_hash(&env, &e3);
+    let l4 = audit_leaf_hash(&env, &e4);
+    let genesis = BytesN::from_array(&env, &[0u8; 32]);
+    let h1 = chain_audit_hash(&env, &genesis, &l1);
+    let h3 = chain_audit_hash(&env, &h1, &l3);
+    assert_eq!(chain_audit_hash(&env, &h3, &l4), root);
+
+    // A proof is the head before the entry, then the later leaves
+    assert!(
+        payroll_client.verify_audit_inclusion(&e1, &vec![&env, genesis, l3.clone(), l4.clone()])
+    );
+    assert!(payroll_client.verify_audit_inclusion(&e3, &vec![&env, h1.clone(), l4]));
+    assert!(payroll_client.verify_audit_inclusion(&e4, &vec![&env, h3]));
+    assert!(!payroll_client.verify_audit_inclusion(&e4, &vec![&env, h1]));
+}
+
+#[test]
+fn rejects_tampered_entry_and_unanchored_period() {
+    let (env, payroll_client, _owner) = setup();
+    let employer = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    payroll_client.create_payroll_agreement(&employer, &token, &3600);
+    payroll_client.create_payroll_agreement(&employer, &token, &3600);
+    let e1 = payroll_client.get_audit_entry(&1).unwrap();
+    let e2 = payroll_client.get_audit_entry(&2).unwrap();
+    let proof = vec![
+        &env,
+        BytesN::from_array(&env, &[0u8; 32]),
+        audit_leaf_hash(&env, &e2),
+    ];
+
+    // Not anchored yet.
+    assert!(!payroll_client.verify_audit_inclusion(&e1, &proof));
+
+    finish_period(&env, 0);
+    payroll_client.anchor_audit_period(&employer, &0);
+    assert!(payroll_client.verify_audit_inclusion(&e1, &proof));
+    assert!(!payroll_client.verify_audit_inclusion(&e1, &vec![&env]));
+
+    let mut tampered = e1.clone();
+    tampered.amount = Some(1);
+    assert!(!payroll_client.verify_audit_inclusion(&tampered, &proof));
+}
+
+#[test]
+fn anchoring_is_permissionless_once_per_finished_period() {
+    let (env, payroll_client, _owner) = setup();
+    let employer = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    payroll_client.create_payroll_agreement(&employer, &token, &3600);
+    assert_eq!(
+        payroll_client.try_anchor_audit_period(&employer, &0),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    finish_period(&env, 0);
+    env.set_auths(&[]);
+    payroll_client.anchor_audit_period(&employer, &0);
+    assert_eq!(
+        payroll_client.try_anchor_audit_period(&employer, &0),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    // Periods without entries have nothing to anchor
+    assert_eq!(
+        payroll_client.try_anchor_audit_period(&employer, &1),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
@@ -11,6 +11,7 @@
 
 use soroban_sdk::{testutils::Address as _, Address, Env};
 use stello_pay_contract::storage::{AgreementMode, AgreementStatus, DisputeStatus, PayrollError};
+use stello_pay_contract::validation::MAX_AMOUNT;
 use stello_pay_contract::{PayrollContract, PayrollContractClient};
 
 // ============================================================================
@@ -306,11 +307,11 @@ fn test_escrow_max_u32_num_periods() {
 }
 
 /// Verifies that creating an escrow agreement with u64::MAX period_seconds
-/// succeeds.
+/// is rejected.
 ///
-/// While impractical (billions of years), the contract should not reject
-/// a technically valid u64 value. The grace_period_seconds computation
-/// `period_seconds * num_periods` may overflow u64 though.
+/// Period counts are tracked as `u32`, so period lengths are capped at
+/// `MAX_PERIOD_SECONDS` (`u32::MAX` seconds) to keep
+/// `period_seconds * num_periods` inside `u64` for every `num_periods`.
 #[test]
 fn test_escrow_max_u64_period_seconds() {
     let env = create_test_env();
@@ -319,13 +320,15 @@ fn test_escrow_max_u64_period_seconds() {
     let contributor = create_test_address(&env);
     let token = create_test_address(&env);
 
-    // With num_periods = 1: grace_period = u64::MAX * 1 = u64::MAX (no overflow)
-    let agreement_id =
-        client.create_escrow_agreement(&employer, &contributor, &token, &1i128, &u64::MAX, &1u32);
-
-    let agreement = client.get_agreement(&agreement_id).unwrap();
-    assert_eq!(agreement.period_seconds, Some(u64::MAX));
-    assert_eq!(agreement.total_amount, 1);
+    let result = client.try_create_escrow_agreement(
+        &employer,
+        &contributor,
+        &token,
+        &1i128,
+        &u64::MAX,
+        &1u32,
+    );
+    assert_eq!(result, Err(Ok(PayrollError::InvalidData)));
 }
 
 // ---------- Payroll Employee Salary Amounts ----------
@@ -403,14 +406,12 @@ fn test_add_employee_minimum_valid_salary_succeeds() {
     assert_eq!(employees.len(), 1);
 }
 
-/// Verifies that adding an employee with `i128::MAX` salary succeeds at
-/// the add step, but tests that total_amount accumulation doesn't overflow.
+/// Verifies that adding an employee with `i128::MAX` salary panics.
 ///
-/// Adding a first employee with i128::MAX succeeds (total_amount = i128::MAX).
-/// Adding a second employee with salary 1 causes `total_amount` to overflow,
-/// which panics in debug mode.
+/// Salaries are capped at `MAX_AMOUNT` (`i128::MAX / 2`), so the
+/// `total_amount` accumulation can never overflow from a single add.
 #[test]
-#[should_panic]
+#[should_panic(expected = "Error(Contract, #13)")]
 fn test_add_employee_i128_max_salary_overflow_risk() {
     let env = create_test_env();
     let (_contract_id, client) = setup_contract(&env);
@@ -419,13 +420,11 @@ fn test_add_employee_i128_max_salary_overflow_risk() {
 
     let agreement_id = client.create_payroll_agreement(&employer, &token, &604800u64);
 
-    // First add succeeds: total_amount = 0 + i128::MAX = i128::MAX
     let emp1 = create_test_address(&env);
-    client.add_employee_to_agreement(&agreement_id, &emp1, &i128::MAX);
+    client.add_employee_to_agreement(&agreement_id, &emp1, &MAX_AMOUNT);
 
-    // Second add overflows: total_amount = i128::MAX + 1 → panic
     let emp2 = create_test_address(&env);
-    client.add_employee_to_agreement(&agreement_id, &emp2, &1i128);
+    client.add_employee_to_agreement(&agreement_id, &emp2, &i128::MAX);
 }
 
 // ---------- Milestone Amounts ----------
@@ -485,13 +484,12 @@ fn test_add_milestone_minimum_valid_amount_succeeds() {
     assert!(!milestone.claimed);
 }
 
-/// Verifies behavior when adding a milestone with `i128::MAX` amount.
+/// Verifies that adding a milestone above `MAX_AMOUNT` is rejected.
 ///
-/// A single milestone at i128::MAX should succeed, but adding a second
-/// milestone with amount 1 causes `total_amount` overflow in the
-/// milestone counter's accumulation (`total + amount`).
+/// A milestone at `MAX_AMOUNT` (`i128::MAX / 2`) is accepted, while
+/// `i128::MAX` returns `Err(PayrollError::InvalidData)` before it can
+/// overflow the agreement's `total_amount`.
 #[test]
-#[should_panic]
 fn test_add_milestone_i128_max_amount_overflow_risk() {
     let env = create_test_env();
     let (_contract_id, client) = setup_contract(&env);
@@ -501,11 +499,11 @@ fn test_add_milestone_i128_max_amount_overflow_risk() {
 
     let agreement_id = client.create_milestone_agreement(&employer, &contributor, &token);
 
-    // First milestone: total = 0 + i128::MAX = i128::MAX (OK)
-    client.add_milestone(&agreement_id, &i128::MAX);
-
-    // Second milestone: total = i128::MAX + 1 → overflow panic
-    client.add_milestone(&agreement_id, &1i128);
+    client.add_milestone(&agreement_id, &MAX_AMOUNT);
+    assert_eq!(
+        client.try_add_milestone(&agreement_id, &i128::MAX),
+        Err(Ok(PayrollError::InvalidData))
+    );
 }
 
 // ---------- Dispute Resolution Amounts ----------
@@ -0,0 +1,200 @@
+#![cfg(test)]
+
+use stello_pay_contract::{
+    cola::{ColaPolicy, ColaRunResult, SECONDS_PER_YEAR},
+    storage::{DataKey, PayrollError},
+    testutils::{Fixture, FIXTURE_START},
+};
+
+const MONTH: u64 = 30 * 86_400;
+
+/// Builds an active monthly payroll of the fixture's employer with one
+/// employee per salary in `salaries`.
+fn create_payroll(f: &Fixture, salaries: &[i128]) -> u128 {
+    let mut payroll = f.payroll().period_seconds(MONTH).funded_periods(0);
+    for salary in salaries {
+        payroll = payroll.employee(&f.address(), *salary);
+    }
+    payroll.build()
+}
+
+#[test]
+fn set_cola_policy_schedules_first_run() {
+    let f = Fixture::new();
+    f.client
+        .set_cola_policy(&f.employer, &300, &SECONDS_PER_YEAR);
+
+    assert_eq!(
+        f.client.get_cola_policy(&f.employer),
+        Some(ColaPolicy {
+            bps_per_year: 300,
+            apply_frequency: SECONDS_PER_YEAR,
+            last_applied_at: None,
+            next_due_at: FIXTURE_START + SECONDS_PER_YEAR,
+        })
+    );
+}
+
+#[test]
+fn set_cola_policy_rejects_out_of_range_values() {
+    let f = Fixture::new();
+    for (bps, frequency) in [
+        (0, MONTH),
+        (5_001, MONTH),
+        (300, 3_600),
+        (300, SECONDS_PER_YEAR + 1),
+    ] {
+        assert_eq!(
+            f.client.try_set_cola_policy(&f.employer, &bps, &frequency),
+            Err(Ok(PayrollError::InvalidData))
+        );
+    }
+}
+
+#[test]
+fn apply_before_due_or_without_policy_fails() {
+    let f = Fixture::new();
+    assert_eq!(
+        f.client.try_apply_cola_policy(&f.employer, &10),
+        Err(Ok(PayrollError::ColaPolicyNotFound))
+    );
+
+    f.client
+        .set_cola_policy(&f.employer, &300, &SECONDS_PER_YEAR);
+    f.warp(SECONDS_PER_YEAR - 1);
+    assert_eq!(
+        f.client.try_apply_cola_policy(&f.employer, &10),
+        Err(Ok(PayrollError::ColaNotDue))
+    );
+    assert_eq!(
+        f.client.try_apply_cola_policy(&f.employer, &21),
+        Err(Ok(PayrollError::BatchTooLarge))
+    );
+}
+
+#[test]
+fn yearly_run_raises_every_salary_and_records_adjustments() {
+    let f = Fixture::new();
+    let first = create_payroll(&f, &[10_000, 20_000]);
+    let second = create_payroll(&f, &[5_000]);
+    f.client
+        .set_cola_policy(&f.employer, &300, &SECONDS_PER_YEAR);
+
+    f.warp(SECONDS_PER_YEAR);
+    assert_eq!(
+        f.client.apply_cola_policy(&f.employer, &10),
+        ColaRunResult {
+            adjusted: 3,
+            completed: true,
+        }
+    );
+
+    assert_eq!(f.client.get_payroll_adjustment_count(), 3);
+    let adjustment = f.client.get_payroll_adjustment(&2).unwrap();
+    assert_eq!(adjustment.agreement_id, first);
+    assert_eq!(adjustment.old_salary, 20_000);
+    assert_eq!(adjustment.new_salary, 20_600);
+    assert_eq!(adjustment.raise_bps, 300);
+    let last = f.client.get_payroll_adjustment(&3).unwrap();
+    assert_eq!(last.agreement_id, second);
+    assert_eq!(last.new_salary, 5_150);
+    assert_eq!(f.client.get_agreement(&first).unwrap().total_amount, 30_900);
+
+    let policy = f.client.get_cola_policy(&f.employer).unwrap();
+    assert_eq!(
+        policy.last_applied_at,
+        Some(FIXTURE_START + SECONDS_PER_YEAR)
+    );
+    assert_eq!(policy.next_due_at, FIXTURE_START + 2 * SECONDS_PER_YEAR);
+    assert_eq!(
+        f.client.try_apply_cola_policy(&f.employer, &10),
+        Err(Ok(PayrollError::ColaNotDue))
+    );
+}
+
+#[test]
+fn run_is_processed_in_bounded_batches() {
+    let f = Fixture::new();
+    create_payroll(&f, &[10_000, 10_000, 10_000]);
+    create_payroll(&f, &[10_000, 10_000]);
+    f.client.set_cola_policy(&f.employer, &1_200, &MONTH);
+
+    f.warp(MONTH);
+
+    let first = f.client.apply_cola_policy(&f.employer, &2);
+    assert_eq!(first.adjusted, 2);
+    assert!(!first.completed);
+    let second = f.client.apply_cola_policy(&f.employer, &2);
+    assert_eq!(second.adjusted, 2);
+    assert!(!second.completed);
+    let last = f.client.apply_cola_policy(&f.employer, &2);
+    assert_eq!(last.adjusted, 1);
+    assert!(last.completed);
+
+    // 1200 bps/year applied every 30 days is 98 bps per run
+    assert_eq!(f.client.get_payroll_adjustment_count(), 5);
+    for id in 1..=5 {
+        let adjustment = f.client.get_payroll_adjustment(&id).unwrap();
+        assert_eq!(adjustment.raise_bps, 98);
+        assert_eq!(adjustment.new_salary, 10_098);
+    }
+}
+
+#[test]
+fn cancelled_agreements_are_skipped() {
+    let f = Fixture::new();
+    let cancelled = create_payroll(&f, &[10_000]);
+    f.client.cancel_agreement(&cancelled);
+    create_payroll(&f, &[10_000]);
+    f.client
+        .set_cola_policy(&f.employer, &300, &SECONDS_PER_YEAR);
+
+    f.warp(SECONDS_PER_YEAR);
+    let result = f.client.apply_cola_policy(&f.employer, &10);
+    assert_eq!(result.adjusted, 1);
+    assert_ne!(
+        f.client.get_payroll_adjustment(&1).unwrap().agreement_id,
+        cancelled
+    );
+}
+
+#[test]
+fn indexed_salary_used_by_claims_is_raised() {
+    let f = Fixture::new();
+    let agreement_id = create_payroll(&f, &[10_000]);
+    f.client
+        .set_cola_policy(&f.employer, &300, &SECONDS_PER_YEAR);
+
+    f.warp(SECONDS_PER_YEAR);
+    f.client.apply_cola_policy(&f.employer, &10);
+
+    let salary = f.env.as_contract(&f.client.address, || {
+        DataKey::get_employee_salary(&f.env, agreement_id, 0)
+    });
+    assert_eq!(salary, Some(10_300));
+}
+
+#[test]
+fn periods_earned_before_a_raise_are_paid_at_the_old_salary() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f
+        .payroll()
+        .employee(&employee, 10_000)
+        .period_seconds(MONTH)
+        .funded_periods(100)
+        .build();
+    f.client
+        .set_cola_policy(&f.employer, &300, &SECONDS_PER_YEAR);
+
+    // Two periods are claimed and ten more elapse before the raise.
+    f.warp(2 * MONTH);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    f.warp(SECONDS_PER_YEAR - 2 * MONTH);
+    f.client.apply_cola_policy(&f.employer, &10);
+    f.warp(2 * MONTH);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+
+    let elapsed = (SECONDS_PER_YEAR / MONTH) as i128;
+    assert_eq!(f.token.balance(&employee), elapsed * 10_000 + 2 * 10_300);
+}
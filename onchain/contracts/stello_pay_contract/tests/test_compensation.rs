@@ -0,0 +1,91 @@
+#![cfg(test)]
+
+use soroban_sdk::vec;
+use stello_pay_contract::{
+    compensation::CompensationDisbursement,
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+const SALARY: i128 = 1_000;
+
+#[test]
+fn only_owner_links_bonus_contract() {
+    let f = Fixture::without_bonus_link();
+    assert_eq!(f.client.get_bonus_contract(), None);
+    assert_eq!(
+        f.client
+            .try_set_bonus_contract(&f.employer, &f.bonus.address),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    f.client.set_bonus_contract(&f.owner, &f.bonus.address);
+    assert_eq!(f.client.get_bonus_contract(), Some(f.bonus.address.clone()));
+}
+
+#[test]
+fn pays_due_salary_and_vested_bonuses_in_one_call() {
+    let f = Fixture::new();
+    let employee = f.address();
+    f.payroll().employee(&employee, SALARY).build();
+    f.payroll().employee(&employee, SALARY).build();
+
+    let now = f.env.ledger().timestamp();
+    f.incentive(&employee).amount(500).build();
+    let unvested = f
+        .incentive(&employee)
+        .amount(700)
+        .vesting_at(now + 10 * DAY)
+        .build();
+
+    f.warp(2 * DAY);
+    let result = f.client.disburse_total_compensation(&f.employer, &employee);
+    assert_eq!(
+        result,
+        CompensationDisbursement {
+            salary_amount: 4 * SALARY,
+            bonus_amount: 500,
+            agreements_paid: 2,
+            incentive_sequence: Some(1),
+        }
+    );
+    assert_eq!(f.token.balance(&employee), 4 * SALARY + 500);
+    assert_eq!(
+        f.bonus.get_open_incentives(&employee),
+        vec![&f.env, unvested]
+    );
+
+    // Nothing is due right after a run: the next one pays nothing but succeeds
+    assert_eq!(
+        f.client.disburse_total_compensation(&f.employer, &employee),
+        CompensationDisbursement::default()
+    );
+}
+
+#[test]
+fn salary_only_without_linked_bonus_contract() {
+    let f = Fixture::without_bonus_link();
+    let employee = f.address();
+    f.payroll().employee(&employee, SALARY).build();
+
+    f.warp(DAY);
+    let result = f.client.disburse_total_compensation(&f.employer, &employee);
+    assert_eq!(result.salary_amount, SALARY);
+    assert_eq!(result.bonus_amount, 0);
+}
+
+#[test]
+fn underfunded_agreement_fails_the_run() {
+    let f = Fixture::new();
+    let employee = f.address();
+    f.payroll()
+        .employee(&employee, SALARY)
+        .funded_periods(1)
+        .build();
+
+    f.warp(2 * DAY);
+    assert_eq!(
+        f.client
+            .try_disburse_total_compensation(&f.employer, &employee),
+        Err(Ok(PayrollError::InsufficientEscrowBalance))
+    );
+}
@@ -0,0 +1,116 @@
+#![cfg(test)]
+
+use soroban_sdk::{Address, BytesN, Env};
+use stello_pay_contract::{
+    confidential::compute_salary_commitment,
+    storage::{DataKey, PayrollError},
+    testutils::{Fixture, DAY},
+};
+
+const SALARY: i128 = 1_000;
+
+/// An active payroll agreement paying `SALARY` per period to one employee.
+fn setup() -> (Fixture<'static>, Address, u128) {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    (f, employee, agreement_id)
+}
+
+fn commit(env: &Env, agreement_id: u128, amount: i128, salt: &BytesN<32>) -> BytesN<32> {
+    compute_salary_commitment(env, agreement_id, 0, amount, salt)
+}
+
+#[test]
+fn commitment_scrubs_plaintext_salary_from_storage() {
+    let (f, _, agreement_id) = setup();
+    let salt = BytesN::from_array(&f.env, &[7u8; 32]);
+
+    f.client.set_salary_commitment(
+        &agreement_id,
+        &0,
+        &commit(&f.env, agreement_id, SALARY, &salt),
+    );
+
+    f.env.as_contract(&f.client.address, || {
+        assert_eq!(DataKey::get_employee_salary(&f.env, agreement_id, 0), None);
+    });
+    assert_eq!(
+        f.client.get_salary_commitment(&agreement_id, &0),
+        Some(commit(&f.env, agreement_id, SALARY, &salt))
+    );
+}
+
+#[test]
+fn employer_reveal_disburses_elapsed_periods() {
+    let (f, employee, agreement_id) = setup();
+    let salt = BytesN::from_array(&f.env, &[9u8; 32]);
+    f.client.set_salary_commitment(
+        &agreement_id,
+        &0,
+        &commit(&f.env, agreement_id, SALARY, &salt),
+    );
+
+    f.warp(3 * DAY);
+    let paid = f
+        .client
+        .disburse_confidential_payroll(&agreement_id, &0, &SALARY, &salt);
+
+    assert_eq!(paid, SALARY * 3);
+    assert_eq!(f.token.balance(&employee), SALARY * 3);
+    assert_eq!(f.client.get_employee_claimed_periods(&agreement_id, &0), 3);
+}
+
+#[test]
+fn wrong_amount_or_salt_is_rejected() {
+    let (f, _, agreement_id) = setup();
+    let salt = BytesN::from_array(&f.env, &[1u8; 32]);
+    let other_salt = BytesN::from_array(&f.env, &[2u8; 32]);
+    f.client.set_salary_commitment(
+        &agreement_id,
+        &0,
+        &commit(&f.env, agreement_id, SALARY, &salt),
+    );
+    f.warp(DAY);
+
+    assert_eq!(
+        f.client
+            .try_disburse_confidential_payroll(&agreement_id, &0, &(SALARY * 2), &salt),
+        Err(Ok(PayrollError::SalaryCommitmentMismatch))
+    );
+    assert_eq!(
+        f.client
+            .try_disburse_confidential_payroll(&agreement_id, &0, &SALARY, &other_salt),
+        Err(Ok(PayrollError::SalaryCommitmentMismatch))
+    );
+}
+
+#[test]
+fn confidential_employee_cannot_self_claim() {
+    let (f, employee, agreement_id) = setup();
+    let salt = BytesN::from_array(&f.env, &[3u8; 32]);
+    f.client.set_salary_commitment(
+        &agreement_id,
+        &0,
+        &commit(&f.env, agreement_id, SALARY, &salt),
+    );
+    f.warp(DAY);
+
+    assert_eq!(
+        f.client.try_claim_payroll(&employee, &agreement_id, &0),
+        Err(Ok(PayrollError::InvalidAgreementMode))
+    );
+}
+
+#[test]
+fn disbursing_without_commitment_fails() {
+    let (f, _, agreement_id) = setup();
+    let salt = BytesN::from_array(&f.env, &[4u8; 32]);
+    f.warp(DAY);
+
+    assert_eq!(
+        f.client
+            .try_disburse_confidential_payroll(&agreement_id, &0, &SALARY, &salt),
+        Err(Ok(PayrollError::SalaryCommitmentMismatch))
+    );
+}
@@ -0,0 +1,135 @@
+#![cfg(test)]
+
+use soroban_sdk::{contract, contractimpl, contracttype, testutils::Address as _, Address, Env};
+use stello_pay_contract::{
+    credentials::CredentialRole, storage::PayrollError, PayrollContract, PayrollContractClient,
+};
+
+#[contracttype]
+#[derive(Clone)]
+enum MockRegistryKey {
+    Valid(Address, CredentialRole),
+}
+
+/// Stand-in for the governance contract's credential queries.
+#[contract]
+pub struct MockCredentialRegistry;
+
+#[contractimpl]
+impl MockCredentialRegistry {
+    pub fn set_valid(env: Env, holder: Address, role: CredentialRole, valid: bool) {
+        env.storage()
+            .persistent()
+            .set(&MockRegistryKey::Valid(holder, role), &valid);
+    }
+
+    pub fn has_valid_credential(env: Env, holder: Address, role: CredentialRole) -> bool {
+        env.storage()
+            .persistent()
+            .get(&MockRegistryKey::Valid(holder, role))
+            .unwrap_or(false)
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: PayrollContractClient<'static>,
+    registry: MockCredentialRegistryClient<'static>,
+    owner: Address,
+    employer: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PayrollContract, ());
+    let client = PayrollContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    client.initialize(&owner);
+
+    let registry_id = env.register(MockCredentialRegistry, ());
+    let registry = MockCredentialRegistryClient::new(&env, &registry_id);
+    let employer = Address::generate(&env);
+
+    Setup {
+        env,
+        client,
+        registry,
+        owner,
+        employer,
+    }
+}
+
+#[test]
+fn only_owner_links_credential_registry() {
+    let s = setup();
+    assert_eq!(
+        s.client
+            .try_set_credential_registry(&s.employer, &s.registry.address),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    s.client
+        .set_credential_registry(&s.owner, &s.registry.address);
+    assert_eq!(
+        s.client.get_credential_registry(),
+        Some(s.registry.address.clone())
+    );
+}
+
+#[test]
+fn arbiter_needs_mediator_credential_once_registry_linked() {
+    let s = setup();
+    // Unchecked until a registry is linked
+    let first = Address::generate(&s.env);
+    s.client.set_arbiter(&s.owner, &first);
+
+    s.client
+        .set_credential_registry(&s.owner, &s.registry.address);
+    let mediator = Address::generate(&s.env);
+    s.registry
+        .set_valid(&mediator, &CredentialRole::Auditor, &true);
+    assert!(s.client.try_set_arbiter(&s.owner, &mediator).is_err());
+
+    s.registry
+        .set_valid(&mediator, &CredentialRole::Mediator, &true);
+    s.client.set_arbiter(&s.owner, &mediator);
+    assert_eq!(s.client.get_arbiter(), Some(mediator));
+}
+
+#[test]
+fn audit_trail_access_follows_auditor_credential() {
+    let s = setup();
+    let auditor = Address::generate(&s.env);
+    // No registry: access cannot be granted
+    assert!(s
+        .client
+        .try_grant_audit_trail_access(&s.employer, &auditor)
+        .is_err());
+
+    s.client
+        .set_credential_registry(&s.owner, &s.registry.address);
+    assert!(s
+        .client
+        .try_grant_audit_trail_access(&s.employer, &auditor)
+        .is_err());
+
+    s.registry
+        .set_valid(&auditor, &CredentialRole::Auditor, &true);
+    s.client.grant_audit_trail_access(&s.employer, &auditor);
+    assert!(s.client.has_audit_trail_access(&s.employer, &auditor));
+    assert!(!s
+        .client
+        .has_audit_trail_access(&Address::generate(&s.env), &auditor));
+
+    // Revoking or expiring the credential removes access without a new grant
+    s.registry
+        .set_valid(&auditor, &CredentialRole::Auditor, &false);
+    assert!(!s.client.has_audit_trail_access(&s.employer, &auditor));
+    s.registry
+        .set_valid(&auditor, &CredentialRole::Auditor, &true);
+    assert!(s.client.has_audit_trail_access(&s.employer, &auditor));
+
+    s.client.revoke_audit_trail_access(&s.employer, &auditor);
+    assert!(!s.client.has_audit_trail_access(&s.employer, &auditor));
+}
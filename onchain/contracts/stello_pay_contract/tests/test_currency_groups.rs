@@ -0,0 +1,356 @@
+#![cfg(test)]
+
+use soroban_sdk::{vec, Address, BytesN};
+use stello_pay_contract::{
+    currency_groups::{FxExposure, TokenMigrationTerms},
+    storage::{DataKey, PayrollError},
+    testutils::{Fixture, TestToken, DAY},
+};
+
+/// Builds an active payroll agreement of the fixture's employer paying
+/// `salary` per period in `token` to `employee`, with `escrow` deposited.
+fn payroll(f: &Fixture, employee: &Address, token: &TestToken, salary: i128, escrow: i128) -> u128 {
+    f.payroll()
+        .token(&token.address)
+        .employee(employee, salary)
+        .funded_periods((escrow / salary) as u32)
+        .build()
+}
+
+#[test]
+fn fx_exposure_sums_upcoming_obligations_per_token() {
+    let f = Fixture::new();
+    let usdc = TestToken::new(&f.env);
+    let eurc = TestToken::new(&f.env);
+
+    payroll(&f, &f.address(), &usdc, 100, 500);
+    payroll(&f, &f.address(), &usdc, 250, 0);
+    // Escrow agreements only owe their remaining periods
+    f.client
+        .create_escrow_agreement(&f.employer, &f.address(), &eurc.address, &30, &DAY, &4);
+
+    let report = f.client.get_fx_exposure(&f.employer);
+    assert_eq!(report.len(), 2);
+    assert_eq!(
+        report.get(0).unwrap(),
+        FxExposure {
+            token: usdc.address,
+            escrow_balance: 500,
+            next_1_period: 350,
+            next_3_periods: 1_050,
+            next_6_periods: 2_100,
+        }
+    );
+    assert_eq!(
+        report.get(1).unwrap(),
+        FxExposure {
+            token: eurc.address,
+            escrow_balance: 0,
+            next_1_period: 30,
+            next_3_periods: 90,
+            next_6_periods: 120,
+        }
+    );
+    assert!(f.client.get_fx_exposure(&f.address()).is_empty());
+}
+
+#[test]
+fn migrating_a_group_converts_pay_and_returns_old_escrow() {
+    let f = Fixture::new();
+    let old_token = TestToken::new(&f.env);
+    let token = TestToken::new(&f.env);
+    let employee = f.address();
+    let first = payroll(&f, &employee, &old_token, 100, 1_000);
+    let second = payroll(&f, &f.address(), &old_token, 40, 0);
+
+    let group_id = f
+        .client
+        .create_currency_group(&f.employer, &old_token.address);
+    f.client
+        .add_to_currency_group(&f.employer, &group_id, &first);
+    f.client
+        .add_to_currency_group(&f.employer, &group_id, &second);
+    assert_eq!(
+        f.client.get_agreement_currency_group(&first),
+        Some(group_id)
+    );
+
+    // 1 old token = 1.5 new tokens
+    assert_eq!(
+        f.client
+            .migrate_group_token(&f.employer, &group_id, &token.address, &1_500_000),
+        2
+    );
+    assert_eq!(old_token.balance(&f.employer), 1_000);
+
+    let group = f.client.get_currency_group(&group_id).unwrap();
+    assert_eq!(group.token, token.address);
+    assert_eq!(group.agreements, vec![&f.env, first, second]);
+    let agreement = f.client.get_agreement(&first).unwrap();
+    assert_eq!(
+        (agreement.token, agreement.total_amount),
+        (token.address.clone(), 150)
+    );
+    assert_eq!(
+        f.client
+            .get_fx_exposure(&f.employer)
+            .get(0)
+            .unwrap()
+            .next_1_period,
+        210
+    );
+
+    // Once funded in the new token, claims pay the converted salary
+    f.env.as_contract(&f.client.address, || {
+        DataKey::set_agreement_escrow_balance(&f.env, first, &token.address, 1_500);
+    });
+    token.mint(&f.client.address, 1_500);
+    f.warp(2 * DAY);
+    f.client.claim_payroll(&employee, &first, &0);
+    assert_eq!(token.balance(&employee), 300);
+}
+
+#[test]
+fn group_membership_and_migration_rules() {
+    let f = Fixture::new();
+    let token = TestToken::new(&f.env);
+    let employee = f.address();
+    let agreement_id = payroll(&f, &employee, &token, 100, 0);
+    let other_token_id = payroll(&f, &f.address(), &TestToken::new(&f.env), 100, 0);
+    let escrow_id =
+        f.client
+            .create_escrow_agreement(&f.employer, &f.address(), &token.address, &30, &DAY, &4);
+    let group_id = f.client.create_currency_group(&f.employer, &token.address);
+
+    assert_eq!(
+        f.client
+            .try_add_to_currency_group(&f.address(), &group_id, &agreement_id),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        f.client
+            .try_add_to_currency_group(&f.employer, &group_id, &other_token_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client
+            .try_add_to_currency_group(&f.employer, &group_id, &escrow_id),
+        Err(Ok(PayrollError::InvalidAgreementMode))
+    );
+    f.client
+        .add_to_currency_group(&f.employer, &group_id, &agreement_id);
+    let other_group = f.client.create_currency_group(&f.employer, &token.address);
+    assert_eq!(
+        f.client
+            .try_add_to_currency_group(&f.employer, &other_group, &agreement_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    let target = TestToken::new(&f.env);
+    assert_eq!(
+        f.client
+            .try_migrate_group_token(&f.employer, &group_id, &target.address, &0),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client
+            .try_migrate_group_token(&f.employer, &group_id, &token.address, &1_000_000),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    // Committed salaries cannot be converted
+    f.client
+        .set_salary_commitment(&agreement_id, &0, &BytesN::from_array(&f.env, &[7; 32]));
+    assert_eq!(
+        f.client
+            .try_migrate_group_token(&f.employer, &group_id, &target.address, &1_000_000),
+        Err(Ok(PayrollError::InvalidAgreementMode))
+    );
+
+    f.client
+        .remove_from_currency_group(&f.employer, &group_id, &agreement_id);
+    assert_eq!(f.client.get_agreement_currency_group(&agreement_id), None);
+    f.client
+        .add_to_currency_group(&f.employer, &other_group, &agreement_id);
+    assert_eq!(
+        f.client
+            .try_remove_from_currency_group(&f.employer, &group_id, &agreement_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn migrating_employees_converts_escrow_in_one_call() {
+    let f = Fixture::new();
+    let old_token = TestToken::new(&f.env);
+    let token = TestToken::new(&f.env);
+    let employee = f.address();
+    let first = payroll(&f, &employee, &old_token, 100, 1_000);
+    let second = payroll(&f, &f.address(), &old_token, 100, 1_000);
+    let group_id = f
+        .client
+        .create_currency_group(&f.employer, &old_token.address);
+    f.client
+        .add_to_currency_group(&f.employer, &group_id, &first);
+    f.client
+        .add_to_currency_group(&f.employer, &group_id, &second);
+    token.mint(&f.employer, 1_000);
+
+    // 1 old token = 0.995 new tokens, within the default 1% threshold
+    assert_eq!(
+        f.client.migrate_payroll_token(
+            &f.employer,
+            &vec![&f.env, employee.clone()],
+            &old_token.address,
+            &token.address,
+            &9_950,
+        ),
+        1
+    );
+    assert_eq!(old_token.balance(&f.employer), 1_000);
+    assert_eq!(token.balance(&f.employer), 5);
+    assert_eq!(f.client.get_agreement(&first).unwrap().token, token.address);
+    assert_eq!(
+        f.client.get_agreement(&second).unwrap().token,
+        old_token.address
+    );
+    assert_eq!(f.client.get_agreement_currency_group(&first), None);
+    assert_eq!(
+        f.client.get_currency_group(&group_id).unwrap().agreements,
+        vec![&f.env, second]
+    );
+
+    let migration = f
+        .client
+        .get_token_migration(&f.client.get_token_migration_count())
+        .unwrap();
+    assert_eq!(migration.agreement_id, first);
+    assert_eq!((migration.old_escrow, migration.new_escrow), (1_000, 995));
+
+    // Claims keep working without a separate deposit
+    f.warp(2 * DAY);
+    f.client.claim_payroll(&employee, &first, &0);
+    assert_eq!(token.balance(&employee), 198);
+}
+
+#[test]
+fn large_rate_deviations_need_sign_off() {
+    let f = Fixture::new();
+    let old_token = TestToken::new(&f.env).address;
+    let token = TestToken::new(&f.env).address;
+    let employee = f.address();
+    let agreement_id = f
+        .payroll()
+        .token(&old_token)
+        .employee(&employee, 100)
+        .funded_periods(0)
+        .build();
+    let employees = vec![&f.env, employee.clone()];
+    let terms = TokenMigrationTerms {
+        old_token: old_token.clone(),
+        new_token: token.clone(),
+        conversion_rate_bps: 8_000,
+    };
+
+    assert_eq!(
+        f.client
+            .try_migrate_payroll_token(&f.employer, &employees, &old_token, &token, &8_000),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    // A sign-off on other terms does not count
+    f.client.approve_token_migration(
+        &employee,
+        &f.employer,
+        &TokenMigrationTerms {
+            conversion_rate_bps: 7_000,
+            ..terms.clone()
+        },
+    );
+    assert_eq!(
+        f.client
+            .try_migrate_payroll_token(&f.employer, &employees, &old_token, &token, &8_000),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+
+    f.client
+        .approve_token_migration(&employee, &f.employer, &terms);
+    f.client
+        .migrate_payroll_token(&f.employer, &employees, &old_token, &token, &8_000);
+    assert_eq!(
+        f.client.get_agreement(&agreement_id).unwrap().total_amount,
+        80
+    );
+    assert_eq!(
+        f.client
+            .get_token_migration_approval(&f.employer, &employee),
+        None
+    );
+
+    // Governance can sign off for every employee; raising the threshold
+    // lifts the requirement
+    f.client.approve_token_migration(
+        &f.owner,
+        &f.employer,
+        &TokenMigrationTerms {
+            old_token: token.clone(),
+            new_token: old_token.clone(),
+            conversion_rate_bps: 12_500,
+        },
+    );
+    f.client
+        .migrate_payroll_token(&f.employer, &employees, &token, &old_token, &12_500);
+    assert_eq!(
+        f.client.get_agreement(&agreement_id).unwrap().total_amount,
+        100
+    );
+    f.client.set_migration_threshold(&f.owner, &3_000);
+    f.client
+        .migrate_payroll_token(&f.employer, &employees, &old_token, &token, &8_000);
+    assert_eq!(f.client.get_token_migration_count(), 3);
+}
+
+#[test]
+fn payroll_migration_rules() {
+    let f = Fixture::new();
+    let old_token = TestToken::new(&f.env).address;
+    let token = TestToken::new(&f.env).address;
+    let employee = f.address();
+    f.payroll()
+        .token(&old_token)
+        .employee(&employee, 100)
+        .employee(&f.address(), 100)
+        .funded_periods(0)
+        .build();
+
+    // The token is set per agreement, so every employee on it must move
+    let employees = vec![&f.env, employee.clone()];
+    assert_eq!(
+        f.client
+            .try_migrate_payroll_token(&f.employer, &employees, &old_token, &token, &10_000),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client.try_migrate_payroll_token(
+            &f.employer,
+            &vec![&f.env, f.address()],
+            &old_token,
+            &token,
+            &10_000,
+        ),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client.try_migrate_payroll_token(
+            &f.employer,
+            &employees,
+            &old_token,
+            &old_token,
+            &10_000
+        ),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client.try_set_migration_threshold(&f.employer, &0),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+}
@@ -0,0 +1,187 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env,
+};
+use stello_pay_contract::{
+    disbursement_windows::{BlackoutWindow, BusinessHours},
+    storage::{DataKey, PayrollError},
+    PayrollContract, PayrollContractClient,
+};
+
+const HOUR: u64 = 3_600;
+const DAY: u64 = 24 * HOUR;
+/// Monday 2024-01-01 00:00 UTC.
+const MONDAY: u64 = 1_704_067_200;
+
+fn setup() -> (Env, PayrollContractClient<'static>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|l| l.timestamp = MONDAY);
+    let contract_id = env.register(PayrollContract, ());
+    let client = PayrollContractClient::new(&env, &contract_id);
+    let employer = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    (env, client, employer, token)
+}
+
+fn set_time(env: &Env, timestamp: u64) {
+    env.ledger().with_mut(|l| l.timestamp = timestamp);
+}
+
+fn office_hours() -> BusinessHours {
+    BusinessHours {
+        open: (9 * HOUR) as u32,
+        close: (17 * HOUR) as u32,
+        weekdays_only: true,
+    }
+}
+
+#[test]
+fn next_allowed_time_skips_closed_hours_weekends_and_blackouts() {
+    let (env, client, employer, _) = setup();
+    assert_eq!(client.get_next_disbursement_time(&employer), MONDAY);
+
+    client.set_business_hours(&employer, &Some(office_hours()));
+    assert_eq!(client.get_business_hours(&employer), Some(office_hours()));
+    set_time(&env, MONDAY + 10 * HOUR);
+    assert_eq!(
+        client.get_next_disbursement_time(&employer),
+        MONDAY + 10 * HOUR
+    );
+    set_time(&env, MONDAY + 18 * HOUR);
+    assert_eq!(
+        client.get_next_disbursement_time(&employer),
+        MONDAY + DAY + 9 * HOUR
+    );
+    // Friday evening waits for Monday morning
+    set_time(&env, MONDAY + 4 * DAY + 18 * HOUR);
+    assert_eq!(
+        client.get_next_disbursement_time(&employer),
+        MONDAY + 7 * DAY + 9 * HOUR
+    );
+
+    // Back to Monday evening: blackouts chain, then business hours apply again
+    set_time(&env, MONDAY + 18 * HOUR);
+    client.add_blackout(&employer, &(MONDAY + DAY), &(MONDAY + DAY + 10 * HOUR));
+    assert_eq!(
+        client.get_next_disbursement_time(&employer),
+        MONDAY + DAY + 10 * HOUR
+    );
+    client.add_blackout(
+        &employer,
+        &(MONDAY + DAY + 10 * HOUR),
+        &(MONDAY + 2 * DAY + 18 * HOUR),
+    );
+    assert_eq!(
+        client.get_next_disbursement_time(&employer),
+        MONDAY + 3 * DAY + 9 * HOUR
+    );
+
+    client.remove_blackout(&employer, &(MONDAY + DAY + 10 * HOUR));
+    client.set_business_hours(&employer, &None);
+    assert_eq!(
+        client.get_next_disbursement_time(&employer),
+        MONDAY + 18 * HOUR
+    );
+}
+
+#[test]
+fn claims_wait_for_the_window_to_open() {
+    let (env, client, employer, token) = setup();
+    let contributor = Address::generate(&env);
+    let agreement_id =
+        client.create_escrow_agreement(&employer, &contributor, &token, &500, &DAY, &4);
+    StellarAssetClient::new(&env, &token).mint(&client.address, &2_000);
+    env.as_contract(&client.address, || {
+        DataKey::set_agreement_escrow_balance(&env, agreement_id, &token, 2_000);
+    });
+    client.activate_agreement(&agreement_id);
+
+    let milestone_id = client.create_milestone_agreement(&employer, &contributor, &token);
+    StellarAssetClient::new(&env, &token).mint(&employer, &300);
+    client.fund_milestone_agreement(&milestone_id, &employer, &300);
+    client.add_milestone(&milestone_id, &300);
+    client.approve_milestone(&milestone_id, &1);
+
+    // Month-end close right as the first period completes
+    set_time(&env, MONDAY + DAY);
+    client.add_blackout(&employer, &(MONDAY + DAY), &(MONDAY + 2 * DAY));
+    assert_eq!(
+        client.try_claim_time_based(&agreement_id),
+        Err(Ok(PayrollError::TimelockActive))
+    );
+    assert_eq!(
+        client.try_claim_milestone(&milestone_id, &1),
+        Err(Ok(PayrollError::TimelockActive))
+    );
+
+    set_time(&env, client.get_next_disbursement_time(&employer));
+    client.claim_time_based(&agreement_id);
+    client.claim_milestone(&milestone_id, &1);
+    assert_eq!(
+        TokenClient::new(&env, &token).balance(&contributor),
+        2 * 500 + 300
+    );
+
+    // Another employer's window does not apply
+    let other = Address::generate(&env);
+    client.set_business_hours(&other, &Some(office_hours()));
+    set_time(&env, MONDAY + 3 * DAY + 20 * HOUR);
+    client.claim_time_based(&agreement_id);
+}
+
+#[test]
+fn window_configuration_is_validated() {
+    let (env, client, employer, _) = setup();
+    let mut hours = office_hours();
+    hours.close = hours.open;
+    assert_eq!(
+        client.try_set_business_hours(&employer, &Some(hours.clone())),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    hours.close = (DAY + 1) as u32;
+    assert_eq!(
+        client.try_set_business_hours(&employer, &Some(hours)),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    set_time(&env, MONDAY + DAY);
+    assert_eq!(
+        client.try_add_blackout(&employer, &(MONDAY + 2 * DAY), &(MONDAY + 2 * DAY)),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        client.try_add_blackout(&employer, &MONDAY, &(MONDAY + DAY)),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        client.try_remove_blackout(&employer, &MONDAY),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    // Ended blackouts drop out and free their slot
+    for i in 0..20 {
+        let start = MONDAY + 2 * DAY + i * HOUR;
+        client.add_blackout(&employer, &start, &(start + HOUR));
+    }
+    assert_eq!(
+        client.try_add_blackout(&employer, &(MONDAY + 5 * DAY), &(MONDAY + 6 * DAY)),
+        Err(Ok(PayrollError::BatchTooLarge))
+    );
+    set_time(&env, MONDAY + 2 * DAY + HOUR);
+    client.add_blackout(&employer, &(MONDAY + 5 * DAY), &(MONDAY + 6 * DAY));
+    let blackouts = client.get_blackouts(&employer);
+    assert_eq!(blackouts.len(), 20);
+    assert_eq!(
+        blackouts.get(19).unwrap(),
+        BlackoutWindow {
+            start: MONDAY + 5 * DAY,
+            end: MONDAY + 6 * DAY,
+        }
+    );
+}
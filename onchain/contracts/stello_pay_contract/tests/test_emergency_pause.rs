@@ -1,7 +1,10 @@
 #![cfg(test)]
 
-use soroban_sdk::{testutils::Address as _, token, Address, Env, Vec};
-use stello_pay_contract::{PayrollContract, PayrollContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, Env, String, Vec,
+};
+use stello_pay_contract::{storage::PayrollError, PayrollContract, PayrollContractClient};
 
 fn create_token_contract<'a>(env: &Env, admin: &Address) -> token::StellarAssetClient<'a> {
     let contract_address = env
@@ -329,3 +332,98 @@ fn test_emergency_recovery_workflow() {
     let _ = client.emergency_unpause();
     assert!(!client.is_emergency_paused());
 }
+
+#[test]
+fn test_timed_pause_lapses_after_duration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, owner, _, _, _) = setup_contract(&env);
+    let reason = String::from_str(&env, "token migration");
+
+    client.emergency_pause_with_terms(&Some(3600), &Some(reason.clone()));
+    assert!(client.is_emergency_paused());
+
+    let info = client.get_pause_info();
+    assert!(info.is_paused);
+    assert_eq!(info.paused_by, Some(owner));
+    assert_eq!(info.resume_at, Some(3600));
+    assert_eq!(info.reason, Some(reason));
+
+    env.ledger().with_mut(|l| l.timestamp = 3599);
+    assert!(client.is_emergency_paused());
+
+    env.ledger().with_mut(|l| l.timestamp = 3600);
+    assert!(!client.is_emergency_paused());
+    let info = client.get_pause_info();
+    assert!(!info.is_paused);
+    assert_eq!(info.reason, None);
+}
+
+#[test]
+fn test_timed_pause_unblocks_claims_after_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _owner, _, _, _) = setup_contract(&env);
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    let employer = Address::generate(&env);
+    let contributor = Address::generate(&env);
+
+    let agreement_id = client.create_milestone_agreement(&employer, &contributor, &token.address);
+    client.add_milestone(&agreement_id, &1000);
+    token.mint(&employer, &1000);
+    client.fund_milestone_agreement(&agreement_id, &employer, &1000);
+    client.approve_milestone(&agreement_id, &1);
+
+    client.emergency_pause_with_terms(&Some(60), &None);
+    assert!(client.try_claim_milestone(&agreement_id, &1).is_err());
+
+    env.ledger().with_mut(|l| l.timestamp += 60);
+    client.claim_milestone(&agreement_id, &1);
+}
+
+#[test]
+fn test_zero_duration_pause_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _owner, _, _, _) = setup_contract(&env);
+
+    assert_eq!(
+        client.try_emergency_pause_with_terms(&Some(0), &None),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert!(!client.is_emergency_paused());
+}
+
+#[test]
+fn test_pause_info_empty_when_never_paused() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _owner, _, _, _) = setup_contract(&env);
+
+    let info = client.get_pause_info();
+    assert!(!info.is_paused);
+    assert_eq!(info.resume_at, None);
+}
+
+#[test]
+fn test_plain_pause_clears_previous_terms() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _owner, _, _, _) = setup_contract(&env);
+    let reason = String::from_str(&env, "token migration");
+
+    client.emergency_pause_with_terms(&Some(60), &Some(reason));
+    client.emergency_pause();
+
+    env.ledger().with_mut(|l| l.timestamp += 60);
+    assert!(client.is_emergency_paused());
+    let info = client.get_pause_info();
+    assert_eq!(info.resume_at, None);
+    assert_eq!(info.reason, None);
+}
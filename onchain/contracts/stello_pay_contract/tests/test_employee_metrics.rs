@@ -0,0 +1,219 @@
+#![cfg(test)]
+
+use payroll_analytics::{
+    EmployeeMetrics, EmployerMetrics, PayrollAnalytics, PayrollAnalyticsClient,
+};
+use soroban_sdk::{vec, Address};
+use stello_pay_contract::testutils::{Fixture, DAY, FIXTURE_START};
+
+const HOUR: u64 = 3_600;
+const SALARY: i128 = 1_000;
+
+struct Setup {
+    f: Fixture<'static>,
+    analytics: PayrollAnalyticsClient<'static>,
+    employee: Address,
+}
+
+/// A payroll contract linked to a fresh analytics contract.
+fn setup() -> Setup {
+    let f = Fixture::new();
+    let analytics = PayrollAnalyticsClient::new(&f.env, &f.env.register(PayrollAnalytics, ()));
+    analytics.initialize(&f.owner, &f.client.address);
+    f.client
+        .set_analytics_contract(&f.owner, &Some(analytics.address.clone()));
+
+    Setup {
+        employee: f.address(),
+        f,
+        analytics,
+    }
+}
+
+#[test]
+fn unpaid_employee_has_empty_metrics() {
+    let Setup {
+        analytics,
+        employee,
+        ..
+    } = setup();
+    assert_eq!(
+        analytics.get_employee_metrics(&employee),
+        EmployeeMetrics::default()
+    );
+}
+
+#[test]
+fn timely_claims_build_a_streak() {
+    let Setup {
+        f,
+        analytics,
+        employee,
+    } = setup();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+
+    // Each period claimed an hour after it falls due
+    f.warp(DAY + HOUR);
+    for _ in 0..3 {
+        f.client.claim_payroll(&employee, &agreement_id, &0);
+        f.warp(DAY);
+    }
+
+    let metrics = analytics.get_employee_metrics(&employee);
+    assert_eq!(metrics.total_received, SALARY * 3);
+    assert_eq!(metrics.on_time_payouts, 3);
+    assert_eq!(metrics.late_payouts, 0);
+    assert_eq!(metrics.average_days_late, 0);
+    assert_eq!(metrics.current_streak, 3);
+    assert_eq!(metrics.last_payout_at, FIXTURE_START + 3 * DAY + HOUR);
+}
+
+#[test]
+fn backlog_claim_counts_late_periods_and_resets_streak() {
+    let Setup {
+        f,
+        analytics,
+        employee,
+    } = setup();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+
+    f.warp(DAY + HOUR);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+
+    // Periods 2..=5 claimed an hour after period 5 falls due: 2, 3 and 4 are
+    // late by 3, 2 and 1 days (plus the hour), period 5 is on time
+    f.warp(4 * DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+
+    let metrics = analytics.get_employee_metrics(&employee);
+    assert_eq!(metrics.total_received, SALARY * 5);
+    assert_eq!(metrics.on_time_payouts, 2);
+    assert_eq!(metrics.late_payouts, 3);
+    assert_eq!(metrics.total_seconds_late, 6 * DAY + 3 * HOUR);
+    assert_eq!(metrics.average_days_late, 2);
+    assert_eq!(metrics.current_streak, 1);
+}
+
+#[test]
+fn batch_claims_are_recorded_per_employee() {
+    let Setup {
+        f,
+        analytics,
+        employee,
+    } = setup();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+
+    f.warp(2 * DAY + 2 * HOUR);
+    f.client
+        .batch_claim_payroll(&employee, &agreement_id, &vec![&f.env, 0u32]);
+
+    let metrics = analytics.get_employee_metrics(&employee);
+    assert_eq!(metrics.total_received, SALARY * 2);
+    assert_eq!(metrics.on_time_payouts, 1);
+    assert_eq!(metrics.late_payouts, 1);
+    assert_eq!(metrics.average_days_late, 1);
+    assert_eq!(metrics.current_streak, 1);
+
+    assert_eq!(
+        analytics.get_employee_metrics(&f.address()),
+        EmployeeMetrics::default()
+    );
+}
+
+#[test]
+fn reporting_currency_converts_payouts_at_oracle_rate() {
+    let Setup {
+        f,
+        analytics,
+        employee,
+    } = setup();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    let usd = f.address();
+    // 1 token = 2.5 USD (FX_SCALE = FIXTURE_START)
+    f.client
+        .set_exchange_rate(&f.owner, &f.token.address, &usd, &2_500_000);
+
+    assert_eq!(
+        analytics.get_employer_metrics(&f.employer),
+        EmployerMetrics::default()
+    );
+    f.client
+        .set_reporting_currency(&f.employer, &Some(usd.clone()));
+    assert_eq!(
+        f.client.get_reporting_currency(&f.employer),
+        Some(usd.clone())
+    );
+
+    f.warp(2 * DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+
+    let metrics = analytics.get_employer_metrics(&f.employer);
+    assert_eq!(metrics.reporting_currency, Some(usd));
+    assert_eq!(metrics.total_paid_reporting, 5_000);
+    assert_eq!(metrics.converted_payouts, 1);
+    assert_eq!(metrics.unconverted_payouts, 0);
+    assert_eq!(metrics.since, FIXTURE_START);
+}
+
+#[test]
+fn payout_without_rate_is_counted_as_unconverted() {
+    let Setup {
+        f,
+        analytics,
+        employee,
+    } = setup();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    f.client
+        .set_reporting_currency(&f.employer, &Some(f.address()));
+
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+
+    let metrics = analytics.get_employer_metrics(&f.employer);
+    assert_eq!(metrics.total_paid_reporting, 0);
+    assert_eq!(metrics.converted_payouts, 0);
+    assert_eq!(metrics.unconverted_payouts, 1);
+    assert_eq!(
+        analytics.get_employee_metrics(&employee).total_received,
+        SALARY
+    );
+
+    f.client.set_reporting_currency(&f.employer, &None);
+    assert_eq!(f.client.get_reporting_currency(&f.employer), None);
+    assert_eq!(
+        analytics.get_employer_metrics(&f.employer),
+        EmployerMetrics::default()
+    );
+}
+
+#[test]
+fn payouts_do_not_depend_on_the_analytics_contract() {
+    let Setup {
+        f,
+        analytics,
+        employee,
+    } = setup();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+
+    // Linked to another payroll contract: every push is rejected
+    analytics.set_payroll_contract(&f.owner, &f.address());
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    assert_eq!(
+        analytics.get_employee_metrics(&employee),
+        EmployeeMetrics::default()
+    );
+
+    f.client.set_analytics_contract(&f.owner, &None);
+    assert_eq!(f.client.get_analytics_contract(), None);
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    assert_eq!(f.token.balance(&employee), SALARY * 2);
+    // The payer's reputation is still tracked here
+    assert_eq!(
+        f.client
+            .get_employer_reputation(&f.employer)
+            .on_time_periods,
+        2
+    );
+}
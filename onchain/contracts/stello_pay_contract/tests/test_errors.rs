@@ -0,0 +1,55 @@
+#![cfg(test)]
+
+use soroban_sdk::{String, Symbol};
+use stello_pay_contract::{
+    errors::{ErrorCategory, ErrorInfo},
+    storage::PayrollError,
+    testutils::Fixture,
+};
+
+const CATALOGS: [&str; 2] = [
+    include_str!("../../../../docs/error-catalog/en.json"),
+    include_str!("../../../../docs/error-catalog/es.json"),
+];
+
+#[test]
+fn every_error_code_is_described() {
+    let f = Fixture::new();
+    assert_eq!(
+        f.client
+            .get_error_info(&(PayrollError::InsufficientEscrowBalance as u32)),
+        Some(ErrorInfo {
+            code: 15,
+            name: String::from_str(&f.env, "InsufficientEscrowBalance"),
+            category: ErrorCategory::Funds,
+            description: String::from_str(&f.env, "The escrow does not cover the payment"),
+            remediation: Symbol::new(&f.env, "fund_escrow"),
+        })
+    );
+    assert_eq!(
+        f.client
+            .get_error_info(&(PayrollError::Unauthorized as u32))
+            .unwrap()
+            .category,
+        ErrorCategory::Auth
+    );
+
+    for code in 1..=PayrollError::ColaNotDue as u32 {
+        let info = f.client.get_error_info(&code).unwrap();
+        assert_eq!(info.code, code);
+        // Every remediation key has a message in every language
+        let key = info.remediation.to_string();
+        for catalog in CATALOGS {
+            assert!(
+                catalog.contains(&format!("\"{key}\":")),
+                "{key} is missing from a catalog"
+            );
+        }
+    }
+    assert_eq!(f.client.get_error_info(&0), None);
+    assert_eq!(
+        f.client
+            .get_error_info(&(PayrollError::ColaNotDue as u32 + 1)),
+        None
+    );
+}
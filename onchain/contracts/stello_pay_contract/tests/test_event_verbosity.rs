@@ -0,0 +1,66 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Events, Symbol, TryFromVal};
+use stello_pay_contract::{
+    events::EventVerbosity,
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+/// Names of the events the payroll contract published in the last call.
+fn published(f: &Fixture) -> std::vec::Vec<std::string::String> {
+    f.env
+        .events()
+        .all()
+        .iter()
+        .filter(|e| e.0 == f.client.address)
+        .filter_map(|e| Symbol::try_from_val(&f.env, &e.1.get(0)?).ok())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+#[test]
+fn verbosity_drops_lower_levels_but_keeps_critical_events() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let payroll = f.payroll().employee(&employee, 100).build();
+    assert_eq!(f.client.get_event_verbosity(), EventVerbosity::Full);
+    assert_eq!(
+        f.client
+            .try_set_event_verbosity(&f.address(), &EventVerbosity::Minimal),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &payroll, &0);
+    let full = published(&f);
+    assert!(full.contains(&"payment_sent_event".into()));
+    assert!(full.contains(&"payment_received_event".into()));
+
+    // Minimal drops detail events that repeat another event
+    f.client
+        .set_event_verbosity(&f.owner, &EventVerbosity::Minimal);
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &payroll, &0);
+    let minimal = published(&f);
+    assert!(minimal.contains(&"payment_sent_event".into()));
+    assert!(!minimal.contains(&"payment_received_event".into()));
+    assert_eq!(minimal.len(), full.len() - 1);
+
+    // Critical-only drops lifecycle events but keeps payouts
+    f.client
+        .set_event_verbosity(&f.owner, &EventVerbosity::CriticalOnly);
+    f.client.pause_agreement(&payroll);
+    assert!(published(&f).is_empty());
+    f.client.resume_agreement(&payroll);
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &payroll, &0);
+    let critical = published(&f);
+    assert!(critical.contains(&"payment_sent_event".into()));
+    assert!(critical.contains(&"receipt_issued_event".into()));
+
+    f.client
+        .set_event_verbosity(&f.owner, &EventVerbosity::Full);
+    f.client.pause_agreement(&payroll);
+    assert_eq!(published(&f), ["agreement_paused_event"]);
+}
@@ -0,0 +1,304 @@
+//! Property-based fund conservation across payroll, escrow and bonus
+//! contracts.
+//!
+//! Each case builds a [`Fixture`] and applies a random sequence of deposits
+//! (funding new payroll agreements, escrow agreements and incentives),
+//! disbursements (payroll claims, escrow claims, incentive claims and unified
+//! pay runs), time advances, cancellations and refunds. Failed calls are
+//! expected along the way and ignored. After every step the global
+//! invariants must hold:
+//!
+//! - **No negative balances**: every token balance and every tracked escrow
+//!   balance is non-negative.
+//! - **Escrow totals equal token balances**: the payroll contract holds
+//!   exactly the sum of its agreements' escrow balances, and the bonus
+//!   contract exactly what its incentives still escrow.
+//! - **Paid never exceeds funded**: no agreement's escrow grows past what was
+//!   deposited, and no worker receives more than was funded for them.
+//! - **Conservation**: every minted token is held by a tracked account.
+
+#![cfg(test)]
+
+use proptest::prelude::*;
+use soroban_sdk::{Address, Env};
+use stello_pay_contract::{
+    storage::DataKey,
+    testutils::{Fixture, DAY},
+};
+
+/// Workers the generated operations pick from.
+const WORKERS: usize = 3;
+
+fn proptest_cases() -> u32 {
+    std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(32)
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    FundPayroll {
+        worker: usize,
+        salary: i128,
+        periods: u32,
+    },
+    FundEscrow {
+        worker: usize,
+        amount: i128,
+        periods: u32,
+    },
+    FundIncentive {
+        worker: usize,
+        amount: i128,
+        payouts: u32,
+    },
+    Advance(u64),
+    ClaimPayroll(usize),
+    ClaimEscrow(usize),
+    ClaimIncentive(usize),
+    PayRun(usize),
+    Cancel(usize),
+    Refund(usize),
+    CancelIncentive(usize),
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..WORKERS, 1i128..5_000, 1u32..6).prop_map(|(worker, salary, periods)| {
+            Op::FundPayroll {
+                worker,
+                salary,
+                periods,
+            }
+        }),
+        (0..WORKERS, 1i128..5_000, 1u32..6).prop_map(|(worker, amount, periods)| {
+            Op::FundEscrow {
+                worker,
+                amount,
+                periods,
+            }
+        }),
+        (0..WORKERS, 1i128..2_000, 1u32..4).prop_map(|(worker, amount, payouts)| {
+            Op::FundIncentive {
+                worker,
+                amount,
+                payouts,
+            }
+        }),
+        (1u64..3 * DAY).prop_map(Op::Advance),
+        any::<usize>().prop_map(Op::ClaimPayroll),
+        any::<usize>().prop_map(Op::ClaimEscrow),
+        any::<usize>().prop_map(Op::ClaimIncentive),
+        (0..WORKERS).prop_map(Op::PayRun),
+        any::<usize>().prop_map(Op::Cancel),
+        any::<usize>().prop_map(Op::Refund),
+        any::<usize>().prop_map(Op::CancelIncentive),
+    ]
+}
+
+/// Payroll or escrow agreement created during a run.
+struct Funded {
+    id: u128,
+    worker: usize,
+    /// Employee index for `claim_payroll`; `None` for escrow agreements.
+    payroll_index: Option<u32>,
+    deposited: i128,
+}
+
+struct Model {
+    f: Fixture<'static>,
+    workers: Vec<Address>,
+    agreements: Vec<Funded>,
+    /// (incentive ID, worker)
+    incentives: Vec<(u128, usize)>,
+    /// Total funded for each worker across all three contracts.
+    funded_for: [i128; WORKERS],
+    minted: i128,
+}
+
+impl Model {
+    fn new() -> Self {
+        let f = Fixture::new();
+        let workers = (0..WORKERS).map(|_| f.address()).collect();
+        Model {
+            f,
+            workers,
+            agreements: Vec::new(),
+            incentives: Vec::new(),
+            funded_for: [0; WORKERS],
+            minted: 0,
+        }
+    }
+
+    fn agreement(&self, pick: usize) -> Option<&Funded> {
+        match self.agreements.len() {
+            0 => None,
+            n => self.agreements.get(pick % n),
+        }
+    }
+
+    fn apply(&mut self, op: &Op) {
+        let f = &self.f;
+        let client = &f.client;
+        match *op {
+            Op::FundPayroll {
+                worker,
+                salary,
+                periods,
+            } => {
+                let id = f
+                    .payroll()
+                    .employee(&self.workers[worker], salary)
+                    .funded_periods(periods)
+                    .build();
+                self.record(id, worker, Some(0), salary * i128::from(periods));
+            }
+            Op::FundEscrow {
+                worker,
+                amount,
+                periods,
+            } => {
+                let id = f
+                    .agreement(&self.workers[worker])
+                    .amount_per_period(amount)
+                    .num_periods(periods)
+                    .build();
+                self.record(id, worker, None, amount * i128::from(periods));
+            }
+            Op::FundIncentive {
+                worker,
+                amount,
+                payouts,
+            } => {
+                let id = f
+                    .incentive(&self.workers[worker])
+                    .amount(amount)
+                    .recurring(payouts, DAY)
+                    .build();
+                self.incentives.push((id, worker));
+                let escrow = amount * i128::from(payouts);
+                self.funded_for[worker] += escrow;
+                self.minted += escrow;
+            }
+            Op::Advance(seconds) => f.warp(seconds),
+            Op::ClaimPayroll(pick) => {
+                if let Some(a) = self.agreement(pick) {
+                    if let Some(index) = a.payroll_index {
+                        let _ = client.try_claim_payroll(&self.workers[a.worker], &a.id, &index);
+                    }
+                }
+            }
+            Op::ClaimEscrow(pick) => {
+                if let Some(a) = self.agreement(pick) {
+                    if a.payroll_index.is_none() {
+                        let _ = client.try_claim_time_based(&a.id);
+                    }
+                }
+            }
+            Op::ClaimIncentive(pick) => {
+                if !self.incentives.is_empty() {
+                    let (id, worker) = self.incentives[pick % self.incentives.len()];
+                    let _ = f.bonus.try_claim_incentive(&self.workers[worker], &id);
+                }
+            }
+            Op::PayRun(worker) => {
+                let _ = client.try_disburse_total_compensation(&f.employer, &self.workers[worker]);
+            }
+            Op::Cancel(pick) => {
+                if let Some(a) = self.agreement(pick) {
+                    let _ = client.try_cancel_agreement(&a.id);
+                }
+            }
+            Op::Refund(pick) => {
+                if let Some(a) = self.agreement(pick) {
+                    let _ = client.try_finalize_grace_period(&a.id);
+                }
+            }
+            Op::CancelIncentive(pick) => {
+                if !self.incentives.is_empty() {
+                    let (id, _) = self.incentives[pick % self.incentives.len()];
+                    let _ = f.bonus.try_cancel_incentive(&f.employer, &id);
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, id: u128, worker: usize, payroll_index: Option<u32>, deposited: i128) {
+        self.agreements.push(Funded {
+            id,
+            worker,
+            payroll_index,
+            deposited,
+        });
+        self.funded_for[worker] += deposited;
+        self.minted += deposited;
+    }
+
+    fn escrow_balance(&self, id: u128) -> i128 {
+        let env: &Env = &self.f.env;
+        env.as_contract(&self.f.client.address, || {
+            DataKey::get_agreement_escrow_balance(env, id, &self.f.token.address)
+        })
+    }
+
+    fn assert_invariants(&self) {
+        let f = &self.f;
+        let token = &f.token;
+
+        let mut escrow_total = 0;
+        for a in &self.agreements {
+            let escrow = self.escrow_balance(a.id);
+            assert!(escrow >= 0, "negative escrow on agreement {}", a.id);
+            assert!(
+                escrow <= a.deposited,
+                "agreement {} escrows {} of {} deposited",
+                a.id,
+                escrow,
+                a.deposited
+            );
+            escrow_total += escrow;
+        }
+        assert_eq!(token.balance(&f.client.address), escrow_total);
+
+        let stats = f.bonus.get_employer_bonus_stats(&f.employer);
+        let bonus_escrow = stats.total_escrowed - stats.total_claimed - stats.total_refunded;
+        assert!(bonus_escrow >= 0);
+        assert_eq!(token.balance(&f.bonus.address), bonus_escrow);
+
+        let mut held = token.balance(&f.client.address)
+            + token.balance(&f.bonus.address)
+            + token.balance(&f.employer);
+        assert!(token.balance(&f.employer) >= 0);
+        for (worker, address) in self.workers.iter().enumerate() {
+            let received = token.balance(address);
+            assert!(received >= 0);
+            assert!(
+                received <= self.funded_for[worker],
+                "worker {} received {} of {} funded",
+                worker,
+                received,
+                self.funded_for[worker]
+            );
+            held += received;
+        }
+        assert_eq!(held, self.minted);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(proptest_cases()))]
+
+    /// Random deposits, disbursements, advances, cancellations and refunds
+    /// never break fund conservation.
+    #[test]
+    fn prop_funds_are_conserved_across_contracts(
+        ops in prop::collection::vec(op_strategy(), 1..40)
+    ) {
+        let mut model = Model::new();
+        for op in &ops {
+            model.apply(op);
+            model.assert_invariants();
+        }
+    }
+}
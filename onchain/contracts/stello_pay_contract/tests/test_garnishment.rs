@@ -0,0 +1,257 @@
+#![cfg(test)]
+
+use soroban_sdk::vec;
+use stello_pay_contract::{
+    garnishment::GarnishmentDeduction,
+    payslip::PayslipDeduction,
+    storage::{DataKey, PayrollError},
+    testutils::{Fixture, TestToken, DAY},
+};
+
+const SALARY: i128 = 1_000;
+
+#[test]
+fn only_owner_or_compliance_admin_can_register() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let recipient = f.address();
+    let stranger = f.address();
+    let deduction = GarnishmentDeduction::Bps(2_500);
+
+    assert_eq!(
+        f.client.try_register_garnishment(
+            &stranger,
+            &employee,
+            &recipient,
+            &f.token.address,
+            &deduction,
+            &500
+        ),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        f.client.try_set_compliance_admin(&stranger, &stranger),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+
+    let admin = f.address();
+    f.client.set_compliance_admin(&f.owner, &admin);
+    assert_eq!(f.client.get_compliance_admin(), Some(admin.clone()));
+    let first = f.client.register_garnishment(
+        &f.owner,
+        &employee,
+        &recipient,
+        &f.token.address,
+        &deduction,
+        &500,
+    );
+    let second = f.client.register_garnishment(
+        &admin,
+        &employee,
+        &recipient,
+        &f.token.address,
+        &deduction,
+        &500,
+    );
+    assert_eq!(
+        f.client.get_employee_garnishments(&employee),
+        vec![&f.env, first, second]
+    );
+}
+
+#[test]
+fn invalid_orders_are_rejected() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let recipient = f.address();
+    for (deduction, cap) in [
+        (GarnishmentDeduction::Bps(0), 500),
+        (GarnishmentDeduction::Bps(10_001), 500),
+        (GarnishmentDeduction::Fixed(0), 500),
+        (GarnishmentDeduction::Fixed(100), 0),
+    ] {
+        assert_eq!(
+            f.client.try_register_garnishment(
+                &f.owner,
+                &employee,
+                &recipient,
+                &f.token.address,
+                &deduction,
+                &cap
+            ),
+            Err(Ok(PayrollError::InvalidData))
+        );
+    }
+}
+
+#[test]
+fn deduction_is_routed_until_cap_and_listed_on_payslip() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    let recipient = f.address();
+    let order_id = f.client.register_garnishment(
+        &f.owner,
+        &employee,
+        &recipient,
+        &f.token.address,
+        &GarnishmentDeduction::Bps(2_500),
+        &400,
+    );
+
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    assert_eq!(f.token.balance(&recipient), 250);
+    assert_eq!(f.token.balance(&employee), 750);
+
+    let payslip = f.client.get_payslip(&employee, &1).unwrap();
+    assert_eq!(payslip.agreement_id, agreement_id);
+    assert_eq!(payslip.gross, SALARY);
+    assert_eq!(payslip.net, 750);
+    assert_eq!(
+        payslip.deductions,
+        vec![
+            &f.env,
+            PayslipDeduction {
+                order_id,
+                recipient: recipient.clone(),
+                amount: 250,
+            }
+        ]
+    );
+
+    // Only 150 left under the cap; the order then completes
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    assert_eq!(f.token.balance(&recipient), 400);
+    assert_eq!(f.token.balance(&employee), 750 + 850);
+    let order = f.client.get_garnishment(&order_id).unwrap();
+    assert_eq!(order.total_withheld, 400);
+    assert!(!order.active);
+    assert!(f.client.get_employee_garnishments(&employee).is_empty());
+
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    assert_eq!(f.token.balance(&employee), 750 + 850 + SALARY);
+    assert_eq!(f.client.get_payslip_count(&employee), 3);
+    assert!(f
+        .client
+        .get_payslip(&employee, &3)
+        .unwrap()
+        .deductions
+        .is_empty());
+}
+
+#[test]
+fn orders_apply_in_sequence_without_exceeding_payout() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    let first = f.address();
+    let second = f.address();
+    f.client.register_garnishment(
+        &f.owner,
+        &employee,
+        &first,
+        &f.token.address,
+        &GarnishmentDeduction::Fixed(700),
+        &10_000,
+    );
+    f.client.register_garnishment(
+        &f.owner,
+        &employee,
+        &second,
+        &f.token.address,
+        &GarnishmentDeduction::Fixed(700),
+        &10_000,
+    );
+
+    f.warp(DAY);
+    f.client
+        .batch_claim_payroll(&employee, &agreement_id, &vec![&f.env, 0u32]);
+
+    assert_eq!(f.token.balance(&first), 700);
+    assert_eq!(f.token.balance(&second), 300);
+    assert_eq!(f.token.balance(&employee), 0);
+    assert_eq!(f.client.get_payslip(&employee, &1).unwrap().net, 0);
+}
+
+#[test]
+fn released_or_other_token_orders_do_not_deduct() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    let recipient = f.address();
+    let other_token = f.address();
+    f.client.register_garnishment(
+        &f.owner,
+        &employee,
+        &recipient,
+        &other_token,
+        &GarnishmentDeduction::Fixed(100),
+        &1_000,
+    );
+    let released = f.client.register_garnishment(
+        &f.owner,
+        &employee,
+        &recipient,
+        &f.token.address,
+        &GarnishmentDeduction::Fixed(100),
+        &1_000,
+    );
+    f.client.release_garnishment(&f.owner, &released);
+    assert_eq!(
+        f.client.try_release_garnishment(&f.owner, &released),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    assert_eq!(f.token.balance(&employee), SALARY);
+}
+
+#[test]
+fn claims_in_another_token_are_garnished_on_their_base_gross() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let recipient = f.address();
+    let id = f.payroll().employee(&employee, SALARY).build();
+    let payout = TestToken::new(&f.env);
+    payout.mint(&f.client.address, 10_000);
+    f.env.as_contract(&f.client.address, || {
+        DataKey::set_agreement_escrow_balance(&f.env, id, &payout.address, 10_000);
+    });
+    f.client
+        .set_exchange_rate(&f.owner, &f.token.address, &payout.address, &2_000_000);
+    let order_id = f.client.register_garnishment(
+        &f.owner,
+        &employee,
+        &recipient,
+        &f.token.address,
+        &GarnishmentDeduction::Bps(2_500),
+        &400,
+    );
+
+    // A quarter of the 1_000 base gross, paid as 500 of the payout token
+    f.warp(DAY);
+    f.client
+        .claim_payroll_in_token(&employee, &id, &0, &payout.address);
+    assert_eq!(payout.balance(&recipient), 500);
+    assert_eq!(payout.balance(&employee), 1_500);
+    assert_eq!(
+        f.client.get_garnishment(&order_id).unwrap().total_withheld,
+        250
+    );
+    let payslip = f.client.get_payslip(&employee, &1).unwrap();
+    assert_eq!(payslip.token, payout.address);
+    assert_eq!(payslip.gross, 2_000);
+    assert_eq!(payslip.net, 1_500);
+
+    // The cap stays in the order's token
+    f.warp(DAY);
+    f.client
+        .claim_payroll_in_token(&employee, &id, &0, &payout.address);
+    assert_eq!(payout.balance(&recipient), 800);
+    assert_eq!(payout.balance(&employee), 1_500 + 1_700);
+    assert!(!f.client.get_garnishment(&order_id).unwrap().active);
+}
@@ -0,0 +1,157 @@
+#![cfg(test)]
+
+use soroban_sdk::{Address, BytesN};
+use stello_pay_contract::{
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+const PERIOD_SECONDS: u64 = 7 * DAY;
+const HOURLY_RATE: i128 = 25;
+const MAX_HOURS: u32 = 60;
+
+struct Setup {
+    f: Fixture<'static>,
+    agreement_id: u128,
+    employee: Address,
+    timekeeper: Address,
+}
+
+/// A funded weekly payroll agreement paying `HOURLY_RATE` to one employee,
+/// switched to hourly pay before it was activated.
+fn setup() -> Setup {
+    let f = Fixture::new();
+    let employee = f.address();
+    let timekeeper = f.address();
+    let agreement_id = f
+        .payroll()
+        .employee(&employee, HOURLY_RATE)
+        .period_seconds(PERIOD_SECONDS)
+        .grace_period_seconds(PERIOD_SECONDS)
+        .funded_periods(4_000)
+        .hourly(&timekeeper, MAX_HOURS)
+        .build();
+
+    Setup {
+        f,
+        agreement_id,
+        employee,
+        timekeeper,
+    }
+}
+
+#[test]
+fn claims_pay_rate_times_attested_hours() {
+    let s = setup();
+    s.f.warp(3 * PERIOD_SECONDS);
+    s.f.client
+        .attest_hours(&s.timekeeper, &s.agreement_id, &s.employee, &0, &40);
+    s.f.client
+        .attest_hours(&s.timekeeper, &s.agreement_id, &s.employee, &1, &10);
+
+    // Period 2 is not attested yet, so only periods 0 and 1 are paid
+    s.f.client.claim_payroll(&s.employee, &s.agreement_id, &0);
+    assert_eq!(s.f.token.balance(&s.employee), 50 * HOURLY_RATE);
+    assert_eq!(
+        s.f.client.get_employee_claimed_periods(&s.agreement_id, &0),
+        2
+    );
+    assert_eq!(
+        s.f.client
+            .try_claim_payroll(&s.employee, &s.agreement_id, &0),
+        Err(Ok(PayrollError::NoPeriodsToClaim))
+    );
+
+    s.f.client
+        .attest_hours(&s.timekeeper, &s.agreement_id, &s.employee, &2, &0);
+    s.f.client.claim_payroll(&s.employee, &s.agreement_id, &0);
+    assert_eq!(s.f.token.balance(&s.employee), 50 * HOURLY_RATE);
+    assert_eq!(
+        s.f.client.get_employee_claimed_periods(&s.agreement_id, &0),
+        3
+    );
+}
+
+#[test]
+fn attestations_are_capped_final_and_for_ended_periods() {
+    let s = setup();
+    s.f.warp(PERIOD_SECONDS);
+
+    assert_eq!(
+        s.f.client
+            .try_attest_hours(&s.f.employer, &s.agreement_id, &s.employee, &0, &40),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        s.f.client.try_attest_hours(
+            &s.timekeeper,
+            &s.agreement_id,
+            &s.employee,
+            &0,
+            &(MAX_HOURS + 1)
+        ),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    // Period 1 is still running
+    assert_eq!(
+        s.f.client
+            .try_attest_hours(&s.timekeeper, &s.agreement_id, &s.employee, &1, &40),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        s.f.client
+            .try_attest_hours(&s.timekeeper, &s.agreement_id, &s.f.address(), &0, &40),
+        Err(Ok(PayrollError::NoEmployee))
+    );
+
+    s.f.client
+        .attest_hours(&s.timekeeper, &s.agreement_id, &s.employee, &0, &MAX_HOURS);
+    assert_eq!(
+        s.f.client
+            .get_attested_hours(&s.agreement_id, &s.employee, &0),
+        Some(MAX_HOURS)
+    );
+    assert_eq!(
+        s.f.client
+            .try_attest_hours(&s.timekeeper, &s.agreement_id, &s.employee, &0, &1),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    // A rotated timekeeper takes over
+    let oracle = s.f.address();
+    s.f.client
+        .set_hourly_timekeeper(&s.f.employer, &s.agreement_id, &oracle);
+    s.f.warp(PERIOD_SECONDS);
+    assert_eq!(
+        s.f.client
+            .try_attest_hours(&s.timekeeper, &s.agreement_id, &s.employee, &1, &8),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    s.f.client
+        .attest_hours(&oracle, &s.agreement_id, &s.employee, &1, &8);
+}
+
+#[test]
+fn hourly_pay_is_fixed_at_activation_and_excludes_confidential_salaries() {
+    let s = setup();
+    assert_eq!(
+        s.f.client
+            .get_hourly_config(&s.agreement_id)
+            .unwrap()
+            .max_hours_per_period,
+        MAX_HOURS
+    );
+    assert_eq!(
+        s.f.client
+            .try_enable_hourly_pay(&s.f.employer, &s.agreement_id, &s.timekeeper, &80),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        s.f.client.try_set_salary_commitment(
+            &s.agreement_id,
+            &0,
+            &BytesN::from_array(&s.f.env, &[1; 32])
+        ),
+        Err(Ok(PayrollError::InvalidAgreementMode))
+    );
+}
@@ -0,0 +1,214 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, BytesN, Env,
+};
+use stello_pay_contract::{
+    invoices::InvoiceStatus,
+    storage::{AgreementStatus, DataKey, PayrollError},
+    PayrollContract, PayrollContractClient,
+};
+
+const DAY: u64 = 86_400;
+
+struct Setup {
+    env: Env,
+    client: PayrollContractClient<'static>,
+    employer: Address,
+    contributor: Address,
+    token: TokenClient<'static>,
+    agreement_id: u128,
+}
+
+/// Creates an activated escrow agreement worth 4 × 1000 with `escrow` of it
+/// funded.
+fn setup(escrow: i128) -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PayrollContract, ());
+    let client = PayrollContractClient::new(&env, &contract_id);
+    let employer = Address::generate(&env);
+    let contributor = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+
+    let agreement_id =
+        client.create_escrow_agreement(&employer, &contributor, &token, &1_000, &DAY, &4);
+    StellarAssetClient::new(&env, &token).mint(&contract_id, &escrow);
+    env.as_contract(&contract_id, || {
+        DataKey::set_agreement_escrow_balance(&env, agreement_id, &token, escrow);
+    });
+    client.activate_agreement(&agreement_id);
+
+    Setup {
+        token: TokenClient::new(&env, &token),
+        env,
+        client,
+        employer,
+        contributor,
+        agreement_id,
+    }
+}
+
+fn submit(s: &Setup, amount: i128, due_in: u64) -> u32 {
+    let due_date = s.env.ledger().timestamp() + due_in;
+    s.client.submit_invoice(
+        &s.contributor,
+        &s.agreement_id,
+        &amount,
+        &BytesN::from_array(&s.env, &[7; 32]),
+        &due_date,
+    )
+}
+
+fn advance(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|l| l.timestamp += seconds);
+}
+
+#[test]
+fn approved_invoices_are_paid_from_escrow() {
+    let s = setup(4_000);
+    assert_eq!(submit(&s, 2_500, 7 * DAY), 1);
+    assert_eq!(submit(&s, 1_500, 7 * DAY), 2);
+    assert_eq!(s.client.get_invoice_count(&s.agreement_id), 2);
+
+    s.client.approve_invoice(&s.employer, &s.agreement_id, &1);
+    assert_eq!(s.token.balance(&s.contributor), 2_500);
+    let invoice = s.client.get_invoice(&s.agreement_id, &1).unwrap();
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    assert_eq!(invoice.settled_at, Some(s.env.ledger().timestamp()));
+    assert_eq!(s.client.get_open_invoices(&s.agreement_id).len(), 1);
+    assert_eq!(
+        s.client.get_agreement(&s.agreement_id).unwrap().paid_amount,
+        2_500
+    );
+
+    // Paying the rest of the total completes the agreement
+    s.client.approve_invoice(&s.employer, &s.agreement_id, &2);
+    assert_eq!(s.token.balance(&s.contributor), 4_000);
+    assert_eq!(
+        s.client.get_agreement(&s.agreement_id).unwrap().status,
+        AgreementStatus::Completed
+    );
+    assert_eq!(
+        s.client
+            .try_approve_invoice(&s.employer, &s.agreement_id, &2),
+        Err(Ok(PayrollError::NotInGracePeriod))
+    );
+}
+
+#[test]
+fn disputed_invoice_can_be_approved_or_withdrawn() {
+    let s = setup(4_000);
+    let first = submit(&s, 1_000, DAY);
+    let second = submit(&s, 1_000, DAY);
+
+    assert_eq!(
+        s.client
+            .try_dispute_invoice(&s.contributor, &s.agreement_id, &first),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    s.client
+        .dispute_invoice(&s.employer, &s.agreement_id, &first);
+    s.client
+        .dispute_invoice(&s.employer, &s.agreement_id, &second);
+    assert_eq!(
+        s.client
+            .get_invoice(&s.agreement_id, &first)
+            .unwrap()
+            .status,
+        InvoiceStatus::Disputed
+    );
+
+    s.client
+        .approve_invoice(&s.employer, &s.agreement_id, &first);
+    assert_eq!(s.token.balance(&s.contributor), 1_000);
+
+    s.client
+        .withdraw_invoice(&s.contributor, &s.agreement_id, &second);
+    assert_eq!(
+        s.client
+            .get_invoice(&s.agreement_id, &second)
+            .unwrap()
+            .status,
+        InvoiceStatus::Withdrawn
+    );
+    assert_eq!(
+        s.client
+            .try_approve_invoice(&s.employer, &s.agreement_id, &second),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert!(s.client.get_open_invoices(&s.agreement_id).is_empty());
+}
+
+#[test]
+fn aging_report_buckets_open_invoices_by_days_overdue() {
+    let s = setup(4_000);
+    submit(&s, 100, 0);
+    submit(&s, 200, 35 * DAY);
+    submit(&s, 300, 80 * DAY);
+    let disputed = submit(&s, 400, 100 * DAY);
+    let paid = submit(&s, 500, 0);
+    s.client
+        .dispute_invoice(&s.employer, &s.agreement_id, &disputed);
+    s.client
+        .approve_invoice(&s.employer, &s.agreement_id, &paid);
+
+    advance(&s.env, 95 * DAY);
+    let report = s.client.get_invoice_aging_report(&s.agreement_id);
+    assert_eq!(report.as_of, s.env.ledger().timestamp());
+    assert_eq!((report.open_count, report.open_amount), (4, 1_000));
+    assert_eq!(report.disputed_amount, 400);
+    assert_eq!(report.current, 400);
+    assert_eq!(report.overdue_1_30_days, 300);
+    assert_eq!(report.overdue_31_60_days, 200);
+    assert_eq!(report.overdue_61_90_days, 0);
+    assert_eq!(report.overdue_over_90_days, 100);
+}
+
+#[test]
+fn invalid_invoices_are_rejected() {
+    let s = setup(1_000);
+    let hash = BytesN::from_array(&s.env, &[0; 32]);
+    let now = s.env.ledger().timestamp();
+    assert_eq!(
+        s.client.try_submit_invoice(
+            &Address::generate(&s.env),
+            &s.agreement_id,
+            &1_000,
+            &hash,
+            &now
+        ),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    for amount in [0, 4_001] {
+        assert_eq!(
+            s.client
+                .try_submit_invoice(&s.contributor, &s.agreement_id, &amount, &hash, &now),
+            Err(Ok(PayrollError::InvalidData))
+        );
+    }
+    advance(&s.env, DAY);
+    assert_eq!(
+        s.client
+            .try_submit_invoice(&s.contributor, &s.agreement_id, &1_000, &hash, &now),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    // Only 1000 of the 4000 total is in escrow
+    let number = submit(&s, 2_000, DAY);
+    assert_eq!(
+        s.client
+            .try_approve_invoice(&Address::generate(&s.env), &s.agreement_id, &number),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        s.client
+            .try_approve_invoice(&s.employer, &s.agreement_id, &number),
+        Err(Ok(PayrollError::InsufficientEscrowBalance))
+    );
+}
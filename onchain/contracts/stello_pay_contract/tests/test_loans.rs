@@ -0,0 +1,235 @@
+#![cfg(test)]
+
+use soroban_sdk::Address;
+use stello_pay_contract::{
+    loans::LoanStatus,
+    payslip::PayslipCategory,
+    storage::{DataKey, PayrollError},
+    testutils::{Fixture, TestToken, DAY},
+};
+
+const SALARY: i128 = 1_000;
+
+struct Setup {
+    f: Fixture<'static>,
+    agreement_id: u128,
+    employee: Address,
+    lender: Address,
+}
+
+/// An active payroll agreement paying `SALARY` per period to one employee,
+/// and an approved lender holding 10_000 of its token.
+fn setup() -> Setup {
+    let f = Fixture::new();
+    let employee = f.address();
+    let lender = f.address();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    f.token.mint(&lender, 10_000);
+    f.client.set_loan_lender(&f.owner, &lender, &true);
+
+    Setup {
+        f,
+        agreement_id,
+        employee,
+        lender,
+    }
+}
+
+fn claim_period(s: &Setup) {
+    s.f.warp(DAY);
+    s.f.client.claim_payroll(&s.employee, &s.agreement_id, &0);
+}
+
+#[test]
+fn pledged_share_repays_lender_until_loan_is_repaid() {
+    let s = setup();
+    // 1000 at 10% interest, repaid from half of up to 4 payouts
+    let loan_id =
+        s.f.client
+            .request_salary_loan(&s.employee, &s.agreement_id, &1_000, &1_000, &5_000, &4);
+    assert_eq!(
+        s.f.client.get_open_loan(&s.employee, &s.agreement_id),
+        Some(loan_id)
+    );
+    s.f.client.fund_salary_loan(&s.lender, &loan_id);
+    assert_eq!(s.f.token.balance(&s.employee), 1_000);
+    assert_eq!(s.f.token.balance(&s.lender), 9_000);
+
+    claim_period(&s);
+    claim_period(&s);
+    assert_eq!(s.f.token.balance(&s.lender), 10_000);
+    assert_eq!(s.f.token.balance(&s.employee), 2_000);
+    assert_eq!(
+        s.f.client
+            .get_payslip(&s.employee, &2)
+            .unwrap()
+            .loan_repayment,
+        500
+    );
+
+    // The last repayment only takes what is still owed
+    claim_period(&s);
+    assert_eq!(s.f.token.balance(&s.lender), 10_100);
+    let payslip = s.f.client.get_payslip(&s.employee, &3).unwrap();
+    assert_eq!(payslip.category, PayslipCategory::Salary);
+    assert_eq!((payslip.loan_repayment, payslip.net), (100, 900));
+
+    let loan = s.f.client.get_salary_loan(&loan_id).unwrap();
+    assert_eq!(loan.status, LoanStatus::Repaid);
+    assert_eq!(loan.repaid, 1_100);
+    assert_eq!(s.f.client.get_open_loan(&s.employee, &s.agreement_id), None);
+
+    claim_period(&s);
+    assert_eq!(
+        s.f.client
+            .get_payslip(&s.employee, &4)
+            .unwrap()
+            .loan_repayment,
+        0
+    );
+}
+
+#[test]
+fn loan_defaults_when_pledged_payouts_run_out() {
+    let s = setup();
+    let loan_id =
+        s.f.client
+            .request_salary_loan(&s.employee, &s.agreement_id, &1_000, &0, &1_000, &2);
+    s.f.client.fund_salary_loan(&s.lender, &loan_id);
+
+    claim_period(&s);
+    claim_period(&s);
+    let loan = s.f.client.get_salary_loan(&loan_id).unwrap();
+    assert_eq!(loan.status, LoanStatus::Defaulted);
+    assert_eq!((loan.repaid, loan.payouts_remaining), (200, 0));
+
+    // Later payouts are no longer pledged
+    claim_period(&s);
+    assert_eq!(s.f.client.get_payslip(&s.employee, &3).unwrap().net, SALARY);
+}
+
+#[test]
+fn lender_declares_default_once_payroll_ends_early() {
+    let s = setup();
+    let loan_id =
+        s.f.client
+            .request_salary_loan(&s.employee, &s.agreement_id, &1_000, &0, &5_000, &4);
+    s.f.client.fund_salary_loan(&s.lender, &loan_id);
+    claim_period(&s);
+
+    assert_eq!(
+        s.f.client.try_declare_loan_default(&s.lender, &loan_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    s.f.client.cancel_agreement(&s.agreement_id);
+    // Grace-period payouts can still repay the loan
+    assert_eq!(
+        s.f.client.try_declare_loan_default(&s.lender, &loan_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    s.f.warp(DAY);
+    assert_eq!(
+        s.f.client
+            .try_declare_loan_default(&s.f.address(), &loan_id),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    s.f.client.declare_loan_default(&s.lender, &loan_id);
+    let loan = s.f.client.get_salary_loan(&loan_id).unwrap();
+    assert_eq!(loan.status, LoanStatus::Defaulted);
+    assert_eq!(loan.amount_due - loan.repaid, 500);
+}
+
+#[test]
+fn invalid_requests_and_funding_are_rejected() {
+    let s = setup();
+    assert_eq!(
+        s.f.client
+            .try_request_salary_loan(&s.f.address(), &s.agreement_id, &1_000, &0, &5_000, &4),
+        Err(Ok(PayrollError::NoEmployee))
+    );
+    for (principal, pledge_bps, payouts) in [(0, 5_000, 4), (1_000, 5_001, 4), (1_000, 5_000, 25)] {
+        assert_eq!(
+            s.f.client.try_request_salary_loan(
+                &s.employee,
+                &s.agreement_id,
+                &principal,
+                &0,
+                &pledge_bps,
+                &payouts
+            ),
+            Err(Ok(PayrollError::InvalidData))
+        );
+    }
+
+    let loan_id =
+        s.f.client
+            .request_salary_loan(&s.employee, &s.agreement_id, &1_000, &0, &5_000, &4);
+    assert_eq!(
+        s.f.client
+            .try_request_salary_loan(&s.employee, &s.agreement_id, &500, &0, &5_000, &4),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        s.f.client.try_fund_salary_loan(&s.f.address(), &loan_id),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+
+    s.f.client.cancel_loan_request(&s.employee, &loan_id);
+    assert_eq!(
+        s.f.client.get_salary_loan(&loan_id).unwrap().status,
+        LoanStatus::Cancelled
+    );
+    assert_eq!(
+        s.f.client.try_fund_salary_loan(&s.lender, &loan_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn claims_in_another_token_repay_the_loan_at_the_payout_rate() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let lender = f.address();
+    let id = f.payroll().employee(&employee, SALARY).build();
+    let payout = TestToken::new(&f.env);
+    payout.mint(&f.client.address, 10_000);
+    f.env.as_contract(&f.client.address, || {
+        DataKey::set_agreement_escrow_balance(&f.env, id, &payout.address, 10_000);
+    });
+    f.client
+        .set_exchange_rate(&f.owner, &f.token.address, &payout.address, &2_000_000);
+    f.client.set_loan_lender(&f.owner, &lender, &true);
+    f.token.mint(&lender, 1_000);
+    let loan_id = f
+        .client
+        .request_salary_loan(&employee, &id, &1_000, &1_000, &5_000, &4);
+    f.client.fund_salary_loan(&lender, &loan_id);
+
+    // Half of the 1_000 base gross, paid as 1_000 of the payout token
+    f.warp(DAY);
+    f.client
+        .claim_payroll_in_token(&employee, &id, &0, &payout.address);
+    assert_eq!(payout.balance(&lender), 1_000);
+    assert_eq!(payout.balance(&employee), 1_000);
+    let loan = f.client.get_salary_loan(&loan_id).unwrap();
+    assert_eq!(loan.repaid, 500);
+    assert_eq!(loan.payouts_remaining, 3);
+    assert_eq!(
+        f.client.get_payslip(&employee, &1).unwrap().loan_repayment,
+        1_000
+    );
+
+    f.warp(DAY);
+    f.client
+        .claim_payroll_in_token(&employee, &id, &0, &payout.address);
+    f.warp(DAY);
+    f.client
+        .claim_payroll_in_token(&employee, &id, &0, &payout.address);
+    assert_eq!(payout.balance(&lender), 2_200);
+    assert_eq!(
+        f.client.get_salary_loan(&loan_id).unwrap().status,
+        LoanStatus::Repaid
+    );
+}
@@ -0,0 +1,136 @@
+#![cfg(test)]
+
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{Address, BytesN};
+use stello_pay_contract::{
+    meta_tx::{SignedAction, SignedRequest},
+    savings::SavingsPlan,
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+const SALARY: i128 = 1_000;
+
+struct Setup {
+    f: Fixture<'static>,
+    employee: Address,
+    relayer: Address,
+    key: SigningKey,
+}
+
+/// Registers the contract and an employee whose signing key is registered.
+fn setup() -> Setup {
+    let f = Fixture::new();
+    let employee = f.address();
+    let key = SigningKey::from_bytes(&[7; 32]);
+    f.client.register_signing_key(
+        &employee,
+        &BytesN::from_array(&f.env, &key.verifying_key().to_bytes()),
+    );
+
+    Setup {
+        relayer: f.address(),
+        f,
+        employee,
+        key,
+    }
+}
+
+fn request(s: &Setup, action: SignedAction, nonce: u64) -> SignedRequest {
+    SignedRequest {
+        employee: s.employee.clone(),
+        action,
+        nonce,
+        expiry: s.f.env.ledger().timestamp() + DAY,
+    }
+}
+
+fn sign(s: &Setup, key: &SigningKey, request: &SignedRequest) -> BytesN<64> {
+    let payload = s.f.client.signed_action_payload(request).to_alloc_vec();
+    BytesN::from_array(&s.f.env, &key.sign(&payload).to_bytes())
+}
+
+#[test]
+fn relayed_claim_pays_the_employee_once() {
+    let s = setup();
+    let agreement_id = s.f.payroll().employee(&s.employee, SALARY).build();
+    s.f.warp(2 * DAY);
+
+    let claim = request(&s, SignedAction::ClaimPayroll(agreement_id, 0), 0);
+    let signature = sign(&s, &s.key, &claim);
+    s.f.client
+        .execute_signed_action(&s.relayer, &claim, &signature);
+
+    // Only the relayer authorized the call
+    assert!(s.f.env.auths().iter().all(|(addr, _)| *addr == s.relayer));
+    assert_eq!(s.f.token.balance(&s.employee), 2 * SALARY);
+    assert_eq!(s.f.client.get_signing_nonce(&s.employee), 1);
+
+    // The same signed request cannot be replayed
+    assert_eq!(
+        s.f.client
+            .try_execute_signed_action(&s.relayer, &claim, &signature),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn relayed_savings_plan_changes() {
+    let s = setup();
+    let plan = request(&s, SignedAction::SetSavingsPlan(1_000, DAY), 0);
+    s.f.client
+        .execute_signed_action(&s.relayer, &plan, &sign(&s, &s.key, &plan));
+    assert_eq!(
+        s.f.client.get_savings_plan(&s.employee),
+        Some(SavingsPlan {
+            bps: 1_000,
+            lock_duration: DAY,
+        })
+    );
+
+    let cancel = request(&s, SignedAction::CancelSavingsPlan, 1);
+    s.f.client
+        .execute_signed_action(&s.relayer, &cancel, &sign(&s, &s.key, &cancel));
+    assert_eq!(s.f.client.get_savings_plan(&s.employee), None);
+    assert_eq!(s.f.client.get_signing_nonce(&s.employee), 2);
+}
+
+#[test]
+fn stale_or_forged_requests_are_rejected() {
+    let s = setup();
+    let action = SignedAction::SetSavingsPlan(1_000, DAY);
+
+    // Nonces must be used in order
+    let skipped = request(&s, action.clone(), 1);
+    assert_eq!(
+        s.f.client
+            .try_execute_signed_action(&s.relayer, &skipped, &sign(&s, &s.key, &skipped)),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    let expired = request(&s, action.clone(), 0);
+    let signature = sign(&s, &s.key, &expired);
+    s.f.warp(2 * DAY);
+    assert_eq!(
+        s.f.client
+            .try_execute_signed_action(&s.relayer, &expired, &signature),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    // A signature by any other key aborts the call
+    let forged = request(&s, action.clone(), 0);
+    let other_key = SigningKey::from_bytes(&[9; 32]);
+    assert!(s
+        .f
+        .client
+        .try_execute_signed_action(&s.relayer, &forged, &sign(&s, &other_key, &forged))
+        .is_err());
+    assert_eq!(s.f.client.get_signing_nonce(&s.employee), 0);
+
+    s.f.client.revoke_signing_key(&s.employee);
+    assert_eq!(
+        s.f.client
+            .try_execute_signed_action(&s.relayer, &forged, &sign(&s, &s.key, &forged)),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+}
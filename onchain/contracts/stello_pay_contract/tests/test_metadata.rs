@@ -0,0 +1,98 @@
+#![cfg(test)]
+
+use soroban_sdk::String;
+use stello_pay_contract::{
+    metadata::{MAX_METADATA_ENTRIES, MAX_METADATA_KEY_LEN},
+    storage::PayrollError,
+    testutils::Fixture,
+};
+
+fn s(f: &Fixture, value: &str) -> String {
+    String::from_str(&f.env, value)
+}
+
+#[test]
+fn entries_are_kept_per_employer_and_employee() {
+    let f = Fixture::new();
+    let employee = Some(f.address());
+    f.client
+        .set_employer_metadata(&f.employer, &None, &s(&f, "erp_id"), &s(&f, "V-1001"));
+    f.client.set_employer_metadata(
+        &f.employer,
+        &employee,
+        &s(&f, "cost_center"),
+        &s(&f, "CC-42"),
+    );
+    f.client.set_employer_metadata(
+        &f.employer,
+        &employee,
+        &s(&f, "cost_center"),
+        &s(&f, "CC-43"),
+    );
+
+    assert_eq!(
+        f.client
+            .get_employer_metadata(&f.employer, &None, &s(&f, "erp_id")),
+        Some(s(&f, "V-1001"))
+    );
+    assert_eq!(
+        f.client
+            .get_employer_metadata(&f.employer, &None, &s(&f, "cost_center")),
+        None
+    );
+    let entries = f.client.list_employer_metadata(&f.employer, &employee);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries.get(s(&f, "cost_center")), Some(s(&f, "CC-43")));
+    assert!(f
+        .client
+        .list_employer_metadata(&f.address(), &None)
+        .is_empty());
+
+    f.client
+        .remove_employer_metadata(&f.employer, &employee, &s(&f, "cost_center"));
+    f.client
+        .remove_employer_metadata(&f.employer, &employee, &s(&f, "missing"));
+    assert!(f
+        .client
+        .list_employer_metadata(&f.employer, &employee)
+        .is_empty());
+}
+
+#[test]
+fn keys_values_and_entry_count_are_bounded() {
+    let f = Fixture::new();
+    let long_key = "k".repeat(MAX_METADATA_KEY_LEN as usize + 1);
+    for (key, value) in [("", "v"), (long_key.as_str(), "v"), ("k", &"v".repeat(257))] {
+        assert_eq!(
+            f.client
+                .try_set_employer_metadata(&f.employer, &None, &s(&f, key), &s(&f, value)),
+            Err(Ok(PayrollError::InvalidData))
+        );
+    }
+
+    for i in 0..MAX_METADATA_ENTRIES {
+        f.client.set_employer_metadata(
+            &f.employer,
+            &None,
+            &s(&f, &format!("key_{i}")),
+            &s(&f, "v"),
+        );
+    }
+    assert_eq!(
+        f.client
+            .try_set_employer_metadata(&f.employer, &None, &s(&f, "one_more"), &s(&f, "v")),
+        Err(Ok(PayrollError::BatchTooLarge))
+    );
+    // Overwriting an existing key does not count against the cap.
+    f.client
+        .set_employer_metadata(&f.employer, &None, &s(&f, "key_0"), &s(&f, "w"));
+}
+
+#[test]
+#[should_panic(expected = "HostError")]
+fn only_the_employer_can_write_its_metadata() {
+    let f = Fixture::new();
+    f.env.mock_auths(&[]);
+    f.client
+        .set_employer_metadata(&f.employer, &None, &s(&f, "erp_id"), &s(&f, "V-1"));
+}
@@ -0,0 +1,118 @@
+#![cfg(test)]
+
+use soroban_sdk::Address;
+use stello_pay_contract::{
+    offers::OfferStatus,
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+const BONUS: i128 = 5_000;
+
+/// Extends an offer for a new, not yet activated payroll agreement.
+fn offer(f: &Fixture) -> (u128, Address, u64) {
+    let candidate = f.address();
+    let id = f
+        .client
+        .create_payroll_agreement(&f.employer, &f.token.address, &DAY);
+    f.token.mint(&f.employer, BONUS);
+    let accept_by = f.env.ledger().timestamp() + 7 * DAY;
+    let offer_id = f
+        .client
+        .extend_offer(&f.employer, &candidate, &id, &BONUS, &accept_by);
+    (id, candidate, offer_id)
+}
+
+#[test]
+fn bonus_is_claimable_once_the_candidate_is_onboarded() {
+    let f = Fixture::new();
+    let (id, candidate, offer_id) = offer(&f);
+    assert_eq!(f.token.balance(&f.employer), 0);
+    f.client.accept_offer(&candidate, &offer_id);
+    assert_eq!(
+        f.client.try_claim_offer_bonus(&candidate, &offer_id),
+        Err(Ok(PayrollError::AgreementNotActivated))
+    );
+
+    f.client.add_employee_to_agreement(&id, &candidate, &1_000);
+    f.client.activate_agreement(&id);
+    assert_eq!(f.client.claim_offer_bonus(&candidate, &offer_id), BONUS);
+    assert_eq!(f.token.balance(&candidate), BONUS);
+    assert_eq!(
+        f.client.get_offer(&offer_id).unwrap().status,
+        OfferStatus::Claimed
+    );
+    assert_eq!(
+        f.client.try_refund_offer(&offer_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn lapsed_offer_refunds_the_employer() {
+    let f = Fixture::new();
+    let (_, candidate, offer_id) = offer(&f);
+    assert_eq!(
+        f.client.try_refund_offer(&offer_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    f.warp(7 * DAY + 1);
+    assert_eq!(
+        f.client.try_accept_offer(&candidate, &offer_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(f.client.refund_offer(&offer_id), BONUS);
+    assert_eq!(f.token.balance(&f.employer), BONUS);
+    assert_eq!(
+        f.client.get_offer(&offer_id).unwrap().status,
+        OfferStatus::Refunded
+    );
+}
+
+#[test]
+fn accepted_offer_refunds_when_the_agreement_is_cancelled() {
+    let f = Fixture::new();
+    let (id, candidate, offer_id) = offer(&f);
+    f.client.accept_offer(&candidate, &offer_id);
+    // Acceptance keeps the bonus locked past the deadline.
+    f.warp(30 * DAY);
+    assert_eq!(
+        f.client.try_refund_offer(&offer_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    f.client.cancel_agreement(&id);
+    assert_eq!(f.client.refund_offer(&offer_id), BONUS);
+    assert_eq!(f.token.balance(&f.employer), BONUS);
+}
+
+#[test]
+fn offers_are_checked_against_the_agreement_and_candidate() {
+    let f = Fixture::new();
+    let (id, candidate, offer_id) = offer(&f);
+    let now = f.env.ledger().timestamp();
+    assert_eq!(
+        f.client
+            .try_extend_offer(&f.address(), &candidate, &id, &BONUS, &(now + DAY)),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        f.client
+            .try_extend_offer(&f.employer, &candidate, &id, &BONUS, &now),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client
+            .try_extend_offer(&f.employer, &candidate, &id, &0, &(now + DAY)),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client.try_accept_offer(&f.address(), &offer_id),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        f.client.try_claim_offer_bonus(&candidate, &offer_id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
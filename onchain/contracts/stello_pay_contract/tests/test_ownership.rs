@@ -0,0 +1,176 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    contract, contractimpl, contracttype,
+    testutils::{Address as _, Ledger},
+    vec, Address, BytesN, Env,
+};
+use stello_pay_contract::{
+    ownership::{OperatingMode, GUARDIAN_MAX_PAUSE_SECONDS},
+    storage::{PayrollError, StorageKey},
+    PayrollContract, PayrollContractClient,
+};
+
+const NEW_CONTRACT_WASM: &[u8] = include_bytes!("./stello_pay_contract.wasm");
+
+#[contracttype]
+#[derive(Clone)]
+enum MockGovernanceKey {
+    ApprovedUpgrade(Address),
+}
+
+/// Stand-in for the governance contract's upgrade approvals.
+#[contract]
+pub struct MockGovernance;
+
+#[contractimpl]
+impl MockGovernance {
+    pub fn approve_upgrade(env: Env, target: Address, wasm_hash: BytesN<32>) {
+        env.storage()
+            .persistent()
+            .set(&MockGovernanceKey::ApprovedUpgrade(target), &wasm_hash);
+    }
+
+    pub fn get_approved_upgrade(env: Env, target: Address) -> Option<BytesN<32>> {
+        env.storage()
+            .persistent()
+            .get(&MockGovernanceKey::ApprovedUpgrade(target))
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: PayrollContractClient<'static>,
+    governance: MockGovernanceClient<'static>,
+    owner: Address,
+    guardian: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(PayrollContract, ());
+    let client = PayrollContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    client.initialize(&owner);
+    let governance_id = env.register(MockGovernance, ());
+    let governance = MockGovernanceClient::new(&env, &governance_id);
+    let guardian = Address::generate(&env);
+    Setup {
+        env,
+        client,
+        governance,
+        owner,
+        guardian,
+    }
+}
+
+#[test]
+fn renouncing_removes_the_owner_for_good() {
+    let s = setup();
+    assert_eq!(s.client.get_ownership_info().mode, OperatingMode::Owned);
+    assert_eq!(
+        s.client.try_renounce_ownership(&s.guardian, &s.guardian),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+
+    s.env.ledger().with_mut(|l| l.timestamp = 1_000);
+    s.client
+        .set_upgrade_governance(&s.owner, &s.governance.address);
+    s.client.renounce_ownership(&s.owner, &s.guardian);
+    let info = s.client.get_ownership_info();
+    assert_eq!(info.mode, OperatingMode::Guarded);
+    assert_eq!(info.owner, None);
+    assert_eq!(info.guardian, Some(s.guardian.clone()));
+    assert_eq!(info.former_owner, Some(s.owner.clone()));
+    assert_eq!(info.renounced_at, Some(1_000));
+    assert_eq!(info.upgrade_governance, Some(s.governance.address.clone()));
+
+    // Owner-gated calls fail and nobody can take over
+    assert_eq!(
+        s.client
+            .try_set_upgrade_governance(&s.owner, &s.governance.address),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        s.client.try_renounce_ownership(&s.owner, &s.owner),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert!(s.client.try_emergency_pause().is_err());
+    assert!(s.client.try_initialize(&s.guardian).is_err());
+}
+
+#[test]
+fn guardian_can_only_pause_for_a_limited_time() {
+    let s = setup();
+    assert_eq!(
+        s.client.try_guardian_pause(&s.guardian, &3_600, &None),
+        Err(Ok(PayrollError::NotGuardian))
+    );
+    s.client.renounce_ownership(&s.owner, &s.guardian);
+
+    assert_eq!(
+        s.client.try_guardian_pause(&s.owner, &3_600, &None),
+        Err(Ok(PayrollError::NotGuardian))
+    );
+    assert_eq!(
+        s.client
+            .try_guardian_pause(&s.guardian, &(GUARDIAN_MAX_PAUSE_SECONDS + 1), &None),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        s.client.try_guardian_pause(&s.guardian, &0, &None),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    s.client.guardian_pause(&s.guardian, &3_600, &None);
+    let pause = s.client.get_pause_info();
+    assert!(pause.is_paused);
+    assert_eq!(pause.paused_by, Some(s.guardian.clone()));
+    assert_eq!(pause.resume_at, Some(s.env.ledger().timestamp() + 3_600));
+
+    // The guardian also lifts open-ended multisig pauses
+    let signers = vec![&s.env, Address::generate(&s.env), Address::generate(&s.env)];
+    s.env.as_contract(&s.client.address, || {
+        s.env
+            .storage()
+            .persistent()
+            .set(&StorageKey::EmergencyGuardians, &signers);
+    });
+    s.client
+        .propose_emergency_pause(&signers.get(0).unwrap(), &0);
+    s.client.approve_emergency_pause(&signers.get(1).unwrap());
+    assert_eq!(s.client.get_pause_info().resume_at, None);
+    s.client.guardian_unpause(&s.guardian);
+    assert!(!s.client.is_emergency_paused());
+}
+
+#[test]
+fn guardian_upgrades_only_to_ratified_hashes() {
+    let s = setup();
+    let wasm_hash = s.env.deployer().upload_contract_wasm(NEW_CONTRACT_WASM);
+    assert_eq!(
+        s.client.try_ratify_upgrade(&wasm_hash),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    s.client
+        .set_upgrade_governance(&s.owner, &s.governance.address);
+    assert_eq!(
+        s.client.try_ratify_upgrade(&wasm_hash),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    s.governance.approve_upgrade(&s.client.address, &wasm_hash);
+    s.client.ratify_upgrade(&wasm_hash);
+    s.client.ratify_upgrade(&wasm_hash);
+    assert_eq!(
+        s.client.get_ratified_upgrades(),
+        vec![&s.env, wasm_hash.clone()]
+    );
+
+    s.client.renounce_ownership(&s.owner, &s.guardian);
+    let unratified = BytesN::from_array(&s.env, &[7; 32]);
+    assert!(s.client.try_upgrade(&wasm_hash, &s.owner).is_err());
+    assert!(s.client.try_upgrade(&unratified, &s.guardian).is_err());
+
+    s.client.upgrade(&wasm_hash, &s.guardian);
+}
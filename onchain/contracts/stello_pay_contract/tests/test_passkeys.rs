@@ -0,0 +1,192 @@
+#![cfg(test)]
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use sha2::{Digest, Sha256};
+use soroban_sdk::{Address, Bytes, BytesN};
+use stello_pay_contract::{
+    meta_tx::{SignedAction, SignedRequest},
+    reimbursements::ReimbursementStatus,
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+/// WebAuthn flags: user present and user verified.
+const FLAGS_UP_UV: u8 = 0x05;
+
+fn passkey(f: &Fixture, user: &Address, seed: u8) -> SigningKey {
+    let key = SigningKey::from_bytes(&[seed; 32].into()).unwrap();
+    let point = key.verifying_key().to_encoded_point(false);
+    f.client.register_passkey(
+        user,
+        &BytesN::from_array(&f.env, point.as_bytes().try_into().unwrap()),
+    );
+    key
+}
+
+fn request(f: &Fixture, signer: &Address, action: SignedAction, nonce: u64) -> SignedRequest {
+    SignedRequest {
+        employee: signer.clone(),
+        action,
+        nonce,
+        expiry: f.env.ledger().timestamp() + DAY,
+    }
+}
+
+/// The authenticator's response to a `navigator.credentials.get()` with
+/// `challenge`: authenticator data, client data JSON and a low-S signature.
+fn assert_with(
+    f: &Fixture,
+    key: &SigningKey,
+    challenge: &[u8; 32],
+    flags: u8,
+) -> (Bytes, Bytes, BytesN<64>) {
+    let mut authenticator_data = [0xAA; 37].to_vec();
+    authenticator_data[32] = flags;
+    authenticator_data[36] = 1;
+
+    let mut encoded = [0u8; 43];
+    URL_SAFE_NO_PAD
+        .encode_slice(challenge, &mut encoded)
+        .unwrap();
+    let client_data = format!(
+        r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://app.stellopay.example","crossOrigin":false}}"#,
+        core::str::from_utf8(&encoded).unwrap()
+    );
+
+    let mut signed = authenticator_data.clone();
+    signed.extend_from_slice(&Sha256::digest(client_data.as_bytes()));
+    let signature: Signature = key.sign(&signed);
+    let signature = signature.normalize_s().unwrap_or(signature);
+
+    (
+        Bytes::from_slice(&f.env, &authenticator_data),
+        Bytes::from_slice(&f.env, client_data.as_bytes()),
+        BytesN::from_array(&f.env, &signature.to_bytes().into()),
+    )
+}
+
+fn assertion(f: &Fixture, key: &SigningKey, request: &SignedRequest) -> (Bytes, Bytes, BytesN<64>) {
+    let challenge = f.client.passkey_challenge(request).to_array();
+    assert_with(f, key, &challenge, FLAGS_UP_UV)
+}
+
+#[test]
+fn employee_claims_with_a_passkey_once() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let relayer = f.address();
+    let id = f.payroll().employee(&employee, 1_000).build();
+    let key = passkey(&f, &employee, 7);
+    f.warp(2 * DAY);
+
+    let claim = request(&f, &employee, SignedAction::ClaimPayroll(id, 0), 0);
+    let (auth_data, client_data, signature) = assertion(&f, &key, &claim);
+    f.client
+        .execute_passkey_action(&relayer, &claim, &auth_data, &client_data, &signature);
+    assert!(f.env.auths().iter().all(|(addr, _)| *addr == relayer));
+    assert_eq!(f.token.balance(&employee), 2_000);
+    assert_eq!(f.client.get_signing_nonce(&employee), 1);
+
+    assert_eq!(
+        f.client
+            .try_execute_passkey_action(&relayer, &claim, &auth_data, &client_data, &signature),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn approver_approves_a_reimbursement_with_a_passkey() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let approver = f.address();
+    f.agreement(&employee).build();
+    f.token.mint(&f.employer, 1_000);
+    f.client
+        .fund_reimbursement_pool(&f.employer, &f.token.address, &1_000);
+    f.client
+        .set_reimbursement_approver(&f.employer, &approver, &true);
+    let request_id = f.client.submit_reimbursement(
+        &employee,
+        &300,
+        &f.token.address,
+        &BytesN::from_array(&f.env, &[1; 32]),
+    );
+
+    let key = passkey(&f, &approver, 3);
+    let approval = request(
+        &f,
+        &approver,
+        SignedAction::ApproveReimbursement(f.employer.clone(), request_id),
+        0,
+    );
+    let (auth_data, client_data, signature) = assertion(&f, &key, &approval);
+    f.client.execute_passkey_action(
+        &f.address(),
+        &approval,
+        &auth_data,
+        &client_data,
+        &signature,
+    );
+
+    let reimbursement = f.client.get_reimbursement(&request_id).unwrap();
+    assert_eq!(reimbursement.status, ReimbursementStatus::Paid);
+    assert_eq!(reimbursement.decided_by, Some(approver));
+    assert_eq!(f.token.balance(&employee), 300);
+}
+
+#[test]
+fn assertions_must_match_the_request_and_the_user() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let relayer = f.address();
+    let key = passkey(&f, &employee, 7);
+    let plan = request(&f, &employee, SignedAction::SetSavingsPlan(1_000, DAY), 0);
+
+    // Signed over another request's challenge
+    let other = request(&f, &employee, SignedAction::CancelSavingsPlan, 0);
+    let (auth_data, client_data, signature) = assertion(&f, &key, &other);
+    assert_eq!(
+        f.client
+            .try_execute_passkey_action(&relayer, &plan, &auth_data, &client_data, &signature),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    // The user was not present
+    let challenge = f.client.passkey_challenge(&plan).to_array();
+    let (auth_data, client_data, signature) = assert_with(&f, &key, &challenge, 0x04);
+    assert_eq!(
+        f.client
+            .try_execute_passkey_action(&relayer, &plan, &auth_data, &client_data, &signature),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    // Signed by another key: the call aborts and the nonce is kept
+    let other_key = SigningKey::from_bytes(&[9; 32].into()).unwrap();
+    let (auth_data, client_data, signature) = assertion(&f, &other_key, &plan);
+    assert!(f
+        .client
+        .try_execute_passkey_action(&relayer, &plan, &auth_data, &client_data, &signature)
+        .is_err());
+    assert_eq!(f.client.get_signing_nonce(&employee), 0);
+
+    f.client.revoke_passkey(&employee);
+    let (auth_data, client_data, signature) = assertion(&f, &key, &plan);
+    assert_eq!(
+        f.client
+            .try_execute_passkey_action(&relayer, &plan, &auth_data, &client_data, &signature),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+}
+
+#[test]
+fn passkeys_must_be_uncompressed() {
+    let f = Fixture::new();
+    let mut compressed = [0u8; 65];
+    compressed[0] = 0x02;
+    assert_eq!(
+        f.client
+            .try_register_passkey(&f.address(), &BytesN::from_array(&f.env, &compressed)),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
@@ -0,0 +1,59 @@
+#![cfg(test)]
+
+use soroban_sdk::vec;
+use stello_pay_contract::{
+    pause_exemptions::PauseCategory,
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+#[test]
+fn exempt_categories_keep_running_while_paused() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let contributor = f.address();
+    let payroll = f.payroll().employee(&employee, 100).build();
+    let escrow = f.agreement(&contributor).build();
+
+    assert_eq!(
+        f.client
+            .try_set_pause_exemptions(&f.address(), &vec![&f.env, PauseCategory::PayrollClaims]),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    f.client.set_pause_exemptions(
+        &f.owner,
+        &vec![
+            &f.env,
+            PauseCategory::PayrollClaims,
+            PauseCategory::PayrollClaims,
+        ],
+    );
+    assert_eq!(
+        f.client.get_pause_exemptions(),
+        vec![&f.env, PauseCategory::PayrollClaims]
+    );
+    // Exemptions only matter while paused
+    assert!(!f.client.is_paused_for(&PauseCategory::EscrowClaims));
+
+    f.client.emergency_pause();
+    f.warp(DAY);
+    assert!(!f.client.is_paused_for(&PauseCategory::PayrollClaims));
+    assert!(f.client.is_paused_for(&PauseCategory::EscrowClaims));
+
+    // Employees keep access to what they have earned
+    f.client.claim_payroll(&employee, &payroll, &0);
+    assert_eq!(f.token.balance(&employee), 100);
+    assert_eq!(
+        f.client.try_claim_time_based(&escrow),
+        Err(Ok(PayrollError::EmergencyPaused))
+    );
+
+    // Clearing the list makes the pause stop everything again
+    f.client.set_pause_exemptions(&f.owner, &vec![&f.env]);
+    assert!(f.client.get_pause_exemptions().is_empty());
+    f.warp(DAY);
+    assert_eq!(
+        f.client.try_claim_payroll(&employee, &payroll, &0),
+        Err(Ok(PayrollError::EmergencyPaused))
+    );
+}
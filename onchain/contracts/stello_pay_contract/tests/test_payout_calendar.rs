@@ -0,0 +1,114 @@
+#![cfg(test)]
+
+use stello_pay_contract::{
+    payout_calendar::PayoutCalendarEntry,
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+fn periods(calendar: &soroban_sdk::Vec<PayoutCalendarEntry>, agreement_id: u128) -> Vec<u32> {
+    calendar
+        .iter()
+        .filter(|entry| entry.agreement_id == agreement_id)
+        .map(|entry| entry.period)
+        .collect()
+}
+
+#[test]
+fn calendar_projects_unclaimed_periods_in_payout_order() {
+    let f = Fixture::new();
+    let start = f.env.ledger().timestamp();
+    let alice = f.address();
+    let bob = f.address();
+    let contributor = f.address();
+    let payroll = f
+        .payroll()
+        .employee(&alice, 100)
+        .employee(&bob, 200)
+        .build();
+    let escrow = f.agreement(&contributor).build();
+
+    let calendar = f
+        .client
+        .get_payout_calendar(&f.employer, &start, &(start + 2 * DAY + 1));
+    let expected = [
+        (payroll, &alice, 1, 100),
+        (payroll, &bob, 1, 200),
+        (escrow, &contributor, 1, 1_000),
+        (payroll, &alice, 2, 100),
+        (payroll, &bob, 2, 200),
+        (escrow, &contributor, 2, 1_000),
+    ];
+    assert_eq!(calendar.len(), expected.len() as u32);
+    for (entry, (agreement_id, payee, period, amount)) in calendar.iter().zip(expected) {
+        let due_at = start + u64::from(period) * DAY;
+        assert_eq!(
+            entry,
+            PayoutCalendarEntry {
+                agreement_id,
+                employee: payee.clone(),
+                token: f.token.address.clone(),
+                period,
+                due_at,
+                payable_at: due_at,
+                amount,
+            }
+        );
+    }
+
+    // Blackouts push payouts back without moving their due date
+    let reopens = start + 2 * DAY + 3_600;
+    f.client
+        .add_blackout(&f.employer, &(start + 2 * DAY - 60), &reopens);
+    let calendar =
+        f.client
+            .get_payout_calendar(&f.employer, &(start + 2 * DAY), &(start + 3 * DAY));
+    assert_eq!(calendar.len(), 3);
+    assert!(calendar
+        .iter()
+        .all(|entry| entry.due_at == start + 2 * DAY && entry.payable_at == reopens));
+
+    // Claimed periods and paused agreements drop out; escrows end after their last period
+    f.warp(DAY);
+    f.client.claim_payroll(&alice, &payroll, &0);
+    f.client.pause_agreement(&escrow);
+    let calendar = f
+        .client
+        .get_payout_calendar(&f.employer, &start, &(start + 5 * DAY));
+    assert!(periods(&calendar, escrow).is_empty());
+    assert!(!calendar
+        .iter()
+        .any(|entry| entry.employee == alice && entry.period == 1));
+    assert_eq!(calendar.len(), 3 + 4);
+
+    f.client.resume_agreement(&escrow);
+    let calendar = f
+        .client
+        .get_payout_calendar(&f.employer, &start, &(start + 10 * DAY));
+    assert_eq!(periods(&calendar, escrow), [1, 2, 3, 4]);
+}
+
+#[test]
+fn calendar_rejects_empty_and_oversized_windows() {
+    let f = Fixture::new();
+    let start = f.env.ledger().timestamp();
+    f.payroll()
+        .employee(&f.address(), 100)
+        .employee(&f.address(), 100)
+        .build();
+
+    assert_eq!(
+        f.client
+            .try_get_payout_calendar(&f.employer, &start, &start),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client
+            .try_get_payout_calendar(&f.employer, &start, &(start + 100 * DAY)),
+        Err(Ok(PayrollError::BatchTooLarge))
+    );
+    assert!(f
+        .client
+        .get_payout_calendar(&f.address(), &start, &(start + 100 * DAY))
+        .is_empty());
+}
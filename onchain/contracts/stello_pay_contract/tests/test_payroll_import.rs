@@ -0,0 +1,174 @@
+#![cfg(test)]
+
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{contract, contractimpl, contracttype, vec, Address, BytesN, Env, Vec};
+use stello_pay_contract::{
+    credentials::CredentialRole,
+    payroll_import::{ImportedHistory, ImportedRecord},
+    storage::{AgreementStatus, PayrollError},
+    testutils::{Fixture, DAY},
+};
+
+#[contracttype]
+#[derive(Clone)]
+enum MockRegistryKey {
+    Valid(Address, CredentialRole),
+}
+
+/// Stand-in for the governance contract's credential queries.
+#[contract]
+pub struct MockCredentialRegistry;
+
+#[contractimpl]
+impl MockCredentialRegistry {
+    pub fn set_valid(env: Env, holder: Address, role: CredentialRole, valid: bool) {
+        env.storage()
+            .persistent()
+            .set(&MockRegistryKey::Valid(holder, role), &valid);
+    }
+
+    pub fn has_valid_credential(env: Env, holder: Address, role: CredentialRole) -> bool {
+        env.storage()
+            .persistent()
+            .get(&MockRegistryKey::Valid(holder, role))
+            .unwrap_or(false)
+    }
+}
+
+fn record(f: &Fixture, employee: &Address, salary: i128, last_paid_at: u64) -> ImportedRecord {
+    ImportedRecord {
+        employee: employee.clone(),
+        token: f.token.address.clone(),
+        salary_per_period: salary,
+        period_seconds: DAY,
+        last_paid_at,
+        last_paid_amount: salary,
+        paid_to_date: 12 * salary,
+    }
+}
+
+fn sign(f: &Fixture, key: &SigningKey, records: &Vec<ImportedRecord>) -> BytesN<64> {
+    let payload = f.client.import_payload(&f.employer, records).to_alloc_vec();
+    BytesN::from_array(&f.env, &key.sign(&payload).to_bytes())
+}
+
+#[test]
+fn attested_exports_import_payrolls_from_their_last_payment() {
+    let f = Fixture::new();
+    let now = f.env.ledger().timestamp();
+    let registry =
+        MockCredentialRegistryClient::new(&f.env, &f.env.register(MockCredentialRegistry, ()));
+    let attestor = f.address();
+    let key = SigningKey::from_bytes(&[9; 32]);
+    f.client.register_signing_key(
+        &attestor,
+        &BytesN::from_array(&f.env, &key.verifying_key().to_bytes()),
+    );
+    let alice = f.address();
+    let bob = f.address();
+    let records = vec![
+        &f.env,
+        record(&f, &alice, 100, now - DAY - DAY / 2),
+        record(&f, &bob, 250, now - DAY / 4),
+    ];
+    let signature = sign(&f, &key, &records);
+
+    // Only credentialed attestors are trusted, and only once a registry is linked
+    assert_eq!(
+        f.client
+            .try_import_payroll_records(&f.employer, &attestor, &records, &signature),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    f.client
+        .set_credential_registry(&f.owner, &registry.address);
+    assert_eq!(
+        f.client
+            .try_import_payroll_records(&f.employer, &attestor, &records, &signature),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    registry.set_valid(&attestor, &CredentialRole::PayrollAttestor, &true);
+
+    let ids = f
+        .client
+        .import_payroll_records(&f.employer, &attestor, &records, &signature);
+    assert_eq!(ids.len(), 2);
+    let alice_payroll = f.client.get_payroll(&alice, &ids.get(0).unwrap()).unwrap();
+    assert_eq!(alice_payroll.status, AgreementStatus::Active);
+    assert_eq!(alice_payroll.activated_at, now - DAY - DAY / 2);
+    assert_eq!(
+        f.client.get_imported_history(&ids.get(1).unwrap()),
+        Some(ImportedHistory {
+            attestor: attestor.clone(),
+            imported_at: now,
+            last_paid_at: now - DAY / 4,
+            last_paid_amount: 250,
+            paid_to_date: 3_000,
+        })
+    );
+
+    // The pay schedule carries on from the last payment
+    let roster = vec![&f.env, alice.clone(), bob.clone()];
+    assert_eq!(
+        f.client.get_claimable_balances(&roster),
+        vec![&f.env, 100, 0]
+    );
+    assert_eq!(
+        f.client.get_next_payout_times(&roster),
+        vec![&f.env, Some(now + DAY / 2), Some(now + 3 * DAY / 4)]
+    );
+
+    // An export cannot be imported twice
+    assert_eq!(
+        f.client
+            .try_import_payroll_records(&f.employer, &attestor, &records, &signature),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn imports_reject_invalid_records() {
+    let f = Fixture::new();
+    let now = f.env.ledger().timestamp();
+    let registry =
+        MockCredentialRegistryClient::new(&f.env, &f.env.register(MockCredentialRegistry, ()));
+    f.client
+        .set_credential_registry(&f.owner, &registry.address);
+    let attestor = f.address();
+    let key = SigningKey::from_bytes(&[9; 32]);
+    f.client.register_signing_key(
+        &attestor,
+        &BytesN::from_array(&f.env, &key.verifying_key().to_bytes()),
+    );
+    registry.set_valid(&attestor, &CredentialRole::PayrollAttestor, &true);
+
+    let employee = f.address();
+    let mut future = record(&f, &employee, 100, now + 1);
+    let mut overpaid = record(&f, &employee, 100, now);
+    overpaid.last_paid_amount = overpaid.paid_to_date + 1;
+    for invalid in [
+        vec![&f.env],
+        vec![&f.env, future.clone()],
+        vec![&f.env, overpaid],
+    ] {
+        assert_eq!(
+            f.client.try_import_payroll_records(
+                &f.employer,
+                &attestor,
+                &invalid,
+                &sign(&f, &key, &invalid)
+            ),
+            Err(Ok(PayrollError::InvalidData))
+        );
+    }
+
+    // A signature over other records is rejected
+    future.last_paid_at = now;
+    let records = vec![&f.env, future.clone()];
+    future.salary_per_period = 1_000;
+    let tampered = vec![&f.env, future];
+    assert!(f
+        .client
+        .try_import_payroll_records(&f.employer, &attestor, &tampered, &sign(&f, &key, &records))
+        .is_err());
+    assert!(f.client.get_employee_agreements(&employee).is_empty());
+}
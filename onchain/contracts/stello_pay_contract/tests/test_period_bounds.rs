@@ -0,0 +1,175 @@
+#![cfg(test)]
+
+use proptest::prelude::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env,
+};
+use stello_pay_contract::backup::{deserialize_agreement, serialize_agreement};
+use stello_pay_contract::storage::{
+    Agreement, AgreementMode, AgreementStatus, DataKey, DisputeStatus, PayrollError, StorageKey,
+    MAX_PERIOD_SECONDS,
+};
+use stello_pay_contract::{PayrollContract, PayrollContractClient};
+
+fn setup() -> (Env, PayrollContractClient<'static>) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PayrollContract, ());
+    let client = PayrollContractClient::new(&env, &contract_id);
+    client.initialize(&Address::generate(&env));
+
+    (env, client)
+}
+
+#[test]
+fn escrow_rejects_period_longer_than_u32_seconds() {
+    let (env, client) = setup();
+    let employer = Address::generate(&env);
+    let contributor = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    let result = client.try_create_escrow_agreement(
+        &employer,
+        &contributor,
+        &token,
+        &100,
+        &(MAX_PERIOD_SECONDS + 1),
+        &1,
+    );
+    assert_eq!(result, Err(Ok(PayrollError::InvalidData)));
+
+    let id = client.create_escrow_agreement(
+        &employer,
+        &contributor,
+        &token,
+        &100,
+        &MAX_PERIOD_SECONDS,
+        &u32::MAX,
+    );
+    let agreement = client.get_agreement(&id).unwrap();
+    assert_eq!(agreement.period_seconds, Some(MAX_PERIOD_SECONDS));
+    assert_eq!(
+        agreement.grace_period_seconds,
+        MAX_PERIOD_SECONDS * u32::MAX as u64
+    );
+}
+
+#[test]
+fn escrow_rejects_total_amount_overflow() {
+    let (env, client) = setup();
+
+    let result = client.try_create_escrow_agreement(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &i128::MAX,
+        &3600,
+        &2,
+    );
+    assert_eq!(result, Err(Ok(PayrollError::InvalidData)));
+}
+
+#[test]
+fn elapsed_periods_saturate_instead_of_wrapping() {
+    let (env, client) = setup();
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let agreement_id = 1u128;
+    let claimed = u32::MAX - 2;
+
+    env.as_contract(&client.address, || {
+        let agreement = Agreement {
+            id: agreement_id,
+            employer: employer.clone(),
+            token: token.clone(),
+            mode: AgreementMode::Payroll,
+            status: AgreementStatus::Active,
+            total_amount: 1,
+            paid_amount: 0,
+            created_at: 0,
+            activated_at: Some(0),
+            cancelled_at: None,
+            grace_period_seconds: 0,
+            amount_per_period: None,
+            period_seconds: Some(1),
+            num_periods: None,
+            claimed_periods: None,
+            dispute_raised_at: None,
+            dispute_status: DisputeStatus::None,
+        };
+        env.storage()
+            .persistent()
+            .set(&StorageKey::Agreement(agreement_id), &agreement);
+        DataKey::set_employee_count(&env, agreement_id, 1);
+        DataKey::set_agreement_activation_time(&env, agreement_id, 0);
+        DataKey::set_agreement_period_duration(&env, agreement_id, 1);
+        DataKey::set_agreement_token(&env, agreement_id, &token);
+        DataKey::set_agreement_escrow_balance(&env, agreement_id, &token, 10);
+        DataKey::set_employee(&env, agreement_id, 0, &employee);
+        DataKey::set_employee_salary(&env, agreement_id, 0, 1);
+        DataKey::set_employee_claimed_periods(&env, agreement_id, 0, claimed);
+    });
+    StellarAssetClient::new(&env, &token).mint(&client.address, &10);
+
+    // 2^32 + 5 one-second periods: a wrapping cast would report 5 elapsed
+    // periods, below the claimed count, and lock the employee out.
+    env.ledger().with_mut(|l| l.timestamp = u32::MAX as u64 + 6);
+    client.claim_payroll(&employee, &agreement_id, &0);
+
+    assert_eq!(TokenClient::new(&env, &token).balance(&employee), 2);
+    assert_eq!(
+        client.get_employee_claimed_periods(&agreement_id, &0),
+        u32::MAX
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    /// Property: serialising an agreement and reading it back preserves every
+    /// period-related field bit for bit, including values at the type limits.
+    #[test]
+    fn prop_period_fields_round_trip(
+        period_seconds in prop::option::of(any::<u64>()),
+        num_periods in prop::option::of(any::<u32>()),
+        claimed_periods in prop::option::of(any::<u32>()),
+        grace_period_seconds in any::<u64>(),
+        activated_at in prop::option::of(any::<u64>()),
+    ) {
+        let env = Env::default();
+        let agreement = Agreement {
+            id: 7,
+            employer: Address::generate(&env),
+            token: Address::generate(&env),
+            mode: AgreementMode::Escrow,
+            status: AgreementStatus::Active,
+            total_amount: 1_000,
+            paid_amount: 0,
+            created_at: 1,
+            activated_at,
+            cancelled_at: None,
+            grace_period_seconds,
+            dispute_status: DisputeStatus::None,
+            dispute_raised_at: None,
+            amount_per_period: Some(10),
+            period_seconds,
+            num_periods,
+            claimed_periods,
+        };
+
+        let bytes = serialize_agreement(&env, &agreement);
+        let restored = deserialize_agreement(&env, &bytes).unwrap();
+
+        prop_assert_eq!(restored.period_seconds, period_seconds);
+        prop_assert_eq!(restored.num_periods, num_periods);
+        prop_assert_eq!(restored.claimed_periods, claimed_periods);
+        prop_assert_eq!(restored.grace_period_seconds, grace_period_seconds);
+        prop_assert_eq!(restored.activated_at, activated_at);
+    }
+}
@@ -0,0 +1,55 @@
+#![cfg(test)]
+
+use soroban_sdk::{map, Map};
+use stello_pay_contract::{
+    stats::ProtocolStats,
+    testutils::{Fixture, DAY},
+};
+
+#[test]
+fn totals_follow_agreements_and_payouts() {
+    let f = Fixture::new();
+    assert_eq!(
+        f.client.get_protocol_stats(),
+        ProtocolStats {
+            total_employers: 0,
+            active_payrolls: 0,
+            disbursed: Map::new(&f.env),
+            incentives_claimed: Some(0),
+        }
+    );
+
+    let employee = f.address();
+    let payroll = f
+        .payroll()
+        .employee(&employee, 100)
+        .funded_periods(10)
+        .build();
+    f.payroll().employee(&f.address(), 100).build();
+    let other_employer = f.address();
+    let escrow = f.agreement(&f.address()).employer(&other_employer).build();
+    f.agreement(&f.address())
+        .employer(&other_employer)
+        .inactive()
+        .build();
+
+    f.warp(2 * DAY);
+    f.client.claim_payroll(&employee, &payroll, &0);
+    let bonus = f.incentive(&employee).amount(300).build();
+    f.bonus.claim_incentive(&employee, &bonus);
+
+    let stats = f.client.get_protocol_stats();
+    assert_eq!((stats.total_employers, stats.active_payrolls), (2, 3));
+    assert_eq!(
+        stats.disbursed,
+        map![&f.env, (f.token.address.clone(), 200)]
+    );
+    assert_eq!(stats.incentives_claimed, Some(1));
+
+    // Pausing, resuming and cancelling move agreements in and out of the count
+    f.client.pause_agreement(&payroll);
+    f.client.cancel_agreement(&escrow);
+    assert_eq!(f.client.get_protocol_stats().active_payrolls, 1);
+    f.client.resume_agreement(&payroll);
+    assert_eq!(f.client.get_protocol_stats().active_payrolls, 2);
+}
@@ -0,0 +1,65 @@
+#![cfg(test)]
+
+use soroban_sdk::BytesN;
+use stello_pay_contract::{
+    audit::AuditEvent,
+    receipts::{receipt_hash, DisbursementReceipt},
+    testutils::{Fixture, DAY},
+};
+
+#[test]
+fn payouts_get_receipts_that_resolve_to_their_audit_record() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let payroll = f.payroll().employee(&employee, 100).build();
+
+    f.warp(3 * DAY);
+    f.client.claim_payroll(&employee, &payroll, &0);
+    let receipt = f
+        .client
+        .get_payslip(&employee, &1)
+        .unwrap()
+        .receipt
+        .unwrap();
+
+    let record = f.client.verify_receipt(&receipt).unwrap();
+    assert_eq!(record.event, AuditEvent::PayoutDisbursed);
+    assert_eq!(record.actor, f.employer);
+    assert_eq!(record.agreement_id, payroll);
+    assert_eq!(record.subject, Some(employee.clone()));
+    assert_eq!(record.amount, Some(300));
+
+    // A third party holding the payout details can recompute the hash
+    assert_eq!(
+        receipt_hash(
+            &f.env,
+            &DisbursementReceipt {
+                employer: f.employer.clone(),
+                employee: employee.clone(),
+                token: f.token.address.clone(),
+                amount: 300,
+                period: 3,
+                audit_id: record.id,
+            }
+        ),
+        receipt
+    );
+
+    // Each payout gets its own receipt
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &payroll, &0);
+    let next = f
+        .client
+        .get_payslip(&employee, &2)
+        .unwrap()
+        .receipt
+        .unwrap();
+    assert_ne!(next, receipt);
+    assert_eq!(f.client.verify_receipt(&next).unwrap().amount, Some(100));
+
+    assert_eq!(
+        f.client
+            .verify_receipt(&BytesN::from_array(&f.env, &[7; 32])),
+        None
+    );
+}
@@ -0,0 +1,174 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, BytesN, Env,
+};
+use stello_pay_contract::{
+    payslip::PayslipCategory, reimbursements::ReimbursementStatus, storage::PayrollError,
+    PayrollContract, PayrollContractClient,
+};
+
+struct Setup {
+    env: Env,
+    client: PayrollContractClient<'static>,
+    employer: Address,
+    employee: Address,
+    token: TokenClient<'static>,
+}
+
+/// Registers an employer with one agreement for `employee` and 5_000 tokens
+/// in its reimbursement pool.
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PayrollContract, ());
+    let client = PayrollContractClient::new(&env, &contract_id);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    client.create_escrow_agreement(&employer, &employee, &token, &1_000, &86_400, &4);
+
+    StellarAssetClient::new(&env, &token).mint(&employer, &5_000);
+    client.fund_reimbursement_pool(&employer, &token, &5_000);
+
+    Setup {
+        token: TokenClient::new(&env, &token),
+        env,
+        client,
+        employer,
+        employee,
+    }
+}
+
+fn receipt(env: &Env, byte: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[byte; 32])
+}
+
+#[test]
+fn approved_reimbursement_is_paid_from_pool_on_its_own_payslip() {
+    let s = setup();
+    let id =
+        s.client
+            .submit_reimbursement(&s.employee, &300, &s.token.address, &receipt(&s.env, 1));
+    assert_eq!(
+        s.client.get_reimbursement_by_receipt(&receipt(&s.env, 1)),
+        Some(id)
+    );
+
+    s.client
+        .approve_reimbursement(&s.employer, &s.employer, &id);
+    assert_eq!(s.token.balance(&s.employee), 300);
+    assert_eq!(
+        s.client
+            .get_reimbursement_pool(&s.employer, &s.token.address),
+        4_700
+    );
+
+    let request = s.client.get_reimbursement(&id).unwrap();
+    assert_eq!(request.status, ReimbursementStatus::Paid);
+    assert_eq!(request.employer, Some(s.employer.clone()));
+
+    let payslip = s.client.get_payslip(&s.employee, &1).unwrap();
+    assert_eq!(payslip.category, PayslipCategory::Reimbursement);
+    assert_eq!((payslip.gross, payslip.net), (300, 300));
+    assert!(payslip.deductions.is_empty());
+
+    // The same receipt cannot be reimbursed again
+    assert_eq!(
+        s.client
+            .try_submit_reimbursement(&s.employee, &300, &s.token.address, &receipt(&s.env, 1)),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn designated_approver_decides_for_employer() {
+    let s = setup();
+    let approver = Address::generate(&s.env);
+    let first =
+        s.client
+            .submit_reimbursement(&s.employee, &100, &s.token.address, &receipt(&s.env, 1));
+    let second =
+        s.client
+            .submit_reimbursement(&s.employee, &200, &s.token.address, &receipt(&s.env, 2));
+
+    assert_eq!(
+        s.client
+            .try_approve_reimbursement(&approver, &s.employer, &first),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    s.client
+        .set_reimbursement_approver(&s.employer, &approver, &true);
+    assert!(s.client.is_reimbursement_approver(&s.employer, &approver));
+
+    s.client
+        .approve_reimbursement(&approver, &s.employer, &first);
+    s.client
+        .reject_reimbursement(&approver, &s.employer, &second);
+    assert_eq!(s.token.balance(&s.employee), 100);
+    let rejected = s.client.get_reimbursement(&second).unwrap();
+    assert_eq!(rejected.status, ReimbursementStatus::Rejected);
+    assert_eq!(rejected.decided_by, Some(approver.clone()));
+    assert_eq!(
+        s.client
+            .try_approve_reimbursement(&s.employer, &s.employer, &second),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    // An approver cannot approve their own request
+    s.client
+        .set_reimbursement_approver(&s.employer, &s.employee, &true);
+    let own =
+        s.client
+            .submit_reimbursement(&s.employee, &50, &s.token.address, &receipt(&s.env, 3));
+    assert_eq!(
+        s.client
+            .try_approve_reimbursement(&s.employee, &s.employer, &own),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+}
+
+#[test]
+fn approval_requires_employment_and_pool_balance() {
+    let s = setup();
+    let outsider = Address::generate(&s.env);
+    let id = s
+        .client
+        .submit_reimbursement(&outsider, &100, &s.token.address, &receipt(&s.env, 1));
+    assert_eq!(
+        s.client
+            .try_approve_reimbursement(&s.employer, &s.employer, &id),
+        Err(Ok(PayrollError::NoEmployee))
+    );
+
+    let large =
+        s.client
+            .submit_reimbursement(&s.employee, &6_000, &s.token.address, &receipt(&s.env, 2));
+    assert_eq!(
+        s.client
+            .try_approve_reimbursement(&s.employer, &s.employer, &large),
+        Err(Ok(PayrollError::InsufficientEscrowBalance))
+    );
+
+    // Cancelling frees the receipt for a corrected request
+    s.client.cancel_reimbursement(&s.employee, &large);
+    let corrected =
+        s.client
+            .submit_reimbursement(&s.employee, &600, &s.token.address, &receipt(&s.env, 2));
+    s.client
+        .approve_reimbursement(&s.employer, &s.employer, &corrected);
+
+    s.client
+        .withdraw_reimbursement_pool(&s.employer, &s.token.address, &4_400);
+    assert_eq!(s.token.balance(&s.employer), 4_400);
+    assert_eq!(
+        s.client
+            .try_withdraw_reimbursement_pool(&s.employer, &s.token.address, &1),
+        Err(Ok(PayrollError::InsufficientEscrowBalance))
+    );
+}
@@ -0,0 +1,170 @@
+#![cfg(test)]
+
+use soroban_sdk::Address;
+use stello_pay_contract::{
+    reputation::{ReputationTier, UNSTAKE_COOLDOWN_SECONDS},
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+const SALARY: i128 = 1_000;
+
+/// Creates an escrow agreement, disputes it and has the arbiter award
+/// `pay_employee` to the contributor and the rest of 1000 to the employer.
+fn resolve_dispute(f: &Fixture, arbiter: &Address, pay_employee: i128) {
+    let agreement_id = f
+        .agreement(&f.address())
+        .amount_per_period(1_000)
+        .num_periods(1)
+        .inactive()
+        .build();
+    f.client.raise_dispute(&f.employer, &agreement_id);
+    f.client.resolve_dispute(
+        arbiter,
+        &agreement_id,
+        &pay_employee,
+        &(1_000 - pay_employee),
+    );
+}
+
+#[test]
+fn new_employer_is_unrated_with_full_score() {
+    let f = Fixture::new();
+    let reputation = f.client.get_employer_reputation(&f.employer);
+    assert_eq!(reputation.tier, ReputationTier::Unrated);
+    assert_eq!(reputation.score, 1_000);
+    assert_eq!(reputation.on_time_periods, 0);
+    assert!(!reputation.stake_boosted);
+}
+
+#[test]
+fn late_payouts_lower_the_score() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f
+        .payroll()
+        .employee(&employee, SALARY)
+        .funded_periods(100)
+        .build();
+
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    let reputation = f.client.get_employer_reputation(&f.employer);
+    assert_eq!(
+        (reputation.on_time_periods, reputation.late_periods),
+        (1, 0)
+    );
+    assert_eq!(reputation.score, 1_000);
+    assert_eq!(reputation.tier, ReputationTier::Gold);
+
+    // Periods 2..=4 are claimed together: 2 days, 1 day and 0 days overdue
+    f.warp(3 * DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    let reputation = f.client.get_employer_reputation(&f.employer);
+    assert_eq!(
+        (reputation.on_time_periods, reputation.late_periods),
+        (2, 2)
+    );
+    assert_eq!(reputation.defaulted_periods, 0);
+    assert_eq!(reputation.score, 600 * 2 / 4 + 400);
+    assert_eq!(reputation.tier, ReputationTier::Silver);
+}
+
+#[test]
+fn defaults_are_penalized() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f
+        .payroll()
+        .employee(&employee, SALARY)
+        .funded_periods(100)
+        .build();
+
+    // Period k of 32 is 32 - k days overdue: periods 1 and 2 are 30+ days late
+    f.warp(32 * DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+
+    let reputation = f.client.get_employer_reputation(&f.employer);
+    assert_eq!(reputation.on_time_periods, 1);
+    assert_eq!(reputation.late_periods, 31);
+    assert_eq!(reputation.defaulted_periods, 2);
+    assert_eq!(reputation.score, 600 / 32 + 400 - 2 * 100);
+    assert_eq!(reputation.tier, ReputationTier::Bronze);
+}
+
+#[test]
+fn dispute_awards_to_employees_count_as_lost() {
+    let f = Fixture::new();
+    let arbiter = f.address();
+    f.client.set_arbiter(&f.owner, &arbiter);
+
+    resolve_dispute(&f, &arbiter, 0);
+    let reputation = f.client.get_employer_reputation(&f.employer);
+    assert_eq!(
+        (reputation.disputes_resolved, reputation.disputes_lost),
+        (1, 0)
+    );
+    assert_eq!(reputation.score, 1_000);
+
+    resolve_dispute(&f, &arbiter, 400);
+    let reputation = f.client.get_employer_reputation(&f.employer);
+    assert_eq!(
+        (reputation.disputes_resolved, reputation.disputes_lost),
+        (2, 1)
+    );
+    assert_eq!(reputation.score, 600 + 200);
+    assert_eq!(reputation.tier, ReputationTier::Gold);
+}
+
+#[test]
+fn stake_boosts_tier_until_unstaked() {
+    let f = Fixture::new();
+    f.token.mint(&f.employer, 100_000);
+    assert_eq!(
+        f.client.try_stake_reputation(&f.employer, &500),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client
+            .try_set_reputation_stake_config(&f.employer, &f.token.address, &1_000),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    f.client
+        .set_reputation_stake_config(&f.owner, &f.token.address, &1_000);
+
+    f.client.stake_reputation(&f.employer, &500);
+    let reputation = f.client.get_employer_reputation(&f.employer);
+    assert_eq!(reputation.active_stake, 500);
+    assert!(!reputation.stake_boosted);
+    assert_eq!(reputation.tier, ReputationTier::Unrated);
+
+    f.client.stake_reputation(&f.employer, &500);
+    let reputation = f.client.get_employer_reputation(&f.employer);
+    assert!(reputation.stake_boosted);
+    assert_eq!(reputation.tier, ReputationTier::Bronze);
+    assert_eq!(f.token.balance(&f.employer), 99_000);
+    let withdrawable_at = f.client.request_unstake(&f.employer);
+    assert_eq!(
+        withdrawable_at,
+        f.env.ledger().timestamp() + UNSTAKE_COOLDOWN_SECONDS
+    );
+
+    // The stake stops counting as soon as the unstake is requested
+    let reputation = f.client.get_employer_reputation(&f.employer);
+    assert_eq!(reputation.active_stake, 0);
+    assert_eq!(reputation.tier, ReputationTier::Unrated);
+    assert_eq!(
+        f.client.try_stake_reputation(&f.employer, &500),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    f.warp(UNSTAKE_COOLDOWN_SECONDS - 1);
+    assert_eq!(
+        f.client.try_withdraw_stake(&f.employer),
+        Err(Ok(PayrollError::TimelockActive))
+    );
+    f.warp(1);
+    assert_eq!(f.client.withdraw_stake(&f.employer), 1_000);
+    assert_eq!(f.token.balance(&f.employer), 100_000);
+    assert_eq!(f.client.get_reputation_stake(&f.employer), None);
+}
@@ -0,0 +1,121 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, Address, Env};
+use stello_pay_contract::resource_estimate::{PayrollOperation, ResourceProfile};
+use stello_pay_contract::{PayrollContract, PayrollContractClient};
+
+fn setup() -> (Env, PayrollContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PayrollContract, ());
+    let client = PayrollContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    client.initialize(&owner);
+
+    (env, client, owner)
+}
+
+fn profile(cpu: u64, reads: u32, writes: u32) -> ResourceProfile {
+    ResourceProfile {
+        cpu_instructions: cpu,
+        memory_bytes: cpu / 10,
+        read_entries: reads,
+        write_entries: writes,
+    }
+}
+
+fn record_profiles(client: &PayrollContractClient, version: u32) {
+    client.set_resource_profile(
+        &version,
+        &PayrollOperation::InvocationBase,
+        &profile(100_000, 2, 1),
+    );
+    client.set_resource_profile(
+        &version,
+        &PayrollOperation::PerAgreement,
+        &profile(50_000, 4, 1),
+    );
+    client.set_resource_profile(
+        &version,
+        &PayrollOperation::PerEmployee,
+        &profile(200_000, 3, 2),
+    );
+    client.set_resource_profile(
+        &version,
+        &PayrollOperation::PerToken,
+        &profile(80_000, 1, 0),
+    );
+}
+
+#[test]
+fn estimates_are_unavailable_without_profiles() {
+    let (env, client, _owner) = setup();
+    assert_eq!(client.estimate_batch_gas(&5), None);
+    assert_eq!(client.estimate_payroll_run(&Address::generate(&env)), None);
+}
+
+#[test]
+fn batch_estimate_scales_with_batch_size() {
+    let (_env, client, _owner) = setup();
+    record_profiles(&client, 0);
+
+    let estimate = client.estimate_batch_gas(&5).unwrap();
+    assert_eq!(estimate.contract_version, 0);
+    assert_eq!(estimate.employee_count, 5);
+    assert_eq!(
+        estimate.cpu_instructions,
+        100_000 + 50_000 + 5 * 200_000 + 80_000
+    );
+    assert_eq!(estimate.read_entries, 2 + 4 + 5 * 3 + 1);
+    assert_eq!(estimate.write_entries, 1 + 1 + 5 * 2);
+}
+
+#[test]
+fn profiles_are_scoped_to_contract_version() {
+    let (_env, client, owner) = setup();
+    record_profiles(&client, 1);
+    assert_eq!(client.estimate_batch_gas(&1), None);
+
+    client.migrate_state(&owner, &0);
+    let estimate = client.estimate_batch_gas(&1).unwrap();
+    assert_eq!(estimate.contract_version, 1);
+    assert!(client
+        .get_resource_profile(&0, &PayrollOperation::PerEmployee)
+        .is_none());
+}
+
+#[test]
+fn payroll_run_counts_active_agreements_employees_and_tokens() {
+    let (env, client, _owner) = setup();
+    record_profiles(&client, 0);
+    let employer = Address::generate(&env);
+    let token_a = Address::generate(&env);
+    let token_b = Address::generate(&env);
+
+    let first = client.create_payroll_agreement(&employer, &token_a, &3600);
+    client.add_employee_to_agreement(&first, &Address::generate(&env), &100);
+    client.add_employee_to_agreement(&first, &Address::generate(&env), &100);
+    client.activate_agreement(&first);
+
+    let second = client.create_payroll_agreement(&employer, &token_a, &3600);
+    client.add_employee_to_agreement(&second, &Address::generate(&env), &100);
+    client.activate_agreement(&second);
+
+    let third = client.create_payroll_agreement(&employer, &token_b, &3600);
+    client.add_employee_to_agreement(&third, &Address::generate(&env), &100);
+    client.activate_agreement(&third);
+
+    // Not yet active: excluded from the run.
+    let draft = client.create_payroll_agreement(&employer, &token_b, &3600);
+    client.add_employee_to_agreement(&draft, &Address::generate(&env), &100);
+
+    let estimate = client.estimate_payroll_run(&employer).unwrap();
+    assert_eq!(estimate.agreement_count, 3);
+    assert_eq!(estimate.employee_count, 4);
+    assert_eq!(estimate.token_count, 2);
+    assert_eq!(
+        estimate.cpu_instructions,
+        100_000 + 3 * 50_000 + 4 * 200_000 + 2 * 80_000
+    );
+}
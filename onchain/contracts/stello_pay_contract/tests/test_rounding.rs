@@ -0,0 +1,156 @@
+#![cfg(test)]
+
+use proptest::prelude::*;
+use stello_pay_contract::{
+    garnishment::GarnishmentDeduction,
+    rounding::{apply_bps, round_div, RoundingMode},
+    testutils::{Fixture, DAY},
+    validation::MAX_AMOUNT,
+};
+
+const MODES: [RoundingMode; 3] = [
+    RoundingMode::Floor,
+    RoundingMode::Ceil,
+    RoundingMode::HalfEven,
+];
+
+#[test]
+fn modes_round_fractions_as_documented() {
+    let cases = [
+        // numerator, floor, ceil, half-even (denominator 10)
+        (25, 2, 3, 2),
+        (35, 3, 4, 4),
+        (26, 2, 3, 3),
+        (24, 2, 3, 2),
+        (30, 3, 3, 3),
+        (-25, -3, -2, -2),
+        (-26, -3, -2, -3),
+    ];
+    for (numerator, floor, ceil, half_even) in cases {
+        assert_eq!(round_div(numerator, 10, RoundingMode::Floor), floor);
+        assert_eq!(round_div(numerator, 10, RoundingMode::Ceil), ceil);
+        assert_eq!(round_div(numerator, 10, RoundingMode::HalfEven), half_even);
+    }
+    // Exact even when value * bps would overflow
+    assert_eq!(apply_bps(i128::MAX, 10_000, RoundingMode::Ceil), i128::MAX);
+}
+
+#[test]
+fn employer_policy_rounds_payout_shares() {
+    let f = Fixture::new();
+    let recipient = f.address();
+    let floor_employee = f.address();
+    let ceil_employee = f.address();
+    let ceil_employer = f.address();
+    f.client
+        .set_rounding_policy(&ceil_employer, &RoundingMode::Ceil);
+    assert_eq!(
+        f.client.get_rounding_policy(&ceil_employer),
+        RoundingMode::Ceil
+    );
+    assert_eq!(
+        f.client.get_rounding_policy(&f.employer),
+        RoundingMode::Floor
+    );
+
+    let floor_payroll = f.payroll().employee(&floor_employee, 1_000).build();
+    let ceil_payroll = f
+        .payroll()
+        .employer(&ceil_employer)
+        .employee(&ceil_employee, 1_000)
+        .build();
+    for employee in [&floor_employee, &ceil_employee] {
+        f.client.register_garnishment(
+            &f.owner,
+            employee,
+            &recipient,
+            &f.token.address,
+            &GarnishmentDeduction::Bps(3_333),
+            &1_000_000,
+        );
+        f.client.set_savings_plan(employee, &1_000, &DAY);
+    }
+    f.warp(DAY);
+    f.client.claim_payroll(&floor_employee, &floor_payroll, &0);
+    f.client.claim_payroll(&ceil_employee, &ceil_payroll, &0);
+
+    // 333.3 withheld, then 66.67 saved of the 666.7 left
+    let floor = f.client.get_payslip(&floor_employee, &1).unwrap();
+    assert_eq!(floor.deductions.get(0).unwrap().amount, 333);
+    assert_eq!(floor.saved, 66);
+    assert_eq!(floor.net, 601);
+    let ceil = f.client.get_payslip(&ceil_employee, &1).unwrap();
+    assert_eq!(ceil.deductions.get(0).unwrap().amount, 334);
+    assert_eq!(ceil.saved, 67);
+    assert_eq!(ceil.net, 599);
+    assert_eq!(f.token.balance(&ceil_employee), 599);
+    assert_eq!(f.token.balance(&recipient), 667);
+}
+
+fn mode() -> impl Strategy<Value = RoundingMode> {
+    (0..MODES.len()).prop_map(|i| MODES[i])
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    /// Property: a share is within one unit of its exact value, modes are
+    /// ordered, and `Floor` matches plain integer division.
+    #[test]
+    fn prop_shares_bracket_exact_value(
+        value in 0i128..=1_000_000_000_000_000,
+        bps in 0u32..=10_000,
+    ) {
+        let floor = apply_bps(value, bps, RoundingMode::Floor);
+        let ceil = apply_bps(value, bps, RoundingMode::Ceil);
+        let half_even = apply_bps(value, bps, RoundingMode::HalfEven);
+        prop_assert_eq!(floor, value * i128::from(bps) / 10_000);
+        prop_assert!(floor <= half_even && half_even <= ceil);
+        prop_assert!(ceil - floor <= 1);
+        prop_assert!(ceil <= value);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    /// Property: whatever the policy, a payout's legs sum to the gross and
+    /// the employee receives exactly the net leg.
+    #[test]
+    fn prop_payout_legs_reconcile(
+        salary in 1i128..=1_000_000_000_000,
+        garnish_bps in prop::collection::vec(1u32..=10_000, 0..3),
+        savings_bps in prop::option::of(1u32..=5_000),
+        mode in mode(),
+    ) {
+        let f = Fixture::new();
+        f.client.set_rounding_policy(&f.employer, &mode);
+        let employee = f.address();
+        let payroll = f.payroll().employee(&employee, salary).build();
+        for bps in garnish_bps.iter() {
+            f.client.register_garnishment(
+                &f.owner,
+                &employee,
+                &f.address(),
+                &f.token.address,
+                &GarnishmentDeduction::Bps(*bps),
+                &MAX_AMOUNT,
+            );
+        }
+        if let Some(bps) = savings_bps {
+            f.client.set_savings_plan(&employee, &bps, &DAY);
+        }
+        f.warp(DAY);
+        f.client.claim_payroll(&employee, &payroll, &0);
+
+        let payslip = f.client.get_payslip(&employee, &1).unwrap();
+        let withheld: i128 = payslip.deductions.iter().map(|d| d.amount).sum();
+        prop_assert_eq!(payslip.gross, salary);
+        prop_assert_eq!(
+            withheld + payslip.loan_repayment + payslip.saved + payslip.net,
+            salary
+        );
+        prop_assert!(payslip.net >= 0);
+        prop_assert_eq!(f.token.balance(&employee), payslip.net);
+    }
+}
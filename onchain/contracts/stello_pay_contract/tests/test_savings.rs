@@ -0,0 +1,180 @@
+#![cfg(test)]
+
+use stello_pay_contract::{
+    garnishment::GarnishmentDeduction,
+    savings::{SavingsPlan, SavingsVault},
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+const LOCK_SECONDS: u64 = 30 * DAY;
+const SALARY: i128 = 1_000;
+
+#[test]
+fn invalid_plans_are_rejected() {
+    let f = Fixture::new();
+    let employee = f.address();
+    for (bps, lock) in [(0, LOCK_SECONDS), (5_001, LOCK_SECONDS), (1_000, 0)] {
+        assert_eq!(
+            f.client.try_set_savings_plan(&employee, &bps, &lock),
+            Err(Ok(PayrollError::InvalidData))
+        );
+    }
+
+    f.client.set_savings_plan(&employee, &1_000, &LOCK_SECONDS);
+    assert_eq!(
+        f.client.get_savings_plan(&employee),
+        Some(SavingsPlan {
+            bps: 1_000,
+            lock_duration: LOCK_SECONDS,
+        })
+    );
+    f.client.cancel_savings_plan(&employee);
+    assert_eq!(f.client.get_savings_plan(&employee), None);
+}
+
+#[test]
+fn savings_share_is_locked_and_listed_on_payslip() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    let recipient = f.address();
+    f.client.register_garnishment(
+        &f.owner,
+        &employee,
+        &recipient,
+        &f.token.address,
+        &GarnishmentDeduction::Fixed(200),
+        &10_000,
+    );
+    f.client.set_savings_plan(&employee, &2_500, &LOCK_SECONDS);
+
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+
+    // Savings apply to what is left after garnishments
+    assert_eq!(f.token.balance(&recipient), 200);
+    assert_eq!(f.token.balance(&employee), 600);
+    let payslip = f.client.get_payslip(&employee, &1).unwrap();
+    assert_eq!(payslip.saved, 200);
+    assert_eq!(payslip.net, 600);
+
+    let unlock_at = f.env.ledger().timestamp() + LOCK_SECONDS;
+    assert_eq!(
+        f.client.get_savings_vault(&employee, &f.token.address),
+        SavingsVault {
+            available: 0,
+            locked: 200,
+            unlock_at,
+        }
+    );
+
+    // A later deposit joins the running term
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    let vault = f.client.get_savings_vault(&employee, &f.token.address);
+    assert_eq!(vault.locked, 400);
+    assert_eq!(vault.unlock_at, unlock_at);
+}
+
+#[test]
+fn unlocked_savings_can_be_withdrawn() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    f.client.set_savings_plan(&employee, &5_000, &LOCK_SECONDS);
+
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+    assert_eq!(f.token.balance(&employee), 500);
+
+    f.warp(LOCK_SECONDS);
+    let vault = f.client.get_savings_vault(&employee, &f.token.address);
+    assert_eq!((vault.available, vault.locked), (500, 0));
+
+    assert_eq!(
+        f.client
+            .withdraw_savings(&employee, &f.token.address, &300, &false),
+        300
+    );
+    assert_eq!(f.token.balance(&employee), 800);
+    assert_eq!(
+        f.client
+            .get_savings_vault(&employee, &f.token.address)
+            .available,
+        200
+    );
+    assert_eq!(
+        f.client
+            .try_withdraw_savings(&employee, &f.token.address, &201, &false),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn early_withdrawal_requires_configured_penalty() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    f.client.set_savings_plan(&employee, &5_000, &LOCK_SECONDS);
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+
+    // No penalty configured: locked savings stay locked
+    assert_eq!(
+        f.client
+            .try_withdraw_savings(&employee, &f.token.address, &100, &true),
+        Err(Ok(PayrollError::TimelockActive))
+    );
+
+    let pool = f.address();
+    let stranger = f.address();
+    assert_eq!(
+        f.client.try_set_savings_penalty(&stranger, &1_000, &pool),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    f.client.set_savings_penalty(&f.owner, &1_000, &pool);
+
+    // The penalty must be explicitly accepted
+    assert_eq!(
+        f.client
+            .try_withdraw_savings(&employee, &f.token.address, &100, &false),
+        Err(Ok(PayrollError::TimelockActive))
+    );
+    assert_eq!(
+        f.client
+            .withdraw_savings(&employee, &f.token.address, &200, &true),
+        180
+    );
+    assert_eq!(f.token.balance(&pool), 20);
+    assert_eq!(f.token.balance(&employee), 500 + 180);
+    assert_eq!(
+        f.client
+            .get_savings_vault(&employee, &f.token.address)
+            .locked,
+        300
+    );
+}
+
+#[test]
+fn early_withdrawal_penalty_can_fund_the_treasury() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let agreement_id = f.payroll().employee(&employee, SALARY).build();
+    f.client.set_savings_plan(&employee, &5_000, &LOCK_SECONDS);
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &agreement_id, &0);
+
+    f.client
+        .set_savings_penalty(&f.owner, &1_000, &f.client.address);
+    assert_eq!(
+        f.client
+            .withdraw_savings(&employee, &f.token.address, &200, &true),
+        180
+    );
+
+    let balances = f.client.get_treasury_balances();
+    assert_eq!(balances.len(), 1);
+    assert_eq!(balances.get(0).unwrap().token, f.token.address);
+    assert_eq!(balances.get(0).unwrap().amount, 20);
+}
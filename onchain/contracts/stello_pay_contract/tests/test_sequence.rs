@@ -0,0 +1,114 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Events, Ledger},
+    token::StellarAssetClient,
+    Address, BytesN, Env, Map, Symbol, TryFromVal, TryIntoVal, Val,
+};
+use stello_pay_contract::{
+    sequence::SequenceCategory, storage::DataKey, PayrollContract, PayrollContractClient,
+};
+
+const PERIOD_SECONDS: u64 = 86_400;
+
+fn setup() -> (Env, PayrollContractClient<'static>, Address, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(PayrollContract, ());
+    let client = PayrollContractClient::new(&env, &contract_id);
+    let employer = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    (env, client, employer, token)
+}
+
+/// Returns the `sequence` field of the last `event_name` event emitted by the
+/// most recent invocation.
+fn event_sequence(env: &Env, event_name: &str) -> u64 {
+    let event = env
+        .events()
+        .all()
+        .iter()
+        .filter(|e| {
+            e.1.get(0)
+                .and_then(|topic| Symbol::try_from_val(env, &topic).ok())
+                .is_some_and(|sym| sym.to_string() == event_name)
+        })
+        .last()
+        .unwrap();
+    let data: Map<Symbol, Val> = event.2.try_into_val(env).unwrap();
+    data.get(Symbol::new(env, "sequence"))
+        .unwrap()
+        .try_into_val(env)
+        .unwrap()
+}
+
+fn advance_time(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|ledger| ledger.timestamp += seconds);
+}
+
+#[test]
+fn lifecycle_changes_take_consecutive_payroll_numbers() {
+    let (env, client, employer, token) = setup();
+    assert_eq!(client.get_latest_sequence(&SequenceCategory::Payroll), 0);
+
+    let agreement_id = client.create_payroll_agreement(&employer, &token, &PERIOD_SECONDS);
+    assert_eq!(event_sequence(&env, "agreement_created_event"), 1);
+    client.add_employee_to_agreement(&agreement_id, &Address::generate(&env), &1_000);
+    assert_eq!(event_sequence(&env, "employee_added_event"), 2);
+    client.activate_agreement(&agreement_id);
+    assert_eq!(event_sequence(&env, "agreement_activated_event"), 3);
+    client.pause_agreement(&agreement_id);
+    assert_eq!(event_sequence(&env, "agreement_paused_event"), 4);
+
+    assert_eq!(client.get_latest_sequence(&SequenceCategory::Payroll), 4);
+    assert_eq!(
+        client.get_latest_sequence(&SequenceCategory::Disbursement),
+        0
+    );
+}
+
+#[test]
+fn each_payout_takes_the_next_disbursement_number() {
+    let (env, client, employer, token) = setup();
+    let contributor = Address::generate(&env);
+    let agreement_id =
+        client.create_escrow_agreement(&employer, &contributor, &token, &500, &PERIOD_SECONDS, &4);
+    StellarAssetClient::new(&env, &token).mint(&client.address, &2_000);
+    env.as_contract(&client.address, || {
+        DataKey::set_agreement_escrow_balance(&env, agreement_id, &token, 2_000);
+    });
+    client.activate_agreement(&agreement_id);
+
+    advance_time(&env, PERIOD_SECONDS);
+    client.claim_time_based(&agreement_id);
+    assert_eq!(event_sequence(&env, "payment_sent_event"), 1);
+    assert_eq!(event_sequence(&env, "payment_received_event"), 1);
+
+    advance_time(&env, PERIOD_SECONDS);
+    client.claim_time_based(&agreement_id);
+    assert_eq!(event_sequence(&env, "payment_sent_event"), 2);
+    assert_eq!(
+        client.get_latest_sequence(&SequenceCategory::Disbursement),
+        2
+    );
+
+    // Reimbursements share the category and record it on the payslip
+    StellarAssetClient::new(&env, &token).mint(&employer, &300);
+    client.fund_reimbursement_pool(&employer, &token, &300);
+    let request_id = client.submit_reimbursement(
+        &contributor,
+        &300,
+        &token,
+        &BytesN::from_array(&env, &[1; 32]),
+    );
+    client.approve_reimbursement(&employer, &employer, &request_id);
+    assert_eq!(event_sequence(&env, "reimbursement_paid_event"), 3);
+    let payslip = client.get_payslip(&contributor, &1).unwrap();
+    assert_eq!(payslip.sequence, 3);
+
+    // Payouts do not advance the payroll category
+    assert_eq!(client.get_latest_sequence(&SequenceCategory::Payroll), 3);
+    assert_eq!(client.get_latest_sequence(&SequenceCategory::Incentive), 0);
+}
@@ -0,0 +1,142 @@
+#![cfg(test)]
+
+use soroban_sdk::Address;
+use stello_pay_contract::{
+    severance::{SeverancePool, SeveranceSettlement},
+    storage::PayrollError,
+    testutils::Fixture,
+};
+
+const POOL: i128 = 100_000;
+
+/// Configures a 2% base premium with 50% coverage and funds the pool.
+fn insured() -> Fixture<'static> {
+    let f = Fixture::new();
+    f.client
+        .set_severance_insurance(&f.owner, &f.token.address, &200, &5_000);
+    let funder = f.address();
+    f.token.mint(&funder, POOL);
+    f.client.fund_severance_pool(&funder, &POOL);
+    f
+}
+
+fn covered(f: &Fixture, severance_amount: i128) -> (u128, Address) {
+    let employee = f.address();
+    let id = f.payroll().employee(&employee, 1_000).build();
+    f.token.mint(&f.employer, 1_000);
+    f.client
+        .buy_severance_cover(&f.employer, &id, &employee, &severance_amount);
+    (id, employee)
+}
+
+#[test]
+fn premium_is_priced_from_reputation_and_utilization() {
+    let f = insured();
+    // Unrated employer: 200 bps * 150% = 300 bps, plus 5% utilization
+    // (5 000 of cover against 100 000) = 315 bps.
+    assert_eq!(f.client.quote_severance_premium(&f.employer, &10_000), 315);
+
+    let (id, employee) = covered(&f, 10_000);
+    let cover = f.client.get_severance_cover(&id, &employee).unwrap();
+    assert_eq!((cover.premium, cover.max_payout), (315, 5_000));
+    assert_eq!(
+        f.client.get_severance_pool(),
+        SeverancePool {
+            balance: POOL + 315,
+            exposure: 5_000,
+        }
+    );
+    // A busier pool charges more for the same cover.
+    assert!(f.client.quote_severance_premium(&f.employer, &10_000) > 315);
+    assert_eq!(
+        f.client.try_quote_severance_premium(&f.employer, &200_000),
+        Err(Ok(PayrollError::InsufficientEscrowBalance))
+    );
+}
+
+#[test]
+fn pool_pays_the_shortfall_and_the_employer_owes_it_back() {
+    let f = insured();
+    let (id, employee) = covered(&f, 10_000);
+    assert_eq!(
+        f.client.try_settle_severance(&id, &employee),
+        Err(Ok(PayrollError::InvalidAgreementMode))
+    );
+
+    // The employer can only cover 4 000 of the 10 000 owed.
+    f.token.mint(&f.employer, 4_000);
+    f.token.approve(&f.employer, &f.client.address, 4_000);
+    f.client.cancel_agreement(&id);
+    assert_eq!(
+        f.client.settle_severance(&id, &employee),
+        SeveranceSettlement {
+            from_employer: 4_000,
+            from_pool: 5_000,
+        }
+    );
+    assert_eq!(f.token.balance(&employee), 9_000);
+    assert_eq!(f.client.get_severance_liability(&f.employer), 5_000);
+    assert_eq!(
+        f.client.get_severance_pool(),
+        SeverancePool {
+            balance: POOL + 315 - 5_000,
+            exposure: 0,
+        }
+    );
+    assert_eq!(
+        f.client.try_settle_severance(&id, &employee),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    f.token.mint(&f.employer, 10_000);
+    assert_eq!(
+        f.client.repay_severance_liability(&f.employer, &2_000),
+        3_000
+    );
+    assert_eq!(f.client.repay_severance_liability(&f.employer, &10_000), 0);
+    assert_eq!(f.client.get_severance_pool().balance, POOL + 315);
+    assert_eq!(
+        f.client.try_repay_severance_liability(&f.employer, &1),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn cover_requires_an_employee_of_the_agreement() {
+    let f = insured();
+    let (id, employee) = covered(&f, 10_000);
+    assert_eq!(
+        f.client
+            .try_buy_severance_cover(&f.employer, &id, &f.address(), &10_000),
+        Err(Ok(PayrollError::NoEmployee))
+    );
+    assert_eq!(
+        f.client
+            .try_buy_severance_cover(&f.employer, &id, &employee, &10_000),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client
+            .try_buy_severance_cover(&f.address(), &id, &employee, &10_000),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        f.client.try_release_severance_cover(&id, &employee),
+        Err(Ok(PayrollError::InvalidAgreementMode))
+    );
+}
+
+#[test]
+fn only_the_owner_sets_the_insurance_terms() {
+    let f = Fixture::new();
+    assert_eq!(
+        f.client
+            .try_set_severance_insurance(&f.employer, &f.token.address, &200, &5_000),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        f.client
+            .try_set_severance_insurance(&f.owner, &f.token.address, &200, &10_001),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
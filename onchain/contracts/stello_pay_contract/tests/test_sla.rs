@@ -0,0 +1,149 @@
+#![cfg(test)]
+
+use soroban_sdk::Address;
+use stello_pay_contract::{
+    sla::SlaConfig,
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+const WINDOW: u64 = 7 * DAY;
+
+fn sla(f: &Fixture) {
+    f.client.set_sla_config(
+        &f.employer,
+        &SlaConfig {
+            target_on_time_bps: 8_000,
+            window_seconds: WINDOW,
+            breach_windows: 2,
+            penalty_points: 50,
+            prefunding_step: 2,
+        },
+    );
+}
+
+/// An active daily payroll of 100 per period, funded for 30 periods.
+fn payroll(f: &Fixture) -> (u128, Address) {
+    let employee = f.address();
+    let id = f
+        .payroll()
+        .employee(&employee, 100)
+        .funded_periods(30)
+        .build();
+    (id, employee)
+}
+
+/// Claims every three days for a week: two of every three periods are paid
+/// a day or more after falling due.
+fn late_week(f: &Fixture, id: u128, employee: &Address) {
+    for _ in 0..2 {
+        f.warp(3 * DAY);
+        f.client.claim_payroll(employee, &id, &0);
+    }
+    f.warp(DAY);
+}
+
+#[test]
+fn punctual_employer_meets_the_sla() {
+    let f = Fixture::new();
+    let (id, employee) = payroll(&f);
+    sla(&f);
+    for _ in 0..7 {
+        f.warp(DAY);
+        f.client.claim_payroll(&employee, &id, &0);
+    }
+    // The last claim already falls in the second window
+    let status = f.client.check_sla(&f.employer);
+    assert_eq!((status.window, status.on_time, status.late), (1, 1, 0));
+    assert_eq!((status.consecutive_breaches, status.alerts), (0, 0));
+}
+
+#[test]
+fn consecutive_breaches_raise_an_alert() {
+    let f = Fixture::new();
+    let (id, employee) = payroll(&f);
+    sla(&f);
+
+    late_week(&f, id, &employee);
+    let status = f.client.check_sla(&f.employer);
+    assert_eq!((status.consecutive_breaches, status.alerts), (1, 0));
+
+    late_week(&f, id, &employee);
+    let score = f.client.get_employer_reputation(&f.employer).score;
+    let status = f.client.check_sla(&f.employer);
+    assert_eq!((status.consecutive_breaches, status.alerts), (0, 1));
+    assert_eq!(status.penalty_points, 50);
+    assert_eq!(status.required_prefunding_periods, 2);
+    assert_eq!(
+        f.client.get_employer_reputation(&f.employer).score,
+        score - 50
+    );
+
+    // New payroll agreements must now be pre-funded for two periods
+    let next = f
+        .client
+        .create_payroll_agreement(&f.employer, &f.token.address, &DAY);
+    f.client
+        .add_employee_to_agreement(&next, &f.address(), &100);
+    assert!(f.client.try_activate_agreement(&next).is_err());
+}
+
+#[test]
+fn a_window_on_target_ends_the_streak() {
+    let f = Fixture::new();
+    let (id, employee) = payroll(&f);
+    sla(&f);
+
+    // The first daily claim pays a period a day late; 6 of 7 still meets 80%
+    late_week(&f, id, &employee);
+    for _ in 0..7 {
+        f.warp(DAY);
+        f.client.claim_payroll(&employee, &id, &0);
+    }
+    late_week(&f, id, &employee);
+    let status = f.client.check_sla(&f.employer);
+    assert_eq!(status.window, 3);
+    assert_eq!((status.consecutive_breaches, status.alerts), (1, 0));
+
+    // Replacing the SLA restarts tracking
+    sla(&f);
+    let status = f.client.get_sla_status(&f.employer);
+    assert_eq!((status.window, status.consecutive_breaches), (0, 0));
+}
+
+#[test]
+fn sla_config_is_validated() {
+    let f = Fixture::new();
+    let config = SlaConfig {
+        target_on_time_bps: 9_000,
+        window_seconds: WINDOW,
+        breach_windows: 2,
+        penalty_points: 50,
+        prefunding_step: 0,
+    };
+    for invalid in [
+        SlaConfig {
+            target_on_time_bps: 10_001,
+            ..config.clone()
+        },
+        SlaConfig {
+            window_seconds: 0,
+            ..config.clone()
+        },
+        SlaConfig {
+            breach_windows: 0,
+            ..config.clone()
+        },
+    ] {
+        assert_eq!(
+            f.client.try_set_sla_config(&f.employer, &invalid),
+            Err(Ok(PayrollError::InvalidData))
+        );
+    }
+    assert_eq!(
+        f.client.try_check_sla(&f.employer),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    f.client.set_sla_config(&f.employer, &config);
+    assert_eq!(f.client.get_sla_config(&f.employer), Some(config));
+}
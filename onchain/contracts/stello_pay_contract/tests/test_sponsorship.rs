@@ -0,0 +1,164 @@
+#![cfg(test)]
+
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{Address, BytesN};
+use stello_pay_contract::{
+    meta_tx::{SignedAction, SignedRequest},
+    sponsorship::SponsorshipPolicy,
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+
+const MAX_FEE: i128 = 10;
+const POOL: i128 = 100;
+
+struct Sponsored {
+    employee: Address,
+    relayer: Address,
+    agreement_id: u128,
+    key: SigningKey,
+}
+
+/// An active payroll whose employer sponsors two withdrawals per week, and
+/// an employee with a registered signing key.
+fn sponsored(f: &Fixture) -> Sponsored {
+    let employee = f.address();
+    let agreement_id = f
+        .payroll()
+        .employee(&employee, 1_000)
+        .funded_periods(30)
+        .build();
+    let key = SigningKey::from_bytes(&[7; 32]);
+    f.client.register_signing_key(
+        &employee,
+        &BytesN::from_array(&f.env, &key.verifying_key().to_bytes()),
+    );
+    f.client.set_sponsorship_policy(
+        &f.employer,
+        &SponsorshipPolicy {
+            token: f.token.address.clone(),
+            max_fee: MAX_FEE,
+            max_per_period: 2,
+            period: 7 * DAY,
+        },
+    );
+    f.token.mint(&f.employer, POOL);
+    f.client.fund_sponsorship_pool(&f.employer, &POOL);
+    Sponsored {
+        employee,
+        relayer: f.address(),
+        agreement_id,
+        key,
+    }
+}
+
+fn claim(f: &Fixture, s: &Sponsored, action: SignedAction) -> (SignedRequest, BytesN<64>) {
+    let request = SignedRequest {
+        employee: s.employee.clone(),
+        action,
+        nonce: f.client.get_signing_nonce(&s.employee),
+        expiry: f.env.ledger().timestamp() + DAY,
+    };
+    let payload = f.client.signed_action_payload(&request).to_alloc_vec();
+    let signature = BytesN::from_array(&f.env, &s.key.sign(&payload).to_bytes());
+    (request, signature)
+}
+
+#[test]
+fn relayer_is_paid_back_from_the_pool() {
+    let f = Fixture::new();
+    let s = sponsored(&f);
+    f.warp(DAY);
+
+    let (request, signature) = claim(&f, &s, SignedAction::ClaimPayroll(s.agreement_id, 0));
+    f.client
+        .sponsored_withdraw(&s.relayer, &request, &signature, &MAX_FEE);
+    assert_eq!(f.token.balance(&s.employee), 1_000);
+    assert_eq!(f.token.balance(&s.relayer), MAX_FEE);
+    assert_eq!(f.client.get_sponsorship_pool(&f.employer), POOL - MAX_FEE);
+    assert_eq!(
+        f.client.get_sponsored_withdrawals(&f.employer, &s.employee),
+        1
+    );
+}
+
+#[test]
+fn sponsored_withdrawals_are_capped_per_period() {
+    let f = Fixture::new();
+    let s = sponsored(&f);
+    // Periods are counted from timestamp zero; start at the beginning of one.
+    f.warp(7 * DAY - f.env.ledger().timestamp() % (7 * DAY));
+    for _ in 0..2 {
+        f.warp(DAY);
+        let (request, signature) = claim(&f, &s, SignedAction::ClaimPayroll(s.agreement_id, 0));
+        f.client
+            .sponsored_withdraw(&s.relayer, &request, &signature, &5);
+    }
+
+    f.warp(DAY);
+    let (request, signature) = claim(&f, &s, SignedAction::ClaimPayroll(s.agreement_id, 0));
+    assert_eq!(
+        f.client
+            .try_sponsored_withdraw(&s.relayer, &request, &signature, &5),
+        Err(Ok(PayrollError::RateLimited))
+    );
+    // The same request can still be relayed unsponsored.
+    f.client
+        .execute_signed_action(&s.relayer, &request, &signature);
+
+    f.warp(7 * DAY);
+    assert_eq!(
+        f.client.get_sponsored_withdrawals(&f.employer, &s.employee),
+        0
+    );
+    let (request, signature) = claim(&f, &s, SignedAction::ClaimPayroll(s.agreement_id, 0));
+    f.client
+        .sponsored_withdraw(&s.relayer, &request, &signature, &5);
+    assert_eq!(f.token.balance(&s.relayer), 15);
+}
+
+#[test]
+fn fee_must_be_within_the_cap_and_the_pool() {
+    let f = Fixture::new();
+    let s = sponsored(&f);
+    f.warp(DAY);
+    let (request, signature) = claim(&f, &s, SignedAction::ClaimPayroll(s.agreement_id, 0));
+    assert_eq!(
+        f.client
+            .try_sponsored_withdraw(&s.relayer, &request, &signature, &(MAX_FEE + 1)),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    f.client.withdraw_sponsorship_pool(&f.employer, &(POOL - 1));
+    assert_eq!(f.token.balance(&f.employer), POOL - 1);
+    assert_eq!(
+        f.client
+            .try_sponsored_withdraw(&s.relayer, &request, &signature, &MAX_FEE),
+        Err(Ok(PayrollError::InsufficientEscrowBalance))
+    );
+    assert_eq!(f.client.get_signing_nonce(&s.employee), 0);
+}
+
+#[test]
+fn only_payroll_claims_are_sponsored() {
+    let f = Fixture::new();
+    let s = sponsored(&f);
+    let (request, signature) = claim(&f, &s, SignedAction::SetSavingsPlan(1_000, DAY));
+    assert_eq!(
+        f.client
+            .try_sponsored_withdraw(&s.relayer, &request, &signature, &MAX_FEE),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client.try_set_sponsorship_policy(
+            &f.employer,
+            &SponsorshipPolicy {
+                token: f.address(),
+                max_fee: MAX_FEE,
+                max_per_period: 1,
+                period: DAY,
+            },
+        ),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
@@ -0,0 +1,205 @@
+#![cfg(test)]
+
+use stello_pay_contract::{
+    garnishment::GarnishmentDeduction,
+    storage::{DataKey, PayrollError},
+    streaming::SalaryStream,
+    testutils::Fixture,
+};
+
+/// A payroll agreement of `employer` with an empty escrow.
+fn unfunded_payroll(f: &Fixture, employer: &soroban_sdk::Address) -> u128 {
+    f.payroll()
+        .employer(employer)
+        .employee(&f.address(), 1_000)
+        .funded_periods(0)
+        .build()
+}
+
+fn escrow(f: &Fixture, agreement_id: u128) -> i128 {
+    f.env.as_contract(&f.client.address, || {
+        DataKey::get_agreement_escrow_balance(&f.env, agreement_id, &f.token.address)
+    })
+}
+
+#[test]
+fn streamed_salary_accrues_per_second_up_to_the_escrow() {
+    let f = Fixture::new();
+    let start = f.env.ledger().timestamp();
+    let employee = f.address();
+    let id = unfunded_payroll(&f, &f.employer);
+    f.token.mint(&f.employer, 10_000);
+    f.client
+        .create_salary_stream(&f.employer, &id, &employee, &2, &1_000);
+    assert_eq!(escrow(&f, id), 1_000);
+    assert_eq!(
+        f.client
+            .try_create_salary_stream(&f.employer, &id, &employee, &2, &1_000),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    f.warp(100);
+    assert_eq!(f.client.get_streamed_balance(&id, &employee), 200);
+    assert_eq!(f.client.withdraw_streamed(&employee, &id), 200);
+    assert_eq!(
+        f.client.try_withdraw_streamed(&employee, &id),
+        Err(Ok(PayrollError::NoPeriodsToClaim))
+    );
+
+    // Time the escrow does not cover stays owed until it is topped up
+    f.warp(500);
+    assert_eq!(f.client.withdraw_streamed(&employee, &id), 800);
+    assert_eq!(
+        f.client.get_salary_stream(&id, &employee),
+        Some(SalaryStream {
+            agreement_id: id,
+            employer: f.employer.clone(),
+            employee: employee.clone(),
+            token: f.token.address.clone(),
+            rate_per_second: 2,
+            started_at: start,
+            last_withdraw_at: start + 500,
+            withdrawn: 1_000,
+        })
+    );
+    assert_eq!(
+        f.client.try_withdraw_streamed(&employee, &id),
+        Err(Ok(PayrollError::InsufficientEscrowBalance))
+    );
+    f.client
+        .fund_salary_stream(&f.employer, &id, &employee, &1_000);
+    assert_eq!(f.client.withdraw_streamed(&employee, &id), 200);
+
+    // Stopping pays what accrued; the rest of the escrow stays with the agreement
+    f.warp(100);
+    assert_eq!(
+        f.client.stop_salary_stream(&f.employer, &id, &employee),
+        200
+    );
+    assert_eq!(f.token.balance(&employee), 1_400);
+    assert_eq!(f.token.balance(&f.employer), 10_000 - 2_000);
+    assert_eq!(escrow(&f, id), 600);
+    assert_eq!(f.client.get_salary_stream(&id, &employee), None);
+    assert_eq!(
+        f.client.try_withdraw_streamed(&employee, &id),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn stopping_draws_unfunded_salary_from_the_employer() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let id = unfunded_payroll(&f, &f.employer);
+    f.token.mint(&f.employer, 1_000);
+    f.client
+        .create_salary_stream(&f.employer, &id, &employee, &3, &30);
+    f.warp(50);
+
+    let stranger = f.address();
+    assert_eq!(
+        f.client
+            .try_fund_salary_stream(&stranger, &id, &employee, &100),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        f.client.try_stop_salary_stream(&stranger, &id, &employee),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(f.client.get_streamed_balance(&id, &employee), 30);
+    assert_eq!(
+        f.client.stop_salary_stream(&f.employer, &id, &employee),
+        150
+    );
+    assert_eq!(f.token.balance(&employee), 150);
+    assert_eq!(f.token.balance(&f.employer), 850);
+    assert_eq!(escrow(&f, id), 0);
+}
+
+#[test]
+fn streams_require_the_agreement_employer_and_a_deposit() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let id = unfunded_payroll(&f, &f.employer);
+    let stranger = f.address();
+    f.token.mint(&stranger, 1_000);
+    f.token.mint(&f.employer, 1_000);
+
+    assert_eq!(
+        f.client
+            .try_create_salary_stream(&stranger, &id, &employee, &1, &100),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        f.client
+            .try_create_salary_stream(&f.employer, &id, &employee, &1, &0),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client
+            .try_create_salary_stream(&f.employer, &999, &employee, &1, &100),
+        Err(Ok(PayrollError::AgreementNotFound))
+    );
+    assert_eq!(f.client.get_salary_stream(&id, &employee), None);
+}
+
+#[test]
+fn an_employee_can_be_streamed_by_several_employers() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let other_employer = f.address();
+    let first = unfunded_payroll(&f, &f.employer);
+    let second = unfunded_payroll(&f, &other_employer);
+    f.token.mint(&f.employer, 1_000);
+    f.token.mint(&other_employer, 1_000);
+
+    f.client
+        .create_salary_stream(&f.employer, &first, &employee, &1, &1_000);
+    f.client
+        .create_salary_stream(&other_employer, &second, &employee, &2, &1_000);
+
+    f.warp(100);
+    assert_eq!(f.client.withdraw_streamed(&employee, &first), 100);
+    assert_eq!(f.client.withdraw_streamed(&employee, &second), 200);
+    assert_eq!(f.token.balance(&employee), 300);
+    assert_eq!(escrow(&f, first), 900);
+    assert_eq!(escrow(&f, second), 800);
+}
+
+#[test]
+fn streamed_pay_is_settled_like_a_claim() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let recipient = f.address();
+    let id = unfunded_payroll(&f, &f.employer);
+    f.token.mint(&f.employer, 1_000);
+    f.client.register_garnishment(
+        &f.owner,
+        &employee,
+        &recipient,
+        &f.token.address,
+        &GarnishmentDeduction::Bps(2_500),
+        &1_000,
+    );
+    f.client
+        .create_salary_stream(&f.employer, &id, &employee, &4, &1_000);
+
+    f.warp(100);
+    assert_eq!(f.client.withdraw_streamed(&employee, &id), 400);
+    assert_eq!(f.token.balance(&recipient), 100);
+    assert_eq!(f.token.balance(&employee), 300);
+
+    f.warp(50);
+    assert_eq!(
+        f.client.stop_salary_stream(&f.employer, &id, &employee),
+        200
+    );
+    assert_eq!(f.token.balance(&recipient), 150);
+    assert_eq!(f.token.balance(&employee), 450);
+
+    assert_eq!(f.client.get_payslip_count(&employee), 2);
+    let payslip = f.client.get_payslip(&employee, &2).unwrap();
+    assert_eq!(payslip.agreement_id, id);
+    assert_eq!(payslip.gross, 200);
+    assert_eq!(payslip.net, 150);
+}
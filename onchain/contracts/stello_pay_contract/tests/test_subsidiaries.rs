@@ -0,0 +1,108 @@
+#![cfg(test)]
+
+use soroban_sdk::{map, vec};
+use stello_pay_contract::{
+    storage::{AgreementStatus, PayrollError},
+    subsidiaries::GroupMetrics,
+    testutils::{Fixture, DAY},
+};
+
+#[test]
+fn parents_see_and_operate_their_subsidiaries() {
+    let f = Fixture::new();
+    let holding = f.address();
+    let retail = f.address();
+    let stores = f.address();
+    let outsider = f.address();
+    f.client.link_subsidiary(&holding, &retail);
+    f.client.link_subsidiary(&retail, &stores);
+    assert_eq!(
+        f.client.get_subsidiaries(&holding),
+        vec![&f.env, retail.clone()]
+    );
+    assert_eq!(f.client.get_parent_employer(&stores), Some(retail.clone()));
+
+    // An employer has one parent, and links cannot form a cycle
+    assert_eq!(
+        f.client.try_link_subsidiary(&outsider, &stores),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client.try_link_subsidiary(&stores, &holding),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    let employee = f.address();
+    let own = f
+        .payroll()
+        .employer(&holding)
+        .employee(&f.address(), 50)
+        .build();
+    let payroll = f
+        .payroll()
+        .employer(&stores)
+        .employee(&employee, 100)
+        .employee(&f.address(), 200)
+        .build();
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &payroll, &0);
+
+    let liability = f.client.get_group_liability(&holding);
+    assert_eq!(liability.len(), 1);
+    let entry = liability.get(0).unwrap();
+    // 500 + 3_000 escrowed, less the 100 claimed
+    assert_eq!(entry.escrow_balance, 3_400);
+    assert_eq!(entry.next_1_period, 350);
+    // A subsidiary's view covers only what is below it
+    assert_eq!(
+        f.client
+            .get_group_liability(&retail)
+            .get(0)
+            .unwrap()
+            .escrow_balance,
+        2_900
+    );
+    assert_eq!(
+        f.client.get_group_metrics(&holding),
+        GroupMetrics {
+            employers: 3,
+            agreements: 2,
+            active_agreements: 2,
+            employees: 3,
+            paid: map![&f.env, (f.token.address.clone(), 100)],
+        }
+    );
+
+    // Parents at any level and their operators can operate the payroll
+    f.client.pause_subsidiary_agreement(&holding, &payroll);
+    assert_eq!(
+        f.client.get_agreement(&payroll).unwrap().status,
+        AgreementStatus::Paused
+    );
+    let operator = f.address();
+    assert_eq!(
+        f.client
+            .try_resume_subsidiary_agreement(&operator, &payroll),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    f.client.set_group_operator(&retail, &operator, &true);
+    f.client.resume_subsidiary_agreement(&operator, &payroll);
+    assert_eq!(
+        f.client.get_agreement(&payroll).unwrap().status,
+        AgreementStatus::Active
+    );
+
+    // Subsidiaries cannot operate their parents' payrolls
+    assert_eq!(
+        f.client.try_cancel_subsidiary_agreement(&retail, &own),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+
+    // Unlinking ends the parent's rights
+    f.client.unlink_subsidiary(&stores, &stores);
+    assert_eq!(
+        f.client.try_cancel_subsidiary_agreement(&holding, &payroll),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(f.client.get_group_metrics(&holding).employers, 2);
+}
@@ -0,0 +1,59 @@
+#![cfg(test)]
+
+use stello_pay_contract::{
+    storage::AgreementStatus,
+    testutils::{Fixture, DAY, FIXTURE_START},
+};
+
+#[test]
+fn payroll_and_incentive_fixtures_pay_out_after_a_warp() {
+    let f = Fixture::new();
+    assert_eq!(f.env.ledger().timestamp(), FIXTURE_START);
+    let alice = f.address();
+    let bob = f.address();
+    let id = f
+        .payroll()
+        .employee(&alice, 1_000)
+        .employee(&bob, 400)
+        .funded_periods(5)
+        .build();
+    assert_eq!(f.token.balance(&f.client.address), 5 * 1_400);
+    f.incentive(&alice).amount(250).build();
+    f.incentive(&alice).amount(100).pending().build();
+
+    f.warp(2 * DAY);
+    f.client.claim_payroll(&bob, &id, &1);
+    assert_eq!(f.token.balance(&bob), 2 * 400);
+    let paid = f.client.disburse_total_compensation(&f.employer, &alice);
+    assert_eq!(paid.salary_amount, 2 * 1_000);
+    assert_eq!(paid.bonus_amount, 250);
+    assert_eq!(f.token.balance(&alice), 2 * 1_000 + 250);
+}
+
+#[test]
+fn escrow_agreement_fixture_and_token_helpers() {
+    let f = Fixture::new();
+    let contributor = f.address();
+    let draft = f.agreement(&contributor).inactive().build();
+    assert_eq!(
+        f.client.get_agreement(&draft).unwrap().status,
+        AgreementStatus::Created
+    );
+
+    let id = f
+        .agreement(&contributor)
+        .amount_per_period(300)
+        .num_periods(2)
+        .build();
+    f.warp_to(FIXTURE_START + 3 * DAY);
+    f.client.claim_time_based(&id);
+    assert_eq!(f.token.balance(&contributor), 2 * 300);
+
+    let spender = f.address();
+    f.token.mint(&contributor, 50);
+    f.token.approve(&contributor, &spender, 40);
+    f.token
+        .client
+        .transfer_from(&spender, &contributor, &spender, &40);
+    assert_eq!(f.token.balance(&spender), 40);
+}
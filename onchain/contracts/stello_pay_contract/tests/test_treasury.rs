@@ -0,0 +1,259 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env,
+};
+use stello_pay_contract::{
+    storage::PayrollError,
+    treasury::{SpendProposal, TreasuryBalance, TREASURY_TIMELOCK_SECONDS},
+    PayrollContract, PayrollContractClient,
+};
+use stellopay_interfaces::treasury::ApprovedSpend;
+
+/// Stand-in for the governance contract's record of executed spend proposals.
+#[contract]
+pub struct MockGovernance;
+
+#[contractimpl]
+impl MockGovernance {
+    pub fn approve_spend(env: Env, proposal_id: u128, spend: ApprovedSpend) {
+        env.storage().persistent().set(&proposal_id, &spend);
+    }
+
+    pub fn get_approved_spend(env: Env, proposal_id: u128) -> Option<ApprovedSpend> {
+        env.storage().persistent().get(&proposal_id)
+    }
+}
+
+struct Setup {
+    env: Env,
+    client: PayrollContractClient<'static>,
+    governance: MockGovernanceClient<'static>,
+    owner: Address,
+    payer: Address,
+    token: Address,
+}
+
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PayrollContract, ());
+    let client = PayrollContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    client.initialize(&owner);
+    let payer = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    StellarAssetClient::new(&env, &token).mint(&payer, &10_000);
+    let governance = MockGovernanceClient::new(&env, &env.register(MockGovernance, ()));
+    client.set_treasury_governance(&owner, &governance.address);
+
+    Setup {
+        env,
+        client,
+        governance,
+        owner,
+        payer,
+        token,
+    }
+}
+
+fn advance(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|l| l.timestamp += seconds);
+}
+
+/// Records governance proposal `proposal_id` as approving a spend from the
+/// payroll treasury.
+fn approve(s: &Setup, proposal_id: u128, to: &Address, amount: i128) {
+    s.governance.approve_spend(
+        &proposal_id,
+        &ApprovedSpend {
+            treasury: s.client.address.clone(),
+            token: s.token.clone(),
+            to: to.clone(),
+            amount,
+        },
+    );
+}
+
+#[test]
+fn deposits_accumulate_per_token() {
+    let s = setup();
+    assert_eq!(s.client.get_treasury_balances(), vec![&s.env]);
+    assert_eq!(
+        s.client.try_deposit_to_treasury(&s.payer, &s.token, &0),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    let other = s
+        .env
+        .register_stellar_asset_contract_v2(Address::generate(&s.env))
+        .address();
+    StellarAssetClient::new(&s.env, &other).mint(&s.payer, &500);
+
+    s.client.deposit_to_treasury(&s.payer, &s.token, &1_000);
+    s.client.deposit_to_treasury(&s.payer, &other, &500);
+    s.client.deposit_to_treasury(&s.payer, &s.token, &250);
+
+    assert_eq!(
+        s.client.get_treasury_balances(),
+        vec![
+            &s.env,
+            TreasuryBalance {
+                token: s.token.clone(),
+                amount: 1_250,
+            },
+            TreasuryBalance {
+                token: other,
+                amount: 500,
+            },
+        ]
+    );
+    assert_eq!(
+        TokenClient::new(&s.env, &s.token).balance(&s.client.address),
+        1_250
+    );
+}
+
+#[test]
+fn spend_needs_governance_approval() {
+    let s = setup();
+    s.client.deposit_to_treasury(&s.payer, &s.token, &1_000);
+    let recipient = Address::generate(&s.env);
+
+    assert_eq!(
+        s.client.try_propose_spend(&s.owner, &1),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+
+    // A spend approved for another contract's treasury does not count
+    s.governance.approve_spend(
+        &1,
+        &ApprovedSpend {
+            treasury: Address::generate(&s.env),
+            token: s.token.clone(),
+            to: recipient.clone(),
+            amount: 400,
+        },
+    );
+    assert_eq!(
+        s.client.try_propose_spend(&s.owner, &1),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+
+    approve(&s, 2, &recipient, 400);
+    s.client.propose_spend(&s.owner, &2);
+    assert_eq!(
+        s.client.try_propose_spend(&s.owner, &2),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn spend_executes_only_after_timelock() {
+    let s = setup();
+    s.client.deposit_to_treasury(&s.payer, &s.token, &1_000);
+    let recipient = Address::generate(&s.env);
+    approve(&s, 7, &recipient, 400);
+
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_propose_spend(&stranger, &7),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    s.client.propose_spend(&s.owner, &7);
+    assert_eq!(
+        s.client.get_spend_proposal(&7),
+        Some(SpendProposal {
+            id: 7,
+            token: s.token.clone(),
+            to: recipient.clone(),
+            amount: 400,
+            executable_at: s.env.ledger().timestamp() + TREASURY_TIMELOCK_SECONDS,
+        })
+    );
+
+    advance(&s.env, TREASURY_TIMELOCK_SECONDS - 1);
+    assert_eq!(
+        s.client.try_execute_spend(&s.owner, &7),
+        Err(Ok(PayrollError::TimelockActive))
+    );
+
+    advance(&s.env, 1);
+    assert_eq!(
+        s.client.try_execute_spend(&stranger, &7),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    s.client.execute_spend(&s.owner, &7);
+
+    assert_eq!(TokenClient::new(&s.env, &s.token).balance(&recipient), 400);
+    assert_eq!(s.client.get_treasury_balances().get(0).unwrap().amount, 600);
+    assert_eq!(s.client.get_spend_proposal(&7), None);
+    assert_eq!(
+        s.client.try_execute_spend(&s.owner, &7),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    // An approved spend is paid once
+    assert_eq!(
+        s.client.try_propose_spend(&s.owner, &7),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn spend_cannot_exceed_treasury_balance() {
+    let s = setup();
+    s.client.deposit_to_treasury(&s.payer, &s.token, &100);
+    approve(&s, 1, &Address::generate(&s.env), 300);
+    s.client.propose_spend(&s.owner, &1);
+
+    advance(&s.env, TREASURY_TIMELOCK_SECONDS);
+    assert_eq!(
+        s.client.try_execute_spend(&s.owner, &1),
+        Err(Ok(PayrollError::InsufficientEscrowBalance))
+    );
+
+    // Income arriving during the timelock makes the spend executable
+    s.client.deposit_to_treasury(&s.payer, &s.token, &200);
+    s.client.execute_spend(&s.owner, &1);
+    assert_eq!(s.client.get_treasury_balances().get(0).unwrap().amount, 0);
+}
+
+#[test]
+fn guardians_can_veto_pending_spend() {
+    let s = setup();
+    let guardian = Address::generate(&s.env);
+    s.client
+        .set_emergency_guardians(&vec![&s.env, guardian.clone()]);
+    s.client.deposit_to_treasury(&s.payer, &s.token, &1_000);
+    approve(&s, 1, &Address::generate(&s.env), 1_000);
+    s.client.propose_spend(&s.owner, &1);
+
+    assert_eq!(
+        s.client.try_cancel_spend(&Address::generate(&s.env), &1),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    s.client.cancel_spend(&guardian, &1);
+    assert_eq!(s.client.get_spend_proposal(&1), None);
+
+    // A vetoed spend cannot be queued again
+    assert_eq!(
+        s.client.try_propose_spend(&s.owner, &1),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    advance(&s.env, TREASURY_TIMELOCK_SECONDS);
+    assert_eq!(
+        s.client.try_execute_spend(&s.owner, &1),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        s.client.get_treasury_balances().get(0).unwrap().amount,
+        1_000
+    );
+}
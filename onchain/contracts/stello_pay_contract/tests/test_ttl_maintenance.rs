@@ -0,0 +1,146 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{storage::Instance as _, storage::Persistent as _, Address as _, Ledger},
+    Address, Env,
+};
+use stello_pay_contract::{
+    storage::{
+        PayrollError, StorageKey, PERSISTENT_BUMP_AMOUNT, PERSISTENT_TTL_THRESHOLD,
+        TEMPORARY_TTL_LEDGERS,
+    },
+    PayrollContract, PayrollContractClient,
+};
+
+const ONE_WEEK: u64 = 604_800;
+const SALARY: i128 = 1_000;
+
+struct Setup {
+    env: Env,
+    client: PayrollContractClient<'static>,
+    owner: Address,
+    employer: Address,
+    token: Address,
+}
+
+/// Every entry starts with a full TTL so the test controls when it nears
+/// expiry; `max_entry_ttl` leaves headroom above the bump target.
+fn setup() -> Setup {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| {
+        li.sequence_number = 1;
+        li.min_persistent_entry_ttl = PERSISTENT_BUMP_AMOUNT;
+        li.max_entry_ttl = PERSISTENT_BUMP_AMOUNT + 100_000;
+    });
+
+    let contract_id = env.register(PayrollContract, ());
+    let client = PayrollContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    client.initialize(&owner);
+    let employer = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    Setup {
+        env,
+        client,
+        owner,
+        employer,
+        token,
+    }
+}
+
+fn advance_until_near_expiry(env: &Env) {
+    env.ledger()
+        .with_mut(|li| li.sequence_number += PERSISTENT_BUMP_AMOUNT - 1_000);
+}
+
+fn persistent_ttl(s: &Setup, key: &StorageKey) -> u32 {
+    s.env.as_contract(&s.client.address, || {
+        s.env.storage().persistent().get_ttl(key)
+    })
+}
+
+#[test]
+fn bump_restores_agreement_and_index_entries() {
+    let s = setup();
+    let employee = Address::generate(&s.env);
+    let id = s
+        .client
+        .create_payroll_agreement(&s.employer, &s.token, &ONE_WEEK);
+    s.client.add_employee_to_agreement(&id, &employee, &SALARY);
+
+    advance_until_near_expiry(&s.env);
+    let keys = [
+        StorageKey::Agreement(id),
+        StorageKey::AgreementEmployees(id),
+        StorageKey::EmployerAgreements(s.employer.clone()),
+        StorageKey::EmployeeAgreements(employee.clone()),
+    ];
+    for key in keys.iter() {
+        assert!(persistent_ttl(&s, key) < PERSISTENT_TTL_THRESHOLD);
+    }
+
+    assert_eq!(
+        s.client.bump_employer_entries(&s.owner, &s.employer, &10),
+        1
+    );
+    for key in keys.iter() {
+        assert_eq!(persistent_ttl(&s, key), PERSISTENT_BUMP_AMOUNT);
+    }
+    let instance_ttl = s
+        .env
+        .as_contract(&s.client.address, || s.env.storage().instance().get_ttl());
+    assert_eq!(instance_ttl, PERSISTENT_BUMP_AMOUNT);
+}
+
+#[test]
+fn bump_resumes_from_cursor_and_wraps() {
+    let s = setup();
+    for _ in 0..3 {
+        s.client
+            .create_payroll_agreement(&s.employer, &s.token, &ONE_WEEK);
+    }
+
+    assert_eq!(s.client.bump_employer_entries(&s.owner, &s.employer, &2), 2);
+    assert_eq!(s.client.bump_employer_entries(&s.owner, &s.employer, &2), 1);
+    assert_eq!(s.client.bump_employer_entries(&s.owner, &s.employer, &2), 2);
+    let stranger = Address::generate(&s.env);
+    assert_eq!(s.client.bump_employer_entries(&s.owner, &stranger, &2), 0);
+}
+
+#[test]
+fn lapsed_cursor_restarts_the_sweep() {
+    let s = setup();
+    for _ in 0..3 {
+        s.client
+            .create_payroll_agreement(&s.employer, &s.token, &ONE_WEEK);
+    }
+    assert_eq!(s.client.bump_employer_entries(&s.owner, &s.employer, &2), 2);
+
+    s.env
+        .ledger()
+        .with_mut(|li| li.sequence_number += TEMPORARY_TTL_LEDGERS + 1);
+    assert_eq!(s.client.bump_employer_entries(&s.owner, &s.employer, &2), 2);
+}
+
+#[test]
+fn bump_requires_owner_and_bounded_limit() {
+    let s = setup();
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.client
+            .try_bump_employer_entries(&stranger, &s.employer, &1),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        s.client
+            .try_bump_employer_entries(&s.owner, &s.employer, &0),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        s.client
+            .try_bump_employer_entries(&s.owner, &s.employer, &21),
+        Err(Ok(PayrollError::BatchTooLarge))
+    );
+}
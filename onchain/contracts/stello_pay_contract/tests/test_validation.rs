@@ -0,0 +1,141 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, String, Vec};
+use stello_pay_contract::{
+    storage::PayrollError,
+    testutils::Fixture,
+    validation::{self, ValidationError, MAX_AMOUNT, MAX_DURATION_SECONDS, MAX_ITEMS},
+};
+
+fn long_string(env: &Env) -> String {
+    String::from_str(env, &"x".repeat(257))
+}
+
+fn addresses(env: &Env, n: u32) -> Vec<Address> {
+    let mut items = Vec::new(env);
+    for _ in 0..n {
+        items.push_back(Address::generate(env));
+    }
+    items
+}
+
+#[test]
+fn checks_accept_bounds_and_map_to_contract_errors() {
+    let env = Env::default();
+    assert_eq!(validation::amount(1), Ok(()));
+    assert_eq!(validation::amount(MAX_AMOUNT), Ok(()));
+    assert_eq!(
+        validation::amount(0),
+        Err(ValidationError::AmountNotPositive)
+    );
+    assert_eq!(
+        validation::amount(MAX_AMOUNT + 1),
+        Err(ValidationError::AmountTooLarge)
+    );
+    assert_eq!(validation::max_amount(-5), Ok(()));
+    assert_eq!(validation::interval(10, 10, 20), Ok(()));
+    assert_eq!(
+        validation::interval(21, 10, 20),
+        Err(ValidationError::IntervalOutOfRange)
+    );
+    assert_eq!(validation::duration(MAX_DURATION_SECONDS), Ok(()));
+    assert_eq!(
+        validation::duration(u64::MAX),
+        Err(ValidationError::IntervalOutOfRange)
+    );
+    assert_eq!(
+        validation::string(&String::from_str(&env, &"x".repeat(256))),
+        Ok(())
+    );
+    assert_eq!(
+        validation::string(&long_string(&env)),
+        Err(ValidationError::StringTooLong)
+    );
+    assert_eq!(validation::items(&addresses(&env, MAX_ITEMS)), Ok(()));
+    assert_eq!(
+        validation::items(&addresses(&env, MAX_ITEMS + 1)),
+        Err(ValidationError::TooManyItems)
+    );
+
+    assert_eq!(
+        PayrollError::from(ValidationError::AmountTooLarge),
+        PayrollError::InvalidData
+    );
+    assert_eq!(
+        PayrollError::from(ValidationError::TooManyItems),
+        PayrollError::BatchTooLarge
+    );
+}
+
+#[test]
+fn entrypoints_reject_out_of_range_amounts() {
+    let f = Fixture::new();
+    let contributor = f.address();
+    assert_eq!(
+        f.client.try_create_escrow_agreement(
+            &f.employer,
+            &contributor,
+            &f.token.address,
+            &(MAX_AMOUNT + 1),
+            &86_400,
+            &1,
+        ),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client
+            .try_deposit_to_treasury(&f.employer, &f.token.address, &i128::MAX),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client
+            .try_convert_currency(&f.token.address, &f.token.address, &i128::MAX),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    let id = f.agreement(&contributor).build();
+    let arbiter = f.address();
+    f.client.set_arbiter(&f.owner, &arbiter);
+    f.client.raise_dispute(&f.employer, &id);
+    assert_eq!(
+        f.client
+            .try_resolve_dispute(&arbiter, &id, &i128::MAX, &i128::MAX),
+        Err(Ok(PayrollError::InvalidData))
+    );
+}
+
+#[test]
+fn entrypoints_reject_long_strings_durations_and_vectors() {
+    let f = Fixture::new();
+    let contributor = f.address();
+    let id = f
+        .client
+        .create_milestone_agreement(&f.employer, &contributor, &f.token.address);
+    f.client.add_milestone(&id, &100);
+    assert_eq!(
+        f.client.try_reject_milestone(&id, &1, &long_string(&f.env)),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client
+            .try_emergency_pause_with_terms(&None, &Some(long_string(&f.env))),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    let guardians = addresses(&f.env, MAX_ITEMS + 1);
+    assert_eq!(
+        f.client.try_set_emergency_guardians(&guardians),
+        Err(Ok(PayrollError::BatchTooLarge.into()))
+    );
+    let guardian = f.address();
+    f.client
+        .set_emergency_guardians(&vec![&f.env, guardian.clone()]);
+    assert_eq!(
+        f.client.try_propose_emergency_pause(&guardian, &u64::MAX),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client.try_get_payrolls(&guardians),
+        Err(Ok(PayrollError::BatchTooLarge))
+    );
+}
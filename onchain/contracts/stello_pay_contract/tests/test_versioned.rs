@@ -0,0 +1,209 @@
+#![cfg(test)]
+
+use soroban_sdk::{contracttype, vec, Address};
+use stello_pay_contract::{
+    storage::{Agreement, PayrollError, StorageKey},
+    testutils::{Fixture, DAY},
+    versioned::{self, MigrationProgress, Versioned},
+};
+
+/// Mirrors the module's private key so tests can rewrite version entries.
+#[contracttype]
+#[derive(Clone)]
+enum VersionedKey {
+    Schema(StorageKey),
+}
+
+/// Drops agreement `id`'s version entry, as if it predated versioning.
+fn store_legacy(f: &Fixture, id: u128) {
+    f.env.as_contract(&f.client.address, || {
+        let key = VersionedKey::Schema(StorageKey::Agreement(id));
+        f.env.storage().persistent().remove(&key);
+    });
+}
+
+fn stored_version(f: &Fixture, id: u128) -> Option<u32> {
+    f.env.as_contract(&f.client.address, || {
+        versioned::stored_version(&f.env, &StorageKey::Agreement(id))
+    })
+}
+
+#[test]
+fn new_agreements_are_written_at_the_current_schema_version() {
+    let f = Fixture::new();
+    let id = f.agreement(&f.address()).build();
+    assert_eq!(stored_version(&f, id), Some(Agreement::SCHEMA_VERSION));
+    assert_eq!(stored_version(&f, id + 1), None);
+}
+
+#[test]
+fn legacy_agreement_is_upgraded_and_rewritten_on_read() {
+    let f = Fixture::new();
+    let contributor = f.address();
+    let id = f.agreement(&contributor).amount_per_period(700).build();
+    store_legacy(&f, id);
+    assert_eq!(stored_version(&f, id), Some(0));
+
+    let agreement = f.client.get_agreement(&id).unwrap();
+    assert_eq!(agreement.id, id);
+    assert_eq!(agreement.amount_per_period, Some(700));
+    assert_eq!(stored_version(&f, id), Some(Agreement::SCHEMA_VERSION));
+}
+
+#[test]
+fn record_from_a_newer_release_is_rejected() {
+    let f = Fixture::new();
+    let id = f.agreement(&f.address()).build();
+    f.env.as_contract(&f.client.address, || {
+        let key = VersionedKey::Schema(StorageKey::Agreement(id));
+        f.env
+            .storage()
+            .persistent()
+            .set(&key, &(Agreement::SCHEMA_VERSION + 1));
+    });
+    assert!(matches!(
+        f.client.try_get_agreement(&id),
+        Err(Ok(err)) if err == PayrollError::InvalidData.into()
+    ));
+}
+
+#[test]
+fn migrate_batch_converts_legacy_agreements_in_bounded_steps() {
+    let f = Fixture::new();
+    let ids: std::vec::Vec<u128> = (0..5).map(|_| f.agreement(&f.address()).build()).collect();
+    for id in &ids[..4] {
+        store_legacy(&f, *id);
+    }
+
+    assert_eq!(
+        f.client.try_migrate_batch(&f.owner, &0),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client.try_migrate_batch(&f.owner, &21),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    assert_eq!(
+        f.client.migrate_batch(&f.owner, &3),
+        MigrationProgress {
+            scanned: 3,
+            migrated: 3,
+            next_id: ids[3],
+            complete: false,
+        }
+    );
+    assert_eq!(stored_version(&f, ids[3]), Some(0));
+    assert_eq!(
+        f.client.migrate_batch(&f.owner, &3),
+        MigrationProgress {
+            scanned: 2,
+            migrated: 1,
+            next_id: ids[4] + 1,
+            complete: true,
+        }
+    );
+    for id in &ids {
+        assert_eq!(stored_version(&f, *id), Some(Agreement::SCHEMA_VERSION));
+    }
+    assert_eq!(f.client.migrate_batch(&f.owner, &3).scanned, 0);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn migrate_batch_requires_upgrade_admin() {
+    let f = Fixture::new();
+    f.client.migrate_batch(&f.employer, &1);
+}
+
+#[test]
+fn migrate_batch_visits_agreements_created_with_a_nonce() {
+    let f = Fixture::new();
+    let sequential = f.agreement(&f.address()).build();
+    let nonced: std::vec::Vec<u128> = (0..2u64)
+        .map(|nonce| {
+            f.client
+                .create_nonced_payroll_agreement(&f.employer, &f.token.address, &DAY, &nonce)
+        })
+        .collect();
+    for id in [sequential, nonced[0], nonced[1]] {
+        store_legacy(&f, id);
+    }
+
+    assert_eq!(
+        f.client.migrate_batch(&f.owner, &2),
+        MigrationProgress {
+            scanned: 2,
+            migrated: 2,
+            next_id: nonced[1],
+            complete: false,
+        }
+    );
+    assert_eq!(stored_version(&f, nonced[1]), Some(0));
+    assert_eq!(
+        f.client.migrate_batch(&f.owner, &2),
+        MigrationProgress {
+            scanned: 1,
+            migrated: 1,
+            next_id: sequential + 1,
+            complete: true,
+        }
+    );
+    for id in nonced {
+        assert_eq!(stored_version(&f, id), Some(Agreement::SCHEMA_VERSION));
+    }
+}
+
+/// Drops `employee`'s agreement index, as if it predated the index.
+fn drop_employee_index(f: &Fixture, employee: &Address) {
+    f.env.as_contract(&f.client.address, || {
+        let key = StorageKey::EmployeeAgreements(employee.clone());
+        f.env.storage().persistent().remove(&key);
+    });
+}
+
+#[test]
+fn migrate_employee_index_backfills_unsettled_agreements() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let settled = f
+        .payroll()
+        .employee(&employee, 100)
+        .grace_period_seconds(DAY)
+        .build();
+    let live = f.payroll().employee(&employee, 100).build();
+    let escrow = f.agreement(&employee).build();
+
+    // Finalizing a cancellation takes the agreement out of the index
+    f.client.cancel_agreement(&settled);
+    f.warp(DAY + 1);
+    f.client.finalize_grace_period(&settled);
+    assert_eq!(
+        f.client.get_employee_agreements(&employee),
+        vec![&f.env, live, escrow]
+    );
+
+    drop_employee_index(&f, &employee);
+    assert_eq!(
+        f.client.migrate_employee_index(&f.owner, &2),
+        MigrationProgress {
+            scanned: 2,
+            migrated: 1,
+            next_id: escrow,
+            complete: false,
+        }
+    );
+    assert_eq!(
+        f.client.migrate_employee_index(&f.owner, &2),
+        MigrationProgress {
+            scanned: 1,
+            migrated: 1,
+            next_id: escrow + 1,
+            complete: true,
+        }
+    );
+    assert_eq!(
+        f.client.get_employee_agreements(&employee),
+        vec![&f.env, live, escrow]
+    );
+}
@@ -0,0 +1,198 @@
+#![cfg(test)]
+
+use soroban_sdk::{vec, Address, Vec};
+use stello_pay_contract::{
+    storage::{AgreementStatus, PayrollError},
+    testutils::{Fixture, DAY},
+    wallet::Payroll,
+};
+
+struct Setup {
+    f: Fixture<'static>,
+    alice: Address,
+    bob: Address,
+    alice_payroll: u128,
+    bob_payroll: u128,
+    start: u64,
+}
+
+/// Payroll agreements paying Alice 100 and Bob 250 per period, both
+/// activated at `start`.
+fn setup() -> Setup {
+    let f = Fixture::new();
+    let alice = f.address();
+    let bob = f.address();
+    let alice_payroll = f.payroll().employee(&alice, 100).build();
+    let bob_payroll = f.payroll().employee(&bob, 250).build();
+
+    Setup {
+        start: f.env.ledger().timestamp(),
+        f,
+        alice,
+        bob,
+        alice_payroll,
+        bob_payroll,
+    }
+}
+
+#[test]
+fn roster_views_answer_per_employee_in_order() {
+    let s = setup();
+    let stranger = s.f.address();
+    let roster = vec![&s.f.env, s.alice.clone(), stranger, s.bob.clone()];
+    s.f.warp(2 * DAY + DAY / 2);
+
+    let payrolls = s.f.client.get_payrolls(&roster);
+    assert_eq!(
+        payrolls.get(0).unwrap(),
+        Some(Payroll {
+            agreement_id: s.alice_payroll,
+            employee_index: 0,
+            employer: s.f.employer.clone(),
+            token: s.f.token.address.clone(),
+            status: AgreementStatus::Active,
+            salary_per_period: Some(100),
+            period_seconds: DAY,
+            claimed_periods: 0,
+            activated_at: s.start,
+        })
+    );
+    assert_eq!(payrolls.get(1).unwrap(), None);
+    assert_eq!(
+        payrolls.get(2).unwrap().unwrap().agreement_id,
+        s.bob_payroll
+    );
+
+    assert_eq!(
+        s.f.client.get_claimable_balances(&roster),
+        vec![&s.f.env, 200, 0, 500]
+    );
+    let next = s.start + 3 * DAY;
+    assert_eq!(
+        s.f.client.get_next_payout_times(&roster),
+        vec![&s.f.env, Some(next), None, Some(next)]
+    );
+
+    // Claiming empties the balance and advances the position
+    s.f.client.claim_payroll(&s.alice, &s.alice_payroll, &0);
+    assert_eq!(
+        s.f.client.get_claimable_balances(&roster),
+        vec![&s.f.env, 0, 0, 500]
+    );
+    assert_eq!(
+        s.f.client
+            .get_payrolls(&roster)
+            .get(0)
+            .unwrap()
+            .unwrap()
+            .claimed_periods,
+        2
+    );
+}
+
+#[test]
+fn paused_and_cancelled_positions_stop_accruing() {
+    let s = setup();
+    let roster = vec![&s.f.env, s.alice.clone(), s.bob.clone()];
+    s.f.warp(2 * DAY + DAY / 2);
+
+    s.f.client.pause_agreement(&s.alice_payroll);
+    assert_eq!(
+        s.f.client
+            .get_payrolls(&roster)
+            .get(0)
+            .unwrap()
+            .unwrap()
+            .status,
+        AgreementStatus::Paused
+    );
+    assert_eq!(
+        s.f.client.get_claimable_balances(&roster),
+        vec![&s.f.env, 0, 500]
+    );
+
+    // Bob's grace period outlasts his next period, then it does not
+    s.f.client.cancel_agreement(&s.bob_payroll);
+    assert_eq!(
+        s.f.client.get_next_payout_times(&roster),
+        vec![&s.f.env, None, Some(s.start + 3 * DAY)]
+    );
+    s.f.warp(DAY * 3 / 4);
+    assert_eq!(
+        s.f.client.get_next_payout_times(&roster),
+        vec![&s.f.env, None, None]
+    );
+    assert_eq!(
+        s.f.client.get_claimable_balances(&roster),
+        vec![&s.f.env, 0, 750]
+    );
+
+    let mut too_many = Vec::new(&s.f.env);
+    for _ in 0..21 {
+        too_many.push_back(s.alice.clone());
+    }
+    assert_eq!(
+        s.f.client.try_get_payrolls(&too_many),
+        Err(Ok(PayrollError::BatchTooLarge))
+    );
+}
+
+#[test]
+fn employees_can_hold_concurrent_payrolls() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let main = f.payroll().employee(&employee, 100).build();
+    let side = f
+        .payroll()
+        .employee(&f.address(), 300)
+        .employee(&employee, 50)
+        .build();
+    f.warp(DAY);
+
+    assert_eq!(
+        f.client.get_employee_agreements(&employee),
+        vec![&f.env, main, side]
+    );
+    let position = f.client.get_payroll(&employee, &side).unwrap();
+    assert_eq!(
+        (position.employee_index, position.salary_per_period),
+        (1, Some(50))
+    );
+    assert_eq!(
+        f.client
+            .get_payroll(&employee, &main)
+            .unwrap()
+            .salary_per_period,
+        Some(100)
+    );
+    assert_eq!(f.client.get_payroll(&f.address(), &main), None);
+
+    // Each payroll is claimed and tracked on its own
+    f.client.claim_payroll(&employee, &side, &1);
+    assert_eq!(f.token.balance(&employee), 50);
+    assert_eq!(
+        f.client
+            .get_payroll(&employee, &side)
+            .unwrap()
+            .claimed_periods,
+        1
+    );
+    assert_eq!(
+        f.client
+            .get_payroll(&employee, &main)
+            .unwrap()
+            .claimed_periods,
+        0
+    );
+
+    // Finished payrolls drop out of the current positions but stay addressable
+    f.client.cancel_agreement(&main);
+    f.warp(2 * DAY);
+    let current = f.client.get_employee_payrolls(&employee);
+    assert_eq!(current.len(), 1);
+    assert_eq!(current.get(0).unwrap().agreement_id, side);
+    assert_eq!(
+        f.client.get_payroll(&employee, &main).unwrap().status,
+        AgreementStatus::Cancelled
+    );
+}
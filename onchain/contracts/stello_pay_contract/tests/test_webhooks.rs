@@ -0,0 +1,148 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Events as _, vec, Address, BytesN, String, TryFromVal};
+use stello_pay_contract::{
+    storage::PayrollError,
+    testutils::{Fixture, DAY},
+};
+use webhook_contract::{
+    NotificationEvent, RetryConfig, SecurityConfig, SignatureMethod, WebhookRegistration,
+    WebhookRegistry, WebhookRegistryClient,
+};
+
+/// A registry allowing the payroll contract to notify, with one webhook of
+/// the employer subscribed to `event`.
+fn registry<'a>(f: &Fixture<'a>, event: &str) -> WebhookRegistryClient<'a> {
+    let env = &f.env;
+    let registry = WebhookRegistryClient::new(env, &env.register(WebhookRegistry, ()));
+    registry.initialize(&f.owner);
+    registry.set_notifier(&f.owner, &f.client.address, &true);
+    registry.register_webhook(
+        &f.employer,
+        &WebhookRegistration {
+            name: String::from_str(env, "payroll"),
+            description: String::from_str(env, ""),
+            url: String::from_str(env, "https://hooks.example.com"),
+            events: vec![env, String::from_str(env, event)],
+            secret_hash: BytesN::from_array(env, &[1; 32]),
+            retry_config: RetryConfig {
+                max_retries: 3,
+                retry_delay: 5,
+                exponential_backoff: true,
+                max_delay: 60,
+            },
+            security_config: SecurityConfig {
+                signature_method: SignatureMethod::HmacSha256,
+                rate_limit_per_minute: 60,
+                require_tls: true,
+            },
+        },
+    );
+    f.client
+        .set_webhook_registry(&f.owner, &Some(registry.address.clone()));
+    registry
+}
+
+/// Notifications the registry emitted during the last invocation.
+fn notifications(f: &Fixture, registry: &Address) -> Vec<(String, NotificationEvent)> {
+    f.env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract, _, _)| contract == registry)
+        .map(|(_, topics, data)| {
+            (
+                String::try_from_val(&f.env, &topics.get(0).unwrap()).unwrap(),
+                NotificationEvent::try_from_val(&f.env, &data).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn claims_notify_subscribed_employers() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let id = f
+        .payroll()
+        .employee(&employee, 100)
+        .funded_periods(10)
+        .build();
+    let registry = registry(&f, "payroll_claimed");
+
+    f.warp(2 * DAY);
+    f.client.claim_payroll(&employee, &id, &0);
+    let sent = notifications(&f, &registry.address);
+    assert_eq!(sent.len(), 1);
+    let (event_type, event) = &sent[0];
+    assert_eq!(*event_type, String::from_str(&f.env, "payroll_claimed"));
+    assert_eq!(
+        (
+            event.source.clone(),
+            event.subject.clone(),
+            event.reference,
+            event.amount
+        ),
+        (f.client.address.clone(), employee, id, 200)
+    );
+}
+
+#[test]
+fn unsubscribed_or_failing_registries_do_not_block_claims() {
+    let f = Fixture::new();
+    let employee = f.address();
+    let id = f
+        .payroll()
+        .employee(&employee, 100)
+        .funded_periods(10)
+        .build();
+    let registry = registry(&f, "escrow_released");
+
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &id, &0);
+    assert!(notifications(&f, &registry.address).is_empty());
+
+    // A registry that no longer accepts this contract rejects the call
+    registry.set_notifier(&f.owner, &f.client.address, &false);
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &id, &0);
+    assert_eq!(f.token.balance(&employee), 200);
+
+    assert_eq!(
+        f.client
+            .try_set_webhook_registry(&employee, &None)
+            .unwrap_err()
+            .unwrap(),
+        PayrollError::Unauthorized
+    );
+    f.client.set_webhook_registry(&f.owner, &None);
+    assert_eq!(f.client.get_webhook_registry(), None);
+}
+
+#[test]
+fn severance_cover_purchases_notify_subscribed_employers() {
+    let f = Fixture::new();
+    f.client
+        .set_severance_insurance(&f.owner, &f.token.address, &200, &5_000);
+    let funder = f.address();
+    f.token.mint(&funder, 100_000);
+    f.client.fund_severance_pool(&funder, &100_000);
+    let employee = f.address();
+    let id = f.payroll().employee(&employee, 1_000).build();
+    f.token.mint(&f.employer, 1_000);
+    let registry = registry(&f, "insurance_cover_purchased");
+
+    f.client
+        .buy_severance_cover(&f.employer, &id, &employee, &10_000);
+    let sent = notifications(&f, &registry.address);
+    assert_eq!(sent.len(), 1);
+    let (event_type, event) = &sent[0];
+    assert_eq!(
+        *event_type,
+        String::from_str(&f.env, "insurance_cover_purchased")
+    );
+    assert_eq!(
+        (event.subject.clone(), event.reference, event.amount),
+        (employee, id, 5_000)
+    );
+}
@@ -0,0 +1,122 @@
+#![cfg(test)]
+
+use stello_pay_contract::{
+    storage::{AgreementStatus, PayrollError},
+    testutils::{Fixture, DAY},
+    winddown::EmployerWinddown,
+};
+
+#[test]
+fn winddown_funds_final_payouts_then_returns_the_rest() {
+    let f = Fixture::new();
+    let start = f.env.ledger().timestamp();
+    let alice = f.address();
+    let bob = f.address();
+    let contributor = f.address();
+    let underfunded = f.payroll().employee(&alice, 100).funded_periods(1).build();
+    let paused = f.payroll().employee(&bob, 50).build();
+    let escrow = f.agreement(&contributor).build();
+    f.client.pause_agreement(&paused);
+    f.token.mint(&f.employer, 1_000);
+    f.warp(2 * DAY);
+
+    // Alice is owed two periods by the end of her grace period but only one
+    // is escrowed, so the other is pulled from the employer. The escrow
+    // agreement's grace period runs longest.
+    let winddown = f.client.begin_employer_winddown(&f.employer);
+    assert_eq!(
+        winddown,
+        EmployerWinddown {
+            started_at: start + 2 * DAY,
+            final_payout_at: start + 6 * DAY,
+            escrowed: 100,
+            completed_at: None,
+        }
+    );
+    assert_eq!(f.token.balance(&f.employer), 900);
+    for id in [underfunded, paused, escrow] {
+        assert_eq!(
+            f.client.get_agreement(&id).unwrap().status,
+            AgreementStatus::Cancelled
+        );
+    }
+    assert_eq!(
+        f.client.try_begin_employer_winddown(&f.employer),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    assert_eq!(
+        f.client.try_create_escrow_agreement(
+            &f.employer,
+            &contributor,
+            &f.token.address,
+            &100,
+            &DAY,
+            &1
+        ),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+
+    // Employees claim their final pay during the grace period
+    f.client.claim_payroll(&alice, &underfunded, &0);
+    f.client.claim_time_based(&escrow);
+    assert_eq!(f.token.balance(&alice), 200);
+    assert_eq!(f.token.balance(&contributor), 2_000);
+    assert_eq!(
+        f.client.try_complete_employer_winddown(&f.employer),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    f.client.claim_payroll(&bob, &paused, &0);
+    f.warp_to(start + 6 * DAY - 1);
+    f.client.claim_time_based(&escrow);
+    assert_eq!(f.token.balance(&contributor), 4_000);
+
+    // Nothing is left to claim, so anyone can complete it before the grace ends
+    assert_eq!(f.client.complete_employer_winddown(&f.employer), 3);
+    assert_eq!(f.token.balance(&f.employer), 900 + 400);
+    assert_eq!(
+        f.client
+            .get_employer_winddown(&f.employer)
+            .unwrap()
+            .completed_at,
+        Some(start + 6 * DAY - 1)
+    );
+    assert!(f.client.get_employer_agreements(&f.employer).is_empty());
+    assert!(f.client.get_employee_agreements(&alice).is_empty());
+    assert!(f.client.get_employee_agreements(&contributor).is_empty());
+    assert_eq!(
+        f.client.try_complete_employer_winddown(&f.employer),
+        Err(Ok(PayrollError::InvalidData))
+    );
+
+    // The creation freeze is lifted
+    f.client
+        .create_payroll_agreement(&f.employer, &f.token.address, &DAY);
+}
+
+#[test]
+fn winddown_completes_once_grace_periods_end() {
+    let f = Fixture::new();
+    let alice = f.address();
+    let payroll = f.payroll().employee(&alice, 100).build();
+    f.warp(DAY);
+
+    let winddown = f.client.begin_employer_winddown(&f.employer);
+    assert_eq!(winddown.escrowed, 0);
+
+    // Alice never claims; her pay stays with the employer after the grace period
+    f.warp(DAY - 1);
+    assert_eq!(
+        f.client.try_complete_employer_winddown(&f.employer),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    f.warp(1);
+    assert_eq!(f.client.complete_employer_winddown(&f.employer), 1);
+    assert_eq!(f.token.balance(&f.employer), 1_000);
+    assert_eq!(
+        f.client
+            .get_payroll(&alice, &payroll)
+            .unwrap()
+            .claimed_periods,
+        0
+    );
+}
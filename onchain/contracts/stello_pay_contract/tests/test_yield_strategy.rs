@@ -0,0 +1,297 @@
+#![cfg(test)]
+
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Env};
+use stello_pay_contract::{
+    storage::{DataKey, PayrollError},
+    testutils::{Fixture, DAY},
+    yield_strategy::YieldPosition,
+};
+
+#[contracttype]
+#[derive(Clone)]
+enum MockStrategyKey {
+    Asset,
+    Held(Address),
+    Frozen,
+}
+
+/// Vault that pays whatever yield the test accrues to a depositor.
+#[contract]
+pub struct MockStrategy;
+
+#[contractimpl]
+impl MockStrategy {
+    pub fn set_asset(env: Env, asset: Address) {
+        env.storage()
+            .persistent()
+            .set(&MockStrategyKey::Asset, &asset);
+    }
+
+    /// Makes withdrawals fail while `frozen`.
+    pub fn set_frozen(env: Env, frozen: bool) {
+        env.storage()
+            .persistent()
+            .set(&MockStrategyKey::Frozen, &frozen);
+    }
+
+    /// Changes `owner`'s position by `change` without moving tokens.
+    pub fn accrue(env: Env, owner: Address, change: i128) {
+        let held = Self::balance(env.clone(), owner.clone());
+        env.storage()
+            .persistent()
+            .set(&MockStrategyKey::Held(owner), &(held + change));
+    }
+
+    pub fn asset(env: Env) -> Address {
+        env.storage()
+            .persistent()
+            .get(&MockStrategyKey::Asset)
+            .unwrap()
+    }
+
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        token::Client::new(&env, &Self::asset(env.clone())).transfer(
+            &from,
+            env.current_contract_address(),
+            &amount,
+        );
+        Self::accrue(env, from, amount);
+    }
+
+    pub fn withdraw(env: Env, to: Address, amount: i128) {
+        to.require_auth();
+        assert!(!env
+            .storage()
+            .persistent()
+            .get(&MockStrategyKey::Frozen)
+            .unwrap_or(false));
+        assert!(Self::balance(env.clone(), to.clone()) >= amount);
+        Self::accrue(env.clone(), to.clone(), -amount);
+        token::Client::new(&env, &Self::asset(env.clone())).transfer(
+            &env.current_contract_address(),
+            &to,
+            &amount,
+        );
+    }
+
+    pub fn balance(env: Env, owner: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&MockStrategyKey::Held(owner))
+            .unwrap_or(0)
+    }
+}
+
+/// Stand-in for the governance contract's strategy allow-list.
+#[contract]
+pub struct MockGovernance;
+
+#[contractimpl]
+impl MockGovernance {
+    pub fn set_approved(env: Env, strategy: Address, approved: bool) {
+        env.storage().persistent().set(&strategy, &approved);
+    }
+
+    pub fn is_strategy_approved(env: Env, strategy: Address) -> bool {
+        env.storage().persistent().get(&strategy).unwrap_or(false)
+    }
+}
+
+fn setup(f: &Fixture) -> (MockStrategyClient<'static>, MockGovernanceClient<'static>) {
+    let strategy = MockStrategyClient::new(&f.env, &f.env.register(MockStrategy, ()));
+    strategy.set_asset(&f.token.address);
+    let governance = MockGovernanceClient::new(&f.env, &f.env.register(MockGovernance, ()));
+    (strategy, governance)
+}
+
+fn escrow(f: &Fixture, agreement_id: u128) -> i128 {
+    f.env.as_contract(&f.client.address, || {
+        DataKey::get_agreement_escrow_balance(&f.env, agreement_id, &f.token.address)
+    })
+}
+
+#[test]
+fn idle_escrow_earns_yield_and_is_withdrawn_before_payouts() {
+    let f = Fixture::new();
+    let (strategy, governance) = setup(&f);
+    let employee = f.address();
+    // 100 per period, 1_000 in escrow
+    let payroll = f.payroll().employee(&employee, 100).build();
+
+    assert_eq!(
+        f.client
+            .try_deposit_to_strategy(&f.employer, &payroll, &strategy.address, &800),
+        Err(Ok(PayrollError::InvalidData))
+    );
+    f.client
+        .set_strategy_governance(&f.owner, &governance.address);
+    assert_eq!(
+        f.client
+            .try_deposit_to_strategy(&f.employer, &payroll, &strategy.address, &800),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    governance.set_approved(&strategy.address, &true);
+    assert_eq!(
+        f.client
+            .try_deposit_to_strategy(&f.employer, &payroll, &strategy.address, &1_001),
+        Err(Ok(PayrollError::InsufficientEscrowBalance))
+    );
+
+    f.client
+        .deposit_to_strategy(&f.employer, &payroll, &strategy.address, &800);
+    assert_eq!(
+        f.client.get_yield_position(&payroll),
+        Some(YieldPosition {
+            strategy: strategy.address.clone(),
+            token: f.token.address.clone(),
+            principal: 800,
+            shares: 800,
+        })
+    );
+    assert_eq!(f.token.balance(&f.client.address), 200);
+    // The principal is still escrow
+    assert_eq!(escrow(&f, payroll), 1_000);
+
+    f.token.mint(&strategy.address, 80);
+    strategy.accrue(&f.client.address, &80);
+    assert_eq!(f.client.get_yield_position_value(&payroll), 880);
+
+    // The idle 200 covers two periods without touching the strategy
+    f.warp(2 * DAY);
+    f.client.claim_payroll(&employee, &payroll, &0);
+    assert!(f.client.get_yield_position(&payroll).is_some());
+
+    // The next period needs the strategy, so the position is closed first
+    f.warp(DAY);
+    f.client.claim_payroll(&employee, &payroll, &0);
+    assert_eq!(f.client.get_yield_position(&payroll), None);
+    assert_eq!(f.token.balance(&employee), 300);
+    assert_eq!(escrow(&f, payroll), 700);
+
+    // The yield is the employer's, not escrow
+    assert_eq!(f.client.get_earned_yield(&f.employer, &f.token.address), 80);
+    assert_eq!(f.token.balance(&f.client.address), 780);
+    assert_eq!(
+        f.client
+            .withdraw_earned_yield(&f.employer, &f.token.address),
+        80
+    );
+    assert_eq!(f.token.balance(&f.employer), 80);
+    assert_eq!(f.client.get_earned_yield(&f.employer, &f.token.address), 0);
+}
+
+#[test]
+fn agreements_share_a_strategy_position_pro_rata() {
+    let f = Fixture::new();
+    let (strategy, governance) = setup(&f);
+    f.client
+        .set_strategy_governance(&f.owner, &governance.address);
+    governance.set_approved(&strategy.address, &true);
+    let first = f.payroll().employee(&f.address(), 100).build();
+    let other_employer = f.address();
+    let second = f
+        .payroll()
+        .employer(&other_employer)
+        .employee(&f.address(), 100)
+        .build();
+
+    f.client
+        .deposit_to_strategy(&f.employer, &first, &strategy.address, &500);
+    f.client
+        .deposit_to_strategy(&other_employer, &second, &strategy.address, &500);
+    assert_eq!(
+        f.client.try_withdraw_from_strategy(&other_employer, &first),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+
+    // A 10% loss is shared, and taken from each agreement's escrow
+    strategy.accrue(&f.client.address, &-100);
+    assert_eq!(f.client.withdraw_from_strategy(&f.employer, &first), -50);
+    assert_eq!(escrow(&f, first), 950);
+    assert_eq!(f.client.get_yield_position_value(&second), 450);
+
+    // Removing a strategy from the allow-list stops deposits, not exits
+    governance.set_approved(&strategy.address, &false);
+    assert_eq!(
+        f.client
+            .try_deposit_to_strategy(&f.employer, &first, &strategy.address, &100),
+        Err(Ok(PayrollError::Unauthorized))
+    );
+    assert_eq!(
+        f.client.withdraw_from_strategy(&other_employer, &second),
+        -50
+    );
+    assert_eq!(escrow(&f, second), 950);
+    assert_eq!(f.token.balance(&f.client.address), 1_900);
+}
+
+#[test]
+fn failed_strategy_withdrawal_keeps_the_position_and_pays_from_liquid_escrow() {
+    let f = Fixture::new();
+    let (strategy, governance) = setup(&f);
+    f.client
+        .set_strategy_governance(&f.owner, &governance.address);
+    governance.set_approved(&strategy.address, &true);
+    let employee = f.address();
+    let payroll = f.payroll().employee(&employee, 100).build();
+    f.client
+        .deposit_to_strategy(&f.employer, &payroll, &strategy.address, &800);
+    strategy.set_frozen(&true);
+
+    // Only the 200 held here can be paid while the strategy does not pay out
+    f.warp(3 * DAY);
+    assert_eq!(
+        f.client.try_claim_payroll(&employee, &payroll, &0),
+        Err(Ok(PayrollError::InsufficientEscrowBalance))
+    );
+    assert_eq!(
+        f.client.try_withdraw_from_strategy(&f.employer, &payroll),
+        Err(Ok(PayrollError::TransferFailed))
+    );
+    assert_eq!(
+        f.client.get_yield_position(&payroll).unwrap().principal,
+        800
+    );
+    assert_eq!(escrow(&f, payroll), 1_000);
+
+    // Once it pays out again, the position is closed for the claim
+    strategy.set_frozen(&false);
+    f.client.claim_payroll(&employee, &payroll, &0);
+    assert_eq!(f.client.get_yield_position(&payroll), None);
+    assert_eq!(f.token.balance(&employee), 300);
+    assert_eq!(escrow(&f, payroll), 700);
+}
+
+#[test]
+fn refund_releases_a_position_the_strategy_does_not_pay_out() {
+    let f = Fixture::new();
+    let (strategy, governance) = setup(&f);
+    f.client
+        .set_strategy_governance(&f.owner, &governance.address);
+    governance.set_approved(&strategy.address, &true);
+    let payroll = f
+        .payroll()
+        .employee(&f.address(), 100)
+        .grace_period_seconds(DAY)
+        .build();
+    f.client
+        .deposit_to_strategy(&f.employer, &payroll, &strategy.address, &800);
+    strategy.set_frozen(&true);
+
+    // The refund pays what is held here and releases the position
+    f.client.cancel_agreement(&payroll);
+    f.warp(DAY + 1);
+    f.client.finalize_grace_period(&payroll);
+    assert_eq!(f.token.balance(&f.employer), 200);
+    assert_eq!(escrow(&f, payroll), 0);
+
+    // Closing it later pays the employer, yield included
+    f.token.mint(&strategy.address, 40);
+    strategy.accrue(&f.client.address, &40);
+    strategy.set_frozen(&false);
+    assert_eq!(f.client.withdraw_from_strategy(&f.employer, &payroll), 40);
+    assert_eq!(f.token.balance(&f.employer), 1_040);
+    assert_eq!(f.client.get_earned_yield(&f.employer, &f.token.address), 0);
+    assert_eq!(escrow(&f, payroll), 0);
+}
@@ -0,0 +1,13 @@
+//! Agreement types shared by the payroll contract and its integrators.
+
+use soroban_sdk::contracttype;
+
+/// Operating mode for agreements
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AgreementMode {
+    /// Escrow mode for freelance/contract work
+    Escrow,
+    /// Payroll mode for traditional employee payroll
+    Payroll,
+}
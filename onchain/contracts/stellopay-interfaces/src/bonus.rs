@@ -0,0 +1,98 @@
+//! Bonus incentive types, events and client.
+//!
+//! Events carrying token movements are defined here so indexers, the CLI and
+//! other contracts decode the same layout the bonus system publishes. Each is
+//! published with topics `(name, incentive_id)`.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IncentiveKind {
+    OneTime,
+    Recurring,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Cancelled,
+    Completed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Incentive {
+    pub id: u128,
+    pub employer: Address,
+    pub employee: Address,
+    pub approver: Address,
+    pub token: Address,
+    pub kind: IncentiveKind,
+    pub status: ApprovalStatus,
+    pub amount_per_payout: i128,
+    pub total_payouts: u32,
+    pub claimed_payouts: u32,
+    pub start_time: u64,
+    pub interval_seconds: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IncentiveCreatedEvent {
+    pub incentive_id: u128,
+    pub employer: Address,
+    pub employee: Address,
+    pub approver: Address,
+    pub token: Address,
+    pub kind: IncentiveKind,
+    pub escrowed_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IncentiveClaimedEvent {
+    pub incentive_id: u128,
+    pub employee: Address,
+    pub payouts_claimed: u32,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IncentiveCancelledEvent {
+    pub incentive_id: u128,
+    pub employer: Address,
+    pub refunded_amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClawbackExecutedEvent {
+    pub admin: Address,
+    pub employee: Address,
+    pub incentive_id: u128,
+    pub clawback_amount: i128,
+    pub reason_hash: u128,
+}
+
+/// Client for the deployed bonus system contract.
+#[contractclient(name = "BonusSystemClient")]
+pub trait BonusSystemInterface {
+    fn get_incentive(env: Env, incentive_id: u128) -> Option<Incentive>;
+
+    /// Payouts of the incentive vested and not yet claimed.
+    fn get_claimable_payouts(env: Env, incentive_id: u128) -> u32;
+
+    /// Incentives of the employee neither completed nor cancelled.
+    fn get_open_incentives(env: Env, employee: Address) -> Vec<u128>;
+
+    /// Claims every vested payout of the incentive and returns the amount.
+    fn claim_incentive(env: Env, employee: Address, incentive_id: u128) -> i128;
+
+    /// Incentive payouts claimed across all employers.
+    fn get_total_claimed_payouts(env: Env) -> u64;
+}
@@ -0,0 +1,53 @@
+//! Payroll escrow events and client.
+//!
+//! Events carrying token movements are defined here so indexers, the CLI and
+//! other contracts decode the same layout the escrow publishes. Each is
+//! published with topics `(name, agreement_id)`.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+/// Events emitted by the escrow contract
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct FundedEvent {
+    pub agreement_id: u128,
+    pub employer: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReleasedEvent {
+    pub agreement_id: u128,
+    pub to: Address,
+    /// Amount received by `to`, after the protocol fee.
+    pub amount: i128,
+    /// Protocol fee withheld from the release.
+    pub fee: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RefundedEvent {
+    pub agreement_id: u128,
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// Client for the deployed payroll escrow contract.
+#[contractclient(name = "PayrollEscrowClient")]
+pub trait PayrollEscrowInterface {
+    /// Deposits `amount` from `from` into the agreement's escrow. The first
+    /// deposit records `employer` as the agreement's employer.
+    fn fund_agreement(env: Env, from: Address, agreement_id: u128, employer: Address, amount: i128);
+
+    /// Releases `amount` to `to`, less the protocol fee. Manager only.
+    fn release(env: Env, caller: Address, agreement_id: u128, to: Address, amount: i128);
+
+    /// Refunds the remaining balance to the employer. Manager only.
+    fn refund_remaining(env: Env, caller: Address, agreement_id: u128);
+
+    fn get_agreement_balance(env: Env, agreement_id: u128) -> i128;
+
+    fn get_agreement_employer(env: Env, agreement_id: u128) -> Option<Address>;
+}
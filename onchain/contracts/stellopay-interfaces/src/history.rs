@@ -0,0 +1,199 @@
+//! Payment history records, events and client.
+//!
+//! The `payment_history` contract stores one immutable [`PaymentRecord`] per
+//! completed payment and serves paginated queries over them. Callers such as
+//! `compliance_reporting` read it through [`PaymentHistoryClient`].
+
+use soroban_sdk::{contractclient, contractevent, contracttype, Address, BytesN, Env, Vec};
+
+/// Canonical record of a single completed payment.
+///
+/// Once written to storage under `StorageKey::Payment(id)`, this record is
+/// never modified. Immutability is enforced at the contract level: there is no
+/// update or delete code path. Any discrepancy between this record and an
+/// off-chain index is therefore always an off-chain artifact, never a
+/// contract-side rewrite.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentRecord {
+    /// Globally unique payment identifier (1-based, monotonically increasing).
+    /// This is the primary key used by all three indices.
+    pub id: u128,
+
+    /// The employment agreement this payment belongs to.
+    pub agreement_id: u128,
+
+    /// 32-byte reference hash supplied by the payroll contract at record time.
+    ///
+    /// @dev Typically the Stellar transaction hash of the token transfer, so
+    /// indexers and UI clients can deep-link directly to the on-chain transaction
+    /// without recomputing payroll math. The contract stores it verbatim and does
+    /// not verify its content; integrity depends on the trustworthy payroll caller.
+    /// A reverse-lookup index (`StorageKey::PaymentByHash`) enables O(1) queries
+    /// by hash in addition to queries by ID, agreement, employer, and employee.
+    pub payment_hash: BytesN<32>,
+
+    /// Stellar asset contract address of the token transferred.
+    pub token: Address,
+
+    /// Transfer amount in the token's smallest base unit.
+    pub amount: i128,
+
+    /// Employer address that originated the payment.
+    pub from: Address,
+
+    /// Employee address that received the payment.
+    ///
+    /// @dev Replaced by the history contract's own address once the employee
+    /// is pseudonymized; `to_pseudonym` then identifies the payee instead.
+    pub to: Address,
+
+    /// Opaque identifier of a pseudonymized payee, `None` otherwise.
+    pub to_pseudonym: Option<BytesN<32>>,
+
+    /// Unix timestamp (seconds) recorded by the payroll contract at the time
+    /// of the transfer.
+    pub timestamp: u64,
+}
+
+/// Which payments a subscription matches.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SubscriptionFilter {
+    /// Payments made by this employer (`from`).
+    Employer(Address),
+    /// Payments received by this employee (`to`).
+    Employee(Address),
+    /// Payments recorded for this agreement.
+    Agreement(u128),
+}
+
+/// Event emitted every time a payment is successfully recorded.
+///
+/// @notice Off-chain indexers should subscribe to this event to maintain a
+/// real-time mirror of the payment history without querying storage on every
+/// block. Three fields serve as stable lookup keys for different query paths:
+/// `payment_id` (global sequential ID), `payment_hash` (32-byte reference
+/// hash for transaction-level linkage), and `agreement_id` (logical grouping).
+///
+/// @dev Topics: `Symbol("payment_recorded")`
+/// Data: all fields below, in declaration order.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PaymentRecorded {
+    /// Globally unique, monotonically increasing payment identifier.
+    ///
+    /// @dev Starts at 1, increments by 1 per recorded payment. Use this as
+    /// the canonical sequential join key when rebuilding paginated indices
+    /// off-chain. All three index families (agreement, employer, employee)
+    /// dereference to this ID.
+    pub payment_id: u128,
+
+    /// 32-byte reference hash for this payment.
+    ///
+    /// @dev Supplied verbatim by the payroll contract — typically the Stellar
+    /// transaction hash of the underlying token transfer. Indexers can use
+    /// this to deep-link to the on-chain transaction via Horizon or RPC
+    /// without recomputing any payroll math. Also serves as the key for the
+    /// `PaymentByHash` reverse-lookup index.
+    pub payment_hash: BytesN<32>,
+
+    /// The employment agreement this payment belongs to.
+    /// Matches the `agreement_id` stored in the `PaymentRecord`.
+    pub agreement_id: u128,
+
+    /// Stellar asset contract address of the token transferred.
+    pub token: Address,
+
+    /// Transfer amount in the token's smallest base unit (always positive).
+    pub amount: i128,
+
+    /// Employer address (payer / `from` side of the transfer).
+    pub from: Address,
+
+    /// Employee address (payee / `to` side of the transfer).
+    pub to: Address,
+
+    /// Unix timestamp in seconds, as supplied by the payroll contract.
+    /// Used for time-range queries and correlation with ledger close time.
+    pub timestamp: u64,
+}
+
+/// Event emitted once per active subscription matching a recorded payment.
+///
+/// @notice Relayers filter on the `subscription_id` or `subscriber` topic to
+/// receive only the payments they registered for, instead of consuming every
+/// `payment_recorded` event.
+///
+/// @dev Topics: `Symbol("payment_matched")`, `subscription_id`, `subscriber`.
+/// The full record is available through `get_payment_by_id(payment_id)`.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct PaymentMatched {
+    #[topic]
+    pub subscription_id: u64,
+    #[topic]
+    pub subscriber: Address,
+    pub payment_id: u128,
+    pub agreement_id: u128,
+}
+
+/// Event emitted when an employee's payment history is pseudonymized.
+///
+/// @notice Carries only the replacement ID, never the erased address.
+/// Indexers holding the employee's records should re-key them to
+/// `replacement_id` and drop the raw address from their copies.
+///
+/// @dev Topics: `Symbol("employee_pseudonymized")`, `replacement_id`.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct EmployeePseudonymized {
+    #[topic]
+    pub replacement_id: BytesN<32>,
+    /// Number of existing payment records rewritten.
+    pub payments_rewritten: u32,
+}
+
+/// Client for the deployed payment history contract.
+#[contractclient(name = "PaymentHistoryClient")]
+pub trait PaymentHistoryInterface {
+    /// Records a completed payment and returns its id. Only the payroll
+    /// contract registered with the history contract may call this.
+    #[allow(clippy::too_many_arguments)]
+    fn record_payment(
+        env: Env,
+        agreement_id: u128,
+        payment_hash: BytesN<32>,
+        token: Address,
+        amount: i128,
+        from: Address,
+        to: Address,
+        timestamp: u64,
+    ) -> u128;
+
+    fn get_payment_by_id(env: Env, payment_id: u128) -> Option<PaymentRecord>;
+
+    /// Payments of an agreement, from the 1-based `start_index`.
+    fn get_payments_by_agreement(
+        env: Env,
+        agreement_id: u128,
+        start_index: u32,
+        limit: u32,
+    ) -> Vec<PaymentRecord>;
+
+    /// Payments made by an employer, from the 1-based `start_index`.
+    fn get_payments_by_employer(
+        env: Env,
+        employer: Address,
+        start_index: u32,
+        limit: u32,
+    ) -> Vec<PaymentRecord>;
+
+    /// Payments received by an employee, from the 1-based `start_index`.
+    fn get_payments_by_employee(
+        env: Env,
+        employee: Address,
+        start_index: u32,
+        limit: u32,
+    ) -> Vec<PaymentRecord>;
+}
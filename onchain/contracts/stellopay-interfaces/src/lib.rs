@@ -0,0 +1,29 @@
+//! Shared contract types and clients for cross-contract calls.
+//!
+//! Every type another contract, the CLI or an indexer decodes lives here
+//! once, next to a `contractclient` for the contract that publishes it, so
+//! the payroll, escrow, bonus and history contracts no longer keep private
+//! mirrors that silently drift from the original layout. The defining
+//! contracts re-export these types from their old paths.
+//!
+//! Depend on this crate (rlib only) from either side of a call; deploy the
+//! contracts separately — do not link one as a cdylib dependency of another.
+//! The earlier single-purpose interface crates are re-exported under
+//! [`analytics`], [`milestones`], [`rbac`] and [`webhooks`].
+
+#![no_std]
+
+pub mod agreement;
+pub mod bonus;
+pub mod escrow;
+pub mod history;
+pub mod multisig;
+pub mod rate_limiter;
+pub mod salary_adjustment;
+pub mod strategy;
+pub mod treasury;
+
+pub use analytics_interface as analytics;
+pub use milestone_interface as milestones;
+pub use rbac_interface as rbac;
+pub use webhook_interface as webhooks;
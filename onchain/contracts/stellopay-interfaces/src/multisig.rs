@@ -0,0 +1,68 @@
+//! Multisig operation types and client.
+//!
+//! The payroll contract reads approved operations from the multisig before
+//! executing large payments, dispute resolutions and upgrades.
+
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env, Vec};
+
+/// Stable identifiers used to configure per-operation thresholds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OperationType {
+    ContractUpgrade,
+    LargePayment,
+    DisputeResolution,
+}
+
+/// Operation kinds supported by the multisig.
+///
+/// These are intentionally generic so that off-chain automation or
+/// higher-level contracts can interpret and act on approved operations.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OperationKind {
+    /// Multi-sig approval for a contract upgrade.
+    ///
+    /// Tuple layout: (target, new_wasm_hash)
+    ContractUpgrade(Address, BytesN<32>),
+    /// Direct token payment executed from the multisig wallet.
+    ///
+    /// Tuple layout: (token, to, amount)
+    LargePayment(Address, Address, i128),
+    /// Dispute resolution intent for an external payroll-style contract.
+    ///
+    /// Tuple layout: (payroll_contract, agreement_id, pay_employee, refund_employer)
+    DisputeResolution(Address, u128, i128, i128),
+    /// Sets or removes the signer threshold override for an operation type.
+    ///
+    /// Tuple layout: (operation_type, threshold). A `None` threshold removes
+    /// the override and restores the default threshold.
+    SetThresholdOverride(OperationType, Option<u32>),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OperationStatus {
+    Pending,
+    Executed,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Operation {
+    pub id: u128,
+    pub kind: OperationKind,
+    pub creator: Address,
+    pub status: OperationStatus,
+    pub created_at: u64,
+    pub executed_at: Option<u64>,
+}
+
+/// Client for the deployed multisig contract.
+#[contractclient(name = "MultisigClient")]
+pub trait MultisigInterface {
+    fn get_operation(env: Env, operation_id: u128) -> Option<Operation>;
+    fn get_effective_threshold(env: Env, operation_type: OperationType) -> u32;
+    fn get_approvals(env: Env, operation_id: u128) -> Vec<Address>;
+}
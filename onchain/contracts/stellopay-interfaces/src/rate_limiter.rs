@@ -0,0 +1,11 @@
+//! Rate limiter client.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Client for the deployed rate limiter contract.
+#[contractclient(name = "RateLimiterClient")]
+pub trait RateLimiterInterface {
+    /// Consumes one unit of `subject`'s token bucket and returns the tokens
+    /// left. Panics once the bucket is empty.
+    fn check_and_consume(env: Env, subject: Address) -> u32;
+}
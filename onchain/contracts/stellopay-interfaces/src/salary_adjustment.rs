@@ -0,0 +1,11 @@
+//! Salary adjustment client.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Client for the deployed salary adjustment contract.
+#[contractclient(name = "SalaryAdjustmentClient")]
+pub trait SalaryAdjustmentInterface {
+    /// Last applied salary of `employee`; `None` until an adjustment has
+    /// been applied.
+    fn get_employee_salary(env: Env, employee: Address) -> Option<i128>;
+}
@@ -0,0 +1,31 @@
+//! Yield strategy adapter and allow-list clients.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Adapter a yield strategy exposes to the payroll contract, which routes
+/// idle escrow to it.
+///
+/// A strategy holds one asset. Positions are per depositor and valued in
+/// that asset, so the payroll contract can tell principal from yield.
+#[contractclient(name = "YieldStrategyClient")]
+pub trait YieldStrategyInterface {
+    /// Token the strategy accepts and pays out.
+    fn asset(env: Env) -> Address;
+
+    /// Moves `amount` of the asset from `from` into `from`'s position.
+    /// `from` authorizes the token transfer.
+    fn deposit(env: Env, from: Address, amount: i128);
+
+    /// Pays `amount` of the asset out of `to`'s position to `to`. Panics when
+    /// the position is worth less.
+    fn withdraw(env: Env, to: Address, amount: i128);
+
+    /// Current value of `owner`'s position, principal plus yield.
+    fn balance(env: Env, owner: Address) -> i128;
+}
+
+/// Client for the governance contract's yield strategy allow-list.
+#[contractclient(name = "StrategyRegistryClient")]
+pub trait StrategyRegistryInterface {
+    fn is_strategy_approved(env: Env, strategy: Address) -> bool;
+}
@@ -0,0 +1,20 @@
+//! Governance-approved treasury spends and their client.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+/// A treasury transfer approved by an executed governance proposal.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovedSpend {
+    /// Contract whose treasury pays.
+    pub treasury: Address,
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+}
+
+/// Client for the governance contract's record of approved treasury spends.
+#[contractclient(name = "SpendRegistryClient")]
+pub trait SpendRegistryInterface {
+    fn get_approved_spend(env: Env, proposal_id: u128) -> Option<ApprovedSpend>;
+}
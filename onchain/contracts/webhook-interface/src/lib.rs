@@ -0,0 +1,79 @@
+//! Shared webhook notification type and client for cross-contract calls.
+//!
+//! The payroll, bonus, escrow and scheduler contracts notify the `webhook_contract`
+//! registry through this interface instead of keeping any webhook state of
+//! their own. Depend on this crate (rlib only); deploy `webhook_contract`
+//! separately — do not link it as a cdylib dependency.
+
+#![no_std]
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, String};
+
+/// Event types the protocol's contracts notify, for `WebhookNotification`
+/// and webhook subscriptions. Subscribing to `*` covers all of them.
+pub mod event_types {
+    /// Periodic payroll payout. `reference` is the agreement.
+    pub const PAYROLL_CLAIMED: &str = "payroll_claimed";
+    /// Incentive created and escrowed. `reference` is the incentive.
+    pub const INCENTIVE_CREATED: &str = "incentive_created";
+    /// Incentive approved by its approver.
+    pub const INCENTIVE_APPROVED: &str = "incentive_approved";
+    /// Vested incentive payouts claimed.
+    pub const INCENTIVE_CLAIMED: &str = "incentive_claimed";
+    /// Escrow deposit. `reference` is the escrow agreement.
+    pub const ESCROW_FUNDED: &str = "escrow_funded";
+    /// Escrow release to a recipient, net of fees.
+    pub const ESCROW_RELEASED: &str = "escrow_released";
+    /// Remaining escrow refunded to the employer.
+    pub const ESCROW_REFUNDED: &str = "escrow_refunded";
+    /// Severance insurance cover bought. `reference` is the agreement.
+    pub const INSURANCE_COVER_PURCHASED: &str = "insurance_cover_purchased";
+    /// Severance insurance claim settled for the employee.
+    pub const INSURANCE_CLAIM_SETTLED: &str = "insurance_claim_settled";
+    /// Severance insurance cover closed without a claim.
+    pub const INSURANCE_COVER_RELEASED: &str = "insurance_cover_released";
+    /// Pool payouts repaid by the employer. `reference` is zero.
+    pub const INSURANCE_LIABILITY_REPAID: &str = "insurance_liability_repaid";
+    /// Scheduled payment job executed. `reference` is the job.
+    pub const SCHEDULE_EXECUTED: &str = "schedule_executed";
+
+    /// Every event type above.
+    pub const ALL: [&str; 12] = [
+        PAYROLL_CLAIMED,
+        INCENTIVE_CREATED,
+        INCENTIVE_APPROVED,
+        INCENTIVE_CLAIMED,
+        ESCROW_FUNDED,
+        ESCROW_RELEASED,
+        ESCROW_REFUNDED,
+        INSURANCE_COVER_PURCHASED,
+        INSURANCE_CLAIM_SETTLED,
+        INSURANCE_COVER_RELEASED,
+        INSURANCE_LIABILITY_REPAID,
+        SCHEDULE_EXECUTED,
+    ];
+}
+
+/// A payment-related occurrence a webhook owner may subscribe to.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WebhookNotification {
+    /// Address whose webhooks are notified, e.g. the employer.
+    pub owner: Address,
+    /// Event type webhooks subscribe to, e.g. `payroll_claimed`.
+    pub event_type: String,
+    /// Address the occurrence concerns, e.g. the paid employee.
+    pub subject: Address,
+    /// Agreement, incentive or other id in the notifying contract.
+    pub reference: u128,
+    pub amount: i128,
+}
+
+/// Client for the deployed webhook registry, as called by notifying
+/// contracts.
+#[contractclient(name = "WebhookRegistryClient")]
+pub trait WebhookRegistryInterface {
+    /// Emits `notification` for relays when one of the owner's active
+    /// webhooks subscribes to its event type. Returns whether it did.
+    fn notify(env: Env, source: Address, notification: WebhookNotification) -> bool;
+}
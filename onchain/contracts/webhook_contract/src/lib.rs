@@ -0,0 +1,736 @@
+//! Webhook registry contract.
+//!
+//! A standalone registry of webhook endpoints that off-chain relays (see
+//! `stellopay-cli webhook relay`) deliver payment events to. It keeps every
+//! piece of webhook state — endpoints, event filters, secret hashes, quotas
+//! and delivery statistics — so the payroll, bonus, escrow and scheduler
+//! contracts never write webhook storage on their disbursement paths. They
+//! only call [`WebhookRegistry::notify`] through `webhook-interface`, with
+//! one of its `event_types`.
+//!
+//! # Flow
+//!
+//! 1. An owner registers endpoints with [`WebhookRegistry::register_webhook`],
+//!    subscribing each to a list of event types (`*` for all).
+//! 2. A notifying contract, allowed by the admin, calls `notify`. The
+//!    registry emits the notification as an event whose first topic is the
+//!    event type, but only when one of the owner's active webhooks
+//!    subscribes to it. Nothing is written to storage.
+//! 3. The relay reads those events, posts them to the matching endpoints and
+//!    reports each final outcome with
+//!    [`WebhookRegistry::record_webhook_delivery`], which updates delivery
+//!    statistics and the owner's usage counters.
+//!
+//! Signing secrets are never stored: registration takes the SHA-256 hash of
+//! the secret, which the relay keeps locally, and reads never return it.
+
+#![no_std]
+#![allow(deprecated)] // env.events().publish(): the first topic is the subscriber-chosen event type
+
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Bytes, BytesN, Env, String, Vec};
+
+pub use webhook_interface::{event_types, WebhookNotification};
+
+/// Most webhooks an owner can register, whatever its quota.
+pub const MAX_WEBHOOKS_PER_OWNER: u32 = 20;
+
+/// Most event types one webhook can subscribe to.
+pub const MAX_EVENT_TYPES: u32 = 16;
+
+/// Longest accepted endpoint URL, in bytes.
+pub const MAX_URL_LENGTH: u32 = 256;
+
+/// Longest accepted name, description, event type or delivery error, in
+/// bytes.
+pub const MAX_TEXT_LENGTH: u32 = 256;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// Storage keys for the webhook registry.
+#[contracttype]
+#[derive(Clone)]
+enum StorageKey {
+    /// Admin managing notifiers and quotas.
+    Admin,
+    /// Contract allowed to call `notify`: Address -> bool
+    Notifier(Address),
+    /// Next webhook id -> u64
+    NextId,
+    /// Webhook by id -> Webhook
+    Webhook(u64),
+    /// Webhook ids of an owner -> Vec<u64>
+    OwnerWebhooks(Address),
+    /// Delivery statistics of a webhook -> DeliveryStats
+    Deliveries(u64),
+    /// Registry-wide statistics -> WebhookStats
+    Stats,
+    /// Quota applying to owners without their own -> WebhookQuota
+    DefaultQuota,
+    /// Quota of an owner -> WebhookQuota
+    OwnerQuota(Address),
+    /// Event counters of an owner -> OwnerEvents
+    OwnerEvents(Address),
+}
+
+/// How the relay retries a failed delivery.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    /// Seconds before the first retry.
+    pub retry_delay: u64,
+    /// Whether the delay doubles on every retry.
+    pub exponential_backoff: bool,
+    /// Upper bound on the delay, in seconds.
+    pub max_delay: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SignatureMethod {
+    HmacSha256,
+}
+
+/// How deliveries are secured.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SecurityConfig {
+    pub signature_method: SignatureMethod,
+    pub rate_limit_per_minute: u32,
+    /// Whether the URL must be `https://`.
+    pub require_tls: bool,
+}
+
+/// Arguments of [`WebhookRegistry::register_webhook`].
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WebhookRegistration {
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    /// Event types to deliver; `*` subscribes to all.
+    pub events: Vec<String>,
+    /// SHA-256 of the signing secret.
+    pub secret_hash: BytesN<32>,
+    pub retry_config: RetryConfig,
+    pub security_config: SecurityConfig,
+}
+
+/// Fields to change in [`WebhookRegistry::update_webhook`]; `None` keeps the
+/// current value.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WebhookUpdate {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub is_active: Option<bool>,
+    /// SHA-256 of a new signing secret.
+    pub secret_hash: Option<BytesN<32>>,
+}
+
+/// A registered webhook as stored.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Webhook {
+    owner: Address,
+    name: String,
+    description: String,
+    url: String,
+    events: Vec<String>,
+    secret_hash: BytesN<32>,
+    is_active: bool,
+    retry_config: RetryConfig,
+    security_config: SecurityConfig,
+    created_at: u64,
+}
+
+/// A webhook as returned by [`WebhookRegistry::get_webhook`], without its
+/// secret hash.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WebhookInfo {
+    pub id: u64,
+    pub owner: Address,
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    pub events: Vec<String>,
+    pub is_active: bool,
+    pub retry_config: RetryConfig,
+    pub security_config: SecurityConfig,
+    pub created_at: u64,
+}
+
+/// Final outcome of delivering one event to one webhook, as reported by the
+/// relay.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeliveryResult {
+    pub webhook_id: u64,
+    pub event_id: String,
+    pub event_type: String,
+    pub success: bool,
+    pub attempts: u32,
+    pub status_code: Option<u32>,
+    pub error: Option<String>,
+}
+
+/// Delivery statistics of one webhook.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DeliveryStats {
+    pub total_deliveries: u64,
+    pub failed_deliveries: u64,
+    /// Delivery attempts across all reported deliveries.
+    pub total_attempts: u64,
+    /// Ledger timestamp of the last reported delivery, 0 if none.
+    pub last_delivery_at: u64,
+    pub last_success: bool,
+}
+
+/// Registry-wide statistics.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WebhookStats {
+    pub total_webhooks: u64,
+    pub active_webhooks: u64,
+    pub total_deliveries: u64,
+    pub failed_deliveries: u64,
+}
+
+/// Limits on an owner's use of the registry; `None` is unlimited.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WebhookQuota {
+    pub max_endpoints: Option<u64>,
+    pub max_events_per_day: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct OwnerEvents {
+    day: u64,
+    today: u64,
+    total: u64,
+}
+
+/// An owner's quotas and usage, as returned by
+/// [`WebhookRegistry::get_webhook_usage`].
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WebhookUsage {
+    pub endpoints: u64,
+    pub max_endpoints: Option<u64>,
+    pub events_today: u64,
+    pub max_events_per_day: Option<u64>,
+    /// Day (days since the Unix epoch) `events_today` counts.
+    pub day: u64,
+    /// Deliveries recorded since registration, for billing.
+    pub total_events: u64,
+}
+
+/// Event data of a notification, published with topics
+/// `(event_type, owner)`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NotificationEvent {
+    /// Contract that sent the notification; the registry itself for tests.
+    pub source: Address,
+    pub subject: Address,
+    pub reference: u128,
+    pub amount: i128,
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn require_admin(env: &Env, admin: &Address) {
+    admin.require_auth();
+    let stored: Address = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::Admin)
+        .expect("Contract not initialized");
+    assert!(*admin == stored, "Only admin");
+}
+
+fn read_webhook(env: &Env, webhook_id: u64) -> Webhook {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::Webhook(webhook_id))
+        .expect("Webhook not found")
+}
+
+fn write_webhook(env: &Env, webhook_id: u64, webhook: &Webhook) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::Webhook(webhook_id), webhook);
+}
+
+fn owner_webhooks(env: &Env, owner: &Address) -> Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::OwnerWebhooks(owner.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn read_stats(env: &Env) -> WebhookStats {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::Stats)
+        .unwrap_or_default()
+}
+
+fn update_stats(env: &Env, update: impl FnOnce(&mut WebhookStats)) {
+    let mut stats = read_stats(env);
+    update(&mut stats);
+    env.storage().persistent().set(&StorageKey::Stats, &stats);
+}
+
+fn quota(env: &Env, owner: &Address) -> WebhookQuota {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::OwnerQuota(owner.clone()))
+        .or_else(|| env.storage().persistent().get(&StorageKey::DefaultQuota))
+        .unwrap_or_default()
+}
+
+/// The owner's event counters, with `today` reset on a new day.
+fn owner_events(env: &Env, owner: &Address) -> OwnerEvents {
+    let day = env.ledger().timestamp() / SECONDS_PER_DAY;
+    let mut events: OwnerEvents = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::OwnerEvents(owner.clone()))
+        .unwrap_or_default();
+    if events.day != day {
+        events.day = day;
+        events.today = 0;
+    }
+    events
+}
+
+fn validate_text(text: &String) {
+    assert!(text.len() <= MAX_TEXT_LENGTH, "Text too long");
+}
+
+fn validate_url(env: &Env, url: &String, security: &SecurityConfig) {
+    assert!(
+        !url.is_empty() && url.len() <= MAX_URL_LENGTH,
+        "Invalid URL length"
+    );
+    if security.require_tls {
+        let scheme = Bytes::from_slice(env, b"https://");
+        let bytes = url.to_bytes();
+        assert!(
+            bytes.len() > scheme.len() && bytes.slice(0..scheme.len()) == scheme,
+            "URL must use https"
+        );
+    }
+}
+
+fn validate_events(events: &Vec<String>) {
+    assert!(
+        !events.is_empty() && events.len() <= MAX_EVENT_TYPES,
+        "Invalid event filter"
+    );
+    for event in events.iter() {
+        assert!(!event.is_empty(), "Invalid event filter");
+        validate_text(&event);
+    }
+}
+
+fn validate_retry(retry: &RetryConfig) {
+    assert!(
+        retry.retry_delay <= retry.max_delay && retry.max_delay <= SECONDS_PER_DAY,
+        "Invalid retry policy"
+    );
+}
+
+/// Whether any of the owner's active webhooks subscribes to `event_type`.
+fn is_subscribed(env: &Env, owner: &Address, event_type: &String) -> bool {
+    let wildcard = String::from_str(env, "*");
+    owner_webhooks(env, owner).iter().any(|webhook_id| {
+        let webhook = read_webhook(env, webhook_id);
+        webhook.is_active
+            && (webhook.events.contains(event_type) || webhook.events.contains(&wildcard))
+    })
+}
+
+fn publish_notification(env: &Env, source: Address, notification: WebhookNotification) {
+    env.events().publish(
+        (notification.event_type, notification.owner),
+        NotificationEvent {
+            source,
+            subject: notification.subject,
+            reference: notification.reference,
+            amount: notification.amount,
+        },
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Contract
+// ---------------------------------------------------------------------------
+
+#[contract]
+pub struct WebhookRegistry;
+
+#[contractimpl]
+impl WebhookRegistry {
+    /// Sets the admin that manages notifiers and quotas.
+    ///
+    /// # Access Control
+    /// Requires admin authentication. Only callable once.
+    pub fn initialize(env: Env, admin: Address) {
+        admin.require_auth();
+        if env.storage().persistent().has(&StorageKey::Admin) {
+            panic!("Already initialized");
+        }
+        env.storage().persistent().set(&StorageKey::Admin, &admin);
+    }
+
+    /// Allows or disallows a contract to call `notify`.
+    ///
+    /// # Access Control
+    /// Requires admin authentication
+    pub fn set_notifier(env: Env, admin: Address, notifier: Address, allowed: bool) {
+        require_admin(&env, &admin);
+        let key = StorageKey::Notifier(notifier);
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Returns whether `notifier` may call `notify`.
+    pub fn is_notifier(env: Env, notifier: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&StorageKey::Notifier(notifier))
+    }
+
+    /// Returns the event types the protocol's contracts notify. Webhooks may
+    /// also subscribe to `*` for all of them.
+    pub fn get_event_types(env: Env) -> Vec<String> {
+        let mut types = Vec::new(&env);
+        for event_type in event_types::ALL {
+            types.push_back(String::from_str(&env, event_type));
+        }
+        types
+    }
+
+    /// Sets the quota of `owner`, or with `None` the default quota for owners
+    /// without their own.
+    ///
+    /// # Access Control
+    /// Requires admin authentication
+    pub fn set_webhook_quota(
+        env: Env,
+        admin: Address,
+        owner: Option<Address>,
+        quota: WebhookQuota,
+    ) {
+        require_admin(&env, &admin);
+        let key = match owner {
+            Some(owner) => StorageKey::OwnerQuota(owner),
+            None => StorageKey::DefaultQuota,
+        };
+        env.storage().persistent().set(&key, &quota);
+    }
+
+    /// Registers a webhook for `owner` and returns its id.
+    ///
+    /// # Panics
+    /// * The name, description, URL or event filter is empty or too long
+    /// * `require_tls` is set and the URL is not `https://`
+    /// * The retry delay exceeds `max_delay`, or `max_delay` exceeds one day
+    /// * The owner has [`MAX_WEBHOOKS_PER_OWNER`] webhooks or reached its
+    ///   endpoint quota
+    ///
+    /// # Access Control
+    /// Requires owner authentication
+    pub fn register_webhook(env: Env, owner: Address, registration: WebhookRegistration) -> u64 {
+        owner.require_auth();
+        assert!(!registration.name.is_empty(), "Name required");
+        validate_text(&registration.name);
+        validate_text(&registration.description);
+        validate_url(&env, &registration.url, &registration.security_config);
+        validate_events(&registration.events);
+        validate_retry(&registration.retry_config);
+
+        let mut ids = owner_webhooks(&env, &owner);
+        assert!(ids.len() < MAX_WEBHOOKS_PER_OWNER, "Too many webhooks");
+        if let Some(max) = quota(&env, &owner).max_endpoints {
+            assert!(u64::from(ids.len()) < max, "Endpoint quota exceeded");
+        }
+
+        let webhook_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&StorageKey::NextId)
+            .unwrap_or(1);
+        env.storage()
+            .persistent()
+            .set(&StorageKey::NextId, &(webhook_id + 1));
+        write_webhook(
+            &env,
+            webhook_id,
+            &Webhook {
+                owner: owner.clone(),
+                name: registration.name,
+                description: registration.description,
+                url: registration.url,
+                events: registration.events,
+                secret_hash: registration.secret_hash,
+                is_active: true,
+                retry_config: registration.retry_config,
+                security_config: registration.security_config,
+                created_at: env.ledger().timestamp(),
+            },
+        );
+        ids.push_back(webhook_id);
+        env.storage()
+            .persistent()
+            .set(&StorageKey::OwnerWebhooks(owner), &ids);
+        update_stats(&env, |stats| {
+            stats.total_webhooks += 1;
+            stats.active_webhooks += 1;
+        });
+        webhook_id
+    }
+
+    /// Changes the given fields of a webhook, validated as on registration.
+    ///
+    /// # Access Control
+    /// Requires authentication of the webhook's owner
+    pub fn update_webhook(env: Env, webhook_id: u64, update: WebhookUpdate) {
+        let mut webhook = read_webhook(&env, webhook_id);
+        webhook.owner.require_auth();
+
+        if let Some(name) = update.name {
+            assert!(!name.is_empty(), "Name required");
+            validate_text(&name);
+            webhook.name = name;
+        }
+        if let Some(description) = update.description {
+            validate_text(&description);
+            webhook.description = description;
+        }
+        if let Some(url) = update.url {
+            validate_url(&env, &url, &webhook.security_config);
+            webhook.url = url;
+        }
+        if let Some(events) = update.events {
+            validate_events(&events);
+            webhook.events = events;
+        }
+        if let Some(secret_hash) = update.secret_hash {
+            webhook.secret_hash = secret_hash;
+        }
+        if let Some(is_active) = update.is_active {
+            if is_active != webhook.is_active {
+                update_stats(&env, |stats| {
+                    if is_active {
+                        stats.active_webhooks += 1;
+                    } else {
+                        stats.active_webhooks -= 1;
+                    }
+                });
+            }
+            webhook.is_active = is_active;
+        }
+        write_webhook(&env, webhook_id, &webhook);
+    }
+
+    /// Deletes a webhook and its delivery statistics.
+    ///
+    /// # Access Control
+    /// Requires authentication of the webhook's owner
+    pub fn delete_webhook(env: Env, webhook_id: u64) {
+        let webhook = read_webhook(&env, webhook_id);
+        webhook.owner.require_auth();
+
+        let mut ids = owner_webhooks(&env, &webhook.owner);
+        if let Some(index) = ids.first_index_of(webhook_id) {
+            ids.remove(index);
+        }
+        env.storage()
+            .persistent()
+            .set(&StorageKey::OwnerWebhooks(webhook.owner), &ids);
+        env.storage()
+            .persistent()
+            .remove(&StorageKey::Webhook(webhook_id));
+        env.storage()
+            .persistent()
+            .remove(&StorageKey::Deliveries(webhook_id));
+        update_stats(&env, |stats| {
+            stats.total_webhooks -= 1;
+            if webhook.is_active {
+                stats.active_webhooks -= 1;
+            }
+        });
+    }
+
+    /// Returns a webhook without its secret hash.
+    ///
+    /// # Panics
+    /// * No webhook has this id
+    pub fn get_webhook(env: Env, webhook_id: u64) -> WebhookInfo {
+        let webhook = read_webhook(&env, webhook_id);
+        WebhookInfo {
+            id: webhook_id,
+            owner: webhook.owner,
+            name: webhook.name,
+            description: webhook.description,
+            url: webhook.url,
+            events: webhook.events,
+            is_active: webhook.is_active,
+            retry_config: webhook.retry_config,
+            security_config: webhook.security_config,
+            created_at: webhook.created_at,
+        }
+    }
+
+    /// Returns whether `secret_hash` is the webhook's current secret hash, so
+    /// a relay can check its local secret before signing with it.
+    pub fn check_webhook_secret(env: Env, webhook_id: u64, secret_hash: BytesN<32>) -> bool {
+        read_webhook(&env, webhook_id).secret_hash == secret_hash
+    }
+
+    /// Returns the ids of the owner's webhooks.
+    pub fn list_owner_webhooks(env: Env, owner: Address) -> Vec<u64> {
+        owner_webhooks(&env, &owner)
+    }
+
+    /// Returns registry-wide webhook and delivery counts.
+    pub fn get_webhook_stats(env: Env) -> WebhookStats {
+        read_stats(&env)
+    }
+
+    /// Returns the delivery statistics of a webhook.
+    pub fn get_delivery_stats(env: Env, webhook_id: u64) -> DeliveryStats {
+        env.storage()
+            .persistent()
+            .get(&StorageKey::Deliveries(webhook_id))
+            .unwrap_or_default()
+    }
+
+    /// Returns the owner's quotas and how much of them is used.
+    pub fn get_webhook_usage(env: Env, owner: Address) -> WebhookUsage {
+        let quota = quota(&env, &owner);
+        let events = owner_events(&env, &owner);
+        WebhookUsage {
+            endpoints: u64::from(owner_webhooks(&env, &owner).len()),
+            max_endpoints: quota.max_endpoints,
+            events_today: events.today,
+            max_events_per_day: quota.max_events_per_day,
+            day: events.day,
+            total_events: events.total,
+        }
+    }
+
+    /// Emits `notification` for relays when one of the owner's active
+    /// webhooks subscribes to its event type, and returns whether it did.
+    /// Writes no storage, so notifying is cheap for the calling contract.
+    ///
+    /// # Panics
+    /// * `source` is not an allowed notifier
+    ///
+    /// # Access Control
+    /// Requires authentication of `source`, an allowed notifier contract
+    pub fn notify(env: Env, source: Address, notification: WebhookNotification) -> bool {
+        source.require_auth();
+        assert!(
+            Self::is_notifier(env.clone(), source.clone()),
+            "Unknown notifier"
+        );
+        if !is_subscribed(&env, &notification.owner, &notification.event_type) {
+            return false;
+        }
+        publish_notification(&env, source, notification);
+        true
+    }
+
+    /// Emits a test notification of `event_type` for the webhook's owner, as
+    /// if a notifier had sent it.
+    ///
+    /// # Access Control
+    /// Requires authentication of the webhook's owner
+    pub fn test_webhook(env: Env, webhook_id: u64, event_type: String) -> bool {
+        let webhook = read_webhook(&env, webhook_id);
+        webhook.owner.require_auth();
+        validate_text(&event_type);
+        if !is_subscribed(&env, &webhook.owner, &event_type) {
+            return false;
+        }
+        publish_notification(
+            &env,
+            env.current_contract_address(),
+            WebhookNotification {
+                owner: webhook.owner.clone(),
+                event_type,
+                subject: webhook.owner,
+                reference: u128::from(webhook_id),
+                amount: 0,
+            },
+        );
+        true
+    }
+
+    /// Records the final outcome of a delivery, updating the webhook's and
+    /// the registry's statistics and the owner's event usage.
+    ///
+    /// # Panics
+    /// * `delivery.webhook_id` differs from `webhook_id`
+    /// * The owner's daily event quota is used up
+    ///
+    /// # Access Control
+    /// Requires authentication of the webhook's owner, whose relay reports
+    pub fn record_webhook_delivery(env: Env, webhook_id: u64, delivery: DeliveryResult) {
+        let webhook = read_webhook(&env, webhook_id);
+        webhook.owner.require_auth();
+        assert!(delivery.webhook_id == webhook_id, "Webhook mismatch");
+        validate_text(&delivery.event_id);
+        validate_text(&delivery.event_type);
+        if let Some(error) = &delivery.error {
+            validate_text(error);
+        }
+
+        let mut events = owner_events(&env, &webhook.owner);
+        if let Some(max) = quota(&env, &webhook.owner).max_events_per_day {
+            assert!(events.today < max, "Daily event quota exceeded");
+        }
+        events.today += 1;
+        events.total += 1;
+        env.storage()
+            .persistent()
+            .set(&StorageKey::OwnerEvents(webhook.owner), &events);
+
+        let key = StorageKey::Deliveries(webhook_id);
+        let mut stats: DeliveryStats = env.storage().persistent().get(&key).unwrap_or_default();
+        stats.total_deliveries += 1;
+        stats.total_attempts += u64::from(delivery.attempts);
+        stats.last_delivery_at = env.ledger().timestamp();
+        stats.last_success = delivery.success;
+        if !delivery.success {
+            stats.failed_deliveries += 1;
+        }
+        env.storage().persistent().set(&key, &stats);
+
+        update_stats(&env, |stats| {
+            stats.total_deliveries += 1;
+            if !delivery.success {
+                stats.failed_deliveries += 1;
+            }
+        });
+    }
+}
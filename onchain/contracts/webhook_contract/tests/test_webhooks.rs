@@ -0,0 +1,239 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, Events as _, Ledger},
+    vec, Address, BytesN, Env, String,
+};
+use webhook_contract::{
+    DeliveryResult, DeliveryStats, RetryConfig, SecurityConfig, SignatureMethod,
+    WebhookNotification, WebhookQuota, WebhookRegistration, WebhookRegistry, WebhookRegistryClient,
+    WebhookStats, WebhookUpdate, WebhookUsage,
+};
+
+const DAY: u64 = 86_400;
+
+fn setup(env: &Env) -> (WebhookRegistryClient<'_>, Address) {
+    env.ledger().with_mut(|l| l.timestamp = 10 * DAY);
+    env.mock_all_auths();
+    let client = WebhookRegistryClient::new(env, &env.register(WebhookRegistry, ()));
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+    (client, admin)
+}
+
+fn registration(env: &Env, events: &[&str]) -> WebhookRegistration {
+    let mut filter = vec![env];
+    for event in events {
+        filter.push_back(String::from_str(env, event));
+    }
+    WebhookRegistration {
+        name: String::from_str(env, "payroll"),
+        description: String::from_str(env, "Payroll notifications"),
+        url: String::from_str(env, "https://hooks.example.com/payroll"),
+        events: filter,
+        secret_hash: BytesN::from_array(env, &[7; 32]),
+        retry_config: RetryConfig {
+            max_retries: 3,
+            retry_delay: 5,
+            exponential_backoff: true,
+            max_delay: 60,
+        },
+        security_config: SecurityConfig {
+            signature_method: SignatureMethod::HmacSha256,
+            rate_limit_per_minute: 60,
+            require_tls: true,
+        },
+    }
+}
+
+fn notification(env: &Env, owner: &Address, event_type: &str) -> WebhookNotification {
+    WebhookNotification {
+        owner: owner.clone(),
+        event_type: String::from_str(env, event_type),
+        subject: Address::generate(env),
+        reference: 1,
+        amount: 1_000,
+    }
+}
+
+fn delivery(env: &Env, webhook_id: u64, success: bool) -> DeliveryResult {
+    DeliveryResult {
+        webhook_id,
+        event_id: String::from_str(env, "evt-1"),
+        event_type: String::from_str(env, "payroll_claimed"),
+        success,
+        attempts: if success { 1 } else { 4 },
+        status_code: Some(if success { 200 } else { 503 }),
+        error: None,
+    }
+}
+
+#[test]
+fn registered_webhooks_are_listed_without_their_secret() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let owner = Address::generate(&env);
+
+    let id = client.register_webhook(&owner, &registration(&env, &["payroll_claimed"]));
+    let second = client.register_webhook(&owner, &registration(&env, &["*"]));
+    assert_eq!(client.list_owner_webhooks(&owner), vec![&env, id, second]);
+
+    let info = client.get_webhook(&id);
+    assert_eq!(info.owner, owner);
+    assert_eq!(
+        info.url,
+        String::from_str(&env, "https://hooks.example.com/payroll")
+    );
+    assert!(info.is_active);
+    assert!(client.check_webhook_secret(&id, &BytesN::from_array(&env, &[7; 32])));
+    assert!(!client.check_webhook_secret(&id, &BytesN::from_array(&env, &[8; 32])));
+
+    client.update_webhook(
+        &id,
+        &WebhookUpdate {
+            is_active: Some(false),
+            secret_hash: Some(BytesN::from_array(&env, &[8; 32])),
+            ..WebhookUpdate::default()
+        },
+    );
+    assert!(client.check_webhook_secret(&id, &BytesN::from_array(&env, &[8; 32])));
+    client.delete_webhook(&second);
+    assert_eq!(client.list_owner_webhooks(&owner), vec![&env, id]);
+    assert_eq!(
+        client.get_webhook_stats(),
+        WebhookStats {
+            total_webhooks: 1,
+            active_webhooks: 0,
+            ..WebhookStats::default()
+        }
+    );
+}
+
+#[test]
+fn invalid_registrations_are_rejected() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+
+    let mut plain_http = registration(&env, &["*"]);
+    plain_http.url = String::from_str(&env, "http://hooks.example.com");
+    assert!(client.try_register_webhook(&owner, &plain_http).is_err());
+
+    let no_events = registration(&env, &[]);
+    assert!(client.try_register_webhook(&owner, &no_events).is_err());
+
+    let mut slow_retry = registration(&env, &["*"]);
+    slow_retry.retry_config.retry_delay = 120;
+    assert!(client.try_register_webhook(&owner, &slow_retry).is_err());
+
+    client.set_webhook_quota(
+        &admin,
+        &None,
+        &WebhookQuota {
+            max_endpoints: Some(1),
+            max_events_per_day: None,
+        },
+    );
+    client.register_webhook(&owner, &registration(&env, &["*"]));
+    assert!(client
+        .try_register_webhook(&owner, &registration(&env, &["*"]))
+        .is_err());
+}
+
+#[test]
+fn notifications_are_emitted_only_for_subscribed_owners() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let payroll = Address::generate(&env);
+    let id = client.register_webhook(&owner, &registration(&env, &["payroll_claimed"]));
+    let event_types = client.get_event_types();
+    assert_eq!(event_types.len(), 12);
+    assert!(event_types.contains(String::from_str(&env, "escrow_funded")));
+
+    // Only allowed notifiers may notify
+    assert!(client
+        .try_notify(&payroll, &notification(&env, &owner, "payroll_claimed"))
+        .is_err());
+    client.set_notifier(&admin, &payroll, &true);
+
+    assert!(client.notify(&payroll, &notification(&env, &owner, "payroll_claimed")));
+    assert_eq!(env.events().all().len(), 1);
+    assert!(!client.notify(&payroll, &notification(&env, &owner, "escrow_released")));
+    assert!(!client.notify(
+        &payroll,
+        &notification(&env, &Address::generate(&env), "payroll_claimed")
+    ));
+
+    client.update_webhook(
+        &id,
+        &WebhookUpdate {
+            events: Some(vec![&env, String::from_str(&env, "*")]),
+            ..WebhookUpdate::default()
+        },
+    );
+    assert!(client.notify(&payroll, &notification(&env, &owner, "escrow_released")));
+    assert!(client.test_webhook(&id, &String::from_str(&env, "incentive_claimed")));
+
+    client.update_webhook(
+        &id,
+        &WebhookUpdate {
+            is_active: Some(false),
+            ..WebhookUpdate::default()
+        },
+    );
+    assert!(!client.notify(&payroll, &notification(&env, &owner, "payroll_claimed")));
+}
+
+#[test]
+fn deliveries_update_stats_and_the_daily_quota() {
+    let env = Env::default();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let id = client.register_webhook(&owner, &registration(&env, &["*"]));
+    client.set_webhook_quota(
+        &admin,
+        &Some(owner.clone()),
+        &WebhookQuota {
+            max_endpoints: None,
+            max_events_per_day: Some(2),
+        },
+    );
+
+    client.record_webhook_delivery(&id, &delivery(&env, id, true));
+    client.record_webhook_delivery(&id, &delivery(&env, id, false));
+    assert!(client
+        .try_record_webhook_delivery(&id, &delivery(&env, id, true))
+        .is_err());
+    assert!(client
+        .try_record_webhook_delivery(&id, &delivery(&env, id + 1, true))
+        .is_err());
+
+    assert_eq!(
+        client.get_delivery_stats(&id),
+        DeliveryStats {
+            total_deliveries: 2,
+            failed_deliveries: 1,
+            total_attempts: 5,
+            last_delivery_at: 10 * DAY,
+            last_success: false,
+        }
+    );
+    let stats = client.get_webhook_stats();
+    assert_eq!((stats.total_deliveries, stats.failed_deliveries), (2, 1));
+
+    // The daily counter resets on the next day; the total keeps counting
+    env.ledger().with_mut(|l| l.timestamp += DAY);
+    client.record_webhook_delivery(&id, &delivery(&env, id, true));
+    assert_eq!(
+        client.get_webhook_usage(&owner),
+        WebhookUsage {
+            endpoints: 1,
+            max_endpoints: None,
+            events_today: 1,
+            max_events_per_day: Some(2),
+            day: 11,
+            total_events: 3,
+        }
+    );
+}
@@ -1,171 +1,764 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
 use anyhow::Result;
 use log::{error, info, warn};
 use std::path::PathBuf;
 
-use crate::config::load_config;
-use crate::utils::{validate_address, SorobanHttpClient, WebhookInfo, WebhookStats};
-use crate::{require_admin, require_not_paused, Config, Error, TokenClient, WebhookCommands};
+use crate::config::{expand_path, get_signer, load_config, save_config};
+use crate::keystore::{read_passphrase, Keystore};
+use crate::output;
+use crate::rpc::{self, Network, SorobanRpcClient};
+use crate::signer::TransactionSigner;
+use crate::templates::{read_template_file, PayrollTemplate, TemplateStore, SHARE_PREFIX};
+use crate::utils::{
+    confirm_action, format_amount, format_duration, format_table, load_webhook_secrets,
+    parse_amount, parse_duration, parse_employee_csv, parse_report_date, save_webhook_secret,
+    sign_webhook_payload, to_csv, truncate_address, validate_address, BulkPayEntry, RetryConfig,
+    WebhookInfo, WebhookStats, WebhookUsage,
+};
+use crate::{
+    frequency_to_seconds, out, outln, require_admin, require_not_paused, seconds_to_frequency,
+    BonusCommands, BonusTarget, BonusTermsArgs, BulkPayArgs, CalendarArgs, CircuitBreakerState,
+    Config, ContractStatus, DeployArgs, DeploySuiteArgs, DevnetCommands, DevnetUpArgs,
+    EmergencyWithdrawArgs, EmployeeAgreementTarget, EmployeeCommands, EmployeePayrollArgs,
+    EmployeePayrollFile, EmployeeTarget, Error, EscrowCommands, EscrowTarget, HealthMetrics,
+    HistoryArgs, KeeperCommands, KeeperRunArgs, KeysCommands, MetadataCommands, MetadataTarget,
+    OutputFormat, ReportArgs, ReportFormat, ScheduleAction, ScheduleCommands, ScheduleExecuteArgs,
+    SchedulerTarget, TemplateCommands, TokenClient, UpcomingArgs, WebhookCommands,
+    WebhookRelayArgs, DEFAULT_WEBHOOK_SECRETS,
+};
+use stellar_xdr::curr::ScVal;
 
 const MAXIMUM_AMOUNT: i128 = 100_000_000;
 
+/// Decimal places used when converting human-readable token amounts.
+const TOKEN_DECIMALS: u32 = 7;
+
+/// Local release build used when neither `--wasm` nor `--wasm-url` is given.
+const DEFAULT_WASM_PATH: &str =
+    "../../onchain/target/wasm32v1-none/release/stello_pay_contract.wasm";
+
+/// Runs `deploy`: uploads the payroll WASM, creates a contract instance from
+/// it, calls `initialize(owner)`, and records the new contract ID as
+/// `default_contract_id` in the config file.
+///
+/// `initialize` needs the owner's authorization, so the signer must be the
+/// owner. The check runs before anything is uploaded.
 pub async fn deploy_command(
-    network: String,
-    owner: String,
-    wasm: Option<PathBuf>,
+    args: DeployArgs,
     config: &Config,
+    config_path: &std::path::Path,
 ) -> Result<()> {
+    let network = Network::from_passphrase(&config.network.network_passphrase)
+        .map(|network| network.name())
+        .unwrap_or("custom");
     info!("Deploying contract to network: {}", network);
 
-    // Determine WASM file path
-    let wasm_path = wasm.unwrap_or_else(|| {
-        PathBuf::from("../../onchain/target/wasm32v1-none/release/stello_pay_contract.wasm")
-    });
+    let signer = owner_signer(&args.owner, config).await?;
+    let (wasm, source) = load_deploy_wasm(&args).await?;
+
+    outln!("Deploying contract with the following parameters:");
+    outln!("  Network: {}", network);
+    outln!("  Owner: {}", args.owner);
+    outln!("  WASM: {} ({} bytes)", source, wasm.len());
+    outln!("  RPC URL: {}", config.network.rpc_url);
+    outln!();
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let deployed =
+        deploy_instance(&rpc, &signer, &wasm, vec![rpc::address_arg(&args.owner)?]).await?;
+    outln!("✅ Contract initialized with owner: {}", args.owner);
+    output::set_result(&deployed)?;
+
+    if !args.no_save {
+        save_default_contract_id(config_path, &deployed.contract_id).await?;
+    }
+
+    Ok(())
+}
 
-    if !wasm_path.exists() {
-        error!("WASM file not found: {:?}", wasm_path);
+/// Resolves the signer for a deployment and checks that it is `owner`:
+/// every `initialize` requires the owner's authorization.
+async fn owner_signer(owner: &str, config: &Config) -> Result<TransactionSigner> {
+    validate_address(owner).map_err(|e| {
+        anyhow::anyhow!("Contract deployment failed: invalid owner {}: {}", owner, e)
+    })?;
+    let signer = get_signer(config).await?;
+    if signer.address() != owner {
         return Err(anyhow::anyhow!(
-            "WASM file not found. Please build the contract first."
+            "initialize requires the owner's authorization; sign as {} (the current signer is {})",
+            owner,
+            signer.address()
         ));
     }
+    Ok(signer)
+}
 
-    // Check if soroban CLI is available
-    let soroban_check = std::process::Command::new("soroban")
-        .arg("--version")
-        .output();
-
-    if soroban_check.is_err() {
-        error!("Soroban CLI not found. Please install it first:");
-        error!("cargo install --locked soroban-cli");
-        return Err(anyhow::anyhow!("Soroban CLI not found"));
-    }
-
-    println!("Deploying contract with the following parameters:");
-    println!("  Network: {}", network);
-    println!("  Owner: {}", owner);
-    println!("  WASM file: {:?}", wasm_path);
-    println!("  RPC URL: {}", config.network.rpc_url);
-    println!();
-
-    // Build the deployment command
-    let mut cmd = std::process::Command::new("soroban");
-    cmd.args([
-        "contract",
-        "deploy",
-        "--wasm",
-        wasm_path.to_str().unwrap(),
-        "--rpc-url",
-        &config.network.rpc_url,
-        "--network",
-        &network,
-    ]);
-
-    println!("Running deployment command...");
-    println!("Command: {:?}", cmd);
-
-    let output = cmd.output()?;
+/// A contract created and initialized by `deploy` or `deploy-suite`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DeployedContract {
+    pub contract_id: String,
+    /// Hex-encoded SHA-256 of the uploaded WASM.
+    pub wasm_hash: String,
+}
 
-    if !output.status.success() {
-        error!("Contract deployment failed:");
-        error!("stderr: {}", String::from_utf8_lossy(&output.stderr));
-        error!("stdout: {}", String::from_utf8_lossy(&output.stdout));
-        return Err(anyhow::anyhow!("Contract deployment failed"));
-    }
+/// Uploads `wasm`, creates an instance of it with a random salt, and calls
+/// `initialize(init_args)` on the new contract.
+async fn deploy_instance(
+    rpc: &SorobanRpcClient,
+    signer: &TransactionSigner,
+    wasm: &[u8],
+    init_args: Vec<ScVal>,
+) -> Result<DeployedContract> {
+    let wasm_hash = rpc.upload_wasm(signer, wasm).await?;
+    outln!("✅ WASM uploaded: {}", hex::encode(wasm_hash));
 
-    let contract_id = String::from_utf8(output.stdout)?.trim().to_string();
+    let mut salt = [0u8; 32];
+    OsRng.fill_bytes(&mut salt);
+    let contract_id = rpc.create_contract(signer, wasm_hash, salt).await?;
     info!("Contract deployed successfully: {}", contract_id);
+    outln!("✅ Contract deployed successfully!");
+    outln!("Contract ID: {}", contract_id);
 
-    println!("✅ Contract deployed successfully!");
-    println!("Contract ID: {}", contract_id);
+    let outcome = rpc
+        .invoke(signer, &contract_id, "initialize", init_args)
+        .await
+        .map_err(|e| {
+            error!("Contract initialization failed: {}", e);
+            anyhow::anyhow!(
+                "Contract {} was created but initialization failed",
+                contract_id
+            )
+        })?;
+    info!("Contract initialized in transaction {}", outcome.hash);
 
-    // Initialize contract
-    let init_output = std::process::Command::new("soroban")
-        .args([
-            "contract",
-            "invoke",
-            "--id",
-            &contract_id,
-            "--rpc-url",
-            &config.network.rpc_url,
-            "--network",
-            &network,
-            "--",
-            "initialize",
-            "--owner",
-            &owner,
-        ])
-        .output()?;
+    Ok(DeployedContract {
+        contract_id,
+        wasm_hash: hex::encode(wasm_hash),
+    })
+}
 
-    if !init_output.status.success() {
-        error!(
-            "Contract initialization failed: {}",
-            String::from_utf8_lossy(&init_output.stderr)
-        );
-        return Err(anyhow::anyhow!("Contract initialization failed"));
+/// Records `contract_id` as `default_contract_id`. The file is re-read so
+/// that command-line overrides such as `--network` are not persisted.
+async fn save_default_contract_id(config_path: &std::path::Path, contract_id: &str) -> Result<()> {
+    let mut saved = load_config(config_path).await?;
+    saved.contract.default_contract_id = Some(contract_id.to_string());
+    save_config(config_path, &saved).await?;
+    outln!("Saved as default_contract_id in {}", config_path.display());
+    Ok(())
+}
+
+/// WASM file stems deployed by `deploy-suite`, in deployment order.
+const SUITE_CONTRACTS: [&str; 4] = [
+    "stello_pay_contract",
+    "payroll_escrow",
+    "payment_history",
+    "bonus_system",
+];
+
+/// Reads the `SUITE_CONTRACTS` release builds from `dir`, in order.
+fn read_suite_wasms(dir: &std::path::Path) -> Result<Vec<Vec<u8>>> {
+    let mut wasms = Vec::with_capacity(SUITE_CONTRACTS.len());
+    for name in SUITE_CONTRACTS {
+        let path = dir.join(format!("{}.wasm", name));
+        if !path.exists() {
+            return Err(anyhow::anyhow!(
+                "WASM file not found: {}. Build the contracts first.",
+                path.display()
+            ));
+        }
+        let wasm = std::fs::read(&path)?;
+        if !wasm.starts_with(b"\0asm") {
+            return Err(anyhow::anyhow!("{} is not a WASM module", path.display()));
+        }
+        wasms.push(wasm);
     }
+    Ok(wasms)
+}
 
-    info!("Contract initialized successfully");
-    println!("✅ Contract initialized with owner: {}", owner);
+/// Output of `deploy-suite`: where each contract lives and how they were
+/// found to be wired together.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DeploymentManifest {
+    pub network: String,
+    pub network_passphrase: String,
+    pub rpc_url: String,
+    pub deployed_at: String,
+    pub owner: String,
+    pub escrow_token: String,
+    pub payroll: DeployedContract,
+    pub escrow: DeployedContract,
+    pub payment_history: DeployedContract,
+    pub bonus_system: DeployedContract,
+    pub wiring: Vec<WiringCheck>,
+}
+
+/// One stored address read back after deployment.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WiringCheck {
+    pub contract: String,
+    pub key: String,
+    pub expected: String,
+    pub actual: Option<String>,
+    pub ok: bool,
+}
 
+/// Runs `deploy-suite`: deploys the payroll, escrow, payment history and
+/// bonus contracts, points the escrow manager and the history recorder at
+/// the payroll contract, reads the wiring back, and writes a manifest.
+///
+/// The manifest is written even when a wiring check fails, so the deployed
+/// IDs are not lost; the command then fails.
+pub async fn deploy_suite_command(
+    args: DeploySuiteArgs,
+    config: &Config,
+    config_path: &std::path::Path,
+) -> Result<()> {
+    let manifest = deploy_suite(&args, config).await?;
+    if !args.no_save {
+        save_default_contract_id(config_path, &manifest.payroll.contract_id).await?;
+    }
     Ok(())
 }
 
-pub async fn info_command(contract_id: Option<String>, config: &Config) -> Result<()> {
+/// Deploys and checks the suite and writes the manifest; see
+/// [`deploy_suite_command`].
+pub async fn deploy_suite(args: &DeploySuiteArgs, config: &Config) -> Result<DeploymentManifest> {
+    let token = args
+        .token
+        .clone()
+        .or_else(|| config.defaults.token.clone())
+        .ok_or_else(|| {
+            anyhow::anyhow!("No escrow token given; pass --token or set defaults.token")
+        })?;
+    validate_address(&token)?;
+    let signer = owner_signer(&args.owner, config).await?;
+
+    // Read every file before submitting anything
+    let wasms = read_suite_wasms(&args.wasm_dir)?;
+    let [payroll_wasm, escrow_wasm, history_wasm, bonus_wasm] = &wasms[..] else {
+        unreachable!("one WASM per suite contract");
+    };
+
+    let network = Network::from_passphrase(&config.network.network_passphrase)
+        .map(|network| network.name())
+        .unwrap_or("custom");
+    outln!("Deploying the contract suite to {}", network);
+    outln!("  Owner: {}", args.owner);
+    outln!("  Escrow token: {}", token);
+    outln!();
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let owner = rpc::address_arg(&args.owner)?;
+
+    outln!("[1/4] payroll");
+    let payroll = deploy_instance(&rpc, &signer, payroll_wasm, vec![owner.clone()]).await?;
+    let payroll_arg = rpc::address_arg(&payroll.contract_id)?;
+    outln!("[2/4] escrow");
+    let escrow = deploy_instance(
+        &rpc,
+        &signer,
+        escrow_wasm,
+        vec![
+            owner.clone(),
+            rpc::address_arg(&token)?,
+            payroll_arg.clone(),
+        ],
+    )
+    .await?;
+    outln!("[3/4] payment history");
+    let payment_history = deploy_instance(
+        &rpc,
+        &signer,
+        history_wasm,
+        vec![owner.clone(), payroll_arg],
+    )
+    .await?;
+    outln!("[4/4] bonus system");
+    let bonus_system = deploy_instance(&rpc, &signer, bonus_wasm, vec![owner]).await?;
+
+    let expected = [
+        ("payroll", &payroll.contract_id, "Owner", &args.owner),
+        ("escrow", &escrow.contract_id, "Admin", &args.owner),
+        ("escrow", &escrow.contract_id, "Token", &token),
+        (
+            "escrow",
+            &escrow.contract_id,
+            "Manager",
+            &payroll.contract_id,
+        ),
+        (
+            "payment_history",
+            &payment_history.contract_id,
+            "Owner",
+            &args.owner,
+        ),
+        (
+            "payment_history",
+            &payment_history.contract_id,
+            "PayrollContract",
+            &payroll.contract_id,
+        ),
+        (
+            "bonus_system",
+            &bonus_system.contract_id,
+            "Owner",
+            &args.owner,
+        ),
+    ];
+    let mut wiring = Vec::with_capacity(expected.len());
+    for (contract, contract_id, key, expected) in expected {
+        let actual = read_stored_address(&rpc, contract_id, key).await?;
+        wiring.push(WiringCheck {
+            contract: contract.to_string(),
+            key: key.to_string(),
+            expected: expected.clone(),
+            ok: actual.as_ref() == Some(expected),
+            actual,
+        });
+    }
+
+    let manifest = DeploymentManifest {
+        network: network.to_string(),
+        network_passphrase: config.network.network_passphrase.clone(),
+        rpc_url: config.network.rpc_url.clone(),
+        deployed_at: chrono::Utc::now().to_rfc3339(),
+        owner: args.owner.clone(),
+        escrow_token: token,
+        payroll,
+        escrow,
+        payment_history,
+        bonus_system,
+        wiring,
+    };
+    output::set_result(&manifest)?;
+    std::fs::write(
+        &args.manifest,
+        serde_json::to_string_pretty(&manifest)? + "\n",
+    )?;
+
+    let rows: Vec<Vec<String>> = manifest
+        .wiring
+        .iter()
+        .map(|check| {
+            vec![
+                check.contract.clone(),
+                check.key.clone(),
+                check
+                    .actual
+                    .clone()
+                    .unwrap_or_else(|| "Not set".to_string()),
+                if check.ok { "✅" } else { "❌" }.to_string(),
+            ]
+        })
+        .collect();
+    outln!();
+    outln!(
+        "{}",
+        format_table(&["Contract", "Key", "Stored address", "OK"], &rows)
+    );
+    outln!("Manifest written to {}", args.manifest.display());
+
+    let failed: Vec<String> = manifest
+        .wiring
+        .iter()
+        .filter(|check| !check.ok)
+        .map(|check| format!("{}.{}", check.contract, check.key))
+        .collect();
+    if !failed.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Wiring check failed for {}",
+            failed.join(", ")
+        ));
+    }
+    Ok(manifest)
+}
+
+/// Reads the WASM to deploy from `--wasm`, `--wasm-url` or the local release
+/// build, and checks it against `--wasm-sha256` when given. Returns the bytes
+/// and a description of where they came from.
+async fn load_deploy_wasm(args: &DeployArgs) -> Result<(Vec<u8>, String)> {
+    let (wasm, source) = match &args.wasm_url {
+        Some(url) => {
+            let response = reqwest::get(url)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to download WASM from {}: {}", url, e))?;
+            if !response.status().is_success() {
+                return Err(anyhow::anyhow!(
+                    "Failed to download WASM from {}: status {}",
+                    url,
+                    response.status()
+                ));
+            }
+            (response.bytes().await?.to_vec(), url.clone())
+        }
+        None => {
+            let path = args
+                .wasm
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_WASM_PATH));
+            if !path.exists() {
+                error!("WASM file not found: {:?}", path);
+                return Err(anyhow::anyhow!(
+                    "WASM file not found. Please build the contract first, or pass --wasm-url with --wasm-sha256."
+                ));
+            }
+            (std::fs::read(&path)?, path.display().to_string())
+        }
+    };
+
+    if let Some(expected) = &args.wasm_sha256 {
+        use sha2::{Digest, Sha256};
+        let actual = hex::encode(Sha256::digest(&wasm));
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(anyhow::anyhow!(
+                "WASM from {} has SHA-256 {}, expected {}",
+                source,
+                actual,
+                expected
+            ));
+        }
+    }
+    if !wasm.starts_with(b"\0asm") {
+        return Err(anyhow::anyhow!("{} is not a WASM module", source));
+    }
+    Ok((wasm, source))
+}
+
+pub async fn info_command(
+    contract_id: Option<String>,
+    format: OutputFormat,
+    config: &Config,
+) -> Result<()> {
     let contract_id = contract_id
         .or_else(|| config.contract.default_contract_id.clone())
         .ok_or_else(|| anyhow::anyhow!("No contract ID provided"))?;
 
     info!("Getting contract information for: {}", contract_id);
 
-    println!("Contract Information:");
-    println!("  Contract ID: {}", contract_id);
-    println!("  Network RPC: {}", config.network.rpc_url);
-    println!(
-        "  Network Passphrase: {}",
-        config.network.network_passphrase
-    );
+    let rpc = std::sync::Arc::new(SorobanRpcClient::from_config(config));
+    let report = fetch_contract_health(&rpc, &contract_id).await?;
 
-    // Try to get contract info using soroban CLI
-    let output = std::process::Command::new("soroban")
-        .args([
-            "contract",
-            "inspect",
-            "--id",
-            &contract_id,
-            "--rpc-url",
-            &config.network.rpc_url,
-        ])
-        .output();
+    match format {
+        OutputFormat::Json => output::emit_json(&report)?,
+        OutputFormat::Table => {
+            output::set_result(&report)?;
+            print_contract_health(&report, config)
+        }
+    }
 
-    match output {
-        Ok(output) if output.status.success() => {
-            println!("\nContract Details:");
-            println!("{}", String::from_utf8_lossy(&output.stdout));
+    if !report.health.is_healthy {
+        return Err(anyhow::anyhow!(
+            "Contract {} is unhealthy: {}",
+            contract_id,
+            report.health.issues.join("; ")
+        ));
+    }
+    Ok(())
+}
+
+/// Number of agreements looked up at a time by `info`.
+const INFO_CONCURRENCY: usize = 10;
+
+/// What `info` reports for a payroll contract.
+#[derive(Debug, serde::Serialize)]
+pub struct ContractHealthReport {
+    pub status: ContractStatus,
+    pub health: HealthMetrics,
+}
+
+/// Tallies the reads made by `info`; every failed read becomes an issue.
+#[derive(Default)]
+struct HealthChecks {
+    total: u32,
+    failed: u32,
+    issues: Vec<String>,
+}
+
+impl HealthChecks {
+    fn record<T>(&mut self, what: &str, result: Result<T>) -> Option<T> {
+        self.total += 1;
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.failed += 1;
+                self.issues.push(format!("Failed to read {}: {}", what, e));
+                None
+            }
         }
-        Ok(output) => {
-            warn!("Failed to get contract details:");
-            warn!("{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    fn into_metrics(self, response_time: u64) -> HealthMetrics {
+        let error_rate = if self.total == 0 {
+            0.0
+        } else {
+            self.failed as f64 / self.total as f64
+        };
+        HealthMetrics {
+            is_healthy: self.issues.is_empty(),
+            response_time,
+            error_rate,
+            success_rate: 1.0 - error_rate,
+            last_check: chrono::Utc::now().timestamp().max(0) as u64,
+            issues: self.issues,
         }
-        Err(e) => {
-            warn!("Could not run soroban CLI: {}", e);
+    }
+}
+
+/// Queries a payroll contract's owner, pause state, agreements, locked value
+/// and last activity.
+///
+/// The contract keeps no token registry, so the supported tokens are the
+/// distinct tokens of its agreements and the locked value of each is the
+/// contract's balance of it. Its only circuit breaker is the emergency pause.
+/// Reads are independent: one that fails is reported as an issue and the
+/// rest of the report is still filled in. `response_time` is the latency of
+/// a `getLatestLedger` probe, in milliseconds.
+pub async fn fetch_contract_health(
+    rpc: &std::sync::Arc<SorobanRpcClient>,
+    contract_id: &str,
+) -> Result<ContractHealthReport> {
+    rpc::contract_address(contract_id)?;
+
+    let mut checks = HealthChecks::default();
+    let mut status = ContractStatus {
+        contract_id: contract_id.to_string(),
+        owner: None,
+        is_paused: false,
+        supported_tokens: Vec::new(),
+        active_payrolls: 0,
+        total_locked_value: std::collections::HashMap::new(),
+        last_activity: 0,
+        circuit_breakers: Vec::new(),
+    };
+
+    let started = std::time::Instant::now();
+    let reachable = checks
+        .record("latest ledger", rpc.get_latest_ledger().await)
+        .is_some();
+    let response_time = started.elapsed().as_millis() as u64;
+    if !reachable {
+        return Ok(ContractHealthReport {
+            status,
+            health: checks.into_metrics(response_time),
+        });
+    }
+
+    let owner = rpc
+        .get_contract_data(
+            contract_id,
+            rpc::enum_key("Owner")?,
+            stellar_xdr::curr::ContractDataDurability::Persistent,
+        )
+        .await;
+    if let Some(owner) = checks.record("owner", owner) {
+        status.owner = owner.as_ref().and_then(rpc::scval_to_address);
+        if status.owner.is_none() {
+            checks
+                .issues
+                .push("Owner is not set; the contract is not initialized".to_string());
         }
     }
 
-    Ok(())
+    let pause = rpc.view(contract_id, "get_pause_info", vec![]).await;
+    if let Some(pause) = checks.record("pause state", pause) {
+        let field = |name| rpc::struct_field(&pause, name);
+        let optional = |name| field(name).filter(|value| **value != ScVal::Void);
+        let breaker = CircuitBreakerState {
+            name: "emergency_pause".to_string(),
+            tripped: field("is_paused") == Some(&ScVal::Bool(true)),
+            tripped_at: optional("paused_at").and_then(|v| u64::try_from(v.clone()).ok()),
+            tripped_by: optional("paused_by").and_then(rpc::scval_to_address),
+            resume_at: optional("resume_at").and_then(|v| u64::try_from(v.clone()).ok()),
+            reason: optional("reason").and_then(|v| match v {
+                ScVal::String(reason) => Some(reason.to_utf8_string_lossy()),
+                _ => None,
+            }),
+        };
+        if breaker.tripped {
+            status.is_paused = true;
+            checks.issues.push(match &breaker.reason {
+                Some(reason) => format!("Emergency pause is active: {}", reason),
+                None => "Emergency pause is active".to_string(),
+            });
+        }
+        status.circuit_breakers.push(breaker);
+    }
+
+    let agreements =
+        fetch_sequential_records(rpc, contract_id, "get_agreement", INFO_CONCURRENCY).await;
+    let mut tokens = std::collections::BTreeSet::new();
+    if let Some(agreements) = checks.record("agreements", agreements) {
+        for agreement in &agreements {
+            let field = |name| rpc::struct_field(agreement, name);
+            let timestamp = |name| field(name).and_then(|v| u64::try_from(v.clone()).ok());
+            if let Some(token) = field("token").and_then(rpc::scval_to_address) {
+                tokens.insert(token);
+            }
+            if field("status").and_then(rpc::enum_variant).as_deref() == Some("Active") {
+                status.active_payrolls += 1;
+            }
+            for name in ["created_at", "activated_at", "cancelled_at"] {
+                status.last_activity = status.last_activity.max(timestamp(name).unwrap_or(0));
+            }
+        }
+    }
+
+    for token in &tokens {
+        let balance = TokenClient::new(rpc, token)
+            .balance(contract_id)
+            .await
+            .map_err(anyhow::Error::from);
+        if let Some(balance) = checks.record(&format!("balance of {}", token), balance) {
+            status.total_locked_value.insert(token.clone(), balance);
+        }
+    }
+    status.supported_tokens = tokens.into_iter().collect();
+
+    if let Some(Some(timestamp)) = checks.record(
+        "audit trail",
+        fetch_last_audit_timestamp(rpc, contract_id).await,
+    ) {
+        status.last_activity = status.last_activity.max(timestamp);
+    }
+
+    Ok(ContractHealthReport {
+        status,
+        health: checks.into_metrics(response_time),
+    })
+}
+
+/// Timestamp of the most recent lifecycle audit entry, if there is one.
+async fn fetch_last_audit_timestamp(
+    rpc: &SorobanRpcClient,
+    contract_id: &str,
+) -> Result<Option<u64>> {
+    let count = u64::try_from(
+        rpc.view(contract_id, "get_audit_entry_count", vec![])
+            .await?,
+    )
+    .map_err(|_| anyhow::anyhow!("get_audit_entry_count returned a non-integer value"))?;
+    if count == 0 {
+        return Ok(None);
+    }
+    let entry = rpc
+        .view(contract_id, "get_audit_entry", vec![count.into()])
+        .await?;
+    Ok(rpc::struct_field(&entry, "timestamp").and_then(|v| u64::try_from(v.clone()).ok()))
+}
+
+fn print_contract_health(report: &ContractHealthReport, config: &Config) {
+    let status = &report.status;
+    let health = &report.health;
+
+    outln!("Contract Information:");
+    outln!(
+        "{}",
+        format_table(
+            &["Field", "Value"],
+            &[
+                vec!["Contract ID".to_string(), status.contract_id.clone()],
+                vec!["Network RPC".to_string(), config.network.rpc_url.clone()],
+                vec![
+                    "Owner".to_string(),
+                    status
+                        .owner
+                        .clone()
+                        .unwrap_or_else(|| "Not set".to_string()),
+                ],
+                vec![
+                    "Paused".to_string(),
+                    if status.is_paused { "yes" } else { "no" }.to_string(),
+                ],
+                vec![
+                    "Active payrolls".to_string(),
+                    status.active_payrolls.to_string(),
+                ],
+                vec![
+                    "Last activity".to_string(),
+                    if status.last_activity == 0 {
+                        "None".to_string()
+                    } else {
+                        format_timestamp(status.last_activity)
+                    },
+                ],
+            ],
+        )
+    );
+
+    outln!("\nLocked Value:");
+    let mut locked: Vec<Vec<String>> = status
+        .supported_tokens
+        .iter()
+        .map(|token| {
+            vec![
+                token.clone(),
+                status
+                    .total_locked_value
+                    .get(token)
+                    .map(|value| format_amount(*value, TOKEN_DECIMALS))
+                    .unwrap_or_else(|| "unknown".to_string()),
+            ]
+        })
+        .collect();
+    locked.sort();
+    outln!("{}", format_table(&["Token", "Locked"], &locked));
+
+    outln!("\nCircuit Breakers:");
+    let breakers: Vec<Vec<String>> = status
+        .circuit_breakers
+        .iter()
+        .map(|breaker| {
+            vec![
+                breaker.name.clone(),
+                if breaker.tripped { "tripped" } else { "ok" }.to_string(),
+                breaker.tripped_at.map(format_timestamp).unwrap_or_default(),
+                breaker.resume_at.map(format_timestamp).unwrap_or_default(),
+                breaker.reason.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    outln!(
+        "{}",
+        format_table(
+            &["Breaker", "State", "Since", "Resumes", "Reason"],
+            &breakers
+        )
+    );
+
+    outln!();
+    if health.is_healthy {
+        outln!("Health: ✅ Healthy");
+    } else {
+        outln!("Health: ❌ Unhealthy");
+    }
+    outln!("  Response time: {} ms", health.response_time);
+    outln!("  Success rate: {:.0}%", health.success_rate * 100.0);
+    for issue in &health.issues {
+        outln!("  - {}", issue);
+    }
+}
+
+/// Renders a Unix timestamp as RFC 3339, or as-is if it is out of range.
+fn format_timestamp(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|d| d.to_rfc3339())
+        .unwrap_or_else(|| timestamp.to_string())
 }
 
 pub async fn status_command(config: &Config) -> Result<()> {
-    println!("StellopayCore CLI Status");
-    println!("========================");
-    println!();
+    outln!("StellopayCore CLI Status");
+    outln!("========================");
+    outln!();
 
     // Check configuration
-    println!("Configuration:");
-    println!("  Network RPC: {}", config.network.rpc_url);
-    println!(
+    outln!("Configuration:");
+    outln!("  Network RPC: {}", config.network.rpc_url);
+    outln!(
         "  Network Passphrase: {}",
         config.network.network_passphrase
     );
-    println!(
+    outln!(
         "  Default Contract ID: {}",
         config
             .contract
@@ -173,72 +766,92 @@ pub async fn status_command(config: &Config) -> Result<()> {
             .as_deref()
             .unwrap_or("Not set")
     );
-    println!();
+    outln!();
+
+    // Check that the RPC endpoint answers
+    out!("Network RPC: ");
+    let rpc = SorobanRpcClient::from_config(config);
+    match rpc.get_latest_ledger().await {
+        Ok(ledger) => outln!("✅ Reachable (latest ledger {})", ledger),
+        Err(e) => {
+            outln!("❌ Unreachable");
+            outln!("   {}", e);
+        }
+    }
 
     // Check if soroban CLI is available
-    print!("Soroban CLI: ");
+    out!("Soroban CLI: ");
     match std::process::Command::new("soroban")
         .arg("--version")
         .output()
     {
         Ok(output) if output.status.success() => {
             let version = String::from_utf8_lossy(&output.stdout);
-            println!("✅ Available ({})", version.trim());
+            outln!("✅ Available ({})", version.trim());
         }
         Ok(_) => {
-            println!("❌ Not working properly");
+            outln!("❌ Not working properly");
         }
         Err(_) => {
-            println!("❌ Not found");
-            println!("   Install with: cargo install --locked soroban-cli");
+            outln!("❌ Not found");
+            outln!("   Install with: cargo install --locked soroban-cli");
         }
     }
 
     // Check if contract WASM exists
     let wasm_path =
         PathBuf::from("../../onchain/target/wasm32v1-none/release/stello_pay_contract.wasm");
-    print!("Contract WASM: ");
+    out!("Contract WASM: ");
     if wasm_path.exists() {
-        println!("✅ Built");
+        outln!("✅ Built");
     } else {
-        println!("❌ Not found");
-        println!(
-            "   Build with: cd onchain/contracts/stello_pay_contract && soroban contract build"
-        );
+        outln!("❌ Not found");
+        outln!("   Build with: cd onchain/contracts/stello_pay_contract && soroban contract build");
     }
 
-    println!();
-    println!("Ready to use StellopayCore CLI!");
+    outln!();
+    outln!("Ready to use StellopayCore CLI!");
 
     Ok(())
 }
 
+/// Validates and submits `emergency_withdraw(token, recipient, amount)`
+/// without prompting, and returns the transaction hash. `context` labels
+/// the verbose output (the network name for the CLI command).
 pub async fn emergency_withdraw(
     config: &Config,
-    dummy_context: &str,
+    context: &str,
     contract_id: &str,
     token: &str,
     recipient: &str,
     amount: i128,
     verbose: bool,
-) -> Result<(), Error> {
+) -> Result<String, Error> {
     //verbose output
     if verbose {
-        println!("Withdrawing {} of token {} to {}", amount, token, recipient);
+        outln!(
+            "[{}] Withdrawing {} of token {} to {}",
+            context,
+            amount,
+            token,
+            recipient
+        );
     }
 
-    //get secret key from config
-    let signer = config
-        .auth
-        .secret_key
-        .clone()
-        .ok_or_else(|| anyhow::anyhow!("Missing secret key"))?;
-    //ensuring caller is admin
-    require_admin(&dummy_context)?;
-
-    //Ensuring contract is not paused
-    require_not_paused(&dummy_context)?;
+    let (signer, rpc) =
+        emergency_withdraw_preflight(config, contract_id, token, recipient, amount).await?;
+    submit_emergency_withdraw(&rpc, &signer, contract_id, token, recipient, amount).await
+}
 
+/// Checks everything that would make the withdrawal fail before anything is
+/// asked or signed, and returns the signer and client to submit with.
+async fn emergency_withdraw_preflight(
+    config: &Config,
+    contract_id: &str,
+    token: &str,
+    recipient: &str,
+    amount: i128,
+) -> Result<(TransactionSigner, SorobanRpcClient), Error> {
     //validating amount is non-zero
     if amount <= 0 {
         return Err(Error::ZeroAmount);
@@ -255,29 +868,225 @@ pub async fn emergency_withdraw(
         Err(_) => return Err(Error::InvalidAddress),
     }
 
-    //preparing soroban contract call
-    let contract_client = SorobanHttpClient::new(&config.network.rpc_url);
-    //performing token transfer
-    let token_client = TokenClient::new(&config.network.rpc_url, token);
-    token_client.transfer(&recipient, amount);
-    //emitting event for transparency
-    // env.events().publish(
-    //     (symbol_short!("emergency_withdraw"),recipient.clone()),
-    //     amount,
-    // );
-    //calling the contract function
-    contract_client
+    //resolving the configured signer
+    let signer = get_signer(config).await?;
+    let rpc = SorobanRpcClient::from_config(config);
+
+    //ensuring caller is admin
+    require_admin(&rpc, contract_id, &signer).await?;
+
+    //Ensuring contract is not paused
+    require_not_paused(&rpc, contract_id).await?;
+
+    //ensuring the contract actually holds the funds being withdrawn
+    let balance = TokenClient::new(&rpc, token).balance(contract_id).await?;
+    if balance < amount {
+        return Err(anyhow::anyhow!(
+            "Contract holds {} of token {}, cannot withdraw {}",
+            balance,
+            token,
+            amount
+        )
+        .into());
+    }
+
+    Ok((signer, rpc))
+}
+
+async fn submit_emergency_withdraw(
+    rpc: &SorobanRpcClient,
+    signer: &TransactionSigner,
+    contract_id: &str,
+    token: &str,
+    recipient: &str,
+    amount: i128,
+) -> Result<String, Error> {
+    //calling the contract function; the contract performs the transfer
+    let outcome = rpc
         .invoke(
+            signer,
             contract_id,
             "emergency_withdraw",
             vec![
-                ("token", token),
-                ("recipient", recipient),
-                ("amount", &amount.to_string()),
+                rpc::address_arg(token)?,
+                rpc::address_arg(recipient)?,
+                amount.into(),
             ],
-            &signer,
         )
         .await?;
+    info!(
+        "Emergency withdrawal confirmed in transaction {}",
+        outcome.hash
+    );
+    Ok(outcome.hash)
+}
+
+/// One line of the emergency withdrawal audit log.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EmergencyAuditEntry {
+    pub timestamp: String,
+    pub network: String,
+    pub contract_id: String,
+    pub token: String,
+    pub recipient: String,
+    pub amount: String,
+    pub reason: String,
+    pub signer: String,
+    /// `confirmed` or `failed`.
+    pub status: String,
+    pub transaction: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Runs `emergency-withdraw`: checks the withdrawal, shows what will happen,
+/// asks for confirmation and for the recipient to be typed back, submits it,
+/// and appends the outcome to the audit log.
+///
+/// `--yes` skips the first prompt only; the recipient must always be typed
+/// or given with `--confirm-recipient`.
+pub async fn emergency_withdraw_command(
+    args: EmergencyWithdrawArgs,
+    config: &Config,
+    verbose: bool,
+) -> Result<()> {
+    let contract_id = resolve_contract_id(args.contract_id.clone(), config)?;
+    let reason = args.reason.trim();
+    if reason.is_empty() {
+        return Err(anyhow::anyhow!("--reason must not be empty"));
+    }
+    validate_address(&args.token)?;
+    let network = Network::from_passphrase(&config.network.network_passphrase)
+        .map(|network| network.name())
+        .unwrap_or("custom");
+    if verbose {
+        outln!(
+            "[{}] Withdrawing {} of token {} to {}",
+            network,
+            args.amount,
+            args.token,
+            args.recipient
+        );
+    }
+
+    let (signer, rpc) = emergency_withdraw_preflight(
+        config,
+        &contract_id,
+        &args.token,
+        &args.recipient,
+        args.amount,
+    )
+    .await?;
+    let decimals = match TokenClient::new(&rpc, &args.token).decimals().await {
+        Ok(decimals) => decimals,
+        Err(e) => {
+            warn!(
+                "Could not read token decimals, assuming {}: {}",
+                TOKEN_DECIMALS, e
+            );
+            TOKEN_DECIMALS
+        }
+    };
+
+    outln!("⚠️  Emergency withdrawal");
+    outln!("  Network: {}", network);
+    outln!("  Contract: {}", contract_id);
+    outln!("  Token: {}", args.token);
+    outln!(
+        "  Amount: {} ({} base units)",
+        format_amount(args.amount, decimals),
+        args.amount
+    );
+    outln!("  Recipient: {}", args.recipient);
+    outln!("  Signer: {}", signer.address());
+    outln!("  Reason: {}", reason);
+    outln!();
+
+    // A dry run only previews, so there is nothing to confirm or record
+    if !config.dry_run {
+        if !confirm_action("Withdraw these funds from the contract?", config.assume_yes)? {
+            outln!("Aborted; nothing was submitted");
+            return Ok(());
+        }
+        let typed = match &args.confirm_recipient {
+            Some(typed) => typed.clone(),
+            None => dialoguer::Input::<String>::new()
+                .with_prompt("Type the recipient address to confirm")
+                .interact_text()
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to read confirmation ({}); pass --confirm-recipient",
+                        e
+                    )
+                })?,
+        };
+        if typed.trim() != args.recipient {
+            return Err(anyhow::anyhow!(
+                "The confirmation does not match the recipient; nothing was submitted"
+            ));
+        }
+    }
+
+    let result = submit_emergency_withdraw(
+        &rpc,
+        &signer,
+        &contract_id,
+        &args.token,
+        &args.recipient,
+        args.amount,
+    )
+    .await;
+    if let Err(Error::DryRun) = &result {
+        return Err(Error::DryRun.into());
+    }
+
+    let entry = EmergencyAuditEntry {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        network: network.to_string(),
+        contract_id,
+        token: args.token.clone(),
+        recipient: args.recipient.clone(),
+        amount: args.amount.to_string(),
+        reason: reason.to_string(),
+        signer: signer.address(),
+        status: if result.is_ok() {
+            "confirmed"
+        } else {
+            "failed"
+        }
+        .to_string(),
+        transaction: result.as_ref().ok().cloned(),
+        error: result.as_ref().err().map(|e| format!("{:#}", e)),
+    };
+    let audit_log = expand_path(&args.audit_log)?;
+    append_emergency_audit_entry(&audit_log, &entry)?;
+    output::set_result(&entry)?;
+
+    let hash = result?;
+    outln!("✅ Emergency withdrawal confirmed in transaction {}", hash);
+    outln!("Recorded in {}", audit_log.display());
+    Ok(())
+}
+
+/// Appends `entry` as one JSON line, creating the log (readable by the
+/// owner only) on first use.
+pub fn append_emergency_audit_entry(
+    path: &std::path::Path,
+    entry: &EmergencyAuditEntry,
+) -> Result<()> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
     Ok(())
 }
 
@@ -303,17 +1112,14 @@ pub async fn webhook_command(command: WebhookCommands, config: &Config) -> Resul
             active,
             contract_id,
         } => {
-            webhook_update_command(
-                webhook_id,
+            let changes = WebhookChanges {
                 name,
                 description,
                 url,
                 events,
                 active,
-                contract_id,
-                config,
-            )
-            .await
+            };
+            webhook_update_command(webhook_id, changes, contract_id, config).await
         }
         WebhookCommands::Delete {
             webhook_id,
@@ -327,11 +1133,15 @@ pub async fn webhook_command(command: WebhookCommands, config: &Config) -> Resul
             contract_id,
         } => webhook_get_command(webhook_id, contract_id, config).await,
         WebhookCommands::Stats { contract_id } => webhook_stats_command(contract_id, config).await,
+        WebhookCommands::Usage { owner, contract_id } => {
+            webhook_usage_command(owner, contract_id, config).await
+        }
         WebhookCommands::Test {
             webhook_id,
             event_type,
             contract_id,
         } => webhook_test_command(webhook_id, event_type, contract_id, config).await,
+        WebhookCommands::Relay(args) => webhook_relay(args, config).await,
     }
 }
 
@@ -344,143 +1154,148 @@ pub async fn webhook_register_command(
     contract_id: Option<String>,
     config: &Config,
 ) -> Result<()> {
-    let contract_id = contract_id
-        .or_else(|| config.contract.default_contract_id.clone())
-        .ok_or_else(|| anyhow::anyhow!("No contract ID provided"))?;
+    let contract_id = resolve_contract_id(contract_id, config)?;
 
     info!("Registering webhook: {}", name);
 
-    println!("Registering Webhook:");
-    println!("  Name: {}", name);
-    println!("  Description: {}", description);
-    println!("  URL: {}", url);
-    println!("  Events: {}", events);
-    println!("  Contract ID: {}", contract_id);
-
-    // Parse events
-    let event_list: Vec<&str> = events.split(',').map(|s| s.trim()).collect();
-
-    // Create webhook registration data structure
-    let registration_data = serde_json::json!({
-        "name": name,
-        "description": description,
-        "url": url,
-        "events": event_list,
-        "secret": secret,
-        "retry_config": {
-            "max_retries": 3,
-            "retry_delay": 60,
-            "exponential_backoff": true,
-            "max_delay": 3600
-        },
-        "security_config": {
-            "signature_method": "HmacSha256",
-            "rate_limit_per_minute": 60,
-            "require_tls": true
-        }
-    });
-
-    // Call contract to register webhook
-    let contract_client = SorobanHttpClient::new(&config.network.rpc_url);
-    let signer = config
-        .auth
-        .secret_key
-        .clone()
-        .ok_or_else(|| anyhow::anyhow!("Missing secret key"))?;
+    outln!("Registering Webhook:");
+    outln!("  Name: {}", name);
+    outln!("  Description: {}", description);
+    outln!("  URL: {}", url);
+    outln!("  Events: {}", events);
+    outln!("  Contract ID: {}", contract_id);
+
+    // The registry only stores the secret's hash; the secret itself stays local
+    let secret_hash: [u8; 32] = {
+        use sha2::{Digest, Sha256};
+        Sha256::digest(secret.as_bytes()).into()
+    };
 
-    let result = contract_client
+    let registration = rpc::struct_arg(vec![
+        ("name", rpc::string_arg(&name)?),
+        ("description", rpc::string_arg(&description)?),
+        ("url", rpc::string_arg(&url)?),
+        ("events", webhook_events_arg(&events)?),
+        ("secret_hash", ScVal::Bytes(secret_hash.to_vec().try_into()?)),
+        ("retry_config", RetryConfig::default().to_scval()?),
+        (
+            "security_config",
+            rpc::struct_arg(vec![
+                ("signature_method", rpc::enum_key("HmacSha256")?),
+                ("rate_limit_per_minute", 60_u32.into()),
+                ("require_tls", true.into()),
+            ])?,
+        ),
+    ])?;
+
+    let signer = get_signer(config).await?;
+    let rpc = SorobanRpcClient::from_config(config);
+    let outcome = rpc
         .invoke(
+            &signer,
             &contract_id,
             "register_webhook",
-            vec![("registration", &registration_data.to_string())],
-            &signer,
+            vec![rpc::address_arg(&signer.address())?, registration],
         )
         .await?;
+    let webhook_id = outcome
+        .return_value
+        .and_then(|value| u64::try_from(value).ok())
+        .ok_or_else(|| anyhow::anyhow!("register_webhook returned no webhook ID"))?;
+
+    outln!("✅ Webhook registered successfully!");
+    outln!("Webhook ID: {}", webhook_id);
 
-    println!("✅ Webhook registered successfully!");
-    println!("Webhook ID: {}", result);
+    // Reads never return the secret, so keep it locally for `webhook relay`
+    let path = expand_path(std::path::Path::new(DEFAULT_WEBHOOK_SECRETS))?;
+    save_webhook_secret(&path, webhook_id, &secret)?;
+    outln!("Signing secret saved to {}", path.display());
+    output::set_result(&serde_json::json!({ "webhook_id": webhook_id }))?;
 
     Ok(())
 }
 
+/// Encodes a comma-separated event list as a `Vec<String>` argument.
+fn webhook_events_arg(events: &str) -> Result<ScVal> {
+    let events = events
+        .split(',')
+        .map(|event| rpc::string_arg(event.trim()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ScVal::Vec(Some(events.try_into()?)))
+}
+
+/// Changes to a webhook; unset fields are left as they are.
+#[derive(Debug, Default)]
+pub struct WebhookChanges {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub events: Option<String>,
+    pub active: Option<bool>,
+}
+
+impl WebhookChanges {
+    /// Encodes the changes as the contract's `WebhookUpdate`.
+    fn to_scval(&self) -> Result<ScVal> {
+        let string = |value: &Option<String>| match value {
+            Some(value) => rpc::string_arg(value),
+            None => Ok(ScVal::Void),
+        };
+        rpc::struct_arg(vec![
+            ("name", string(&self.name)?),
+            ("description", string(&self.description)?),
+            ("url", string(&self.url)?),
+            (
+                "events",
+                match &self.events {
+                    Some(events) => webhook_events_arg(events)?,
+                    None => ScVal::Void,
+                },
+            ),
+            ("is_active", self.active.map_or(ScVal::Void, ScVal::Bool)),
+            ("secret_hash", ScVal::Void),
+        ])
+    }
+}
+
 pub async fn webhook_update_command(
     webhook_id: u64,
-    name: Option<String>,
-    description: Option<String>,
-    url: Option<String>,
-    events: Option<String>,
-    active: Option<bool>,
+    changes: WebhookChanges,
     contract_id: Option<String>,
     config: &Config,
 ) -> Result<()> {
-    let contract_id = contract_id
-        .or_else(|| config.contract.default_contract_id.clone())
-        .ok_or_else(|| anyhow::anyhow!("No contract ID provided"))?;
+    let contract_id = resolve_contract_id(contract_id, config)?;
 
     info!("Updating webhook: {}", webhook_id);
 
-    println!("Updating Webhook {}:", webhook_id);
-
-    // Create update data structure
-    let mut update_data = serde_json::Map::new();
-
-    if let Some(name) = name {
-        update_data.insert("name".to_string(), serde_json::Value::String(name.clone()));
-        println!("  Name: {}", name);
+    outln!("Updating Webhook {}:", webhook_id);
+    if let Some(name) = &changes.name {
+        outln!("  Name: {}", name);
     }
-    if let Some(description) = description {
-        update_data.insert(
-            "description".to_string(),
-            serde_json::Value::String(description.clone()),
-        );
-        println!("  Description: {}", description);
-    }
-    if let Some(url) = url {
-        update_data.insert("url".to_string(), serde_json::Value::String(url.clone()));
-        println!("  URL: {}", url);
-    }
-    if let Some(events) = events {
-        let event_list: Vec<&str> = events.split(',').map(|s| s.trim()).collect();
-        update_data.insert(
-            "events".to_string(),
-            serde_json::Value::Array(
-                event_list
-                    .iter()
-                    .map(|e| serde_json::Value::String(e.to_string()))
-                    .collect(),
-            ),
-        );
-        println!("  Events: {}", events);
+    if let Some(description) = &changes.description {
+        outln!("  Description: {}", description);
     }
-    if let Some(active) = active {
-        update_data.insert("is_active".to_string(), serde_json::Value::Bool(active));
-        println!("  Active: {}", active);
+    if let Some(url) = &changes.url {
+        outln!("  URL: {}", url);
+    }
+    if let Some(events) = &changes.events {
+        outln!("  Events: {}", events);
+    }
+    if let Some(active) = changes.active {
+        outln!("  Active: {}", active);
     }
 
-    // Call contract to update webhook
-    let contract_client = SorobanHttpClient::new(&config.network.rpc_url);
-    let signer = config
-        .auth
-        .secret_key
-        .clone()
-        .ok_or_else(|| anyhow::anyhow!("Missing secret key"))?;
-
-    contract_client
+    let signer = get_signer(config).await?;
+    SorobanRpcClient::from_config(config)
         .invoke(
+            &signer,
             &contract_id,
             "update_webhook",
-            vec![
-                ("webhook_id", &webhook_id.to_string()),
-                (
-                    "update",
-                    &serde_json::Value::Object(update_data).to_string(),
-                ),
-            ],
-            &signer,
+            vec![webhook_id.into(), changes.to_scval()?],
         )
         .await?;
 
-    println!("✅ Webhook updated successfully!");
+    outln!("✅ Webhook updated successfully!");
 
     Ok(())
 }
@@ -490,60 +1305,112 @@ pub async fn webhook_delete_command(
     contract_id: Option<String>,
     config: &Config,
 ) -> Result<()> {
-    let contract_id = contract_id
-        .or_else(|| config.contract.default_contract_id.clone())
-        .ok_or_else(|| anyhow::anyhow!("No contract ID provided"))?;
+    let contract_id = resolve_contract_id(contract_id, config)?;
 
     info!("Deleting webhook: {}", webhook_id);
 
-    println!("Deleting Webhook {}:", webhook_id);
-
-    // Call contract to delete webhook
-    let contract_client = SorobanHttpClient::new(&config.network.rpc_url);
-    let signer = config
-        .auth
-        .secret_key
-        .clone()
-        .ok_or_else(|| anyhow::anyhow!("Missing secret key"))?;
+    outln!("Deleting Webhook {}:", webhook_id);
 
-    contract_client
+    let signer = get_signer(config).await?;
+    SorobanRpcClient::from_config(config)
         .invoke(
+            &signer,
             &contract_id,
             "delete_webhook",
-            vec![("webhook_id", &webhook_id.to_string())],
-            &signer,
+            vec![webhook_id.into()],
         )
         .await?;
 
-    println!("✅ Webhook deleted successfully!");
+    outln!("✅ Webhook deleted successfully!");
 
     Ok(())
 }
 
-pub async fn webhook_list_command(
-    owner: String,
-    contract_id: Option<String>,
-    config: &Config,
-) -> Result<()> {
-    let contract_id = contract_id
-        .or_else(|| config.contract.default_contract_id.clone())
-        .ok_or_else(|| anyhow::anyhow!("No contract ID provided"))?;
-
-    info!("Listing webhooks for owner: {}", owner);
-
-    println!("Webhooks for Owner: {}", owner);
+/// Reads the IDs of an owner's webhooks with `list_owner_webhooks`.
+async fn list_owner_webhooks(
+    rpc: &SorobanRpcClient,
+    contract_id: &str,
+    owner: &str,
+) -> Result<Vec<u64>> {
+    let value = rpc
+        .view(
+            contract_id,
+            "list_owner_webhooks",
+            vec![rpc::address_arg(owner)?],
+        )
+        .await?;
+    let ids = match &value {
+        ScVal::Vec(Some(ids)) => ids
+            .iter()
+            .map(|id| u64::try_from(id.clone()).ok())
+            .collect::<Option<Vec<_>>>(),
+        _ => None,
+    };
+    ids.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unexpected list_owner_webhooks result: {}",
+            rpc::scval_to_json(&value)
+        )
+    })
+}
 
-    // Call contract to list webhooks
-    let contract_client = SorobanHttpClient::new(&config.network.rpc_url);
+/// Reads a webhook with `get_webhook`.
+async fn get_webhook(
+    rpc: &SorobanRpcClient,
+    contract_id: &str,
+    webhook_id: u64,
+) -> Result<WebhookInfo> {
+    let value = rpc
+        .view(contract_id, "get_webhook", vec![webhook_id.into()])
+        .await?;
+    WebhookInfo::from_scval(&value).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unexpected get_webhook result: {}",
+            rpc::scval_to_json(&value)
+        )
+    })
+}
 
-    let webhook_ids: Vec<u64> = contract_client
-        .query_as(&contract_id, "list_owner_webhooks", vec![("owner", &owner)])
+/// Reads an owner's quotas and usage with `get_webhook_usage`.
+async fn get_webhook_usage(
+    rpc: &SorobanRpcClient,
+    contract_id: &str,
+    owner: &str,
+) -> Result<WebhookUsage> {
+    let value = rpc
+        .view(
+            contract_id,
+            "get_webhook_usage",
+            vec![rpc::address_arg(owner)?],
+        )
         .await?;
+    WebhookUsage::from_scval(&value).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unexpected get_webhook_usage result: {}",
+            rpc::scval_to_json(&value)
+        )
+    })
+}
+
+pub async fn webhook_list_command(
+    owner: String,
+    contract_id: Option<String>,
+    config: &Config,
+) -> Result<()> {
+    let contract_id = resolve_contract_id(contract_id, config)?;
+    validate_address(&owner)?;
+
+    info!("Listing webhooks for owner: {}", owner);
+
+    outln!("Webhooks for Owner: {}", owner);
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let webhook_ids = list_owner_webhooks(&rpc, &contract_id, &owner).await?;
 
     if webhook_ids.is_empty() {
-        println!("No webhooks found for this owner.");
+        outln!("No webhooks found for this owner.");
     } else {
-        println!(
+        outln!(
             "Webhook IDs: {}",
             webhook_ids
                 .iter()
@@ -552,6 +1419,7 @@ pub async fn webhook_list_command(
                 .join(", ")
         );
     }
+    output::set_result(&webhook_ids)?;
 
     Ok(())
 }
@@ -561,33 +1429,23 @@ pub async fn webhook_get_command(
     contract_id: Option<String>,
     config: &Config,
 ) -> Result<()> {
-    let contract_id = contract_id
-        .or_else(|| config.contract.default_contract_id.clone())
-        .ok_or_else(|| anyhow::anyhow!("No contract ID provided"))?;
+    let contract_id = resolve_contract_id(contract_id, config)?;
 
     info!("Getting webhook: {}", webhook_id);
 
-    println!("Webhook Information:");
-    println!("  Webhook ID: {}", webhook_id);
+    outln!("Webhook Information:");
+    outln!("  Webhook ID: {}", webhook_id);
 
-    // Call contract to get webhook
-    let contract_client = SorobanHttpClient::new(&config.network.rpc_url);
-
-    let webhook: WebhookInfo = contract_client
-        .query_as(
-            &contract_id,
-            "get_webhook",
-            vec![("webhook_id", &webhook_id.to_string())],
-        )
-        .await?;
+    let rpc = SorobanRpcClient::from_config(config);
+    let webhook = get_webhook(&rpc, &contract_id, webhook_id).await?;
 
-    println!("  Name: {}", webhook.name.as_deref().unwrap_or("-"));
-    println!(
+    outln!("  Name: {}", webhook.name.as_deref().unwrap_or("-"));
+    outln!(
         "  Description: {}",
         webhook.description.as_deref().unwrap_or("-")
     );
-    println!("  URL: {}", webhook.url.as_deref().unwrap_or("-"));
-    println!(
+    outln!("  URL: {}", webhook.url.as_deref().unwrap_or("-"));
+    outln!(
         "  Events: {}",
         webhook
             .events
@@ -595,61 +1453,106 @@ pub async fn webhook_get_command(
             .map(|e| e.join(", "))
             .unwrap_or_else(|| "-".to_string())
     );
-    println!(
+    outln!(
         "  Active: {}",
         webhook
             .is_active
             .map(|a| a.to_string())
             .unwrap_or_else(|| "-".to_string())
     );
+    output::set_result(&webhook)?;
 
     Ok(())
 }
 
 pub async fn webhook_stats_command(contract_id: Option<String>, config: &Config) -> Result<()> {
-    let contract_id = contract_id
-        .or_else(|| config.contract.default_contract_id.clone())
-        .ok_or_else(|| anyhow::anyhow!("No contract ID provided"))?;
+    let contract_id = resolve_contract_id(contract_id, config)?;
 
     info!("Getting webhook statistics");
 
-    println!("Webhook Statistics:");
-
-    // Call contract to get webhook stats
-    let contract_client = SorobanHttpClient::new(&config.network.rpc_url);
+    outln!("Webhook Statistics:");
 
-    let stats: WebhookStats = contract_client
-        .query_as(&contract_id, "get_webhook_stats", vec![])
+    let value = SorobanRpcClient::from_config(config)
+        .view(&contract_id, "get_webhook_stats", vec![])
         .await?;
+    let stats = WebhookStats::from_scval(&value).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unexpected get_webhook_stats result: {}",
+            rpc::scval_to_json(&value)
+        )
+    })?;
 
-    println!(
+    outln!(
         "  Total Webhooks: {}",
         stats
             .total_webhooks
             .map(|v| v.to_string())
             .unwrap_or_else(|| "-".to_string())
     );
-    println!(
+    outln!(
         "  Active Webhooks: {}",
         stats
             .active_webhooks
             .map(|v| v.to_string())
             .unwrap_or_else(|| "-".to_string())
     );
-    println!(
+    outln!(
         "  Total Deliveries: {}",
         stats
             .total_deliveries
             .map(|v| v.to_string())
             .unwrap_or_else(|| "-".to_string())
     );
-    println!(
+    outln!(
         "  Failed Deliveries: {}",
         stats
             .failed_deliveries
             .map(|v| v.to_string())
             .unwrap_or_else(|| "-".to_string())
     );
+    output::set_result(&stats)?;
+
+    Ok(())
+}
+
+pub async fn webhook_usage_command(
+    owner: String,
+    contract_id: Option<String>,
+    config: &Config,
+) -> Result<()> {
+    let contract_id = resolve_contract_id(contract_id, config)?;
+    validate_address(&owner)?;
+
+    info!("Getting webhook usage for owner: {}", owner);
+
+    outln!("Webhook Usage for Owner: {}", owner);
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let usage = get_webhook_usage(&rpc, &contract_id, &owner).await?;
+
+    let used_of = |used: Option<u64>, max: Option<u64>| {
+        let used = used.map_or_else(|| "-".to_string(), |v| v.to_string());
+        match max {
+            Some(max) => format!("{} / {}", used, max),
+            None => format!("{} (no limit)", used),
+        }
+    };
+    outln!(
+        "  Endpoints: {}",
+        used_of(usage.endpoints, usage.max_endpoints)
+    );
+    outln!(
+        "  Events Today: {}",
+        used_of(usage.events_today, usage.max_events_per_day)
+    );
+    outln!(
+        "  Total Events: {}",
+        usage
+            .total_events
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    );
+    output::set_result(&usage)?;
 
     Ok(())
 }
@@ -660,38 +1563,4060 @@ pub async fn webhook_test_command(
     contract_id: Option<String>,
     config: &Config,
 ) -> Result<()> {
-    let contract_id = contract_id
-        .or_else(|| config.contract.default_contract_id.clone())
-        .ok_or_else(|| anyhow::anyhow!("No contract ID provided"))?;
+    let contract_id = resolve_contract_id(contract_id, config)?;
 
     info!("Testing webhook: {} with event: {}", webhook_id, event_type);
 
-    println!("Testing Webhook:");
-    println!("  Webhook ID: {}", webhook_id);
-    println!("  Event Type: {}", event_type);
+    outln!("Testing Webhook:");
+    outln!("  Webhook ID: {}", webhook_id);
+    outln!("  Event Type: {}", event_type);
 
-    // Call contract to test webhook
-    let contract_client = SorobanHttpClient::new(&config.network.rpc_url);
-    let signer = config
-        .auth
-        .secret_key
-        .clone()
-        .ok_or_else(|| anyhow::anyhow!("Missing secret key"))?;
-
-    let result = contract_client
+    let signer = get_signer(config).await?;
+    let outcome = SorobanRpcClient::from_config(config)
         .invoke(
+            &signer,
             &contract_id,
             "test_webhook",
+            vec![webhook_id.into(), rpc::string_arg(&event_type)?],
+        )
+        .await?;
+    let subscribed = matches!(outcome.return_value, Some(ScVal::Bool(true)));
+
+    outln!("✅ Webhook test completed!");
+    if subscribed {
+        outln!("Result: test notification published for {}", event_type);
+    } else {
+        outln!("Result: webhook is not subscribed to {}", event_type);
+    }
+    output::set_result(&serde_json::json!({ "subscribed": subscribed }))?;
+
+    Ok(())
+}
+
+/// Final outcome of delivering one event to one webhook, as reported to the
+/// contract with `record_webhook_delivery`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DeliveryResult {
+    pub webhook_id: u64,
+    pub event_id: String,
+    pub event_type: String,
+    pub success: bool,
+    pub attempts: u32,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl DeliveryResult {
+    /// Encodes the result as the contract's `DeliveryResult`.
+    pub fn to_scval(&self) -> anyhow::Result<ScVal> {
+        rpc::struct_arg(vec![
+            ("webhook_id", self.webhook_id.into()),
+            ("event_id", rpc::string_arg(&self.event_id)?),
+            ("event_type", rpc::string_arg(&self.event_type)?),
+            ("success", self.success.into()),
+            ("attempts", self.attempts.into()),
+            (
+                "status_code",
+                self.status_code
+                    .map_or(ScVal::Void, |code| ScVal::U32(code.into())),
+            ),
+            (
+                "error",
+                match &self.error {
+                    Some(error) => rpc::string_arg(error)?,
+                    None => ScVal::Void,
+                },
+            ),
+        ])
+    }
+}
+
+/// Summary of one relay poll.
+#[derive(Debug, Default)]
+pub struct RelayTick {
+    /// Contract events read in this poll.
+    pub events: usize,
+    /// Deliveries that succeeded or ran out of retries in this poll.
+    pub results: Vec<DeliveryResult>,
+    /// Deliveries still waiting for a retry.
+    pub retrying: usize,
+    /// Deliveries dropped because the owner's daily event quota is used up.
+    pub throttled: usize,
+}
+
+struct PendingDelivery {
+    webhook_id: u64,
+    url: String,
+    secret: String,
+    retry: RetryConfig,
+    event_id: String,
+    event_type: String,
+    body: String,
+    attempts: u32,
+    next_attempt_at: u64,
+}
+
+/// Relays contract events to the owner's registered webhooks.
+///
+/// Events are read from Soroban RPC `getEvents`, starting at `--start-ledger`
+/// (or the latest ledger) and then following the returned cursor. Each event
+/// is matched against the active webhooks by type, posted with an HMAC
+/// signature (see [`sign_webhook_payload`]) and retried according to the
+/// webhook's retry policy. Pending retries live in memory, so they are lost
+/// if the relay stops.
+///
+/// Deliveries beyond the owner's daily event quota (see `get_webhook_usage`)
+/// are dropped and not reported, since the contract would reject them.
+///
+/// Results are reported with `record_webhook_delivery`, which the webhook
+/// owner must sign, so reporting requires the configured signer to be the
+/// owner.
+pub struct WebhookRelay {
+    args: WebhookRelayArgs,
+    contract_id: String,
+    rpc: SorobanRpcClient,
+    http: reqwest::Client,
+    reporter: Option<TransactionSigner>,
+    from: Option<rpc::EventsFrom>,
+    pending: Vec<PendingDelivery>,
+}
+
+impl WebhookRelay {
+    pub async fn new(args: WebhookRelayArgs, config: &Config) -> Result<Self> {
+        validate_address(&args.owner)?;
+        let contract_id = resolve_contract_id(args.contract_id.clone(), config)?;
+        let reporter = if args.no_report {
+            None
+        } else {
+            let signer = get_signer(config).await?;
+            if signer.address() != args.owner {
+                return Err(anyhow::anyhow!(
+                    "Deliveries must be reported by the webhook owner {}, but the signer is {}; use --no-report to relay without reporting",
+                    args.owner,
+                    signer.address()
+                ));
+            }
+            Some(signer)
+        };
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(args.timeout_secs))
+            .build()?;
+
+        Ok(WebhookRelay {
+            from: args.start_ledger.map(rpc::EventsFrom::Ledger),
+            args: WebhookRelayArgs {
+                secrets: expand_path(&args.secrets)?,
+                ..args
+            },
+            contract_id,
+            rpc: SorobanRpcClient::from_config(config),
+            http,
+            reporter,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Reads new events, queues their deliveries and attempts every delivery
+    /// that is due at `now` (Unix seconds).
+    pub async fn tick(&mut self, now: u64) -> Result<RelayTick> {
+        let mut tick = RelayTick::default();
+
+        let from = match &self.from {
+            Some(from) => from.clone(),
+            None => rpc::EventsFrom::Ledger(self.rpc.get_latest_ledger().await?),
+        };
+        let page = self
+            .rpc
+            .get_events(&self.contract_id, &from, self.args.max_events)
+            .await?;
+        self.from = Some(page.cursor.map(rpc::EventsFrom::Cursor).unwrap_or(from));
+        tick.events = page.events.len();
+
+        if !page.events.is_empty() {
+            let webhooks = self.fetch_webhooks().await?;
+            let secrets = load_webhook_secrets(&self.args.secrets)?;
+            let mut quota_left = self.daily_quota_left().await;
+            for event in &page.events {
+                let Some(event_type) = event.event_type() else {
+                    continue;
+                };
+                let body = webhook_event_payload(event, &event_type).to_string();
+
+                for (webhook_id, webhook) in &webhooks {
+                    let subscribed = webhook
+                        .events
+                        .as_deref()
+                        .unwrap_or_default()
+                        .iter()
+                        .any(|e| e == "*" || *e == event_type);
+                    if !subscribed {
+                        continue;
+                    }
+                    let Some(secret) = secrets.get(webhook_id) else {
+                        tick.results.push(DeliveryResult {
+                            webhook_id: *webhook_id,
+                            event_id: event.id.clone(),
+                            event_type: event_type.clone(),
+                            success: false,
+                            attempts: 0,
+                            status_code: None,
+                            error: Some("No signing secret configured".to_string()),
+                        });
+                        continue;
+                    };
+                    match quota_left.as_mut() {
+                        Some(0) => {
+                            tick.throttled += 1;
+                            continue;
+                        }
+                        Some(left) => *left -= 1,
+                        None => {}
+                    }
+                    self.pending.push(PendingDelivery {
+                        webhook_id: *webhook_id,
+                        url: webhook.url.clone().unwrap_or_default(),
+                        secret: secret.clone(),
+                        retry: webhook.retry_config.clone().unwrap_or_default(),
+                        event_id: event.id.clone(),
+                        event_type: event_type.clone(),
+                        body: body.clone(),
+                        attempts: 0,
+                        next_attempt_at: now,
+                    });
+                }
+            }
+        }
+
+        let mut waiting = Vec::new();
+        for mut delivery in std::mem::take(&mut self.pending) {
+            if delivery.next_attempt_at > now {
+                waiting.push(delivery);
+                continue;
+            }
+
+            delivery.attempts += 1;
+            let (status_code, error) = self.deliver(&delivery, now).await;
+            let retries_left = delivery.attempts <= delivery.retry.max_retries;
+            if error.is_some() && retries_left {
+                delivery.next_attempt_at = now + delivery.retry.delay_for(delivery.attempts);
+                waiting.push(delivery);
+                continue;
+            }
+
+            tick.results.push(DeliveryResult {
+                webhook_id: delivery.webhook_id,
+                event_id: delivery.event_id,
+                event_type: delivery.event_type,
+                success: error.is_none(),
+                attempts: delivery.attempts,
+                status_code,
+                error,
+            });
+        }
+        self.pending = waiting;
+        tick.retrying = self.pending.len();
+
+        if let Some(signer) = &self.reporter {
+            for result in &tick.results {
+                let reported = match result.to_scval() {
+                    Ok(delivery) => self
+                        .rpc
+                        .invoke(
+                            signer,
+                            &self.contract_id,
+                            "record_webhook_delivery",
+                            vec![result.webhook_id.into(), delivery],
+                        )
+                        .await
+                        .map(|_| ()),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = reported {
+                    warn!(
+                        "Failed to report delivery of {} to webhook {}: {}",
+                        result.event_id, result.webhook_id, e
+                    );
+                }
+            }
+        }
+
+        Ok(tick)
+    }
+
+    /// Deliveries the owner can still queue today: its remaining daily event
+    /// quota less the deliveries already waiting for a retry. `None` when no
+    /// quota is enforced or usage cannot be read.
+    async fn daily_quota_left(&self) -> Option<u64> {
+        match get_webhook_usage(&self.rpc, &self.contract_id, &self.args.owner).await {
+            Ok(usage) => usage
+                .events_left_today()
+                .map(|left| left.saturating_sub(self.pending.len() as u64)),
+            Err(e) => {
+                warn!(
+                    "Could not read webhook usage of {}, relaying without quota: {}",
+                    self.args.owner, e
+                );
+                None
+            }
+        }
+    }
+
+    /// Active webhooks of the owner that have a delivery URL.
+    async fn fetch_webhooks(&self) -> Result<Vec<(u64, WebhookInfo)>> {
+        let ids = list_owner_webhooks(&self.rpc, &self.contract_id, &self.args.owner).await?;
+
+        let mut webhooks = Vec::with_capacity(ids.len());
+        for id in ids {
+            let webhook = get_webhook(&self.rpc, &self.contract_id, id).await?;
+            if webhook.is_active != Some(false) && webhook.url.is_some() {
+                webhooks.push((id, webhook));
+            }
+        }
+        Ok(webhooks)
+    }
+
+    /// Makes one delivery attempt, returning the HTTP status and, on
+    /// failure, the error.
+    async fn deliver(&self, delivery: &PendingDelivery, now: u64) -> (Option<u16>, Option<String>) {
+        let response = self
+            .http
+            .post(&delivery.url)
+            .header("Content-Type", "application/json")
+            .header("X-Stellopay-Event", &delivery.event_type)
+            .header(
+                "X-Stellopay-Delivery",
+                format!("{}-{}", delivery.webhook_id, delivery.event_id),
+            )
+            .header("X-Stellopay-Timestamp", now.to_string())
+            .header(
+                "X-Stellopay-Signature",
+                sign_webhook_payload(&delivery.secret, now, &delivery.body),
+            )
+            .body(delivery.body.clone())
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => {
+                (Some(response.status().as_u16()), None)
+            }
+            Ok(response) => (
+                Some(response.status().as_u16()),
+                Some(format!("HTTP {}", response.status())),
+            ),
+            Err(e) => (None, Some(e.to_string())),
+        }
+    }
+}
+
+/// JSON body posted to webhooks for a contract event.
+pub fn webhook_event_payload(event: &rpc::ContractEvent, event_type: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": event.id,
+        "event_type": event_type,
+        "contract_id": event.contract_id,
+        "ledger": event.ledger,
+        "ledger_closed_at": event.ledger_closed_at,
+        "transaction_hash": event.transaction_hash,
+        "topics": event.topics.iter().map(rpc::scval_to_json).collect::<Vec<_>>(),
+        "data": rpc::scval_to_json(&event.value),
+    })
+}
+
+/// Long-running loop for `webhook relay`, stopped by Ctrl-C or `--max-ticks`.
+pub async fn webhook_relay(args: WebhookRelayArgs, config: &Config) -> Result<()> {
+    if args.interval_secs == 0 || args.max_events == 0 {
+        return Err(anyhow::anyhow!(
+            "--interval-secs and --max-events must be greater than zero"
+        ));
+    }
+    let max_ticks = args.max_ticks;
+    let interval_secs = args.interval_secs;
+    let mut relay = WebhookRelay::new(args, config).await?;
+
+    info!(
+        "Relaying events of {} every {}s",
+        relay.contract_id, interval_secs
+    );
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+    let mut ticks = 0u64;
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = tokio::signal::ctrl_c() => {
+                info!("Webhook relay stopped");
+                return Ok(());
+            }
+        }
+
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        match relay.tick(now).await {
+            Ok(tick) => {
+                for result in &tick.results {
+                    if result.success {
+                        outln!(
+                            "✅ Delivered {} ({}) to webhook {} after {} attempt(s)",
+                            result.event_type,
+                            result.event_id,
+                            result.webhook_id,
+                            result.attempts
+                        );
+                    } else {
+                        outln!(
+                            "❌ Failed to deliver {} ({}) to webhook {}: {}",
+                            result.event_type,
+                            result.event_id,
+                            result.webhook_id,
+                            result.error.as_deref().unwrap_or("unknown error")
+                        );
+                    }
+                }
+                if tick.retrying > 0 {
+                    info!("{} deliveries waiting for retry", tick.retrying);
+                }
+                if tick.throttled > 0 {
+                    warn!(
+                        "Dropped {} deliveries: daily event quota reached",
+                        tick.throttled
+                    );
+                }
+            }
+            Err(e) => warn!("Webhook relay poll failed: {}", e),
+        }
+
+        ticks += 1;
+        if max_ticks.is_some_and(|max| ticks >= max) {
+            return Ok(());
+        }
+    }
+}
+
+pub async fn employee_command(command: EmployeeCommands, config: &Config) -> Result<()> {
+    match command {
+        EmployeeCommands::Create { payroll } => employee_create_command(payroll, config).await,
+        EmployeeCommands::Pause { target } => {
+            employee_agreement_command(target, "pause_agreement", "paused", config).await
+        }
+        EmployeeCommands::Resume { target } => {
+            employee_agreement_command(target, "resume_agreement", "resumed", config).await
+        }
+        EmployeeCommands::Cancel { target } => {
+            employee_agreement_command(target, "cancel_agreement", "cancelled", config).await
+        }
+        EmployeeCommands::Show { target } => employee_show_command(target, config).await,
+    }
+}
+
+/// Creates a payroll agreement paying one employee: `create_payroll_agreement`
+/// with the signer as employer, then `add_employee_to_agreement`, then
+/// `activate_agreement` unless `--no-activate` is given.
+///
+/// The printed agreement ID is what `employee pause|resume|cancel` take.
+pub async fn employee_create_command(payroll: EmployeePayrollArgs, config: &Config) -> Result<()> {
+    let contract_id = resolve_contract_id(payroll.contract_id.clone(), config)?;
+    validate_address(&payroll.employee)?;
+    let (token, salary, grace_period_seconds) = resolve_payroll_terms(&payroll, config)?;
+
+    let signer = get_signer(config).await?;
+    let rpc = SorobanRpcClient::from_config(config);
+
+    info!(
+        "Creating payroll agreement for employee: {}",
+        payroll.employee
+    );
+    let outcome = rpc
+        .invoke(
+            &signer,
+            &contract_id,
+            "create_payroll_agreement",
             vec![
-                ("webhook_id", &webhook_id.to_string()),
-                ("event_type", &event_type),
+                rpc::address_arg(&signer.address())?,
+                rpc::address_arg(&token)?,
+                grace_period_seconds.into(),
             ],
+        )
+        .await?;
+    let agreement_id = outcome
+        .return_value
+        .and_then(|value| u128::try_from(value).ok())
+        .ok_or_else(|| {
+            anyhow::anyhow!("create_payroll_agreement did not return an agreement ID")
+        })?;
+
+    rpc.invoke(
+        &signer,
+        &contract_id,
+        "add_employee_to_agreement",
+        vec![
+            agreement_id.into(),
+            rpc::address_arg(&payroll.employee)?,
+            salary.into(),
+        ],
+    )
+    .await
+    .map_err(|e| {
+        error!("Adding employee {} failed: {}", payroll.employee, e);
+        anyhow::anyhow!(
+            "Agreement {} was created but adding employee {} failed",
+            agreement_id,
+            payroll.employee
+        )
+    })?;
+
+    if !payroll.no_activate {
+        rpc.invoke(
+            &signer,
+            &contract_id,
+            "activate_agreement",
+            vec![agreement_id.into()],
+        )
+        .await?;
+    }
+
+    output::set_result(&serde_json::json!({
+        "agreement_id": agreement_id.to_string(),
+        "employee": payroll.employee,
+        "token": token,
+        "salary": salary.to_string(),
+        "grace_period_seconds": grace_period_seconds,
+        "activated": !payroll.no_activate,
+    }))?;
+    outln!("✅ Payroll agreement {} created!", agreement_id);
+    outln!("  Employee: {}", payroll.employee);
+    outln!("  Token: {}", token);
+    outln!(
+        "  Salary per period: {}",
+        format_amount(salary, TOKEN_DECIMALS)
+    );
+    outln!("  Grace period: {}", format_duration(grace_period_seconds));
+    outln!(
+        "  Status: {}",
+        if payroll.no_activate {
+            "created (not activated)"
+        } else {
+            "active"
+        }
+    );
+
+    Ok(())
+}
+
+async fn employee_agreement_command(
+    target: EmployeeAgreementTarget,
+    method: &str,
+    past_tense: &str,
+    config: &Config,
+) -> Result<()> {
+    let contract_id = resolve_contract_id(target.contract_id, config)?;
+    let signer = get_signer(config).await?;
+
+    info!("Calling {} for agreement: {}", method, target.agreement_id);
+
+    SorobanRpcClient::from_config(config)
+        .invoke(
             &signer,
+            &contract_id,
+            method,
+            vec![target.agreement_id.into()],
         )
         .await?;
 
-    println!("✅ Webhook test completed!");
-    println!("Result: {}", result);
+    outln!(
+        "✅ Payroll agreement {} {}!",
+        target.agreement_id,
+        past_tense
+    );
+
+    Ok(())
+}
+
+/// Prints the employee's position on `--agreement-id` (`get_payroll`), or
+/// every current position they hold (`get_employee_payrolls`).
+pub async fn employee_show_command(target: EmployeeTarget, config: &Config) -> Result<()> {
+    let contract_id = resolve_contract_id(target.contract_id, config)?;
+    validate_address(&target.employee)?;
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let employee = rpc::address_arg(&target.employee)?;
+    let payrolls = match target.agreement_id {
+        Some(agreement_id) => {
+            let value = rpc
+                .view(
+                    &contract_id,
+                    "get_payroll",
+                    vec![employee, agreement_id.into()],
+                )
+                .await?;
+            match value {
+                ScVal::Void => Vec::new(),
+                value => vec![EmployeePayroll::from_scval(&value).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unexpected get_payroll result: {}",
+                        rpc::scval_to_json(&value)
+                    )
+                })?],
+            }
+        }
+        None => {
+            let value = rpc
+                .view(&contract_id, "get_employee_payrolls", vec![employee])
+                .await?;
+            let ScVal::Vec(Some(items)) = &value else {
+                return Err(anyhow::anyhow!(
+                    "Unexpected get_employee_payrolls result: {}",
+                    rpc::scval_to_json(&value)
+                ));
+            };
+            items
+                .iter()
+                .map(|item| {
+                    EmployeePayroll::from_scval(item).ok_or_else(|| {
+                        anyhow::anyhow!("Unexpected payroll record: {}", rpc::scval_to_json(item))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?
+        }
+    };
+
+    output::set_result(&payrolls)?;
+    if payrolls.is_empty() {
+        outln!("No payroll positions found for {}", target.employee);
+    }
+    for payroll in &payrolls {
+        print_payroll_info(&target.employee, payroll);
+    }
+
+    Ok(())
+}
+
+/// Merges payroll terms from flags, the optional TOML file and config
+/// defaults, in that order of precedence.
+///
+/// Returns `(token, salary_in_stroops, grace_period_seconds)`.
+pub fn resolve_payroll_terms(
+    payroll: &EmployeePayrollArgs,
+    config: &Config,
+) -> Result<(String, i128, u64)> {
+    let file = match &payroll.file {
+        Some(path) => {
+            let content = std::fs::read_to_string(path).map_err(|e| {
+                anyhow::anyhow!("Failed to read payroll file {}: {}", path.display(), e)
+            })?;
+            toml::from_str::<EmployeePayrollFile>(&content)
+                .map_err(|e| anyhow::anyhow!("Invalid payroll file {}: {}", path.display(), e))?
+        }
+        None => EmployeePayrollFile::default(),
+    };
+
+    let token = payroll
+        .token
+        .clone()
+        .or(file.token)
+        .or_else(|| config.defaults.token.clone())
+        .ok_or_else(|| anyhow::anyhow!("No token provided"))?;
+    validate_address(&token)?;
+
+    let amount = payroll
+        .amount
+        .clone()
+        .or(file.amount)
+        .ok_or_else(|| anyhow::anyhow!("No amount provided"))?;
+    let amount = parse_amount(&amount, TOKEN_DECIMALS)?;
+    if amount <= 0 {
+        return Err(Error::ZeroAmount.into());
+    }
+
+    let grace_period_seconds = match payroll.grace_period.clone().or(file.grace_period) {
+        Some(grace_period) => parse_duration(&grace_period)
+            .map_err(|_| anyhow::anyhow!("Invalid grace period: {}", grace_period))?,
+        None => 0,
+    };
+
+    Ok((token, amount, grace_period_seconds))
+}
+
+fn resolve_contract_id(contract_id: Option<String>, config: &Config) -> Result<String> {
+    contract_id
+        .or_else(|| config.contract.default_contract_id.clone())
+        .ok_or_else(|| anyhow::anyhow!("No contract ID provided"))
+}
+
+/// An employee's position on a payroll agreement, as returned by the payroll
+/// contract's `get_payroll` and `get_employee_payrolls`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct EmployeePayroll {
+    pub agreement_id: u128,
+    pub employee_index: u32,
+    pub employer: String,
+    pub token: String,
+    pub status: String,
+    /// `None` when the salary is confidential.
+    pub salary_per_period: Option<i128>,
+    pub period_seconds: u64,
+    pub claimed_periods: u32,
+    pub activated_at: u64,
+}
+
+impl EmployeePayroll {
+    /// Decodes a `Payroll`.
+    pub fn from_scval(value: &ScVal) -> Option<Self> {
+        let field = |name| rpc::struct_field(value, name);
+        Some(EmployeePayroll {
+            agreement_id: u128::try_from(field("agreement_id")?.clone()).ok()?,
+            employee_index: u32::try_from(field("employee_index")?.clone()).ok()?,
+            employer: rpc::scval_to_address(field("employer")?)?,
+            token: rpc::scval_to_address(field("token")?)?,
+            status: rpc::enum_variant(field("status")?)?,
+            salary_per_period: match field("salary_per_period")? {
+                ScVal::Void => None,
+                value => Some(i128::try_from(value.clone()).ok()?),
+            },
+            period_seconds: u64::try_from(field("period_seconds")?.clone()).ok()?,
+            claimed_periods: u32::try_from(field("claimed_periods")?.clone()).ok()?,
+            activated_at: u64::try_from(field("activated_at")?.clone()).ok()?,
+        })
+    }
+}
+
+pub fn print_payroll_info(employee: &str, record: &EmployeePayroll) {
+    outln!("Payroll Record:");
+    outln!("  Agreement ID: {}", record.agreement_id);
+    outln!("  Employee: {}", employee);
+    outln!("  Employer: {}", record.employer);
+    outln!("  Token: {}", record.token);
+    outln!(
+        "  Salary per period: {}",
+        record
+            .salary_per_period
+            .map(|salary| format_amount(salary, TOKEN_DECIMALS))
+            .unwrap_or_else(|| "confidential".to_string())
+    );
+    outln!("  Period: {}", seconds_to_frequency(record.period_seconds));
+    outln!("  Claimed periods: {}", record.claimed_periods);
+    outln!("  Activated at: {}", record.activated_at);
+    outln!("  Status: {}", record.status);
+}
+
+/// Outcome of one employee's payment in a bulk run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkPayResult {
+    pub entry: BulkPayEntry,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+/// Runs `pay --employees <CSV>`: transfers each employee's amount from the
+/// signer with the token's `transfer`, retrying a failed payment with
+/// exponential backoff, and prints a per-employee report.
+///
+/// Fails (after printing the report) if any employee could not be paid.
+pub async fn pay_command(args: BulkPayArgs, config: &Config) -> Result<()> {
+    let results = bulk_pay(&args, config).await?;
+    print_bulk_pay_report(&results);
+
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "{} of {} payments failed",
+            failed,
+            results.len()
+        ));
+    }
 
     Ok(())
 }
+
+/// Submits the bulk run described by `args` and returns one result per CSV row.
+///
+/// Every row needs an amount, and the signer's balance must cover the total
+/// before anything is submitted.
+pub async fn bulk_pay(args: &BulkPayArgs, config: &Config) -> Result<Vec<BulkPayResult>> {
+    let token = args
+        .token
+        .clone()
+        .or_else(|| config.defaults.token.clone())
+        .ok_or_else(|| anyhow::anyhow!("No token provided"))?;
+    validate_address(&token)?;
+
+    let content = std::fs::read_to_string(&args.employees).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to read employees file {}: {}",
+            args.employees.display(),
+            e
+        )
+    })?;
+    let entries = parse_employee_csv(&content)?;
+    if entries.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No employees found in {}",
+            args.employees.display()
+        ));
+    }
+    let mut total: i128 = 0;
+    for entry in &entries {
+        let amount = entry
+            .amount
+            .ok_or_else(|| anyhow::anyhow!("No amount given for employee {}", entry.employee))?;
+        total = total
+            .checked_add(amount)
+            .ok_or_else(|| anyhow::anyhow!("Total amount overflows"))?;
+    }
+
+    let signer = get_signer(config).await?;
+    let rpc = SorobanRpcClient::from_config(config);
+    let token_client = TokenClient::new(&rpc, &token);
+    let balance = token_client.balance(&signer.address()).await?;
+    if balance < total {
+        return Err(anyhow::anyhow!(
+            "Signer holds {} of token {}, cannot pay {}",
+            format_amount(balance, TOKEN_DECIMALS),
+            token,
+            format_amount(total, TOKEN_DECIMALS)
+        ));
+    }
+
+    let mut results = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.iter().enumerate() {
+        let amount = entry.amount.unwrap_or_default();
+        info!(
+            "Paying employee {}/{}: {}",
+            index + 1,
+            entries.len(),
+            entry.employee
+        );
+
+        let mut attempts = 0;
+        let error = loop {
+            attempts += 1;
+            match token_client
+                .transfer(&signer, &entry.employee, amount)
+                .await
+            {
+                Ok(_) => break None,
+                Err(e) if attempts > args.retries => break Some(e.to_string()),
+                Err(e) => {
+                    warn!(
+                        "Payment to {} failed (attempt {}): {}",
+                        entry.employee, attempts, e
+                    );
+                    let delay = args
+                        .retry_delay_ms
+                        .saturating_mul(1 << (attempts - 1).min(16));
+                    tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                }
+            }
+        };
+
+        results.push(BulkPayResult {
+            entry: entry.clone(),
+            attempts,
+            error,
+        });
+    }
+
+    Ok(results)
+}
+
+fn print_bulk_pay_report(results: &[BulkPayResult]) {
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|r| {
+            vec![
+                r.entry.employee.clone(),
+                format_amount(r.entry.amount.unwrap_or_default(), TOKEN_DECIMALS),
+                if r.error.is_some() { "failed" } else { "paid" }.to_string(),
+                r.attempts.to_string(),
+                r.error.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    outln!(
+        "{}",
+        format_table(
+            &["Employee", "Amount", "Status", "Attempts", "Error"],
+            &rows
+        )
+    );
+    let paid = results.iter().filter(|r| r.error.is_none()).count();
+    outln!("Paid {} of {} employees", paid, results.len());
+}
+
+/// A PaymentScheduler job.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SchedulerJob {
+    pub id: u128,
+    pub employer: String,
+    pub recipient: String,
+    pub token: String,
+    pub amount: i128,
+    pub interval_seconds: u64,
+    pub next_scheduled_time: u64,
+    pub max_executions: Option<u32>,
+    pub executions: u32,
+    pub retry_count: u32,
+    pub status: String,
+}
+
+impl SchedulerJob {
+    fn from_scval(value: &ScVal) -> Option<Self> {
+        let field = |name| rpc::struct_field(value, name);
+        Some(SchedulerJob {
+            id: u128::try_from(field("id")?.clone()).ok()?,
+            employer: rpc::scval_to_address(field("employer")?)?,
+            recipient: rpc::scval_to_address(field("recipient")?)?,
+            token: rpc::scval_to_address(field("token")?)?,
+            amount: i128::try_from(field("amount")?.clone()).ok()?,
+            interval_seconds: u64::try_from(field("interval_seconds")?.clone()).ok()?,
+            next_scheduled_time: u64::try_from(field("next_scheduled_time")?.clone()).ok()?,
+            max_executions: match field("max_executions")? {
+                ScVal::Void => None,
+                value => Some(u32::try_from(value.clone()).ok()?),
+            },
+            executions: u32::try_from(field("executions")?.clone()).ok()?,
+            retry_count: u32::try_from(field("retry_count")?.clone()).ok()?,
+            status: rpc::enum_variant(field("status")?)?,
+        })
+    }
+
+    pub fn is_due(&self, now: u64) -> bool {
+        self.status == "Active" && self.next_scheduled_time <= now
+    }
+}
+
+/// What a single keeper poll found and did.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct KeeperTick {
+    /// Due jobs of the filtered employer (empty when not filtering).
+    pub employer_due_jobs: Vec<u128>,
+    /// Jobs `process_due_payments` reported it would evaluate on the first pass.
+    pub pending: u32,
+    /// Jobs evaluated by submitted transactions.
+    pub processed: u32,
+    /// Hashes of submitted transactions.
+    pub transactions: Vec<String>,
+}
+
+pub async fn keeper_command(command: KeeperCommands, config: &Config) -> Result<()> {
+    match command {
+        KeeperCommands::Run(args) => keeper_run(args, config).await,
+    }
+}
+
+/// Long-running keeper loop for `keeper run`.
+///
+/// Each poll runs [`keeper_tick`]; failures are logged and retried on the next
+/// poll so that a flaky RPC node does not stop the keeper. Stops on Ctrl-C or
+/// after `--max-ticks` polls.
+pub async fn keeper_run(args: KeeperRunArgs, config: &Config) -> Result<()> {
+    if args.interval_secs == 0 {
+        return Err(anyhow::anyhow!("--interval-secs must be greater than zero"));
+    }
+    if args.max_jobs == 0 || args.concurrency == 0 {
+        return Err(anyhow::anyhow!(
+            "--max-jobs and --concurrency must be greater than zero"
+        ));
+    }
+    if let Some(employer) = &args.employer {
+        validate_address(employer)?;
+    }
+    let signer = if args.dry_run {
+        None
+    } else {
+        Some(get_signer(config).await?)
+    };
+    let rpc = std::sync::Arc::new(SorobanRpcClient::from_config(config));
+
+    info!(
+        "Keeper watching scheduler {} every {}s{}",
+        args.scheduler_id,
+        args.interval_secs,
+        if args.dry_run { " (dry run)" } else { "" }
+    );
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(args.interval_secs));
+    let mut ticks = 0u64;
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = tokio::signal::ctrl_c() => {
+                info!("Keeper stopped");
+                return Ok(());
+            }
+        }
+
+        match keeper_tick(&rpc, &args, signer.as_ref()).await {
+            Ok(tick) if args.dry_run && tick.pending > 0 => {
+                outln!("[dry run] {} due job(s) would be processed", tick.pending);
+            }
+            Ok(tick) if !tick.transactions.is_empty() => {
+                outln!(
+                    "Processed {} job(s) in {} transaction(s): {}",
+                    tick.processed,
+                    tick.transactions.len(),
+                    tick.transactions.join(", ")
+                );
+            }
+            Ok(_) => info!("No due jobs"),
+            Err(e) => warn!("Keeper poll failed: {}", e),
+        }
+
+        ticks += 1;
+        if args.max_ticks.is_some_and(|max| ticks >= max) {
+            return Ok(());
+        }
+    }
+}
+
+/// Runs one keeper poll against the scheduler.
+///
+/// With `--employer`, jobs are listed first and nothing is submitted unless
+/// that employer has a due job. `process_due_payments` is then simulated to
+/// learn how many jobs it would evaluate; outside dry-run mode it is submitted
+/// repeatedly while full batches of `max_jobs` keep coming back.
+pub async fn keeper_tick(
+    rpc: &std::sync::Arc<SorobanRpcClient>,
+    args: &KeeperRunArgs,
+    signer: Option<&TransactionSigner>,
+) -> Result<KeeperTick> {
+    let mut tick = KeeperTick::default();
+
+    if let Some(employer) = &args.employer {
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        tick.employer_due_jobs = fetch_scheduler_jobs(rpc, &args.scheduler_id, args.concurrency)
+            .await?
+            .into_iter()
+            .filter(|job| &job.employer == employer && job.is_due(now))
+            .map(|job| job.id)
+            .collect();
+        if tick.employer_due_jobs.is_empty() {
+            return Ok(tick);
+        }
+    }
+
+    loop {
+        let pending = u32::try_from(
+            rpc.view(
+                &args.scheduler_id,
+                "process_due_payments",
+                vec![args.max_jobs.into()],
+            )
+            .await?,
+        )
+        .map_err(|_| anyhow::anyhow!("process_due_payments returned a non-u32 value"))?;
+        if tick.transactions.is_empty() {
+            tick.pending = pending;
+        }
+
+        let Some(signer) = signer.filter(|_| pending > 0) else {
+            return Ok(tick);
+        };
+
+        let outcome = rpc
+            .invoke(
+                signer,
+                &args.scheduler_id,
+                "process_due_payments",
+                vec![args.max_jobs.into()],
+            )
+            .await?;
+        let processed = outcome
+            .return_value
+            .and_then(|value| u32::try_from(value).ok())
+            .unwrap_or(pending);
+        tick.processed += processed;
+        tick.transactions.push(outcome.hash);
+
+        if processed < args.max_jobs {
+            return Ok(tick);
+        }
+    }
+}
+
+/// Lists scheduler jobs by walking sequential ids until the first gap,
+/// looking up `concurrency` ids at a time.
+pub async fn fetch_scheduler_jobs(
+    rpc: &std::sync::Arc<SorobanRpcClient>,
+    scheduler_id: &str,
+    concurrency: usize,
+) -> Result<Vec<SchedulerJob>> {
+    fetch_sequential_records(rpc, scheduler_id, "get_job", concurrency)
+        .await?
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            SchedulerJob::from_scval(&value).ok_or_else(|| {
+                anyhow::anyhow!("Scheduler job {} has an unexpected shape", index + 1)
+            })
+        })
+        .collect()
+}
+
+/// Calls `function(id)` for ids 1, 2, ... until it returns `None`, looking up
+/// `concurrency` ids at a time, and returns the records in id order.
+async fn fetch_sequential_records(
+    rpc: &std::sync::Arc<SorobanRpcClient>,
+    contract_id: &str,
+    function: &'static str,
+    concurrency: usize,
+) -> Result<Vec<ScVal>> {
+    let mut records = Vec::new();
+    let mut next_id = 1u128;
+
+    loop {
+        let mut lookups = tokio::task::JoinSet::new();
+        for id in next_id..next_id + concurrency as u128 {
+            let rpc = rpc.clone();
+            let contract_id = contract_id.to_string();
+            lookups.spawn(async move {
+                let record = rpc.view(&contract_id, function, vec![id.into()]).await;
+                (id, record)
+            });
+        }
+
+        let mut window = Vec::new();
+        while let Some(joined) = lookups.join_next().await {
+            let (id, record) = joined?;
+            window.push((id, record?));
+        }
+        window.sort_by_key(|(id, _)| *id);
+
+        for (_, value) in window {
+            if value == ScVal::Void {
+                return Ok(records);
+            }
+            records.push(value);
+        }
+        next_id += concurrency as u128;
+    }
+}
+
+pub async fn schedule_command(command: ScheduleCommands, config: &Config) -> Result<()> {
+    match command {
+        ScheduleCommands::Create {
+            scheduler,
+            recipient,
+            amount,
+            token,
+            interval,
+            start,
+            max_executions,
+            max_retries,
+        } => {
+            validate_address(&recipient)?;
+            let amount = parse_positive_amount(&amount)?;
+            let token = token
+                .or_else(|| config.defaults.token.clone())
+                .ok_or_else(|| anyhow::anyhow!("No token provided"))?;
+            validate_address(&token)?;
+            let start = match start {
+                Some(start) => parse_report_date(&start)?,
+                None => chrono::Utc::now().timestamp().max(0) as u64,
+            };
+            // The contract only accepts a zero interval for one-time payments
+            let (interval, max_executions) = match interval {
+                Some(interval) => {
+                    let seconds = match interval.parse::<u64>() {
+                        Ok(seconds) => seconds,
+                        Err(_) => {
+                            frequency_to_seconds(&interval).map_err(|e| anyhow::anyhow!(e))?
+                        }
+                    };
+                    if seconds == 0 {
+                        return Err(anyhow::anyhow!("--interval must be greater than zero"));
+                    }
+                    (seconds, max_executions)
+                }
+                None if max_executions.map_or(true, |max| max == 1) => (0, Some(1)),
+                None => {
+                    return Err(anyhow::anyhow!(
+                        "--max-executions above 1 requires --interval"
+                    ))
+                }
+            };
+
+            let signer = get_signer(config).await?;
+            let rpc = SorobanRpcClient::from_config(config);
+            let outcome = rpc
+                .invoke(
+                    &signer,
+                    &scheduler.scheduler_id,
+                    "create_job",
+                    vec![
+                        rpc::address_arg(&signer.address())?,
+                        rpc::address_arg(&recipient)?,
+                        rpc::address_arg(&token)?,
+                        amount.into(),
+                        interval.into(),
+                        start.into(),
+                        max_executions.map_or(ScVal::Void, ScVal::from),
+                        max_retries.into(),
+                    ],
+                )
+                .await?;
+            let id = outcome
+                .return_value
+                .and_then(|value| u128::try_from(value).ok())
+                .ok_or_else(|| anyhow::anyhow!("create_job did not return a schedule ID"))?;
+
+            outln!("✅ Schedule {} created", id);
+            let job = fetch_scheduler_job(&rpc, &scheduler.scheduler_id, id).await?;
+            output::set_result(&job)?;
+            print_scheduler_job(&job);
+            outln!("Fund it with the scheduler's fund_job before it comes due.");
+            Ok(())
+        }
+        ScheduleCommands::List {
+            scheduler,
+            employer,
+            status,
+            format,
+        } => {
+            let jobs: Vec<SchedulerJob> = list_schedules(&scheduler, config)
+                .await?
+                .into_iter()
+                .filter(|job| employer.as_ref().map_or(true, |e| *e == job.employer))
+                .filter(|job| {
+                    status
+                        .as_ref()
+                        .map_or(true, |s| s.eq_ignore_ascii_case(&job.status))
+                })
+                .collect();
+            match format {
+                OutputFormat::Json => output::emit_json(&jobs)?,
+                OutputFormat::Table => {
+                    output::set_result(&jobs)?;
+                    print_schedule_table(&jobs)
+                }
+            }
+            Ok(())
+        }
+        ScheduleCommands::Update {
+            scheduler,
+            id,
+            action,
+        } => {
+            let signer = get_signer(config).await?;
+            let rpc = SorobanRpcClient::from_config(config);
+            let job = fetch_scheduler_job(&rpc, &scheduler.scheduler_id, id).await?;
+            if job.employer != signer.address() {
+                return Err(anyhow::anyhow!(
+                    "Schedule {} belongs to employer {}",
+                    id,
+                    job.employer
+                ));
+            }
+            let (function, done) = match action {
+                ScheduleAction::Pause => ("pause_job", "paused"),
+                ScheduleAction::Resume => ("resume_job", "resumed"),
+                ScheduleAction::Cancel => ("cancel_job", "cancelled"),
+            };
+            if action == ScheduleAction::Cancel
+                && !confirm_action(
+                    &format!("Cancel schedule {}? This cannot be undone", id),
+                    config.assume_yes || config.dry_run,
+                )?
+            {
+                outln!("Aborted; nothing was submitted");
+                return Ok(());
+            }
+
+            rpc.invoke(
+                &signer,
+                &scheduler.scheduler_id,
+                function,
+                vec![rpc::address_arg(&signer.address())?, id.into()],
+            )
+            .await?;
+            outln!("✅ Schedule {} {}", id, done);
+            let job = fetch_scheduler_job(&rpc, &scheduler.scheduler_id, id).await?;
+            output::set_result(&job)?;
+            print_scheduler_job(&job);
+            Ok(())
+        }
+        ScheduleCommands::Execute(args) => schedule_execute_command(args, config).await.map(|_| ()),
+        ScheduleCommands::Due {
+            scheduler,
+            employer,
+        } => {
+            let employers = if employer.is_empty() {
+                config.defaults.employers.clone()
+            } else {
+                employer
+            };
+            for employer in &employers {
+                validate_address(employer)?;
+            }
+            let now = chrono::Utc::now().timestamp().max(0) as u64;
+            let due = due_schedules(list_schedules(&scheduler, config).await?, &employers, now);
+            output::set_result(&due)?;
+            if due.is_empty() {
+                outln!("No due schedules");
+                return Ok(());
+            }
+            print_schedule_table(&due);
+            outln!("{} schedule(s) due", due.len());
+            Ok(())
+        }
+    }
+}
+
+async fn list_schedules(scheduler: &SchedulerTarget, config: &Config) -> Result<Vec<SchedulerJob>> {
+    if scheduler.concurrency == 0 {
+        return Err(anyhow::anyhow!("--concurrency must be greater than zero"));
+    }
+    let rpc = std::sync::Arc::new(SorobanRpcClient::from_config(config));
+    fetch_scheduler_jobs(&rpc, &scheduler.scheduler_id, scheduler.concurrency).await
+}
+
+/// Due schedules of `employers` (all employers when empty), in ID order.
+pub fn due_schedules(jobs: Vec<SchedulerJob>, employers: &[String], now: u64) -> Vec<SchedulerJob> {
+    jobs.into_iter()
+        .filter(|job| job.is_due(now))
+        .filter(|job| employers.is_empty() || employers.contains(&job.employer))
+        .collect()
+}
+
+pub async fn fetch_scheduler_job(
+    rpc: &SorobanRpcClient,
+    scheduler_id: &str,
+    id: u128,
+) -> Result<SchedulerJob> {
+    let value = rpc.view(scheduler_id, "get_job", vec![id.into()]).await?;
+    if value == ScVal::Void {
+        return Err(anyhow::anyhow!("Schedule {} not found", id));
+    }
+    SchedulerJob::from_scval(&value)
+        .ok_or_else(|| anyhow::anyhow!("Scheduler job {} has an unexpected shape", id))
+}
+
+/// What happened to one due schedule during `schedule execute`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleExecution {
+    pub before: SchedulerJob,
+    /// State read back after the run; `None` if it could not be read.
+    pub after: Option<SchedulerJob>,
+}
+
+impl ScheduleExecution {
+    pub fn outcome(&self) -> &'static str {
+        match &self.after {
+            None => "Unknown",
+            Some(after) if after.executions > self.before.executions => "Paid",
+            // Unfunded payments are handed to the retry contract and the
+            // schedule moves on to its next period
+            Some(after) if after.next_scheduled_time != self.before.next_scheduled_time => {
+                "Retry scheduled"
+            }
+            Some(_) => "Not executed",
+        }
+    }
+}
+
+/// Runs `schedule execute`.
+///
+/// `process_due_payments` always takes due schedules in ID order, so the
+/// default mode submits it with `max_jobs = 1` once per due schedule, asking
+/// before each; declining stops the run, since a later schedule cannot be
+/// executed ahead of an earlier one. `--all` submits batches of `--max-jobs`
+/// until a batch comes back short. Transactions are spaced by `--delay-ms`.
+pub async fn schedule_execute_command(
+    args: ScheduleExecuteArgs,
+    config: &Config,
+) -> Result<Vec<ScheduleExecution>> {
+    if args.max_jobs == 0 {
+        return Err(anyhow::anyhow!("--max-jobs must be greater than zero"));
+    }
+    let scheduler_id = &args.scheduler.scheduler_id;
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let due = due_schedules(list_schedules(&args.scheduler, config).await?, &[], now);
+    if due.is_empty() {
+        outln!("No due schedules");
+        return Ok(Vec::new());
+    }
+    print_schedule_table(&due);
+
+    let signer = get_signer(config).await?;
+    let rpc = SorobanRpcClient::from_config(config);
+    let delay = std::time::Duration::from_millis(args.delay_ms);
+    let mut transactions = Vec::new();
+
+    if args.all {
+        loop {
+            if !transactions.is_empty() {
+                tokio::time::sleep(delay).await;
+            }
+            let (processed, hash) =
+                process_due_payments(&rpc, &signer, scheduler_id, args.max_jobs).await?;
+            transactions.push(hash);
+            if processed < args.max_jobs
+                || transactions.len() as u64 * args.max_jobs as u64 >= due.len() as u64
+            {
+                break;
+            }
+        }
+    } else {
+        for job in &due {
+            let prompt = format!(
+                "Execute schedule {} ({} to {})?",
+                job.id,
+                format_amount(job.amount, TOKEN_DECIMALS),
+                job.recipient
+            );
+            if !confirm_action(&prompt, config.assume_yes || config.dry_run)? {
+                outln!("Stopped; the remaining schedules stay due.");
+                break;
+            }
+            if !transactions.is_empty() {
+                tokio::time::sleep(delay).await;
+            }
+            let (processed, hash) = process_due_payments(&rpc, &signer, scheduler_id, 1).await?;
+            transactions.push(hash);
+            if processed == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut executions = Vec::with_capacity(due.len());
+    for before in due {
+        let after = fetch_scheduler_job(&rpc, scheduler_id, before.id)
+            .await
+            .map_err(|e| warn!("Could not read schedule {} back: {}", before.id, e))
+            .ok();
+        executions.push(ScheduleExecution { before, after });
+    }
+
+    let rows: Vec<Vec<String>> = executions
+        .iter()
+        .map(|execution| {
+            vec![
+                execution.before.id.to_string(),
+                execution.before.recipient.clone(),
+                format_amount(execution.before.amount, TOKEN_DECIMALS),
+                execution.outcome().to_string(),
+                execution
+                    .after
+                    .as_ref()
+                    .map(|after| {
+                        format!(
+                            "{} ({})",
+                            format_timestamp(after.next_scheduled_time),
+                            after.status
+                        )
+                    })
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect();
+    outln!(
+        "{}",
+        format_table(
+            &["ID", "Recipient", "Amount", "Result", "Next execution"],
+            &rows
+        )
+    );
+    let summary: Vec<serde_json::Value> = executions
+        .iter()
+        .map(|execution| {
+            serde_json::json!({
+                "id": execution.before.id,
+                "outcome": execution.outcome(),
+                "next_scheduled_time": execution.after.as_ref().map(|after| after.next_scheduled_time),
+                "status": execution.after.as_ref().map(|after| after.status.clone()),
+            })
+        })
+        .collect();
+    output::set_result(&summary)?;
+    let paid = executions
+        .iter()
+        .filter(|execution| execution.outcome() == "Paid")
+        .count();
+    outln!(
+        "Paid {} of {} due schedule(s) in {} transaction(s)",
+        paid,
+        executions.len(),
+        transactions.len()
+    );
+    Ok(executions)
+}
+
+/// Submits `process_due_payments(max_jobs)` and returns how many schedules
+/// it evaluated, with the transaction hash.
+async fn process_due_payments(
+    rpc: &SorobanRpcClient,
+    signer: &TransactionSigner,
+    scheduler_id: &str,
+    max_jobs: u32,
+) -> Result<(u32, String)> {
+    let outcome = rpc
+        .invoke(
+            signer,
+            scheduler_id,
+            "process_due_payments",
+            vec![max_jobs.into()],
+        )
+        .await?;
+    let processed = outcome
+        .return_value
+        .and_then(|value| u32::try_from(value).ok())
+        .unwrap_or(max_jobs);
+    info!(
+        "process_due_payments evaluated {} schedule(s) in {}",
+        processed, outcome.hash
+    );
+    Ok((processed, outcome.hash))
+}
+
+fn print_schedule_table(jobs: &[SchedulerJob]) {
+    if jobs.is_empty() {
+        outln!("No schedules found");
+        return;
+    }
+    let rows: Vec<Vec<String>> = jobs
+        .iter()
+        .map(|job| {
+            vec![
+                job.id.to_string(),
+                job.employer.clone(),
+                job.recipient.clone(),
+                format_amount(job.amount, TOKEN_DECIMALS),
+                schedule_interval(job),
+                format_timestamp(job.next_scheduled_time),
+                job.status.clone(),
+            ]
+        })
+        .collect();
+    outln!(
+        "{}",
+        format_table(
+            &[
+                "ID",
+                "Employer",
+                "Recipient",
+                "Amount",
+                "Interval",
+                "Next execution",
+                "Status"
+            ],
+            &rows
+        )
+    );
+}
+
+fn schedule_interval(job: &SchedulerJob) -> String {
+    if job.interval_seconds == 0 {
+        "one-time".to_string()
+    } else {
+        seconds_to_frequency(job.interval_seconds)
+    }
+}
+
+pub fn print_scheduler_job(job: &SchedulerJob) {
+    outln!("Schedule {}:", job.id);
+    outln!("  Status: {}", job.status);
+    outln!("  Employer: {}", job.employer);
+    outln!("  Recipient: {}", job.recipient);
+    outln!("  Token: {}", job.token);
+    outln!("  Amount: {}", format_amount(job.amount, TOKEN_DECIMALS));
+    outln!("  Interval: {}", schedule_interval(job));
+    outln!(
+        "  Next execution: {}",
+        format_timestamp(job.next_scheduled_time)
+    );
+    match job.max_executions {
+        Some(max) => outln!("  Executions: {} of {}", job.executions, max),
+        None => outln!("  Executions: {}", job.executions),
+    }
+    if job.retry_count > 0 {
+        outln!("  Failed attempts: {}", job.retry_count);
+    }
+}
+
+/// Payouts one schedule makes within the `upcoming` window.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct UpcomingPayout {
+    pub schedule_id: u128,
+    pub recipient: String,
+    pub token: String,
+    pub amount: i128,
+    pub next_scheduled_time: u64,
+    /// Executions falling in the window, including one that is already due.
+    pub payouts: u32,
+    pub total: i128,
+}
+
+/// Funds needed in one token against the employer's balance.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct UpcomingTokenTotal {
+    pub token: String,
+    pub decimals: u32,
+    pub required: i128,
+    pub balance: i128,
+    pub shortfall: i128,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct UpcomingReport {
+    pub employer: String,
+    pub window_end: u64,
+    pub payouts: Vec<UpcomingPayout>,
+    pub totals: Vec<UpcomingTokenTotal>,
+}
+
+/// Active schedules of `employer` with executions at or before `window_end`,
+/// counting every execution of a schedule whose interval repeats inside the
+/// window, up to its remaining `max_executions`. An overdue schedule pays
+/// once when next processed and then every interval from `now`, as
+/// `process_due_payments` does not catch up missed periods.
+pub fn upcoming_payouts(
+    jobs: &[SchedulerJob],
+    employer: &str,
+    now: u64,
+    window_end: u64,
+) -> Vec<UpcomingPayout> {
+    jobs.iter()
+        .filter(|job| job.status == "Active" && job.employer == employer)
+        .filter(|job| job.next_scheduled_time <= window_end)
+        .filter_map(|job| {
+            let span = window_end.saturating_sub(job.next_scheduled_time.max(now));
+            let mut payouts = match span.checked_div(job.interval_seconds) {
+                Some(repeats) => u32::try_from(repeats + 1).unwrap_or(u32::MAX),
+                None => 1,
+            };
+            if let Some(max) = job.max_executions {
+                payouts = payouts.min(max.saturating_sub(job.executions));
+            }
+            (payouts > 0).then(|| UpcomingPayout {
+                schedule_id: job.id,
+                recipient: job.recipient.clone(),
+                token: job.token.clone(),
+                amount: job.amount,
+                next_scheduled_time: job.next_scheduled_time,
+                payouts,
+                total: job.amount.saturating_mul(i128::from(payouts)),
+            })
+        })
+        .collect()
+}
+
+/// Runs `upcoming`: lists the employer's payouts due within `--within`, the
+/// amount required per token and any shortfall against the employer's token
+/// balance, which funds the schedules through `fund_job`.
+pub async fn upcoming_command(args: UpcomingArgs, config: &Config) -> Result<()> {
+    validate_address(&args.employer)?;
+    let within = parse_duration(&args.within)
+        .map_err(|e| anyhow::anyhow!("Invalid --within '{}': {}", args.within, e))?;
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let report = upcoming_report(&args, config, now, now.saturating_add(within)).await?;
+    output::set_result(&report)?;
+    print_upcoming_report(&report, &args.within);
+
+    if args.notify {
+        let url = config
+            .notifications
+            .webhook_url
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("--notify needs notifications.webhook_url"))?;
+        post_upcoming_summary(url, &report, &args.within).await?;
+        outln!("Summary posted to the notification webhook");
+    }
+    Ok(())
+}
+
+pub async fn upcoming_report(
+    args: &UpcomingArgs,
+    config: &Config,
+    now: u64,
+    window_end: u64,
+) -> Result<UpcomingReport> {
+    let jobs = list_schedules(&args.scheduler, config).await?;
+    let payouts = upcoming_payouts(&jobs, &args.employer, now, window_end);
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let decimals = fetch_token_decimals(&rpc, payouts.iter().map(|p| p.token.as_str())).await;
+    let mut totals: Vec<UpcomingTokenTotal> = Vec::new();
+    for payout in &payouts {
+        match totals.iter_mut().find(|total| total.token == payout.token) {
+            Some(total) => total.required = total.required.saturating_add(payout.total),
+            None => totals.push(UpcomingTokenTotal {
+                token: payout.token.clone(),
+                decimals: decimals
+                    .get(&payout.token)
+                    .copied()
+                    .unwrap_or(TOKEN_DECIMALS),
+                required: payout.total,
+                balance: 0,
+                shortfall: 0,
+            }),
+        }
+    }
+    for total in &mut totals {
+        total.balance = TokenClient::new(&rpc, &total.token)
+            .balance(&args.employer)
+            .await?;
+        total.shortfall = (total.required - total.balance).max(0);
+    }
+
+    Ok(UpcomingReport {
+        employer: args.employer.clone(),
+        window_end,
+        payouts,
+        totals,
+    })
+}
+
+fn print_upcoming_report(report: &UpcomingReport, within: &str) {
+    if report.payouts.is_empty() {
+        outln!(
+            "No payouts due for {} in the next {}",
+            report.employer,
+            within
+        );
+        return;
+    }
+    let decimals = |token: &str| {
+        report
+            .totals
+            .iter()
+            .find(|total| total.token == token)
+            .map_or(TOKEN_DECIMALS, |total| total.decimals)
+    };
+    let rows: Vec<Vec<String>> = report
+        .payouts
+        .iter()
+        .map(|payout| {
+            vec![
+                payout.recipient.clone(),
+                payout.schedule_id.to_string(),
+                format_timestamp(payout.next_scheduled_time),
+                payout.payouts.to_string(),
+                payout.token.clone(),
+                format_amount(payout.total, decimals(&payout.token)),
+            ]
+        })
+        .collect();
+    outln!(
+        "{}",
+        format_table(
+            &[
+                "Employee",
+                "Schedule",
+                "Next payout",
+                "Payouts",
+                "Token",
+                "Total"
+            ],
+            &rows
+        )
+    );
+    let rows: Vec<Vec<String>> = report
+        .totals
+        .iter()
+        .map(|total| {
+            vec![
+                total.token.clone(),
+                format_amount(total.required, total.decimals),
+                format_amount(total.balance, total.decimals),
+                format_amount(total.shortfall, total.decimals),
+            ]
+        })
+        .collect();
+    outln!(
+        "{}",
+        format_table(&["Token", "Required", "Balance", "Shortfall"], &rows)
+    );
+    let short = report
+        .totals
+        .iter()
+        .filter(|total| total.shortfall > 0)
+        .count();
+    if short > 0 {
+        outln!(
+            "⚠️  Balance falls short in {} token(s) before {}",
+            short,
+            format_timestamp(report.window_end)
+        );
+    }
+}
+
+/// One-line-per-token summary posted by `upcoming --notify`.
+pub fn upcoming_summary(report: &UpcomingReport, within: &str) -> String {
+    let employees = report
+        .payouts
+        .iter()
+        .map(|payout| payout.recipient.as_str())
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+    let mut summary = format!(
+        "Upcoming payouts for {} in the next {}: {} payout(s) to {} employee(s)",
+        report.employer,
+        within,
+        report.payouts.iter().map(|p| p.payouts).sum::<u32>(),
+        employees
+    );
+    for total in &report.totals {
+        summary.push_str(&format!(
+            "\n{}: {} required, balance {}",
+            total.token,
+            format_amount(total.required, total.decimals),
+            format_amount(total.balance, total.decimals)
+        ));
+        if total.shortfall > 0 {
+            summary.push_str(&format!(
+                ", short by {}",
+                format_amount(total.shortfall, total.decimals)
+            ));
+        }
+    }
+    summary
+}
+
+/// Posts `{"text": ..., "report": ...}`; Slack incoming webhooks show the
+/// text, other receivers can read the report.
+async fn post_upcoming_summary(url: &str, report: &UpcomingReport, within: &str) -> Result<()> {
+    reqwest::Client::new()
+        .post(url)
+        .json(&serde_json::json!({
+            "text": upcoming_summary(report, within),
+            "report": report,
+        }))
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// One projected payout from the payroll contract's `get_payout_calendar`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct CalendarPayout {
+    pub agreement_id: u128,
+    pub employee: String,
+    pub token: String,
+    pub period: u32,
+    /// When the period ends and its pay becomes claimable.
+    pub due_at: u64,
+    /// Earliest time the payout can run under business hours and blackouts.
+    pub payable_at: u64,
+    pub amount: i128,
+}
+
+impl CalendarPayout {
+    /// Decodes a `PayoutCalendarEntry`.
+    pub fn from_scval(value: &ScVal) -> Option<Self> {
+        let field = |name| rpc::struct_field(value, name);
+        Some(CalendarPayout {
+            agreement_id: u128::try_from(field("agreement_id")?.clone()).ok()?,
+            employee: rpc::scval_to_address(field("employee")?)?,
+            token: rpc::scval_to_address(field("token")?)?,
+            period: u32::try_from(field("period")?.clone()).ok()?,
+            due_at: u64::try_from(field("due_at")?.clone()).ok()?,
+            payable_at: u64::try_from(field("payable_at")?.clone()).ok()?,
+            amount: i128::try_from(field("amount")?.clone()).ok()?,
+        })
+    }
+}
+
+/// Runs `calendar`: lists the employer's projected payouts for the range and
+/// optionally writes them to an iCalendar file.
+pub async fn calendar_command(args: CalendarArgs, config: &Config) -> Result<()> {
+    validate_address(&args.employer)?;
+    let contract_id = resolve_contract_id(args.contract_id.clone(), config)?;
+    let from = parse_report_date(&args.from)?;
+    let until = parse_range_end(&args.to)?;
+    if until <= from {
+        return Err(anyhow::anyhow!("--to must not be before --from"));
+    }
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let payouts = fetch_payout_calendar(&rpc, &contract_id, &args.employer, from, until).await?;
+    output::set_result(&payouts)?;
+    print_payout_calendar(&args.employer, &payouts);
+
+    if let Some(path) = &args.ical {
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        std::fs::write(path, payout_calendar_ics(&args.employer, &payouts, now))?;
+        outln!("  wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// Reads the payouts projected for periods ending in `[from, until)`. The
+/// contract rejects ranges with more than 100 payouts.
+pub async fn fetch_payout_calendar(
+    rpc: &SorobanRpcClient,
+    contract_id: &str,
+    employer: &str,
+    from: u64,
+    until: u64,
+) -> Result<Vec<CalendarPayout>> {
+    let value = rpc
+        .view(
+            contract_id,
+            "get_payout_calendar",
+            vec![rpc::address_arg(employer)?, from.into(), until.into()],
+        )
+        .await?;
+    let ScVal::Vec(Some(entries)) = value else {
+        return Err(anyhow::anyhow!("Payout calendar has an unexpected shape"));
+    };
+    entries
+        .iter()
+        .map(|entry| {
+            CalendarPayout::from_scval(entry)
+                .ok_or_else(|| anyhow::anyhow!("Payout calendar has an unexpected shape"))
+        })
+        .collect()
+}
+
+fn print_payout_calendar(employer: &str, payouts: &[CalendarPayout]) {
+    if payouts.is_empty() {
+        outln!("No payouts projected for {} in the range", employer);
+        return;
+    }
+    let rows: Vec<Vec<String>> = payouts
+        .iter()
+        .map(|payout| {
+            vec![
+                format_timestamp(payout.payable_at),
+                format_timestamp(payout.due_at),
+                payout.employee.clone(),
+                payout.agreement_id.to_string(),
+                payout.period.to_string(),
+                payout.token.clone(),
+                format_amount(payout.amount, TOKEN_DECIMALS),
+            ]
+        })
+        .collect();
+    outln!(
+        "{}",
+        format_table(
+            &[
+                "Payable",
+                "Due",
+                "Employee",
+                "Agreement",
+                "Period",
+                "Token",
+                "Amount"
+            ],
+            &rows
+        )
+    );
+}
+
+/// Renders payouts as an RFC 5545 iCalendar with one event per payout at its
+/// payable time. `generated_at` stamps every event.
+pub fn payout_calendar_ics(
+    employer: &str,
+    payouts: &[CalendarPayout],
+    generated_at: u64,
+) -> String {
+    let time = |timestamp: u64| {
+        chrono::DateTime::from_timestamp(timestamp as i64, 0)
+            .map(|d| d.format("%Y%m%dT%H%M%SZ").to_string())
+            .unwrap_or_default()
+    };
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//StellopayCore//stellopay-cli//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:Payroll payouts of {}", employer),
+    ];
+    for payout in payouts {
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!(
+                "UID:{}-{}-{}@stellopay",
+                payout.agreement_id, payout.employee, payout.period
+            ),
+            format!("DTSTAMP:{}", time(generated_at)),
+            format!("DTSTART:{}", time(payout.payable_at)),
+            format!(
+                "SUMMARY:Payout of {} to {}",
+                format_amount(payout.amount, TOKEN_DECIMALS),
+                truncate_address(&payout.employee, 6)
+            ),
+            format!(
+                "DESCRIPTION:Agreement {} period {}\\nEmployee {}\\nToken {}\\nDue {}",
+                payout.agreement_id,
+                payout.period,
+                payout.employee,
+                payout.token,
+                format_timestamp(payout.due_at)
+            ),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold_ics_line(line)).collect()
+}
+
+/// Folds a content line to 75 octets per physical line and terminates it
+/// with CRLF, as RFC 5545 requires. Lines here are ASCII.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / 74 * 3 + 2);
+    let (first, mut rest) = line.split_at(line.len().min(75));
+    folded.push_str(first);
+    while !rest.is_empty() {
+        let (chunk, tail) = rest.split_at(rest.len().min(74));
+        folded.push_str("\r\n ");
+        folded.push_str(chunk);
+        rest = tail;
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// Page size used when walking paginated contract queries (`MAX_PAGE_SIZE`).
+const REPORT_PAGE_SIZE: u32 = 100;
+
+/// One `PaymentHistory` record.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ReportPayment {
+    pub id: u128,
+    pub agreement_id: u128,
+    pub payment_hash: String,
+    pub token: String,
+    pub amount: i128,
+    pub employer: String,
+    pub employee: String,
+    pub timestamp: u64,
+}
+
+impl ReportPayment {
+    /// Decodes a `PaymentRecord` as returned by the history contract.
+    pub fn from_scval(value: &ScVal) -> Option<Self> {
+        let field = |name| rpc::struct_field(value, name);
+        let ScVal::Bytes(hash) = field("payment_hash")? else {
+            return None;
+        };
+        Some(ReportPayment {
+            id: u128::try_from(field("id")?.clone()).ok()?,
+            agreement_id: u128::try_from(field("agreement_id")?.clone()).ok()?,
+            payment_hash: hash.iter().map(|b| format!("{:02x}", b)).collect(),
+            token: rpc::scval_to_address(field("token")?)?,
+            amount: i128::try_from(field("amount")?.clone()).ok()?,
+            employer: rpc::scval_to_address(field("from")?)?,
+            employee: rpc::scval_to_address(field("to")?)?,
+            timestamp: u64::try_from(field("timestamp")?.clone()).ok()?,
+        })
+    }
+}
+
+/// One lifecycle audit entry from the payroll contract.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ReportAuditEntry {
+    pub id: u64,
+    pub timestamp: u64,
+    pub actor: String,
+    pub event: String,
+    pub agreement_id: u128,
+    pub subject: Option<String>,
+    pub amount: Option<i128>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct EmployeeTotal {
+    pub employee: String,
+    pub token: String,
+    pub payments: u32,
+    pub total: i128,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct TokenTotal {
+    pub token: String,
+    pub payments: u32,
+    pub employees: u32,
+    pub total: i128,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ReportMetrics {
+    pub payment_count: u32,
+    pub employee_count: u32,
+    pub token_count: u32,
+    pub audit_event_count: u32,
+    pub first_payment_at: Option<u64>,
+    pub last_payment_at: Option<u64>,
+}
+
+/// Everything `report` exports for one employer and date range.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PayrollReport {
+    pub employer: String,
+    /// Inclusive start of the range (Unix seconds).
+    pub from: u64,
+    /// Exclusive end of the range (Unix seconds).
+    pub until: u64,
+    pub metrics: ReportMetrics,
+    pub employee_totals: Vec<EmployeeTotal>,
+    pub token_totals: Vec<TokenTotal>,
+    pub payments: Vec<ReportPayment>,
+    pub audit_trail: Vec<ReportAuditEntry>,
+}
+
+pub async fn report_command(args: ReportArgs, config: &Config) -> Result<()> {
+    let report = build_report(&args, config).await?;
+    let files = write_report(&report, args.format, &args.output)?;
+
+    outln!(
+        "Report for {}: {} payments to {} employees, {} audit events",
+        report.employer,
+        report.metrics.payment_count,
+        report.metrics.employee_count,
+        report.metrics.audit_event_count
+    );
+    for file in files {
+        outln!("  wrote {}", file.display());
+    }
+    Ok(())
+}
+
+/// Pulls payment history and the audit trail for `args.employer` and
+/// aggregates them over the requested range.
+pub async fn build_report(args: &ReportArgs, config: &Config) -> Result<PayrollReport> {
+    validate_address(&args.employer)?;
+    let payroll_id = resolve_contract_id(args.contract_id.clone(), config)?;
+    let from = parse_report_date(&args.from)?;
+    let until = parse_range_end(&args.to)?;
+    if until <= from {
+        return Err(anyhow::anyhow!("--to must not be before --from"));
+    }
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let in_range = |timestamp: u64| timestamp >= from && timestamp < until;
+
+    let payments: Vec<ReportPayment> =
+        fetch_employer_payments(&rpc, &args.history_id, &args.employer)
+            .await?
+            .into_iter()
+            .filter(|p| in_range(p.timestamp))
+            .collect();
+    let audit_trail: Vec<ReportAuditEntry> =
+        fetch_employer_audit_trail(&rpc, &payroll_id, &args.employer)
+            .await?
+            .into_iter()
+            .filter(|e| in_range(e.timestamp))
+            .collect();
+
+    let (employee_totals, token_totals) = aggregate_payments(&payments);
+    let metrics = ReportMetrics {
+        payment_count: payments.len() as u32,
+        employee_count: employee_totals
+            .iter()
+            .map(|t| &t.employee)
+            .collect::<std::collections::BTreeSet<_>>()
+            .len() as u32,
+        token_count: token_totals.len() as u32,
+        audit_event_count: audit_trail.len() as u32,
+        first_payment_at: payments.iter().map(|p| p.timestamp).min(),
+        last_payment_at: payments.iter().map(|p| p.timestamp).max(),
+    };
+
+    Ok(PayrollReport {
+        employer: args.employer.clone(),
+        from,
+        until,
+        metrics,
+        employee_totals,
+        token_totals,
+        payments,
+        audit_trail,
+    })
+}
+
+/// Totals payments per `(employee, token)` and per token, sorted by key.
+pub fn aggregate_payments(payments: &[ReportPayment]) -> (Vec<EmployeeTotal>, Vec<TokenTotal>) {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    let mut per_employee: BTreeMap<(String, String), (u32, i128)> = BTreeMap::new();
+    let mut per_token: BTreeMap<String, (u32, i128, BTreeSet<String>)> = BTreeMap::new();
+    for payment in payments {
+        let employee = per_employee
+            .entry((payment.employee.clone(), payment.token.clone()))
+            .or_default();
+        employee.0 += 1;
+        employee.1 = employee.1.saturating_add(payment.amount);
+
+        let token = per_token.entry(payment.token.clone()).or_default();
+        token.0 += 1;
+        token.1 = token.1.saturating_add(payment.amount);
+        token.2.insert(payment.employee.clone());
+    }
+
+    let employee_totals = per_employee
+        .into_iter()
+        .map(|((employee, token), (payments, total))| EmployeeTotal {
+            employee,
+            token,
+            payments,
+            total,
+        })
+        .collect();
+    let token_totals = per_token
+        .into_iter()
+        .map(|(token, (payments, total, employees))| TokenTotal {
+            token,
+            payments,
+            employees: employees.len() as u32,
+            total,
+        })
+        .collect();
+    (employee_totals, token_totals)
+}
+
+/// Writes the report into `dir`: one CSV per table, or a single JSON file.
+pub fn write_report(
+    report: &PayrollReport,
+    format: ReportFormat,
+    dir: &std::path::Path,
+) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+    let amount = |value: i128| format_amount(value, TOKEN_DECIMALS);
+
+    let files: Vec<(&str, String)> = match format {
+        ReportFormat::Json => vec![("report.json", serde_json::to_string_pretty(report)?)],
+        ReportFormat::Csv => vec![
+            (
+                "payments.csv",
+                to_csv(
+                    &[
+                        "payment_id",
+                        "date",
+                        "agreement_id",
+                        "employee",
+                        "token",
+                        "amount",
+                        "payment_hash",
+                    ],
+                    &report
+                        .payments
+                        .iter()
+                        .map(|p| {
+                            vec![
+                                p.id.to_string(),
+                                format_timestamp(p.timestamp),
+                                p.agreement_id.to_string(),
+                                p.employee.clone(),
+                                p.token.clone(),
+                                amount(p.amount),
+                                p.payment_hash.clone(),
+                            ]
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+            (
+                "employee_totals.csv",
+                to_csv(
+                    &["employee", "token", "payments", "total"],
+                    &report
+                        .employee_totals
+                        .iter()
+                        .map(|t| {
+                            vec![
+                                t.employee.clone(),
+                                t.token.clone(),
+                                t.payments.to_string(),
+                                amount(t.total),
+                            ]
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+            (
+                "token_totals.csv",
+                to_csv(
+                    &["token", "payments", "employees", "total"],
+                    &report
+                        .token_totals
+                        .iter()
+                        .map(|t| {
+                            vec![
+                                t.token.clone(),
+                                t.payments.to_string(),
+                                t.employees.to_string(),
+                                amount(t.total),
+                            ]
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+            (
+                "audit_trail.csv",
+                to_csv(
+                    &[
+                        "audit_id",
+                        "date",
+                        "event",
+                        "agreement_id",
+                        "actor",
+                        "subject",
+                        "amount",
+                    ],
+                    &report
+                        .audit_trail
+                        .iter()
+                        .map(|e| {
+                            vec![
+                                e.id.to_string(),
+                                format_timestamp(e.timestamp),
+                                e.event.clone(),
+                                e.agreement_id.to_string(),
+                                e.actor.clone(),
+                                e.subject.clone().unwrap_or_default(),
+                                e.amount.map(amount).unwrap_or_default(),
+                            ]
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+            ),
+        ],
+    };
+
+    let mut written = Vec::with_capacity(files.len());
+    for (name, content) in files {
+        let path = dir.join(name);
+        std::fs::write(&path, content)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Parses the last day of a date range into an exclusive bound: a calendar
+/// date includes the whole day, a raw timestamp includes that second.
+fn parse_range_end(date_str: &str) -> Result<u64> {
+    let end = parse_report_date(date_str)?;
+    Ok(end
+        + if date_str.parse::<u64>().is_ok() {
+            1
+        } else {
+            86_400
+        })
+}
+
+/// Runs `history`: lists one page of matching payments, or exports them all.
+/// Pages are cut client-side after the date filter, so `--page` and
+/// `--limit` count matching payments only.
+pub async fn history_command(args: HistoryArgs, config: &Config) -> Result<()> {
+    let selector = HistorySelector::from_args(&args)?;
+    if args.limit == 0 || args.page == 0 {
+        return Err(anyhow::anyhow!(
+            "--limit and --page must be greater than zero"
+        ));
+    }
+    let from = args.from.as_deref().map(parse_report_date).transpose()?;
+    let until = args.to.as_deref().map(parse_range_end).transpose()?;
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let payments: Vec<ReportPayment> = fetch_payment_history(&rpc, &args.history_id, &selector)
+        .await?
+        .into_iter()
+        .filter(|p| from.map_or(true, |from| p.timestamp >= from))
+        .filter(|p| until.map_or(true, |until| p.timestamp < until))
+        .collect();
+    let decimals = fetch_token_decimals(&rpc, payments.iter().map(|p| p.token.as_str())).await;
+
+    if let Some(format) = args.export {
+        let content = export_payment_history(&payments, format, &decimals)?;
+        match &args.output {
+            Some(path) => {
+                std::fs::write(path, content)?;
+                outln!("Exported {} payments to {}", payments.len(), path.display());
+            }
+            None => output::emit_text(&content)?,
+        }
+        return Ok(());
+    }
+
+    let pages = payments.len().div_ceil(args.limit).max(1);
+    let page: Vec<&ReportPayment> = payments
+        .iter()
+        .skip((args.page - 1).saturating_mul(args.limit))
+        .take(args.limit)
+        .collect();
+    let counterparty = match selector {
+        HistorySelector::Employee(_) => "Employer",
+        _ => "Employee",
+    };
+    let rows: Vec<Vec<String>> = page
+        .iter()
+        .map(|p| {
+            vec![
+                p.id.to_string(),
+                format_timestamp(p.timestamp),
+                p.agreement_id.to_string(),
+                match selector {
+                    HistorySelector::Employee(_) => p.employer.clone(),
+                    _ => p.employee.clone(),
+                },
+                p.token.clone(),
+                format_token_amount(p, &decimals),
+            ]
+        })
+        .collect();
+    output::set_result(&page)?;
+    outln!(
+        "{}",
+        format_table(
+            &["ID", "Date", "Agreement", counterparty, "Token", "Amount"],
+            &rows
+        )
+    );
+    outln!(
+        "Page {} of {} ({} payments)",
+        args.page,
+        pages,
+        payments.len()
+    );
+    Ok(())
+}
+
+/// Renders payments as CSV, or as JSON with raw integer amounts.
+pub fn export_payment_history(
+    payments: &[ReportPayment],
+    format: ReportFormat,
+    decimals: &std::collections::HashMap<String, u32>,
+) -> Result<String> {
+    Ok(match format {
+        ReportFormat::Json => serde_json::to_string_pretty(payments)? + "\n",
+        ReportFormat::Csv => to_csv(
+            &[
+                "payment_id",
+                "date",
+                "agreement_id",
+                "employer",
+                "employee",
+                "token",
+                "amount",
+                "payment_hash",
+            ],
+            &payments
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.id.to_string(),
+                        format_timestamp(p.timestamp),
+                        p.agreement_id.to_string(),
+                        p.employer.clone(),
+                        p.employee.clone(),
+                        p.token.clone(),
+                        format_token_amount(p, decimals),
+                        p.payment_hash.clone(),
+                    ]
+                })
+                .collect::<Vec<_>>(),
+        ),
+    })
+}
+
+fn format_token_amount(
+    payment: &ReportPayment,
+    decimals: &std::collections::HashMap<String, u32>,
+) -> String {
+    format_amount(
+        payment.amount,
+        decimals
+            .get(&payment.token)
+            .copied()
+            .unwrap_or(TOKEN_DECIMALS),
+    )
+}
+
+/// Looks up `decimals()` for each distinct token. Tokens that do not answer
+/// are left out, and their amounts fall back to `TOKEN_DECIMALS`.
+async fn fetch_token_decimals<'a>(
+    rpc: &SorobanRpcClient,
+    tokens: impl Iterator<Item = &'a str>,
+) -> std::collections::HashMap<String, u32> {
+    let mut decimals = std::collections::HashMap::new();
+    for token in tokens {
+        if decimals.contains_key(token) {
+            continue;
+        }
+        match TokenClient::new(rpc, token).decimals().await {
+            Ok(value) => {
+                decimals.insert(token.to_string(), value);
+            }
+            Err(e) => warn!(
+                "Could not read decimals of token {}, assuming {}: {}",
+                token, TOKEN_DECIMALS, e
+            ),
+        }
+    }
+    decimals
+}
+
+/// Reads every `PaymentHistory` record where `employer` is the payer.
+pub async fn fetch_employer_payments(
+    rpc: &SorobanRpcClient,
+    history_id: &str,
+    employer: &str,
+) -> Result<Vec<ReportPayment>> {
+    fetch_payment_history(
+        rpc,
+        history_id,
+        &HistorySelector::Employer(employer.to_string()),
+    )
+    .await
+}
+
+/// Which payments a PaymentHistory query returns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistorySelector {
+    Employee(String),
+    Employer(String),
+    Agreement(u128),
+}
+
+impl HistorySelector {
+    pub fn from_args(args: &HistoryArgs) -> Result<Self> {
+        let selector = match (&args.employee, &args.employer, args.agreement) {
+            (Some(employee), None, None) => HistorySelector::Employee(employee.clone()),
+            (None, Some(employer), None) => HistorySelector::Employer(employer.clone()),
+            (None, None, Some(agreement)) => HistorySelector::Agreement(agreement),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Pass exactly one of --employee, --employer and --agreement"
+                ))
+            }
+        };
+        if let HistorySelector::Employee(address) | HistorySelector::Employer(address) = &selector {
+            validate_address(address)?;
+        }
+        Ok(selector)
+    }
+
+    /// Count and page functions for the selector, and their leading argument.
+    fn queries(&self) -> Result<(&'static str, &'static str, ScVal)> {
+        Ok(match self {
+            HistorySelector::Employee(employee) => (
+                "get_employee_payment_count",
+                "get_payments_by_employee",
+                rpc::address_arg(employee)?,
+            ),
+            HistorySelector::Employer(employer) => (
+                "get_employer_payment_count",
+                "get_payments_by_employer",
+                rpc::address_arg(employer)?,
+            ),
+            HistorySelector::Agreement(agreement_id) => (
+                "get_agreement_payment_count",
+                "get_payments_by_agreement",
+                (*agreement_id).into(),
+            ),
+        })
+    }
+}
+
+/// Reads every `PaymentHistory` record matching `selector`, oldest first,
+/// walking the contract's 1-based pages of `REPORT_PAGE_SIZE` records.
+pub async fn fetch_payment_history(
+    rpc: &SorobanRpcClient,
+    history_id: &str,
+    selector: &HistorySelector,
+) -> Result<Vec<ReportPayment>> {
+    let (count_function, page_function, key) = selector.queries()?;
+    let count = u32::try_from(
+        rpc.view(history_id, count_function, vec![key.clone()])
+            .await?,
+    )
+    .map_err(|_| anyhow::anyhow!("{} returned a non-u32 value", count_function))?;
+
+    let mut payments = Vec::with_capacity(count as usize);
+    let mut start = 1u32;
+    while start <= count {
+        let page = rpc
+            .view(
+                history_id,
+                page_function,
+                vec![key.clone(), start.into(), REPORT_PAGE_SIZE.into()],
+            )
+            .await?;
+        let ScVal::Vec(Some(records)) = page else {
+            return Err(anyhow::anyhow!(
+                "{} returned a non-vector value",
+                page_function
+            ));
+        };
+        if records.is_empty() {
+            break;
+        }
+        for record in records.iter() {
+            payments.push(
+                ReportPayment::from_scval(record)
+                    .ok_or_else(|| anyhow::anyhow!("Payment record has an unexpected shape"))?,
+            );
+        }
+        start += records.len() as u32;
+    }
+    Ok(payments)
+}
+
+/// Reads the payroll contract's lifecycle audit entries for `employer`.
+pub async fn fetch_employer_audit_trail(
+    rpc: &SorobanRpcClient,
+    payroll_id: &str,
+    employer: &str,
+) -> Result<Vec<ReportAuditEntry>> {
+    let mut entries = Vec::new();
+    let mut start_id = 1u64;
+    loop {
+        let page = rpc
+            .view(
+                payroll_id,
+                "get_audit_entries_by_employer",
+                vec![
+                    rpc::address_arg(employer)?,
+                    start_id.into(),
+                    REPORT_PAGE_SIZE.into(),
+                ],
+            )
+            .await?;
+        let Some(ScVal::Vec(Some(page_entries))) = rpc::struct_field(&page, "entries") else {
+            return Err(anyhow::anyhow!("Audit page has an unexpected shape"));
+        };
+        for entry in page_entries.iter() {
+            entries.push(
+                decode_audit_entry(entry)
+                    .ok_or_else(|| anyhow::anyhow!("Audit entry has an unexpected shape"))?,
+            );
+        }
+        match rpc::struct_field(&page, "next_start_id") {
+            Some(ScVal::U64(next)) if *next > start_id => start_id = *next,
+            _ => return Ok(entries),
+        }
+    }
+}
+
+fn decode_audit_entry(value: &ScVal) -> Option<ReportAuditEntry> {
+    let field = |name| rpc::struct_field(value, name);
+    Some(ReportAuditEntry {
+        id: u64::try_from(field("id")?.clone()).ok()?,
+        timestamp: u64::try_from(field("timestamp")?.clone()).ok()?,
+        actor: rpc::scval_to_address(field("actor")?)?,
+        event: rpc::enum_variant(field("event")?)?,
+        agreement_id: u128::try_from(field("agreement_id")?.clone()).ok()?,
+        subject: field("subject").and_then(rpc::scval_to_address),
+        amount: field("amount").and_then(|v| i128::try_from(v.clone()).ok()),
+    })
+}
+
+pub async fn keys_command(
+    command: KeysCommands,
+    config: &Config,
+    config_path: &std::path::Path,
+) -> Result<()> {
+    let keystore = Keystore::open_default()?;
+    match command {
+        KeysCommands::Import {
+            name,
+            from_config,
+            set_default,
+        } => {
+            let secret_key = if from_config {
+                config
+                    .auth
+                    .secret_key
+                    .clone()
+                    .map(zeroize::Zeroizing::new)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Config file has no auth.secret_key to import")
+                    })?
+            } else {
+                read_secret_key()?
+            };
+            let passphrase = read_passphrase(&format!("New passphrase for key '{}'", name), true)?;
+            let key = keystore.import(&name, &secret_key, &passphrase)?;
+            outln!("✅ Imported key '{}' ({})", key.name, key.public_key);
+
+            if from_config || set_default {
+                use_key(config_path, &key.name, from_config).await?;
+            }
+            Ok(())
+        }
+        KeysCommands::Generate { name, set_default } => {
+            let passphrase = read_passphrase(&format!("New passphrase for key '{}'", name), true)?;
+            let key = keystore.generate(&name, &passphrase)?;
+            outln!("✅ Generated key '{}' ({})", key.name, key.public_key);
+
+            if set_default {
+                use_key(config_path, &key.name, false).await?;
+            }
+            Ok(())
+        }
+        KeysCommands::List => {
+            let keys = keystore.list()?;
+            if keys.is_empty() {
+                outln!("No keys in {}", keystore.dir().display());
+                return Ok(());
+            }
+            let rows: Vec<Vec<String>> = keys
+                .into_iter()
+                .map(|key| {
+                    let default = if config.auth.key_name.as_deref() == Some(key.name.as_str()) {
+                        "*"
+                    } else {
+                        ""
+                    };
+                    vec![key.name, key.public_key, default.to_string()]
+                })
+                .collect();
+            outln!(
+                "{}",
+                format_table(&["Name", "Public Key", "Default"], &rows)
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Reads a secret key to import from `STELLOPAY_SECRET_KEY` or a hidden prompt.
+fn read_secret_key() -> Result<zeroize::Zeroizing<String>> {
+    if let Ok(secret_key) = std::env::var("STELLOPAY_SECRET_KEY") {
+        return Ok(zeroize::Zeroizing::new(secret_key));
+    }
+    let secret_key = dialoguer::Password::new()
+        .with_prompt("Secret key (S...)")
+        .interact()
+        .map_err(|e| anyhow::anyhow!("Failed to read secret key: {}", e))?;
+    Ok(zeroize::Zeroizing::new(secret_key))
+}
+
+/// Points `auth.key_name` at `name` in the config file, optionally dropping
+/// the plaintext `auth.secret_key` it replaces. The file is re-read so that
+/// command-line overrides such as `--network` are not persisted.
+async fn use_key(config_path: &std::path::Path, name: &str, clear_secret: bool) -> Result<()> {
+    let mut config = load_config(config_path).await?;
+    config.auth.key_name = Some(name.to_string());
+    if clear_secret {
+        config.auth.secret_key = None;
+    }
+    save_config(config_path, &config).await?;
+
+    outln!("Config now signs with key '{}'", name);
+    if clear_secret {
+        outln!("Removed plaintext auth.secret_key from the config file");
+    }
+    Ok(())
+}
+
+/// A BonusSystem `Incentive` record.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct BonusIncentive {
+    pub id: u128,
+    pub employer: String,
+    pub employee: String,
+    pub approver: String,
+    pub token: String,
+    pub kind: String,
+    pub status: String,
+    pub amount_per_payout: i128,
+    pub total_payouts: u32,
+    pub claimed_payouts: u32,
+    pub start_time: u64,
+    pub interval_seconds: u64,
+}
+
+impl BonusIncentive {
+    pub fn from_scval(value: &ScVal) -> Option<Self> {
+        let field = |name| rpc::struct_field(value, name);
+        Some(BonusIncentive {
+            id: u128::try_from(field("id")?.clone()).ok()?,
+            employer: rpc::scval_to_address(field("employer")?)?,
+            employee: rpc::scval_to_address(field("employee")?)?,
+            approver: rpc::scval_to_address(field("approver")?)?,
+            token: rpc::scval_to_address(field("token")?)?,
+            kind: rpc::enum_variant(field("kind")?)?,
+            status: rpc::enum_variant(field("status")?)?,
+            amount_per_payout: i128::try_from(field("amount_per_payout")?.clone()).ok()?,
+            total_payouts: u32::try_from(field("total_payouts")?.clone()).ok()?,
+            claimed_payouts: u32::try_from(field("claimed_payouts")?.clone()).ok()?,
+            start_time: u64::try_from(field("start_time")?.clone()).ok()?,
+            interval_seconds: u64::try_from(field("interval_seconds")?.clone()).ok()?,
+        })
+    }
+
+    /// When payout `index` (0-based) unlocks.
+    pub fn payout_time(&self, index: u32) -> u64 {
+        self.start_time
+            .saturating_add(self.interval_seconds.saturating_mul(index as u64))
+    }
+
+    /// Payouts unlocked by `now`, claimed or not. Mirrors the contract's
+    /// `vested_payouts`; a one-time bonus (zero interval) vests its single
+    /// payout at `start_time`.
+    pub fn vested_payouts(&self, now: u64) -> u32 {
+        if now < self.start_time {
+            return 0;
+        }
+        let cycles = (now - self.start_time)
+            .checked_div(self.interval_seconds)
+            .and_then(|cycles| cycles.checked_add(1))
+            .unwrap_or(u64::MAX);
+        cycles.min(self.total_payouts as u64) as u32
+    }
+
+    /// Payouts `claim_incentive` would pay at `now`, as `get_claimable_payouts`
+    /// computes them on-chain.
+    pub fn claimable_payouts(&self, now: u64) -> u32 {
+        if self.status != "Approved" {
+            return 0;
+        }
+        self.vested_payouts(now)
+            .saturating_sub(self.claimed_payouts)
+    }
+}
+
+/// State-changing bonus subcommands, which all take `(caller, incentive_id)`.
+#[derive(Debug, Clone, Copy)]
+enum BonusAction {
+    Approve,
+    Reject,
+    Claim,
+    Cancel,
+}
+
+impl BonusAction {
+    fn function(self) -> &'static str {
+        match self {
+            BonusAction::Approve => "approve_incentive",
+            BonusAction::Reject => "reject_incentive",
+            BonusAction::Claim => "claim_incentive",
+            BonusAction::Cancel => "cancel_incentive",
+        }
+    }
+
+    fn past_tense(self) -> &'static str {
+        match self {
+            BonusAction::Approve => "approved",
+            BonusAction::Reject => "rejected",
+            BonusAction::Claim => "claimed",
+            BonusAction::Cancel => "cancelled",
+        }
+    }
+
+    /// Checks the caller role and status the contract requires, so that a
+    /// refused call is explained instead of surfacing as a failed simulation.
+    fn check(self, incentive: &BonusIncentive, caller: &str) -> Result<()> {
+        let (role, party, statuses): (&str, &str, &[&str]) = match self {
+            BonusAction::Approve | BonusAction::Reject => {
+                ("approver", &incentive.approver, &["Pending"])
+            }
+            BonusAction::Claim => ("employee", &incentive.employee, &["Approved"]),
+            BonusAction::Cancel => ("employer", &incentive.employer, &["Pending", "Rejected"]),
+        };
+        if party != caller {
+            return Err(anyhow::anyhow!(
+                "Only the {} ({}) can {} incentive {}",
+                role,
+                party,
+                self.function().trim_end_matches("_incentive"),
+                incentive.id
+            ));
+        }
+        if !statuses.contains(&incentive.status.as_str()) {
+            return Err(anyhow::anyhow!(
+                "Incentive {} is {}; expected {}",
+                incentive.id,
+                incentive.status,
+                statuses.join(" or ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub async fn bonus_command(command: BonusCommands, config: &Config) -> Result<()> {
+    match command {
+        BonusCommands::CreateOneTime {
+            terms,
+            amount,
+            unlock_at,
+        } => {
+            let amount = parse_positive_amount(&amount)?;
+            let unlock_at = parse_report_date(&unlock_at)?;
+            bonus_create_command(
+                &terms,
+                "create_one_time_bonus",
+                vec![amount.into(), unlock_at.into()],
+                config,
+            )
+            .await
+        }
+        BonusCommands::CreateRecurring {
+            terms,
+            amount_per_payout,
+            payouts,
+            start,
+            interval,
+        } => {
+            let amount_per_payout = parse_positive_amount(&amount_per_payout)?;
+            if payouts == 0 {
+                return Err(anyhow::anyhow!("--payouts must be greater than zero"));
+            }
+            let start = parse_report_date(&start)?;
+            let interval = match interval.parse::<u64>() {
+                Ok(seconds) => seconds,
+                Err(_) => frequency_to_seconds(&interval).map_err(|e| anyhow::anyhow!(e))?,
+            };
+            if interval == 0 {
+                return Err(anyhow::anyhow!("--interval must be greater than zero"));
+            }
+            bonus_create_command(
+                &terms,
+                "create_recurring_incentive",
+                vec![
+                    amount_per_payout.into(),
+                    payouts.into(),
+                    start.into(),
+                    interval.into(),
+                ],
+                config,
+            )
+            .await
+        }
+        BonusCommands::Approve(target) => {
+            bonus_action_command(target, BonusAction::Approve, config).await
+        }
+        BonusCommands::Reject(target) => {
+            bonus_action_command(target, BonusAction::Reject, config).await
+        }
+        BonusCommands::Claim(target) => {
+            bonus_action_command(target, BonusAction::Claim, config).await
+        }
+        BonusCommands::Cancel(target) => {
+            bonus_action_command(target, BonusAction::Cancel, config).await
+        }
+        BonusCommands::Show(target) => {
+            let rpc = SorobanRpcClient::from_config(config);
+            let incentive = fetch_bonus_incentive(&rpc, &target.contract_id, target.id).await?;
+            print_bonus_incentive(&incentive);
+            Ok(())
+        }
+        BonusCommands::Preview { target, at } => {
+            let now = match at {
+                Some(at) => parse_report_date(&at)?,
+                None => chrono::Utc::now().timestamp().max(0) as u64,
+            };
+            let rpc = SorobanRpcClient::from_config(config);
+            let incentive = fetch_bonus_incentive(&rpc, &target.contract_id, target.id).await?;
+            print_bonus_incentive(&incentive);
+            outln!();
+            print_bonus_preview(&incentive, now);
+            Ok(())
+        }
+    }
+}
+
+fn parse_positive_amount(amount: &str) -> Result<i128> {
+    let amount = parse_amount(amount, TOKEN_DECIMALS)?;
+    if amount <= 0 {
+        return Err(Error::ZeroAmount.into());
+    }
+    Ok(amount)
+}
+
+/// Creates an incentive funded by the signer. `schedule` holds the
+/// arguments that follow `(employer, employee, approver, token)`.
+async fn bonus_create_command(
+    terms: &BonusTermsArgs,
+    function: &str,
+    schedule: Vec<ScVal>,
+    config: &Config,
+) -> Result<()> {
+    validate_address(&terms.employee)?;
+    validate_address(&terms.approver)?;
+    let token = terms
+        .token
+        .clone()
+        .or_else(|| config.defaults.token.clone())
+        .ok_or_else(|| anyhow::anyhow!("No token provided"))?;
+    validate_address(&token)?;
+
+    let signer = get_signer(config).await?;
+    let rpc = SorobanRpcClient::from_config(config);
+
+    let mut args = vec![
+        rpc::address_arg(&signer.address())?,
+        rpc::address_arg(&terms.employee)?,
+        rpc::address_arg(&terms.approver)?,
+        rpc::address_arg(&token)?,
+    ];
+    args.extend(schedule);
+
+    info!("Calling {} for employee: {}", function, terms.employee);
+    let outcome = rpc
+        .invoke(&signer, &terms.contract_id, function, args)
+        .await?;
+    let id = outcome
+        .return_value
+        .and_then(|value| u128::try_from(value).ok())
+        .ok_or_else(|| anyhow::anyhow!("{} did not return an incentive ID", function))?;
+
+    outln!(
+        "✅ Incentive {} created, awaiting approval by {}",
+        id,
+        terms.approver
+    );
+    let incentive = fetch_bonus_incentive(&rpc, &terms.contract_id, id).await?;
+    output::set_result(&incentive)?;
+    print_bonus_incentive(&incentive);
+
+    Ok(())
+}
+
+async fn bonus_action_command(
+    target: BonusTarget,
+    action: BonusAction,
+    config: &Config,
+) -> Result<()> {
+    let signer = get_signer(config).await?;
+    let rpc = SorobanRpcClient::from_config(config);
+
+    let incentive = fetch_bonus_incentive(&rpc, &target.contract_id, target.id).await?;
+    action.check(&incentive, &signer.address())?;
+
+    info!("Calling {} for incentive {}", action.function(), target.id);
+    let outcome = rpc
+        .invoke(
+            &signer,
+            &target.contract_id,
+            action.function(),
+            vec![rpc::address_arg(&signer.address())?, target.id.into()],
+        )
+        .await?;
+
+    outln!("✅ Incentive {} {}!", target.id, action.past_tense());
+    // Claims return the amount paid out, cancellations the amount refunded.
+    if let Some(amount) = outcome
+        .return_value
+        .and_then(|value| i128::try_from(value).ok())
+    {
+        outln!(
+            "  {}: {}",
+            if matches!(action, BonusAction::Cancel) {
+                "Refunded"
+            } else {
+                "Paid out"
+            },
+            format_amount(amount, TOKEN_DECIMALS)
+        );
+    }
+    print_bonus_incentive(&fetch_bonus_incentive(&rpc, &target.contract_id, target.id).await?);
+
+    Ok(())
+}
+
+pub async fn fetch_bonus_incentive(
+    rpc: &SorobanRpcClient,
+    contract_id: &str,
+    id: u128,
+) -> Result<BonusIncentive> {
+    let value = rpc
+        .view(contract_id, "get_incentive", vec![id.into()])
+        .await?;
+    if value == ScVal::Void {
+        return Err(anyhow::anyhow!("Incentive {} not found", id));
+    }
+    BonusIncentive::from_scval(&value)
+        .ok_or_else(|| anyhow::anyhow!("Incentive {} has an unexpected shape", id))
+}
+
+pub fn print_bonus_incentive(incentive: &BonusIncentive) {
+    outln!("Incentive {}:", incentive.id);
+    outln!("  Kind: {}", incentive.kind);
+    outln!("  Status: {}", incentive.status);
+    outln!("  Employer: {}", incentive.employer);
+    outln!("  Employee: {}", incentive.employee);
+    outln!("  Approver: {}", incentive.approver);
+    outln!("  Token: {}", incentive.token);
+    outln!(
+        "  Amount per payout: {}",
+        format_amount(incentive.amount_per_payout, TOKEN_DECIMALS)
+    );
+    outln!(
+        "  Payouts claimed: {} of {}",
+        incentive.claimed_payouts,
+        incentive.total_payouts
+    );
+    outln!(
+        "  {}: {}",
+        if incentive.interval_seconds == 0 {
+            "Unlocks"
+        } else {
+            "Starts"
+        },
+        format_timestamp(incentive.start_time)
+    );
+    if incentive.interval_seconds > 0 {
+        outln!(
+            "  Interval: {}",
+            seconds_to_frequency(incentive.interval_seconds)
+        );
+    }
+}
+
+/// Prints the payout schedule and what a claim would pay at `now`, computed
+/// locally from the incentive record.
+pub fn print_bonus_preview(incentive: &BonusIncentive, now: u64) {
+    let vested = incentive.vested_payouts(now);
+    let claimable = incentive.claimable_payouts(now);
+
+    let rows: Vec<Vec<String>> = (0..incentive.total_payouts)
+        .map(|index| {
+            let state = if index < incentive.claimed_payouts {
+                "claimed"
+            } else if index < incentive.claimed_payouts + claimable {
+                "claimable"
+            } else if index < vested {
+                "unlocked"
+            } else {
+                "locked"
+            };
+            vec![
+                (index + 1).to_string(),
+                format_timestamp(incentive.payout_time(index)),
+                format_amount(incentive.amount_per_payout, TOKEN_DECIMALS),
+                state.to_string(),
+            ]
+        })
+        .collect();
+    outln!("Payout schedule as of {}:", format_timestamp(now));
+    outln!(
+        "{}",
+        format_table(&["#", "Unlocks", "Amount", "State"], &rows)
+    );
+
+    outln!(
+        "Claimable now: {} payout(s), {}",
+        claimable,
+        format_amount(
+            incentive
+                .amount_per_payout
+                .saturating_mul(claimable as i128),
+            TOKEN_DECIMALS
+        )
+    );
+    if incentive.status != "Approved" && vested > incentive.claimed_payouts {
+        outln!(
+            "  Unlocked payouts cannot be claimed while the incentive is {}",
+            incentive.status
+        );
+    }
+}
+
+/// PayrollEscrow state for one agreement.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct EscrowState {
+    pub agreement_id: u128,
+    pub balance: i128,
+    /// Recorded on the agreement's first deposit.
+    pub employer: Option<String>,
+    pub token: Option<String>,
+    /// The only account allowed to release or refund.
+    pub manager: Option<String>,
+}
+
+impl EscrowState {
+    /// Fails unless `caller` is the escrow manager.
+    fn require_manager(&self, caller: &str, action: &str) -> Result<()> {
+        match &self.manager {
+            Some(manager) if manager == caller => Ok(()),
+            Some(manager) => Err(anyhow::anyhow!(
+                "Only the escrow manager ({}) can {} funds",
+                manager,
+                action
+            )),
+            None => Err(anyhow::anyhow!("Escrow contract has no manager set")),
+        }
+    }
+}
+
+pub async fn escrow_command(
+    command: EscrowCommands,
+    config: &Config,
+    assume_yes: bool,
+) -> Result<()> {
+    match command {
+        EscrowCommands::Fund {
+            target,
+            amount,
+            employer,
+        } => escrow_fund_command(target, &amount, employer, config).await,
+        EscrowCommands::Release { target, to, amount } => {
+            escrow_release_command(target, &to, &amount, config, assume_yes).await
+        }
+        EscrowCommands::Refund(target) => escrow_refund_command(target, config, assume_yes).await,
+        EscrowCommands::Balance(target) => {
+            let rpc = SorobanRpcClient::from_config(config);
+            let state = fetch_escrow_state(&rpc, &target.contract_id, target.agreement_id).await?;
+            print_escrow_state(&state);
+            Ok(())
+        }
+    }
+}
+
+pub async fn escrow_fund_command(
+    target: EscrowTarget,
+    amount: &str,
+    employer: Option<String>,
+    config: &Config,
+) -> Result<()> {
+    let amount = parse_positive_amount(amount)?;
+    let signer = get_signer(config).await?;
+    let employer = employer.unwrap_or_else(|| signer.address());
+    validate_address(&employer)?;
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let state = fetch_escrow_state(&rpc, &target.contract_id, target.agreement_id).await?;
+    if let Some(existing) = state.employer.as_ref().filter(|e| **e != employer) {
+        return Err(anyhow::anyhow!(
+            "Agreement {} belongs to employer {}",
+            target.agreement_id,
+            existing
+        ));
+    }
+
+    info!(
+        "Funding agreement {} with {}",
+        target.agreement_id,
+        format_amount(amount, TOKEN_DECIMALS)
+    );
+    rpc.invoke(
+        &signer,
+        &target.contract_id,
+        "fund_agreement",
+        vec![
+            rpc::address_arg(&signer.address())?,
+            target.agreement_id.into(),
+            rpc::address_arg(&employer)?,
+            amount.into(),
+        ],
+    )
+    .await?;
+
+    outln!("✅ Agreement {} funded!", target.agreement_id);
+    print_escrow_state(&fetch_escrow_state(&rpc, &target.contract_id, target.agreement_id).await?);
+    Ok(())
+}
+
+pub async fn escrow_release_command(
+    target: EscrowTarget,
+    to: &str,
+    amount: &str,
+    config: &Config,
+    assume_yes: bool,
+) -> Result<()> {
+    validate_address(to)?;
+    let amount = parse_positive_amount(amount)?;
+    let signer = get_signer(config).await?;
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let state = fetch_escrow_state(&rpc, &target.contract_id, target.agreement_id).await?;
+    state.require_manager(&signer.address(), "release")?;
+    if state.balance < amount {
+        return Err(anyhow::anyhow!(
+            "Insufficient escrow balance: {} available",
+            format_amount(state.balance, TOKEN_DECIMALS)
+        ));
+    }
+
+    print_escrow_state(&state);
+    if !confirm_action(
+        &format!(
+            "Release {} from agreement {} to {}?",
+            format_amount(amount, TOKEN_DECIMALS),
+            target.agreement_id,
+            to
+        ),
+        // A dry run stops at the preview, so there is nothing to confirm yet
+        assume_yes || config.dry_run,
+    )? {
+        outln!("Aborted; nothing was submitted");
+        return Ok(());
+    }
+
+    rpc.invoke(
+        &signer,
+        &target.contract_id,
+        "release",
+        vec![
+            rpc::address_arg(&signer.address())?,
+            target.agreement_id.into(),
+            rpc::address_arg(to)?,
+            amount.into(),
+        ],
+    )
+    .await?;
+
+    outln!(
+        "✅ Released {} to {}",
+        format_amount(amount, TOKEN_DECIMALS),
+        to
+    );
+    print_escrow_state(&fetch_escrow_state(&rpc, &target.contract_id, target.agreement_id).await?);
+    Ok(())
+}
+
+pub async fn escrow_refund_command(
+    target: EscrowTarget,
+    config: &Config,
+    assume_yes: bool,
+) -> Result<()> {
+    let signer = get_signer(config).await?;
+
+    let rpc = SorobanRpcClient::from_config(config);
+    let state = fetch_escrow_state(&rpc, &target.contract_id, target.agreement_id).await?;
+    state.require_manager(&signer.address(), "refund")?;
+    if state.balance <= 0 {
+        return Err(anyhow::anyhow!(
+            "Agreement {} has no balance to refund",
+            target.agreement_id
+        ));
+    }
+    let employer = state.employer.clone().ok_or_else(|| {
+        anyhow::anyhow!("Agreement {} has no employer recorded", target.agreement_id)
+    })?;
+
+    print_escrow_state(&state);
+    if !confirm_action(
+        &format!(
+            "Refund {} from agreement {} to employer {}?",
+            format_amount(state.balance, TOKEN_DECIMALS),
+            target.agreement_id,
+            employer
+        ),
+        assume_yes || config.dry_run,
+    )? {
+        outln!("Aborted; nothing was submitted");
+        return Ok(());
+    }
+
+    rpc.invoke(
+        &signer,
+        &target.contract_id,
+        "refund_remaining",
+        vec![
+            rpc::address_arg(&signer.address())?,
+            target.agreement_id.into(),
+        ],
+    )
+    .await?;
+
+    outln!(
+        "✅ Refunded {} to {}",
+        format_amount(state.balance, TOKEN_DECIMALS),
+        employer
+    );
+    Ok(())
+}
+
+/// Reads an agreement's balance and employer, and the escrow's token and
+/// manager, from a PayrollEscrow contract.
+pub async fn fetch_escrow_state(
+    rpc: &SorobanRpcClient,
+    contract_id: &str,
+    agreement_id: u128,
+) -> Result<EscrowState> {
+    let balance = i128::try_from(
+        rpc.view(
+            contract_id,
+            "get_agreement_balance",
+            vec![agreement_id.into()],
+        )
+        .await?,
+    )
+    .map_err(|_| anyhow::anyhow!("get_agreement_balance returned a non-i128 value"))?;
+    let employer = rpc
+        .view(
+            contract_id,
+            "get_agreement_employer",
+            vec![agreement_id.into()],
+        )
+        .await?;
+
+    Ok(EscrowState {
+        agreement_id,
+        balance,
+        employer: rpc::scval_to_address(&employer),
+        token: read_stored_address(rpc, contract_id, "Token").await?,
+        manager: read_stored_address(rpc, contract_id, "Manager").await?,
+    })
+}
+
+/// Reads an address kept under a unit `StorageKey` variant in persistent storage.
+async fn read_stored_address(
+    rpc: &SorobanRpcClient,
+    contract_id: &str,
+    key: &str,
+) -> Result<Option<String>> {
+    Ok(rpc
+        .get_contract_data(
+            contract_id,
+            rpc::enum_key(key)?,
+            stellar_xdr::curr::ContractDataDurability::Persistent,
+        )
+        .await?
+        .as_ref()
+        .and_then(rpc::scval_to_address))
+}
+
+pub fn print_escrow_state(state: &EscrowState) {
+    let or_unset = |value: &Option<String>| value.clone().unwrap_or_else(|| "Not set".to_string());
+    outln!("Escrow for agreement {}:", state.agreement_id);
+    outln!(
+        "  Balance: {}",
+        format_amount(state.balance, TOKEN_DECIMALS)
+    );
+    outln!("  Employer: {}", or_unset(&state.employer));
+    outln!("  Token: {}", or_unset(&state.token));
+    outln!("  Manager: {}", or_unset(&state.manager));
+}
+
+pub async fn metadata_command(command: MetadataCommands, config: &Config) -> Result<()> {
+    let rpc = SorobanRpcClient::from_config(config);
+    let (target, employer) = match command {
+        MetadataCommands::Set { target, key, value } => {
+            let signer = get_signer(config).await?;
+            let contract_id = resolve_contract_id(target.contract_id.clone(), config)?;
+            info!("Setting metadata key {}", key);
+            rpc.invoke(
+                &signer,
+                &contract_id,
+                "set_employer_metadata",
+                vec![
+                    rpc::address_arg(&signer.address())?,
+                    metadata_subject_arg(&target)?,
+                    rpc::string_arg(&key)?,
+                    rpc::string_arg(&value)?,
+                ],
+            )
+            .await?;
+            outln!("✅ Metadata key {} set", key);
+            (target, signer.address())
+        }
+        MetadataCommands::Remove { target, key } => {
+            let signer = get_signer(config).await?;
+            let contract_id = resolve_contract_id(target.contract_id.clone(), config)?;
+            info!("Removing metadata key {}", key);
+            rpc.invoke(
+                &signer,
+                &contract_id,
+                "remove_employer_metadata",
+                vec![
+                    rpc::address_arg(&signer.address())?,
+                    metadata_subject_arg(&target)?,
+                    rpc::string_arg(&key)?,
+                ],
+            )
+            .await?;
+            outln!("✅ Metadata key {} removed", key);
+            (target, signer.address())
+        }
+        MetadataCommands::Show { target, employer } => {
+            validate_address(&employer)?;
+            (target, employer)
+        }
+    };
+
+    let contract_id = resolve_contract_id(target.contract_id.clone(), config)?;
+    let entries =
+        fetch_employer_metadata(&rpc, &contract_id, &employer, target.employee.as_deref()).await?;
+    output::set_result(&entries)?;
+    print_employer_metadata(&employer, target.employee.as_deref(), &entries);
+    Ok(())
+}
+
+/// Optional employee argument: the address, or `None` (void) for the
+/// employer's own metadata.
+fn metadata_subject_arg(target: &MetadataTarget) -> Result<ScVal> {
+    match &target.employee {
+        Some(employee) => rpc::address_arg(employee),
+        None => Ok(ScVal::Void),
+    }
+}
+
+/// Reads every metadata entry of `employer`, or of `employee` under it, in
+/// key order.
+pub async fn fetch_employer_metadata(
+    rpc: &SorobanRpcClient,
+    contract_id: &str,
+    employer: &str,
+    employee: Option<&str>,
+) -> Result<std::collections::BTreeMap<String, String>> {
+    let subject = match employee {
+        Some(employee) => rpc::address_arg(employee)?,
+        None => ScVal::Void,
+    };
+    let value = rpc
+        .view(
+            contract_id,
+            "list_employer_metadata",
+            vec![rpc::address_arg(employer)?, subject],
+        )
+        .await?;
+    let ScVal::Map(Some(map)) = value else {
+        return Err(anyhow::anyhow!("Metadata has an unexpected shape"));
+    };
+    map.iter()
+        .map(|entry| match (&entry.key, &entry.val) {
+            (ScVal::String(key), ScVal::String(val)) => {
+                Ok((key.to_utf8_string_lossy(), val.to_utf8_string_lossy()))
+            }
+            _ => Err(anyhow::anyhow!("Metadata has an unexpected shape")),
+        })
+        .collect()
+}
+
+pub fn print_employer_metadata(
+    employer: &str,
+    employee: Option<&str>,
+    entries: &std::collections::BTreeMap<String, String>,
+) {
+    match employee {
+        Some(employee) => outln!("Metadata for employee {} of {}:", employee, employer),
+        None => outln!("Metadata for employer {}:", employer),
+    }
+    if entries.is_empty() {
+        outln!("  No entries");
+    }
+    for (key, value) in entries {
+        outln!("  {}: {}", key, value);
+    }
+}
+
+pub async fn template_command(command: TemplateCommands, config: &Config) -> Result<()> {
+    let store = TemplateStore::open_default()?;
+    match command {
+        TemplateCommands::Create {
+            name,
+            salary,
+            token,
+            grace_period_secs,
+            description,
+            no_activate,
+            force,
+        } => {
+            let template = PayrollTemplate {
+                name,
+                description,
+                token,
+                salary_per_period: salary,
+                grace_period_seconds: grace_period_secs,
+                activate: !no_activate,
+            };
+            let path = store.save(&template, force)?;
+            outln!(
+                "✅ Template '{}' written to {}",
+                template.name,
+                path.display()
+            );
+            Ok(())
+        }
+        TemplateCommands::List => {
+            let templates = store.list()?;
+            if templates.is_empty() {
+                outln!("No templates in {}", store.dir().display());
+                return Ok(());
+            }
+            let rows: Vec<Vec<String>> = templates
+                .into_iter()
+                .map(|template| {
+                    vec![
+                        template.name,
+                        template.salary_per_period,
+                        template
+                            .token
+                            .unwrap_or_else(|| "defaults.token".to_string()),
+                        template.grace_period_seconds.to_string(),
+                        if template.activate { "yes" } else { "no" }.to_string(),
+                        template.description.unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            outln!(
+                "{}",
+                format_table(
+                    &[
+                        "Name",
+                        "Salary",
+                        "Token",
+                        "Grace (s)",
+                        "Activate",
+                        "Description"
+                    ],
+                    &rows
+                )
+            );
+            Ok(())
+        }
+        TemplateCommands::Apply {
+            name,
+            employees,
+            employees_file,
+            contract_id,
+        } => {
+            let template = store.load(&name)?;
+            let entries = match employees_file {
+                Some(path) => parse_employee_csv(&std::fs::read_to_string(&path)?)?,
+                None => employees
+                    .into_iter()
+                    .map(|employee| BulkPayEntry {
+                        employee: employee.trim().to_string(),
+                        amount: None,
+                    })
+                    .collect(),
+            };
+            template_apply_command(&template, entries, contract_id, config).await
+        }
+        TemplateCommands::Share { name } => {
+            use sha2::{Digest, Sha256};
+            let template = store.load(&name)?;
+            let shared = template.share_string()?;
+            outln!("{}", shared);
+            eprintln!(
+                "SHA-256: {}",
+                hex::encode(Sha256::digest(template.to_yaml()?.as_bytes()))
+            );
+            Ok(())
+        }
+        TemplateCommands::Export { name, output } => {
+            let yaml = store.load(&name)?.to_yaml()?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, yaml)?;
+                    outln!("✅ Template '{}' exported to {}", name, path.display());
+                }
+                None => output::emit_text(&yaml)?,
+            }
+            Ok(())
+        }
+        TemplateCommands::Import { source, force } => {
+            let template = if source.trim().starts_with(SHARE_PREFIX) {
+                PayrollTemplate::from_share_string(&source)?
+            } else {
+                read_template_file(std::path::Path::new(&source))?
+            };
+            let path = store.save(&template, force)?;
+            outln!(
+                "✅ Template '{}' imported to {}",
+                template.name,
+                path.display()
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Creates an agreement with the template's token and grace period, adds
+/// each employee at the template salary (or the row's own salary), and
+/// activates the agreement unless the template says otherwise.
+pub async fn template_apply_command(
+    template: &PayrollTemplate,
+    entries: Vec<BulkPayEntry>,
+    contract_id: Option<String>,
+    config: &Config,
+) -> Result<()> {
+    let contract_id = contract_id
+        .or_else(|| config.contract.default_contract_id.clone())
+        .ok_or_else(|| anyhow::anyhow!("No contract ID provided"))?;
+    let token = template
+        .token
+        .clone()
+        .or_else(|| config.defaults.token.clone())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Template '{}' has no token; add one or set defaults.token",
+                template.name
+            )
+        })?;
+    validate_address(&token)?;
+
+    if entries.is_empty() {
+        return Err(anyhow::anyhow!("No employees given"));
+    }
+    let salary = template.salary()?;
+    let mut employees = Vec::with_capacity(entries.len());
+    for entry in entries {
+        validate_address(&entry.employee)?;
+        if employees
+            .iter()
+            .any(|(employee, _)| *employee == entry.employee)
+        {
+            return Err(anyhow::anyhow!(
+                "Employee {} is listed more than once",
+                entry.employee
+            ));
+        }
+        let salary = entry.amount.unwrap_or(salary);
+        if salary <= 0 {
+            return Err(Error::ZeroAmount.into());
+        }
+        employees.push((entry.employee, salary));
+    }
+
+    let signer = get_signer(config).await?;
+    let rpc = SorobanRpcClient::from_config(config);
+
+    info!(
+        "Applying template '{}' to {} employees",
+        template.name,
+        employees.len()
+    );
+    let outcome = rpc
+        .invoke(
+            &signer,
+            &contract_id,
+            "create_payroll_agreement",
+            vec![
+                rpc::address_arg(&signer.address())?,
+                rpc::address_arg(&token)?,
+                template.grace_period_seconds.into(),
+            ],
+        )
+        .await?;
+    let agreement_id = outcome
+        .return_value
+        .and_then(|value| u128::try_from(value).ok())
+        .ok_or_else(|| {
+            anyhow::anyhow!("create_payroll_agreement did not return an agreement ID")
+        })?;
+    outln!(
+        "✅ Agreement {} created from template '{}'",
+        agreement_id,
+        template.name
+    );
+
+    for (employee, salary) in &employees {
+        rpc.invoke(
+            &signer,
+            &contract_id,
+            "add_employee_to_agreement",
+            vec![
+                agreement_id.into(),
+                rpc::address_arg(employee)?,
+                (*salary).into(),
+            ],
+        )
+        .await
+        .map_err(|e| {
+            error!("Adding employee {} failed: {}", employee, e);
+            anyhow::anyhow!(
+                "Agreement {} was created but adding employee {} failed",
+                agreement_id,
+                employee
+            )
+        })?;
+    }
+
+    if template.activate {
+        rpc.invoke(
+            &signer,
+            &contract_id,
+            "activate_agreement",
+            vec![agreement_id.into()],
+        )
+        .await?;
+    }
+
+    let rows: Vec<Vec<String>> = employees
+        .iter()
+        .map(|(employee, salary)| vec![employee.clone(), format_amount(*salary, TOKEN_DECIMALS)])
+        .collect();
+    outln!(
+        "{}",
+        format_table(&["Employee", "Salary per period"], &rows)
+    );
+    output::set_result(&serde_json::json!({
+        "agreement_id": agreement_id.to_string(),
+        "employees": employees
+            .iter()
+            .map(|(employee, salary)| serde_json::json!({
+                "employee": employee,
+                "salary": salary.to_string(),
+            }))
+            .collect::<Vec<_>>(),
+        "activated": template.activate,
+    }))?;
+    outln!(
+        "Agreement {}: {} employees, {}",
+        agreement_id,
+        employees.len(),
+        if template.activate {
+            "active"
+        } else {
+            "not activated"
+        }
+    );
+    Ok(())
+}
+
+pub async fn devnet_command(command: DevnetCommands, config: &Config) -> Result<()> {
+    match command {
+        DevnetCommands::Up(args) => devnet_up_command(args, config).await,
+        DevnetCommands::Down { container } => {
+            docker(&["stop", &container]).await?;
+            outln!("✅ Stopped {}", container);
+            Ok(())
+        }
+    }
+}
+
+/// Friendbot endpoint of a quickstart container, served next to its RPC at
+/// `/soroban/rpc`.
+pub fn devnet_friendbot_url(rpc_url: &str) -> Option<String> {
+    rpc_url
+        .trim_end_matches('/')
+        .strip_suffix("/soroban/rpc")
+        .map(|base| format!("{}/friendbot", base))
+}
+
+/// Runs `devnet up`: starts a quickstart container (unless a local network
+/// is given with `--rpc-url` or `[devnet]`), funds a fresh owner account,
+/// deploys the native asset contract and the contract suite, adds sample
+/// employees to an active agreement, and writes a config profile for the
+/// network.
+pub async fn devnet_up_command(args: DevnetUpArgs, config: &Config) -> Result<()> {
+    if config.dry_run {
+        return Err(anyhow::anyhow!("devnet up cannot run with --dry-run"));
+    }
+    if std::env::var_os("STELLOPAY_SECRET_KEY").is_some() {
+        return Err(anyhow::anyhow!(
+            "devnet up signs with a new owner key; unset STELLOPAY_SECRET_KEY"
+        ));
+    }
+    // Fail on a missing build before starting anything
+    read_suite_wasms(&args.wasm_dir)?;
+
+    let rpc_url = match args.rpc_url.clone().or(config.devnet.rpc_url.clone()) {
+        Some(rpc_url) => rpc_url,
+        None => start_quickstart(&args).await?,
+    };
+    let friendbot_url = args
+        .friendbot_url
+        .clone()
+        .or(config.devnet.friendbot_url.clone())
+        .or_else(|| devnet_friendbot_url(&rpc_url))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Cannot derive the friendbot URL from {}; pass --friendbot-url",
+                rpc_url
+            )
+        })?;
+    let network_passphrase = config
+        .devnet
+        .network_passphrase
+        .clone()
+        .unwrap_or_else(|| Network::Local.passphrase().to_string());
+
+    let mut seed = [0u8; 32];
+    OsRng.fill_bytes(&mut seed);
+    let owner_secret = stellar_strkey::ed25519::PrivateKey(seed).to_string();
+    let owner = rpc::public_key_strkey(&rpc::signing_key_from_secret(&owner_secret)?);
+    let mut devnet = Config {
+        network: crate::NetworkConfig {
+            rpc_url: rpc_url.clone(),
+            network_passphrase,
+        },
+        auth: crate::AuthConfig {
+            secret_key: Some(owner_secret.clone()),
+            ..Default::default()
+        },
+        assume_yes: true,
+        ..Config::default()
+    };
+    devnet.defaults.employers = vec![owner.clone()];
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(args.timeout_secs);
+    outln!("Waiting for {}", rpc_url);
+    let rpc = SorobanRpcClient::from_config(&devnet);
+    while let Err(e) = rpc.get_latest_ledger().await {
+        if std::time::Instant::now() > deadline {
+            return Err(Error::Network(e.context("Local network did not come up in time")).into());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
+
+    let http = reqwest::Client::new();
+    fund_account(&http, &friendbot_url, &owner, deadline).await?;
+    outln!("✅ Owner {} funded", owner);
+
+    let signer = get_signer(&devnet).await?;
+    let token = rpc
+        .deploy_asset_contract(&signer, stellar_xdr::curr::Asset::Native)
+        .await?;
+    outln!("✅ Native XLM asset contract: {}", token);
+    devnet.defaults.token = Some(token.clone());
+
+    let manifest = deploy_suite(
+        &DeploySuiteArgs {
+            owner: owner.clone(),
+            token: Some(token.clone()),
+            wasm_dir: args.wasm_dir.clone(),
+            manifest: args.manifest.clone(),
+            no_save: true,
+        },
+        &devnet,
+    )
+    .await?;
+    devnet.contract.default_contract_id = Some(manifest.payroll.contract_id.clone());
+
+    let mut employees = Vec::with_capacity(args.employees);
+    for _ in 0..args.employees {
+        OsRng.fill_bytes(&mut seed);
+        let secret = stellar_strkey::ed25519::PrivateKey(seed).to_string();
+        let address = rpc::public_key_strkey(&rpc::signing_key_from_secret(&secret)?);
+        fund_account(&http, &friendbot_url, &address, deadline).await?;
+        employees.push((address, secret));
+    }
+    if !employees.is_empty() {
+        outln!("Adding {} sample employees", employees.len());
+        let template = PayrollTemplate {
+            name: "devnet".to_string(),
+            description: Some("Sample agreement created by devnet up".to_string()),
+            token: Some(token.clone()),
+            salary_per_period: "100".to_string(),
+            grace_period_seconds: 7 * 24 * 60 * 60,
+            activate: true,
+        };
+        let entries = employees
+            .iter()
+            .map(|(employee, _)| BulkPayEntry {
+                employee: employee.clone(),
+                amount: None,
+            })
+            .collect();
+        template_apply_command(&template, entries, None, &devnet).await?;
+    }
+
+    save_config(&args.profile, &devnet).await?;
+    output::set_result(&serde_json::json!({
+        "profile": args.profile,
+        "rpc_url": rpc_url,
+        "friendbot_url": friendbot_url,
+        "owner": owner,
+        "token": token,
+        "manifest": manifest,
+        "employees": employees
+            .iter()
+            .map(|(address, secret)| serde_json::json!({ "address": address, "secret_key": secret }))
+            .collect::<Vec<_>>(),
+    }))?;
+
+    let mut rows = vec![
+        vec!["Owner".to_string(), owner],
+        vec!["Token (XLM)".to_string(), token],
+        vec!["Payroll".to_string(), manifest.payroll.contract_id],
+        vec!["Escrow".to_string(), manifest.escrow.contract_id],
+        vec![
+            "Payment history".to_string(),
+            manifest.payment_history.contract_id,
+        ],
+        vec![
+            "Bonus system".to_string(),
+            manifest.bonus_system.contract_id,
+        ],
+    ];
+    rows.extend(
+        employees
+            .iter()
+            .enumerate()
+            .map(|(index, (address, _))| vec![format!("Employee {}", index + 1), address.clone()]),
+    );
+    outln!();
+    outln!("{}", format_table(&["Account", "Address"], &rows));
+    if !employees.is_empty() {
+        outln!("Employee secret keys (local network only):");
+        for (address, secret) in &employees {
+            outln!("  {}  {}", address, secret);
+        }
+    }
+    outln!();
+    outln!(
+        "✅ Local network ready. Profile written to {}",
+        args.profile.display()
+    );
+    outln!("   stellopay-cli --config {} info", args.profile.display());
+    Ok(())
+}
+
+/// Starts the quickstart container in local mode, or reuses it if it is
+/// already running, and returns its RPC URL.
+async fn start_quickstart(args: &DevnetUpArgs) -> Result<String> {
+    let running = docker(&["inspect", "--format", "{{.State.Running}}", &args.container])
+        .await
+        .is_ok_and(|state| state == "true");
+    if running {
+        outln!("Using running container {}", args.container);
+    } else {
+        outln!("Starting {} as {}", args.image, args.container);
+        docker(&[
+            "run",
+            "--detach",
+            "--rm",
+            "--name",
+            &args.container,
+            "--publish",
+            &format!("{}:8000", args.port),
+            &args.image,
+            "--local",
+        ])
+        .await?;
+    }
+    Ok(format!("http://localhost:{}/soroban/rpc", args.port))
+}
+
+async fn docker(args: &[&str]) -> Result<String> {
+    let output = tokio::process::Command::new("docker")
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Could not run docker: {}. Install Docker, or pass --rpc-url for a network that is already running",
+                e
+            )
+        })?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "docker {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Funds `address` from friendbot, retrying until `deadline` while the
+/// network is still starting up.
+pub async fn fund_account(
+    http: &reqwest::Client,
+    friendbot_url: &str,
+    address: &str,
+    deadline: std::time::Instant,
+) -> Result<()> {
+    loop {
+        let error = match http
+            .get(friendbot_url)
+            .query(&[("addr", address)])
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => anyhow::anyhow!("friendbot returned {}", response.status()),
+            Err(e) => e.into(),
+        };
+        if std::time::Instant::now() > deadline {
+            return Err(
+                Error::Network(error.context(format!("Could not fund {}", address))).into(),
+            );
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
+}
@@ -1,19 +1,27 @@
-use crate::Config;
+use crate::keystore::{read_passphrase, Keystore};
+use crate::signer::{ExternalSigner, TransactionSigner};
+use crate::{Config, Error};
 use anyhow::Result;
-use std::path::Path;
+use log::warn;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
-pub async fn load_config(config_path: &Path) -> Result<Config> {
+/// Expands a leading `~` to the home directory.
+pub fn expand_path(config_path: &Path) -> Result<PathBuf> {
     // Expand tilde in path
-    let expanded_path = if config_path.starts_with("~") {
+    if config_path.starts_with("~") {
         let home_dir =
             dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
         let path_str = config_path.to_string_lossy();
         let without_tilde = &path_str[1..]; // Remove the ~
-        home_dir.join(without_tilde.trim_start_matches('/'))
+        Ok(home_dir.join(without_tilde.trim_start_matches('/')))
     } else {
-        config_path.to_path_buf()
-    };
+        Ok(config_path.to_path_buf())
+    }
+}
+
+pub async fn load_config(config_path: &Path) -> Result<Config> {
+    let expanded_path = expand_path(config_path)?;
 
     if !expanded_path.exists() {
         // Create default config if it doesn't exist
@@ -37,21 +45,65 @@ pub async fn create_config_file(path: &Path, config: &Config) -> Result<()> {
     let config_content = toml::to_string_pretty(config)?;
     fs::write(path, config_content).await?;
 
-    println!("Created default config file at: {}", path.display());
+    crate::outln!("Created default config file at: {}", path.display());
 
     Ok(())
 }
 
+/// Writes `config` back to an existing config file.
+pub async fn save_config(config_path: &Path, config: &Config) -> Result<()> {
+    let config_content = toml::to_string_pretty(config)?;
+    fs::write(expand_path(config_path)?, config_content).await?;
+    Ok(())
+}
+
+/// Resolves the secret key for commands that need it in-process, in order:
+/// `STELLOPAY_SECRET_KEY`, the keystore key named by `auth.key_name`, then
+/// the deprecated plaintext `auth.secret_key`.
 pub fn get_secret_key(config: &Config) -> Result<String> {
+    resolve_secret_key(config).map_err(|e| Error::Auth(e).into())
+}
+
+fn resolve_secret_key(config: &Config) -> Result<String> {
     // Check environment variable first
     if let Ok(key) = std::env::var("STELLOPAY_SECRET_KEY") {
         return Ok(key);
     }
 
-    // Check config file
+    // Then the encrypted keystore
+    if let Some(name) = &config.auth.key_name {
+        let keystore = Keystore::open_default()?;
+        let passphrase = read_passphrase(&format!("Passphrase for key '{}'", name), false)?;
+        let signing_key = keystore.unlock(name, &passphrase)?;
+        return Ok(stellar_strkey::ed25519::PrivateKey(signing_key.to_bytes()).to_string());
+    }
+
+    // Plaintext config secrets still work, but should be migrated
     if let Some(secret_key) = &config.auth.secret_key {
+        warn!("auth.secret_key is stored in plaintext; move it into the keystore with `stellopay-cli keys import --name <NAME> --from-config --set-default`");
         return Ok(secret_key.clone());
     }
 
-    Err(anyhow::anyhow!("No secret key found. Set STELLOPAY_SECRET_KEY environment variable or add it to config file"))
+    if config.auth.signer_command.is_some() {
+        return Err(anyhow::anyhow!("This command cannot use an external signer; configure auth.key_name or set STELLOPAY_SECRET_KEY"));
+    }
+
+    Err(anyhow::anyhow!("No secret key found. Import one with `stellopay-cli keys import` and set auth.key_name, or set STELLOPAY_SECRET_KEY"))
+}
+
+/// Resolves the signer for transactions built and signed by this CLI.
+///
+/// `auth.signer_command` takes precedence over local keys unless
+/// `STELLOPAY_SECRET_KEY` is set; otherwise see [`get_secret_key`].
+pub async fn get_signer(config: &Config) -> Result<TransactionSigner> {
+    if std::env::var("STELLOPAY_SECRET_KEY").is_err() {
+        if let Some(command) = &config.auth.signer_command {
+            return Ok(TransactionSigner::External(
+                ExternalSigner::connect(command)
+                    .await
+                    .map_err(Error::Auth)?,
+            ));
+        }
+    }
+    TransactionSigner::from_secret(&get_secret_key(config)?).map_err(|e| Error::Auth(e).into())
 }
@@ -0,0 +1,304 @@
+//! Encrypted keystore for signing keys.
+//!
+//! Each key lives in its own file, `~/.stellopay/keys/<name>.json` (or under
+//! `STELLOPAY_KEYSTORE_DIR`), holding the account's public key in the clear
+//! and the secret seed encrypted with AES-256-GCM. The encryption key is
+//! derived from a passphrase with PBKDF2-HMAC-SHA256; the KDF name and
+//! iteration count are stored per file so the parameters can be raised, or
+//! the KDF replaced, without breaking existing keys.
+//!
+//! The passphrase is read from `STELLOPAY_KEYSTORE_PASSPHRASE` when set (for
+//! CI and keepers), otherwise it is prompted for on the terminal.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, Context, Result};
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+use crate::rpc;
+
+/// PBKDF2 iterations for newly written keys (OWASP 2023 guidance for
+/// PBKDF2-HMAC-SHA256).
+pub const DEFAULT_ITERATIONS: u32 = 600_000;
+
+const KEYSTORE_VERSION: u32 = 1;
+const KDF_PBKDF2_SHA256: &str = "pbkdf2-hmac-sha256";
+const CIPHER_AES_256_GCM: &str = "aes-256-gcm";
+
+/// On-disk representation of one encrypted key.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeyFile {
+    pub version: u32,
+    pub name: String,
+    pub public_key: String,
+    pub kdf: String,
+    pub iterations: u32,
+    /// Hex-encoded KDF salt.
+    pub salt: String,
+    pub cipher: String,
+    /// Hex-encoded AES-GCM nonce.
+    pub nonce: String,
+    /// Hex-encoded encrypted 32-byte ed25519 seed.
+    pub ciphertext: String,
+}
+
+/// Name and address of a stored key. Never contains secret material.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyInfo {
+    pub name: String,
+    pub public_key: String,
+}
+
+pub struct Keystore {
+    dir: PathBuf,
+    iterations: u32,
+}
+
+impl Keystore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Keystore {
+            dir: dir.into(),
+            iterations: DEFAULT_ITERATIONS,
+        }
+    }
+
+    /// Opens `STELLOPAY_KEYSTORE_DIR`, or `~/.stellopay/keys` by default.
+    pub fn open_default() -> Result<Self> {
+        if let Ok(dir) = std::env::var("STELLOPAY_KEYSTORE_DIR") {
+            return Ok(Keystore::new(dir));
+        }
+        let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
+        Ok(Keystore::new(home.join(".stellopay").join("keys")))
+    }
+
+    /// Overrides the PBKDF2 iteration count used for keys written from now on.
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Encrypts and stores an `S...` secret seed under `name`.
+    pub fn import(&self, name: &str, secret_key: &str, passphrase: &str) -> Result<KeyInfo> {
+        let signing_key = rpc::signing_key_from_secret(secret_key)?;
+        self.store(name, &signing_key, passphrase)
+    }
+
+    /// Generates a new random key and stores it under `name`.
+    pub fn generate(&self, name: &str, passphrase: &str) -> Result<KeyInfo> {
+        let mut seed = Zeroizing::new([0u8; 32]);
+        OsRng.fill_bytes(seed.as_mut());
+        self.store(name, &SigningKey::from_bytes(&seed), passphrase)
+    }
+
+    /// Lists stored keys, sorted by name.
+    pub fn list(&self) -> Result<Vec<KeyInfo>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let file = read_key_file(&path)?;
+            keys.push(KeyInfo {
+                name: file.name,
+                public_key: file.public_key,
+            });
+        }
+        keys.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(keys)
+    }
+
+    /// Decrypts the key stored under `name`.
+    pub fn unlock(&self, name: &str, passphrase: &str) -> Result<SigningKey> {
+        let path = self.key_path(name)?;
+        if !path.exists() {
+            return Err(anyhow!(
+                "Key '{}' not found in keystore {}",
+                name,
+                self.dir.display()
+            ));
+        }
+        let file = read_key_file(&path)?;
+        let signing_key = decrypt_key(&file, passphrase)
+            .with_context(|| format!("Failed to unlock key '{}'", name))?;
+
+        if rpc::public_key_strkey(&signing_key) != file.public_key {
+            return Err(anyhow!(
+                "Key '{}' does not match its recorded public key",
+                name
+            ));
+        }
+        Ok(signing_key)
+    }
+
+    fn store(&self, name: &str, signing_key: &SigningKey, passphrase: &str) -> Result<KeyInfo> {
+        let path = self.key_path(name)?;
+        if path.exists() {
+            return Err(anyhow!("Key '{}' already exists in keystore", name));
+        }
+
+        let file = encrypt_key(name, signing_key, passphrase, self.iterations)?;
+        fs::create_dir_all(&self.dir)?;
+        write_private(&path, serde_json::to_string_pretty(&file)?.as_bytes())?;
+
+        Ok(KeyInfo {
+            name: file.name,
+            public_key: file.public_key,
+        })
+    }
+
+    fn key_path(&self, name: &str) -> Result<PathBuf> {
+        validate_key_name(name)?;
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+}
+
+/// Key names become file names, so only `[A-Za-z0-9_-]` is accepted.
+pub fn validate_key_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Invalid key name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the keystore passphrase from `STELLOPAY_KEYSTORE_PASSPHRASE`, or
+/// prompts for it. `confirm` asks twice, for passphrases protecting new keys.
+pub fn read_passphrase(prompt: &str, confirm: bool) -> Result<Zeroizing<String>> {
+    if let Ok(passphrase) = std::env::var("STELLOPAY_KEYSTORE_PASSPHRASE") {
+        return Ok(Zeroizing::new(passphrase));
+    }
+
+    let mut input = dialoguer::Password::new().with_prompt(prompt);
+    if confirm {
+        input = input.with_confirmation("Repeat passphrase", "Passphrases do not match");
+    }
+    let passphrase = input.interact().context(
+        "Failed to read passphrase; set STELLOPAY_KEYSTORE_PASSPHRASE when not on a terminal",
+    )?;
+    Ok(Zeroizing::new(passphrase))
+}
+
+pub fn encrypt_key(
+    name: &str,
+    signing_key: &SigningKey,
+    passphrase: &str,
+    iterations: u32,
+) -> Result<KeyFile> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let public_key = rpc::public_key_strkey(signing_key);
+    let cipher = Aes256Gcm::new_from_slice(derive_key(passphrase, &salt, iterations).as_ref())?;
+    let ciphertext = cipher
+        .encrypt(
+            &Nonce::from(nonce),
+            Payload {
+                msg: signing_key.as_bytes(),
+                aad: public_key.as_bytes(),
+            },
+        )
+        .map_err(|_| anyhow!("Failed to encrypt key"))?;
+
+    Ok(KeyFile {
+        version: KEYSTORE_VERSION,
+        name: name.to_string(),
+        public_key,
+        kdf: KDF_PBKDF2_SHA256.to_string(),
+        iterations,
+        salt: hex::encode(salt),
+        cipher: CIPHER_AES_256_GCM.to_string(),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    })
+}
+
+pub fn decrypt_key(file: &KeyFile, passphrase: &str) -> Result<SigningKey> {
+    if file.version != KEYSTORE_VERSION
+        || file.kdf != KDF_PBKDF2_SHA256
+        || file.cipher != CIPHER_AES_256_GCM
+    {
+        return Err(anyhow!(
+            "Unsupported keystore format (version {}, {}, {})",
+            file.version,
+            file.kdf,
+            file.cipher
+        ));
+    }
+
+    let salt = hex::decode(&file.salt).context("Corrupt keystore salt")?;
+    let nonce = hex::decode(&file.nonce).context("Corrupt keystore nonce")?;
+    let ciphertext = hex::decode(&file.ciphertext).context("Corrupt keystore ciphertext")?;
+    let nonce: [u8; 12] = nonce
+        .try_into()
+        .map_err(|_| anyhow!("Corrupt keystore nonce"))?;
+
+    let cipher =
+        Aes256Gcm::new_from_slice(derive_key(passphrase, &salt, file.iterations).as_ref())?;
+    let seed = Zeroizing::new(
+        cipher
+            .decrypt(
+                &Nonce::from(nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: file.public_key.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("Incorrect passphrase"))?,
+    );
+    let seed: &[u8; 32] = seed
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow!("Corrupt keystore ciphertext"))?;
+
+    Ok(SigningKey::from_bytes(seed))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, key.as_mut());
+    key
+}
+
+fn read_key_file(path: &Path) -> Result<KeyFile> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read key file {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid key file {}", path.display()))
+}
+
+/// Writes a file readable only by the current user.
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(content)?;
+    Ok(())
+}
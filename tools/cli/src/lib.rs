@@ -1,10 +1,17 @@
 pub mod commands;
 pub mod config;
+pub mod keystore;
+pub mod output;
+pub mod rpc;
+pub mod signer;
+pub mod templates;
 pub mod utils;
 
-pub use config::{create_config_file, get_secret_key, load_config};
+pub use config::{create_config_file, get_secret_key, get_signer, load_config};
+pub use rpc::{Network, SorobanRpcClient, TransactionOutcome};
+pub use signer::{ExternalSigner, TransactionSigner};
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -25,25 +32,36 @@ pub struct Cli {
     #[arg(short, long)]
     pub verbose: bool,
 
+    /// Output format; `json` prints one JSON document with the result,
+    /// transactions and error, and sends progress text to stderr
+    #[arg(long, value_enum, default_value = "text")]
+    pub output: output::OutputMode,
+
+    /// Skip confirmation prompts
     #[arg(long, short = 'y', global = true)]
     pub yes: bool,
+
+    /// Simulate transactions and print their preview instead of submitting them
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Network to use (testnet, mainnet, futurenet); overrides the config file
+    #[arg(long, global = true)]
+    pub network: Option<String>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    /// Deploy a new contract
+    /// Upload, create and initialize a new payroll contract
     Deploy {
-        /// Network to deploy to
-        #[arg(long, default_value = "testnet")]
-        network: String,
-
-        /// Owner address
-        #[arg(long)]
-        owner: String,
+        #[command(flatten)]
+        args: DeployArgs,
+    },
 
-        /// WASM file path
-        #[arg(long)]
-        wasm: Option<PathBuf>,
+    /// Deploy the payroll, escrow, payment history and bonus contracts together
+    DeploySuite {
+        #[command(flatten)]
+        args: DeploySuiteArgs,
     },
 
     /// Get contract information
@@ -51,27 +69,803 @@ pub enum Commands {
         /// Contract ID to inspect
         #[arg(long)]
         contract_id: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
     },
     /// Show CLI status
     Status,
-    /// Emergency Command
+    /// Withdraw funds held by the contract in an emergency (owner only)
     EmergencyWithdraw {
+        #[command(flatten)]
+        args: EmergencyWithdrawArgs,
+    },
+    /// Webhook management commands
+    Webhook {
+        #[command(subcommand)]
+        command: WebhookCommands,
+    },
+    /// Employee payroll management commands
+    Employee {
+        #[command(subcommand)]
+        command: EmployeeCommands,
+    },
+    /// Pay employees listed in a CSV file from the signer's token balance
+    Pay {
+        #[command(flatten)]
+        args: BulkPayArgs,
+    },
+    /// Automated payment scheduler keeper
+    Keeper {
+        #[command(subcommand)]
+        command: KeeperCommands,
+    },
+    /// Manage and run payment schedules in the PaymentScheduler contract
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommands,
+    },
+    /// List an employer's scheduled payouts due within a window and the funds they need
+    Upcoming {
+        #[command(flatten)]
+        args: UpcomingArgs,
+    },
+    /// Export an employer's projected payroll payouts as a calendar
+    Calendar {
+        #[command(flatten)]
+        args: CalendarArgs,
+    },
+    /// Export an employer's payments, totals and audit trail for a date range
+    Report {
+        #[command(flatten)]
+        args: ReportArgs,
+    },
+    /// Manage signing keys in the encrypted keystore
+    Keys {
+        #[command(subcommand)]
+        command: KeysCommands,
+    },
+    /// Bonus and incentive commands for the BonusSystem contract
+    Bonus {
+        #[command(subcommand)]
+        command: BonusCommands,
+    },
+    /// Agreement escrow commands for the PayrollEscrow contract
+    Escrow {
+        #[command(subcommand)]
+        command: EscrowCommands,
+    },
+    /// Employer and employee metadata in the payroll contract
+    Metadata {
+        #[command(subcommand)]
+        command: MetadataCommands,
+    },
+    /// List payments recorded in the PaymentHistory contract
+    History {
+        #[command(flatten)]
+        args: HistoryArgs,
+    },
+    /// Manage local payroll templates and apply them to employees
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
+    /// Run a local network with the contracts deployed, for development
+    Devnet {
+        #[command(subcommand)]
+        command: DevnetCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DevnetCommands {
+    /// Start a local network, deploy the contracts, seed sample data and write a profile
+    Up(DevnetUpArgs),
+    /// Stop and remove the container started by `devnet up`
+    Down {
+        /// Container name
+        #[arg(long, default_value = DEVNET_CONTAINER)]
+        container: String,
+    },
+}
+
+/// Container started by `devnet up` unless `--container` names another.
+pub const DEVNET_CONTAINER: &str = "stellopay-devnet";
+
+/// Options for `devnet up`.
+#[derive(Args, Debug, Clone)]
+pub struct DevnetUpArgs {
+    /// RPC URL of a running local network to use instead of starting a
+    /// container [default: devnet.rpc_url]
+    #[arg(long)]
+    pub rpc_url: Option<String>,
+    /// Friendbot URL that funds new accounts [default: devnet.friendbot_url,
+    /// or /friendbot next to a quickstart's /soroban/rpc]
+    #[arg(long)]
+    pub friendbot_url: Option<String>,
+    /// Quickstart image to run
+    #[arg(long, default_value = "stellar/quickstart:latest")]
+    pub image: String,
+    /// Container name
+    #[arg(long, default_value = DEVNET_CONTAINER)]
+    pub container: String,
+    /// Host port for the container's HTTP port
+    #[arg(long, default_value_t = 8000)]
+    pub port: u16,
+    /// Seconds to wait for the network to accept transactions
+    #[arg(long, default_value_t = 300)]
+    pub timeout_secs: u64,
+    /// Directory with the release builds of the suite contracts
+    #[arg(long, default_value = "../../onchain/target/wasm32v1-none/release")]
+    pub wasm_dir: PathBuf,
+    /// Sample employees to fund and add to an agreement
+    #[arg(long, default_value_t = 3)]
+    pub employees: usize,
+    /// Config file to write for the local network
+    #[arg(long, default_value = "devnet.toml")]
+    pub profile: PathBuf,
+    /// Deployment manifest to write
+    #[arg(long, default_value = "devnet-manifest.json")]
+    pub manifest: PathBuf,
+}
+
+#[derive(Subcommand)]
+pub enum KeysCommands {
+    /// Encrypt an existing secret key into the keystore
+    Import {
+        /// Name to store the key under
+        #[arg(long)]
+        name: String,
+        /// Move the plaintext auth.secret_key out of the config file
+        #[arg(long)]
+        from_config: bool,
+        /// Make this the configured signing key (sets auth.key_name)
+        #[arg(long)]
+        set_default: bool,
+    },
+    /// Generate a new key in the keystore
+    Generate {
+        /// Name to store the key under
+        #[arg(long)]
+        name: String,
+        /// Make this the configured signing key (sets auth.key_name)
+        #[arg(long)]
+        set_default: bool,
+    },
+    /// List keys in the keystore
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum TemplateCommands {
+    /// Write a new template to the template directory
+    Create {
+        /// Template name, also the file name
+        #[arg(long)]
+        name: String,
+        /// Salary per period in token units
+        #[arg(long)]
+        salary: String,
+        /// Token contract [default: defaults.token when applied]
+        #[arg(long)]
+        token: Option<String>,
+        /// Grace period of the agreement in seconds
+        #[arg(long, default_value_t = 0)]
+        grace_period_secs: u64,
+        /// Free-form description shown by `template list`
+        #[arg(long)]
+        description: Option<String>,
+        /// Leave applied agreements in the Created state
+        #[arg(long)]
+        no_activate: bool,
+        /// Replace an existing template of the same name
+        #[arg(long)]
+        force: bool,
+    },
+    /// List templates in the template directory
+    List,
+    /// Create an agreement from a template and add employees to it
+    Apply {
+        /// Template name
+        name: String,
+        /// Comma-separated employee addresses
+        #[arg(
+            long,
+            value_delimiter = ',',
+            required_unless_present = "employees_file"
+        )]
+        employees: Vec<String>,
+        /// CSV of `address[,salary]` rows; a salary overrides the template's
+        #[arg(long, conflicts_with = "employees")]
+        employees_file: Option<PathBuf>,
+        /// Payroll contract ID [default: contract.default_contract_id]
         #[arg(long)]
         contract_id: Option<String>,
+    },
+    /// Print a template as a single line that `template import` accepts
+    Share {
+        /// Template name
+        name: String,
+    },
+    /// Write a template's YAML to a file or stdout
+    Export {
+        /// Template name
+        name: String,
+        /// Output file [default: stdout]
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Add a template from a YAML file or a `template share` line
+    Import {
+        /// YAML file path or `stellopay-template:...` line
+        source: String,
+        /// Replace an existing template of the same name
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DeployArgs {
+    /// Owner passed to `initialize`; must be the signing account
+    #[arg(long)]
+    pub owner: String,
+
+    /// WASM file path [default: the local release build]
+    #[arg(long, conflicts_with = "wasm_url")]
+    pub wasm: Option<PathBuf>,
+
+    /// Download the WASM from a release artifact URL instead
+    #[arg(long, requires = "wasm_sha256")]
+    pub wasm_url: Option<String>,
+
+    /// Expected SHA-256 of the WASM in hex; required with --wasm-url
+    #[arg(long)]
+    pub wasm_sha256: Option<String>,
+
+    /// Do not record the new contract as `default_contract_id`
+    #[arg(long)]
+    pub no_save: bool,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DeploySuiteArgs {
+    /// Owner and admin of every contract; must be the signing account
+    #[arg(long)]
+    pub owner: String,
+
+    /// Token held by the escrow contract [default: defaults.token]
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Directory with the release builds of the four contracts
+    #[arg(long, default_value = "../../onchain/target/wasm32v1-none/release")]
+    pub wasm_dir: PathBuf,
+
+    /// Where to write the deployment manifest
+    #[arg(long, default_value = "deployment-manifest.json")]
+    pub manifest: PathBuf,
+
+    /// Do not record the payroll contract as `default_contract_id`
+    #[arg(long)]
+    pub no_save: bool,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+/// Options for `history`. Exactly one of `--employee`, `--employer` and
+/// `--agreement` selects the payments.
+#[derive(Args, Debug, Clone)]
+#[command(group(
+    clap::ArgGroup::new("selector")
+        .required(true)
+        .args(["employee", "employer", "agreement"])
+))]
+pub struct HistoryArgs {
+    /// PaymentHistory contract ID
+    #[arg(long)]
+    pub history_id: String,
+    /// Payments received by this employee
+    #[arg(long)]
+    pub employee: Option<String>,
+    /// Payments made by this employer
+    #[arg(long)]
+    pub employer: Option<String>,
+    /// Payments made under this agreement ID
+    #[arg(long)]
+    pub agreement: Option<u128>,
+    /// First day included, as YYYY-MM-DD (UTC) or Unix seconds
+    #[arg(long)]
+    pub from: Option<String>,
+    /// Last day included, as YYYY-MM-DD (UTC) or Unix seconds
+    #[arg(long)]
+    pub to: Option<String>,
+    /// Payments per page
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+    /// Page to show, starting at 1
+    #[arg(long, default_value_t = 1)]
+    pub page: usize,
+    /// Export every matching payment in this format instead of showing a page
+    #[arg(long, value_enum)]
+    pub export: Option<ReportFormat>,
+    /// File to export to (defaults to stdout)
+    #[arg(long, requires = "export")]
+    pub output: Option<PathBuf>,
+}
+
+/// Options for `report`.
+#[derive(Args, Debug, Clone)]
+pub struct ReportArgs {
+    /// Employer address to report on
+    #[arg(long)]
+    pub employer: String,
+    /// First day included, as YYYY-MM-DD (UTC) or Unix seconds
+    #[arg(long)]
+    pub from: String,
+    /// Last day included, as YYYY-MM-DD (UTC) or Unix seconds
+    #[arg(long)]
+    pub to: String,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ReportFormat::Csv)]
+    pub format: ReportFormat,
+    /// PaymentHistory contract ID
+    #[arg(long)]
+    pub history_id: String,
+    /// Payroll contract ID to read the audit trail from
+    #[arg(long)]
+    pub contract_id: Option<String>,
+    /// Directory the report files are written to
+    #[arg(long, default_value = ".")]
+    pub output: PathBuf,
+}
+
+#[derive(Subcommand)]
+pub enum BonusCommands {
+    /// Create a one-time bonus, escrowing its amount from the signer
+    CreateOneTime {
+        #[command(flatten)]
+        terms: BonusTermsArgs,
+        /// Bonus amount, in token units (e.g. "250.50")
         #[arg(long)]
-        token: String,
+        amount: String,
+        /// Earliest claim time, as YYYY-MM-DD (UTC) or Unix seconds
+        #[arg(long)]
+        unlock_at: String,
+    },
+    /// Create a recurring incentive, escrowing every payout from the signer
+    CreateRecurring {
+        #[command(flatten)]
+        terms: BonusTermsArgs,
+        /// Amount per payout, in token units
+        #[arg(long)]
+        amount_per_payout: String,
+        /// Number of payouts
+        #[arg(long)]
+        payouts: u32,
+        /// When the first payout becomes claimable, as YYYY-MM-DD (UTC) or Unix seconds
+        #[arg(long)]
+        start: String,
+        /// Time between payouts: a frequency (weekly, monthly, ...) or seconds
+        #[arg(long)]
+        interval: String,
+    },
+    /// Approve a pending incentive (signer must be its approver)
+    Approve(BonusTarget),
+    /// Reject a pending incentive (signer must be its approver)
+    Reject(BonusTarget),
+    /// Claim the payouts currently available (signer must be the employee)
+    Claim(BonusTarget),
+    /// Cancel a pending or rejected incentive and refund its escrow (signer must be the employer)
+    Cancel(BonusTarget),
+    /// Show an incentive
+    Show(BonusTarget),
+    /// Show an incentive's payout schedule and what is claimable now
+    Preview {
+        #[command(flatten)]
+        target: BonusTarget,
+        /// Preview at this time instead of now, as YYYY-MM-DD (UTC) or Unix seconds
+        #[arg(long)]
+        at: Option<String>,
+    },
+}
+
+/// Incentive and contract shared by the bonus subcommands.
+#[derive(Args, Debug, Clone)]
+pub struct BonusTarget {
+    /// Incentive ID
+    #[arg(long)]
+    pub id: u128,
+    /// BonusSystem contract ID
+    #[arg(long)]
+    pub contract_id: String,
+}
+
+/// Parties and token for `bonus create-one-time|create-recurring`. The
+/// signer is the employer funding the incentive.
+#[derive(Args, Debug, Clone)]
+pub struct BonusTermsArgs {
+    /// BonusSystem contract ID
+    #[arg(long)]
+    pub contract_id: String,
+    /// Employee allowed to claim
+    #[arg(long)]
+    pub employee: String,
+    /// Address that approves or rejects the incentive
+    #[arg(long)]
+    pub approver: String,
+    /// Payout token (defaults to `defaults.token`)
+    #[arg(long)]
+    pub token: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum EscrowCommands {
+    /// Deposit tokens from the signer into an agreement's escrow
+    Fund {
+        #[command(flatten)]
+        target: EscrowTarget,
+        /// Amount to deposit, in token units
+        #[arg(long)]
+        amount: String,
+        /// Employer the agreement belongs to (defaults to the signer)
+        #[arg(long)]
+        employer: Option<String>,
+    },
+    /// Release escrowed tokens to a recipient (signer must be the escrow manager)
+    Release {
+        #[command(flatten)]
+        target: EscrowTarget,
+        /// Recipient address
+        #[arg(long)]
+        to: String,
+        /// Amount to release, in token units
+        #[arg(long)]
+        amount: String,
+    },
+    /// Refund an agreement's remaining balance to its employer (signer must be the escrow manager)
+    Refund(EscrowTarget),
+    /// Show an agreement's escrow balance
+    Balance(EscrowTarget),
+}
+
+/// Agreement and contract shared by the escrow subcommands.
+#[derive(Args, Debug, Clone)]
+pub struct EscrowTarget {
+    /// Agreement ID
+    #[arg(long)]
+    pub agreement_id: u128,
+    /// PayrollEscrow contract ID
+    #[arg(long)]
+    pub contract_id: String,
+}
+
+#[derive(Subcommand)]
+pub enum MetadataCommands {
+    /// Set a metadata entry (signer must be the employer)
+    Set {
+        #[command(flatten)]
+        target: MetadataTarget,
+        /// Entry key (e.g. "erp_id", "cost_center")
+        #[arg(long)]
+        key: String,
+        /// Entry value
+        #[arg(long)]
+        value: String,
+    },
+    /// Remove a metadata entry (signer must be the employer)
+    Remove {
+        #[command(flatten)]
+        target: MetadataTarget,
+        /// Entry key
+        #[arg(long)]
+        key: String,
+    },
+    /// Show an employer's or employee's metadata
+    Show {
+        #[command(flatten)]
+        target: MetadataTarget,
+        /// Employer address
+        #[arg(long)]
+        employer: String,
+    },
+}
+
+/// Subject and contract shared by the metadata subcommands.
+#[derive(Args, Debug, Clone)]
+pub struct MetadataTarget {
+    /// Employee address; omit for the employer's own metadata
+    #[arg(long)]
+    pub employee: Option<String>,
+    /// Contract ID
+    #[arg(long)]
+    pub contract_id: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum KeeperCommands {
+    /// Run continuously, executing due scheduler jobs as they come up
+    Run(KeeperRunArgs),
+}
+
+/// Options for `keeper run`.
+#[derive(Args, Debug, Clone)]
+pub struct KeeperRunArgs {
+    /// PaymentScheduler contract ID
+    #[arg(long)]
+    pub scheduler_id: String,
+    /// Only trigger a run when this employer has due jobs
+    #[arg(long)]
+    pub employer: Option<String>,
+    /// Seconds between polls
+    #[arg(long, default_value_t = 60)]
+    pub interval_secs: u64,
+    /// Maximum jobs executed per `process_due_payments` transaction
+    #[arg(long, default_value_t = 20)]
+    pub max_jobs: u32,
+    /// Concurrent job lookups when filtering by employer
+    #[arg(long, default_value_t = 4)]
+    pub concurrency: usize,
+    /// Report due jobs without submitting transactions
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Stop after this many polls instead of running until interrupted
+    #[arg(long)]
+    pub max_ticks: Option<u64>,
+}
+
+#[derive(Subcommand)]
+pub enum ScheduleCommands {
+    /// Create a recurring or one-time payment schedule with the signer as employer
+    Create {
+        #[command(flatten)]
+        scheduler: SchedulerTarget,
+        /// Address receiving each payment
         #[arg(long)]
         recipient: String,
+        /// Amount per payment, in token units
         #[arg(long)]
-        amount: i128,
+        amount: String,
+        /// Token contract [default: defaults.token]
+        #[arg(long)]
+        token: Option<String>,
+        /// Time between payments: a frequency (weekly, monthly, ...) or seconds.
+        /// Omit for a one-time payment
+        #[arg(long)]
+        interval: Option<String>,
+        /// First execution time, as YYYY-MM-DD (UTC) or Unix seconds [default: now]
+        #[arg(long)]
+        start: Option<String>,
+        /// Stop after this many payments [default: unlimited]
+        #[arg(long)]
+        max_executions: Option<u32>,
+        /// Failed attempts allowed before the schedule is marked Failed
+        #[arg(long, default_value_t = 3)]
+        max_retries: u32,
     },
-    /// Webhook management commands
-    Webhook {
-        #[command(subcommand)]
-        command: WebhookCommands,
+    /// List schedules
+    List {
+        #[command(flatten)]
+        scheduler: SchedulerTarget,
+        /// Only schedules of this employer
+        #[arg(long)]
+        employer: Option<String>,
+        /// Only schedules with this status (Active, Paused, Failed, Completed, Cancelled)
+        #[arg(long)]
+        status: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+    /// Pause, resume or cancel a schedule (signer must be its employer)
+    Update {
+        #[command(flatten)]
+        scheduler: SchedulerTarget,
+        /// Schedule ID
+        #[arg(long)]
+        id: u128,
+        #[arg(value_enum)]
+        action: ScheduleAction,
+    },
+    /// Execute due schedules, one per transaction or in batches with --all
+    Execute(ScheduleExecuteArgs),
+    /// List active schedules whose next execution time has passed
+    Due {
+        #[command(flatten)]
+        scheduler: SchedulerTarget,
+        /// Employers to include; repeat for several [default: defaults.employers, or all]
+        #[arg(long)]
+        employer: Vec<String>,
     },
 }
 
+/// PaymentScheduler contract and how fast to read it.
+#[derive(Args, Debug, Clone)]
+pub struct SchedulerTarget {
+    /// PaymentScheduler contract ID
+    #[arg(long)]
+    pub scheduler_id: String,
+    /// Concurrent schedule lookups
+    #[arg(long, default_value_t = 4)]
+    pub concurrency: usize,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleAction {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+/// Options for `schedule execute`.
+#[derive(Args, Debug, Clone)]
+pub struct ScheduleExecuteArgs {
+    #[command(flatten)]
+    pub scheduler: SchedulerTarget,
+    /// Execute every due schedule in batches instead of one per transaction
+    #[arg(long)]
+    pub all: bool,
+    /// Schedules per `process_due_payments` transaction with --all
+    #[arg(long, default_value_t = 20)]
+    pub max_jobs: u32,
+    /// Pause between transactions, to stay under the RPC provider's rate limit
+    #[arg(long, default_value_t = 1000)]
+    pub delay_ms: u64,
+}
+
+/// Options for `upcoming`.
+#[derive(Args, Debug, Clone)]
+pub struct UpcomingArgs {
+    #[command(flatten)]
+    pub scheduler: SchedulerTarget,
+    /// Employer whose payouts to list
+    #[arg(long)]
+    pub employer: String,
+    /// How far ahead to look, e.g. 7d, 12h or seconds
+    #[arg(long, default_value = "7d")]
+    pub within: String,
+    /// Post a summary to notifications.webhook_url
+    #[arg(long)]
+    pub notify: bool,
+}
+
+/// Options for `calendar`.
+#[derive(Args, Debug, Clone)]
+pub struct CalendarArgs {
+    /// Employer whose payouts to project
+    #[arg(long)]
+    pub employer: String,
+    /// First day included, as YYYY-MM-DD (UTC) or Unix seconds
+    #[arg(long)]
+    pub from: String,
+    /// Last day included, as YYYY-MM-DD (UTC) or Unix seconds
+    #[arg(long)]
+    pub to: String,
+    /// Payroll contract ID
+    #[arg(long)]
+    pub contract_id: Option<String>,
+    /// Also write the payouts to this iCalendar (.ics) file
+    #[arg(long)]
+    pub ical: Option<PathBuf>,
+}
+
+/// Options for `pay --employees <CSV>`.
+#[derive(Args, Debug, Clone)]
+pub struct BulkPayArgs {
+    /// CSV file with one `address,amount` row per employee
+    #[arg(long)]
+    pub employees: PathBuf,
+    /// Token contract to pay in (defaults to `defaults.token` in the config)
+    #[arg(long)]
+    pub token: Option<String>,
+    /// Extra attempts for a payment that fails to submit
+    #[arg(long, default_value_t = 2)]
+    pub retries: u32,
+    /// Delay before the first retry, doubled on each further attempt
+    #[arg(long, default_value_t = 1000)]
+    pub retry_delay_ms: u64,
+}
+
+#[derive(Subcommand)]
+pub enum EmployeeCommands {
+    /// Create a payroll agreement, with the signer as employer, that pays one employee
+    Create {
+        #[command(flatten)]
+        payroll: EmployeePayrollArgs,
+    },
+    /// Pause payments on an employee's payroll agreement
+    Pause {
+        #[command(flatten)]
+        target: EmployeeAgreementTarget,
+    },
+    /// Resume payments on a paused payroll agreement
+    Resume {
+        #[command(flatten)]
+        target: EmployeeAgreementTarget,
+    },
+    /// Cancel an employee's payroll agreement; earned pay stays claimable during the grace period
+    Cancel {
+        #[command(flatten)]
+        target: EmployeeAgreementTarget,
+    },
+    /// Show an employee's payroll positions
+    Show {
+        #[command(flatten)]
+        target: EmployeeTarget,
+    },
+}
+
+/// Employee address and contract for `employee show`.
+#[derive(Args, Debug, Clone)]
+pub struct EmployeeTarget {
+    /// Employee address
+    #[arg(long)]
+    pub employee: String,
+    /// Only show the position on this agreement
+    #[arg(long)]
+    pub agreement_id: Option<u128>,
+    /// Contract ID
+    #[arg(long)]
+    pub contract_id: Option<String>,
+}
+
+/// Agreement and contract shared by `employee pause|resume|cancel`.
+#[derive(Args, Debug, Clone)]
+pub struct EmployeeAgreementTarget {
+    /// Agreement ID, as printed by `employee create`
+    #[arg(long)]
+    pub agreement_id: u128,
+    /// Contract ID
+    #[arg(long)]
+    pub contract_id: Option<String>,
+}
+
+/// Payroll terms for `employee create`.
+///
+/// Values given as flags take precedence over values read from `--file`.
+#[derive(Args, Debug, Clone)]
+pub struct EmployeePayrollArgs {
+    /// Employee address
+    #[arg(long)]
+    pub employee: String,
+    /// Contract ID
+    #[arg(long)]
+    pub contract_id: Option<String>,
+    /// Salary per period, in token units (e.g. "1500.50")
+    #[arg(long)]
+    pub amount: Option<String>,
+    /// Token contract address (defaults to `defaults.token` in the config)
+    #[arg(long)]
+    pub token: Option<String>,
+    /// How long earned pay stays claimable after cancellation (e.g. "7d") [default: 0]
+    #[arg(long)]
+    pub grace_period: Option<String>,
+    /// TOML file providing `amount`, `token` and/or `grace_period`
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+    /// Leave the agreement in Created status instead of activating it
+    #[arg(long)]
+    pub no_activate: bool,
+}
+
+/// Payroll terms as read from an `employee create --file` TOML file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EmployeePayrollFile {
+    pub amount: Option<String>,
+    pub token: Option<String>,
+    pub grace_period: Option<String>,
+}
+
 #[derive(Subcommand)]
 pub enum WebhookCommands {
     /// Register a new webhook
@@ -152,6 +946,15 @@ pub enum WebhookCommands {
         #[arg(long)]
         contract_id: Option<String>,
     },
+    /// Show an owner's webhook quotas and usage counters
+    Usage {
+        /// Owner address
+        #[arg(long)]
+        owner: String,
+        /// Contract ID
+        #[arg(long)]
+        contract_id: Option<String>,
+    },
     /// Test webhook delivery
     Test {
         /// Webhook ID
@@ -164,6 +967,72 @@ pub enum WebhookCommands {
         #[arg(long)]
         contract_id: Option<String>,
     },
+    /// Deliver contract events to registered webhooks
+    Relay(WebhookRelayArgs),
+}
+
+/// Where `emergency-withdraw` records every submitted withdrawal.
+pub const DEFAULT_EMERGENCY_AUDIT_LOG: &str = "~/.stellopay/emergency_audit.jsonl";
+
+/// Options for `emergency-withdraw`.
+#[derive(Args, Debug, Clone)]
+pub struct EmergencyWithdrawArgs {
+    /// Contract ID [default: contract.default_contract_id]
+    #[arg(long)]
+    pub contract_id: Option<String>,
+    /// Token to withdraw
+    #[arg(long)]
+    pub token: String,
+    /// Account receiving the funds
+    #[arg(long)]
+    pub recipient: String,
+    /// Amount in the token's smallest unit
+    #[arg(long)]
+    pub amount: i128,
+    /// Why the withdrawal is needed; recorded in the audit log
+    #[arg(long)]
+    pub reason: String,
+    /// The recipient address again, for runs without a terminal to type it in
+    #[arg(long)]
+    pub confirm_recipient: Option<String>,
+    /// File the audit entry is appended to
+    #[arg(long, default_value = DEFAULT_EMERGENCY_AUDIT_LOG)]
+    pub audit_log: PathBuf,
+}
+
+/// Where `webhook register` keeps signing secrets for `webhook relay`.
+pub const DEFAULT_WEBHOOK_SECRETS: &str = "~/.stellopay/webhook_secrets.json";
+
+/// Options for `webhook relay`.
+#[derive(Args, Debug, Clone)]
+pub struct WebhookRelayArgs {
+    /// Owner whose registered webhooks receive deliveries
+    #[arg(long)]
+    pub owner: String,
+    /// Contract ID whose events are relayed
+    #[arg(long)]
+    pub contract_id: Option<String>,
+    /// JSON file mapping webhook IDs to signing secrets
+    #[arg(long, default_value = DEFAULT_WEBHOOK_SECRETS)]
+    pub secrets: PathBuf,
+    /// Ledger to start reading events from (defaults to the latest ledger)
+    #[arg(long)]
+    pub start_ledger: Option<u32>,
+    /// Seconds between event polls
+    #[arg(long, default_value_t = 10)]
+    pub interval_secs: u64,
+    /// Maximum events read per poll
+    #[arg(long, default_value_t = 100)]
+    pub max_events: u32,
+    /// HTTP timeout for each delivery attempt, in seconds
+    #[arg(long, default_value_t = 10)]
+    pub timeout_secs: u64,
+    /// Deliver without reporting results back to the contract
+    #[arg(long)]
+    pub no_report: bool,
+    /// Stop after this many polls (runs until interrupted by default)
+    #[arg(long)]
+    pub max_ticks: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -172,6 +1041,16 @@ pub struct Config {
     pub contract: ContractConfig,
     pub auth: AuthConfig,
     pub defaults: DefaultsConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub devnet: DevnetConfig,
+    /// Set from `--dry-run`; never read from the config file.
+    #[serde(skip)]
+    pub dry_run: bool,
+    /// Set from `--yes`; never read from the config file.
+    #[serde(skip)]
+    pub assume_yes: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -185,27 +1064,43 @@ pub struct ContractConfig {
     pub default_contract_id: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct AuthConfig {
+    /// Plaintext secret key. Deprecated: use `key_name` or `signer_command`.
     pub secret_key: Option<String>,
+    /// Name of the keystore key used for signing.
+    pub key_name: Option<String>,
+    /// External signer command, see [`signer`] for the protocol.
+    pub signer_command: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DefaultsConfig {
     pub token: Option<String>,
     pub frequency: String,
+    /// Total fee, in XLM, above which transactions must be confirmed
+    /// interactively or with `--yes`.
+    #[serde(default)]
+    pub fee_confirm_threshold: Option<String>,
+    /// Employers whose schedules `schedule due` lists by default.
+    #[serde(default)]
+    pub employers: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PayrollInfo {
-    pub employee: String,
-    pub employer: String,
-    pub token: String,
-    pub amount: i128,
-    pub frequency: u64,
-    pub next_payment: u64,
-    pub last_payment: u64,
-    pub active: bool,
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    /// Slack incoming webhook or other URL that `upcoming --notify` posts to.
+    pub webhook_url: Option<String>,
+}
+
+/// A local network that `devnet up` uses instead of starting a container,
+/// such as a quickstart container run by docker compose.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DevnetConfig {
+    pub rpc_url: Option<String>,
+    pub friendbot_url: Option<String>,
+    /// Defaults to the standalone network passphrase.
+    pub network_passphrase: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -227,6 +1122,18 @@ pub struct ContractStatus {
     pub active_payrolls: u32,
     pub total_locked_value: HashMap<String, i128>,
     pub last_activity: u64,
+    pub circuit_breakers: Vec<CircuitBreakerState>,
+}
+
+/// State of a contract-wide switch that halts payments when tripped.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CircuitBreakerState {
+    pub name: String,
+    pub tripped: bool,
+    pub tripped_at: Option<u64>,
+    pub tripped_by: Option<String>,
+    pub resume_at: Option<u64>,
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -268,9 +1175,97 @@ pub enum Error {
     InvalidAddress,
     #[error("Missing secret key")]
     MissingSecretKey,
+    #[error("Signer is not the contract owner")]
+    NotAdmin,
+    #[error("Contract is emergency paused")]
+    ContractPaused,
+    #[error("Dry run: transaction was not submitted")]
+    DryRun,
+    /// No usable signing key, or the signer may not perform the action.
+    #[error(transparent)]
+    Auth(anyhow::Error),
+    /// Simulation was rejected for a reason other than a contract error.
+    #[error(transparent)]
+    Simulation(anyhow::Error),
+    /// The contract returned an error or the transaction failed on-chain.
+    #[error(transparent)]
+    Contract(anyhow::Error),
+    /// The RPC node could not be reached or did not answer usefully.
+    #[error(transparent)]
+    Network(anyhow::Error),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
+
+impl Error {
+    /// True when `error` is, or wraps, [`Error::DryRun`].
+    pub fn is_dry_run(error: &anyhow::Error) -> bool {
+        match error.downcast_ref::<Error>() {
+            Some(Error::DryRun) => true,
+            Some(Error::Other(inner)) => Error::is_dry_run(inner),
+            _ => false,
+        }
+    }
+
+    /// Classifies `error` for the exit code and `--output json`.
+    pub fn class(error: &anyhow::Error) -> ErrorClass {
+        match error.downcast_ref::<Error>() {
+            Some(Error::Other(inner)) => Error::class(inner),
+            Some(Error::ZeroAmount | Error::MaximumAmount | Error::InvalidAddress) => {
+                ErrorClass::InvalidInput
+            }
+            Some(Error::MissingSecretKey | Error::NotAdmin | Error::Auth(_)) => ErrorClass::Auth,
+            Some(Error::Simulation(_)) => ErrorClass::Simulation,
+            Some(Error::ContractPaused | Error::Contract(_)) => ErrorClass::Contract,
+            Some(Error::Network(_)) => ErrorClass::Network,
+            Some(Error::DryRun) => ErrorClass::General,
+            // Direct HTTP calls (notifications, WASM downloads) surface reqwest errors
+            None if error.chain().any(|cause| cause.is::<reqwest::Error>()) => ErrorClass::Network,
+            None => ErrorClass::General,
+        }
+    }
+}
+
+/// Error classes, each with a stable exit code and `error.code` string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Any other failure (exit code 1).
+    General,
+    /// Invalid arguments or input (exit code 2, as for argument parsing errors).
+    InvalidInput,
+    /// Signing key missing, locked or not authorized (exit code 3).
+    Auth,
+    /// Transaction simulation failed (exit code 4).
+    Simulation,
+    /// Contract error or failed transaction (exit code 5).
+    Contract,
+    /// RPC node unreachable, erroring or not confirming (exit code 6).
+    Network,
+}
+
+impl ErrorClass {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorClass::General => 1,
+            ErrorClass::InvalidInput => 2,
+            ErrorClass::Auth => 3,
+            ErrorClass::Simulation => 4,
+            ErrorClass::Contract => 5,
+            ErrorClass::Network => 6,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorClass::General => "error",
+            ErrorClass::InvalidInput => "invalid_input",
+            ErrorClass::Auth => "auth_failure",
+            ErrorClass::Simulation => "simulation_failure",
+            ErrorClass::Contract => "contract_error",
+            ErrorClass::Network => "network_error",
+        }
+    }
+}
 // Helper functions for frequency conversion
 pub fn frequency_to_seconds(frequency: &str) -> Result<u64, String> {
     match frequency.to_lowercase().as_str() {
@@ -304,31 +1299,107 @@ impl Default for Config {
             contract: ContractConfig {
                 default_contract_id: None,
             },
-            auth: AuthConfig { secret_key: None },
+            auth: AuthConfig::default(),
             defaults: DefaultsConfig {
                 token: None,
                 frequency: "monthly".to_string(),
+                fee_confirm_threshold: None,
+                employers: Vec::new(),
             },
+            notifications: NotificationsConfig::default(),
+            devnet: DevnetConfig::default(),
+            dry_run: false,
+            assume_yes: false,
         }
     }
 }
-//admin and pause checks
-pub fn require_admin(_context: &str) -> Result<(), Error> {
-    //dummy implementation
+/// Fails unless `signer` is the contract owner recorded in the contract's
+/// persistent storage.
+pub async fn require_admin(
+    rpc: &SorobanRpcClient,
+    contract_id: &str,
+    signer: &TransactionSigner,
+) -> Result<(), Error> {
+    let signer = signer.address();
+    let owner = rpc
+        .get_contract_data(
+            contract_id,
+            rpc::enum_key("Owner")?,
+            stellar_xdr::curr::ContractDataDurability::Persistent,
+        )
+        .await?
+        .as_ref()
+        .and_then(rpc::scval_to_address)
+        .ok_or_else(|| anyhow::anyhow!("Contract {} has no owner set", contract_id))?;
+
+    if owner != signer {
+        return Err(Error::NotAdmin);
+    }
     Ok(())
 }
-pub fn require_not_paused(_context: &str) -> Result<(), Error> {
-    //dummy implementation
-    Ok(())
+
+/// Fails if the contract reports an active emergency pause.
+pub async fn require_not_paused(rpc: &SorobanRpcClient, contract_id: &str) -> Result<(), Error> {
+    let paused = rpc.view(contract_id, "is_emergency_paused", vec![]).await?;
+    match bool::try_from(paused) {
+        Ok(false) => Ok(()),
+        Ok(true) => Err(Error::ContractPaused),
+        Err(_) => Err(anyhow::anyhow!("is_emergency_paused returned a non-boolean value").into()),
+    }
+}
+
+/// Client for a Stellar Asset Contract (or any SEP-41 token).
+pub struct TokenClient<'a> {
+    rpc: &'a SorobanRpcClient,
+    token_address: String,
 }
-//token client
-pub struct TokenClient;
-impl TokenClient {
-    pub fn new(_rpc_url: &str, _token_address: &str) -> Self {
-        TokenClient
+
+impl<'a> TokenClient<'a> {
+    pub fn new(rpc: &'a SorobanRpcClient, token_address: &str) -> Self {
+        TokenClient {
+            rpc,
+            token_address: token_address.to_string(),
+        }
+    }
+
+    pub async fn balance(&self, id: &str) -> Result<i128, Error> {
+        let balance = self
+            .rpc
+            .view(&self.token_address, "balance", vec![rpc::address_arg(id)?])
+            .await?;
+        i128::try_from(balance).map_err(|_| anyhow::anyhow!("Token balance is not an i128").into())
     }
-    pub fn transfer(&self, _to: &str, _amount: i128) -> Result<(), Error> {
-        //dummy implementation
-        Ok(())
+
+    /// Decimal places used to display the token's amounts.
+    pub async fn decimals(&self) -> Result<u32, Error> {
+        let decimals = self
+            .rpc
+            .view(&self.token_address, "decimals", vec![])
+            .await?;
+        u32::try_from(decimals).map_err(|_| anyhow::anyhow!("Token decimals is not a u32").into())
+    }
+
+    /// Transfers `amount` from the signer's account to `to`.
+    pub async fn transfer(
+        &self,
+        signer: &TransactionSigner,
+        to: &str,
+        amount: i128,
+    ) -> Result<TransactionOutcome, Error> {
+        let from = signer.address();
+        let outcome = self
+            .rpc
+            .invoke(
+                signer,
+                &self.token_address,
+                "transfer",
+                vec![
+                    rpc::address_arg(&from)?,
+                    rpc::address_arg(to)?,
+                    amount.into(),
+                ],
+            )
+            .await?;
+        Ok(outcome)
     }
 }
@@ -4,11 +4,13 @@ use std::process;
 use anyhow::anyhow;
 use stellopay_cli::commands::*;
 use stellopay_cli::config::*;
-use stellopay_cli::{Cli, Commands, Config, Error, WebhookCommands};
+use stellopay_cli::output;
+use stellopay_cli::{Cli, Commands, Error, Network};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    output::set_mode(cli.output);
 
     // Set up logging
     if cli.verbose {
@@ -18,61 +20,70 @@ async fn main() -> anyhow::Result<()> {
     }
 
     // Load configuration
-    let config = match load_config(&cli.config).await {
+    let mut config = match load_config(&cli.config).await {
         Ok(config) => config,
-        Err(e) => {
-            eprintln!("Error loading config: {}", e);
-            process::exit(1);
-        }
+        Err(e) => finish(Err(anyhow!("Could not load config: {}", e))),
     };
 
+    // A --network flag overrides the RPC endpoint and passphrase from the config
+    if let Some(network) = &cli.network {
+        match Network::from_name(network) {
+            Ok(network) => config.network = network.into(),
+            Err(e) => finish(Err(e)),
+        }
+    }
+
+    config.dry_run = cli.dry_run;
+    config.assume_yes = cli.yes;
+
     // Execute command
     let result = match cli.command {
-        Commands::Deploy {
-            network,
-            owner,
-            wasm,
-        } => deploy_command(network, owner, wasm, &config).await,
-        Commands::Info { contract_id } => info_command(contract_id, &config).await,
+        Commands::Deploy { args } => deploy_command(args, &config, &cli.config).await,
+        Commands::DeploySuite { args } => deploy_suite_command(args, &config, &cli.config).await,
+        Commands::Info {
+            contract_id,
+            format,
+        } => info_command(contract_id, format, &config).await,
         Commands::Status => status_command(&config).await,
         Commands::Webhook { command } => webhook_command(command, &config).await,
-        Commands::EmergencyWithdraw {
-            contract_id,
-            token,
-            recipient,
-            amount,
-        } => {
-            // //Loading config
-            let dummy_env = "cli-context";
-            // let config=load_config(&cli.config)?;
-            // //resolving contract ID from arg or config
-            let contract_id_str = contract_id
-                .as_deref()
-                .ok_or_else(|| anyhow!("Missing contract ID"))?;
-
-            //calling logic function
-            emergency_withdraw(
-                &config,
-                &dummy_env,
-                contract_id_str,
-                &token,
-                &recipient,
-                amount,
-                cli.verbose,
-            )
-            .await?;
-            Ok(())
+        Commands::Employee { command } => employee_command(command, &config).await,
+        Commands::Pay { args } => pay_command(args, &config).await,
+        Commands::Keeper { command } => keeper_command(command, &config).await,
+        Commands::Schedule { command } => schedule_command(command, &config).await,
+        Commands::Upcoming { args } => upcoming_command(args, &config).await,
+        Commands::Calendar { args } => calendar_command(args, &config).await,
+        Commands::Report { args } => report_command(args, &config).await,
+        Commands::Keys { command } => keys_command(command, &config, &cli.config).await,
+        Commands::Bonus { command } => bonus_command(command, &config).await,
+        Commands::Escrow { command } => escrow_command(command, &config, cli.yes).await,
+        Commands::Metadata { command } => metadata_command(command, &config).await,
+        Commands::History { args } => history_command(args, &config).await,
+        Commands::Template { command } => template_command(command, &config).await,
+        Commands::Devnet { command } => devnet_command(command, &config).await,
+        Commands::EmergencyWithdraw { args } => {
+            emergency_withdraw_command(args, &config, cli.verbose).await
         }
     };
 
-    match result {
-        Ok(()) => {}
-        Err(e) => {
+    finish(result)
+}
+
+/// Reports the outcome and exits: with `--output json` the JSON document is
+/// printed to stdout, otherwise a failure is printed to stderr. The exit code
+/// comes from the error's class (see `ErrorClass`).
+fn finish(result: anyhow::Result<()>) -> ! {
+    if output::is_json() {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&output::document(&result)).unwrap_or_default()
+        );
+    } else if let Err(e) = &result {
+        // The preview has been printed; stopping there is the expected outcome
+        if !Error::is_dry_run(e) {
             eprintln!("Error: {}", e);
-            process::exit(1);
         }
     }
-    Ok(())
+    process::exit(output::exit_code(&result))
 }
 
 pub struct DeployArgs {
@@ -92,11 +103,6 @@ pub struct PayArgs {
     pub employer: Option<String>,
 }
 
-pub struct BulkPayArgs {
-    pub employees: std::path::PathBuf,
-    pub limit: usize,
-}
-
 pub struct InfoArgs {
     pub detailed: bool,
 }
@@ -105,19 +111,3 @@ pub struct StreamArgs {
     pub events: Vec<String>,
     pub format: String,
 }
-
-fn get_rpc_url_for_network(network: &str) -> String {
-    match network {
-        "testnet" => "https://soroban-testnet.stellar.org:443".to_string(),
-        "mainnet" => "https://soroban-mainnet.stellar.org:443".to_string(),
-        _ => panic!("Unknown network: {}", network),
-    }
-}
-
-fn get_network_passphrase(network: &str) -> String {
-    match network {
-        "testnet" => "Test SDF Network ; September 2015".to_string(),
-        "mainnet" => "Public Global Stellar Network ; September 2015".to_string(),
-        _ => panic!("Unknown network: {}", network),
-    }
-}
@@ -0,0 +1,164 @@
+//! Machine-readable output for `--output json`.
+//!
+//! In JSON mode a command writes exactly one JSON document to stdout when it
+//! finishes:
+//!
+//! ```json
+//! {
+//!   "ok": true,
+//!   "result": { ... },
+//!   "transactions": [{ "hash": "...", "ledger": 123, "return_value": ..., "events": [...] }],
+//!   "error": null
+//! }
+//! ```
+//!
+//! `result` is the command's data where it has any (the same data its table
+//! shows), `transactions` lists every transaction submitted along the way,
+//! and on failure `error` holds the error class, exit code and message.
+//! Human-readable progress output written with [`outln!`](crate::outln) goes
+//! to stderr instead of stdout while JSON mode is on.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::rpc::{scval_to_json, TransactionOutcome};
+use crate::Error;
+
+static JSON_MODE: AtomicBool = AtomicBool::new(false);
+static RESULT: Mutex<Option<Value>> = Mutex::new(None);
+static TRANSACTIONS: Mutex<Vec<Value>> = Mutex::new(Vec::new());
+
+/// Format selected by the global `--output` flag.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Human-readable text and tables
+    #[default]
+    Text,
+    /// One JSON document on stdout, progress on stderr
+    Json,
+}
+
+pub fn set_mode(mode: OutputMode) {
+    JSON_MODE.store(mode == OutputMode::Json, Ordering::Relaxed);
+}
+
+pub fn is_json() -> bool {
+    JSON_MODE.load(Ordering::Relaxed)
+}
+
+/// `println!` for human-readable output: stdout normally, stderr under
+/// `--output json` so that stdout carries only the JSON document.
+#[macro_export]
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        if $crate::output::is_json() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+/// `print!` counterpart of [`outln!`](crate::outln).
+#[macro_export]
+macro_rules! out {
+    ($($arg:tt)*) => {
+        if $crate::output::is_json() {
+            eprint!($($arg)*)
+        } else {
+            print!($($arg)*)
+        }
+    };
+}
+
+/// Sets the command's `result` in JSON mode.
+pub fn set_result<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    if is_json() {
+        *RESULT.lock().unwrap_or_else(|e| e.into_inner()) = Some(serde_json::to_value(value)?);
+    }
+    Ok(())
+}
+
+/// Prints `value` as pretty JSON, or makes it the `result` in JSON mode.
+/// Used by commands that offer `--format json` of their own.
+pub fn emit_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    if is_json() {
+        set_result(value)
+    } else {
+        println!("{}", serde_json::to_string_pretty(value)?);
+        Ok(())
+    }
+}
+
+/// Prints exported file content to stdout, or makes it the `result` string
+/// in JSON mode.
+pub fn emit_text(content: &str) -> anyhow::Result<()> {
+    if is_json() {
+        set_result(&content)
+    } else {
+        print!("{}", content);
+        Ok(())
+    }
+}
+
+/// Records a confirmed transaction for the `transactions` list.
+pub fn record_transaction(outcome: &TransactionOutcome) {
+    if !is_json() {
+        return;
+    }
+    let events: Vec<Value> = outcome
+        .events
+        .iter()
+        .map(|event| {
+            json!({
+                "contract_id": event.contract_id,
+                "topics": event.topics.iter().map(scval_to_json).collect::<Vec<_>>(),
+                "data": scval_to_json(&event.data),
+            })
+        })
+        .collect();
+    TRANSACTIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(json!({
+            "hash": outcome.hash,
+            "ledger": outcome.ledger,
+            "return_value": outcome.return_value.as_ref().map(scval_to_json),
+            "events": events,
+        }));
+}
+
+/// Builds the final JSON document for a finished command.
+pub fn document(result: &anyhow::Result<()>) -> Value {
+    let error = match result {
+        Ok(()) => Value::Null,
+        Err(e) if Error::is_dry_run(e) => Value::Null,
+        Err(e) => {
+            let class = Error::class(e);
+            json!({
+                "code": class.code(),
+                "exit_code": class.exit_code(),
+                "message": format!("{:#}", e),
+            })
+        }
+    };
+    json!({
+        "ok": error.is_null(),
+        "dry_run": result.as_ref().is_err_and(Error::is_dry_run),
+        "result": RESULT.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        "transactions": TRANSACTIONS.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        "error": error,
+    })
+}
+
+/// Exit code for a finished command: 0 on success (including a dry run),
+/// otherwise the code of the error's [`ErrorClass`](crate::ErrorClass).
+pub fn exit_code(result: &anyhow::Result<()>) -> i32 {
+    match result {
+        Ok(()) => 0,
+        Err(e) if Error::is_dry_run(e) => 0,
+        Err(e) => Error::class(e).exit_code(),
+    }
+}
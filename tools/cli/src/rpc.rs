@@ -0,0 +1,1133 @@
+//! Soroban JSON-RPC client shared by all commands.
+//!
+//! Contract calls are built locally as `InvokeHostFunction` transactions,
+//! simulated to obtain the footprint, authorization entries and resource fee,
+//! signed by the configured [`TransactionSigner`], submitted with `sendTransaction`,
+//! and polled with `getTransaction` until they are included in a ledger.
+//! Read-only calls stop after simulation and never need a secret key.
+//!
+//! Between simulation and signing, [`SubmitPolicy`] decides whether the
+//! transaction is only previewed (`--dry-run`), needs confirmation because
+//! its fee is above `defaults.fee_confirm_threshold`, or goes straight out.
+
+use anyhow::{anyhow, Context, Result};
+use ed25519_dalek::SigningKey;
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use stellar_xdr::curr::{
+    AccountId, Asset, ContractDataDurability, ContractEventBody, ContractExecutable,
+    ContractIdPreimage, ContractIdPreimageFromAddress, CreateContractArgsV2, Hash, HashIdPreimage,
+    HashIdPreimageContractId, HostFunction, InvokeContractArgs, InvokeHostFunctionOp,
+    LedgerEntryData, LedgerKey, LedgerKeyAccount, LedgerKeyContractData, Limits, Memo,
+    MuxedAccount, Operation, OperationBody, Preconditions, PublicKey, ReadXdr, ScAddress, ScMap,
+    ScMapEntry, ScString, ScSymbol, ScVal, ScVec, SequenceNumber, SorobanAuthorizationEntry,
+    SorobanTransactionData, Transaction, TransactionEnvelope, TransactionExt, TransactionMeta,
+    TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
+    TransactionV1Envelope, Uint256, WriteXdr,
+};
+
+use crate::signer::TransactionSigner;
+use crate::utils::{confirm_action, format_amount, parse_amount};
+use crate::{Config, Error, NetworkConfig};
+
+/// Inclusion fee offered for every transaction, in stroops. The resource fee
+/// returned by simulation is added on top.
+pub const BASE_FEE: u32 = 100;
+
+/// Decimal places of XLM amounts (1 XLM = 10^7 stroops).
+pub const XLM_DECIMALS: u32 = 7;
+
+/// Well-known Stellar networks the CLI can target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Testnet,
+    Mainnet,
+    Futurenet,
+    /// A standalone quickstart network on this machine, as started by
+    /// `devnet up`.
+    Local,
+}
+
+impl Network {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "testnet" => Ok(Network::Testnet),
+            "mainnet" | "pubnet" => Ok(Network::Mainnet),
+            "futurenet" => Ok(Network::Futurenet),
+            "local" | "standalone" => Ok(Network::Local),
+            _ => Err(anyhow!(
+                "Unknown network: {} (expected testnet, mainnet, futurenet or local)",
+                name
+            )),
+        }
+    }
+
+    /// Identifies a well-known network from its passphrase.
+    pub fn from_passphrase(passphrase: &str) -> Option<Self> {
+        [
+            Network::Testnet,
+            Network::Mainnet,
+            Network::Futurenet,
+            Network::Local,
+        ]
+        .into_iter()
+        .find(|network| network.passphrase() == passphrase)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Network::Testnet => "testnet",
+            Network::Mainnet => "mainnet",
+            Network::Futurenet => "futurenet",
+            Network::Local => "local",
+        }
+    }
+
+    pub fn rpc_url(&self) -> &'static str {
+        match self {
+            Network::Testnet => "https://soroban-testnet.stellar.org:443",
+            Network::Mainnet => "https://soroban-mainnet.stellar.org:443",
+            Network::Futurenet => "https://rpc-futurenet.stellar.org:443",
+            Network::Local => "http://localhost:8000/soroban/rpc",
+        }
+    }
+
+    pub fn passphrase(&self) -> &'static str {
+        match self {
+            Network::Testnet => "Test SDF Network ; September 2015",
+            Network::Mainnet => "Public Global Stellar Network ; September 2015",
+            Network::Futurenet => "Test SDF Future Network ; October 2022",
+            Network::Local => "Standalone Network ; February 2017",
+        }
+    }
+}
+
+impl From<Network> for NetworkConfig {
+    fn from(network: Network) -> Self {
+        NetworkConfig {
+            rpc_url: network.rpc_url().to_string(),
+            network_passphrase: network.passphrase().to_string(),
+        }
+    }
+}
+
+/// Footprint, authorization and fee information returned by simulation.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub transaction_data: SorobanTransactionData,
+    pub min_resource_fee: i64,
+    pub auth: Vec<SorobanAuthorizationEntry>,
+    pub return_value: Option<ScVal>,
+}
+
+/// What `invoke` does with a simulated transaction before signing it.
+#[derive(Debug, Clone, Default)]
+pub struct SubmitPolicy {
+    /// Print the preview and stop with [`Error::DryRun`] instead of signing.
+    pub dry_run: bool,
+    /// Submit without prompting, even above `confirm_fee_above`.
+    pub assume_yes: bool,
+    /// Total fee, in stroops, above which submission must be confirmed.
+    pub confirm_fee_above: Option<i64>,
+}
+
+impl SubmitPolicy {
+    /// Reads `--dry-run`, `--yes` and `defaults.fee_confirm_threshold`. An
+    /// unparsable threshold is treated as zero, so every transaction is
+    /// confirmed rather than none.
+    pub fn from_config(config: &Config) -> Self {
+        let confirm_fee_above = config.defaults.fee_confirm_threshold.as_deref().map(|xlm| {
+            parse_amount(xlm, XLM_DECIMALS)
+                .ok()
+                .and_then(|stroops| i64::try_from(stroops).ok())
+                .filter(|stroops| *stroops >= 0)
+                .unwrap_or_else(|| {
+                    warn!(
+                        "Invalid defaults.fee_confirm_threshold '{}'; confirming every transaction",
+                        xlm
+                    );
+                    0
+                })
+        });
+        SubmitPolicy {
+            dry_run: config.dry_run,
+            assume_yes: config.assume_yes,
+            confirm_fee_above,
+        }
+    }
+}
+
+/// Decoded result, footprint and fee of a simulated transaction.
+#[derive(Debug, Clone)]
+pub struct TransactionPreview {
+    /// What the transaction does, e.g. `claim on C...`.
+    pub action: String,
+    pub return_value: Option<ScVal>,
+    pub auth_entries: usize,
+    pub read_only_entries: usize,
+    pub read_write_entries: usize,
+    pub instructions: u32,
+    pub read_bytes: u32,
+    pub write_bytes: u32,
+    /// Resource fee from simulation, in stroops.
+    pub resource_fee: i64,
+    /// Inclusion plus resource fee, in stroops.
+    pub total_fee: i64,
+}
+
+impl TransactionPreview {
+    pub fn new(action: String, simulation: &Simulation) -> Self {
+        let resources = &simulation.transaction_data.resources;
+        TransactionPreview {
+            action,
+            return_value: simulation.return_value.clone(),
+            auth_entries: simulation.auth.len(),
+            read_only_entries: resources.footprint.read_only.len(),
+            read_write_entries: resources.footprint.read_write.len(),
+            instructions: resources.instructions,
+            read_bytes: resources.disk_read_bytes,
+            write_bytes: resources.write_bytes,
+            resource_fee: simulation.min_resource_fee,
+            total_fee: i64::from(BASE_FEE) + simulation.min_resource_fee,
+        }
+    }
+}
+
+impl fmt::Display for TransactionPreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = match &self.return_value {
+            Some(value) => scval_to_json(value).to_string(),
+            None => "none".to_string(),
+        };
+        writeln!(f, "Transaction preview: {}", self.action)?;
+        writeln!(f, "  Result:       {}", result)?;
+        writeln!(f, "  Auth entries: {}", self.auth_entries)?;
+        writeln!(
+            f,
+            "  Footprint:    {} read-only, {} read-write ledger entries",
+            self.read_only_entries, self.read_write_entries
+        )?;
+        writeln!(
+            f,
+            "  Resources:    {} instructions, {} bytes read, {} bytes written",
+            self.instructions, self.read_bytes, self.write_bytes
+        )?;
+        write!(
+            f,
+            "  Fee:          {} XLM ({} inclusion + {} resource)",
+            format_amount(self.total_fee.into(), XLM_DECIMALS),
+            format_amount(BASE_FEE.into(), XLM_DECIMALS),
+            format_amount(self.resource_fee.into(), XLM_DECIMALS)
+        )
+    }
+}
+
+/// A submitted transaction that was successfully included in a ledger.
+#[derive(Debug, Clone)]
+pub struct TransactionOutcome {
+    pub hash: String,
+    pub ledger: Option<u32>,
+    pub return_value: Option<ScVal>,
+    /// Contract events emitted by the transaction.
+    pub events: Vec<EmittedEvent>,
+}
+
+/// A contract event decoded from a transaction's result meta.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmittedEvent {
+    pub contract_id: Option<String>,
+    pub topics: Vec<ScVal>,
+    pub data: ScVal,
+}
+
+/// A contract event returned by `getEvents`, with topics and value decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractEvent {
+    /// RPC-assigned event id, unique and ordered across ledgers.
+    pub id: String,
+    pub contract_id: String,
+    pub ledger: u32,
+    pub ledger_closed_at: String,
+    pub transaction_hash: String,
+    pub topics: Vec<ScVal>,
+    pub value: ScVal,
+}
+
+impl ContractEvent {
+    /// First topic as a string; `#[contractevent]` types publish their
+    /// snake_case name there (e.g. `payroll_claimed_event`).
+    pub fn event_type(&self) -> Option<String> {
+        match self.topics.first()? {
+            ScVal::Symbol(symbol) => Some(symbol.to_utf8_string_lossy()),
+            ScVal::String(string) => Some(string.to_utf8_string_lossy()),
+            _ => None,
+        }
+    }
+}
+
+/// One page of `getEvents` results.
+#[derive(Debug, Clone)]
+pub struct EventPage {
+    pub events: Vec<ContractEvent>,
+    /// Cursor to pass to the next `get_events` call.
+    pub cursor: Option<String>,
+    pub latest_ledger: u32,
+}
+
+/// Where a `get_events` call starts reading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventsFrom {
+    Ledger(u32),
+    Cursor(String),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LatestLedgerResponse {
+    sequence: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetEventsResponse {
+    #[serde(default)]
+    events: Vec<EventResult>,
+    latest_ledger: u32,
+    cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventResult {
+    id: String,
+    ledger: u32,
+    #[serde(default)]
+    ledger_closed_at: String,
+    #[serde(default)]
+    contract_id: String,
+    #[serde(default)]
+    tx_hash: String,
+    #[serde(default)]
+    topic: Vec<String>,
+    value: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerEntriesResponse {
+    #[serde(default)]
+    entries: Option<Vec<LedgerEntryResult>>,
+}
+
+#[derive(Deserialize)]
+struct LedgerEntryResult {
+    xdr: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulateResponse {
+    error: Option<String>,
+    transaction_data: Option<String>,
+    min_resource_fee: Option<String>,
+    #[serde(default)]
+    results: Vec<SimulateHostFunctionResult>,
+    restore_preamble: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct SimulateHostFunctionResult {
+    #[serde(default)]
+    auth: Vec<String>,
+    xdr: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SendTransactionResponse {
+    status: String,
+    hash: String,
+    error_result_xdr: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetTransactionResponse {
+    status: String,
+    ledger: Option<u32>,
+    return_value: Option<String>,
+    result_meta_xdr: Option<String>,
+    result_xdr: Option<String>,
+}
+
+pub struct SorobanRpcClient {
+    client: reqwest::Client,
+    rpc_url: String,
+    network_passphrase: String,
+    poll_interval: Duration,
+    max_polls: u32,
+    submit_policy: SubmitPolicy,
+}
+
+impl SorobanRpcClient {
+    pub fn new(rpc_url: &str, network_passphrase: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            rpc_url: rpc_url.to_string(),
+            network_passphrase: network_passphrase.to_string(),
+            poll_interval: Duration::from_secs(1),
+            max_polls: 30,
+            submit_policy: SubmitPolicy::default(),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(&config.network.rpc_url, &config.network.network_passphrase)
+            .with_submit_policy(SubmitPolicy::from_config(config))
+    }
+
+    /// Overrides what `invoke` does between simulation and signing.
+    pub fn with_submit_policy(mut self, policy: SubmitPolicy) -> Self {
+        self.submit_policy = policy;
+        self
+    }
+
+    /// Overrides how often, and how many times, `getTransaction` is polled
+    /// after submission.
+    pub fn with_polling(mut self, interval: Duration, max_polls: u32) -> Self {
+        self.poll_interval = interval;
+        self.max_polls = max_polls;
+        self
+    }
+
+    pub fn network_passphrase(&self) -> &str {
+        &self.network_passphrase
+    }
+
+    async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response = self
+            .client
+            .post(&self.rpc_url)
+            .json(&payload)
+            .send()
+            .await
+            .with_context(|| format!("Soroban RPC {} request failed", method))
+            .map_err(Error::Network)?;
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .with_context(|| format!("Soroban RPC {} returned a non-JSON response", method))
+            .map_err(Error::Network)?;
+
+        if let Some(error) = body.get("error") {
+            return Err(Error::Network(anyhow!("Soroban RPC {} error: {}", method, error)).into());
+        }
+        if !status.is_success() {
+            return Err(Error::Network(anyhow!(
+                "Soroban RPC {} failed with status {}",
+                method,
+                status
+            ))
+            .into());
+        }
+
+        let result = body.get("result").cloned().ok_or_else(|| {
+            Error::Network(anyhow!("Soroban RPC {} response has no result", method))
+        })?;
+        serde_json::from_value(result)
+            .with_context(|| format!("Unexpected Soroban RPC {} result", method))
+    }
+
+    async fn get_ledger_entry(&self, key: LedgerKey) -> Result<Option<LedgerEntryData>> {
+        let response: LedgerEntriesResponse = self
+            .request(
+                "getLedgerEntries",
+                json!({ "keys": [key.to_xdr_base64(Limits::none())?] }),
+            )
+            .await?;
+
+        match response.entries.unwrap_or_default().first() {
+            Some(entry) => Ok(Some(LedgerEntryData::from_xdr_base64(
+                &entry.xdr,
+                Limits::none(),
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the current sequence number of a `G...` account.
+    pub async fn get_account_sequence(&self, account: &str) -> Result<i64> {
+        let account_id = AccountId::from_str(account)
+            .map_err(|e| anyhow!("Invalid account {}: {}", account, e))?;
+        match self
+            .get_ledger_entry(LedgerKey::Account(LedgerKeyAccount { account_id }))
+            .await?
+        {
+            Some(LedgerEntryData::Account(entry)) => Ok(entry.seq_num.0),
+            _ => Err(anyhow!("Account {} not found on the network", account)),
+        }
+    }
+
+    /// Reads a contract storage entry directly from the ledger.
+    pub async fn get_contract_data(
+        &self,
+        contract_id: &str,
+        key: ScVal,
+        durability: ContractDataDurability,
+    ) -> Result<Option<ScVal>> {
+        let ledger_key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: contract_address(contract_id)?,
+            key,
+            durability,
+        });
+        match self.get_ledger_entry(ledger_key).await? {
+            Some(LedgerEntryData::ContractData(entry)) => Ok(Some(entry.val)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the sequence number of the latest ledger known to the node.
+    pub async fn get_latest_ledger(&self) -> Result<u32> {
+        let response: LatestLedgerResponse = self.request("getLatestLedger", json!({})).await?;
+        Ok(response.sequence)
+    }
+
+    /// Reads up to `limit` events emitted by `contract_id`.
+    pub async fn get_events(
+        &self,
+        contract_id: &str,
+        from: &EventsFrom,
+        limit: u32,
+    ) -> Result<EventPage> {
+        let filters = json!([{ "type": "contract", "contractIds": [contract_id] }]);
+        let params = match from {
+            EventsFrom::Ledger(ledger) => json!({
+                "startLedger": ledger,
+                "filters": filters,
+                "pagination": { "limit": limit },
+            }),
+            EventsFrom::Cursor(cursor) => json!({
+                "filters": filters,
+                "pagination": { "cursor": cursor, "limit": limit },
+            }),
+        };
+        let response: GetEventsResponse = self.request("getEvents", params).await?;
+
+        let events = response
+            .events
+            .into_iter()
+            .map(|event| {
+                Ok(ContractEvent {
+                    id: event.id,
+                    contract_id: event.contract_id,
+                    ledger: event.ledger,
+                    ledger_closed_at: event.ledger_closed_at,
+                    transaction_hash: event.tx_hash,
+                    topics: event
+                        .topic
+                        .iter()
+                        .map(|topic| ScVal::from_xdr_base64(topic, Limits::none()))
+                        .collect::<Result<_, _>>()?,
+                    value: ScVal::from_xdr_base64(&event.value, Limits::none())?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Older nodes only return a cursor per event.
+        let cursor = response
+            .cursor
+            .or_else(|| events.last().map(|event| event.id.clone()));
+        Ok(EventPage {
+            events,
+            cursor,
+            latest_ledger: response.latest_ledger,
+        })
+    }
+
+    pub async fn simulate(&self, tx: &Transaction) -> Result<Simulation> {
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: tx.clone(),
+            signatures: Default::default(),
+        });
+        let response: SimulateResponse = self
+            .request(
+                "simulateTransaction",
+                json!({ "transaction": envelope.to_xdr_base64(Limits::none())? }),
+            )
+            .await?;
+
+        if let Some(error) = response.error {
+            let error = anyhow!("Simulation failed: {}", error);
+            // Host errors raised by the contract itself read `Error(Contract, #N)`
+            return Err(if error.to_string().contains("Error(Contract,") {
+                Error::Contract(error)
+            } else {
+                Error::Simulation(error)
+            }
+            .into());
+        }
+        if response.restore_preamble.is_some() {
+            return Err(Error::Simulation(anyhow!(
+                "Simulation requires restoring archived ledger entries first"
+            ))
+            .into());
+        }
+
+        let transaction_data = SorobanTransactionData::from_xdr_base64(
+            response
+                .transaction_data
+                .as_deref()
+                .ok_or_else(|| anyhow!("Simulation returned no transaction data"))?,
+            Limits::none(),
+        )?;
+        let min_resource_fee = response
+            .min_resource_fee
+            .as_deref()
+            .unwrap_or("0")
+            .parse::<i64>()
+            .context("Simulation returned an invalid resource fee")?;
+
+        let mut auth = Vec::new();
+        let mut return_value = None;
+        if let Some(result) = response.results.first() {
+            for entry in &result.auth {
+                auth.push(SorobanAuthorizationEntry::from_xdr_base64(
+                    entry,
+                    Limits::none(),
+                )?);
+            }
+            if let Some(xdr) = &result.xdr {
+                return_value = Some(ScVal::from_xdr_base64(xdr, Limits::none())?);
+            }
+        }
+
+        Ok(Simulation {
+            transaction_data,
+            min_resource_fee,
+            auth,
+            return_value,
+        })
+    }
+
+    /// Simulates a read-only contract call and returns its result.
+    ///
+    /// Simulation does not check the source account, so a zero account is
+    /// used and no secret key is required.
+    pub async fn view(&self, contract_id: &str, function: &str, args: Vec<ScVal>) -> Result<ScVal> {
+        let tx = build_invoke_transaction([0u8; 32], 0, contract_id, function, args)?;
+        self.simulate(&tx)
+            .await?
+            .return_value
+            .ok_or_else(|| anyhow!("Simulation of {} returned no value", function))
+    }
+
+    /// Simulates, signs, submits and waits for a contract invocation.
+    pub async fn invoke(
+        &self,
+        signer: &TransactionSigner,
+        contract_id: &str,
+        function: &str,
+        args: Vec<ScVal>,
+    ) -> Result<TransactionOutcome> {
+        let invoke = invoke_contract_function(contract_id, function, args)?;
+        self.execute(
+            signer,
+            HostFunction::InvokeContract(invoke),
+            format!("{} on {}", function, contract_id),
+        )
+        .await
+    }
+
+    /// Uploads contract code and returns its hash. Uploading code that is
+    /// already on the ledger succeeds and returns the same hash.
+    pub async fn upload_wasm(&self, signer: &TransactionSigner, wasm: &[u8]) -> Result<[u8; 32]> {
+        self.execute(
+            signer,
+            HostFunction::UploadContractWasm(wasm.to_vec().try_into()?),
+            format!("upload contract WASM ({} bytes)", wasm.len()),
+        )
+        .await?;
+        Ok(Sha256::digest(wasm).into())
+    }
+
+    /// Creates a contract instance of uploaded code, deployed by the signer
+    /// with `salt`, and returns its `C...` ID.
+    pub async fn create_contract(
+        &self,
+        signer: &TransactionSigner,
+        wasm_hash: [u8; 32],
+        salt: [u8; 32],
+    ) -> Result<String> {
+        let preimage = ContractIdPreimage::Address(ContractIdPreimageFromAddress {
+            address: ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+                signer.public_key(),
+            )))),
+            salt: Uint256(salt),
+        });
+        let contract_id = contract_id_from_preimage(&self.network_passphrase, &preimage)?;
+        let outcome = self
+            .execute(
+                signer,
+                HostFunction::CreateContractV2(CreateContractArgsV2 {
+                    contract_id_preimage: preimage,
+                    executable: ContractExecutable::Wasm(Hash(wasm_hash)),
+                    constructor_args: Default::default(),
+                }),
+                format!("create contract from WASM {}", hex::encode(wasm_hash)),
+            )
+            .await?;
+
+        match outcome.return_value.as_ref().and_then(scval_to_address) {
+            Some(created) if created != contract_id => Err(anyhow!(
+                "Created contract {} does not match the expected ID {}",
+                created,
+                contract_id
+            )),
+            _ => Ok(contract_id),
+        }
+    }
+
+    /// Deploys the Stellar Asset Contract of `asset` and returns its `C...`
+    /// ID. An asset contract that is already deployed is left as it is.
+    pub async fn deploy_asset_contract(
+        &self,
+        signer: &TransactionSigner,
+        asset: Asset,
+    ) -> Result<String> {
+        let preimage = ContractIdPreimage::Asset(asset);
+        let contract_id = contract_id_from_preimage(&self.network_passphrase, &preimage)?;
+        let instance = self
+            .get_contract_data(
+                &contract_id,
+                ScVal::LedgerKeyContractInstance,
+                ContractDataDurability::Persistent,
+            )
+            .await?;
+        if instance.is_none() {
+            self.execute(
+                signer,
+                HostFunction::CreateContractV2(CreateContractArgsV2 {
+                    contract_id_preimage: preimage,
+                    executable: ContractExecutable::StellarAsset,
+                    constructor_args: Default::default(),
+                }),
+                format!("deploy asset contract {}", contract_id),
+            )
+            .await?;
+        }
+        Ok(contract_id)
+    }
+
+    /// Simulates, signs, submits and waits for a single host function call
+    /// from the signer's account.
+    async fn execute(
+        &self,
+        signer: &TransactionSigner,
+        host_function: HostFunction,
+        action: String,
+    ) -> Result<TransactionOutcome> {
+        let sequence = self.get_account_sequence(&signer.address()).await?;
+
+        let tx = build_transaction(signer.public_key(), sequence + 1, host_function)?;
+        let simulation = self.simulate(&tx).await?;
+        self.check_submit_policy(&TransactionPreview::new(action, &simulation))?;
+        let tx = assemble_transaction(tx, &simulation)?;
+        let envelope = signer
+            .sign_transaction(tx, &self.network_passphrase)
+            .await
+            .map_err(Error::Auth)?;
+
+        let hash = self.submit(&envelope).await?;
+        let outcome = self.wait_for_transaction(&hash).await?;
+        crate::output::record_transaction(&outcome);
+        Ok(outcome)
+    }
+
+    /// Prints the preview and stops on `--dry-run`, or asks for confirmation
+    /// when the fee is above the threshold and `--yes` was not given.
+    fn check_submit_policy(&self, preview: &TransactionPreview) -> Result<()> {
+        let policy = &self.submit_policy;
+        let above_threshold = policy
+            .confirm_fee_above
+            .is_some_and(|limit| preview.total_fee > limit);
+
+        if policy.dry_run {
+            crate::outln!("{}", preview);
+            if above_threshold {
+                crate::outln!("  Fee is above the confirmation threshold; submitting needs confirmation or --yes");
+            }
+            return Err(Error::DryRun.into());
+        }
+        if above_threshold && !policy.assume_yes {
+            crate::outln!("{}", preview);
+            if !confirm_action("Fee is above the confirmation threshold. Submit?", false)? {
+                return Err(anyhow!("Transaction not submitted: {}", preview.action));
+            }
+        }
+        Ok(())
+    }
+
+    /// Submits a signed envelope and returns its hash.
+    pub async fn submit(&self, envelope: &TransactionEnvelope) -> Result<String> {
+        let response: SendTransactionResponse = self
+            .request(
+                "sendTransaction",
+                json!({ "transaction": envelope.to_xdr_base64(Limits::none())? }),
+            )
+            .await?;
+
+        match response.status.as_str() {
+            "PENDING" | "DUPLICATE" => Ok(response.hash),
+            "TRY_AGAIN_LATER" => Err(Error::Network(anyhow!(
+                "Transaction {} was not accepted, try again later",
+                response.hash
+            ))
+            .into()),
+            status => Err(anyhow!(
+                "Transaction {} rejected with status {}: {}",
+                response.hash,
+                status,
+                response.error_result_xdr.unwrap_or_default()
+            )),
+        }
+    }
+
+    /// Polls `getTransaction` until the transaction succeeds, fails, or the
+    /// polling budget runs out.
+    pub async fn wait_for_transaction(&self, hash: &str) -> Result<TransactionOutcome> {
+        for attempt in 0..self.max_polls {
+            if attempt > 0 {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+            let response: GetTransactionResponse = self
+                .request("getTransaction", json!({ "hash": hash }))
+                .await?;
+
+            match response.status.as_str() {
+                "SUCCESS" => {
+                    let (return_value, events) = transaction_result(&response)?;
+                    return Ok(TransactionOutcome {
+                        hash: hash.to_string(),
+                        ledger: response.ledger,
+                        return_value,
+                        events,
+                    });
+                }
+                "FAILED" => {
+                    return Err(Error::Contract(anyhow!(
+                        "Transaction {} failed: {}",
+                        hash,
+                        response.result_xdr.unwrap_or_default()
+                    ))
+                    .into())
+                }
+                _ => continue,
+            }
+        }
+
+        Err(Error::Network(anyhow!(
+            "Transaction {} was not confirmed after {} polls",
+            hash,
+            self.max_polls
+        ))
+        .into())
+    }
+}
+
+/// Return value and contract events of a successful transaction.
+fn transaction_result(
+    response: &GetTransactionResponse,
+) -> Result<(Option<ScVal>, Vec<EmittedEvent>)> {
+    let mut return_value = match &response.return_value {
+        Some(value) => Some(ScVal::from_xdr_base64(value, Limits::none())?),
+        None => None,
+    };
+    let Some(meta) = &response.result_meta_xdr else {
+        return Ok((return_value, Vec::new()));
+    };
+    let meta = TransactionMeta::from_xdr_base64(meta, Limits::none())?;
+    let events: Vec<stellar_xdr::curr::ContractEvent> = match meta {
+        TransactionMeta::V3(meta) => {
+            let soroban_meta = meta.soroban_meta;
+            if return_value.is_none() {
+                return_value = soroban_meta.as_ref().map(|m| m.return_value.clone());
+            }
+            soroban_meta.map(|m| m.events.to_vec()).unwrap_or_default()
+        }
+        TransactionMeta::V4(meta) => {
+            if return_value.is_none() {
+                return_value = meta.soroban_meta.and_then(|m| m.return_value);
+            }
+            meta.operations
+                .iter()
+                .flat_map(|operation| operation.events.iter().cloned())
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    let events = events
+        .into_iter()
+        .map(|event| {
+            let ContractEventBody::V0(body) = event.body;
+            EmittedEvent {
+                contract_id: event
+                    .contract_id
+                    .map(|id| stellar_strkey::Contract(id.0 .0).to_string()),
+                topics: body.topics.to_vec(),
+                data: body.data,
+            }
+        })
+        .collect();
+    Ok((return_value, events))
+}
+
+/// Builds an unsigned, unsimulated contract invocation.
+pub fn build_invoke_transaction(
+    source: [u8; 32],
+    sequence: i64,
+    contract_id: &str,
+    function: &str,
+    args: Vec<ScVal>,
+) -> Result<Transaction> {
+    let invoke = invoke_contract_function(contract_id, function, args)?;
+    build_transaction(source, sequence, HostFunction::InvokeContract(invoke))
+}
+
+fn invoke_contract_function(
+    contract_id: &str,
+    function: &str,
+    args: Vec<ScVal>,
+) -> Result<InvokeContractArgs> {
+    Ok(InvokeContractArgs {
+        contract_address: contract_address(contract_id)?,
+        function_name: ScSymbol::try_from(function)
+            .map_err(|_| anyhow!("Invalid contract function name: {}", function))?,
+        args: args.try_into()?,
+    })
+}
+
+/// Builds an unsigned, unsimulated transaction with one host function call.
+pub fn build_transaction(
+    source: [u8; 32],
+    sequence: i64,
+    host_function: HostFunction,
+) -> Result<Transaction> {
+    let operation = Operation {
+        source_account: None,
+        body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+            host_function,
+            auth: Default::default(),
+        }),
+    };
+
+    Ok(Transaction {
+        source_account: MuxedAccount::Ed25519(Uint256(source)),
+        fee: BASE_FEE,
+        seq_num: SequenceNumber(sequence),
+        cond: Preconditions::None,
+        memo: Memo::None,
+        operations: vec![operation].try_into()?,
+        ext: TransactionExt::V0,
+    })
+}
+
+/// Applies the simulated footprint, authorization entries and resource fee.
+pub fn assemble_transaction(mut tx: Transaction, simulation: &Simulation) -> Result<Transaction> {
+    let resource_fee = u32::try_from(simulation.min_resource_fee)
+        .context("Simulated resource fee out of range")?;
+    tx.fee = BASE_FEE
+        .checked_add(resource_fee)
+        .ok_or_else(|| anyhow!("Transaction fee overflow"))?;
+    tx.ext = TransactionExt::V1(simulation.transaction_data.clone());
+
+    let mut operations = tx.operations.to_vec();
+    if let Some(Operation {
+        body: OperationBody::InvokeHostFunction(op),
+        ..
+    }) = operations.first_mut()
+    {
+        op.auth = simulation.auth.clone().try_into()?;
+    }
+    tx.operations = operations.try_into()?;
+
+    Ok(tx)
+}
+
+/// Returns the hash that is signed for `tx` on the given network.
+pub fn transaction_hash(tx: &Transaction, network_passphrase: &str) -> Result<[u8; 32]> {
+    let payload = TransactionSignaturePayload {
+        network_id: Hash(Sha256::digest(network_passphrase.as_bytes()).into()),
+        tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+    };
+    Ok(Sha256::digest(payload.to_xdr(Limits::none())?).into())
+}
+
+/// Derives the `C...` ID a contract gets when created from `preimage` on
+/// the given network.
+pub fn contract_id_from_preimage(
+    network_passphrase: &str,
+    preimage: &ContractIdPreimage,
+) -> Result<String> {
+    let id_preimage = HashIdPreimage::ContractId(HashIdPreimageContractId {
+        network_id: Hash(Sha256::digest(network_passphrase.as_bytes()).into()),
+        contract_id_preimage: preimage.clone(),
+    });
+    let hash: [u8; 32] = Sha256::digest(id_preimage.to_xdr(Limits::none())?).into();
+    Ok(stellar_strkey::Contract(hash).to_string())
+}
+
+/// Parses an `S...` secret seed.
+pub fn signing_key_from_secret(secret_key: &str) -> Result<SigningKey> {
+    let seed = stellar_strkey::ed25519::PrivateKey::from_string(secret_key)
+        .map_err(|_| anyhow!("Invalid secret key"))?;
+    Ok(SigningKey::from_bytes(&seed.0))
+}
+
+/// Returns the `G...` account address for a signing key.
+pub fn public_key_strkey(signing_key: &SigningKey) -> String {
+    stellar_strkey::ed25519::PublicKey(signing_key.verifying_key().to_bytes()).to_string()
+}
+
+/// Parses a `C...` contract ID.
+pub fn contract_address(contract_id: &str) -> Result<ScAddress> {
+    match ScAddress::from_str(contract_id) {
+        Ok(address @ ScAddress::Contract(_)) => Ok(address),
+        _ => Err(anyhow!("Invalid contract ID: {}", contract_id)),
+    }
+}
+
+/// Converts a `G...` or `C...` address into a contract argument.
+pub fn address_arg(address: &str) -> Result<ScVal> {
+    ScAddress::from_str(address)
+        .map(ScVal::Address)
+        .map_err(|_| anyhow!("Invalid address: {}", address))
+}
+
+pub fn string_arg(value: &str) -> Result<ScVal> {
+    Ok(ScVal::String(ScString(value.try_into()?)))
+}
+
+/// Storage key of a unit variant of a `#[contracttype]` enum, e.g.
+/// `StorageKey::Owner`, which the SDK encodes as a one-symbol vector.
+pub fn enum_key(variant: &str) -> Result<ScVal> {
+    let symbol =
+        ScSymbol::try_from(variant).map_err(|_| anyhow!("Invalid enum variant: {}", variant))?;
+    Ok(ScVal::Vec(Some(ScVec(
+        vec![ScVal::Symbol(symbol)].try_into()?,
+    ))))
+}
+
+/// Renders an address value as its strkey, if `value` is an address.
+pub fn scval_to_address(value: &ScVal) -> Option<String> {
+    match value {
+        ScVal::Address(address) => Some(address.to_string()),
+        _ => None,
+    }
+}
+
+/// Encodes a `#[contracttype]` struct argument: a map keyed by field-name
+/// symbols, sorted by name as the host requires.
+pub fn struct_arg(fields: Vec<(&str, ScVal)>) -> Result<ScVal> {
+    let mut entries = fields
+        .into_iter()
+        .map(|(name, val)| {
+            let key = ScSymbol::try_from(name).map_err(|_| anyhow!("Invalid field: {}", name))?;
+            Ok(ScMapEntry {
+                key: ScVal::Symbol(key),
+                val,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(ScVal::Map(Some(ScMap(entries.try_into()?))))
+}
+
+/// Looks up a field of a `#[contracttype]` struct, which the SDK encodes as a
+/// map keyed by field-name symbols.
+pub fn struct_field<'a>(value: &'a ScVal, name: &str) -> Option<&'a ScVal> {
+    let ScVal::Map(Some(map)) = value else {
+        return None;
+    };
+    map.iter().find_map(|entry| match &entry.key {
+        ScVal::Symbol(symbol) if symbol.0.as_slice() == name.as_bytes() => Some(&entry.val),
+        _ => None,
+    })
+}
+
+/// Returns the variant name of a `#[contracttype]` enum value.
+pub fn enum_variant(value: &ScVal) -> Option<String> {
+    let ScVal::Vec(Some(items)) = value else {
+        return None;
+    };
+    match items.first() {
+        Some(ScVal::Symbol(symbol)) => Some(symbol.to_utf8_string_lossy()),
+        _ => None,
+    }
+}
+
+/// Renders a value as JSON for consumers outside Soroban.
+///
+/// Structs (symbol-keyed maps) become objects, 64- and 128-bit integers
+/// become strings so they survive JavaScript number precision, bytes become
+/// hex, and anything without a natural JSON form falls back to base64 XDR.
+pub fn scval_to_json(value: &ScVal) -> Value {
+    match value {
+        ScVal::Void => Value::Null,
+        ScVal::Bool(b) => json!(b),
+        ScVal::U32(n) => json!(n),
+        ScVal::I32(n) => json!(n),
+        ScVal::U64(n) => json!(n.to_string()),
+        ScVal::I64(n) => json!(n.to_string()),
+        ScVal::Timepoint(t) => json!(t.0.to_string()),
+        ScVal::Duration(d) => json!(d.0.to_string()),
+        ScVal::U128(_) => json!(u128::try_from(value.clone())
+            .unwrap_or_default()
+            .to_string()),
+        ScVal::I128(_) => json!(i128::try_from(value.clone())
+            .unwrap_or_default()
+            .to_string()),
+        ScVal::Bytes(bytes) => json!(hex::encode(bytes.as_slice())),
+        ScVal::String(string) => json!(string.to_utf8_string_lossy()),
+        ScVal::Symbol(symbol) => json!(symbol.to_utf8_string_lossy()),
+        ScVal::Address(address) => json!(address.to_string()),
+        ScVal::Vec(Some(items)) => Value::Array(items.iter().map(scval_to_json).collect()),
+        ScVal::Map(Some(map))
+            if map
+                .iter()
+                .all(|entry| matches!(entry.key, ScVal::Symbol(_) | ScVal::String(_))) =>
+        {
+            Value::Object(
+                map.iter()
+                    .map(|entry| {
+                        let key = match &entry.key {
+                            ScVal::Symbol(symbol) => symbol.to_utf8_string_lossy(),
+                            ScVal::String(string) => string.to_utf8_string_lossy(),
+                            _ => unreachable!(),
+                        };
+                        (key, scval_to_json(&entry.val))
+                    })
+                    .collect(),
+            )
+        }
+        ScVal::Map(Some(map)) => Value::Array(
+            map.iter()
+                .map(|entry| json!([scval_to_json(&entry.key), scval_to_json(&entry.val)]))
+                .collect(),
+        ),
+        other => json!(other.to_xdr_base64(Limits::none()).unwrap_or_default()),
+    }
+}
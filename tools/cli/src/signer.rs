@@ -0,0 +1,183 @@
+//! Transaction signers.
+//!
+//! A transaction is either signed in-process with a key unlocked from the
+//! keystore (or, for compatibility, taken from `STELLOPAY_SECRET_KEY`), or
+//! handed to an external signer command so the secret never enters this
+//! process at all - e.g. a hardware wallet bridge or an HSM client.
+//!
+//! External signer protocol: the configured command is run with one extra
+//! argument.
+//!
+//! * `<command> public-key` prints the signer's `G...` address.
+//! * `<command> sign` reads the hex-encoded 32-byte transaction hash on stdin
+//!   and prints the hex-encoded 64-byte ed25519 signature. The unsigned
+//!   transaction (base64 XDR) and the network passphrase are passed in the
+//!   `STELLOPAY_SIGN_TRANSACTION` and `STELLOPAY_NETWORK_PASSPHRASE`
+//!   environment variables for signers that display or verify what they sign.
+//!
+//! Signatures returned by an external signer are verified before use.
+
+use anyhow::{anyhow, Context, Result};
+use ed25519_dalek::{Signature as DalekSignature, Signer, SigningKey, Verifier, VerifyingKey};
+use std::process::Stdio;
+use stellar_xdr::curr::{
+    DecoratedSignature, Limits, Signature, SignatureHint, Transaction, TransactionEnvelope,
+    TransactionV1Envelope, WriteXdr,
+};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::rpc;
+
+/// Signs transactions on behalf of a single account.
+pub enum TransactionSigner {
+    /// Key held in memory for the duration of the command.
+    Local(SigningKey),
+    /// Key held by an external signer command.
+    External(ExternalSigner),
+}
+
+impl TransactionSigner {
+    /// Parses an `S...` secret seed into an in-process signer.
+    pub fn from_secret(secret_key: &str) -> Result<Self> {
+        Ok(TransactionSigner::Local(rpc::signing_key_from_secret(
+            secret_key,
+        )?))
+    }
+
+    /// Raw ed25519 public key of the signing account.
+    pub fn public_key(&self) -> [u8; 32] {
+        match self {
+            TransactionSigner::Local(key) => key.verifying_key().to_bytes(),
+            TransactionSigner::External(signer) => signer.public_key.to_bytes(),
+        }
+    }
+
+    /// `G...` address of the signing account.
+    pub fn address(&self) -> String {
+        stellar_strkey::ed25519::PublicKey(self.public_key()).to_string()
+    }
+
+    /// Signs `tx` for the given network and wraps it in an envelope.
+    pub async fn sign_transaction(
+        &self,
+        tx: Transaction,
+        network_passphrase: &str,
+    ) -> Result<TransactionEnvelope> {
+        let hash = rpc::transaction_hash(&tx, network_passphrase)?;
+        let signature = match self {
+            TransactionSigner::Local(key) => key.sign(&hash).to_bytes(),
+            TransactionSigner::External(signer) => {
+                signer.sign(&tx, &hash, network_passphrase).await?
+            }
+        };
+
+        let public_key = self.public_key();
+        let signature = DecoratedSignature {
+            hint: SignatureHint(public_key[28..].try_into()?),
+            signature: Signature(signature.to_vec().try_into()?),
+        };
+        Ok(TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx,
+            signatures: vec![signature].try_into()?,
+        }))
+    }
+}
+
+/// Signer backed by an external command, see the module docs for the protocol.
+pub struct ExternalSigner {
+    program: String,
+    args: Vec<String>,
+    public_key: VerifyingKey,
+}
+
+impl ExternalSigner {
+    /// Starts a signer from a command line such as `ledger-signer --account 0`
+    /// and asks it for its public key.
+    pub async fn connect(command: &str) -> Result<Self> {
+        let mut parts = command.split_whitespace().map(str::to_string);
+        let program = parts
+            .next()
+            .ok_or_else(|| anyhow!("External signer command is empty"))?;
+        let args: Vec<String> = parts.collect();
+
+        let output = Command::new(&program)
+            .args(&args)
+            .arg("public-key")
+            .stderr(Stdio::inherit())
+            .output()
+            .await
+            .with_context(|| format!("Failed to run external signer '{}'", program))?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "External signer '{}' failed to report its public key ({})",
+                program,
+                output.status
+            ));
+        }
+
+        let address = String::from_utf8(output.stdout)?;
+        let public_key =
+            stellar_strkey::ed25519::PublicKey::from_string(address.trim()).map_err(|_| {
+                anyhow!(
+                    "External signer returned an invalid public key: {}",
+                    address.trim()
+                )
+            })?;
+
+        Ok(ExternalSigner {
+            program,
+            args,
+            public_key: VerifyingKey::from_bytes(&public_key.0)?,
+        })
+    }
+
+    async fn sign(
+        &self,
+        tx: &Transaction,
+        hash: &[u8; 32],
+        network_passphrase: &str,
+    ) -> Result<[u8; 64]> {
+        let unsigned = TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: tx.clone(),
+            signatures: Default::default(),
+        })
+        .to_xdr_base64(Limits::none())?;
+
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .arg("sign")
+            .env("STELLOPAY_SIGN_TRANSACTION", unsigned)
+            .env("STELLOPAY_NETWORK_PASSPHRASE", network_passphrase)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| format!("Failed to run external signer '{}'", self.program))?;
+
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        stdin.write_all(hex::encode(hash).as_bytes()).await?;
+        stdin.write_all(b"\n").await?;
+        drop(stdin);
+
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "External signer '{}' refused to sign ({})",
+                self.program,
+                output.status
+            ));
+        }
+
+        let bytes = hex::decode(String::from_utf8(output.stdout)?.trim())
+            .map_err(|_| anyhow!("External signer returned a non-hex signature"))?;
+        let signature: [u8; 64] = bytes
+            .try_into()
+            .map_err(|_| anyhow!("External signer returned a signature of the wrong length"))?;
+        self.public_key
+            .verify(hash, &DalekSignature::from_bytes(&signature))
+            .map_err(|_| anyhow!("External signer returned an invalid signature"))?;
+
+        Ok(signature)
+    }
+}
@@ -0,0 +1,192 @@
+//! Local payroll templates.
+//!
+//! A template holds the terms of a payroll agreement: token, salary per
+//! period, grace period and whether to activate once employees are added.
+//! Each template is a YAML file, `~/.stellopay/templates/<name>.yaml` (or
+//! under `STELLOPAY_TEMPLATE_DIR`), so a team can keep the directory in git
+//! and review changes like any other file.
+//!
+//! `template apply` maps a template onto the payroll contract's
+//! `create_payroll_agreement`, `add_employee_to_agreement` and
+//! `activate_agreement` calls.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::keystore::validate_key_name;
+use crate::utils::{parse_amount, validate_address};
+
+/// Prefix of the single-line form printed by `template share`.
+pub const SHARE_PREFIX: &str = "stellopay-template:";
+
+/// Decimal places of `salary_per_period`.
+const TOKEN_DECIMALS: u32 = 7;
+
+/// Payroll agreement terms stored in a template file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PayrollTemplate {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Token contract; `defaults.token` is used when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Salary per period in token units, e.g. `"2500.50"`.
+    pub salary_per_period: String,
+    /// Grace period of the agreement, in seconds.
+    #[serde(default)]
+    pub grace_period_seconds: u64,
+    /// Activate the agreement once all employees are added.
+    #[serde(default = "activate_by_default")]
+    pub activate: bool,
+}
+
+fn activate_by_default() -> bool {
+    true
+}
+
+impl PayrollTemplate {
+    /// Checks the name, token and salary so a bad file fails before anything
+    /// is submitted.
+    pub fn validate(&self) -> Result<()> {
+        validate_key_name(&self.name).map_err(|_| {
+            anyhow!(
+                "Invalid template name '{}': use letters, digits, '-' and '_'",
+                self.name
+            )
+        })?;
+        if let Some(token) = &self.token {
+            validate_address(token)
+                .with_context(|| format!("Template '{}': invalid token", self.name))?;
+        }
+        if self.salary()? <= 0 {
+            return Err(anyhow!(
+                "Template '{}': salary_per_period must be positive",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Salary per period in the token's smallest unit.
+    pub fn salary(&self) -> Result<i128> {
+        parse_amount(&self.salary_per_period, TOKEN_DECIMALS)
+            .with_context(|| format!("Template '{}': invalid salary_per_period", self.name))
+    }
+
+    pub fn to_yaml(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    pub fn from_yaml(content: &str) -> Result<Self> {
+        let template: PayrollTemplate = serde_yaml::from_str(content)?;
+        template.validate()?;
+        Ok(template)
+    }
+
+    /// Single-line, copy-pasteable form of the template.
+    pub fn share_string(&self) -> Result<String> {
+        Ok(format!("{}{}", SHARE_PREFIX, hex::encode(self.to_yaml()?)))
+    }
+
+    /// Parses the output of [`PayrollTemplate::share_string`].
+    pub fn from_share_string(shared: &str) -> Result<Self> {
+        let encoded = shared
+            .trim()
+            .strip_prefix(SHARE_PREFIX)
+            .ok_or_else(|| anyhow!("Shared template must start with '{}'", SHARE_PREFIX))?;
+        let yaml = hex::decode(encoded).map_err(|_| anyhow!("Shared template is corrupt"))?;
+        Self::from_yaml(&String::from_utf8(yaml)?)
+    }
+}
+
+pub struct TemplateStore {
+    dir: PathBuf,
+}
+
+impl TemplateStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        TemplateStore { dir: dir.into() }
+    }
+
+    /// Opens `STELLOPAY_TEMPLATE_DIR`, or `~/.stellopay/templates` by default.
+    pub fn open_default() -> Result<Self> {
+        if let Ok(dir) = std::env::var("STELLOPAY_TEMPLATE_DIR") {
+            return Ok(TemplateStore::new(dir));
+        }
+        let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
+        Ok(TemplateStore::new(
+            home.join(".stellopay").join("templates"),
+        ))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes `template`, refusing to replace an existing one unless `overwrite`.
+    pub fn save(&self, template: &PayrollTemplate, overwrite: bool) -> Result<PathBuf> {
+        template.validate()?;
+        let path = self.path(&template.name)?;
+        if path.exists() && !overwrite {
+            return Err(anyhow!(
+                "Template '{}' already exists; pass --force to replace it",
+                template.name
+            ));
+        }
+        fs::create_dir_all(&self.dir)?;
+        fs::write(&path, template.to_yaml()?)?;
+        Ok(path)
+    }
+
+    pub fn load(&self, name: &str) -> Result<PayrollTemplate> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Err(anyhow!(
+                "Template '{}' not found in {}",
+                name,
+                self.dir.display()
+            ));
+        }
+        read_template_file(&path)
+    }
+
+    /// Lists stored templates, sorted by name.
+    pub fn list(&self) -> Result<Vec<PayrollTemplate>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut templates = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("yaml") {
+                continue;
+            }
+            templates.push(read_template_file(&path)?);
+        }
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(templates)
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        validate_key_name(name).map_err(|_| {
+            anyhow!(
+                "Invalid template name '{}': use letters, digits, '-' and '_'",
+                name
+            )
+        })?;
+        Ok(self.dir.join(format!("{}.yaml", name)))
+    }
+}
+
+/// Reads and validates a template file anywhere on disk.
+pub fn read_template_file(path: &Path) -> Result<PayrollTemplate> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read template file {}", path.display()))?;
+    PayrollTemplate::from_yaml(&content)
+        .with_context(|| format!("Invalid template file {}", path.display()))
+}
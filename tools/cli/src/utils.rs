@@ -1,10 +1,10 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use serde_json::Value;
 use std::collections::HashMap;
+use stellar_xdr::curr::ScVal;
+
+use crate::rpc;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Employee {
@@ -140,6 +140,18 @@ pub fn parse_duration(duration_str: &str) -> Result<u64> {
     }
 }
 
+/// Parses a report boundary given as `YYYY-MM-DD` (midnight UTC) or as Unix
+/// seconds.
+pub fn parse_report_date(date_str: &str) -> Result<u64> {
+    if let Ok(seconds) = date_str.parse::<u64>() {
+        return Ok(seconds);
+    }
+    let date = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid date: {} (expected YYYY-MM-DD)", date_str))?;
+    let timestamp = date.and_time(chrono::NaiveTime::MIN).and_utc().timestamp();
+    u64::try_from(timestamp).map_err(|_| anyhow::anyhow!("Date before 1970: {}", date_str))
+}
+
 pub fn validate_address(address: &str) -> Result<()> {
     if address.is_empty() {
         return Err(anyhow::anyhow!("Address cannot be empty"));
@@ -192,57 +204,97 @@ pub fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
     let mut result = String::new();
 
     // Header
-    result.push_str("┌");
+    result.push('┌');
     for (i, width) in widths.iter().enumerate() {
         result.push_str(&"─".repeat(width + 2));
         if i < widths.len() - 1 {
-            result.push_str("┬");
+            result.push('┬');
         }
     }
     result.push_str("┐\n");
 
     // Header row
-    result.push_str("│");
+    result.push('│');
     for (i, (header, width)) in headers.iter().zip(widths.iter()).enumerate() {
         result.push_str(&format!(" {:<width$} ", header, width = width));
         if i < widths.len() - 1 {
-            result.push_str("│");
+            result.push('│');
         }
     }
     result.push_str("│\n");
 
     // Header separator
-    result.push_str("├");
+    result.push('├');
     for (i, width) in widths.iter().enumerate() {
         result.push_str(&"─".repeat(width + 2));
         if i < widths.len() - 1 {
-            result.push_str("┼");
+            result.push('┼');
         }
     }
     result.push_str("┤\n");
 
     // Data rows
     for row in rows {
-        result.push_str("│");
+        result.push('│');
         for (i, (cell, width)) in row.iter().zip(widths.iter()).enumerate() {
             result.push_str(&format!(" {:<width$} ", cell, width = width));
             if i < widths.len() - 1 {
-                result.push_str("│");
+                result.push('│');
             }
         }
         result.push_str("│\n");
     }
 
     // Bottom border
-    result.push_str("└");
+    result.push('└');
     for (i, width) in widths.iter().enumerate() {
         result.push_str(&"─".repeat(width + 2));
         if i < widths.len() - 1 {
-            result.push_str("┴");
+            result.push('┴');
+        }
+    }
+    result.push('┘');
+
+    result
+}
+
+/// Asks for confirmation before an irreversible action. `assume_yes` (the
+/// global `--yes` flag) answers for the user; without a terminal the prompt
+/// fails, so scripts must pass `--yes`.
+pub fn confirm_action(prompt: &str, assume_yes: bool) -> Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+    dialoguer::Confirm::new()
+        .with_prompt(prompt)
+        .default(false)
+        .interact()
+        .map_err(|e| {
+            anyhow::anyhow!("Failed to read confirmation ({}); pass --yes to proceed", e)
+        })
+}
+
+/// Renders rows as RFC 4180 CSV, quoting fields that contain commas, quotes
+/// or line breaks.
+pub fn to_csv(headers: &[&str], rows: &[Vec<String>]) -> String {
+    fn field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
         }
     }
-    result.push_str("┘");
 
+    let mut result = headers
+        .iter()
+        .map(|h| field(h))
+        .collect::<Vec<_>>()
+        .join(",");
+    result.push('\n');
+    for row in rows {
+        result.push_str(&row.iter().map(|c| field(c)).collect::<Vec<_>>().join(","));
+        result.push('\n');
+    }
     result
 }
 
@@ -278,10 +330,87 @@ pub fn format_gas(gas: u64) -> String {
     }
 }
 
+/// One row of a bulk payroll CSV.
+///
+/// `amount` is `None` when the row only names the employee, in which case the
+/// contract pays the employee's configured salary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkPayEntry {
+    pub employee: String,
+    pub amount: Option<i128>,
+}
+
+/// Parses a bulk payroll CSV of `address[,amount]` rows.
+///
+/// Blank lines and `#` comments are skipped, as is a leading header row whose
+/// first column is `address` or `employee`. Amounts are in token units and
+/// converted with 7 decimals. Errors name the offending 1-based line.
+pub fn parse_employee_csv(content: &str) -> Result<Vec<BulkPayEntry>> {
+    let mut entries = Vec::new();
+
+    for (index, raw) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let columns: Vec<&str> = line.split(',').map(|c| c.trim()).collect();
+        if entries.is_empty()
+            && matches!(columns[0].to_lowercase().as_str(), "address" | "employee")
+        {
+            continue;
+        }
+        if columns.len() > 2 {
+            return Err(anyhow::anyhow!(
+                "Line {}: expected `address[,amount]`, found {} columns",
+                line_no,
+                columns.len()
+            ));
+        }
+
+        validate_address(columns[0]).map_err(|e| anyhow::anyhow!("Line {}: {}", line_no, e))?;
+        let amount = match columns.get(1).filter(|a| !a.is_empty()) {
+            Some(amount) => {
+                let parsed = parse_amount(amount, 7)
+                    .map_err(|e| anyhow::anyhow!("Line {}: invalid amount: {}", line_no, e))?;
+                if parsed <= 0 {
+                    return Err(anyhow::anyhow!("Line {}: amount must be positive", line_no));
+                }
+                Some(parsed)
+            }
+            None => None,
+        };
+
+        entries.push(BulkPayEntry {
+            employee: columns[0].to_string(),
+            amount,
+        });
+    }
+
+    Ok(entries)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_to_csv_quotes_special_fields() {
+        let csv = to_csv(
+            &["name", "note"],
+            &[vec!["a,b".to_string(), "say \"hi\"".to_string()]],
+        );
+        assert_eq!(csv, "name,note\n\"a,b\",\"say \"\"hi\"\"\"\n");
+    }
+
+    #[test]
+    fn test_parse_report_date() {
+        assert_eq!(parse_report_date("1970-01-02").unwrap(), 86_400);
+        assert_eq!(parse_report_date("1700000000").unwrap(), 1_700_000_000);
+        assert!(parse_report_date("02/01/1970").is_err());
+    }
+
     #[test]
     fn test_format_amount() {
         assert_eq!(format_amount(1000000000, 7), "100");
@@ -313,6 +442,55 @@ mod tests {
         assert!(validate_address("").is_err());
     }
 
+    #[test]
+    fn test_parse_employee_csv() {
+        let csv = "address,amount\n\
+                   GCKFBEIYTKP6RCZEKMGL2QAPLGKUBGE5UAHRQJRXGCQHKPQM6CHCM4K4,100.5\n\
+                   # contractor paid at configured rate\n\
+                   GBZXN7PIRZGNMHGA7MUUUF4GWPY5AYPGK4YVMQKN74ILIXB4UGOT7ZNA\n";
+        let entries = parse_employee_csv(csv).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].amount, Some(1_005_000_000));
+        assert_eq!(entries[1].amount, None);
+
+        let err = parse_employee_csv("invalid,1").unwrap_err();
+        assert!(err.to_string().starts_with("Line 1:"));
+    }
+
+    #[test]
+    fn test_sign_webhook_payload_binds_timestamp() {
+        let signature = sign_webhook_payload("secret", 1_700_000_000, "{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(
+            signature,
+            sign_webhook_payload("secret", 1_700_000_000, "{}")
+        );
+        assert_ne!(
+            signature,
+            sign_webhook_payload("secret", 1_700_000_001, "{}")
+        );
+        assert_ne!(
+            signature,
+            sign_webhook_payload("other", 1_700_000_000, "{}")
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_up_to_max() {
+        let retry = RetryConfig::default();
+        assert_eq!(retry.delay_for(1), 60);
+        assert_eq!(retry.delay_for(2), 120);
+        assert_eq!(retry.delay_for(3), 240);
+        assert_eq!(retry.delay_for(100), 3600);
+
+        let fixed = RetryConfig {
+            exponential_backoff: false,
+            ..RetryConfig::default()
+        };
+        assert_eq!(fixed.delay_for(5), 60);
+    }
+
     #[test]
     fn test_truncate_address() {
         let addr = "GCKFBEIYTKP6RCZEKMGL2QAPLGKUBGE5UAHRQJRXGCQHKPQM6CHCM4K4";
@@ -329,6 +507,54 @@ pub struct RetryConfig {
     pub max_delay: u64,
 }
 
+impl Default for RetryConfig {
+    /// The policy `webhook register` attaches to new webhooks.
+    fn default() -> Self {
+        RetryConfig {
+            max_retries: 3,
+            retry_delay: 60,
+            exponential_backoff: true,
+            max_delay: 3600,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Decodes a `RetryConfig`.
+    pub fn from_scval(value: &ScVal) -> Option<Self> {
+        let field = |name| rpc::struct_field(value, name);
+        Some(RetryConfig {
+            max_retries: u32::try_from(field("max_retries")?.clone()).ok()?,
+            retry_delay: u64::try_from(field("retry_delay")?.clone()).ok()?,
+            exponential_backoff: bool::try_from(field("exponential_backoff")?.clone()).ok()?,
+            max_delay: u64::try_from(field("max_delay")?.clone()).ok()?,
+        })
+    }
+
+    /// Encodes the policy as the contract's `RetryConfig`.
+    pub fn to_scval(&self) -> Result<ScVal> {
+        rpc::struct_arg(vec![
+            ("max_retries", self.max_retries.into()),
+            ("retry_delay", self.retry_delay.into()),
+            ("exponential_backoff", self.exponential_backoff.into()),
+            ("max_delay", self.max_delay.into()),
+        ])
+    }
+
+    /// Seconds to wait before retry number `retry` (1-based).
+    pub fn delay_for(&self, retry: u32) -> u64 {
+        let delay = if self.exponential_backoff {
+            self.retry_delay.saturating_mul(
+                1u64.checked_shl(retry.saturating_sub(1))
+                    .unwrap_or(u64::MAX),
+            )
+        } else {
+            self.retry_delay
+        };
+        delay.min(self.max_delay)
+    }
+}
+
 /// Delivery security policy attached to a webhook.
 ///
 /// This deliberately excludes the webhook secret: read responses must never
@@ -357,6 +583,50 @@ pub struct WebhookInfo {
     pub security_config: Option<SecurityConfig>,
 }
 
+impl SecurityConfig {
+    /// Decodes a `SecurityConfig`.
+    pub fn from_scval(value: &ScVal) -> Option<Self> {
+        let field = |name| rpc::struct_field(value, name);
+        Some(SecurityConfig {
+            signature_method: rpc::enum_variant(field("signature_method")?)?,
+            rate_limit_per_minute: u32::try_from(field("rate_limit_per_minute")?.clone()).ok()?,
+            require_tls: bool::try_from(field("require_tls")?.clone()).ok()?,
+        })
+    }
+}
+
+impl WebhookInfo {
+    /// Decodes the contract's `WebhookInfo`. Fields it does not return are
+    /// left unset.
+    pub fn from_scval(value: &ScVal) -> Option<Self> {
+        let field = |name| rpc::struct_field(value, name);
+        let string = |name| match field(name)? {
+            ScVal::String(s) => Some(s.to_utf8_string_lossy()),
+            _ => None,
+        };
+        let events = match field("events")? {
+            ScVal::Vec(Some(items)) => items
+                .iter()
+                .map(|item| match item {
+                    ScVal::String(s) => Some(s.to_utf8_string_lossy()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?,
+            _ => return None,
+        };
+        Some(WebhookInfo {
+            id: Some(u64::try_from(field("id")?.clone()).ok()?),
+            name: Some(string("name")?),
+            description: Some(string("description")?),
+            url: Some(string("url")?),
+            events: Some(events),
+            is_active: Some(bool::try_from(field("is_active")?.clone()).ok()?),
+            retry_config: Some(RetryConfig::from_scval(field("retry_config")?)?),
+            security_config: Some(SecurityConfig::from_scval(field("security_config")?)?),
+        })
+    }
+}
+
 /// Typed view of aggregate webhook statistics, as returned by `get_webhook_stats`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WebhookStats {
@@ -366,101 +636,104 @@ pub struct WebhookStats {
     pub failed_deliveries: Option<u64>,
 }
 
-pub struct SorobanHttpClient {
-    base_url: String,
-    client: reqwest::Client,
+impl WebhookStats {
+    /// Decodes the contract's `WebhookStats`.
+    pub fn from_scval(value: &ScVal) -> Option<Self> {
+        let count = |name| u64::try_from(rpc::struct_field(value, name)?.clone()).ok();
+        Some(WebhookStats {
+            total_webhooks: Some(count("total_webhooks")?),
+            active_webhooks: Some(count("active_webhooks")?),
+            total_deliveries: Some(count("total_deliveries")?),
+            failed_deliveries: Some(count("failed_deliveries")?),
+        })
+    }
+}
+
+/// Typed view of an owner's webhook quotas and usage, as returned by
+/// `get_webhook_usage`.
+///
+/// The contract counts endpoints on registration and events on every
+/// reported delivery, so hosted relayers can bill from these counters.
+/// A missing `max_*` field means the quota is not enforced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookUsage {
+    pub endpoints: Option<u64>,
+    pub max_endpoints: Option<u64>,
+    pub events_today: Option<u64>,
+    pub max_events_per_day: Option<u64>,
+    /// Day (days since the Unix epoch) `events_today` counts.
+    pub day: Option<u64>,
+    /// Events recorded since registration, for billing.
+    pub total_events: Option<u64>,
 }
-impl SorobanHttpClient {
-    pub fn new(base_url: &str) -> Self {
-        Self {
-            base_url: base_url.to_string(),
-            client: reqwest::Client::new(),
-        }
+
+impl WebhookUsage {
+    /// Decodes the contract's `WebhookUsage`.
+    pub fn from_scval(value: &ScVal) -> Option<Self> {
+        let field = |name| rpc::struct_field(value, name);
+        let count = |name| u64::try_from(field(name)?.clone()).ok();
+        let quota = |name| match field(name)? {
+            ScVal::Void => Some(None),
+            value => u64::try_from(value.clone()).ok().map(Some),
+        };
+        Some(WebhookUsage {
+            endpoints: Some(count("endpoints")?),
+            max_endpoints: quota("max_endpoints")?,
+            events_today: Some(count("events_today")?),
+            max_events_per_day: quota("max_events_per_day")?,
+            day: Some(count("day")?),
+            total_events: Some(count("total_events")?),
+        })
     }
-    pub async fn get_ledger_info(&self) -> Result<String> {
-        let url = format!("{}/ledger", self.base_url);
-        let res = self.client.get(&url).send().await?;
-        let body = res.text().await?;
-        Ok(body)
-    }
-    pub async fn invoke(
-        &self,
-        contract_id: &str,
-        method: &str,
-        args: Vec<(&str, &str)>,
-        signer: &str,
-    ) -> Result<String> {
-        let url = format!("{}/invoke", self.base_url.trim_end_matches('/'));
-        println!("Invoking Soroban at: {}", url);
-        let payload = json!({
-            "contract_id":contract_id,
-            "method":method,
-            "args":args.iter().map(|(k,v)| json!({(*k):v})).collect::<Vec<_>>(),
-            "signer":signer,
-        });
-        let response = self.client.post(&url).json(&payload).send().await?;
-        let body = response.text().await?;
-        Ok(body)
-    }
-
-    /// Simulate a read-only contract call without signing or submitting a transaction.
-    ///
-    /// This method is intentionally separate from `invoke`: it sends only the
-    /// contract id, method, and arguments to the RPC query endpoint and never
-    /// accepts a signer or secret key.
-    pub async fn query(
-        &self,
-        contract_id: &str,
-        method: &str,
-        args: Vec<(&str, &str)>,
-    ) -> Result<Value> {
-        let url = format!("{}/query", self.base_url);
-        let payload = self.query_payload(contract_id, method, args);
-        let response = self.client.post(&url).json(&payload).send().await?;
-        let status = response.status();
-        let body = response.text().await?;
-
-        if !status.is_success() {
-            return Err(anyhow::anyhow!(
-                "Soroban query failed with status {}: {}",
-                status,
-                body
-            ));
-        }
 
-        let value: Value = serde_json::from_str(&body)
-            .map_err(|e| anyhow::anyhow!("Malformed Soroban query response: {}", e))?;
+    /// Events the owner can still trigger today, or `None` without a daily quota.
+    pub fn events_left_today(&self) -> Option<u64> {
+        let max = self.max_events_per_day?;
+        Some(max.saturating_sub(self.events_today.unwrap_or(0)))
+    }
+}
 
-        if let Some(error) = value.get("error") {
-            return Err(anyhow::anyhow!("Soroban query RPC error: {}", error));
-        }
+/// Computes the `X-Stellopay-Signature` header for a webhook delivery:
+/// `sha256=` followed by the hex HMAC-SHA256 of `"{timestamp}.{body}"`.
+///
+/// Binding the timestamp lets receivers reject replayed deliveries.
+pub fn sign_webhook_payload(secret: &str, timestamp: u64, body: &str) -> String {
+    use hmac::{Hmac, Mac};
+
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
 
-        Ok(value.get("result").cloned().unwrap_or(value))
-    }
-
-    /// Same read-only query as [`SorobanHttpClient::query`], but deserialized
-    /// into a typed result `T` instead of a raw [`Value`].
-    ///
-    /// Prefer this over `query` when the shape of the contract method's
-    /// return value is known (e.g. [`WebhookInfo`], [`WebhookStats`]), so
-    /// callers get compile-time field access instead of indexing into JSON.
-    pub async fn query_as<T: serde::de::DeserializeOwned>(
-        &self,
-        contract_id: &str,
-        method: &str,
-        args: Vec<(&str, &str)>,
-    ) -> Result<T> {
-        let value = self.query(contract_id, method, args).await?;
-        serde_json::from_value(value)
-            .map_err(|e| anyhow::anyhow!("Soroban query result did not match expected shape: {}", e))
-    }
-
-    fn query_payload(&self, contract_id: &str, method: &str, args: Vec<(&str, &str)>) -> Value {
-        json!({
-            "contract_id": contract_id,
-            "method": method,
-            "args": args.iter().map(|(k, v)| json!({ (*k): v })).collect::<Vec<_>>(),
-            "read_only": true,
-        })
+/// Loads the local webhook signing secrets, keyed by webhook ID.
+///
+/// The contract never returns secrets from its read methods, so
+/// `webhook register` keeps them in this file for `webhook relay`.
+pub fn load_webhook_secrets(path: &std::path::Path) -> Result<HashMap<u64, String>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid webhook secrets file {}: {}", path.display(), e))
+}
+
+/// Records the signing secret of a webhook, keeping the file private to the user.
+pub fn save_webhook_secret(path: &std::path::Path, webhook_id: u64, secret: &str) -> Result<()> {
+    let mut secrets = load_webhook_secrets(path)?;
+    secrets.insert(webhook_id, secret.to_string());
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
+    std::fs::write(path, serde_json::to_string_pretty(&secrets)?)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
 }
@@ -0,0 +1,88 @@
+//! Decoders of contract return values checked against the shared types in
+//! `stellopay-interfaces`, so a field renamed or retyped on-chain fails here
+//! instead of surfacing as "unexpected shape" against a live network.
+
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, IntoVal, TryFromVal, Val};
+use stellar_xdr::curr::ScVal;
+use stellopay_cli::commands::{BonusIncentive, ReportPayment};
+use stellopay_cli::rpc;
+use stellopay_interfaces::bonus::{ApprovalStatus, Incentive, IncentiveKind};
+use stellopay_interfaces::history::PaymentRecord;
+
+fn scval<T: IntoVal<Env, Val>>(env: &Env, value: &T) -> ScVal {
+    ScVal::try_from_val(env, &value.into_val(env)).unwrap()
+}
+
+fn strkey(env: &Env, address: &Address) -> String {
+    rpc::scval_to_address(&scval(env, address)).unwrap()
+}
+
+#[test]
+fn payment_records_decode() {
+    let env = Env::default();
+    let record = PaymentRecord {
+        id: 7,
+        agreement_id: 3,
+        payment_hash: BytesN::from_array(&env, &[0xab; 32]),
+        token: Address::generate(&env),
+        amount: 1_500,
+        from: Address::generate(&env),
+        to: Address::generate(&env),
+        to_pseudonym: None,
+        timestamp: 86_400,
+    };
+
+    let decoded = ReportPayment::from_scval(&scval(&env, &record)).unwrap();
+    assert_eq!(
+        decoded,
+        ReportPayment {
+            id: 7,
+            agreement_id: 3,
+            payment_hash: "ab".repeat(32),
+            token: strkey(&env, &record.token),
+            amount: 1_500,
+            employer: strkey(&env, &record.from),
+            employee: strkey(&env, &record.to),
+            timestamp: 86_400,
+        }
+    );
+}
+
+#[test]
+fn bonus_incentives_decode() {
+    let env = Env::default();
+    let incentive = Incentive {
+        id: 2,
+        employer: Address::generate(&env),
+        employee: Address::generate(&env),
+        approver: Address::generate(&env),
+        token: Address::generate(&env),
+        kind: IncentiveKind::Recurring,
+        status: ApprovalStatus::Approved,
+        amount_per_payout: 250,
+        total_payouts: 4,
+        claimed_payouts: 1,
+        start_time: 1_000,
+        interval_seconds: 60,
+    };
+
+    let decoded = BonusIncentive::from_scval(&scval(&env, &incentive)).unwrap();
+    assert_eq!(
+        decoded,
+        BonusIncentive {
+            id: 2,
+            employer: strkey(&env, &incentive.employer),
+            employee: strkey(&env, &incentive.employee),
+            approver: strkey(&env, &incentive.approver),
+            token: strkey(&env, &incentive.token),
+            kind: "Recurring".to_string(),
+            status: "Approved".to_string(),
+            amount_per_payout: 250,
+            total_payouts: 4,
+            claimed_payouts: 1,
+            start_time: 1_000,
+            interval_seconds: 60,
+        }
+    );
+    assert_eq!(decoded.claimable_payouts(1_060), 1);
+}
@@ -4,12 +4,12 @@ use std::process::Command;
 use tempfile::TempDir;
 
 use std::path::PathBuf;
-use tokio::fs;
 
 use stellopay_cli::commands::emergency_withdraw;
-use stellopay_cli::config::{get_secret_key, load_config};
-use stellopay_cli::utils::SorobanHttpClient;
-use stellopay_cli::{AuthConfig, Config, ContractConfig, DefaultsConfig, Error, NetworkConfig};
+use stellopay_cli::config::load_config;
+use stellopay_cli::{
+    AuthConfig, Config, ContractConfig, DefaultsConfig, Error, ErrorClass, NetworkConfig,
+};
 use wiremock::matchers::{body_partial_json, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -32,11 +32,18 @@ fn make_config(secret_key: Option<&str>) -> Config {
         },
         auth: AuthConfig {
             secret_key: secret_key.map(str::to_string),
+            ..Default::default()
         },
         defaults: DefaultsConfig {
             token: None,
             frequency: "monthly".to_string(),
+            fee_confirm_threshold: None,
+            employers: Vec::new(),
         },
+        notifications: Default::default(),
+        devnet: Default::default(),
+        dry_run: false,
+        assume_yes: false,
     }
 }
 
@@ -67,18 +74,128 @@ fn test_cli_info_without_contract_id() {
         .stderr(predicate::str::contains("No contract ID provided"));
 }
 
+#[test]
+fn test_cli_json_output_reports_error_and_exit_code() {
+    let dir = TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("stellopay-cli").unwrap();
+    cmd.arg("--config")
+        .arg(dir.path().join("config.toml"))
+        .arg("--output")
+        .arg("json")
+        .arg("info");
+    let output = cmd.assert().code(1).get_output().stdout.clone();
+    let document: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(document["ok"], false);
+    assert_eq!(document["error"]["code"], "error");
+    assert_eq!(document["error"]["exit_code"], 1);
+    assert!(document["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("No contract ID provided"));
+    assert_eq!(document["transactions"], serde_json::json!([]));
+}
+
+#[test]
+fn test_cli_devnet_up_requires_contract_builds() {
+    let dir = TempDir::new().unwrap();
+    let mut cmd = Command::cargo_bin("stellopay-cli").unwrap();
+    cmd.env_remove("STELLOPAY_SECRET_KEY")
+        .arg("--config")
+        .arg(dir.path().join("config.toml"))
+        .args([
+            "devnet",
+            "up",
+            "--rpc-url",
+            "http://127.0.0.1:9/soroban/rpc",
+        ])
+        .arg("--wasm-dir")
+        .arg(dir.path());
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("WASM file not found"));
+    // Nothing was written for a network that was never set up
+    assert!(!dir.path().join("devnet.toml").exists());
+}
+
+#[test]
+fn test_devnet_friendbot_url_follows_quickstart_layout() {
+    use stellopay_cli::commands::devnet_friendbot_url;
+
+    assert_eq!(
+        devnet_friendbot_url("http://localhost:8000/soroban/rpc").as_deref(),
+        Some("http://localhost:8000/friendbot")
+    );
+    assert_eq!(
+        devnet_friendbot_url("http://quickstart:8001/soroban/rpc/").as_deref(),
+        Some("http://quickstart:8001/friendbot")
+    );
+    assert_eq!(devnet_friendbot_url("http://localhost:8003"), None);
+}
+
+#[tokio::test]
+async fn test_fund_account_retries_until_friendbot_is_up() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/friendbot"))
+        .respond_with(ResponseTemplate::new(502))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/friendbot"))
+        .and(wiremock::matchers::query_param("addr", VALID_RECIPIENT))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    stellopay_cli::commands::fund_account(
+        &reqwest::Client::new(),
+        &format!("{}/friendbot", server.uri()),
+        VALID_RECIPIENT,
+        std::time::Instant::now() + std::time::Duration::from_secs(30),
+    )
+    .await
+    .unwrap();
+
+    let err = stellopay_cli::commands::fund_account(
+        &reqwest::Client::new(),
+        &format!("{}/missing", server.uri()),
+        VALID_RECIPIENT,
+        std::time::Instant::now(),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(Error::class(&err), ErrorClass::Network);
+}
+
+#[test]
+fn test_error_classes_map_to_exit_codes() {
+    let classify = |error: Error| Error::class(&error.into());
+    assert_eq!(classify(Error::MissingSecretKey).exit_code(), 3);
+    assert_eq!(classify(Error::NotAdmin), ErrorClass::Auth);
+    assert_eq!(classify(Error::InvalidAddress).exit_code(), 2);
+    assert_eq!(classify(Error::ContractPaused), ErrorClass::Contract);
+    assert_eq!(
+        Error::class(&anyhow::anyhow!("something else")),
+        ErrorClass::General
+    );
+    // Context added on top keeps the class of the underlying error
+    let err = anyhow::Error::from(Error::Network(anyhow::anyhow!("connection refused")))
+        .context("Fetching ledger");
+    assert_eq!(Error::class(&err).code(), "network_error");
+}
+
 #[test]
 fn test_cli_info_with_contract_id() {
     let mut cmd = Command::cargo_bin("stellopay-cli").unwrap();
-    cmd.arg("info")
-        .arg("--contract-id")
-        .arg("CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAE");
+    cmd.arg("info").arg("--contract-id").arg(VALID_CONTRACT);
 
-    // This might fail depending on network connectivity, but should at least attempt to connect
+    // This might fail depending on network connectivity; an unreachable
+    // network is reported as an unhealthy contract
     let output = cmd.output().unwrap();
     assert!(
-        output.status.success()
-            || String::from_utf8_lossy(&output.stderr).contains("Failed to get contract details")
+        output.status.success() || String::from_utf8_lossy(&output.stderr).contains("is unhealthy")
     );
 }
 
@@ -180,10 +297,9 @@ fn test_info_with_invalid_contract_id() {
         .arg("--contract-id")
         .arg("invalid_contract_id");
 
-    let output = cmd.output().unwrap();
-    // The CLI currently doesn't validate contract ID format but logs warnings
-    assert!(output.status.success());
-    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to get contract details"));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid contract ID"));
 }
 
 #[test]
@@ -363,261 +479,3197 @@ async fn test_over_limit_checked_before_address_validation() {
     );
 }
 
-// --- SorobanHttpClient::query tests ---
+const EMPLOYEE: &str = "GBZXN7PIRZGNMHGA7MUUUF4GWPY5AYPGK4YVMQKN74ILIXB4UGOT7ZNA";
+
+fn make_mock_config(server: &MockServer) -> Config {
+    let mut config = make_config(Some(SECRET_KEY));
+    config.network.rpc_url = server.uri();
+    config.defaults.token = Some(VALID_CONTRACT.to_string());
+    config
+}
+
+fn payroll_args(amount: Option<&str>, file: Option<PathBuf>) -> stellopay_cli::EmployeePayrollArgs {
+    stellopay_cli::EmployeePayrollArgs {
+        employee: EMPLOYEE.to_string(),
+        contract_id: None,
+        amount: amount.map(str::to_string),
+        token: None,
+        grace_period: None,
+        file,
+        no_activate: false,
+    }
+}
+
+#[test]
+fn test_employee_terms_flags_override_file() {
+    use stellopay_cli::commands::resolve_payroll_terms;
+
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("employee.toml");
+    std::fs::write(&file, "amount = \"100\"\ngrace_period = \"7d\"\n").unwrap();
+
+    let mut config = make_config(Some(SECRET_KEY));
+    config.defaults.token = Some(VALID_CONTRACT.to_string());
+
+    let (token, amount, grace_period) =
+        resolve_payroll_terms(&payroll_args(Some("250"), Some(file)), &config).unwrap();
+    assert_eq!(token, VALID_CONTRACT);
+    assert_eq!(amount, 2_500_000_000);
+    assert_eq!(grace_period, 7 * 24 * 60 * 60);
+}
+
+#[test]
+fn test_employee_create_requires_amount() {
+    use stellopay_cli::commands::resolve_payroll_terms;
+
+    let mut config = make_config(Some(SECRET_KEY));
+    config.defaults.token = Some(VALID_CONTRACT.to_string());
+
+    let err = resolve_payroll_terms(&payroll_args(None, None), &config).unwrap_err();
+    assert!(err.to_string().contains("No amount provided"));
+}
+
+// --- SorobanRpcClient tests ---
 //
-// These tests exercise the read-only `query` path against a local mock RPC
-// server (wiremock), so they do not depend on network access or a live
-// Soroban node.
+// A wiremock server stands in for a Soroban RPC node; responses are real XDR
+// so the client's transaction assembly and signing are exercised end to end.
+
+mod rpc_client {
+    use super::*;
+    use ed25519_dalek::{Signature as DalekSignature, Verifier};
+    use stellar_xdr::curr::{
+        AccountEntry, AccountEntryExt, ContractDataDurability, ContractDataEntry, ContractEvent,
+        ContractEventBody, ContractEventType, ContractEventV0, ContractId, ExtensionPoint, Hash,
+        LedgerEntryData, Limits, ReadXdr, ScAddress, ScVal, SequenceNumber, SorobanTransactionData,
+        SorobanTransactionMeta, SorobanTransactionMetaExt, Thresholds, TransactionEnvelope,
+        TransactionMeta, TransactionMetaV3, WriteXdr,
+    };
+    use stellopay_cli::rpc::{self, Network, SorobanRpcClient};
+    use stellopay_cli::templates::PayrollTemplate;
+    use stellopay_cli::utils::parse_employee_csv;
+    use stellopay_cli::{require_admin, require_not_paused, TokenClient, TransactionSigner};
+
+    const SEED: [u8; 32] = [7u8; 32];
+
+    fn secret() -> String {
+        stellar_strkey::ed25519::PrivateKey(SEED).to_string()
+    }
 
-#[tokio::test]
-async fn test_query_success_returns_result_field() {
-    let server = MockServer::start().await;
+    fn signer_address() -> String {
+        rpc::public_key_strkey(&rpc::signing_key_from_secret(&secret()).unwrap())
+    }
 
-    Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "result": { "webhook_id": 1, "active": true }
-        })))
-        .mount(&server)
+    fn signer() -> TransactionSigner {
+        TransactionSigner::from_secret(&secret()).unwrap()
+    }
+
+    fn contract() -> String {
+        stellar_strkey::Contract([1u8; 32]).to_string()
+    }
+
+    fn client(server: &MockServer) -> SorobanRpcClient {
+        SorobanRpcClient::new(&server.uri(), Network::Testnet.passphrase())
+            .with_polling(std::time::Duration::from_millis(0), 5)
+    }
+
+    fn rpc_result(result: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .set_body_json(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+    }
+
+    async fn mount_rpc(server: &MockServer, rpc_method: &str, result: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({ "method": rpc_method }),
+            ))
+            .respond_with(rpc_result(result))
+            .mount(server)
+            .await;
+    }
+
+    fn ledger_entry(data: LedgerEntryData) -> serde_json::Value {
+        serde_json::json!({
+            "entries": [{ "key": "", "xdr": data.to_xdr_base64(Limits::none()).unwrap() }],
+            "latestLedger": 100
+        })
+    }
+
+    async fn mount_account(server: &MockServer, sequence: i64) {
+        let account = LedgerEntryData::Account(AccountEntry {
+            account_id: signer_address().parse().unwrap(),
+            balance: 100_0000000,
+            seq_num: SequenceNumber(sequence),
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: 0,
+            home_domain: Default::default(),
+            thresholds: Thresholds([1, 0, 0, 0]),
+            signers: Default::default(),
+            ext: AccountEntryExt::V0,
+        });
+        mount_rpc(server, "getLedgerEntries", ledger_entry(account)).await;
+    }
+
+    async fn mount_simulation(server: &MockServer, min_resource_fee: &str, retval: ScVal) {
+        mount_rpc(
+            server,
+            "simulateTransaction",
+            serde_json::json!({
+                "transactionData": SorobanTransactionData::default()
+                    .to_xdr_base64(Limits::none())
+                    .unwrap(),
+                "minResourceFee": min_resource_fee,
+                "results": [{ "auth": [], "xdr": retval.to_xdr_base64(Limits::none()).unwrap() }],
+                "latestLedger": 100
+            }),
+        )
+        .await;
+    }
+
+    #[test]
+    fn test_network_selection() {
+        assert_eq!(Network::from_name("Mainnet").unwrap(), Network::Mainnet);
+        assert!(Network::from_name("devnet").is_err());
+        assert_eq!(Network::from_name("standalone").unwrap(), Network::Local);
+        assert_eq!(
+            Network::from_passphrase("Standalone Network ; February 2017"),
+            Some(Network::Local)
+        );
+        assert_eq!(
+            Network::from_passphrase("Public Global Stellar Network ; September 2015"),
+            Some(Network::Mainnet)
+        );
+
+        let network: stellopay_cli::NetworkConfig = Network::Testnet.into();
+        assert_eq!(network.rpc_url, "https://soroban-testnet.stellar.org:443");
+    }
+
+    #[tokio::test]
+    async fn test_invoke_simulates_signs_submits_and_polls() {
+        let server = MockServer::start().await;
+        mount_account(&server, 41).await;
+        mount_simulation(&server, "250", ScVal::Void).await;
+        mount_rpc(
+            &server,
+            "sendTransaction",
+            serde_json::json!({ "status": "PENDING", "hash": "abc123", "latestLedger": 100 }),
+        )
         .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({ "method": "getTransaction" }),
+            ))
+            .respond_with(rpc_result(serde_json::json!({ "status": "NOT_FOUND" })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        mount_rpc(
+            &server,
+            "getTransaction",
+            serde_json::json!({
+                "status": "SUCCESS",
+                "ledger": 101,
+                "returnValue": ScVal::U32(5).to_xdr_base64(Limits::none()).unwrap()
+            }),
+        )
+        .await;
+
+        let outcome = client(&server)
+            .invoke(
+                &signer(),
+                &contract(),
+                "initialize",
+                vec![ScVal::Bool(true)],
+            )
+            .await
+            .expect("invoke should succeed");
+
+        assert_eq!(outcome.hash, "abc123");
+        assert_eq!(outcome.ledger, Some(101));
+        assert_eq!(outcome.return_value, Some(ScVal::U32(5)));
+
+        // The submitted envelope carries the simulated fee, the next sequence
+        // number, and a valid signature over the testnet transaction hash.
+        let requests = server.received_requests().await.unwrap();
+        let send = requests
+            .iter()
+            .map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).unwrap())
+            .find(|body| body["method"] == "sendTransaction")
+            .expect("transaction was submitted");
+        let envelope = TransactionEnvelope::from_xdr_base64(
+            send["params"]["transaction"].as_str().unwrap(),
+            Limits::none(),
+        )
+        .unwrap();
+        let TransactionEnvelope::Tx(envelope) = envelope else {
+            panic!("expected a v1 envelope");
+        };
+        assert_eq!(envelope.tx.fee, rpc::BASE_FEE + 250);
+        assert_eq!(envelope.tx.seq_num, SequenceNumber(42));
+
+        let hash = rpc::transaction_hash(&envelope.tx, Network::Testnet.passphrase()).unwrap();
+        let signature =
+            DalekSignature::from_slice(envelope.signatures[0].signature.0.as_slice()).unwrap();
+        rpc::signing_key_from_secret(&secret())
+            .unwrap()
+            .verifying_key()
+            .verify(&hash, &signature)
+            .expect("signature should verify");
+    }
+
+    #[tokio::test]
+    async fn test_failed_simulation_is_not_submitted() {
+        let server = MockServer::start().await;
+        mount_account(&server, 1).await;
+        mount_rpc(
+            &server,
+            "simulateTransaction",
+            serde_json::json!({ "error": "HostError: Error(Contract, #1)", "latestLedger": 100 }),
+        )
+        .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({ "method": "sendTransaction" }),
+            ))
+            .respond_with(rpc_result(serde_json::json!({})))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let err = client(&server)
+            .invoke(&signer(), &contract(), "initialize", vec![])
+            .await
+            .expect_err("simulation error should abort the invocation");
+        assert!(err.to_string().contains("Simulation failed"));
+        assert_eq!(Error::class(&err), ErrorClass::Contract);
+        assert_eq!(Error::class(&err).exit_code(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_error_classes_for_simulation_and_network_failures() {
+        let server = MockServer::start().await;
+        mount_account(&server, 1).await;
+        mount_rpc(
+            &server,
+            "simulateTransaction",
+            serde_json::json!({ "error": "HostError: Error(Budget, ExceededLimit)", "latestLedger": 100 }),
+        )
+        .await;
+        let err = client(&server)
+            .invoke(&signer(), &contract(), "initialize", vec![])
+            .await
+            .unwrap_err();
+        assert_eq!(Error::class(&err), ErrorClass::Simulation);
+        assert_eq!(Error::class(&err).code(), "simulation_failure");
+
+        let down = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&down)
+            .await;
+        let err = client(&down)
+            .invoke(&signer(), &contract(), "initialize", vec![])
+            .await
+            .unwrap_err();
+        assert_eq!(Error::class(&err), ErrorClass::Network);
+        assert_eq!(Error::class(&err).exit_code(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_invoke_decodes_events_from_result_meta() {
+        let server = MockServer::start().await;
+        mount_account(&server, 1).await;
+        mount_simulation(&server, "100", ScVal::Void).await;
+        mount_rpc(
+            &server,
+            "sendTransaction",
+            serde_json::json!({ "status": "PENDING", "hash": "abc123", "latestLedger": 100 }),
+        )
+        .await;
+        let event = ContractEvent {
+            ext: ExtensionPoint::V0,
+            contract_id: Some(ContractId(Hash([1u8; 32]))),
+            type_: ContractEventType::Contract,
+            body: ContractEventBody::V0(ContractEventV0 {
+                topics: vec![ScVal::Symbol("paid".try_into().unwrap())]
+                    .try_into()
+                    .unwrap(),
+                data: ScVal::from(500i128),
+            }),
+        };
+        let meta = TransactionMeta::V3(TransactionMetaV3 {
+            ext: ExtensionPoint::V0,
+            tx_changes_before: Default::default(),
+            operations: Default::default(),
+            tx_changes_after: Default::default(),
+            soroban_meta: Some(SorobanTransactionMeta {
+                ext: SorobanTransactionMetaExt::V0,
+                events: vec![event].try_into().unwrap(),
+                return_value: ScVal::U32(9),
+                diagnostic_events: Default::default(),
+            }),
+        });
+        mount_rpc(
+            &server,
+            "getTransaction",
+            serde_json::json!({
+                "status": "SUCCESS",
+                "ledger": 101,
+                "resultMetaXdr": meta.to_xdr_base64(Limits::none()).unwrap()
+            }),
+        )
+        .await;
+
+        let outcome = client(&server)
+            .invoke(&signer(), &contract(), "initialize", vec![])
+            .await
+            .unwrap();
+        assert_eq!(outcome.return_value, Some(ScVal::U32(9)));
+        assert_eq!(outcome.events.len(), 1);
+        assert_eq!(
+            outcome.events[0].contract_id,
+            Some(stellar_strkey::Contract([1u8; 32]).to_string())
+        );
+        assert_eq!(
+            outcome.events[0].topics,
+            vec![ScVal::Symbol("paid".try_into().unwrap())]
+        );
+        assert_eq!(outcome.events[0].data, ScVal::from(500i128));
+    }
 
-    let client = SorobanHttpClient::new(&server.uri());
-    let result = client
-        .query(VALID_CONTRACT, "get_webhook", vec![("webhook_id", "1")])
+    #[tokio::test]
+    async fn test_require_not_paused_reads_contract_state() {
+        let server = MockServer::start().await;
+        mount_simulation(&server, "0", ScVal::Bool(true)).await;
+
+        let result = require_not_paused(&client(&server), &contract()).await;
+        assert!(
+            matches!(result, Err(Error::ContractPaused)),
+            "got: {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_require_admin_compares_signer_with_stored_owner() {
+        let server = MockServer::start().await;
+        let owner: ScAddress = VALID_OWNER.parse().unwrap();
+        let entry = LedgerEntryData::ContractData(ContractDataEntry {
+            ext: ExtensionPoint::V0,
+            contract: contract().parse().unwrap(),
+            key: rpc::enum_key("Owner").unwrap(),
+            durability: ContractDataDurability::Persistent,
+            val: ScVal::Address(owner),
+        });
+        mount_rpc(&server, "getLedgerEntries", ledger_entry(entry)).await;
+
+        let result = require_admin(&client(&server), &contract(), &signer()).await;
+        assert!(matches!(result, Err(Error::NotAdmin)), "got: {result:?}");
+    }
+
+    #[tokio::test]
+    async fn test_token_balance_decodes_i128() {
+        let server = MockServer::start().await;
+        mount_simulation(&server, "0", ScVal::from(1_234_i128)).await;
+
+        let rpc = client(&server);
+        let balance = TokenClient::new(&rpc, &contract())
+            .balance(&signer_address())
+            .await
+            .unwrap();
+        assert_eq!(balance, 1_234);
+    }
+
+    const VALID_OWNER: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+
+    /// Matches a `simulateTransaction` request invoking `function`, optionally
+    /// with `arg` as its first argument.
+    struct SimulatesCall {
+        function: &'static str,
+        arg: Option<ScVal>,
+    }
+
+    impl wiremock::Match for SimulatesCall {
+        fn matches(&self, request: &wiremock::Request) -> bool {
+            use stellar_xdr::curr::{HostFunction, OperationBody};
+
+            let Ok(body) = serde_json::from_slice::<serde_json::Value>(&request.body) else {
+                return false;
+            };
+            if body["method"] != "simulateTransaction" {
+                return false;
+            }
+            let Some(Ok(TransactionEnvelope::Tx(envelope))) = body["params"]["transaction"]
+                .as_str()
+                .map(|xdr| TransactionEnvelope::from_xdr_base64(xdr, Limits::none()))
+            else {
+                return false;
+            };
+            let OperationBody::InvokeHostFunction(op) = &envelope.tx.operations[0].body else {
+                return false;
+            };
+            let HostFunction::InvokeContract(call) = &op.host_function else {
+                return false;
+            };
+            call.function_name.0.as_slice() == self.function.as_bytes()
+                && self
+                    .arg
+                    .as_ref()
+                    .map_or(true, |arg| call.args.first() == Some(arg))
+        }
+    }
+
+    fn simulation_result(retval: ScVal) -> ResponseTemplate {
+        rpc_result(serde_json::json!({
+            "transactionData": SorobanTransactionData::default()
+                .to_xdr_base64(Limits::none())
+                .unwrap(),
+            "minResourceFee": "0",
+            "results": [{ "auth": [], "xdr": retval.to_xdr_base64(Limits::none()).unwrap() }],
+            "latestLedger": 100
+        }))
+    }
+
+    /// Encodes a `#[contracttype]` struct the way the SDK does: a map keyed
+    /// by field-name symbols.
+    fn contract_struct(fields: Vec<(&str, ScVal)>) -> ScVal {
+        use stellar_xdr::curr::{ScMap, ScMapEntry, ScSymbol};
+
+        let mut entries: Vec<ScMapEntry> = fields
+            .into_iter()
+            .map(|(name, val)| ScMapEntry {
+                key: ScVal::Symbol(ScSymbol::try_from(name).unwrap()),
+                val,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        ScVal::Map(Some(ScMap(entries.try_into().unwrap())))
+    }
+
+    fn contract_vec(items: Vec<ScVal>) -> ScVal {
+        ScVal::Vec(Some(stellar_xdr::curr::ScVec(items.try_into().unwrap())))
+    }
+
+    fn scheduler_job(id: u128, employer: &str, next_scheduled_time: u64) -> ScVal {
+        scheduler_job_after(id, employer, next_scheduled_time, 0)
+    }
+
+    fn scheduler_job_after(
+        id: u128,
+        employer: &str,
+        next_scheduled_time: u64,
+        executions: u32,
+    ) -> ScVal {
+        contract_struct(vec![
+            ("amount", 10_000_000i128.into()),
+            ("employer", rpc::address_arg(employer).unwrap()),
+            ("executions", executions.into()),
+            ("id", id.into()),
+            ("interval_seconds", 2_592_000u64.into()),
+            ("max_executions", ScVal::Void),
+            ("max_retries", 3u32.into()),
+            ("next_scheduled_time", next_scheduled_time.into()),
+            ("recipient", rpc::address_arg(VALID_OWNER).unwrap()),
+            ("retry_count", 0u32.into()),
+            ("schedule_id", vec![0u8; 32].try_into().unwrap()),
+            ("status", rpc::enum_key("Active").unwrap()),
+            ("token", rpc::address_arg(&token()).unwrap()),
+        ])
+    }
+
+    async fn mount_jobs(server: &MockServer, jobs: Vec<ScVal>) {
+        let count = jobs.len() as u128;
+        for (index, job) in jobs.into_iter().enumerate() {
+            Mock::given(method("POST"))
+                .and(SimulatesCall {
+                    function: "get_job",
+                    arg: Some((index as u128 + 1).into()),
+                })
+                .respond_with(simulation_result(job))
+                .mount(server)
+                .await;
+        }
+        // Every id past the last job is empty.
+        for id in count + 1..count + 8 {
+            Mock::given(method("POST"))
+                .and(SimulatesCall {
+                    function: "get_job",
+                    arg: Some(id.into()),
+                })
+                .respond_with(simulation_result(ScVal::Void))
+                .mount(server)
+                .await;
+        }
+    }
+
+    fn keeper_args(employer: Option<&str>, dry_run: bool) -> stellopay_cli::KeeperRunArgs {
+        stellopay_cli::KeeperRunArgs {
+            scheduler_id: contract(),
+            employer: employer.map(str::to_string),
+            interval_secs: 1,
+            max_jobs: 20,
+            concurrency: 2,
+            dry_run,
+            max_ticks: Some(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keeper_lists_jobs_across_concurrent_windows() {
+        let server = MockServer::start().await;
+        mount_jobs(
+            &server,
+            vec![
+                scheduler_job(1, VALID_OWNER, 10),
+                scheduler_job(2, &signer_address(), 20),
+                scheduler_job(3, VALID_OWNER, 30),
+            ],
+        )
+        .await;
+
+        let jobs = stellopay_cli::commands::fetch_scheduler_jobs(
+            &std::sync::Arc::new(client(&server)),
+            &contract(),
+            2,
+        )
         .await
-        .expect("query should succeed");
+        .unwrap();
 
-    assert_eq!(result["webhook_id"], 1);
-    assert_eq!(result["active"], true);
-}
+        assert_eq!(jobs.iter().map(|j| j.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(jobs[1].employer, signer_address());
+        assert!(jobs[0].is_due(10) && !jobs[2].is_due(29));
+    }
 
-#[tokio::test]
-async fn test_query_empty_result() {
-    let server = MockServer::start().await;
+    #[tokio::test]
+    async fn test_keeper_skips_employer_without_due_jobs() {
+        let server = MockServer::start().await;
+        mount_jobs(&server, vec![scheduler_job(1, VALID_OWNER, 0)]).await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "process_due_payments",
+                arg: None,
+            })
+            .respond_with(simulation_result(ScVal::U32(1)))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let tick = stellopay_cli::commands::keeper_tick(
+            &std::sync::Arc::new(client(&server)),
+            &keeper_args(Some(&signer_address()), false),
+            Some(&signer()),
+        )
+        .await
+        .unwrap();
 
-    Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "result": []
-        })))
-        .mount(&server)
+        assert_eq!(tick, stellopay_cli::commands::KeeperTick::default());
+    }
+
+    #[tokio::test]
+    async fn test_keeper_dry_run_reports_without_submitting() {
+        let server = MockServer::start().await;
+        mount_jobs(&server, vec![scheduler_job(1, VALID_OWNER, 0)]).await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "process_due_payments",
+                arg: Some(20u32.into()),
+            })
+            .respond_with(simulation_result(ScVal::U32(3)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({ "method": "sendTransaction" }),
+            ))
+            .respond_with(rpc_result(serde_json::json!({})))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let tick = stellopay_cli::commands::keeper_tick(
+            &std::sync::Arc::new(client(&server)),
+            &keeper_args(Some(VALID_OWNER), true),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tick.employer_due_jobs, vec![1]);
+        assert_eq!(tick.pending, 3);
+        assert!(tick.transactions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_keeper_submits_process_due_payments() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        mount_simulation(&server, "100", ScVal::U32(2)).await;
+        mount_rpc(
+            &server,
+            "sendTransaction",
+            serde_json::json!({ "status": "PENDING", "hash": "feed01", "latestLedger": 100 }),
+        )
+        .await;
+        mount_rpc(
+            &server,
+            "getTransaction",
+            serde_json::json!({
+                "status": "SUCCESS",
+                "ledger": 102,
+                "returnValue": ScVal::U32(2).to_xdr_base64(Limits::none()).unwrap()
+            }),
+        )
         .await;
 
-    let client = SorobanHttpClient::new(&server.uri());
-    let result = client
-        .query(VALID_CONTRACT, "list_owner_webhooks", vec![("owner", "G...")])
+        let tick = stellopay_cli::commands::keeper_tick(
+            &std::sync::Arc::new(client(&server)),
+            &keeper_args(None, false),
+            Some(&signer()),
+        )
         .await
-        .expect("query should succeed even with an empty result");
+        .unwrap();
 
-    assert_eq!(result, serde_json::json!([]));
-}
+        assert_eq!(tick.pending, 2);
+        assert_eq!(tick.processed, 2);
+        assert_eq!(tick.transactions, vec!["feed01".to_string()]);
+    }
 
-#[tokio::test]
-async fn test_query_returns_whole_body_when_no_result_field() {
-    let server = MockServer::start().await;
+    fn schedule_execute_args(all: bool) -> stellopay_cli::ScheduleExecuteArgs {
+        stellopay_cli::ScheduleExecuteArgs {
+            scheduler: stellopay_cli::SchedulerTarget {
+                scheduler_id: contract(),
+                concurrency: 2,
+            },
+            all,
+            max_jobs: 20,
+            delay_ms: 0,
+        }
+    }
 
-    Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "total_webhooks": 3,
-            "active_webhooks": 2
-        })))
-        .mount(&server)
+    #[tokio::test]
+    async fn test_schedule_due_filters_configured_employers() {
+        let server = MockServer::start().await;
+        mount_jobs(
+            &server,
+            vec![
+                scheduler_job(1, VALID_OWNER, 10),
+                scheduler_job(2, &signer_address(), 20),
+                scheduler_job(3, VALID_OWNER, 30),
+            ],
+        )
         .await;
 
-    let client = SorobanHttpClient::new(&server.uri());
-    let result = client
-        .query(VALID_CONTRACT, "get_webhook_stats", vec![])
+        let jobs = stellopay_cli::commands::fetch_scheduler_jobs(
+            &std::sync::Arc::new(client(&server)),
+            &contract(),
+            2,
+        )
         .await
-        .expect("query should succeed");
+        .unwrap();
+        assert_eq!(jobs[0].amount, 10_000_000);
+        assert_eq!(jobs[0].max_executions, None);
+
+        let due = |employers: &[String]| {
+            stellopay_cli::commands::due_schedules(jobs.clone(), employers, 25)
+                .into_iter()
+                .map(|job| job.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(due(&[]), vec![1, 2]);
+        assert_eq!(due(&[VALID_OWNER.to_string()]), vec![1]);
+    }
 
-    assert_eq!(result["total_webhooks"], 3);
-    assert_eq!(result["active_webhooks"], 2);
-}
+    #[tokio::test]
+    async fn test_schedule_execute_runs_due_schedules_one_per_transaction() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        // Before the run: both due. After: 1 paid, 2 handed to the retry contract.
+        for (id, after) in [
+            (1u128, scheduler_job_after(1, VALID_OWNER, 2_592_010, 1)),
+            (2u128, scheduler_job_after(2, VALID_OWNER, 2_592_020, 0)),
+        ] {
+            Mock::given(method("POST"))
+                .and(SimulatesCall {
+                    function: "get_job",
+                    arg: Some(id.into()),
+                })
+                .respond_with(simulation_result(scheduler_job(id, VALID_OWNER, id as u64)))
+                .up_to_n_times(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("POST"))
+                .and(SimulatesCall {
+                    function: "get_job",
+                    arg: Some(id.into()),
+                })
+                .respond_with(simulation_result(after))
+                .mount(&server)
+                .await;
+        }
+        mount_jobs(&server, Vec::new()).await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "process_due_payments",
+                arg: Some(1u32.into()),
+            })
+            .respond_with(simulation_result(ScVal::U32(1)))
+            .expect(2)
+            .mount(&server)
+            .await;
+        mount_rpc(
+            &server,
+            "getTransaction",
+            serde_json::json!({
+                "status": "SUCCESS",
+                "ledger": 101,
+                "returnValue": ScVal::U32(1).to_xdr_base64(Limits::none()).unwrap()
+            }),
+        )
+        .await;
+        expect_submissions(&server, 2).await;
+
+        let mut config = signing_config(&server);
+        config.assume_yes = true;
+        let executions = stellopay_cli::commands::schedule_execute_command(
+            schedule_execute_args(false),
+            &config,
+        )
+        .await
+        .unwrap();
 
-#[tokio::test]
-async fn test_query_rpc_error_surfaces_as_err() {
-    let server = MockServer::start().await;
+        let outcomes: Vec<_> = executions.iter().map(|e| e.outcome()).collect();
+        assert_eq!(outcomes, vec!["Paid", "Retry scheduled"]);
+    }
 
-    Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "error": "contract not found"
-        })))
-        .mount(&server)
+    #[tokio::test]
+    async fn test_schedule_execute_all_batches_due_schedules() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        mount_jobs(
+            &server,
+            vec![
+                scheduler_job(1, VALID_OWNER, 10),
+                scheduler_job(2, &signer_address(), 20),
+            ],
+        )
+        .await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "process_due_payments",
+                arg: Some(20u32.into()),
+            })
+            .respond_with(simulation_result(ScVal::U32(2)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        expect_submissions(&server, 1).await;
+
+        let executions = stellopay_cli::commands::schedule_execute_command(
+            schedule_execute_args(true),
+            &signing_config(&server),
+        )
+        .await
+        .unwrap();
+        assert_eq!(executions.len(), 2);
+    }
+
+    fn upcoming_args(notify: bool) -> stellopay_cli::UpcomingArgs {
+        stellopay_cli::UpcomingArgs {
+            scheduler: stellopay_cli::SchedulerTarget {
+                scheduler_id: contract(),
+                concurrency: 2,
+            },
+            employer: VALID_OWNER.to_string(),
+            within: "7d".to_string(),
+            notify,
+        }
+    }
+
+    #[test]
+    fn test_upcoming_counts_repeats_within_window() {
+        let job = |id, interval_seconds, max_executions, status: &str| {
+            stellopay_cli::commands::SchedulerJob {
+                id,
+                employer: VALID_OWNER.to_string(),
+                recipient: signer_address(),
+                token: token(),
+                amount: 100,
+                interval_seconds,
+                next_scheduled_time: 1_000,
+                max_executions,
+                executions: 1,
+                retry_count: 0,
+                status: status.to_string(),
+            }
+        };
+        let jobs = vec![
+            job(1, 86_400, None, "Active"),
+            job(2, 86_400, Some(3), "Active"),
+            job(3, 0, None, "Active"),
+            job(4, 86_400, None, "Paused"),
+        ];
+
+        let payouts =
+            stellopay_cli::commands::upcoming_payouts(&jobs, VALID_OWNER, 0, 1_000 + 7 * 86_400);
+        let counts: Vec<(u128, u32, i128)> = payouts
+            .iter()
+            .map(|p| (p.schedule_id, p.payouts, p.total))
+            .collect();
+        // Daily for a week (both ends included), capped at the 2 remaining
+        // executions, and a one-off
+        assert_eq!(counts, vec![(1, 8, 800), (2, 2, 200), (3, 1, 100)]);
+
+        assert!(stellopay_cli::commands::upcoming_payouts(&jobs, VALID_OWNER, 0, 999).is_empty());
+
+        // Overdue: one payout now, the next a full interval later
+        let overdue = stellopay_cli::commands::upcoming_payouts(
+            &jobs[..1],
+            VALID_OWNER,
+            500_000,
+            500_000 + 86_400,
+        );
+        assert_eq!(overdue[0].payouts, 2);
+        assert!(
+            stellopay_cli::commands::upcoming_payouts(&jobs, &signer_address(), 0, u64::MAX)
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upcoming_reports_shortfall_and_posts_summary() {
+        let server = MockServer::start().await;
+        mount_jobs(
+            &server,
+            vec![
+                scheduler_job(1, VALID_OWNER, 10),
+                scheduler_job(2, &signer_address(), 20),
+                scheduler_job(3, VALID_OWNER, 30),
+                scheduler_job(4, VALID_OWNER, u64::MAX / 2),
+            ],
+        )
         .await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "decimals",
+                arg: None,
+            })
+            .respond_with(simulation_result(7u32.into()))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "balance",
+                arg: Some(rpc::address_arg(VALID_OWNER).unwrap()),
+            })
+            .respond_with(simulation_result(ScVal::from(15_000_000i128)))
+            .mount(&server)
+            .await;
+        let hook = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&hook)
+            .await;
+
+        let mut config = make_mock_config(&server);
+        let report =
+            stellopay_cli::commands::upcoming_report(&upcoming_args(false), &config, 100, 100)
+                .await
+                .unwrap();
+        let ids: Vec<u128> = report.payouts.iter().map(|p| p.schedule_id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(report.totals.len(), 1);
+        assert_eq!(report.totals[0].required, 20_000_000);
+        assert_eq!(report.totals[0].balance, 15_000_000);
+        assert_eq!(report.totals[0].shortfall, 5_000_000);
+
+        let summary = stellopay_cli::commands::upcoming_summary(&report, "7d");
+        assert!(summary.contains("2 payout(s) to 1 employee(s)"));
+        assert!(summary.contains("2 required, balance 1.5, short by 0.5"));
+
+        config.notifications.webhook_url = Some(format!("{}/hook", hook.uri()));
+        stellopay_cli::commands::upcoming_command(upcoming_args(true), &config)
+            .await
+            .unwrap();
+        let requests = hook.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert!(body["text"].as_str().unwrap().contains(VALID_OWNER));
+        assert_eq!(body["report"]["totals"][0]["shortfall"], 5_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_upcoming_notify_requires_webhook_url() {
+        let server = MockServer::start().await;
+        mount_jobs(&server, vec![]).await;
 
-    let client = SorobanHttpClient::new(&server.uri());
-    let err = client
-        .query("unknown_contract", "get_webhook", vec![])
+        let err = stellopay_cli::commands::upcoming_command(
+            upcoming_args(true),
+            &make_mock_config(&server),
+        )
         .await
-        .expect_err("an RPC-level error field should surface as Err");
+        .unwrap_err();
+        assert!(err.to_string().contains("notifications.webhook_url"));
+    }
 
-    assert!(
-        err.to_string().contains("contract not found"),
-        "expected error message to include RPC error, got: {err}"
-    );
-}
+    fn token() -> String {
+        stellar_strkey::Contract([2u8; 32]).to_string()
+    }
 
-#[tokio::test]
-async fn test_query_http_error_status_surfaces_as_err() {
-    let server = MockServer::start().await;
+    fn payment_record(id: u128, employee: &str, amount: i128, timestamp: u64) -> ScVal {
+        contract_struct(vec![
+            ("id", id.into()),
+            ("agreement_id", 7u128.into()),
+            ("payment_hash", vec![0xabu8; 32].try_into().unwrap()),
+            ("token", rpc::address_arg(&token()).unwrap()),
+            ("amount", amount.into()),
+            ("from", rpc::address_arg(&signer_address()).unwrap()),
+            ("to", rpc::address_arg(employee).unwrap()),
+            ("timestamp", timestamp.into()),
+        ])
+    }
 
-    Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(500).set_body_string("internal server error"))
-        .mount(&server)
+    async fn mount_view(server: &MockServer, function: &'static str, retval: ScVal) {
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function,
+                arg: None,
+            })
+            .respond_with(simulation_result(retval))
+            .mount(server)
+            .await;
+    }
+
+    // 2024-01-15, 2024-01-31 12:00 and 2024-02-01 (UTC)
+    const JAN_15: u64 = 1_705_276_800;
+    const JAN_31_NOON: u64 = 1_706_702_400;
+    const FEB_01: u64 = 1_706_745_600;
+
+    async fn mount_report_sources(server: &MockServer) {
+        mount_view(server, "get_employer_payment_count", ScVal::U32(3)).await;
+        mount_view(
+            server,
+            "get_payments_by_employer",
+            contract_vec(vec![
+                payment_record(1, VALID_OWNER, 10_0000000, JAN_15),
+                payment_record(2, VALID_OWNER, 5_0000000, JAN_31_NOON),
+                payment_record(3, &signer_address(), 7_0000000, FEB_01),
+            ]),
+        )
         .await;
+        mount_view(
+            server,
+            "get_audit_entries_by_employer",
+            contract_struct(vec![
+                (
+                    "entries",
+                    contract_vec(vec![contract_struct(vec![
+                        ("id", 1u64.into()),
+                        ("timestamp", JAN_15.into()),
+                        ("actor", rpc::address_arg(&signer_address()).unwrap()),
+                        ("event", rpc::enum_key("AgreementCreated").unwrap()),
+                        ("agreement_id", 7u128.into()),
+                        ("subject", ScVal::Void),
+                        ("amount", ScVal::Void),
+                        ("external_log_id", ScVal::Void),
+                    ])]),
+                ),
+                ("next_start_id", ScVal::Void),
+            ]),
+        )
+        .await;
+    }
+
+    fn report_args(
+        format: stellopay_cli::ReportFormat,
+        output: PathBuf,
+    ) -> stellopay_cli::ReportArgs {
+        stellopay_cli::ReportArgs {
+            employer: signer_address(),
+            from: "2024-01-01".to_string(),
+            to: "2024-01-31".to_string(),
+            format,
+            history_id: contract(),
+            contract_id: Some(contract()),
+            output,
+        }
+    }
 
-    let client = SorobanHttpClient::new(&server.uri());
-    let err = client
-        .query(VALID_CONTRACT, "get_webhook", vec![])
+    #[tokio::test]
+    async fn test_report_aggregates_payments_in_range() {
+        let server = MockServer::start().await;
+        mount_report_sources(&server).await;
+        let config = make_mock_config(&server);
+        let dir = TempDir::new().unwrap();
+        let report = stellopay_cli::commands::build_report(
+            &report_args(stellopay_cli::ReportFormat::Csv, dir.path().to_path_buf()),
+            &config,
+        )
         .await
-        .expect_err("a non-2xx HTTP status should surface as Err");
+        .unwrap();
+
+        // The 1 February payment falls outside the inclusive 31 January end.
+        assert_eq!(report.metrics.payment_count, 2);
+        assert_eq!(report.metrics.employee_count, 1);
+        assert_eq!(report.metrics.audit_event_count, 1);
+        assert_eq!(report.employee_totals.len(), 1);
+        assert_eq!(report.employee_totals[0].total, 15_0000000);
+        assert_eq!(report.token_totals[0].payments, 2);
+        assert_eq!(report.audit_trail[0].event, "AgreementCreated");
+    }
 
-    assert!(
-        err.to_string().contains("500"),
-        "expected error message to include status code, got: {err}"
-    );
-}
+    #[tokio::test]
+    async fn test_report_writes_accountant_csv_files() {
+        let server = MockServer::start().await;
+        mount_report_sources(&server).await;
+        let config = make_mock_config(&server);
+        let dir = TempDir::new().unwrap();
+
+        stellopay_cli::commands::report_command(
+            report_args(stellopay_cli::ReportFormat::Csv, dir.path().to_path_buf()),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let totals = std::fs::read_to_string(dir.path().join("employee_totals.csv")).unwrap();
+        assert_eq!(
+            totals,
+            format!(
+                "employee,token,payments,total\n{},{},2,15\n",
+                VALID_OWNER,
+                token()
+            )
+        );
+        let payments = std::fs::read_to_string(dir.path().join("payments.csv")).unwrap();
+        assert!(payments.contains("2024-01-15T00:00:00+00:00"));
+        assert_eq!(payments.lines().count(), 3);
+        assert!(dir.path().join("token_totals.csv").exists());
+        assert!(dir.path().join("audit_trail.csv").exists());
+    }
 
-#[tokio::test]
-async fn test_query_malformed_response_surfaces_as_err() {
-    let server = MockServer::start().await;
+    #[tokio::test]
+    async fn test_report_json_output() {
+        let server = MockServer::start().await;
+        mount_report_sources(&server).await;
+        let dir = TempDir::new().unwrap();
 
-    Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(200).set_body_string("not json at all"))
-        .mount(&server)
+        stellopay_cli::commands::report_command(
+            report_args(stellopay_cli::ReportFormat::Json, dir.path().to_path_buf()),
+            &make_mock_config(&server),
+        )
+        .await
+        .unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join("report.json")).unwrap())
+                .unwrap();
+        assert_eq!(json["metrics"]["payment_count"], 2);
+        assert_eq!(json["token_totals"][0]["total"], 15_0000000);
+    }
+
+    // --- payment history ---
+
+    async fn mount_employee_history(server: &MockServer) {
+        mount_view(server, "get_employee_payment_count", ScVal::U32(3)).await;
+        mount_view(
+            server,
+            "get_payments_by_employee",
+            contract_vec(vec![
+                payment_record(1, VALID_OWNER, 10_0000000, JAN_15),
+                payment_record(2, VALID_OWNER, 5_0000000, JAN_31_NOON),
+                payment_record(3, VALID_OWNER, 7_0000000, FEB_01),
+            ]),
+        )
         .await;
+        mount_view(server, "decimals", ScVal::U32(6)).await;
+    }
+
+    fn history_args() -> stellopay_cli::HistoryArgs {
+        stellopay_cli::HistoryArgs {
+            history_id: contract(),
+            employee: Some(VALID_OWNER.to_string()),
+            employer: None,
+            agreement: None,
+            from: None,
+            to: None,
+            limit: 20,
+            page: 1,
+            export: None,
+            output: None,
+        }
+    }
 
-    let client = SorobanHttpClient::new(&server.uri());
-    let err = client
-        .query(VALID_CONTRACT, "get_webhook", vec![])
+    #[tokio::test]
+    async fn test_history_exports_date_filtered_csv_with_token_decimals() {
+        let server = MockServer::start().await;
+        mount_employee_history(&server).await;
+        let dir = TempDir::new().unwrap();
+        let output = dir.path().join("history.csv");
+
+        stellopay_cli::commands::history_command(
+            stellopay_cli::HistoryArgs {
+                from: Some("2024-01-20".to_string()),
+                to: Some("2024-01-31".to_string()),
+                export: Some(stellopay_cli::ReportFormat::Csv),
+                output: Some(output.clone()),
+                ..history_args()
+            },
+            &make_mock_config(&server),
+        )
         .await
-        .expect_err("a non-JSON body should surface as Err");
+        .unwrap();
+
+        let csv = std::fs::read_to_string(output).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "payment_id,date,agreement_id,employer,employee,token,amount,payment_hash"
+        );
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with(&format!(
+            "2,2024-01-31T12:00:00+00:00,7,{},{},{},50,",
+            signer_address(),
+            VALID_OWNER,
+            token()
+        )));
+    }
 
-    assert!(
-        err.to_string().contains("Malformed"),
-        "expected error message to flag malformed response, got: {err}"
-    );
-}
+    #[tokio::test]
+    async fn test_history_pages_client_side() {
+        let server = MockServer::start().await;
+        mount_employee_history(&server).await;
+        let config = make_mock_config(&server);
+
+        stellopay_cli::commands::history_command(
+            stellopay_cli::HistoryArgs {
+                limit: 2,
+                page: 2,
+                ..history_args()
+            },
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let err = stellopay_cli::commands::history_command(
+            stellopay_cli::HistoryArgs {
+                limit: 0,
+                ..history_args()
+            },
+            &config,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("greater than zero"));
+    }
 
-#[tokio::test]
-async fn test_query_request_never_includes_a_signer() {
-    // Security property: the read-only query path must not carry a signer or
-    // secret key, unlike `invoke`. We assert this both by the method's
-    // signature (it takes no signer argument) and by inspecting the actual
-    // request body sent over the wire.
-    let server = MockServer::start().await;
+    #[test]
+    fn test_history_requires_a_selector() {
+        let mut cmd = Command::cargo_bin("stellopay-cli").unwrap();
+        cmd.args(["history", "--history-id", VALID_CONTRACT])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--employee"));
+    }
 
-    Mock::given(method("POST"))
-        .and(path("/query"))
-        .and(body_partial_json(serde_json::json!({ "read_only": true })))
-        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "result": "ok"
-        })))
-        .mount(&server)
+    // --- webhook relay ---
+
+    const RELAY_SECRET: &str = "whsec_test";
+
+    fn claimed_event(id: &str) -> serde_json::Value {
+        use stellar_xdr::curr::ScSymbol;
+
+        let topic = ScVal::Symbol(ScSymbol::try_from("payroll_claimed_event").unwrap());
+        let value = contract_struct(vec![
+            ("agreement_id", ScVal::from(7_u128)),
+            ("amount", ScVal::from(2_500_i128)),
+            ("employee", ScVal::Address(VALID_OWNER.parse().unwrap())),
+        ]);
+        serde_json::json!({
+            "type": "contract",
+            "ledger": 120,
+            "ledgerClosedAt": "2024-05-01T12:00:00Z",
+            "contractId": contract(),
+            "id": id,
+            "txHash": "feedbeef",
+            "topic": [topic.to_xdr_base64(Limits::none()).unwrap()],
+            "value": value.to_xdr_base64(Limits::none()).unwrap(),
+        })
+    }
+
+    fn webhook_info(server: &MockServer, id: u64, event: &str, retry_delay: u64) -> ScVal {
+        let string = |value: &str| rpc::string_arg(value).unwrap();
+        contract_struct(vec![
+            ("id", id.into()),
+            ("owner", rpc::address_arg(&signer_address()).unwrap()),
+            ("name", string("payroll hook")),
+            ("description", string("")),
+            ("url", string(&format!("{}/hook", server.uri()))),
+            ("events", contract_vec(vec![string(event)])),
+            ("is_active", true.into()),
+            (
+                "retry_config",
+                contract_struct(vec![
+                    ("max_retries", 1_u32.into()),
+                    ("retry_delay", retry_delay.into()),
+                    ("exponential_backoff", true.into()),
+                    ("max_delay", 3600_u64.into()),
+                ]),
+            ),
+            (
+                "security_config",
+                contract_struct(vec![
+                    ("signature_method", rpc::enum_key("HmacSha256").unwrap()),
+                    ("rate_limit_per_minute", 60_u32.into()),
+                    ("require_tls", true.into()),
+                ]),
+            ),
+            ("created_at", 1_000_u64.into()),
+        ])
+    }
+
+    /// Serves one page with a `payroll_claimed_event`, then empty pages, and
+    /// registers two webhooks of the signer: #1 subscribed to claims and
+    /// pointing at `/hook`, #2 subscribed to something else.
+    async fn mount_relay(server: &MockServer, retry_delay: u64) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({ "method": "getEvents" }),
+            ))
+            .respond_with(rpc_result(serde_json::json!({
+                "events": [claimed_event("0000000515396075520-0000000001")],
+                "latestLedger": 125,
+                "cursor": "cursor-1"
+            })))
+            .up_to_n_times(1)
+            .mount(server)
+            .await;
+        mount_rpc(
+            server,
+            "getEvents",
+            serde_json::json!({ "events": [], "latestLedger": 126, "cursor": "cursor-2" }),
+        )
+        .await;
+        mount_view(
+            server,
+            "list_owner_webhooks",
+            contract_vec(vec![1_u64.into(), 2_u64.into()]),
+        )
         .await;
+        for (id, event) in [
+            (1_u64, "payroll_claimed_event"),
+            (2, "agreement_paused_event"),
+        ] {
+            Mock::given(method("POST"))
+                .and(SimulatesCall {
+                    function: "get_webhook",
+                    arg: Some(id.into()),
+                })
+                .respond_with(simulation_result(webhook_info(
+                    server,
+                    id,
+                    event,
+                    retry_delay,
+                )))
+                .mount(server)
+                .await;
+        }
+    }
 
-    let client = SorobanHttpClient::new(&server.uri());
-    let result = client
-        .query(VALID_CONTRACT, "get_webhook_stats", vec![])
+    fn relay_args(dir: &tempfile::TempDir) -> stellopay_cli::WebhookRelayArgs {
+        let secrets = dir.path().join("webhook_secrets.json");
+        stellopay_cli::utils::save_webhook_secret(&secrets, 1, RELAY_SECRET).unwrap();
+        stellopay_cli::WebhookRelayArgs {
+            owner: signer_address(),
+            contract_id: Some(contract()),
+            secrets,
+            start_ledger: Some(100),
+            interval_secs: 1,
+            max_events: 50,
+            timeout_secs: 5,
+            no_report: false,
+            max_ticks: None,
+        }
+    }
+
+    /// Arguments of the transactions submitted to the mock server.
+    async fn submitted_calls(server: &MockServer) -> Vec<(String, Vec<ScVal>)> {
+        use stellar_xdr::curr::{HostFunction, OperationBody};
+
+        let requests = server.received_requests().await.unwrap();
+        requests
+            .iter()
+            .filter_map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).ok())
+            .filter(|body| body["method"] == "sendTransaction")
+            .filter_map(|body| {
+                let xdr = body["params"]["transaction"].as_str()?.to_string();
+                let TransactionEnvelope::Tx(envelope) =
+                    TransactionEnvelope::from_xdr_base64(xdr, Limits::none()).ok()?
+                else {
+                    return None;
+                };
+                let OperationBody::InvokeHostFunction(op) = &envelope.tx.operations[0].body else {
+                    return None;
+                };
+                let HostFunction::InvokeContract(call) = &op.host_function else {
+                    return None;
+                };
+                Some((
+                    call.function_name.to_utf8_string_lossy(),
+                    call.args.to_vec(),
+                ))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_relay_delivers_signed_event_and_reports_result() {
+        let server = MockServer::start().await;
+        mount_relay(&server, 0).await;
+        mount_account(&server, 9).await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "record_webhook_delivery",
+                arg: Some(1_u64.into()),
+            })
+            .respond_with(simulation_result(ScVal::Void))
+            .expect(1)
+            .mount(&server)
+            .await;
+        expect_submissions(&server, 1).await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut relay =
+            stellopay_cli::commands::WebhookRelay::new(relay_args(&dir), &signing_config(&server))
+                .await
+                .unwrap();
+        let tick = relay.tick(1_700_000_000).await.unwrap();
+
+        assert_eq!(tick.events, 1);
+        assert_eq!(tick.retrying, 0);
+        assert_eq!(tick.results.len(), 1);
+        assert!(tick.results[0].success);
+        assert_eq!(tick.results[0].webhook_id, 1);
+        assert_eq!(tick.results[0].status_code, Some(200));
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests
+            .iter()
+            .all(|r| !String::from_utf8_lossy(&r.body).contains(&secret())));
+        let hook = requests
+            .iter()
+            .find(|r| r.url.path() == "/hook")
+            .expect("webhook was called");
+        let body = String::from_utf8(hook.body.clone()).unwrap();
+        assert_eq!(
+            hook.headers.get("x-stellopay-signature").unwrap(),
+            &stellopay_cli::utils::sign_webhook_payload(RELAY_SECRET, 1_700_000_000, &body)
+        );
+        assert_eq!(
+            hook.headers.get("x-stellopay-event").unwrap(),
+            "payroll_claimed_event"
+        );
+        let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(payload["data"]["amount"], "2500");
+        assert_eq!(payload["data"]["employee"], VALID_OWNER);
+        assert_eq!(payload["transaction_hash"], "feedbeef");
+
+        let calls = submitted_calls(&server).await;
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "record_webhook_delivery");
+        assert_eq!(
+            calls[0].1,
+            vec![
+                ScVal::from(1_u64),
+                contract_struct(vec![
+                    ("webhook_id", 1_u64.into()),
+                    (
+                        "event_id",
+                        rpc::string_arg("0000000515396075520-0000000001").unwrap()
+                    ),
+                    (
+                        "event_type",
+                        rpc::string_arg("payroll_claimed_event").unwrap()
+                    ),
+                    ("success", true.into()),
+                    ("attempts", 1_u32.into()),
+                    ("status_code", 200_u32.into()),
+                    ("error", ScVal::Void),
+                ]),
+            ]
+        );
+
+        // The next poll follows the cursor instead of re-reading the ledger.
+        relay.tick(1_700_000_010).await.unwrap();
+        let requests = server.received_requests().await.unwrap();
+        let polls: Vec<serde_json::Value> = requests
+            .iter()
+            .map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).unwrap())
+            .filter(|body| body["method"] == "getEvents")
+            .collect();
+        assert_eq!(polls[0]["params"]["startLedger"], 100);
+        assert_eq!(polls[1]["params"]["pagination"]["cursor"], "cursor-1");
+    }
+
+    #[tokio::test]
+    async fn test_relay_requires_owner_signer_to_report() {
+        let server = MockServer::start().await;
+        let dir = tempfile::TempDir::new().unwrap();
+        let args = stellopay_cli::WebhookRelayArgs {
+            owner: VALID_OWNER.to_string(),
+            ..relay_args(&dir)
+        };
+
+        let err =
+            stellopay_cli::commands::WebhookRelay::new(args.clone(), &signing_config(&server))
+                .await
+                .err()
+                .expect("a non-owner signer cannot report deliveries");
+        assert!(err.to_string().contains("--no-report"));
+
+        let args = stellopay_cli::WebhookRelayArgs {
+            no_report: true,
+            ..args
+        };
+        assert!(
+            stellopay_cli::commands::WebhookRelay::new(args, &make_mock_config(&server))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_webhook_info_decodes_contract_webhook() {
+        use stellopay_cli::utils::{RetryConfig, WebhookInfo};
+
+        let server_uri = "http://relay.test";
+        let value = contract_struct(vec![
+            ("id", 4_u64.into()),
+            ("owner", rpc::address_arg(VALID_OWNER).unwrap()),
+            ("name", rpc::string_arg("payroll hook").unwrap()),
+            ("description", rpc::string_arg("").unwrap()),
+            ("url", rpc::string_arg(server_uri).unwrap()),
+            ("events", contract_vec(vec![rpc::string_arg("*").unwrap()])),
+            ("is_active", false.into()),
+            ("retry_config", RetryConfig::default().to_scval().unwrap()),
+            (
+                "security_config",
+                contract_struct(vec![
+                    ("signature_method", rpc::enum_key("HmacSha256").unwrap()),
+                    ("rate_limit_per_minute", 60_u32.into()),
+                    ("require_tls", true.into()),
+                ]),
+            ),
+            ("created_at", 1_000_u64.into()),
+        ]);
+
+        let webhook = WebhookInfo::from_scval(&value).unwrap();
+        assert_eq!(webhook.id, Some(4));
+        assert_eq!(webhook.url.as_deref(), Some(server_uri));
+        assert_eq!(webhook.events, Some(vec!["*".to_string()]));
+        assert_eq!(webhook.is_active, Some(false));
+        assert_eq!(webhook.retry_config, Some(RetryConfig::default()));
+        assert_eq!(
+            webhook.security_config.unwrap().signature_method,
+            "HmacSha256"
+        );
+        assert_eq!(WebhookInfo::from_scval(&ScVal::Void), None);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_update_signs_update_locally() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        mount_view(&server, "update_webhook", ScVal::Void).await;
+        expect_submissions(&server, 1).await;
+
+        let changes = stellopay_cli::commands::WebhookChanges {
+            events: Some("payroll_claimed_event, *".to_string()),
+            active: Some(false),
+            ..Default::default()
+        };
+        stellopay_cli::commands::webhook_update_command(
+            3,
+            changes,
+            Some(contract()),
+            &signing_config(&server),
+        )
         .await
-        .expect("query should succeed");
+        .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests
+            .iter()
+            .all(|r| !String::from_utf8_lossy(&r.body).contains(&secret())));
+        let calls = submitted_calls(&server).await;
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "update_webhook");
+        assert_eq!(
+            calls[0].1,
+            vec![
+                ScVal::from(3_u64),
+                contract_struct(vec![
+                    ("name", ScVal::Void),
+                    ("description", ScVal::Void),
+                    ("url", ScVal::Void),
+                    (
+                        "events",
+                        contract_vec(vec![
+                            rpc::string_arg("payroll_claimed_event").unwrap(),
+                            rpc::string_arg("*").unwrap(),
+                        ]),
+                    ),
+                    ("is_active", false.into()),
+                    ("secret_hash", ScVal::Void),
+                ]),
+            ]
+        );
+    }
 
-    assert_eq!(result, "ok");
+    #[tokio::test]
+    async fn test_relay_retries_with_backoff_then_reports_failure() {
+        let server = MockServer::start().await;
+        mount_relay(&server, 30).await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let args = stellopay_cli::WebhookRelayArgs {
+            no_report: true,
+            ..relay_args(&dir)
+        };
+        let mut relay =
+            stellopay_cli::commands::WebhookRelay::new(args, &make_mock_config(&server))
+                .await
+                .unwrap();
+
+        let first = relay.tick(1_000).await.unwrap();
+        assert!(first.results.is_empty());
+        assert_eq!(first.retrying, 1);
+
+        // Not due yet: the first retry waits `retry_delay` seconds.
+        let early = relay.tick(1_010).await.unwrap();
+        assert!(early.results.is_empty());
+        assert_eq!(early.retrying, 1);
+
+        let last = relay.tick(1_030).await.unwrap();
+        assert_eq!(last.retrying, 0);
+        assert_eq!(last.results.len(), 1);
+        assert!(!last.results[0].success);
+        assert_eq!(last.results[0].attempts, 2);
+        assert_eq!(last.results[0].status_code, Some(500));
+    }
 
-    let requests = server.received_requests().await.unwrap();
-    assert_eq!(requests.len(), 1);
-    let sent_body: serde_json::Value = requests[0].body_json().unwrap();
-    assert!(
-        sent_body.get("signer").is_none(),
-        "query request body must never include a signer field, got: {sent_body}"
-    );
-    assert_eq!(sent_body["read_only"], true);
-}
+    #[tokio::test]
+    async fn test_relay_drops_deliveries_over_daily_quota() {
+        let server = MockServer::start().await;
+        mount_relay(&server, 0).await;
+        mount_view(
+            &server,
+            "get_webhook_usage",
+            contract_struct(vec![
+                ("endpoints", 2_u64.into()),
+                ("max_endpoints", 5_u64.into()),
+                ("events_today", 1000_u64.into()),
+                ("max_events_per_day", 1000_u64.into()),
+                ("day", 19_675_u64.into()),
+                ("total_events", 48_210_u64.into()),
+            ]),
+        )
+        .await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+        expect_submissions(&server, 0).await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut relay =
+            stellopay_cli::commands::WebhookRelay::new(relay_args(&dir), &signing_config(&server))
+                .await
+                .unwrap();
+        let tick = relay.tick(1_700_000_000).await.unwrap();
+
+        assert_eq!(tick.throttled, 1);
+        assert!(tick.results.is_empty());
+        assert_eq!(tick.retrying, 0);
+    }
 
-#[tokio::test]
-async fn test_query_as_deserializes_into_typed_struct() {
-    use stellopay_cli::utils::WebhookInfo;
+    #[test]
+    fn test_webhook_usage_decodes_unset_quotas() {
+        use stellopay_cli::utils::WebhookUsage;
+
+        let usage = WebhookUsage::from_scval(&contract_struct(vec![
+            ("endpoints", 2_u64.into()),
+            ("max_endpoints", ScVal::Void),
+            ("events_today", 7_u64.into()),
+            ("max_events_per_day", 1000_u64.into()),
+            ("day", 19_675_u64.into()),
+            ("total_events", 48_210_u64.into()),
+        ]))
+        .unwrap();
+        assert_eq!(usage.max_endpoints, None);
+        assert_eq!(usage.events_left_today(), Some(993));
+        assert_eq!(usage.total_events, Some(48_210));
+    }
 
-    let server = MockServer::start().await;
+    fn agreement(id: u128, status: &str, created_at: u64, activated_at: Option<u64>) -> ScVal {
+        contract_struct(vec![
+            ("id", id.into()),
+            ("employer", rpc::address_arg(VALID_OWNER).unwrap()),
+            ("token", rpc::address_arg(&token()).unwrap()),
+            ("status", rpc::enum_key(status).unwrap()),
+            ("created_at", created_at.into()),
+            (
+                "activated_at",
+                activated_at.map_or(ScVal::Void, ScVal::from),
+            ),
+            ("cancelled_at", ScVal::Void),
+        ])
+    }
+
+    async fn mount_contract_health(server: &MockServer, pause_info: ScVal) {
+        mount_rpc(
+            server,
+            "getLatestLedger",
+            serde_json::json!({ "sequence": 100 }),
+        )
+        .await;
+        let owner = LedgerEntryData::ContractData(ContractDataEntry {
+            ext: ExtensionPoint::V0,
+            contract: contract().parse().unwrap(),
+            key: rpc::enum_key("Owner").unwrap(),
+            durability: ContractDataDurability::Persistent,
+            val: rpc::address_arg(VALID_OWNER).unwrap(),
+        });
+        mount_rpc(server, "getLedgerEntries", ledger_entry(owner)).await;
+        mount_view(server, "get_pause_info", pause_info).await;
+
+        let agreements = [
+            agreement(1, "Active", 1_000, Some(2_000)),
+            agreement(2, "Completed", 1_500, None),
+        ];
+        for (index, value) in agreements.into_iter().enumerate() {
+            Mock::given(method("POST"))
+                .and(SimulatesCall {
+                    function: "get_agreement",
+                    arg: Some((index as u128 + 1).into()),
+                })
+                .respond_with(simulation_result(value))
+                .mount(server)
+                .await;
+        }
+        for id in 3..=10u128 {
+            Mock::given(method("POST"))
+                .and(SimulatesCall {
+                    function: "get_agreement",
+                    arg: Some(id.into()),
+                })
+                .respond_with(simulation_result(ScVal::Void))
+                .mount(server)
+                .await;
+        }
+
+        mount_view(server, "balance", ScVal::from(750_i128)).await;
+        mount_view(server, "get_audit_entry_count", ScVal::U64(4)).await;
+        mount_view(
+            server,
+            "get_audit_entry",
+            contract_struct(vec![("id", 4u64.into()), ("timestamp", 3_000u64.into())]),
+        )
+        .await;
+    }
+
+    fn pause_info(paused: bool, reason: Option<&str>) -> ScVal {
+        contract_struct(vec![
+            ("is_paused", ScVal::Bool(paused)),
+            (
+                "paused_at",
+                if paused { 2_500u64.into() } else { ScVal::Void },
+            ),
+            ("paused_by", ScVal::Void),
+            ("resume_at", ScVal::Void),
+            (
+                "reason",
+                reason.map_or(ScVal::Void, |r| rpc::string_arg(r).unwrap()),
+            ),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_info_reports_contract_status_and_health() {
+        let server = MockServer::start().await;
+        mount_contract_health(&server, pause_info(false, None)).await;
+
+        let report = stellopay_cli::commands::fetch_contract_health(
+            &std::sync::Arc::new(client(&server)),
+            &contract(),
+        )
+        .await
+        .unwrap();
+
+        let status = &report.status;
+        assert_eq!(status.owner.as_deref(), Some(VALID_OWNER));
+        assert!(!status.is_paused);
+        assert_eq!(status.active_payrolls, 1);
+        assert_eq!(status.supported_tokens, vec![token()]);
+        assert_eq!(status.total_locked_value.get(&token()), Some(&750));
+        assert_eq!(status.last_activity, 3_000);
+        assert_eq!(status.circuit_breakers.len(), 1);
+        assert!(!status.circuit_breakers[0].tripped);
+
+        assert!(
+            report.health.is_healthy,
+            "issues: {:?}",
+            report.health.issues
+        );
+        assert_eq!(report.health.success_rate, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_info_fails_when_contract_is_paused() {
+        let server = MockServer::start().await;
+        mount_contract_health(&server, pause_info(true, Some("incident"))).await;
+        let mut config = make_mock_config(&server);
+        config.network.network_passphrase = Network::Testnet.passphrase().to_string();
+
+        let report = stellopay_cli::commands::fetch_contract_health(
+            &std::sync::Arc::new(client(&server)),
+            &contract(),
+        )
+        .await
+        .unwrap();
+        assert!(report.status.is_paused);
+        assert_eq!(report.status.circuit_breakers[0].tripped_at, Some(2_500));
+        assert!(!report.health.is_healthy);
+        assert_eq!(
+            report.health.issues,
+            vec!["Emergency pause is active: incident".to_string()]
+        );
+
+        let err = stellopay_cli::commands::info_command(
+            Some(contract()),
+            stellopay_cli::OutputFormat::Json,
+            &config,
+        )
+        .await
+        .expect_err("an unhealthy contract must fail the command");
+        assert!(err.to_string().contains("is unhealthy"));
+    }
+
+    #[tokio::test]
+    async fn test_info_reports_unreachable_rpc() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let report = stellopay_cli::commands::fetch_contract_health(
+            &std::sync::Arc::new(client(&server)),
+            &contract(),
+        )
+        .await
+        .unwrap();
+        assert!(!report.health.is_healthy);
+        assert_eq!(report.health.error_rate, 1.0);
+        assert!(report.health.issues[0].contains("latest ledger"));
+    }
+
+    fn incentive(
+        kind: &str,
+        status: &str,
+        total_payouts: u32,
+        claimed_payouts: u32,
+        interval_seconds: u64,
+    ) -> stellopay_cli::commands::BonusIncentive {
+        stellopay_cli::commands::BonusIncentive {
+            id: 3,
+            employer: VALID_OWNER.to_string(),
+            employee: VALID_OWNER.to_string(),
+            approver: signer_address(),
+            token: token(),
+            kind: kind.to_string(),
+            status: status.to_string(),
+            amount_per_payout: 100_0000000,
+            total_payouts,
+            claimed_payouts,
+            start_time: 1_000,
+            interval_seconds,
+        }
+    }
+
+    fn incentive_scval(incentive: &stellopay_cli::commands::BonusIncentive) -> ScVal {
+        contract_struct(vec![
+            ("id", incentive.id.into()),
+            ("employer", rpc::address_arg(&incentive.employer).unwrap()),
+            ("employee", rpc::address_arg(&incentive.employee).unwrap()),
+            ("approver", rpc::address_arg(&incentive.approver).unwrap()),
+            ("token", rpc::address_arg(&incentive.token).unwrap()),
+            ("kind", rpc::enum_key(&incentive.kind).unwrap()),
+            ("status", rpc::enum_key(&incentive.status).unwrap()),
+            ("amount_per_payout", incentive.amount_per_payout.into()),
+            ("total_payouts", incentive.total_payouts.into()),
+            ("claimed_payouts", incentive.claimed_payouts.into()),
+            ("start_time", incentive.start_time.into()),
+            ("interval_seconds", incentive.interval_seconds.into()),
+        ])
+    }
+
+    fn signing_config(server: &MockServer) -> Config {
+        let mut config = make_mock_config(server);
+        config.auth.secret_key = Some(secret());
+        config
+    }
+
+    #[test]
+    fn test_bonus_preview_matches_contract_vesting() {
+        let recurring = incentive("Recurring", "Approved", 4, 1, 100);
+        assert_eq!(recurring.vested_payouts(999), 0);
+        assert_eq!(recurring.claimable_payouts(1_000), 0);
+        assert_eq!(recurring.vested_payouts(1_250), 3);
+        assert_eq!(recurring.claimable_payouts(1_250), 2);
+        assert_eq!(recurring.claimable_payouts(50_000), 3);
+        assert_eq!(recurring.payout_time(3), 1_300);
+
+        let pending = incentive("Recurring", "Pending", 4, 0, 100);
+        assert_eq!(pending.vested_payouts(1_250), 3);
+        assert_eq!(pending.claimable_payouts(1_250), 0);
+
+        let one_time = incentive("OneTime", "Approved", 1, 0, 0);
+        assert_eq!(one_time.claimable_payouts(999), 0);
+        assert_eq!(one_time.claimable_payouts(1_000), 1);
+        assert_eq!(
+            incentive("OneTime", "Approved", 1, 1, 0).claimable_payouts(5_000),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bonus_show_decodes_incentive() {
+        let server = MockServer::start().await;
+        let expected = incentive("Recurring", "Approved", 4, 1, 100);
+        mount_view(&server, "get_incentive", incentive_scval(&expected)).await;
+
+        let fetched =
+            stellopay_cli::commands::fetch_bonus_incentive(&client(&server), &contract(), 3)
+                .await
+                .unwrap();
+        assert_eq!(fetched, expected);
+    }
 
-    Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "result": {
-                "id": 7,
-                "name": "payroll-events",
-                "description": "notifies on payroll runs",
-                "url": "https://example.com/hook",
-                "events": ["payment.sent"],
-                "is_active": true
+    #[tokio::test]
+    async fn test_bonus_claim_rejects_signer_who_is_not_the_employee() {
+        let server = MockServer::start().await;
+        let record = incentive("OneTime", "Approved", 1, 0, 0);
+        mount_view(&server, "get_incentive", incentive_scval(&record)).await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({ "method": "sendTransaction" }),
+            ))
+            .respond_with(rpc_result(serde_json::json!({})))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let err = stellopay_cli::commands::bonus_command(
+            stellopay_cli::BonusCommands::Claim(stellopay_cli::BonusTarget {
+                id: 3,
+                contract_id: contract(),
+            }),
+            &signing_config(&server),
+        )
+        .await
+        .expect_err("only the employee may claim");
+        assert!(err.to_string().contains("Only the employee"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_bonus_approve_submits_as_approver() {
+        let server = MockServer::start().await;
+        let record = incentive("Recurring", "Pending", 4, 0, 100);
+        mount_view(&server, "get_incentive", incentive_scval(&record)).await;
+        mount_account(&server, 9).await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "approve_incentive",
+                arg: Some(rpc::address_arg(&signer_address()).unwrap()),
+            })
+            .respond_with(simulation_result(ScVal::Void))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({ "method": "sendTransaction" }),
+            ))
+            .respond_with(rpc_result(serde_json::json!({
+                "status": "PENDING", "hash": "b0b0", "latestLedger": 100
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        mount_rpc(
+            &server,
+            "getTransaction",
+            serde_json::json!({ "status": "SUCCESS", "ledger": 101 }),
+        )
+        .await;
+
+        stellopay_cli::commands::bonus_command(
+            stellopay_cli::BonusCommands::Approve(stellopay_cli::BonusTarget {
+                id: 3,
+                contract_id: contract(),
+            }),
+            &signing_config(&server),
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Matches a `getLedgerEntries` request for the contract data entry `key`.
+    struct ReadsContractData {
+        key: ScVal,
+    }
+
+    impl wiremock::Match for ReadsContractData {
+        fn matches(&self, request: &wiremock::Request) -> bool {
+            use stellar_xdr::curr::LedgerKey;
+
+            let Ok(body) = serde_json::from_slice::<serde_json::Value>(&request.body) else {
+                return false;
+            };
+            if body["method"] != "getLedgerEntries" {
+                return false;
             }
-        })))
-        .mount(&server)
+            match body["params"]["keys"][0]
+                .as_str()
+                .map(|xdr| LedgerKey::from_xdr_base64(xdr, Limits::none()))
+            {
+                Some(Ok(LedgerKey::ContractData(entry))) => entry.key == self.key,
+                _ => false,
+            }
+        }
+    }
+
+    async fn mount_escrow(server: &MockServer, balance: i128, manager: &str) {
+        mount_view(server, "get_agreement_balance", ScVal::from(balance)).await;
+        mount_view(
+            server,
+            "get_agreement_employer",
+            rpc::address_arg(VALID_OWNER).unwrap(),
+        )
+        .await;
+        for (name, value) in [("Token", token()), ("Manager", manager.to_string())] {
+            let key = rpc::enum_key(name).unwrap();
+            let entry = LedgerEntryData::ContractData(ContractDataEntry {
+                ext: ExtensionPoint::V0,
+                contract: contract().parse().unwrap(),
+                key: key.clone(),
+                durability: ContractDataDurability::Persistent,
+                val: rpc::address_arg(&value).unwrap(),
+            });
+            Mock::given(method("POST"))
+                .and(ReadsContractData { key })
+                .respond_with(rpc_result(ledger_entry(entry)))
+                .mount(server)
+                .await;
+        }
+    }
+
+    fn escrow_target() -> stellopay_cli::EscrowTarget {
+        stellopay_cli::EscrowTarget {
+            agreement_id: 7,
+            contract_id: contract(),
+        }
+    }
+
+    async fn expect_submissions(server: &MockServer, count: u64) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                serde_json::json!({ "method": "sendTransaction" }),
+            ))
+            .respond_with(rpc_result(serde_json::json!({
+                "status": "PENDING", "hash": "e5c0", "latestLedger": 100
+            })))
+            .expect(count)
+            .mount(server)
+            .await;
+        mount_rpc(
+            server,
+            "getTransaction",
+            serde_json::json!({ "status": "SUCCESS", "ledger": 101 }),
+        )
         .await;
+    }
+
+    /// Mounts the reads `emergency-withdraw` makes before submitting: the
+    /// stored owner (the signer), the pause flag, and a balance of 5000.
+    async fn mount_emergency_preflight(server: &MockServer) {
+        let owner_key =
+            stellar_xdr::curr::LedgerKey::ContractData(stellar_xdr::curr::LedgerKeyContractData {
+                contract: contract().parse().unwrap(),
+                key: rpc::enum_key("Owner").unwrap(),
+                durability: ContractDataDurability::Persistent,
+            });
+        let owner = LedgerEntryData::ContractData(ContractDataEntry {
+            ext: ExtensionPoint::V0,
+            contract: contract().parse().unwrap(),
+            key: rpc::enum_key("Owner").unwrap(),
+            durability: ContractDataDurability::Persistent,
+            val: rpc::address_arg(&signer_address()).unwrap(),
+        });
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "method": "getLedgerEntries",
+                "params": { "keys": [owner_key.to_xdr_base64(Limits::none()).unwrap()] }
+            })))
+            .respond_with(rpc_result(ledger_entry(owner)))
+            .mount(server)
+            .await;
+        mount_account(server, 7).await;
+        mount_view(server, "is_emergency_paused", ScVal::Bool(false)).await;
+        mount_view(server, "balance", ScVal::from(5_000_i128)).await;
+        mount_view(server, "decimals", 7u32.into()).await;
+        mount_simulation(server, "100", ScVal::Void).await;
+    }
 
-    let client = SorobanHttpClient::new(&server.uri());
-    let webhook: WebhookInfo = client
-        .query_as(VALID_CONTRACT, "get_webhook", vec![("webhook_id", "7")])
+    fn emergency_args(
+        dir: &tempfile::TempDir,
+        confirm_recipient: &str,
+    ) -> stellopay_cli::EmergencyWithdrawArgs {
+        stellopay_cli::EmergencyWithdrawArgs {
+            contract_id: Some(contract()),
+            token: token(),
+            recipient: VALID_OWNER.to_string(),
+            amount: 1_000,
+            reason: "Compromised payroll key".to_string(),
+            confirm_recipient: Some(confirm_recipient.to_string()),
+            audit_log: dir.path().join("audit.jsonl"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_emergency_withdraw_submits_and_writes_audit_entry() {
+        let server = MockServer::start().await;
+        mount_emergency_preflight(&server).await;
+        expect_submissions(&server, 1).await;
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = signing_config(&server);
+        config.assume_yes = true;
+
+        stellopay_cli::commands::emergency_withdraw_command(
+            emergency_args(&dir, VALID_OWNER),
+            &config,
+            false,
+        )
         .await
-        .expect("typed query should succeed");
+        .unwrap();
+
+        let log = std::fs::read_to_string(dir.path().join("audit.jsonl")).unwrap();
+        let entries: Vec<stellopay_cli::commands::EmergencyAuditEntry> = log
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].status, "confirmed");
+        assert_eq!(entries[0].transaction.as_deref(), Some("e5c0"));
+        assert_eq!(entries[0].reason, "Compromised payroll key");
+        assert_eq!(entries[0].amount, "1000");
+        assert_eq!(entries[0].signer, signer_address());
+    }
 
-    assert_eq!(webhook.id, Some(7));
-    assert_eq!(webhook.name, Some("payroll-events".to_string()));
-    assert_eq!(webhook.is_active, Some(true));
-    assert_eq!(webhook.events, Some(vec!["payment.sent".to_string()]));
-}
+    #[tokio::test]
+    async fn test_emergency_withdraw_stops_when_recipient_is_not_confirmed() {
+        let server = MockServer::start().await;
+        mount_emergency_preflight(&server).await;
+        expect_submissions(&server, 0).await;
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut config = signing_config(&server);
+        config.assume_yes = true;
+
+        let err = stellopay_cli::commands::emergency_withdraw_command(
+            emergency_args(&dir, &signer_address()),
+            &config,
+            false,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("does not match the recipient"));
+        assert!(!dir.path().join("audit.jsonl").exists());
+
+        let mut args = emergency_args(&dir, VALID_OWNER);
+        args.reason = "  ".to_string();
+        let err = stellopay_cli::commands::emergency_withdraw_command(args, &config, false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("--reason"));
+    }
 
-#[tokio::test]
-async fn test_query_as_tolerates_missing_optional_fields() {
-    use stellopay_cli::utils::WebhookInfo;
+    fn payroll_template(activate: bool) -> PayrollTemplate {
+        PayrollTemplate {
+            name: "engineering".to_string(),
+            description: None,
+            token: Some(token()),
+            salary_per_period: "2500".to_string(),
+            grace_period_seconds: 86_400,
+            activate,
+        }
+    }
 
-    let server = MockServer::start().await;
+    #[tokio::test]
+    async fn test_template_apply_creates_agreement_and_adds_employees() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        let agreement = ScVal::from(5u128);
+        for (function, arg, retval, calls) in [
+            (
+                "create_payroll_agreement",
+                rpc::address_arg(&signer_address()).unwrap(),
+                agreement.clone(),
+                1,
+            ),
+            (
+                "add_employee_to_agreement",
+                agreement.clone(),
+                ScVal::Void,
+                2,
+            ),
+            ("activate_agreement", agreement.clone(), ScVal::Void, 1),
+        ] {
+            Mock::given(method("POST"))
+                .and(SimulatesCall {
+                    function,
+                    arg: Some(arg),
+                })
+                .respond_with(simulation_result(retval))
+                .expect(calls)
+                .mount(&server)
+                .await;
+        }
+        mount_rpc(
+            &server,
+            "getTransaction",
+            serde_json::json!({
+                "status": "SUCCESS",
+                "ledger": 101,
+                "returnValue": agreement.to_xdr_base64(Limits::none()).unwrap()
+            }),
+        )
+        .await;
+        // create, two employees, activate
+        expect_submissions(&server, 4).await;
+
+        let entries =
+            parse_employee_csv(&format!("{}\n{},3000\n", VALID_OWNER, signer_address())).unwrap();
+        stellopay_cli::commands::template_apply_command(
+            &payroll_template(true),
+            entries,
+            Some(contract()),
+            &signing_config(&server),
+        )
+        .await
+        .unwrap();
+    }
 
-    Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "result": { "id": 9 }
-        })))
-        .mount(&server)
+    #[tokio::test]
+    async fn test_template_apply_rejects_bad_employees_before_submitting() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        mount_simulation(&server, "0", ScVal::from(5u128)).await;
+        expect_submissions(&server, 0).await;
+        let mut config = signing_config(&server);
+
+        let duplicate = parse_employee_csv(&format!("{}\n{}\n", VALID_OWNER, VALID_OWNER)).unwrap();
+        let err = stellopay_cli::commands::template_apply_command(
+            &payroll_template(false),
+            duplicate,
+            Some(contract()),
+            &config,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("listed more than once"));
+
+        let mut no_token = payroll_template(false);
+        no_token.token = None;
+        config.defaults.token = None;
+        let err = stellopay_cli::commands::template_apply_command(
+            &no_token,
+            parse_employee_csv(VALID_OWNER).unwrap(),
+            Some(contract()),
+            &config,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("has no token"));
+    }
+
+    #[tokio::test]
+    async fn test_employee_create_creates_and_activates_agreement() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        let agreement = ScVal::from(5u128);
+        for (function, arg, retval) in [
+            (
+                "create_payroll_agreement",
+                rpc::address_arg(&signer_address()).unwrap(),
+                agreement.clone(),
+            ),
+            ("add_employee_to_agreement", agreement.clone(), ScVal::Void),
+            ("activate_agreement", agreement.clone(), ScVal::Void),
+        ] {
+            Mock::given(method("POST"))
+                .and(SimulatesCall {
+                    function,
+                    arg: Some(arg),
+                })
+                .respond_with(simulation_result(retval))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        mount_rpc(
+            &server,
+            "getTransaction",
+            serde_json::json!({
+                "status": "SUCCESS",
+                "ledger": 101,
+                "returnValue": agreement.to_xdr_base64(Limits::none()).unwrap()
+            }),
+        )
         .await;
+        expect_submissions(&server, 3).await;
+
+        stellopay_cli::commands::employee_command(
+            stellopay_cli::EmployeeCommands::Create {
+                payroll: stellopay_cli::EmployeePayrollArgs {
+                    employee: VALID_OWNER.to_string(),
+                    contract_id: Some(contract()),
+                    token: Some(token()),
+                    ..super::payroll_args(Some("1500.5"), None)
+                },
+            },
+            &signing_config(&server),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_employee_pause_signs_pause_agreement_locally() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "pause_agreement",
+                arg: Some(7u128.into()),
+            })
+            .respond_with(simulation_result(ScVal::Void))
+            .expect(1)
+            .mount(&server)
+            .await;
+        expect_submissions(&server, 1).await;
+
+        stellopay_cli::commands::employee_command(
+            stellopay_cli::EmployeeCommands::Pause {
+                target: stellopay_cli::EmployeeAgreementTarget {
+                    agreement_id: 7,
+                    contract_id: Some(contract()),
+                },
+            },
+            &signing_config(&server),
+        )
+        .await
+        .unwrap();
+
+        // Only the signed envelope leaves the machine, never the secret key.
+        for request in server.received_requests().await.unwrap() {
+            assert!(!String::from_utf8_lossy(&request.body).contains(&secret()));
+        }
+    }
 
-    let client = SorobanHttpClient::new(&server.uri());
-    let webhook: WebhookInfo = client
-        .query_as(VALID_CONTRACT, "get_webhook", vec![("webhook_id", "9")])
+    #[tokio::test]
+    async fn test_employee_show_reads_payroll_for_agreement() {
+        use stellopay_cli::commands::EmployeePayroll;
+
+        let payroll = contract_struct(vec![
+            ("agreement_id", 7u128.into()),
+            ("employee_index", 0u32.into()),
+            ("employer", rpc::address_arg(&signer_address()).unwrap()),
+            ("token", rpc::address_arg(&token()).unwrap()),
+            ("status", rpc::enum_key("Active").unwrap()),
+            ("salary_per_period", 25_000_000i128.into()),
+            ("period_seconds", 2_592_000u64.into()),
+            ("claimed_periods", 2u32.into()),
+            ("activated_at", 1_000u64.into()),
+        ]);
+        assert_eq!(
+            EmployeePayroll::from_scval(&payroll),
+            Some(EmployeePayroll {
+                agreement_id: 7,
+                employee_index: 0,
+                employer: signer_address(),
+                token: token(),
+                status: "Active".to_string(),
+                salary_per_period: Some(25_000_000),
+                period_seconds: 2_592_000,
+                claimed_periods: 2,
+                activated_at: 1_000,
+            })
+        );
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "get_payroll",
+                arg: Some(rpc::address_arg(VALID_OWNER).unwrap()),
+            })
+            .respond_with(simulation_result(payroll))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        stellopay_cli::commands::employee_show_command(
+            stellopay_cli::EmployeeTarget {
+                employee: VALID_OWNER.to_string(),
+                agreement_id: Some(7),
+                contract_id: Some(contract()),
+            },
+            &make_mock_config(&server),
+        )
         .await
-        .expect("typed query should tolerate missing optional fields");
+        .unwrap();
+    }
 
-    assert_eq!(webhook.id, Some(9));
-    assert_eq!(webhook.name, None);
-    assert_eq!(webhook.retry_config, None);
-}
+    fn bulk_pay_args(
+        dir: &tempfile::TempDir,
+        rows: &[String],
+        retries: u32,
+    ) -> stellopay_cli::BulkPayArgs {
+        let employees = dir.path().join("employees.csv");
+        std::fs::write(&employees, format!("address,amount\n{}\n", rows.join("\n"))).unwrap();
+        stellopay_cli::BulkPayArgs {
+            employees,
+            token: Some(token()),
+            retries,
+            retry_delay_ms: 0,
+        }
+    }
 
-#[tokio::test]
-async fn test_query_as_returns_err_on_shape_mismatch() {
-    use stellopay_cli::utils::WebhookStats;
+    fn second_employee() -> String {
+        stellar_strkey::ed25519::PublicKey([3u8; 32]).to_string()
+    }
 
-    let server = MockServer::start().await;
+    #[tokio::test]
+    async fn test_bulk_pay_transfers_to_each_employee() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        mount_view(&server, "balance", ScVal::from(1_000_000_000_i128)).await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "transfer",
+                arg: Some(rpc::address_arg(&signer_address()).unwrap()),
+            })
+            .respond_with(simulation_result(ScVal::Void))
+            .expect(2)
+            .mount(&server)
+            .await;
+        expect_submissions(&server, 2).await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let rows = [
+            format!("{VALID_OWNER},10"),
+            format!("{},2.5", second_employee()),
+        ];
+        let results = stellopay_cli::commands::bulk_pay(
+            &bulk_pay_args(&dir, &rows, 0),
+            &signing_config(&server),
+        )
+        .await
+        .unwrap();
 
-    Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-            "result": { "total_webhooks": "not-a-number" }
-        })))
-        .mount(&server)
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.error.is_none() && r.attempts == 1));
+        assert_eq!(results[0].entry.amount, Some(100_000_000));
+        assert_eq!(results[1].entry.amount, Some(25_000_000));
+    }
+
+    /// Mounts a `transfer` simulation that fails with a contract error,
+    /// `times` times when given.
+    async fn mount_failing_transfer(server: &MockServer, times: Option<u64>) {
+        let mock = Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "transfer",
+                arg: None,
+            })
+            .respond_with(rpc_result(serde_json::json!({
+                "error": "HostError: Error(Contract, #10)", "latestLedger": 100
+            })));
+        match times {
+            Some(times) => mock.up_to_n_times(times).mount(server).await,
+            None => mock.mount(server).await,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk_pay_retries_failed_payment() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        mount_view(&server, "balance", ScVal::from(1_000_000_000_i128)).await;
+        mount_failing_transfer(&server, Some(1)).await;
+        mount_view(&server, "transfer", ScVal::Void).await;
+        expect_submissions(&server, 1).await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let results = stellopay_cli::commands::bulk_pay(
+            &bulk_pay_args(&dir, &[format!("{VALID_OWNER},10")], 2),
+            &signing_config(&server),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(results[0].attempts, 2);
+        assert_eq!(results[0].error, None);
+    }
+
+    #[tokio::test]
+    async fn test_pay_command_reports_failure_after_retries() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        mount_view(&server, "balance", ScVal::from(1_000_000_000_i128)).await;
+        mount_failing_transfer(&server, None).await;
+        expect_submissions(&server, 0).await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let args = bulk_pay_args(&dir, &[format!("{VALID_OWNER},10")], 1);
+        let config = signing_config(&server);
+
+        let results = stellopay_cli::commands::bulk_pay(&args, &config)
+            .await
+            .expect("failures are reported per employee");
+        assert_eq!(results[0].attempts, 2);
+        assert!(results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("Error(Contract, #10)"));
+
+        let err = stellopay_cli::commands::pay_command(args, &config)
+            .await
+            .expect_err("a failed payment should fail the command");
+        assert!(err.to_string().contains("1 of 1 payments failed"));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_pay_checks_amounts_and_balance_before_submitting() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        mount_view(&server, "balance", ScVal::from(50_000_000_i128)).await;
+        expect_submissions(&server, 0).await;
+        let config = signing_config(&server);
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let missing = [format!("{VALID_OWNER},10"), second_employee()];
+        let err = stellopay_cli::commands::bulk_pay(&bulk_pay_args(&dir, &missing, 0), &config)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No amount given"));
+
+        let err = stellopay_cli::commands::bulk_pay(
+            &bulk_pay_args(&dir, &[format!("{VALID_OWNER},10")], 0),
+            &config,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("cannot pay 10"));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_show_decodes_entries_in_key_order() {
+        use stellar_xdr::curr::{ScMap, ScMapEntry, ScString};
+
+        let string = |s: &str| ScVal::String(ScString(s.try_into().unwrap()));
+        let server = MockServer::start().await;
+        mount_view(
+            &server,
+            "list_employer_metadata",
+            ScVal::Map(Some(ScMap(
+                vec![
+                    ScMapEntry {
+                        key: string("cost_center"),
+                        val: string("CC-42"),
+                    },
+                    ScMapEntry {
+                        key: string("erp_id"),
+                        val: string("V-1001"),
+                    },
+                ]
+                .try_into()
+                .unwrap(),
+            ))),
+        )
         .await;
 
-    let client = SorobanHttpClient::new(&server.uri());
-    let err = client
-        .query_as::<WebhookStats>(VALID_CONTRACT, "get_webhook_stats", vec![])
+        let entries = stellopay_cli::commands::fetch_employer_metadata(
+            &client(&server),
+            &contract(),
+            VALID_OWNER,
+            None,
+        )
         .await
-        .expect_err("a type mismatch in the result shape should surface as Err");
+        .unwrap();
+        assert_eq!(
+            entries.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("cost_center".to_string(), "CC-42".to_string()),
+                ("erp_id".to_string(), "V-1001".to_string()),
+            ]
+        );
+    }
 
-    assert!(
-        err.to_string().contains("did not match expected shape"),
-        "expected shape-mismatch error, got: {err}"
-    );
+    #[tokio::test]
+    async fn test_calendar_decodes_payouts_and_exports_ical() {
+        let server = MockServer::start().await;
+        let token = stellar_strkey::Contract([2u8; 32]).to_string();
+        mount_view(
+            &server,
+            "get_payout_calendar",
+            contract_vec(vec![contract_struct(vec![
+                ("agreement_id", 7u128.into()),
+                ("employee", rpc::address_arg(VALID_OWNER).unwrap()),
+                ("token", rpc::address_arg(&token).unwrap()),
+                ("period", ScVal::U32(3)),
+                ("due_at", JAN_31_NOON.into()),
+                ("payable_at", FEB_01.into()),
+                ("amount", 25_000_000i128.into()),
+            ])]),
+        )
+        .await;
+
+        let payouts = stellopay_cli::commands::fetch_payout_calendar(
+            &client(&server),
+            &contract(),
+            VALID_OWNER,
+            JAN_15,
+            FEB_01 + 86_400,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            payouts,
+            vec![stellopay_cli::commands::CalendarPayout {
+                agreement_id: 7,
+                employee: VALID_OWNER.to_string(),
+                token: token.clone(),
+                period: 3,
+                due_at: JAN_31_NOON,
+                payable_at: FEB_01,
+                amount: 25_000_000,
+            }]
+        );
+
+        let ics = stellopay_cli::commands::payout_calendar_ics(VALID_OWNER, &payouts, JAN_15);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(ics.contains("\r\nDTSTAMP:20240115T000000Z\r\n"));
+        assert!(ics.contains("\r\nDTSTART:20240201T000000Z\r\n"));
+        assert!(ics.contains("\r\nSUMMARY:Payout of 2.5 to GAAAAA...AAAWHF\r\n"));
+        // Long lines are folded at 75 octets
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("UID:7-{}-3@stellopay\r\n", VALID_OWNER)));
+        assert!(unfolded.contains(&format!(
+            "\\nToken {}\\nDue 2024-01-31T12:00:00+00:00",
+            token
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_escrow_balance_reads_agreement_and_contract_state() {
+        let server = MockServer::start().await;
+        mount_escrow(&server, 5_000, VALID_OWNER).await;
+
+        let state = stellopay_cli::commands::fetch_escrow_state(&client(&server), &contract(), 7)
+            .await
+            .unwrap();
+        assert_eq!(
+            state,
+            stellopay_cli::commands::EscrowState {
+                agreement_id: 7,
+                balance: 5_000,
+                employer: Some(VALID_OWNER.to_string()),
+                token: Some(token()),
+                manager: Some(VALID_OWNER.to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_escrow_release_requires_manager_and_balance() {
+        let server = MockServer::start().await;
+        mount_escrow(&server, 5_000, VALID_OWNER).await;
+        expect_submissions(&server, 0).await;
+        let config = signing_config(&server);
+
+        let err = stellopay_cli::commands::escrow_release_command(
+            escrow_target(),
+            VALID_OWNER,
+            "0.0001",
+            &config,
+            true,
+        )
+        .await
+        .expect_err("signer is not the manager");
+        assert!(
+            err.to_string().contains("Only the escrow manager"),
+            "got: {err}"
+        );
+
+        let server = MockServer::start().await;
+        mount_escrow(&server, 5_000, &signer_address()).await;
+        expect_submissions(&server, 0).await;
+        let err = stellopay_cli::commands::escrow_release_command(
+            escrow_target(),
+            VALID_OWNER,
+            "1",
+            &signing_config(&server),
+            true,
+        )
+        .await
+        .expect_err("release exceeds the balance");
+        assert!(
+            err.to_string().contains("Insufficient escrow balance"),
+            "got: {err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_escrow_refund_with_yes_submits_as_manager() {
+        let server = MockServer::start().await;
+        mount_escrow(&server, 5_000, &signer_address()).await;
+        mount_account(&server, 9).await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "refund_remaining",
+                arg: Some(rpc::address_arg(&signer_address()).unwrap()),
+            })
+            .respond_with(simulation_result(ScVal::Void))
+            .expect(1)
+            .mount(&server)
+            .await;
+        expect_submissions(&server, 1).await;
+
+        stellopay_cli::commands::escrow_refund_command(
+            escrow_target(),
+            &signing_config(&server),
+            true,
+        )
+        .await
+        .unwrap();
+    }
+
+    // --- deploy ---
+
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+
+    fn deploy_args(dir: &TempDir, owner: &str, sha256: &str) -> stellopay_cli::DeployArgs {
+        let wasm = dir.path().join("stello_pay_contract.wasm");
+        std::fs::write(&wasm, WASM).unwrap();
+        stellopay_cli::DeployArgs {
+            owner: owner.to_string(),
+            wasm: Some(wasm),
+            wasm_url: None,
+            wasm_sha256: Some(sha256.to_string()),
+            no_save: false,
+        }
+    }
+
+    fn wasm_sha256() -> String {
+        use sha2::{Digest, Sha256};
+        hex::encode(Sha256::digest(WASM))
+    }
+
+    #[tokio::test]
+    async fn test_deploy_uploads_creates_initializes_and_saves_contract_id() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        Mock::given(method("POST"))
+            .and(SimulatesCall {
+                function: "initialize",
+                arg: Some(rpc::address_arg(&signer_address()).unwrap()),
+            })
+            .respond_with(simulation_result(ScVal::Void))
+            .expect(1)
+            .mount(&server)
+            .await;
+        mount_simulation(&server, "0", ScVal::Void).await;
+        // upload, create and initialize
+        expect_submissions(&server, 3).await;
+
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let config = signing_config(&server);
+        stellopay_cli::config::save_config(&config_path, &config)
+            .await
+            .unwrap();
+
+        stellopay_cli::commands::deploy_command(
+            deploy_args(&dir, &signer_address(), &wasm_sha256()),
+            &config,
+            &config_path,
+        )
+        .await
+        .unwrap();
+
+        let saved = load_config(&config_path).await.unwrap();
+        let contract_id = saved.contract.default_contract_id.unwrap();
+        assert_ne!(
+            Some(&contract_id),
+            config.contract.default_contract_id.as_ref()
+        );
+        assert!(rpc::contract_address(&contract_id).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_checks_owner_and_wasm_hash_before_uploading() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        mount_simulation(&server, "0", ScVal::Void).await;
+        expect_submissions(&server, 0).await;
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let config = signing_config(&server);
+
+        let err = stellopay_cli::commands::deploy_command(
+            deploy_args(&dir, VALID_OWNER, &wasm_sha256()),
+            &config,
+            &config_path,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("owner's authorization"),
+            "got: {err}"
+        );
+
+        let err = stellopay_cli::commands::deploy_command(
+            deploy_args(&dir, &signer_address(), &"0".repeat(64)),
+            &config,
+            &config_path,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("expected"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn test_deploy_suite_writes_manifest_and_reports_broken_wiring() {
+        let server = MockServer::start().await;
+        // Owner, admin and token read back as deployed. The manager and the
+        // history recorder point at an unrelated contract instead of the
+        // new payroll contract.
+        for (name, value) in [
+            ("Owner", signer_address()),
+            ("Admin", signer_address()),
+            ("Token", token()),
+            ("Manager", contract()),
+            ("PayrollContract", contract()),
+        ] {
+            let key = rpc::enum_key(name).unwrap();
+            let entry = LedgerEntryData::ContractData(ContractDataEntry {
+                ext: ExtensionPoint::V0,
+                contract: contract().parse().unwrap(),
+                key: key.clone(),
+                durability: ContractDataDurability::Persistent,
+                val: rpc::address_arg(&value).unwrap(),
+            });
+            Mock::given(method("POST"))
+                .and(ReadsContractData { key })
+                .respond_with(rpc_result(ledger_entry(entry)))
+                .mount(&server)
+                .await;
+        }
+        mount_account(&server, 9).await;
+        mount_simulation(&server, "0", ScVal::Void).await;
+        // upload, create and initialize for each of the four contracts
+        expect_submissions(&server, 12).await;
+
+        let dir = TempDir::new().unwrap();
+        for name in [
+            "stello_pay_contract",
+            "payroll_escrow",
+            "payment_history",
+            "bonus_system",
+        ] {
+            std::fs::write(dir.path().join(format!("{name}.wasm")), WASM).unwrap();
+        }
+        let manifest_path = dir.path().join("manifest.json");
+        let config_path = dir.path().join("config.toml");
+
+        let err = stellopay_cli::commands::deploy_suite_command(
+            stellopay_cli::DeploySuiteArgs {
+                owner: signer_address(),
+                token: Some(token()),
+                wasm_dir: dir.path().to_path_buf(),
+                manifest: manifest_path.clone(),
+                no_save: false,
+            },
+            &signing_config(&server),
+            &config_path,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Wiring check failed for escrow.Manager, payment_history.PayrollContract"
+        );
+        // Nothing is saved as the default contract when the wiring is wrong
+        assert!(!config_path.exists());
+
+        let manifest: stellopay_cli::commands::DeploymentManifest =
+            serde_json::from_str(&std::fs::read_to_string(manifest_path).unwrap()).unwrap();
+        assert_eq!(manifest.owner, signer_address());
+        assert_eq!(manifest.escrow_token, token());
+        assert_eq!(manifest.wiring.len(), 7);
+        let manager = manifest
+            .wiring
+            .iter()
+            .find(|check| check.key == "Manager")
+            .unwrap();
+        assert_eq!(manager.expected, manifest.payroll.contract_id);
+        assert!(!manager.ok);
+        let ids = [
+            &manifest.payroll.contract_id,
+            &manifest.escrow.contract_id,
+            &manifest.payment_history.contract_id,
+            &manifest.bonus_system.contract_id,
+        ];
+        assert!(ids.iter().all(|id| rpc::contract_address(id).is_ok()));
+        assert_ne!(ids[0], ids[1]);
+    }
+
+    // --- dry run and fee confirmation ---
+
+    #[tokio::test]
+    async fn test_dry_run_stops_after_simulation() {
+        let server = MockServer::start().await;
+        mount_escrow(&server, 5_000, &signer_address()).await;
+        mount_account(&server, 9).await;
+        mount_simulation(&server, "25000", ScVal::Void).await;
+        expect_submissions(&server, 0).await;
+        let mut config = signing_config(&server);
+        config.dry_run = true;
+
+        // No --yes: the escrow prompt is skipped because nothing is submitted
+        let err = stellopay_cli::commands::escrow_refund_command(escrow_target(), &config, false)
+            .await
+            .unwrap_err();
+        assert!(stellopay_cli::Error::is_dry_run(&err), "got: {err}");
+    }
+
+    #[test]
+    fn test_transaction_preview_reports_fee_in_xlm() {
+        let simulation = rpc::Simulation {
+            transaction_data: SorobanTransactionData::default(),
+            min_resource_fee: 25_000,
+            auth: Vec::new(),
+            return_value: Some(ScVal::U32(3)),
+        };
+        let preview = rpc::TransactionPreview::new(format!("claim on {}", contract()), &simulation);
+        assert_eq!(preview.total_fee, 25_100);
+
+        let text = preview.to_string();
+        assert!(text.contains("claim on"), "got: {text}");
+        assert!(text.contains("Result:       3"), "got: {text}");
+        assert!(
+            text.contains("0.00251 XLM (0.00001 inclusion + 0.0025 resource)"),
+            "got: {text}"
+        );
+    }
+
+    #[test]
+    fn test_fee_confirm_threshold_is_read_in_xlm() {
+        let mut config = make_config(None);
+        config.assume_yes = true;
+        config.defaults.fee_confirm_threshold = Some("0.001".to_string());
+        let policy = rpc::SubmitPolicy::from_config(&config);
+        assert_eq!(policy.confirm_fee_above, Some(10_000));
+        assert!(policy.assume_yes && !policy.dry_run);
+
+        // A threshold that cannot be parsed confirms everything
+        config.defaults.fee_confirm_threshold = Some("cheap".to_string());
+        assert_eq!(
+            rpc::SubmitPolicy::from_config(&config).confirm_fee_above,
+            Some(0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_previews_webhook_changes_without_submitting() {
+        let server = MockServer::start().await;
+        mount_account(&server, 9).await;
+        mount_view(&server, "delete_webhook", ScVal::Void).await;
+        expect_submissions(&server, 0).await;
+        let mut config = signing_config(&server);
+        config.dry_run = true;
+
+        let err = stellopay_cli::commands::webhook_delete_command(3, Some(contract()), &config)
+            .await
+            .unwrap_err();
+        assert!(Error::is_dry_run(&err));
+    }
+}
+
+// --- Keystore and external signer tests ---
+
+mod signing_keys {
+    use ed25519_dalek::Signer;
+    use stellar_xdr::curr::{ScVal, TransactionEnvelope};
+    use stellopay_cli::keystore::Keystore;
+    use stellopay_cli::rpc::{self, Network};
+    use stellopay_cli::{ExternalSigner, TransactionSigner};
+    use tempfile::TempDir;
+
+    const SEED: [u8; 32] = [9u8; 32];
+    const PASSPHRASE: &str = "correct horse battery staple";
+
+    fn secret() -> String {
+        stellar_strkey::ed25519::PrivateKey(SEED).to_string()
+    }
+
+    fn address() -> String {
+        rpc::public_key_strkey(&rpc::signing_key_from_secret(&secret()).unwrap())
+    }
+
+    // Real iteration counts make debug-build tests slow; the count is stored
+    // per key so decryption still exercises the recorded parameters.
+    fn keystore(dir: &TempDir) -> Keystore {
+        Keystore::new(dir.path()).with_iterations(1_000)
+    }
+
+    #[test]
+    fn test_imported_key_round_trips_and_is_encrypted_at_rest() {
+        let dir = TempDir::new().unwrap();
+        let keystore = keystore(&dir);
+
+        let key = keystore.import("payroll", &secret(), PASSPHRASE).unwrap();
+        assert_eq!(key.public_key, address());
+
+        let unlocked = keystore.unlock("payroll", PASSPHRASE).unwrap();
+        assert_eq!(unlocked.to_bytes(), SEED);
+
+        let on_disk = std::fs::read_to_string(dir.path().join("payroll.json")).unwrap();
+        assert!(!on_disk.contains(&secret()));
+        assert!(!on_disk.contains(&hex::encode(SEED)));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.path().join("payroll.json"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let keystore = keystore(&dir);
+        keystore.import("payroll", &secret(), PASSPHRASE).unwrap();
+
+        let err = keystore
+            .unlock("payroll", "not the passphrase")
+            .expect_err("wrong passphrase must not decrypt");
+        assert!(format!("{:#}", err).contains("Incorrect passphrase"));
+    }
+
+    #[test]
+    fn test_generate_and_list_keys() {
+        let dir = TempDir::new().unwrap();
+        let keystore = keystore(&dir);
+
+        let generated = keystore.generate("ops", PASSPHRASE).unwrap();
+        keystore.import("admin", &secret(), PASSPHRASE).unwrap();
+        assert!(keystore.import("admin", &secret(), PASSPHRASE).is_err());
+        assert!(keystore.generate("../escape", PASSPHRASE).is_err());
+
+        let names: Vec<(String, String)> = keystore
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|key| (key.name, key.public_key))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("admin".to_string(), address()),
+                ("ops".to_string(), generated.public_key.clone()),
+            ]
+        );
+        let unlocked = keystore.unlock("ops", PASSPHRASE).unwrap();
+        assert_eq!(rpc::public_key_strkey(&unlocked), generated.public_key);
+    }
+
+    /// Writes an external signer script that reports `address` and answers
+    /// `sign` with `signature`, but only for the expected transaction hash.
+    #[cfg(unix)]
+    fn signer_script(dir: &TempDir, address: &str, hash: &str, signature: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.path().join("signer.sh");
+        std::fs::write(
+            &path,
+            format!(
+                "#!/bin/sh\n\
+                 case \"$1\" in\n\
+                 public-key) echo {address} ;;\n\
+                 sign) read hash; [ \"$hash\" = {hash} ] || exit 1;\n\
+                 [ -n \"$STELLOPAY_SIGN_TRANSACTION\" ] || exit 1; echo {signature} ;;\n\
+                 *) exit 2 ;;\n\
+                 esac\n"
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_external_signer_signs_transaction_hash() {
+        let dir = TempDir::new().unwrap();
+        let local = rpc::signing_key_from_secret(&secret()).unwrap();
+        let tx = rpc::build_invoke_transaction(
+            local.verifying_key().to_bytes(),
+            7,
+            &stellar_strkey::Contract([1u8; 32]).to_string(),
+            "initialize",
+            vec![ScVal::Bool(true)],
+        )
+        .unwrap();
+        let hash = rpc::transaction_hash(&tx, Network::Testnet.passphrase()).unwrap();
+        let signature = hex::encode(local.sign(&hash).to_bytes());
+
+        let command = signer_script(&dir, &address(), &hex::encode(hash), &signature);
+        let signer = TransactionSigner::External(ExternalSigner::connect(&command).await.unwrap());
+        assert_eq!(signer.address(), address());
+
+        let TransactionEnvelope::Tx(envelope) = signer
+            .sign_transaction(tx, Network::Testnet.passphrase())
+            .await
+            .unwrap()
+        else {
+            panic!("expected a v1 envelope");
+        };
+        assert_eq!(
+            hex::encode(envelope.signatures[0].signature.0.as_slice()),
+            signature
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_external_signer_signature_is_verified() {
+        let dir = TempDir::new().unwrap();
+        let tx = rpc::build_invoke_transaction(
+            rpc::signing_key_from_secret(&secret())
+                .unwrap()
+                .verifying_key()
+                .to_bytes(),
+            7,
+            &stellar_strkey::Contract([1u8; 32]).to_string(),
+            "initialize",
+            vec![],
+        )
+        .unwrap();
+        let hash = rpc::transaction_hash(&tx, Network::Testnet.passphrase()).unwrap();
+
+        let command = signer_script(&dir, &address(), &hex::encode(hash), &"00".repeat(64));
+        let signer = TransactionSigner::External(ExternalSigner::connect(&command).await.unwrap());
+
+        let err = signer
+            .sign_transaction(tx, Network::Testnet.passphrase())
+            .await
+            .expect_err("a forged signature must be rejected");
+        assert!(err.to_string().contains("invalid signature"));
+    }
+}
+
+mod payroll_templates {
+    use stellopay_cli::templates::{PayrollTemplate, TemplateStore};
+    use tempfile::TempDir;
+
+    fn yaml() -> String {
+        format!(
+            "name: engineering\n\
+             description: Engineers on monthly payroll\n\
+             token: {}\n\
+             salary_per_period: '2500.50'\n\
+             grace_period_seconds: 604800\n",
+            stellar_strkey::Contract([2u8; 32])
+        )
+    }
+
+    #[test]
+    fn test_template_store_round_trips_and_lists_by_name() {
+        let dir = TempDir::new().unwrap();
+        let store = TemplateStore::new(dir.path());
+        let template = PayrollTemplate::from_yaml(&yaml()).unwrap();
+        assert!(template.activate);
+        assert_eq!(template.salary().unwrap(), 25_005_000_000);
+
+        store.save(&template, false).unwrap();
+        let mut contractors = template.clone();
+        contractors.name = "contractors".to_string();
+        contractors.activate = false;
+        store.save(&contractors, false).unwrap();
+
+        assert_eq!(store.load("engineering").unwrap(), template);
+        let names: Vec<String> = store.list().unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, ["contractors", "engineering"]);
+
+        let err = store.save(&template, false).unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        store.save(&template, true).unwrap();
+    }
+
+    #[test]
+    fn test_shared_template_imports_unchanged() {
+        let template = PayrollTemplate::from_yaml(&yaml()).unwrap();
+        let shared = template.share_string().unwrap();
+        assert!(!shared.contains('\n'));
+        assert_eq!(
+            PayrollTemplate::from_share_string(&shared).unwrap(),
+            template
+        );
+        assert!(PayrollTemplate::from_share_string("engineering").is_err());
+    }
+
+    #[test]
+    fn test_invalid_template_files_are_rejected() {
+        let unknown_field = yaml() + "salary: '10'\n";
+        assert!(PayrollTemplate::from_yaml(&unknown_field).is_err());
+
+        let zero_salary = yaml().replace("'2500.50'", "'0'");
+        let err = PayrollTemplate::from_yaml(&zero_salary).unwrap_err();
+        assert!(err.to_string().contains("must be positive"));
+
+        let bad_name = yaml().replace("name: engineering", "name: ../engineering");
+        assert!(PayrollTemplate::from_yaml(&bad_name).is_err());
+    }
 }